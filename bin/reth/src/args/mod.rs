@@ -43,4 +43,8 @@ pub use dev_args::DevArgs;
 mod pruning_args;
 pub use pruning_args::PruningArgs;
 
+/// WarmupArgs for configuring the state warmup after a restart
+mod warmup_args;
+pub use warmup_args::WarmupArgs;
+
 pub mod utils;
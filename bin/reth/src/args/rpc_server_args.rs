@@ -14,6 +14,7 @@ use clap::{
 };
 use futures::TryFutureExt;
 use reth_network_api::{NetworkInfo, Peers};
+use reth_primitives::B256;
 use reth_provider::{
     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
     EvmEnvProvider, HeaderProvider, StateProviderFactory,
@@ -23,9 +24,12 @@ use reth_rpc::{
         cache::{
             DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_ENV_CACHE_MAX_LEN, DEFAULT_RECEIPT_CACHE_MAX_LEN,
         },
+        error::SignError,
         gas_oracle::GasPriceOracleConfig,
-        RPC_DEFAULT_GAS_CAP,
+        DevSigner, DevSignerConfig, DEFAULT_DEV_ACCOUNTS, DEV_MNEMONIC, RPC_DEFAULT_GAS_CAP,
+        RPC_DEFAULT_MEMORY_LIMIT,
     },
+    trace_cache::TraceCacheConfig,
     JwtError, JwtSecret,
 };
 use reth_rpc_builder::{
@@ -42,6 +46,8 @@ use std::{
     ffi::OsStr,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
+    str::FromStr,
+    sync::Arc,
 };
 use tracing::{debug, info};
 
@@ -154,6 +160,44 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// Mnemonic of the accounts the node signs with, e.g. for `eth_sendTransaction`.
+    ///
+    /// Defaults to the mnemonic of the prefunded accounts in dev mode. Without a mnemonic or
+    /// private keys, the node has no accounts.
+    #[arg(
+        long = "rpc.dev-mnemonic",
+        value_name = "MNEMONIC",
+        value_parser = parse_dev_mnemonic,
+        default_value_if("dev", "true", DEV_MNEMONIC)
+    )]
+    pub dev_mnemonic: Option<String>,
+
+    /// Number of accounts derived from the mnemonic.
+    #[arg(long = "rpc.dev-accounts", value_name = "COUNT", default_value_t = DEFAULT_DEV_ACCOUNTS)]
+    pub dev_accounts: u32,
+
+    /// Private key of an additional account the node signs with. Can be repeated.
+    #[arg(long = "rpc.dev-private-key", value_name = "KEY", value_parser = parse_dev_private_key)]
+    pub dev_private_keys: Vec<B256>,
+
+    /// Maximum memory in bytes that `eth_call` and call tracing RPC methods can allocate.
+    #[arg(
+        long,
+        value_name = "BYTES",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        default_value_t = RPC_DEFAULT_MEMORY_LIMIT
+    )]
+    pub rpc_memory_limit: u64,
+
+    /// Maximum depth of nested calls for `eth_call`, `eth_callMany` and `eth_estimateGas`.
+    #[arg(long, value_name = "DEPTH")]
+    pub rpc_max_call_depth: Option<u64>,
+
+    /// Disables the cache of block traces for `debug_traceBlockByNumber` and
+    /// `debug_traceBlockByHash`.
+    #[arg(long)]
+    pub rpc_disable_trace_cache: bool,
+
     /// Gas price oracle configuration.
     #[clap(flatten)]
     pub gas_price_oracle: GasPriceOracleArgs,
@@ -207,7 +251,15 @@ impl RpcServerArgs {
         // apply configured customization
         conf.extend_rpc_modules(self, components, rpc_components)?;
 
-        let server_config = self.rpc_server_config();
+        let mut server_config = self.rpc_server_config();
+        if modules
+            .module_config()
+            .http()
+            .is_some_and(|http| http.iter_selection().any(|module| module == RethRpcModule::Debug))
+        {
+            // block traces can be huge, so they're streamed instead of buffered in full
+            server_config = server_config.with_http_trace_streaming(Arc::new(registry.debug_api()));
+        }
         let launch_rpc = modules.clone().start_server(server_config).map_ok(|handle| {
             if let Some(url) = handle.ipc_endpoint() {
                 info!(target: "reth::cli", url=%url, "RPC IPC server started");
@@ -328,7 +380,18 @@ impl RethRpcConfig for RpcServerArgs {
             .max_tracing_requests(self.rpc_max_tracing_requests)
             .max_logs_per_response(self.rpc_max_logs_per_response)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .rpc_memory_limit(self.rpc_memory_limit)
+            .rpc_max_call_depth(self.rpc_max_call_depth)
             .gpo_config(self.gas_price_oracle_config())
+            .dev_signer(DevSignerConfig {
+                mnemonic: self.dev_mnemonic.clone(),
+                mnemonic_accounts: self.dev_accounts,
+                private_keys: self.dev_private_keys.clone(),
+            })
+            .trace_cache(TraceCacheConfig {
+                enabled: !self.rpc_disable_trace_cache,
+                ..Default::default()
+            })
     }
 
     fn rpc_max_request_size_bytes(&self) -> u32 {
@@ -473,6 +536,19 @@ impl TypedValueParser for RpcModuleSelectionValueParser {
     }
 }
 
+/// Parses a mnemonic of [RpcServerArgs::dev_mnemonic], making sure accounts can be derived from it.
+fn parse_dev_mnemonic(value: &str) -> Result<String, SignError> {
+    DevSigner::from_mnemonic(value, 1)?;
+    Ok(value.to_string())
+}
+
+/// Parses a private key of [RpcServerArgs::dev_private_keys].
+fn parse_dev_private_key(value: &str) -> eyre::Result<B256> {
+    let key = B256::from_str(value)?;
+    DevSigner::from_private_keys(&[key])?;
+    Ok(key)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -501,6 +577,51 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_rpc_dev_signer() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(args.eth_config().dev_signer.is_empty());
+
+        let key = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.dev-mnemonic",
+            DEV_MNEMONIC,
+            "--rpc.dev-accounts",
+            "2",
+            "--rpc.dev-private-key",
+            key,
+        ])
+        .args;
+        let config = args.eth_config().dev_signer;
+        assert_eq!(config.mnemonic.as_deref(), Some(DEV_MNEMONIC));
+        assert_eq!(config.mnemonic_accounts, 2);
+        assert_eq!(config.private_keys, vec![B256::from_str(key).unwrap()]);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.dev-mnemonic",
+            "not a mnemonic",
+        ]);
+        assert!(args.is_err());
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.dev-private-key",
+            "0x0000000000000000000000000000000000000000000000000000000000000000",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_trace_cache() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(args.eth_config().trace_cache.enabled);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc-disable-trace-cache"]).args;
+        assert!(!args.eth_config().trace_cache.enabled);
+    }
+
     #[test]
     fn test_rpc_server_args_parser() {
         let args =
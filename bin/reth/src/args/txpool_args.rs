@@ -2,9 +2,9 @@
 
 use clap::Args;
 use reth_transaction_pool::{
-    PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP,
-    TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
-    TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+    PoolConfig, PriceBumpConfig, SpamGuardConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
+    REPLACE_BLOB_PRICE_BUMP, SPAM_GUARD_THRESHOLD_DEFAULT, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
 
 /// Parameters for debugging purposes
@@ -42,6 +42,10 @@ pub struct TxPoolArgs {
     /// Price bump percentage to replace an already existing blob transaction
     #[arg(long = "blobpool.pricebump", help_heading = "TxPool", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
+
+    /// Spam score at which a sender is subject to stricter limits.
+    #[arg(long = "txpool.spam_threshold", help_heading = "TxPool", default_value_t = SPAM_GUARD_THRESHOLD_DEFAULT)]
+    pub spam_threshold: f64,
 }
 
 impl TxPoolArgs {
@@ -65,6 +69,8 @@ impl TxPoolArgs {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            spam_guard: SpamGuardConfig { threshold: self.spam_threshold, ..Default::default() },
+            queued_overflow: None,
         }
     }
 }
@@ -29,7 +29,9 @@ pub fn chain_spec_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Er
         "dev" => DEV.clone(),
         _ => {
             let raw = fs::read_to_string(PathBuf::from(shellexpand::full(s)?.into_owned()))?;
-            serde_json::from_str(&raw)?
+            let spec: ChainSpec = serde_json::from_str(&raw)?;
+            spec.validate()?;
+            Arc::new(spec)
         }
     })
 }
@@ -46,7 +48,9 @@ pub fn genesis_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Error
         _ => {
             let raw = fs::read_to_string(PathBuf::from(shellexpand::full(s)?.into_owned()))?;
             let genesis: AllGenesisFormats = serde_json::from_str(&raw)?;
-            Arc::new(genesis.into())
+            let spec: ChainSpec = genesis.into();
+            spec.validate()?;
+            Arc::new(spec)
         }
     })
 }
@@ -0,0 +1,88 @@
+//! clap [Args](clap::Args) for the state warmup after a restart
+
+use crate::args::utils::parse_duration_from_secs;
+use clap::Args;
+use reth_blockchain_tree::{
+    warmup::{DEFAULT_WARMUP_BLOCKS, DEFAULT_WARMUP_TRIE_DEPTH},
+    WarmupConfig,
+};
+use std::time::Duration;
+
+/// Parameters for the state warmup
+#[derive(Debug, Args, PartialEq, Clone, Copy)]
+#[command(next_help_heading = "Warmup")]
+pub struct WarmupArgs {
+    /// Preload the recently changed state before accepting engine calls.
+    ///
+    /// Speeds up the execution of the first blocks after a restart.
+    #[arg(long = "warmup")]
+    pub enabled: bool,
+
+    /// The number of most recent blocks whose changed accounts and storage slots are preloaded.
+    #[arg(long = "warmup.blocks", default_value_t = DEFAULT_WARMUP_BLOCKS)]
+    pub blocks: u64,
+
+    /// The number of top levels of the account trie that are read.
+    #[arg(long = "warmup.trie-depth", default_value_t = DEFAULT_WARMUP_TRIE_DEPTH)]
+    pub trie_depth: usize,
+
+    /// The time budget of the warmup.
+    #[arg(long = "warmup.budget", value_parser = parse_duration_from_secs, default_value = "30", value_name = "SECONDS")]
+    pub budget: Duration,
+}
+
+impl WarmupArgs {
+    /// Returns the warmup configuration, if the warmup is enabled.
+    pub fn warmup_config(&self) -> Option<WarmupConfig> {
+        self.enabled.then_some(WarmupConfig {
+            blocks: self.blocks,
+            trie_depth: self.trie_depth,
+            budget: self.budget,
+        })
+    }
+}
+
+impl Default for WarmupArgs {
+    fn default() -> Self {
+        let WarmupConfig { blocks, trie_depth, budget } = WarmupConfig::default();
+        Self { enabled: false, blocks, trie_depth, budget }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_warmup_args() {
+        let args = CommandParser::<WarmupArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, WarmupArgs::default());
+        assert_eq!(args.warmup_config(), None);
+
+        let args = CommandParser::<WarmupArgs>::parse_from([
+            "reth",
+            "--warmup",
+            "--warmup.blocks",
+            "16",
+            "--warmup.budget",
+            "5",
+        ])
+        .args;
+        assert_eq!(
+            args.warmup_config(),
+            Some(WarmupConfig {
+                blocks: 16,
+                trie_depth: DEFAULT_WARMUP_TRIE_DEPTH,
+                budget: Duration::from_secs(5)
+            })
+        );
+    }
+}
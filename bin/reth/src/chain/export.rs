@@ -0,0 +1,378 @@
+use crate::{
+    args::{utils::genesis_value_parser, DatabaseArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+    version::SHORT_VERSION,
+};
+use alloy_rlp::Encodable;
+use clap::Parser;
+use eyre::{ensure, Context};
+use reth_db::{database::Database, open_db_read_only};
+use reth_interfaces::provider::ProviderError;
+use reth_primitives::{keccak256, BlockNumber, ChainSpec, Header, B256};
+use reth_provider::{
+    BlockHashReader, BlockNumReader, BlockReader, HeaderProvider, ProviderFactory,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::info;
+
+/// The name of the manifest file of an export directory.
+pub const EXPORT_MANIFEST_FILE: &str = "manifest.json";
+
+/// Exports the canonical chain to RLP encoded block files.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The first block to export.
+    #[arg(long, value_name = "BLOCK", default_value_t = 1)]
+    first_block: BlockNumber,
+
+    /// The last block to export.
+    ///
+    /// Defaults to the highest synced block.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    last_block: Option<BlockNumber>,
+
+    /// The number of blocks per chunk file.
+    #[arg(long, value_name = "BLOCKS", default_value_t = 10_000)]
+    chunk_size: u64,
+
+    /// The directory the chunk files and the manifest are written to.
+    ///
+    /// If the directory contains the manifest of an interrupted export, the export continues
+    /// after the last complete chunk.
+    #[arg(value_name = "EXPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ExportCommand {
+    /// Execute `export` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = Arc::new(open_db_read_only(&db_path, self.db.log_level)?);
+        info!(target: "reth::cli", "Database opened");
+
+        let factory = ProviderFactory::new(db, self.chain.clone());
+        let last_block = match self.last_block {
+            Some(last_block) => last_block,
+            None => factory.provider()?.best_block_number()?,
+        };
+
+        let manifest =
+            export_chain(&factory, self.first_block..=last_block, &self.path, self.chunk_size)?;
+        info!(target: "reth::cli", chunks = manifest.chunks.len(), path = ?self.path, "Chain exported");
+        Ok(())
+    }
+}
+
+/// The manifest of an export directory, see [export_chain].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// The complete chunks, in block order.
+    pub chunks: Vec<ExportChunk>,
+}
+
+impl ExportManifest {
+    /// Reads the manifest of the export directory, returns `None` if there is none.
+    pub fn load(dir: &Path) -> eyre::Result<Option<Self>> {
+        let path = dir.join(EXPORT_MANIFEST_FILE);
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        serde_json::from_slice(&content)
+            .map(Some)
+            .wrap_err_with(|| format!("Invalid export manifest {path:?}"))
+    }
+
+    /// Writes the manifest to the export directory.
+    ///
+    /// The previous manifest is replaced atomically, so an interrupted write does not lose the
+    /// chunks that are already complete.
+    fn save(&self, dir: &Path) -> eyre::Result<()> {
+        let tmp = dir.join(format!("{EXPORT_MANIFEST_FILE}.tmp"));
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp, dir.join(EXPORT_MANIFEST_FILE))?;
+        Ok(())
+    }
+}
+
+/// A file of an export that contains a range of blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportChunk {
+    /// The name of the chunk file in the export directory.
+    pub file: String,
+    /// The first block of the chunk.
+    pub first_block: BlockNumber,
+    /// The last block of the chunk.
+    pub last_block: BlockNumber,
+    /// The hash of the last block of the chunk.
+    pub last_hash: B256,
+    /// The keccak256 hash of the content of the chunk file.
+    pub checksum: B256,
+}
+
+impl ExportChunk {
+    /// Returns true if the chunk file exists in the export directory and matches the checksum.
+    pub fn is_complete(&self, dir: &Path) -> eyre::Result<bool> {
+        match fs::read(dir.join(&self.file)) {
+            Ok(content) => Ok(keccak256(content) == self.checksum),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Exports the canonical blocks of the range to chunk files of `chunk_size` blocks in the given
+/// directory and returns the manifest of the export.
+///
+/// The blocks of a chunk are RLP encoded and concatenated, which is the format written by
+/// `geth export` and read by `reth import`. The header of every block must hash to the canonical
+/// hash of its number and link to the previous block, so a corrupted database fails the export
+/// instead of producing invalid files.
+///
+/// If the directory contains the manifest of an interrupted export, its complete chunks that
+/// match the requested range and chunk size are kept and the export continues with the first
+/// chunk that is missing.
+pub fn export_chain<DB: Database>(
+    factory: &ProviderFactory<DB>,
+    range: RangeInclusive<BlockNumber>,
+    dir: &Path,
+    chunk_size: u64,
+) -> eyre::Result<ExportManifest> {
+    ensure!(chunk_size > 0, "The chunk size must not be zero");
+    ensure!(*range.start() > 0, "The genesis block can't be exported");
+    ensure!(!range.is_empty(), "Empty block range {range:?}");
+
+    fs::create_dir_all(dir)?;
+    let provider = factory.provider()?;
+
+    // keep the chunks of a previous export that are complete
+    let mut manifest = ExportManifest::load(dir)?.unwrap_or_default();
+    let mut next_block = *range.start();
+    let mut complete = 0;
+    for chunk in &manifest.chunks {
+        let last_block = chunk_end(next_block, chunk_size, *range.end());
+        if chunk.first_block != next_block ||
+            chunk.last_block != last_block ||
+            !chunk.is_complete(dir)?
+        {
+            break
+        }
+        next_block = last_block + 1;
+        complete += 1;
+    }
+    manifest.chunks.truncate(complete);
+
+    let parent = provider
+        .header_by_number(next_block - 1)?
+        .ok_or(ProviderError::HeaderNotFound((next_block - 1).into()))?;
+    let mut parent_hash = canonical_header_hash(&provider, &parent)?;
+    if let Some(chunk) = manifest.chunks.last() {
+        ensure!(
+            chunk.last_hash == parent_hash,
+            "Block {} of the previous export is no longer canonical",
+            chunk.last_block
+        );
+        info!(target: "reth::cli", chunks = complete, next_block, "Resuming export");
+    }
+
+    while next_block <= *range.end() {
+        let last_block = chunk_end(next_block, chunk_size, *range.end());
+
+        let mut buf = Vec::new();
+        for number in next_block..=last_block {
+            let block = provider
+                .block(number.into())?
+                .ok_or(ProviderError::BlockNotFound(number.into()))?;
+            ensure!(
+                block.number == number && block.parent_hash == parent_hash,
+                "Block {number} does not link to its parent"
+            );
+            parent_hash = canonical_header_hash(&provider, &block.header)?;
+            block.encode(&mut buf);
+        }
+
+        let chunk = ExportChunk {
+            file: format!("chunk-{:05}.rlp", manifest.chunks.len()),
+            first_block: next_block,
+            last_block,
+            last_hash: parent_hash,
+            checksum: keccak256(&buf),
+        };
+        let tmp = dir.join(format!("{}.tmp", chunk.file));
+        fs::write(&tmp, &buf)?;
+        fs::rename(tmp, dir.join(&chunk.file))?;
+        info!(target: "reth::cli", file = chunk.file, first_block = next_block, last_block, "Exported chunk");
+
+        manifest.chunks.push(chunk);
+        manifest.save(dir)?;
+        next_block = last_block + 1;
+    }
+
+    Ok(manifest)
+}
+
+/// Returns the last block of the chunk that starts at `first_block`.
+fn chunk_end(first_block: BlockNumber, chunk_size: u64, last_block: BlockNumber) -> BlockNumber {
+    first_block.saturating_add(chunk_size - 1).min(last_block)
+}
+
+/// Returns the hash of the header, after checking that it is the canonical hash of its number.
+fn canonical_header_hash<Provider: BlockHashReader>(
+    provider: &Provider,
+    header: &Header,
+) -> eyre::Result<B256> {
+    let hash = header.hash_slow();
+    let canonical = provider
+        .block_hash(header.number)?
+        .ok_or(ProviderError::HeaderNotFound(header.number.into()))?;
+    ensure!(
+        hash == canonical,
+        "Header of block {} hashes to {hash}, but the canonical hash is {canonical}",
+        header.number
+    );
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chain::import_chain, init::init_genesis};
+    use reth_config::Config;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_primitives::{
+        basefee::next_block_base_fee,
+        constants::{EMPTY_RECEIPTS, EMPTY_TRANSACTIONS},
+        proofs::EMPTY_ROOT,
+        Block, ChainSpecBuilder, Genesis, EMPTY_OMMER_ROOT,
+    };
+    use reth_provider::BlockWriter;
+
+    const BLOCKS: u64 = 300;
+
+    /// Returns a post merge chain without state, so that empty blocks are valid.
+    fn chain_spec() -> Arc<ChainSpec> {
+        Arc::new(
+            ChainSpecBuilder::default()
+                .chain(reth_primitives::Chain::dev())
+                .genesis(Genesis::default().with_gas_limit(30_000_000))
+                .paris_activated()
+                .build(),
+        )
+    }
+
+    /// Returns a valid chain of empty blocks on top of the genesis block.
+    fn empty_blocks(chain_spec: &ChainSpec, count: u64) -> Vec<Block> {
+        let mut parent = chain_spec.sealed_genesis_header();
+        let mut blocks = Vec::new();
+        for number in 1..=count {
+            let header = Header {
+                parent_hash: parent.hash(),
+                number,
+                timestamp: parent.timestamp + 12,
+                gas_limit: parent.gas_limit,
+                base_fee_per_gas: next_block_base_fee(&parent, chain_spec),
+                state_root: EMPTY_ROOT,
+                transactions_root: EMPTY_TRANSACTIONS,
+                receipts_root: EMPTY_RECEIPTS,
+                ommers_hash: EMPTY_OMMER_ROOT,
+                ..Default::default()
+            };
+            parent = header.clone().seal_slow();
+            blocks.push(Block { header, ..Default::default() });
+        }
+        blocks
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn export_resume_and_import() {
+        let chain_spec = chain_spec();
+        let source = create_test_rw_db();
+        init_genesis(source.clone(), chain_spec.clone()).unwrap();
+        let factory = ProviderFactory::new(source, chain_spec.clone());
+        {
+            let provider = factory.provider_rw().unwrap();
+            for block in empty_blocks(&chain_spec, BLOCKS) {
+                provider.insert_block(block.seal_slow(), None, None).unwrap();
+            }
+            provider.commit().unwrap();
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = export_chain(&factory, 1..=BLOCKS, dir.path(), 100).unwrap();
+        assert_eq!(manifest.chunks.len(), 3);
+        assert_eq!(
+            manifest
+                .chunks
+                .iter()
+                .map(|chunk| chunk.first_block..=chunk.last_block)
+                .collect::<Vec<_>>(),
+            vec![1..=100, 101..=200, 201..=300]
+        );
+
+        // interrupted export, the complete chunks are kept
+        let last = manifest.chunks.last().unwrap().clone();
+        fs::remove_file(dir.path().join(&last.file)).unwrap();
+        let first_chunk = dir.path().join(&manifest.chunks[0].file);
+        let modified = fs::metadata(&first_chunk).unwrap().modified().unwrap();
+        let resumed = export_chain(&factory, 1..=BLOCKS, dir.path(), 100).unwrap();
+        assert_eq!(resumed, manifest);
+        assert_eq!(fs::metadata(&first_chunk).unwrap().modified().unwrap(), modified);
+        assert!(last.is_complete(dir.path()).unwrap());
+
+        // import into a clean node
+        let target = create_test_rw_db();
+        init_genesis(target.clone(), chain_spec.clone()).unwrap();
+        import_chain(target.clone(), chain_spec.clone(), &Config::default(), dir.path())
+            .await
+            .unwrap();
+
+        let imported = ProviderFactory::new(target, chain_spec).provider().unwrap();
+        assert_eq!(imported.best_block_number().unwrap(), BLOCKS);
+        assert_eq!(imported.block_hash(BLOCKS).unwrap(), Some(last.last_hash));
+        assert_eq!(factory.provider().unwrap().block_hash(BLOCKS).unwrap(), Some(last.last_hash));
+    }
+}
@@ -1,11 +1,12 @@
 use crate::{
+    chain::ExportManifest,
     dirs::{DataDirPath, MaybePlatformPath},
     init::init_genesis,
     node::events::{handle_events, NodeEvent},
     version::SHORT_VERSION,
 };
 use clap::Parser;
-use eyre::Context;
+use eyre::{ensure, Context};
 use futures::{Stream, StreamExt};
 use reth_beacon_consensus::BeaconConsensus;
 use reth_provider::{ProviderFactory, StageCheckpointReader};
@@ -26,7 +27,10 @@ use reth_stages::{
         TotalDifficultyStage,
     },
 };
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::sync::watch;
 use tracing::{debug, info};
 
@@ -68,10 +72,11 @@ pub struct ImportCommand {
     #[clap(flatten)]
     db: DatabaseArgs,
 
-    /// The path to a block file for import.
+    /// The path to a block file for import, or to a directory written by `reth export`.
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
-    /// remaining stages are executed.
+    /// remaining stages are executed. The chunks of an export directory are imported one after
+    /// another.
     #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
     path: PathBuf,
 }
@@ -98,116 +103,150 @@ impl ImportCommand {
 
         init_genesis(db.clone(), self.chain.clone())?;
 
-        let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
-        info!(target: "reth::cli", "Consensus engine initialized");
+        import_chain(db, self.chain.clone(), &config, &self.path).await?;
+
+        info!(target: "reth::cli", "Finishing up");
+        Ok(())
+    }
 
+    /// Loads the reth config
+    fn load_config(&self, config_path: PathBuf) -> eyre::Result<Config> {
+        confy::load_path::<Config>(config_path.clone())
+            .wrap_err_with(|| format!("Could not load config file {:?}", config_path))
+    }
+}
+
+/// Imports the blocks of a block file, or of all chunks of a directory written by
+/// [export_chain](super::export_chain), into the database.
+///
+/// Every file is imported by a separate run of the pipeline that targets the last block of the
+/// file, so all blocks are validated and executed as during a regular sync.
+pub async fn import_chain<DB>(
+    db: DB,
+    chain: Arc<ChainSpec>,
+    config: &Config,
+    path: &Path,
+) -> eyre::Result<()>
+where
+    DB: Database + Clone + Unpin + 'static,
+{
+    let files = if path.is_dir() {
+        let manifest = ExportManifest::load(path)?
+            .ok_or_else(|| eyre::eyre!("No export manifest found in {path:?}"))?;
+        let mut files = Vec::with_capacity(manifest.chunks.len());
+        for chunk in manifest.chunks {
+            ensure!(chunk.is_complete(path)?, "Chunk file {} is missing or corrupted", chunk.file);
+            files.push(path.join(chunk.file));
+        }
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let consensus = Arc::new(BeaconConsensus::new(chain.clone()));
+    info!(target: "reth::cli", "Consensus engine initialized");
+
+    for file in files {
         // create a new FileClient
-        info!(target: "reth::cli", "Importing chain file");
-        let file_client = Arc::new(FileClient::new(&self.path).await?);
+        info!(target: "reth::cli", path = ?file, "Importing chain file");
+        let file_client = Arc::new(FileClient::new(&file).await?);
 
         // override the tip
-        let tip = file_client.tip().expect("file client has no tip");
+        let tip = file_client.tip().ok_or_else(|| eyre::eyre!("file client has no tip"))?;
         info!(target: "reth::cli", "Chain file imported");
 
         let (mut pipeline, events) =
-            self.build_import_pipeline(config, Arc::clone(&db), &consensus, file_client).await?;
+            build_import_pipeline(config, db.clone(), chain.clone(), &consensus, file_client)?;
 
         // override the tip
         pipeline.set_tip(tip);
         debug!(target: "reth::cli", ?tip, "Tip manually set");
 
-        let factory = ProviderFactory::new(&db, self.chain.clone());
-        let provider = factory.provider().map_err(PipelineError::Interface)?;
-
-        let latest_block_number =
-            provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
+        let latest_block_number = ProviderFactory::new(db.clone(), chain.clone())
+            .provider()
+            .map_err(PipelineError::Interface)?
+            .get_stage_checkpoint(StageId::Finish)?
+            .map(|ch| ch.block_number);
         tokio::spawn(handle_events(None, latest_block_number, events));
 
         // Run pipeline
         info!(target: "reth::cli", "Starting sync pipeline");
         tokio::select! {
             res = pipeline.run() => res?,
-            _ = tokio::signal::ctrl_c() => {},
+            _ = tokio::signal::ctrl_c() => return Ok(()),
         };
-
-        info!(target: "reth::cli", "Finishing up");
-        Ok(())
     }
 
-    async fn build_import_pipeline<DB, C>(
-        &self,
-        config: Config,
-        db: DB,
-        consensus: &Arc<C>,
-        file_client: Arc<FileClient>,
-    ) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
-    where
-        DB: Database + Clone + Unpin + 'static,
-        C: Consensus + 'static,
-    {
-        if !file_client.has_canonical_blocks() {
-            eyre::bail!("unable to import non canonical blocks");
-        }
-
-        let header_downloader = ReverseHeadersDownloaderBuilder::from(config.stages.headers)
-            .build(file_client.clone(), consensus.clone())
-            .into_task();
-
-        let body_downloader = BodiesDownloaderBuilder::from(config.stages.bodies)
-            .build(file_client.clone(), consensus.clone(), db.clone())
-            .into_task();
-
-        let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
-        let factory = reth_revm::Factory::new(self.chain.clone());
-
-        let max_block = file_client.max_block().unwrap_or(0);
-        let mut pipeline = Pipeline::builder()
-            .with_tip_sender(tip_tx)
-            // we want to sync all blocks the file client provides or 0 if empty
-            .with_max_block(max_block)
-            .add_stages(
-                DefaultStages::new(
-                    HeaderSyncMode::Tip(tip_rx),
-                    consensus.clone(),
-                    header_downloader,
-                    body_downloader,
-                    factory.clone(),
-                )
-                .set(
-                    TotalDifficultyStage::new(consensus.clone())
-                        .with_commit_threshold(config.stages.total_difficulty.commit_threshold),
-                )
-                .set(SenderRecoveryStage {
-                    commit_threshold: config.stages.sender_recovery.commit_threshold,
-                })
-                .set(ExecutionStage::new(
-                    factory,
-                    ExecutionStageThresholds {
-                        max_blocks: config.stages.execution.max_blocks,
-                        max_changes: config.stages.execution.max_changes,
-                        max_cumulative_gas: config.stages.execution.max_cumulative_gas,
-                    },
-                    config
-                        .stages
-                        .merkle
-                        .clean_threshold
-                        .max(config.stages.account_hashing.clean_threshold)
-                        .max(config.stages.storage_hashing.clean_threshold),
-                    config.prune.map(|prune| prune.segments).unwrap_or_default(),
-                )),
-            )
-            .build(db, self.chain.clone());
-
-        let events = pipeline.events().map(Into::into);
+    Ok(())
+}
 
-        Ok((pipeline, events))
+fn build_import_pipeline<DB, C>(
+    config: &Config,
+    db: DB,
+    chain: Arc<ChainSpec>,
+    consensus: &Arc<C>,
+    file_client: Arc<FileClient>,
+) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
+where
+    DB: Database + Clone + Unpin + 'static,
+    C: Consensus + 'static,
+{
+    if !file_client.has_canonical_blocks() {
+        eyre::bail!("unable to import non canonical blocks");
     }
 
-    /// Loads the reth config
-    fn load_config(&self, config_path: PathBuf) -> eyre::Result<Config> {
-        confy::load_path::<Config>(config_path.clone())
-            .wrap_err_with(|| format!("Could not load config file {:?}", config_path))
-    }
+    let header_downloader = ReverseHeadersDownloaderBuilder::from(config.stages.headers)
+        .build(file_client.clone(), consensus.clone())
+        .into_task();
+
+    let body_downloader = BodiesDownloaderBuilder::from(config.stages.bodies)
+        .build(file_client.clone(), consensus.clone(), db.clone())
+        .into_task();
+
+    let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+    let factory = reth_revm::Factory::new(chain.clone());
+
+    let max_block = file_client.max_block().unwrap_or(0);
+    let mut pipeline = Pipeline::builder()
+        .with_tip_sender(tip_tx)
+        // we want to sync all blocks the file client provides or 0 if empty
+        .with_max_block(max_block)
+        .add_stages(
+            DefaultStages::new(
+                HeaderSyncMode::Tip(tip_rx),
+                consensus.clone(),
+                header_downloader,
+                body_downloader,
+                factory.clone(),
+            )
+            .set(
+                TotalDifficultyStage::new(consensus.clone())
+                    .with_commit_threshold(config.stages.total_difficulty.commit_threshold),
+            )
+            .set(SenderRecoveryStage {
+                commit_threshold: config.stages.sender_recovery.commit_threshold,
+            })
+            .set(ExecutionStage::new(
+                factory,
+                ExecutionStageThresholds {
+                    max_blocks: config.stages.execution.max_blocks,
+                    max_changes: config.stages.execution.max_changes,
+                    max_cumulative_gas: config.stages.execution.max_cumulative_gas,
+                },
+                config
+                    .stages
+                    .merkle
+                    .clean_threshold
+                    .max(config.stages.account_hashing.clean_threshold)
+                    .max(config.stages.storage_hashing.clean_threshold),
+                config.prune.as_ref().map(|prune| prune.segments.clone()).unwrap_or_default(),
+            )),
+        )
+        .build(db, chain);
+
+    let events = pipeline.events().map(Into::into);
+
+    Ok((pipeline, events))
 }
 
 #[cfg(test)]
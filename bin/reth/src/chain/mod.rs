@@ -1,7 +1,9 @@
 //! Command line utilities for initializing a chain.
 
+mod export;
 mod import;
 mod init;
 
-pub use import::ImportCommand;
+pub use export::{export_chain, ExportChunk, ExportCommand, ExportManifest, EXPORT_MANIFEST_FILE};
+pub use import::{import_chain, ImportCommand};
 pub use init::InitCommand;
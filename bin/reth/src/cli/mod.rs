@@ -88,6 +88,7 @@ impl<Ext: RethCliExt> Cli<Ext> {
             Commands::Node(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::Export(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Stage(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
@@ -142,6 +143,9 @@ pub enum Commands<Ext: RethCliExt = ()> {
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(chain::ImportCommand),
+    /// Export the canonical chain to RLP encoded block files.
+    #[command(name = "export")]
+    Export(chain::ExportCommand),
     /// Database debugging utilities
     #[command(name = "db")]
     Db(db::Command),
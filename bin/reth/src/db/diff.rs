@@ -19,8 +19,9 @@ use reth_db::{
     AccountChangeSet, AccountHistory, AccountsTrie, BlockBodyIndices, BlockOmmers,
     BlockWithdrawals, Bytecodes, CanonicalHeaders, DatabaseEnvRO, HashedAccount, HashedStorage,
     HeaderNumbers, HeaderTD, Headers, PlainAccountState, PlainStorageState, PruneCheckpoints,
-    Receipts, StorageChangeSet, StorageHistory, StoragesTrie, SyncStage, SyncStageProgress, Tables,
-    TransactionBlock, Transactions, TxHashNumber, TxSenders,
+    Receipts, StorageChangeSet, StorageHistory, StorageMetadata, StoragesTrie, SyncStage,
+    SyncStageProgress, Tables, TimestampBlockNumber, TransactionBlock, Transactions, TxHashNumber,
+    TxSenders,
 };
 use tracing::info;
 
@@ -140,6 +141,12 @@ impl Command {
                 Tables::PruneCheckpoints => {
                     find_diffs::<PruneCheckpoints>(primary_tx, secondary_tx, output_dir)?
                 }
+                Tables::TimestampBlockNumber => {
+                    find_diffs::<TimestampBlockNumber>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::StorageMetadata => {
+                    find_diffs::<StorageMetadata>(primary_tx, secondary_tx, output_dir)?
+                }
             };
         }
 
@@ -289,6 +289,7 @@ impl Command {
                     Vec::from([block_with_senders]),
                     state,
                     None,
+                    None,
                 )?;
                 info!(target: "reth::cli", "Successfully appended built block");
             }
@@ -209,6 +209,7 @@ pub fn insert_genesis_header<DB: Database>(
 
     tx.put::<tables::CanonicalHeaders>(0, header.hash)?;
     tx.put::<tables::HeaderNumbers>(header.hash, 0)?;
+    tx.put::<tables::TimestampBlockNumber>(header.timestamp, 0)?;
     tx.put::<tables::BlockBodyIndices>(0, Default::default())?;
     tx.put::<tables::HeaderTD>(0, header.difficulty.into())?;
     tx.put::<tables::Headers>(0, header.header)?;
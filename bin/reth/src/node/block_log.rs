@@ -0,0 +1,251 @@
+//! Structured log of canonical blocks for external collectors.
+//!
+//! Every canonical block is written as one JSON line, see [BlockLogLine] for the schema.
+
+use futures::{Stream, StreamExt};
+use reth_primitives::{constants::eip4844::DATA_GAS_PER_BLOB, BlockHash, BlockNumber, SealedBlock};
+use reth_provider::CanonStateNotification;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    net::{AddrParseError, SocketAddr, TcpStream},
+    path::PathBuf,
+    str::FromStr,
+};
+use tracing::warn;
+
+/// The version of the [BlockLogLine] schema.
+///
+/// This is incremented whenever a field is changed or removed.
+pub const BLOCK_LOG_VERSION: u64 = 1;
+
+/// Marks a block that was logged because of a reorg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReorgMarker {
+    /// The block was removed from the canonical chain.
+    Removed,
+    /// The block was added to the canonical chain by a reorg.
+    Added,
+}
+
+/// A single line of the block log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockLogLine {
+    /// The version of the schema, see [BLOCK_LOG_VERSION].
+    pub version: u64,
+    /// The number of the block.
+    pub number: BlockNumber,
+    /// The hash of the block.
+    pub hash: BlockHash,
+    /// The hash of the parent block.
+    pub parent_hash: BlockHash,
+    /// The gas used by the block.
+    pub gas_used: u64,
+    /// The gas limit of the block.
+    pub gas_limit: u64,
+    /// The number of transactions in the block.
+    pub tx_count: usize,
+    /// The number of blobs in the block.
+    pub blob_count: u64,
+    /// The base fee of the block, if London is active.
+    pub base_fee: Option<u64>,
+    /// Set if the block was removed or added by a reorg.
+    pub reorg: Option<ReorgMarker>,
+}
+
+impl BlockLogLine {
+    /// Creates the line of the given block.
+    pub fn new(block: &SealedBlock, reorg: Option<ReorgMarker>) -> Self {
+        Self {
+            version: BLOCK_LOG_VERSION,
+            number: block.number,
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            gas_used: block.gas_used,
+            gas_limit: block.gas_limit,
+            tx_count: block.body.len(),
+            blob_count: block.blob_gas_used() / DATA_GAS_PER_BLOB,
+            base_fee: block.base_fee_per_gas,
+            reorg,
+        }
+    }
+}
+
+/// Returns the lines of all blocks of the notification.
+///
+/// For a reorg, the removed blocks are returned first starting at the old tip, followed by the
+/// added blocks in ascending order.
+pub fn block_log_lines(notification: &CanonStateNotification) -> Vec<BlockLogLine> {
+    match notification {
+        CanonStateNotification::Commit { new } => {
+            new.blocks().values().map(|block| BlockLogLine::new(block, None)).collect()
+        }
+        CanonStateNotification::Reorg { old, new } => old
+            .blocks()
+            .values()
+            .rev()
+            .map(|block| BlockLogLine::new(block, Some(ReorgMarker::Removed)))
+            .chain(
+                new.blocks()
+                    .values()
+                    .map(|block| BlockLogLine::new(block, Some(ReorgMarker::Added))),
+            )
+            .collect(),
+    }
+}
+
+/// Where the block log is written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockLogSink {
+    /// Appends to the file at the path.
+    File(PathBuf),
+    /// Connects to the TCP socket at the address.
+    Tcp(SocketAddr),
+}
+
+impl BlockLogSink {
+    /// Opens the sink for writing.
+    pub fn open(&self) -> io::Result<Box<dyn Write + Send>> {
+        Ok(match self {
+            BlockLogSink::File(path) => {
+                Box::new(OpenOptions::new().create(true).append(true).open(path)?)
+            }
+            BlockLogSink::Tcp(addr) => Box::new(TcpStream::connect(addr)?),
+        })
+    }
+}
+
+impl FromStr for BlockLogSink {
+    type Err = AddrParseError;
+
+    /// Parses `tcp://<addr>` as a TCP socket and anything else as a file path.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("tcp://") {
+            Some(addr) => Ok(BlockLogSink::Tcp(addr.parse()?)),
+            None => Ok(BlockLogSink::File(s.into())),
+        }
+    }
+}
+
+impl fmt::Display for BlockLogSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockLogSink::File(path) => write!(f, "{}", path.display()),
+            BlockLogSink::Tcp(addr) => write!(f, "tcp://{addr}"),
+        }
+    }
+}
+
+/// Writes the lines of all blocks of the notification, one JSON object per line.
+pub fn write_notification(
+    writer: &mut impl Write,
+    notification: &CanonStateNotification,
+) -> io::Result<()> {
+    for line in block_log_lines(notification) {
+        serde_json::to_writer(&mut *writer, &line)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Writes every canonical state notification of the stream to the sink until the stream ends.
+pub async fn log_canonical_blocks<St>(events: St, sink: BlockLogSink)
+where
+    St: Stream<Item = CanonStateNotification>,
+{
+    let mut writer = match sink.open() {
+        Ok(writer) => BufWriter::new(writer),
+        Err(err) => {
+            warn!(target: "reth::cli", %err, %sink, "Failed to open block log");
+            return
+        }
+    };
+
+    let mut events = std::pin::pin!(events);
+    while let Some(notification) = events.next().await {
+        if let Err(err) = write_notification(&mut writer, &notification) {
+            warn!(target: "reth::cli", %err, %sink, "Failed to write block log");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+    use reth_provider::{test_utils::blocks::BlockChainTestData, Chain};
+    use std::sync::Arc;
+
+    const FIXTURE: &str = r#"{"version":1,"number":2,"hash":"0x0000000000000000000000000000000000000000000000000000000000000002","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000001","gasUsed":21000,"gasLimit":30000000,"txCount":1,"blobCount":0,"baseFee":7,"reorg":"removed"}"#;
+
+    #[test]
+    fn schema_roundtrip() {
+        let line = BlockLogLine {
+            version: BLOCK_LOG_VERSION,
+            number: 2,
+            hash: B256::with_last_byte(2),
+            parent_hash: B256::with_last_byte(1),
+            gas_used: 21_000,
+            gas_limit: 30_000_000,
+            tx_count: 1,
+            blob_count: 0,
+            base_fee: Some(7),
+            reorg: Some(ReorgMarker::Removed),
+        };
+        assert_eq!(serde_json::to_string(&line).unwrap(), FIXTURE);
+        assert_eq!(serde_json::from_str::<BlockLogLine>(FIXTURE).unwrap(), line);
+    }
+
+    #[test]
+    fn reorg_logs_removed_and_added_blocks() {
+        let BlockChainTestData { blocks, .. } = BlockChainTestData::default();
+        let (block1, block2) = (blocks[0].0.clone(), blocks[1].0.clone());
+
+        // replace block 2 with a sibling
+        let mut sibling = block2.clone();
+        let mut header = sibling.block.header.clone().unseal();
+        header.extra_data = vec![1].into();
+        sibling.block.header = header.seal_slow();
+
+        let commit = CanonStateNotification::Commit {
+            new: Arc::new(Chain::new(vec![block1, block2.clone()], Default::default())),
+        };
+        let reorg = CanonStateNotification::Reorg {
+            old: Arc::new(Chain::new(vec![block2.clone()], Default::default())),
+            new: Arc::new(Chain::new(vec![sibling.clone()], Default::default())),
+        };
+
+        let mut out = Vec::new();
+        write_notification(&mut out, &commit).unwrap();
+        write_notification(&mut out, &reorg).unwrap();
+        let lines = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<BlockLogLine>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[..2].iter().all(|line| line.reorg.is_none()));
+        assert_eq!(lines[2], BlockLogLine::new(&block2.block, Some(ReorgMarker::Removed)));
+        assert_eq!(lines[3], BlockLogLine::new(&sibling.block, Some(ReorgMarker::Added)));
+        assert_eq!(lines[2].number, lines[3].number);
+        assert_ne!(lines[2].hash, lines[3].hash);
+    }
+
+    #[test]
+    fn parse_sink() {
+        assert_eq!(
+            "tcp://127.0.0.1:9000".parse::<BlockLogSink>().unwrap(),
+            BlockLogSink::Tcp("127.0.0.1:9000".parse().unwrap())
+        );
+        assert_eq!(
+            "/tmp/blocks.jsonl".parse::<BlockLogSink>().unwrap(),
+            BlockLogSink::File("/tmp/blocks.jsonl".into())
+        );
+        assert!("tcp://localhost".parse::<BlockLogSink>().is_err());
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
         get_secret_key,
         utils::{genesis_value_parser, parse_socket_address},
         DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-        RpcServerArgs, TxPoolArgs,
+        RpcServerArgs, TxPoolArgs, WarmupArgs,
     },
     cli::{
         components::RethNodeComponentsImpl,
@@ -15,7 +15,7 @@ use crate::{
     },
     dirs::{DataDirPath, MaybePlatformPath},
     init::init_genesis,
-    node::cl_events::ConsensusLayerHealthEvents,
+    node::{block_log::BlockLogSink, cl_events::ConsensusLayerHealthEvents},
     prometheus_exporter,
     runner::CliContext,
     utils::get_single_header,
@@ -31,7 +31,8 @@ use reth_beacon_consensus::{
     BeaconConsensus, BeaconConsensusEngine, MIN_BLOCKS_FOR_PIPELINE_RUN,
 };
 use reth_blockchain_tree::{
-    config::BlockchainTreeConfig, externals::TreeExternals, BlockchainTree, ShareableBlockchainTree,
+    config::BlockchainTreeConfig, externals::TreeExternals, warm_up, BlockchainTree,
+    ShareableBlockchainTree,
 };
 use reth_config::{config::PruneConfig, Config};
 use reth_db::{database::Database, init_db, DatabaseEnv};
@@ -46,6 +47,7 @@ use reth_interfaces::{
         either::EitherDownloader,
         headers::{client::HeadersClient, downloader::HeaderDownloader},
     },
+    sync::NetworkSyncUpdater,
     RethResult,
 };
 use reth_network::{error::NetworkError, NetworkConfig, NetworkHandle, NetworkManager};
@@ -58,7 +60,7 @@ use reth_primitives::{
 };
 use reth_provider::{
     providers::BlockchainProvider, BlockHashReader, BlockReader, CanonStateSubscriptions,
-    HeaderProvider, ProviderFactory, StageCheckpointReader,
+    HeaderProvider, ProviderFactory, StageCheckpointReader, StateCache,
 };
 use reth_prune::{segments::SegmentSet, Pruner};
 use reth_revm::Factory;
@@ -74,7 +76,10 @@ use reth_stages::{
     },
     MetricEventsSender, MetricsListener,
 };
-use reth_tasks::TaskExecutor;
+use reth_tasks::{
+    supervisor::{RestartPolicy, TaskSupervisor},
+    TaskExecutor,
+};
 use reth_transaction_pool::{
     blobstore::InMemoryBlobStore, TransactionPool, TransactionValidationTaskExecutor,
 };
@@ -87,6 +92,7 @@ use std::{
 use tokio::sync::{mpsc::unbounded_channel, oneshot, watch};
 use tracing::*;
 
+pub mod block_log;
 pub mod cl_events;
 pub mod events;
 
@@ -154,6 +160,12 @@ pub struct NodeCommand<Ext: RethCliExt = ()> {
     #[arg(long, value_name = "PATH")]
     pub trusted_setup_file: Option<PathBuf>,
 
+    /// Writes a JSON line for every canonical block to the given file or `tcp://` address.
+    ///
+    /// Blocks that are removed by a reorg are written again with a reorg marker.
+    #[arg(long, value_name = "PATH_OR_TCP")]
+    pub block_log: Option<BlockLogSink>,
+
     /// All networking related arguments
     #[clap(flatten)]
     pub network: NetworkArgs,
@@ -186,6 +198,10 @@ pub struct NodeCommand<Ext: RethCliExt = ()> {
     #[clap(flatten)]
     pub pruning: PruningArgs,
 
+    /// All state warmup related arguments with --warmup prefix
+    #[clap(flatten)]
+    pub warmup: WarmupArgs,
+
     /// Additional cli arguments
     #[clap(flatten)]
     pub ext: Ext::Node,
@@ -201,6 +217,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             metrics,
             trusted_setup_file,
             instance,
+            block_log,
             network,
             rpc,
             txpool,
@@ -209,6 +226,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            warmup,
             ..
         } = self;
         NodeCommand {
@@ -218,6 +236,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             metrics,
             instance,
             trusted_setup_file,
+            block_log,
             network,
             rpc,
             txpool,
@@ -226,6 +245,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            warmup,
             ext,
         }
     }
@@ -258,6 +278,12 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
 
         let genesis_hash = init_genesis(db.clone(), self.chain.clone())?;
 
+        // migrate the stored data before anything reads it, this fails if the database was
+        // written by a newer version
+        let storage_version =
+            ProviderFactory::new(Arc::clone(&db), Arc::clone(&self.chain)).migrate()?;
+        debug!(target: "reth::cli", storage_version, "Database migrated");
+
         info!(target: "reth::cli", "{}", DisplayHardforks::from(self.chain.hardforks().clone()));
 
         let consensus: Arc<dyn Consensus> = if self.dev.dev {
@@ -278,12 +304,17 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             self.pruning.prune_config(Arc::clone(&self.chain))?.or(config.prune.clone());
 
         // configure blockchain tree
-        let tree_externals = TreeExternals::new(
+        let warmup_config = self.warmup.warmup_config();
+        let state_cache = warmup_config.map(|_| Arc::new(StateCache::default()));
+        let mut tree_externals = TreeExternals::new(
             Arc::clone(&db),
             Arc::clone(&consensus),
             Factory::new(self.chain.clone()),
             Arc::clone(&self.chain),
         );
+        if let Some(state_cache) = &state_cache {
+            tree_externals = tree_externals.with_state_cache(Arc::clone(state_cache));
+        }
         let tree = BlockchainTree::new(
             tree_externals,
             BlockchainTreeConfig::default(),
@@ -298,6 +329,13 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
 
         // setup the blockchain provider
         let factory = ProviderFactory::new(Arc::clone(&db), Arc::clone(&self.chain));
+
+        // preload the hot state before the engine starts executing blocks
+        if let Some((config, state_cache)) = warmup_config.zip(state_cache) {
+            info!(target: "reth::cli", ?config, "Warming up state");
+            warm_up(&factory, &state_cache, config)?;
+        }
+
         let blockchain_db = BlockchainProvider::new(factory, blockchain_tree.clone())?;
         let blob_store = InMemoryBlobStore::default();
         let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&self.chain))
@@ -310,24 +348,34 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             reth_transaction_pool::Pool::eth_pool(validator, blob_store, self.txpool.pool_config());
         info!(target: "reth::cli", "Transaction pool initialized");
 
-        // spawn txpool maintenance task
+        // spawn txpool maintenance task, which is restarted if it panics
         {
             let pool = transaction_pool.clone();
-            let chain_events = blockchain_db.canonical_state_stream();
             let client = blockchain_db.clone();
-            ctx.task_executor.spawn_critical(
+            let executor = ctx.task_executor.clone();
+            TaskSupervisor::new(ctx.task_executor.clone()).spawn_supervised(
                 "txpool maintenance task",
-                reth_transaction_pool::maintain::maintain_transaction_pool_future(
-                    client,
-                    pool,
-                    chain_events,
-                    ctx.task_executor.clone(),
-                    Default::default(),
-                ),
+                RestartPolicy::WithBackoff(Default::default()),
+                move || {
+                    // the restarted task needs a new subscription to the canonical state
+                    reth_transaction_pool::maintain::maintain_transaction_pool_future(
+                        client.clone(),
+                        pool.clone(),
+                        client.canonical_state_stream(),
+                        executor.clone(),
+                        Default::default(),
+                    )
+                },
             );
             debug!(target: "reth::cli", "Spawned txpool maintenance task");
         }
 
+        if let Some(sink) = self.block_log.clone() {
+            let chain_events = blockchain_db.canonical_state_stream();
+            ctx.task_executor.spawn(block_log::log_canonical_blocks(chain_events, sink.clone()));
+            info!(target: "reth::cli", %sink, "Block log enabled");
+        }
+
         info!(target: "reth::cli", "Connecting to P2P network");
         let network_secret_path =
             self.network.p2p_secret_key.clone().unwrap_or_else(|| data_dir.p2p_secret_path());
@@ -411,6 +459,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                     metrics_tx,
                     prune_config.clone(),
                     max_block,
+                    Box::new(network.clone()),
                 )
                 .await?;
 
@@ -431,12 +480,20 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                     metrics_tx,
                     prune_config.clone(),
                     max_block,
+                    Box::new(network.clone()),
                 )
                 .await?;
 
             (pipeline, EitherDownloader::Right(network_client))
         };
 
+        // Stage checkpoints can be mutually inconsistent if the node crashed during a multi-stage
+        // commit or the database was copied mid-sync.
+        let adjustments = pipeline.reconcile_checkpoints().await?;
+        if !adjustments.is_empty() {
+            info!(target: "reth::cli", ?adjustments, "Reconciled inconsistent stage checkpoints");
+        }
+
         let pipeline_events = pipeline.events();
 
         let initial_target = if let Some(tip) = self.debug.tip {
@@ -567,6 +624,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         metrics_tx: MetricEventsSender,
         prune_config: Option<PruneConfig>,
         max_block: Option<BlockNumber>,
+        sync_state_updater: Box<dyn NetworkSyncUpdater>,
     ) -> eyre::Result<Pipeline<DB>>
     where
         DB: Database + Unpin + Clone + 'static,
@@ -592,6 +650,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                 self.debug.continuous,
                 metrics_tx,
                 prune_config,
+                sync_state_updater,
             )
             .await?;
 
@@ -791,6 +850,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         continuous: bool,
         metrics_tx: MetricEventsSender,
         prune_config: Option<PruneConfig>,
+        sync_state_updater: Box<dyn NetworkSyncUpdater>,
     ) -> eyre::Result<Pipeline<DB>>
     where
         DB: Database + Clone + 'static,
@@ -832,6 +892,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         let pipeline = builder
             .with_tip_sender(tip_tx)
             .with_metrics_tx(metrics_tx.clone())
+            .with_sync_state_updater(sync_state_updater)
             .add_stages(
                 DefaultStages::new(
                     header_mode,
@@ -1092,6 +1153,7 @@ mod tests {
 
         assert!(cmd.rpc.http);
         assert!(cmd.network.discovery.disable_discovery);
+        assert_eq!(cmd.rpc.dev_mnemonic.as_deref(), Some(reth_rpc::eth::DEV_MNEMONIC));
 
         assert!(cmd.dev.dev);
     }
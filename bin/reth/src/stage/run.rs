@@ -187,6 +187,12 @@ impl Command {
                                 config.stages.bodies.downloader_min_concurrent_requests..=
                                     config.stages.bodies.downloader_max_concurrent_requests,
                             )
+                            .with_validation_parallelism(
+                                config.stages.bodies.downloader_validation_parallelism,
+                            )
+                            .with_max_pending_validations(
+                                config.stages.bodies.downloader_max_pending_validations,
+                            )
                             .build(fetch_client, consensus.clone(), db.clone()),
                         consensus: consensus.clone(),
                     };
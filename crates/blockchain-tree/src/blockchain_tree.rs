@@ -28,7 +28,7 @@ use reth_provider::{
 };
 use reth_stages::{MetricEvent, MetricEventsSender};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     sync::Arc,
 };
 use tracing::{debug, error, info, instrument, trace, warn};
@@ -130,6 +130,7 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
                 last_finalized_block_number,
                 last_canonical_hashes,
                 config.max_unconnected_blocks(),
+                config.max_trie_updates_cache_size(),
             ),
             config,
             canon_state_notification_sender,
@@ -374,7 +375,7 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         // TODO save pending block to database
         // https://github.com/paradigmxyz/reth/issues/1713
 
-        let (block_status, chain) = {
+        let (block_status, chain, trie_updates) = {
             let factory = self.externals.database();
             let provider = factory
                 .provider()
@@ -414,14 +415,19 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
             let canonical_chain = self.canonical_chain();
 
             if block.parent_hash == canonical_chain.tip().hash {
-                let chain = AppendableChain::new_canonical_head_fork(
+                let optimistic = self.is_optimistic(block.number);
+                let (chain, trie_updates) = AppendableChain::new_canonical_head_fork(
                     block,
                     &parent_header,
                     canonical_chain.inner(),
                     parent,
                     &self.externals,
+                    optimistic,
                 )?;
-                (BlockStatus::Valid, chain)
+                if optimistic {
+                    self.metrics.optimistic_blocks.increment(1);
+                }
+                (BlockStatus::Valid, chain, trie_updates)
             } else {
                 let chain = AppendableChain::new_canonical_fork(
                     block,
@@ -430,10 +436,13 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
                     parent,
                     &self.externals,
                 )?;
-                (BlockStatus::Accepted, chain)
+                (BlockStatus::Accepted, chain, None)
             }
         };
 
+        if let Some(trie_updates) = trie_updates {
+            self.state.trie_updates.insert(block_num_hash.hash, parent.hash, trie_updates);
+        }
         self.insert_chain(chain);
         self.try_connect_buffered_blocks(block_num_hash);
         Ok(block_status)
@@ -465,6 +474,8 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
             Some(fork) => fork,
         };
 
+        let optimistic = self.is_optimistic(block.number);
+
         // get chain that block needs to join to.
         let parent_chain = match self.state.chains.get_mut(&chain_id) {
             Some(parent_chain) => parent_chain,
@@ -482,16 +493,19 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         // append the block if it is continuing the side chain.
         let status = if chain_tip == block.parent_hash {
             // check if the chain extends the currently tracked canonical head
-            let block_kind = if canonical_fork.hash == canonical_chain.tip().hash {
-                BlockKind::ExtendsCanonicalHead
-            } else {
+            let block_kind = if canonical_fork.hash != canonical_chain.tip().hash {
                 BlockKind::ForksHistoricalBlock
+            } else if optimistic {
+                self.metrics.optimistic_blocks.increment(1);
+                BlockKind::Optimistic
+            } else {
+                BlockKind::ExtendsCanonicalHead
             };
 
             debug!(target: "blockchain_tree", "Appending block to side chain");
             let block_hash = block.hash();
             let block_number = block.number;
-            parent_chain.append_block(
+            let trie_updates = parent_chain.append_block(
                 block,
                 block_hashes,
                 canonical_chain.inner(),
@@ -499,6 +513,9 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
                 canonical_fork,
                 block_kind,
             )?;
+            if let Some(trie_updates) = trie_updates {
+                self.state.trie_updates.insert(block_hash, canonical_fork.hash, trie_updates);
+            }
 
             self.block_indices_mut().insert_non_fork_block(block_number, block_hash, chain_id);
 
@@ -542,7 +559,11 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         let mut hashes = BTreeMap::new();
         loop {
             let Some(chain) = self.state.chains.get(&chain_id) else { return hashes };
-            hashes.extend(chain.blocks().values().map(|b| (b.number, b.hash())));
+            // the blocks of a parent chain above the fork are not ancestors, and must not replace
+            // the blocks of the forked chain at the same height
+            for block in chain.blocks().values() {
+                hashes.entry(block.number).or_insert_with(|| block.hash());
+            }
 
             let fork_block = chain.fork_block_hash();
             if let Some(next_chain_id) = self.block_indices().get_blocks_chain_id(&fork_block) {
@@ -704,7 +725,86 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
             return Err(InsertBlockError::consensus_error(err, block.block))
         }
 
-        Ok(InsertPayloadOk::Inserted(self.try_insert_validated_block(block)?))
+        let block_hash = block.hash;
+        let status = self.try_insert_validated_block(block)?;
+        self.evict_side_chains(block_hash);
+        Ok(InsertPayloadOk::Inserted(status))
+    }
+
+    /// Evicts stale side chains until the estimated memory usage of all side chains is within the
+    /// budget, see [BlockchainTreeConfig::max_side_chains_memory].
+    ///
+    /// The chains whose tip is furthest below the canonical head are evicted first, together with
+    /// the chains that fork off them. The chain of the given block, its parent chains and the
+    /// chains that extend the canonical head are never evicted. Buffered blocks are kept.
+    fn evict_side_chains(&mut self, block_hash: BlockHash) {
+        let mut sizes = self
+            .state
+            .chains
+            .iter()
+            .map(|(chain_id, chain)| (*chain_id, chain.size_hint()))
+            .collect::<HashMap<_, _>>();
+        let mut total = sizes.values().sum::<usize>();
+        let max_size = self.config.max_side_chains_memory();
+
+        if total > max_size {
+            // the chain of the block and its parent chains
+            let mut keep = Vec::new();
+            let mut next = self.block_indices().get_blocks_chain_id(&block_hash);
+            while let Some(chain_id) = next {
+                keep.push(chain_id);
+                next = self.state.chains.get(&chain_id).and_then(|chain| {
+                    self.block_indices().get_blocks_chain_id(&chain.fork_block_hash())
+                });
+            }
+
+            let canonical_tip = self.canonical_chain().tip();
+            let mut stale = self
+                .state
+                .chains
+                .iter()
+                .filter(|(chain_id, chain)| {
+                    !keep.contains(chain_id) && chain.fork_block_hash() != canonical_tip.hash
+                })
+                .map(|(chain_id, chain)| (chain.tip().number, *chain_id))
+                .collect::<Vec<_>>();
+            stale.sort_unstable();
+
+            for (_, chain_id) in stale {
+                if total <= max_size {
+                    break
+                }
+
+                let mut remove_chains = BTreeSet::from([chain_id]);
+                while let Some(chain_id) = remove_chains.pop_first() {
+                    let Some(chain) = self.state.chains.remove(&chain_id) else { continue };
+                    let size = sizes.remove(&chain_id).unwrap_or_default();
+                    total -= size;
+                    remove_chains.extend(self.state.block_indices.remove_chain(&chain));
+                    self.state
+                        .trie_updates
+                        .remove_all(chain.blocks().values().map(|block| &block.hash));
+                    self.metrics.evicted_sidechains.increment(1);
+                    info!(
+                        target: "blockchain_tree",
+                        ?chain_id,
+                        blocks = %DisplayBlocksChain(chain.blocks()),
+                        size,
+                        "Evicted stale side chain over the memory budget"
+                    );
+                }
+            }
+        }
+
+        self.metrics.sidechains_memory.set(total as f64);
+    }
+
+    /// Returns `true` if a block at the given height is executed optimistically, i.e. its state
+    /// root is only verified when the block is committed to the database.
+    fn is_optimistic(&self, block_number: BlockNumber) -> bool {
+        self.config.optimistic_execution_distance().map_or(false, |distance| {
+            block_number > self.block_indices().last_finalized_block() + distance
+        })
     }
 
     /// Finalize blocks up until and including `finalized_block`, and remove them from the tree.
@@ -979,10 +1079,15 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
 
         // if joins to the tip;
         if new_canon_chain.fork_block_hash() == old_tip.hash {
-            chain_notification =
-                CanonStateNotification::Commit { new: Arc::new(new_canon_chain.clone()) };
+            let new = Arc::new(new_canon_chain);
             // append to database
-            self.commit_canonical(new_canon_chain)?;
+            if let Err(err) = self.commit_canonical((*new).clone()) {
+                // the state root of an optimistically executed block can fail to verify here, in
+                // which case none of the blocks of the chain is kept
+                self.invalidate_uncommitted_chain(old_tip.number, &new);
+                return Err(err)
+            }
+            chain_notification = CanonStateNotification::Commit { new };
         } else {
             // it forks to canonical block that is not the tip.
 
@@ -1060,18 +1165,38 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
     }
 
     /// Canonicalize the given chain and commit it to the database.
-    fn commit_canonical(&self, chain: Chain) -> RethResult<()> {
+    ///
+    /// The trie updates that were computed while validating the tip of the chain are written
+    /// instead of recomputing the trie, if they were computed against the block the chain forks
+    /// off, which is the canonical tip in the database at this point.
+    fn commit_canonical(&mut self, chain: Chain) -> RethResult<()> {
         let provider = DatabaseProvider::new_rw(
             self.externals.db.tx_mut()?,
             self.externals.chain_spec.clone(),
         );
 
+        let trie_updates =
+            self.state.trie_updates.take(&chain.tip().hash(), &chain.fork_block_hash());
+        if trie_updates.is_some() {
+            self.state.trie_updates.reused += 1;
+        } else {
+            self.state.trie_updates.recomputed += 1;
+        }
+
+        if let Some(cache) = &self.externals.state_cache {
+            // the cached state of the accounts changed by the chain is stale once it is committed
+            cache.advance(chain.tip().num_hash(), chain.state().state().state().keys());
+        }
+
         let (blocks, state) = chain.into_inner();
+        // the trie updates of the committed blocks are computed against a stale canonical tip now
+        self.state.trie_updates.remove_all(blocks.iter().map(|(_, block)| &block.hash));
 
         provider
             .append_blocks_with_bundle_state(
                 blocks.into_blocks().collect(),
                 state,
+                trie_updates,
                 self.prune_modes.as_ref(),
             )
             .map_err(|e| BlockExecutionError::CanonicalCommit { inner: e.to_string() })?;
@@ -1081,6 +1206,34 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         Ok(())
     }
 
+    /// Drop a chain that was canonicalized in the indices but could not be committed to the
+    /// database, together with all chains that fork off it.
+    ///
+    /// The canonical chain is unwound to `last_committed`, the tip that is still in the database.
+    fn invalidate_uncommitted_chain(&mut self, last_committed: BlockNumber, chain: &Chain) {
+        warn!(
+            target: "blockchain_tree",
+            blocks = %DisplayBlocksChain(chain.blocks()),
+            "Discarding chain that failed to commit"
+        );
+        self.block_indices_mut().unwind_canonical_chain(last_committed);
+        if let Some(cache) = &self.externals.state_cache {
+            // the cache was already moved to the tip of the chain
+            cache.clear();
+        }
+        self.state.trie_updates.remove_all(chain.blocks().values().map(|block| &block.hash));
+
+        let mut remove_chains = self.state.block_indices.remove_chain(chain);
+        while let Some(chain_id) = remove_chains.pop_first() {
+            if let Some(chain) = self.state.chains.remove(&chain_id) {
+                remove_chains.extend(self.state.block_indices.remove_chain(&chain));
+                self.state
+                    .trie_updates
+                    .remove_all(chain.blocks().values().map(|block| &block.hash));
+            }
+        }
+    }
+
     /// Unwind tables and put it inside state
     pub fn unwind(&mut self, unwind_to: BlockNumber) -> RethResult<()> {
         // nothing to be done if unwind_to is higher then the tip
@@ -1113,6 +1266,9 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
 
         let tip = provider.last_block_number()?;
         let revert_range = (revert_until + 1)..=tip;
+        if let Some(cache) = &self.externals.state_cache {
+            cache.clear();
+        }
         info!(target: "blockchain_tree", "Unwinding canonical chain blocks: {:?}", revert_range);
         // read block and execution result from database. and remove traces of block from tables.
         let blocks_and_execution = provider
@@ -1174,12 +1330,14 @@ mod tests {
     use reth_db::{test_utils::create_test_rw_db, transaction::DbTxMut, DatabaseEnv};
     use reth_interfaces::test_utils::TestConsensus;
     use reth_primitives::{
-        proofs::EMPTY_ROOT, stage::StageCheckpoint, ChainSpecBuilder, B256, MAINNET,
+        proofs::EMPTY_ROOT, stage::StageCheckpoint, Account, Address, ChainSpecBuilder, Header,
+        Receipts, B256, MAINNET,
     };
     use reth_provider::{
         test_utils::{blocks::BlockChainTestData, TestExecutorFactory},
         BlockWriter, BundleStateWithReceipts, ProviderFactory,
     };
+    use reth_trie::{test_utils::state_root, StateRoot};
     use std::{collections::HashSet, sync::Arc};
 
     fn setup_externals(
@@ -1662,4 +1820,272 @@ mod tests {
             .with_buffered_blocks(BTreeMap::from([]))
             .assert(&tree);
     }
+
+    #[tokio::test]
+    async fn evicts_stale_side_chains_over_memory_budget() {
+        let data = BlockChainTestData::default_with_numbers(11, 12);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let genesis_hash = block1.parent_hash;
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec2.clone(), exec1.clone(), exec2, exec1]);
+        setup_genesis(externals.db.clone(), data.genesis);
+        let mut tree = BlockchainTree::new(externals, BlockchainTreeConfig::new(1, 2, 3, 2), None)
+            .expect("failed to create tree");
+        tree.make_canonical(&B256::ZERO).unwrap();
+        tree.finalize_block(10);
+
+        assert_eq!(
+            tree.insert_block(block1.clone()).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid)
+        );
+        tree.make_canonical(&block1.hash).unwrap();
+
+        // large blocks that can't be executed by the test executor
+        let fake_block =
+            |number: BlockNumber, parent_hash: B256, salt: u8| SealedBlockWithSenders {
+                block: SealedBlock {
+                    header: Header {
+                        number,
+                        parent_hash,
+                        extra_data: vec![salt; 64 * 1024].into(),
+                        ..Default::default()
+                    }
+                    .seal_slow(),
+                    body: vec![],
+                    ommers: vec![],
+                    withdrawals: Some(vec![]),
+                },
+                senders: vec![],
+            };
+
+        // two stale side chains off g1, [stale1] and [stale2, stale2_child]
+        let stale1 = fake_block(11, genesis_hash, 1);
+        let stale2 = fake_block(11, genesis_hash, 2);
+        let stale2_child = fake_block(12, stale2.hash, 3);
+        let chain1 = AppendableChain::new(Chain::new(vec![stale1.clone()], Default::default()));
+        let chain2 = AppendableChain::new(Chain::new(
+            vec![stale2.clone(), stale2_child.clone()],
+            Default::default(),
+        ));
+        let block_size = chain1.size_hint();
+        let chain1_id = tree.state.insert_chain(chain1).unwrap();
+        let chain2_id = tree.state.insert_chain(chain2).unwrap();
+
+        // a disconnected block stays buffered
+        let buffered = fake_block(14, B256::random(), 4);
+        tree.buffer_block(buffered.clone()).unwrap();
+
+        // the stale chains and the new pending block don't fit into the budget
+        tree.config = tree.config.with_max_side_chains_memory(block_size * 5 / 2);
+        assert_eq!(
+            tree.insert_block(block2.clone()).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid)
+        );
+
+        // the chain with the lowest tip is evicted first, the pending block is kept
+        let pending_id = tree.block_indices().get_blocks_chain_id(&block2.hash).unwrap();
+        assert_eq!(
+            tree.state.chains.keys().copied().collect::<HashSet<_>>(),
+            HashSet::from([chain2_id, pending_id])
+        );
+        assert!(!tree.state.chains.contains_key(&chain1_id));
+        assert_eq!(tree.block_indices().get_blocks_chain_id(&stale1.hash), None);
+        assert_eq!(tree.block_indices().get_blocks_chain_id(&stale2_child.hash), Some(chain2_id));
+        assert_eq!(tree.get_buffered_block(&buffered.hash), Some(&buffered));
+
+        // the pending block is still canonicalized
+        tree.make_canonical(&block2.hash).unwrap();
+        assert_eq!(tree.canonical_chain().tip(), block2.num_hash());
+        assert!(!tree.state.chains.contains_key(&pending_id));
+    }
+
+    #[tokio::test]
+    async fn canonicalize_with_cached_trie_updates() {
+        let data = BlockChainTestData::default();
+        let mut accounts = BTreeMap::new();
+        let mut parent_hash = B256::ZERO;
+        let mut blocks = Vec::new();
+        let mut exec_res = Vec::new();
+        for number in 11..=20 {
+            let mut state_init = HashMap::new();
+            let mut reverts = HashMap::new();
+            for _ in 0..16 {
+                let address = Address::random();
+                let account =
+                    Account { nonce: 1, balance: U256::from(number), bytecode_hash: None };
+                accounts.insert(address, account);
+                state_init.insert(address, (None, Some(account), HashMap::new()));
+                reverts.insert(address, (Some(None), vec![]));
+            }
+            let state_root = state_root(
+                accounts
+                    .iter()
+                    .map(|(address, account)| (*address, (*account, Vec::<(B256, U256)>::new()))),
+            );
+            let header =
+                Header { number, parent_hash, state_root, ..Default::default() }.seal_slow();
+            parent_hash = header.hash();
+            blocks.push(SealedBlockWithSenders {
+                block: SealedBlock {
+                    header,
+                    body: vec![],
+                    ommers: vec![],
+                    withdrawals: Some(vec![]),
+                },
+                senders: vec![],
+            });
+            exec_res.push(BundleStateWithReceipts::new_init(
+                state_init,
+                HashMap::from([(number, reverts)]),
+                vec![],
+                Receipts::from_vec(vec![vec![]]),
+                number,
+            ));
+        }
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(exec_res.into_iter().rev().collect());
+        setup_genesis(externals.db.clone(), data.genesis);
+        let mut tree =
+            BlockchainTree::new(externals, BlockchainTreeConfig::default(), None).unwrap();
+
+        for block in blocks.iter().cloned() {
+            assert_eq!(
+                tree.insert_block(block).unwrap(),
+                InsertPayloadOk::Inserted(BlockStatus::Valid)
+            );
+        }
+        let tip = blocks.last().unwrap();
+        tree.make_canonical(&tip.hash).unwrap();
+        assert_eq!(tree.state.trie_updates.reused, 1);
+        assert_eq!(tree.state.trie_updates.recomputed, 0);
+
+        // the written trie matches a trie that is rebuilt from the hashed state
+        let factory = ProviderFactory::new(tree.externals.db.clone(), MAINNET.clone());
+        let provider = factory.provider_rw().unwrap();
+        let written = provider.table::<tables::AccountsTrie>().unwrap();
+        assert!(!written.is_empty());
+        provider.tx_ref().clear::<tables::AccountsTrie>().unwrap();
+        provider.tx_ref().clear::<tables::StoragesTrie>().unwrap();
+        let (root, updates) = StateRoot::new(provider.tx_ref()).root_with_updates().unwrap();
+        assert_eq!(root, tip.state_root);
+        updates.flush(provider.tx_ref()).unwrap();
+        assert_eq!(provider.table::<tables::AccountsTrie>().unwrap(), written);
+    }
+
+    /// Returns the blocks on top of the test genesis with the given length and their execution
+    /// results, where the block at `corrupt` sets a balance that does not match its state root.
+    fn optimistic_chain(
+        len: u64,
+        corrupt: Option<BlockNumber>,
+    ) -> (Vec<SealedBlockWithSenders>, Vec<BundleStateWithReceipts>) {
+        let mut accounts = BTreeMap::new();
+        let mut parent_hash = B256::ZERO;
+        let mut blocks = Vec::new();
+        let mut exec_res = Vec::new();
+        for number in 11..11 + len {
+            let mut state_init = HashMap::new();
+            let mut reverts = HashMap::new();
+            for _ in 0..4 {
+                let address = Address::random();
+                let account =
+                    Account { nonce: 1, balance: U256::from(number), bytecode_hash: None };
+                accounts.insert(address, account);
+                let executed = if corrupt == Some(number) {
+                    Account { balance: U256::MAX, ..account }
+                } else {
+                    account
+                };
+                state_init.insert(address, (None, Some(executed), HashMap::new()));
+                reverts.insert(address, (Some(None), vec![]));
+            }
+            let state_root = state_root(
+                accounts
+                    .iter()
+                    .map(|(address, account)| (*address, (*account, Vec::<(B256, U256)>::new()))),
+            );
+            let header =
+                Header { number, parent_hash, state_root, ..Default::default() }.seal_slow();
+            parent_hash = header.hash();
+            blocks.push(SealedBlockWithSenders {
+                block: SealedBlock {
+                    header,
+                    body: vec![],
+                    ommers: vec![],
+                    withdrawals: Some(vec![]),
+                },
+                senders: vec![],
+            });
+            exec_res.push(BundleStateWithReceipts::new_init(
+                state_init,
+                HashMap::from([(number, reverts)]),
+                vec![],
+                Receipts::from_vec(vec![vec![]]),
+                number,
+            ));
+        }
+        (blocks, exec_res)
+    }
+
+    #[tokio::test]
+    async fn canonicalize_optimistic_blocks() {
+        let data = BlockChainTestData::default();
+        let (blocks, exec_res) = optimistic_chain(500, None);
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(exec_res.into_iter().rev().collect());
+        setup_genesis(externals.db.clone(), data.genesis);
+        let config = BlockchainTreeConfig::default().with_optimistic_execution(0);
+        let mut tree = BlockchainTree::new(externals, config, None).unwrap();
+
+        for block in blocks.iter().cloned() {
+            assert_eq!(
+                tree.insert_block(block).unwrap(),
+                InsertPayloadOk::Inserted(BlockStatus::Valid)
+            );
+        }
+
+        // the state root is verified once for the whole chain
+        let tip = blocks.last().unwrap();
+        tree.make_canonical(&tip.hash).unwrap();
+        assert_eq!(tree.state.trie_updates.reused, 0);
+        assert_eq!(tree.state.trie_updates.recomputed, 1);
+        assert_eq!(tree.block_indices().canonical_tip(), tip.num_hash());
+        assert!(tree.state.chains.is_empty());
+    }
+
+    #[tokio::test]
+    async fn discard_optimistic_blocks_with_invalid_state_root() {
+        let data = BlockChainTestData::default();
+        let (blocks, exec_res) = optimistic_chain(10, Some(15));
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(exec_res.into_iter().rev().collect());
+        let genesis = BlockNumHash::new(10, data.genesis.hash);
+        setup_genesis(externals.db.clone(), data.genesis);
+        let config = BlockchainTreeConfig::default().with_optimistic_execution(0);
+        let mut tree = BlockchainTree::new(externals, config, None).unwrap();
+
+        for block in blocks.iter().cloned() {
+            assert_eq!(
+                tree.insert_block(block).unwrap(),
+                InsertPayloadOk::Inserted(BlockStatus::Valid)
+            );
+        }
+
+        // canonicalize only part of the chain, the rest stays as a side chain
+        let target = &blocks[7];
+        assert!(tree.make_canonical(&target.hash).is_err());
+
+        // nothing was committed and all blocks were dropped from the tree
+        assert_eq!(tree.block_indices().canonical_tip(), genesis);
+        assert!(tree.state.chains.is_empty());
+        for block in &blocks {
+            assert_eq!(tree.block_indices().get_blocks_chain_id(&block.hash), None);
+        }
+        let provider = tree.externals.database().provider().unwrap();
+        assert_eq!(provider.last_block_number().unwrap(), genesis.number);
+    }
 }
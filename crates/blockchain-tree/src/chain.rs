@@ -11,17 +11,24 @@ use reth_interfaces::{
     RethResult,
 };
 use reth_primitives::{
-    BlockHash, BlockNumber, ForkBlock, SealedBlockWithSenders, SealedHeader, U256,
+    constants::BLOCK_HASH_HISTORY, Address, BlockHash, BlockNumber, ForkBlock, Log, Receipt,
+    SealedBlockWithSenders, SealedHeader, B256, U256,
 };
 use reth_provider::{
-    providers::BundleStateProvider, BundleStateDataProvider, BundleStateWithReceipts, Chain,
-    ExecutorFactory, StateRootProvider,
+    providers::{BundleStateProvider, CachedStateProvider},
+    BundleStateDataProvider, BundleStateWithReceipts, Chain, ExecutorFactory, StateRootProvider,
 };
+use reth_trie::updates::TrieUpdates;
 use std::{
     collections::BTreeMap,
+    mem,
     ops::{Deref, DerefMut},
 };
 
+/// The estimated memory usage of a single account, storage slot, revert or bytecode entry of the
+/// bundle state in bytes.
+const BUNDLE_STATE_ENTRY_SIZE: usize = 128;
+
 /// A chain if the blockchain tree, that has functionality to execute blocks and append them to the
 /// it self.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -54,16 +61,40 @@ impl AppendableChain {
         self.chain
     }
 
+    /// Calculates a heuristic for the in-memory size of the blocks, receipts and bundle state of
+    /// the chain in bytes.
+    pub fn size_hint(&self) -> usize {
+        let blocks = self
+            .blocks()
+            .values()
+            .map(|block| block.size() + block.senders.capacity() * mem::size_of::<Address>())
+            .sum::<usize>();
+        let receipts = self
+            .state()
+            .receipts()
+            .receipt_vec
+            .iter()
+            .flatten()
+            .map(|receipt| {
+                receipt.as_ref().map_or(0, receipt_size) + mem::size_of::<Option<Receipt>>()
+            })
+            .sum::<usize>();
+        blocks + receipts + self.state().state().size_hint() * BUNDLE_STATE_ENTRY_SIZE
+    }
+
     /// Create a new chain that forks off the canonical.
     ///
-    /// This will also verify the state root of the block extending the canonical chain.
+    /// This will also verify the state root of the block extending the canonical chain, and
+    /// returns the trie updates that were computed for it. If `optimistic` is set, the state root
+    /// is not verified until the chain is canonicalized.
     pub fn new_canonical_head_fork<DB, EF>(
         block: SealedBlockWithSenders,
         parent_header: &SealedHeader,
         canonical_block_hashes: &BTreeMap<BlockNumber, BlockHash>,
         canonical_fork: ForkBlock,
         externals: &TreeExternals<DB, EF>,
-    ) -> Result<Self, InsertBlockError>
+        optimistic: bool,
+    ) -> Result<(Self, Option<TrieUpdates>), InsertBlockError>
     where
         DB: Database,
         EF: ExecutorFactory,
@@ -78,15 +109,18 @@ impl AppendableChain {
             canonical_fork,
         };
 
-        let bundle_state = Self::validate_and_execute_canonical_head_descendant(
+        let block_kind =
+            if optimistic { BlockKind::Optimistic } else { BlockKind::ExtendsCanonicalHead };
+        let (bundle_state, trie_updates) = Self::validate_and_execute(
             block.clone(),
             parent_header,
             state_provider,
             externals,
+            block_kind,
         )
         .map_err(|err| InsertBlockError::new(block.block.clone(), err.into()))?;
 
-        Ok(Self { chain: Chain::new(vec![block], bundle_state) })
+        Ok((Self { chain: Chain::new(vec![block], bundle_state) }, trie_updates))
     }
 
     /// Create a new chain that forks off of the canonical chain.
@@ -111,7 +145,7 @@ impl AppendableChain {
             canonical_fork,
         };
 
-        let bundle_state = Self::validate_and_execute_sidechain(
+        let (bundle_state, _) = Self::validate_and_execute_sidechain(
             block.clone(),
             parent_header,
             state_provider,
@@ -157,7 +191,7 @@ impl AppendableChain {
             canonical_block_hashes,
             canonical_fork,
         };
-        let block_state =
+        let (block_state, _) =
             Self::validate_and_execute_sidechain(block.clone(), parent, post_state_data, externals)
                 .map_err(|err| InsertBlockError::new(block.block.clone(), err.into()))?;
         state.extend(block_state);
@@ -171,13 +205,16 @@ impl AppendableChain {
 
     /// Validate and execute the given block that _extends the canonical chain_, validating its
     /// state root after execution.
+    ///
+    /// Returns the trie updates from the trie of the canonical fork to the trie of the block if the
+    /// state root was validated.
     fn validate_and_execute<BSDP, DB, EF>(
         block: SealedBlockWithSenders,
         parent_block: &SealedHeader,
         post_state_data_provider: BSDP,
         externals: &TreeExternals<DB, EF>,
         block_kind: BlockKind,
-    ) -> RethResult<BundleStateWithReceipts>
+    ) -> RethResult<(BundleStateWithReceipts, Option<TrieUpdates>)>
     where
         BSDP: BundleStateDataProvider,
         DB: Database,
@@ -192,18 +229,32 @@ impl AppendableChain {
         // get the state provider.
         let db = externals.database();
         let canonical_fork = post_state_data_provider.canonical_fork();
-        let state_provider = db.history_by_block_number(canonical_fork.number)?;
+        let mut state_provider = db.history_by_block_number(canonical_fork.number)?;
+        if let Some(cache) =
+            externals.state_cache.as_deref().filter(|cache| cache.is_valid_for(canonical_fork))
+        {
+            state_provider = Box::new(CachedStateProvider::new(state_provider, cache));
+        }
+
+        // the hashes of all ancestors in the window of the block are known in memory, including
+        // the sidechain blocks that are not known to the database
+        let block_hashes = (block.number.saturating_sub(BLOCK_HASH_HISTORY)..block.number)
+            .filter_map(|number| {
+                post_state_data_provider.block_hash(number).map(|hash| (number, hash))
+            })
+            .collect();
 
         let provider = BundleStateProvider::new(state_provider, post_state_data_provider);
 
-        let mut executor = externals.executor_factory.with_state(&provider);
+        let mut executor =
+            externals.executor_factory.with_state_and_block_hashes(&provider, block_hashes);
         executor.execute_and_verify_receipt(&block, U256::MAX, Some(senders))?;
         let bundle_state = executor.take_output_state();
 
-        // check state root if the block extends the canonical chain.
-        if block_kind.extends_canonical_head() {
+        // check state root if the block extends the canonical chain, unless it is deferred.
+        let trie_updates = if block_kind == BlockKind::ExtendsCanonicalHead {
             // check state root
-            let state_root = provider.state_root(&bundle_state)?;
+            let (state_root, trie_updates) = provider.state_root_with_updates(&bundle_state)?;
             if block.state_root != state_root {
                 return Err(ConsensusError::BodyStateRootDiff {
                     got: state_root,
@@ -211,31 +262,12 @@ impl AppendableChain {
                 }
                 .into())
             }
-        }
+            Some(trie_updates)
+        } else {
+            None
+        };
 
-        Ok(bundle_state)
-    }
-
-    /// Validate and execute the given block that _extends the canonical chain_, validating its
-    /// state root after execution.
-    fn validate_and_execute_canonical_head_descendant<BSDP, DB, EF>(
-        block: SealedBlockWithSenders,
-        parent_block: &SealedHeader,
-        post_state_data_provider: BSDP,
-        externals: &TreeExternals<DB, EF>,
-    ) -> RethResult<BundleStateWithReceipts>
-    where
-        BSDP: BundleStateDataProvider,
-        DB: Database,
-        EF: ExecutorFactory,
-    {
-        Self::validate_and_execute(
-            block,
-            parent_block,
-            post_state_data_provider,
-            externals,
-            BlockKind::ExtendsCanonicalHead,
-        )
+        Ok((bundle_state, trie_updates))
     }
 
     /// Validate and execute the given sidechain block, skipping state root validation.
@@ -244,7 +276,7 @@ impl AppendableChain {
         parent_block: &SealedHeader,
         post_state_data_provider: BSDP,
         externals: &TreeExternals<DB, EF>,
-    ) -> RethResult<BundleStateWithReceipts>
+    ) -> RethResult<(BundleStateWithReceipts, Option<TrieUpdates>)>
     where
         BSDP: BundleStateDataProvider,
         DB: Database,
@@ -270,6 +302,8 @@ impl AppendableChain {
     /// CAUTION: This will only perform state root check if it's possible: if the `canonical_fork`
     /// is the canonical head, or: state root check can't be performed if the given canonical is
     /// __not__ the canonical head.
+    ///
+    /// Returns the trie updates of the chain up to the block if the state root was checked.
     #[track_caller]
     pub(crate) fn append_block<DB, EF>(
         &mut self,
@@ -279,7 +313,7 @@ impl AppendableChain {
         externals: &TreeExternals<DB, EF>,
         canonical_fork: ForkBlock,
         block_kind: BlockKind,
-    ) -> Result<(), InsertBlockError>
+    ) -> Result<Option<TrieUpdates>, InsertBlockError>
     where
         DB: Database,
         EF: ExecutorFactory,
@@ -293,7 +327,7 @@ impl AppendableChain {
             canonical_fork,
         };
 
-        let block_state = Self::validate_and_execute(
+        let (block_state, trie_updates) = Self::validate_and_execute(
             block.clone(),
             parent_block,
             post_state_data,
@@ -304,10 +338,21 @@ impl AppendableChain {
         // extend the state.
         self.state.extend(block_state);
         self.blocks.insert(block.number, block);
-        Ok(())
+        Ok(trie_updates)
     }
 }
 
+/// Calculates a heuristic for the in-memory size of the logs of the receipt in bytes.
+fn receipt_size(receipt: &Receipt) -> usize {
+    receipt
+        .logs
+        .iter()
+        .map(|log| {
+            mem::size_of::<Log>() + log.topics.len() * mem::size_of::<B256>() + log.data.len()
+        })
+        .sum()
+}
+
 /// Represents what kind of block is being executed and validated.
 ///
 /// This is required because the state root check can only be performed if the targeted block can be
@@ -321,12 +366,16 @@ pub(crate) enum BlockKind {
     /// The block can be traced back to an ancestor of the canonical head: a historical block, but
     /// this chain does __not__ include the canonical head.
     ForksHistoricalBlock,
+    /// The `block` is a descendant of the canonical head that is executed optimistically: its
+    /// state root is only verified when the chain is canonicalized, see
+    /// [BlockchainTreeConfig::optimistic_execution_distance](crate::BlockchainTreeConfig::optimistic_execution_distance).
+    Optimistic,
 }
 
 impl BlockKind {
     /// Returns `true` if the block is a descendant of the canonical head.
     #[inline]
     pub(crate) fn extends_canonical_head(&self) -> bool {
-        matches!(self, BlockKind::ExtendsCanonicalHead)
+        matches!(self, BlockKind::ExtendsCanonicalHead | BlockKind::Optimistic)
     }
 }
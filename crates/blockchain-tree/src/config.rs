@@ -19,6 +19,15 @@ pub struct BlockchainTreeConfig {
     /// be 256. It covers both number of blocks required for reorg, and number of blocks
     /// required for `BLOCKHASH` EVM opcode.
     num_of_additional_canonical_block_hashes: u64,
+    /// The memory budget in bytes for the trie updates of validated blocks that are kept to
+    /// canonicalize them without recomputing the trie.
+    max_trie_updates_cache_size: usize,
+    /// The memory budget in bytes for the blocks, receipts and state of all side chains. Stale
+    /// side chains are evicted once it is exceeded.
+    max_side_chains_memory: usize,
+    /// The distance from the last finalized block beyond which blocks extending the canonical
+    /// head are executed without verifying their state root, if set.
+    optimistic_execution_distance: Option<u64>,
 }
 
 impl Default for BlockchainTreeConfig {
@@ -33,6 +42,12 @@ impl Default for BlockchainTreeConfig {
             num_of_additional_canonical_block_hashes: 256,
             // max unconnected blocks.
             max_unconnected_blocks: 200,
+            // 256 MiB of cached trie updates.
+            max_trie_updates_cache_size: 256 * 1024 * 1024,
+            // 1 GiB of side chains.
+            max_side_chains_memory: 1024 * 1024 * 1024,
+            // every block is fully validated.
+            optimistic_execution_distance: None,
         }
     }
 }
@@ -53,9 +68,35 @@ impl BlockchainTreeConfig {
             max_reorg_depth,
             num_of_additional_canonical_block_hashes,
             max_unconnected_blocks,
+            max_trie_updates_cache_size: Self::default().max_trie_updates_cache_size,
+            max_side_chains_memory: Self::default().max_side_chains_memory,
+            optimistic_execution_distance: None,
         }
     }
 
+    /// Set the memory budget in bytes for the cached trie updates of validated blocks.
+    pub fn with_max_trie_updates_cache_size(mut self, max_trie_updates_cache_size: usize) -> Self {
+        self.max_trie_updates_cache_size = max_trie_updates_cache_size;
+        self
+    }
+
+    /// Set the memory budget in bytes for all side chains.
+    pub fn with_max_side_chains_memory(mut self, max_side_chains_memory: usize) -> Self {
+        self.max_side_chains_memory = max_side_chains_memory;
+        self
+    }
+
+    /// Enable optimistic execution of the blocks that are more than `distance` blocks above the
+    /// last finalized block.
+    ///
+    /// The state roots of these blocks are not verified when they are inserted, but in a single
+    /// batch for the whole chain when it is canonicalized. A mismatch discards the chain before
+    /// it is written, so the database never contains unverified blocks.
+    pub fn with_optimistic_execution(mut self, distance: u64) -> Self {
+        self.optimistic_execution_distance = Some(distance);
+        self
+    }
+
     /// Return the maximum reorg depth.
     pub fn max_reorg_depth(&self) -> u64 {
         self.max_reorg_depth
@@ -88,4 +129,20 @@ impl BlockchainTreeConfig {
     pub fn max_unconnected_blocks(&self) -> usize {
         self.max_unconnected_blocks
     }
+
+    /// Return the memory budget in bytes for the cached trie updates of validated blocks.
+    pub fn max_trie_updates_cache_size(&self) -> usize {
+        self.max_trie_updates_cache_size
+    }
+
+    /// Return the memory budget in bytes for all side chains.
+    pub fn max_side_chains_memory(&self) -> usize {
+        self.max_side_chains_memory
+    }
+
+    /// Return the distance from the last finalized block beyond which blocks are executed
+    /// optimistically, if enabled.
+    pub fn optimistic_execution_distance(&self) -> Option<u64> {
+        self.optimistic_execution_distance
+    }
 }
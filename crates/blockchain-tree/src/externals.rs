@@ -3,7 +3,7 @@
 use reth_db::database::Database;
 use reth_interfaces::consensus::Consensus;
 use reth_primitives::ChainSpec;
-use reth_provider::ProviderFactory;
+use reth_provider::{ProviderFactory, StateCache};
 use std::sync::Arc;
 
 /// A container for external components.
@@ -15,6 +15,7 @@ use std::sync::Arc;
 /// - A handle to the consensus engine
 /// - The executor factory to execute blocks with
 /// - The chain spec
+/// - An optional cache of the state at the canonical tip
 #[derive(Debug)]
 pub struct TreeExternals<DB, EF> {
     /// The database, used to commit the canonical chain, or unwind it.
@@ -25,6 +26,8 @@ pub struct TreeExternals<DB, EF> {
     pub(crate) executor_factory: EF,
     /// The chain spec.
     pub(crate) chain_spec: Arc<ChainSpec>,
+    /// The cache of the state at the canonical tip, used when executing blocks on top of it.
+    pub(crate) state_cache: Option<Arc<StateCache>>,
 }
 
 impl<DB, EF> TreeExternals<DB, EF> {
@@ -35,7 +38,15 @@ impl<DB, EF> TreeExternals<DB, EF> {
         executor_factory: EF,
         chain_spec: Arc<ChainSpec>,
     ) -> Self {
-        Self { db, consensus, executor_factory, chain_spec }
+        Self { db, consensus, executor_factory, chain_spec, state_cache: None }
+    }
+
+    /// Sets the cache of the state at the canonical tip.
+    ///
+    /// The tree keeps the cache valid when it commits or reverts canonical blocks.
+    pub fn with_state_cache(mut self, state_cache: Arc<StateCache>) -> Self {
+        self.state_cache = Some(state_cache);
+        self
     }
 }
 
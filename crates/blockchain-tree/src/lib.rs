@@ -53,3 +53,8 @@ pub use block_buffer::BlockBuffer;
 pub mod noop;
 
 mod state;
+
+mod trie_updates;
+
+pub mod warmup;
+pub use warmup::{warm_up, WarmupConfig, WarmupOutcome};
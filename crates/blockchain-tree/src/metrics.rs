@@ -17,6 +17,12 @@ pub struct TreeMetrics {
     pub latest_reorg_depth: Gauge,
     /// Longest sidechain height
     pub longest_sidechain_height: Gauge,
+    /// The estimated memory usage of all sidechains in bytes
+    pub sidechains_memory: Gauge,
+    /// The number of sidechains that were evicted because the memory budget was exceeded
+    pub evicted_sidechains: Counter,
+    /// The number of blocks that were inserted without verifying their state root
+    pub optimistic_blocks: Counter,
 }
 
 /// Metrics for the blockchain tree block buffer
@@ -26,3 +32,13 @@ pub struct BlockBufferMetrics {
     /// Total blocks in the block buffer
     pub blocks: Gauge,
 }
+
+/// Metrics for the state warmup after a restart
+#[derive(Metrics)]
+#[metrics(scope = "blockchain_tree.warmup")]
+pub struct WarmupMetrics {
+    /// The duration of the last warmup in seconds
+    pub duration_seconds: Gauge,
+    /// The number of entries loaded by the last warmup
+    pub entries_loaded: Gauge,
+}
@@ -1,6 +1,6 @@
 //! Blockchain tree state.
 
-use crate::{AppendableChain, BlockBuffer, BlockIndices};
+use crate::{trie_updates::TrieUpdatesCache, AppendableChain, BlockBuffer, BlockIndices};
 use reth_primitives::{BlockHash, BlockNumber, Receipt, SealedBlock, SealedBlockWithSenders};
 use std::collections::{BTreeMap, HashMap};
 
@@ -18,6 +18,8 @@ pub(crate) struct TreeState {
     pub(crate) block_indices: BlockIndices,
     /// Unconnected block buffer.
     pub(crate) buffered_blocks: BlockBuffer,
+    /// Trie updates of the validated blocks that extend the canonical chain.
+    pub(crate) trie_updates: TrieUpdatesCache,
 }
 
 impl TreeState {
@@ -27,6 +29,7 @@ impl TreeState {
         last_finalized_block_number: BlockNumber,
         last_canonical_hashes: Vec<(BlockNumber, BlockHash)>,
         buffer_limit: usize,
+        trie_updates_cache_size: usize,
     ) -> Self {
         Self {
             block_chain_id_generator: 0,
@@ -36,6 +39,7 @@ impl TreeState {
                 BTreeMap::from_iter(last_canonical_hashes),
             ),
             buffered_blocks: BlockBuffer::new(buffer_limit),
+            trie_updates: TrieUpdatesCache::new(trie_updates_cache_size),
         }
     }
 
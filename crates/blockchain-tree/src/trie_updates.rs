@@ -0,0 +1,144 @@
+//! Cache of the trie updates that were computed while validating blocks.
+
+use reth_primitives::{BlockHash, B256};
+use reth_trie::updates::{TrieKey, TrieOp, TrieUpdates};
+use std::{
+    collections::{HashMap, VecDeque},
+    mem,
+};
+
+/// The trie updates of a block that extends the canonical chain.
+#[derive(Debug)]
+struct CachedTrieUpdates {
+    /// The canonical block the updates were computed against.
+    base: BlockHash,
+    /// The updates from the trie of the base block to the trie of the block.
+    updates: TrieUpdates,
+    /// The estimated memory usage of the updates in bytes.
+    size: usize,
+}
+
+/// A bounded cache of the trie updates of the blocks in the tree, by block hash.
+///
+/// The updates of a block cover all blocks between the canonical block that was the tip while the
+/// block was validated and the block itself, so they can be written as is when the chain up to
+/// that block is canonicalized on top of the same tip. The updates that were inserted first are
+/// evicted first if the cache exceeds its memory budget.
+#[derive(Debug)]
+pub(crate) struct TrieUpdatesCache {
+    /// The cached updates by block hash.
+    entries: HashMap<BlockHash, CachedTrieUpdates>,
+    /// Block hashes in insertion order.
+    order: VecDeque<BlockHash>,
+    /// The estimated memory usage of all cached updates in bytes.
+    size: usize,
+    /// The memory budget of the cache in bytes.
+    max_size: usize,
+    /// The number of canonicalized chains whose cached updates were written.
+    pub(crate) reused: u64,
+    /// The number of canonicalized chains whose trie updates had to be recomputed.
+    pub(crate) recomputed: u64,
+}
+
+impl TrieUpdatesCache {
+    /// Creates an empty cache with the given memory budget in bytes.
+    pub(crate) fn new(max_size: usize) -> Self {
+        Self {
+            entries: Default::default(),
+            order: Default::default(),
+            size: 0,
+            max_size,
+            reused: 0,
+            recomputed: 0,
+        }
+    }
+
+    /// Caches the updates of the block that were computed against the trie of the `base` block.
+    pub(crate) fn insert(&mut self, block_hash: BlockHash, base: BlockHash, updates: TrieUpdates) {
+        let size = estimated_size(&updates);
+        if size > self.max_size {
+            return
+        }
+        self.remove(&block_hash);
+
+        self.size += size;
+        self.entries.insert(block_hash, CachedTrieUpdates { base, updates, size });
+        self.order.push_back(block_hash);
+
+        while self.size > self.max_size {
+            let Some(evicted) = self.order.pop_front() else { break };
+            if let Some(entry) = self.entries.remove(&evicted) {
+                self.size -= entry.size;
+            }
+        }
+    }
+
+    /// Removes the updates of the block and returns them if they were computed against the trie
+    /// of the `base` block.
+    pub(crate) fn take(&mut self, block_hash: &BlockHash, base: &BlockHash) -> Option<TrieUpdates> {
+        self.remove(block_hash).filter(|entry| entry.base == *base).map(|entry| entry.updates)
+    }
+
+    /// Removes the updates of the block.
+    fn remove(&mut self, block_hash: &BlockHash) -> Option<CachedTrieUpdates> {
+        let entry = self.entries.remove(block_hash)?;
+        self.size -= entry.size;
+        self.order.retain(|hash| hash != block_hash);
+        Some(entry)
+    }
+
+    /// Removes the updates of all given blocks.
+    pub(crate) fn remove_all<'a>(&mut self, block_hashes: impl IntoIterator<Item = &'a BlockHash>) {
+        for block_hash in block_hashes {
+            self.remove(block_hash);
+        }
+    }
+}
+
+/// Returns the estimated memory usage of the trie updates in bytes.
+fn estimated_size(updates: &TrieUpdates) -> usize {
+    updates
+        .values()
+        .map(|op| {
+            let hashes = match op {
+                TrieOp::Update(node) => node.hashes.len(),
+                TrieOp::Delete => 0,
+            };
+            mem::size_of::<(TrieKey, TrieOp)>() + hashes * mem::size_of::<B256>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::trie::{BranchNodeCompact, StoredNibbles};
+
+    fn updates(nodes: u8) -> TrieUpdates {
+        let mut updates = TrieUpdates::default();
+        updates.extend((0..nodes).map(|nibble| {
+            (
+                TrieKey::AccountNode(StoredNibbles::from(vec![nibble])),
+                TrieOp::Update(BranchNodeCompact::new(0b11, 0, 0b11, vec![B256::ZERO; 2], None)),
+            )
+        }));
+        updates
+    }
+
+    #[test]
+    fn evicts_oldest_updates_over_budget() {
+        let base = B256::random();
+        let (first, second) = (B256::random(), B256::random());
+        let mut cache = TrieUpdatesCache::new(estimated_size(&updates(3)));
+
+        cache.insert(first, base, updates(2));
+        cache.insert(second, base, updates(2));
+        assert!(cache.take(&first, &base).is_none());
+        assert!(cache.take(&second, &B256::random()).is_none());
+
+        cache.insert(second, base, updates(2));
+        assert_eq!(cache.take(&second, &base).map(|updates| updates.len()), Some(2));
+        assert_eq!(cache.size, 0);
+        assert!(cache.order.is_empty());
+    }
+}
@@ -0,0 +1,339 @@
+//! Warmup of the state that is likely accessed by the first blocks after a restart.
+
+use crate::metrics::WarmupMetrics;
+use reth_db::{cursor::DbCursorRO, database::Database, tables, transaction::DbTx};
+use reth_interfaces::RethResult;
+use reth_primitives::{trie::StoredNibbles, BlockNumHash};
+use reth_provider::{
+    AccountExtReader, AccountReader, BlockHashReader, BlockNumReader, LatestStateProviderRef,
+    ProviderFactory, StateCache, StateProvider, StorageReader,
+};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// The default number of most recent blocks whose state is preloaded.
+pub const DEFAULT_WARMUP_BLOCKS: u64 = 64;
+
+/// The default number of top levels of the account trie that are read.
+pub const DEFAULT_WARMUP_TRIE_DEPTH: usize = 3;
+
+/// The default time budget of the warmup.
+pub const DEFAULT_WARMUP_BUDGET: Duration = Duration::from_secs(30);
+
+/// Configuration of the state warmup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmupConfig {
+    /// The number of most recent canonical blocks whose changed accounts and storage slots are
+    /// preloaded.
+    pub blocks: u64,
+    /// The number of top levels of the account trie that are read.
+    pub trie_depth: usize,
+    /// The time after which the warmup stops, even if not everything was loaded.
+    pub budget: Duration,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            blocks: DEFAULT_WARMUP_BLOCKS,
+            trie_depth: DEFAULT_WARMUP_TRIE_DEPTH,
+            budget: DEFAULT_WARMUP_BUDGET,
+        }
+    }
+}
+
+/// The entries that were loaded by a warmup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WarmupOutcome {
+    /// The number of preloaded accounts.
+    pub accounts: usize,
+    /// The number of preloaded storage slots.
+    pub storage_slots: usize,
+    /// The number of preloaded bytecodes.
+    pub bytecodes: usize,
+    /// The number of account trie nodes that were read.
+    pub trie_nodes: usize,
+    /// Whether the warmup stopped because its time budget was exhausted.
+    pub budget_exhausted: bool,
+}
+
+impl WarmupOutcome {
+    /// Returns the total number of loaded entries.
+    pub fn entries(&self) -> usize {
+        self.accounts + self.storage_slots + self.bytecodes + self.trie_nodes
+    }
+}
+
+/// Preloads the state that is likely accessed by the next blocks into the cache.
+///
+/// The accounts and storage slots that were changed by the last [WarmupConfig::blocks] canonical
+/// blocks are considered hot: they are loaded, together with the bytecode of the accounts, into
+/// the cache, which is set to the state of the canonical tip. The top [WarmupConfig::trie_depth]
+/// levels of the account trie are read as well, which pulls them into the page cache of the
+/// database, since the state root computation reads the trie from the database directly.
+///
+/// The warmup stops once its [WarmupConfig::budget] is exhausted.
+pub fn warm_up<DB: Database>(
+    factory: &ProviderFactory<DB>,
+    cache: &StateCache,
+    config: WarmupConfig,
+) -> RethResult<WarmupOutcome> {
+    let start = Instant::now();
+    let deadline = start + config.budget;
+    let mut outcome = WarmupOutcome::default();
+
+    let provider = factory.provider()?;
+    let tip = provider.last_block_number()?;
+    let Some(tip_hash) = provider.block_hash(tip)? else { return Ok(outcome) };
+    cache.set_tip(BlockNumHash::new(tip, tip_hash));
+
+    let range = (tip + 1).saturating_sub(config.blocks)..=tip;
+    debug!(target: "blockchain_tree::warmup", ?range, "Collecting hot accounts and storage slots");
+    let mut accounts = provider.changed_accounts_with_range(range.clone())?;
+    let storages = provider.changed_storages_with_range(range)?;
+    accounts.extend(storages.keys().copied());
+
+    let state = LatestStateProviderRef::new(provider.tx_ref());
+    'load: {
+        for address in accounts {
+            if Instant::now() >= deadline {
+                outcome.budget_exhausted = true;
+                break 'load
+            }
+            let account = state.basic_account(address)?;
+            cache.insert_account(address, account);
+            outcome.accounts += 1;
+
+            if let Some(code_hash) = account.and_then(|account| account.bytecode_hash) {
+                cache.insert_bytecode(code_hash, state.bytecode_by_hash(code_hash)?);
+                outcome.bytecodes += 1;
+            }
+        }
+
+        for (address, keys) in storages {
+            for key in keys {
+                if Instant::now() >= deadline {
+                    outcome.budget_exhausted = true;
+                    break 'load
+                }
+                cache.insert_storage(address, key, state.storage(address, key)?);
+                outcome.storage_slots += 1;
+            }
+        }
+
+        // the root node is not stored, so the first level consists of all single nibble paths
+        let mut trie_cursor = provider.tx_ref().cursor_read::<tables::AccountsTrie>()?;
+        let mut level = (0..16).map(|nibble| vec![nibble]).collect::<Vec<_>>();
+        for _ in 0..config.trie_depth {
+            let mut next_level = Vec::new();
+            for path in level {
+                if Instant::now() >= deadline {
+                    outcome.budget_exhausted = true;
+                    break 'load
+                }
+                let Some((_, node)) = trie_cursor.seek_exact(StoredNibbles::from(path.clone()))?
+                else {
+                    continue
+                };
+                outcome.trie_nodes += 1;
+                next_level.extend((0..16).filter(|nibble| node.tree_mask.is_bit_set(*nibble)).map(
+                    |nibble| {
+                        let mut child = path.clone();
+                        child.push(nibble);
+                        child
+                    },
+                ));
+            }
+            level = next_level;
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let metrics = WarmupMetrics::default();
+    metrics.duration_seconds.set(elapsed.as_secs_f64());
+    metrics.entries_loaded.set(outcome.entries() as f64);
+    info!(
+        target: "blockchain_tree::warmup",
+        ?elapsed,
+        accounts = outcome.accounts,
+        storage_slots = outcome.storage_slots,
+        bytecodes = outcome.bytecodes,
+        trie_nodes = outcome.trie_nodes,
+        budget_exhausted = outcome.budget_exhausted,
+        "Warmup finished"
+    );
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{
+        models::{AccountBeforeTx, BlockNumberAddress},
+        test_utils::create_test_rw_db,
+        transaction::DbTxMut,
+    };
+    use reth_primitives::{
+        trie::{AccountProof, BranchNodeCompact},
+        Account, Address, BlockNumber, Bytecode, StorageEntry, StorageKey, StorageValue, B256,
+        MAINNET, U256,
+    };
+    use reth_provider::{BundleStateWithReceipts, CachedStateProvider, StateRootProvider};
+    use reth_trie::updates::TrieUpdates;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the reads that reach the inner state provider.
+    struct CountingStateProvider<SP> {
+        inner: SP,
+        reads: AtomicUsize,
+    }
+
+    impl<SP> CountingStateProvider<SP> {
+        fn read(&self) {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    impl<SP: StateProvider> BlockHashReader for CountingStateProvider<SP> {
+        fn block_hash(&self, number: BlockNumber) -> RethResult<Option<B256>> {
+            self.read();
+            self.inner.block_hash(number)
+        }
+
+        fn canonical_hashes_range(
+            &self,
+            start: BlockNumber,
+            end: BlockNumber,
+        ) -> RethResult<Vec<B256>> {
+            self.read();
+            self.inner.canonical_hashes_range(start, end)
+        }
+    }
+
+    impl<SP: StateProvider> AccountReader for CountingStateProvider<SP> {
+        fn basic_account(&self, address: Address) -> RethResult<Option<Account>> {
+            self.read();
+            self.inner.basic_account(address)
+        }
+    }
+
+    impl<SP: StateProvider> StateRootProvider for CountingStateProvider<SP> {
+        fn state_root(&self, state: &BundleStateWithReceipts) -> RethResult<B256> {
+            self.inner.state_root(state)
+        }
+
+        fn state_root_with_updates(
+            &self,
+            state: &BundleStateWithReceipts,
+        ) -> RethResult<(B256, TrieUpdates)> {
+            self.inner.state_root_with_updates(state)
+        }
+    }
+
+    impl<SP: StateProvider> StateProvider for CountingStateProvider<SP> {
+        fn storage(&self, account: Address, key: StorageKey) -> RethResult<Option<StorageValue>> {
+            self.read();
+            self.inner.storage(account, key)
+        }
+
+        fn bytecode_by_hash(&self, code_hash: B256) -> RethResult<Option<Bytecode>> {
+            self.read();
+            self.inner.bytecode_by_hash(code_hash)
+        }
+
+        fn proof(&self, address: Address, keys: &[B256]) -> RethResult<AccountProof> {
+            self.inner.proof(address, keys)
+        }
+    }
+
+    /// Reads the state the next block accesses: the hot accounts, their code and storage.
+    fn execute_next_block(state: &impl StateProvider, hot: &[(Address, StorageKey)]) {
+        for (address, key) in hot {
+            let account = state.basic_account(*address).unwrap().unwrap();
+            state.bytecode_by_hash(account.bytecode_hash.unwrap()).unwrap();
+            state.storage(*address, *key).unwrap();
+        }
+    }
+
+    #[test]
+    fn warmup_reduces_reads_of_next_block() {
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(&db, MAINNET.clone());
+        let bytecode = Bytecode::new_raw(vec![0x60, 0x00].into());
+        let code_hash = bytecode.hash_slow();
+
+        let hot = (1..=8u8)
+            .map(|i| (Address::with_last_byte(i), B256::with_last_byte(i)))
+            .collect::<Vec<_>>();
+        {
+            let provider = factory.provider_rw().unwrap();
+            let tx = provider.tx_ref();
+            for number in 0..=2 {
+                tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                    .unwrap();
+            }
+            for (address, key) in &hot {
+                let account =
+                    Account { nonce: 1, bytecode_hash: Some(code_hash), ..Default::default() };
+                tx.put::<tables::PlainAccountState>(*address, account).unwrap();
+                tx.put::<tables::PlainStorageState>(
+                    *address,
+                    StorageEntry { key: *key, value: U256::from(1) },
+                )
+                .unwrap();
+                tx.put::<tables::AccountChangeSet>(
+                    2,
+                    AccountBeforeTx { address: *address, info: None },
+                )
+                .unwrap();
+                tx.put::<tables::StorageChangeSet>(
+                    BlockNumberAddress((2, *address)),
+                    StorageEntry { key: *key, value: U256::ZERO },
+                )
+                .unwrap();
+            }
+            tx.put::<tables::Bytecodes>(code_hash, bytecode).unwrap();
+            tx.put::<tables::AccountsTrie>(
+                StoredNibbles::from(vec![1]),
+                BranchNodeCompact::new(0b11, 0b01, 0, vec![], None),
+            )
+            .unwrap();
+            tx.put::<tables::AccountsTrie>(
+                StoredNibbles::from(vec![1, 0]),
+                BranchNodeCompact::new(0b11, 0, 0, vec![], None),
+            )
+            .unwrap();
+            provider.commit().unwrap();
+        }
+
+        let reads = |cache: &StateCache| {
+            let counting = CountingStateProvider {
+                inner: factory.latest().unwrap(),
+                reads: AtomicUsize::new(0),
+            };
+            let state = CachedStateProvider::new(&counting, cache);
+            execute_next_block(&state, &hot);
+            counting.reads.load(Ordering::Relaxed)
+        };
+
+        let cold = reads(&StateCache::default());
+        assert_eq!(cold, hot.len() * 3);
+
+        let cache = StateCache::default();
+        let outcome = warm_up(&factory, &cache, WarmupConfig::default()).unwrap();
+        assert_eq!(outcome.accounts, hot.len());
+        assert_eq!(outcome.storage_slots, hot.len());
+        assert_eq!(outcome.trie_nodes, 2);
+        assert!(!outcome.budget_exhausted);
+        assert!(cache.is_valid_for(BlockNumHash::new(2, B256::with_last_byte(2))));
+        assert_eq!(reads(&cache), 0);
+
+        // nothing is loaded without a budget
+        let cache = StateCache::default();
+        let config = WarmupConfig { budget: Duration::ZERO, ..Default::default() };
+        let outcome = warm_up(&factory, &cache, config).unwrap();
+        assert!(outcome.budget_exhausted);
+        assert_eq!(outcome.entries(), 0);
+    }
+}
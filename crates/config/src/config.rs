@@ -161,6 +161,14 @@ pub struct BodiesConfig {
     ///
     /// Default: 100
     pub downloader_max_concurrent_requests: usize,
+    /// The number of threads that validate received bodies, one per CPU if zero.
+    ///
+    /// Default: 0
+    pub downloader_validation_parallelism: usize,
+    /// The maximum number of responses waiting for validation before no new requests are sent.
+    ///
+    /// Default: 50
+    pub downloader_max_pending_validations: usize,
 }
 
 impl Default for BodiesConfig {
@@ -171,6 +179,8 @@ impl Default for BodiesConfig {
             downloader_max_buffered_blocks_size_bytes: 2 * 1024 * 1024 * 1024, // ~2GB
             downloader_min_concurrent_requests: 5,
             downloader_max_concurrent_requests: 100,
+            downloader_validation_parallelism: 0,
+            downloader_max_pending_validations: 50,
         }
     }
 }
@@ -185,6 +195,8 @@ impl From<BodiesConfig> for BodiesDownloaderBuilder {
                 config.downloader_min_concurrent_requests..=
                     config.downloader_max_concurrent_requests,
             )
+            .with_validation_parallelism(config.downloader_validation_parallelism)
+            .with_max_pending_validations(config.downloader_max_pending_validations)
     }
 }
 
@@ -22,6 +22,7 @@ use reth_interfaces::{
     executor::{BlockExecutionError, BlockValidationError},
 };
 use reth_primitives::{
+    basefee::next_block_base_fee,
     constants::{EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, ETHEREUM_BLOCK_GAS_LIMIT},
     proofs, Address, Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Bloom, ChainSpec,
     Header, ReceiptWithBloom, SealedBlock, SealedHeader, TransactionSigned, B256, EMPTY_OMMER_ROOT,
@@ -258,7 +259,7 @@ impl StorageInner {
         let base_fee_per_gas = self
             .headers
             .get(&self.best_block)
-            .and_then(|parent| parent.next_block_base_fee(chain_spec.base_fee_params));
+            .and_then(|parent| next_block_base_fee(parent, &chain_spec));
 
         let mut header = Header {
             parent_hash: self.best_hash,
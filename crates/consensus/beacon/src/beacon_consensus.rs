@@ -100,7 +100,7 @@ impl Consensus for BeaconConsensus {
     }
 
     fn validate_block(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
-        validation::validate_block_standalone(block, &self.chain_spec)
+        validation::validate_block_pre_execution(block, &self.chain_spec)
     }
 }
 
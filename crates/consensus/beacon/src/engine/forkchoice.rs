@@ -45,6 +45,14 @@ impl ForkchoiceStateTracker {
         self.latest.as_ref().map(|s| s.status)
     }
 
+    /// Returns true if the given state is identical to the latest received FCU and that FCU was
+    /// processed as [ForkchoiceStatus::Valid].
+    pub(crate) fn is_latest_valid_duplicate(&self, state: &ForkchoiceState) -> bool {
+        self.latest
+            .as_ref()
+            .map_or(false, |latest| latest.status.is_valid() && latest.state == *state)
+    }
+
     /// Returns whether the latest received FCU is valid: [ForkchoiceStatus::Valid]
     #[allow(unused)]
     pub(crate) fn is_latest_valid(&self) -> bool {
@@ -2,6 +2,7 @@ use reth_metrics::{
     metrics::{Counter, Gauge, Histogram},
     Metrics,
 };
+use reth_rpc_types::engine::TransactionDecodeErrorKind;
 
 /// Beacon consensus engine metrics.
 #[derive(Metrics)]
@@ -11,6 +12,14 @@ pub(crate) struct EngineMetrics {
     pub(crate) pipeline_runs: Counter,
     /// The total count of forkchoice updated messages received.
     pub(crate) forkchoice_updated_messages: Counter,
+    /// The total count of forkchoice updated messages that were identical to the last valid
+    /// forkchoice state and answered without consulting the blockchain tree.
+    pub(crate) forkchoice_updated_fast_path: Counter,
+    /// The total count of forkchoice updated messages that were processed by the blockchain tree.
+    pub(crate) forkchoice_updated_full_path: Counter,
+    /// The total count of forkchoice updated messages with a head that is an ancestor of the
+    /// current canonical head.
+    pub(crate) forkchoice_updated_canonical_ancestor: Counter,
     /// The total count of new payload messages received.
     pub(crate) new_payload_messages: Counter,
     /// Latency for making canonical already canonical block
@@ -23,6 +32,21 @@ pub(crate) struct EngineMetrics {
     pub(crate) make_canonical_latency: Histogram,
 }
 
+/// Metrics of the transactions of new payloads that could not be decoded, labeled by category.
+#[derive(Metrics)]
+#[metrics(scope = "consensus.engine.beacon.invalid_payload_transactions")]
+pub(crate) struct InvalidPayloadTransactionMetrics {
+    /// The total count of payload transactions that could not be decoded.
+    pub(crate) count: Counter,
+}
+
+impl InvalidPayloadTransactionMetrics {
+    /// Increments the counter of the given category.
+    pub(crate) fn record(kind: TransactionDecodeErrorKind) {
+        Self::new_with_labels(&[("category", kind.as_str())]).count.increment(1);
+    }
+}
+
 /// Metrics for the `EngineSyncController`.
 #[derive(Metrics)]
 #[metrics(scope = "consensus.engine.beacon")]
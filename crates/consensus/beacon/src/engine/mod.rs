@@ -2,7 +2,7 @@ use crate::{
     engine::{
         forkchoice::{ForkchoiceStateHash, ForkchoiceStateTracker},
         message::OnForkChoiceUpdated,
-        metrics::EngineMetrics,
+        metrics::{EngineMetrics, InvalidPayloadTransactionMetrics},
     },
     hooks::{EngineContext, EngineHooksController},
     sync::{EngineSyncController, EngineSyncEvent},
@@ -655,6 +655,20 @@ where
             return Ok(OnForkChoiceUpdated::syncing())
         }
 
+        // CLs frequently resend the same forkchoice state multiple times per slot. If it's
+        // identical to the last state we processed as VALID and there are no payload attributes,
+        // the tree is already in the requested state and we can respond immediately. This must
+        // come after the checks above, which may no longer consider the same state VALID.
+        if attrs.is_none() && self.forkchoice_state_tracker.is_latest_valid_duplicate(&state) {
+            trace!(target: "consensus::engine", ?state, "Received duplicate forkchoice state, skipping tree update");
+            self.metrics.forkchoice_updated_fast_path.increment(1);
+            return Ok(OnForkChoiceUpdated::valid(PayloadStatus::new(
+                PayloadStatusEnum::Valid,
+                Some(state.head_block_hash),
+            )))
+        }
+        self.metrics.forkchoice_updated_full_path.increment(1);
+
         let start = Instant::now();
         let make_canonical_result = self.blockchain.make_canonical(&state.head_block_hash);
         let elapsed = self.record_make_canonical_latency(start, &make_canonical_result);
@@ -662,10 +676,17 @@ where
             Ok(outcome) => {
                 match outcome {
                     CanonicalOutcome::AlreadyCanonical { ref header } => {
+                        // The head is an ancestor of (or equal to) the current canonical head.
+                        // Per the Engine API spec we may skip the update and return VALID with
+                        // `latestValidHash` set to the FCU head, without unwinding the chain.
+                        let current_head_num = self.blockchain.canonical_tip().number;
+                        if header.number < current_head_num {
+                            self.metrics.forkchoice_updated_canonical_ancestor.increment(1);
+                        }
                         debug!(
                             target: "consensus::engine",
                             fcu_head_num=?header.number,
-                            current_head_num=?self.blockchain.canonical_tip().number,
+                            ?current_head_num,
                             "Ignoring beacon update to old head"
                         );
                     }
@@ -1174,6 +1195,9 @@ where
             Ok(block) => block,
             Err(error) => {
                 error!(target: "consensus::engine", ?error, "Invalid payload");
+                if let PayloadError::InvalidTransaction { kind, .. } = error {
+                    InvalidPayloadTransactionMetrics::record(kind);
+                }
 
                 let mut latest_valid_hash = None;
                 if !error.is_block_hash_mismatch() {
@@ -1912,7 +1936,7 @@ mod tests {
     use assert_matches::assert_matches;
     use reth_interfaces::test_utils::generators::{self, Rng};
     use reth_primitives::{stage::StageCheckpoint, ChainSpec, ChainSpecBuilder, B256, MAINNET};
-    use reth_provider::{BlockWriter, ProviderFactory};
+    use reth_provider::{BlockNumReader, BlockWriter, ProviderFactory};
     use reth_rpc_types::engine::{ForkchoiceState, ForkchoiceUpdated, PayloadStatus};
     use reth_rpc_types_compat::engine::payload::try_block_to_payload_v1;
     use reth_stages::{ExecOutput, PipelineError, StageError};
@@ -2180,6 +2204,184 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn duplicate_forkchoice_skips_tree() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+            let block1 = random_block(&mut rng, 1, Some(genesis.hash), None, Some(0));
+            insert_blocks(env.db.as_ref(), chain_spec.clone(), [&genesis, &block1].into_iter());
+            env.db
+                .update(|tx| {
+                    tx.put::<tables::SyncStage>(
+                        StageId::Finish.to_string(),
+                        StageCheckpoint::new(block1.number),
+                    )
+                })
+                .unwrap()
+                .unwrap();
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            let forkchoice = ForkchoiceState {
+                head_block_hash: block1.hash,
+                finalized_block_hash: block1.hash,
+                ..Default::default()
+            };
+            let expected_result = ForkchoiceUpdated::new(PayloadStatus::new(
+                PayloadStatusEnum::Valid,
+                Some(block1.hash),
+            ));
+
+            let result = env.send_forkchoice_updated(forkchoice).await.unwrap();
+            assert_eq!(result, expected_result);
+
+            // Remove the total difficulty of the head, so any attempt of the tree to make the
+            // head canonical again fails and the engine falls back to syncing.
+            env.db
+                .update(|tx| tx.delete::<tables::HeaderTD>(block1.number, None))
+                .unwrap()
+                .unwrap();
+
+            // Identical forkchoice states must be answered without consulting the tree.
+            for _ in 0..2 {
+                let result = env.send_forkchoice_updated(forkchoice).await.unwrap();
+                assert_eq!(result, expected_result);
+            }
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
+        #[tokio::test]
+        async fn duplicate_forkchoice_while_pipeline_active() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (mut consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+            let block1 = random_block(&mut rng, 1, Some(genesis.hash), None, Some(0));
+            insert_blocks(env.db.as_ref(), chain_spec.clone(), [&genesis, &block1].into_iter());
+
+            let forkchoice = ForkchoiceState {
+                head_block_hash: block1.hash,
+                finalized_block_hash: block1.hash,
+                ..Default::default()
+            };
+            consensus_engine
+                .forkchoice_state_tracker
+                .set_latest(forkchoice, ForkchoiceStatus::Valid);
+
+            // Start the pipeline, it stays active until the sync controller is polled again.
+            consensus_engine.sync.set_pipeline_sync_target(block1.hash);
+            std::future::poll_fn(|cx| {
+                let _ = consensus_engine.sync.poll(cx);
+                Poll::Ready(())
+            })
+            .await;
+            assert!(consensus_engine.sync.is_pipeline_active());
+
+            // The pipeline has exclusive access to the database, so the duplicate state must not
+            // be answered as VALID.
+            let result = consensus_engine.forkchoice_updated(forkchoice, None).unwrap();
+            assert_eq!(result.forkchoice_status(), ForkchoiceStatus::Syncing);
+        }
+
+        #[tokio::test]
+        async fn canonical_ancestor_head() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+            let block1 = random_block(&mut rng, 1, Some(genesis.hash), None, Some(0));
+            let block2 = random_block(&mut rng, 2, Some(block1.hash), None, Some(0));
+            insert_blocks(
+                env.db.as_ref(),
+                chain_spec.clone(),
+                [&genesis, &block1, &block2].into_iter(),
+            );
+            env.db
+                .update(|tx| {
+                    tx.put::<tables::SyncStage>(
+                        StageId::Finish.to_string(),
+                        StageCheckpoint::new(block2.number),
+                    )
+                })
+                .unwrap()
+                .unwrap();
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            let forkchoice = ForkchoiceState {
+                head_block_hash: block2.hash,
+                finalized_block_hash: block1.hash,
+                ..Default::default()
+            };
+            let result = env.send_forkchoice_updated(forkchoice).await.unwrap();
+            let expected_result = ForkchoiceUpdated::new(PayloadStatus::new(
+                PayloadStatusEnum::Valid,
+                Some(block2.hash),
+            ));
+            assert_eq!(result, expected_result);
+
+            // The head is an ancestor of the canonical head, this must not unwind the chain and
+            // the latest valid hash must be the requested head.
+            let ancestor_forkchoice = ForkchoiceState {
+                head_block_hash: block1.hash,
+                finalized_block_hash: block1.hash,
+                ..Default::default()
+            };
+            let result = env.send_forkchoice_updated(ancestor_forkchoice).await.unwrap();
+            let expected_result = ForkchoiceUpdated::new(PayloadStatus::new(
+                PayloadStatusEnum::Valid,
+                Some(block1.hash),
+            ));
+            assert_eq!(result, expected_result);
+
+            let factory = ProviderFactory::new(env.db.as_ref(), chain_spec);
+            assert_eq!(factory.provider().unwrap().last_block_number().unwrap(), block2.number);
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn unknown_head_hash() {
             let mut rng = generators::rng();
@@ -24,17 +24,60 @@ pub fn base_block_reward(
     block_number: BlockNumber,
     block_difficulty: U256,
     total_difficulty: U256,
-) -> Option<u128> {
-    if chain_spec.chain == Chain::goerli() ||
-        chain_spec.fork(Hardfork::Paris).active_at_ttd(total_difficulty, block_difficulty)
-    {
-        None
+) -> Option<U256> {
+    if chain_spec.chain == Chain::goerli() {
+        return None
+    }
+    reward_era(chain_spec, block_number, total_difficulty.saturating_sub(block_difficulty))
+        .base_block_reward()
+}
+
+/// The block reward regime that applies to a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RewardEra {
+    /// Blocks before Byzantium, with a base reward of 5 ETH.
+    Frontier,
+    /// Blocks from Byzantium until Petersburg, with a base reward of 3 ETH.
+    Byzantium,
+    /// Blocks from Constantinople and Petersburg until the merge, with a base reward of 2 ETH.
+    Constantinople,
+    /// Blocks after the merge, which are not rewarded.
+    PostMerge,
+}
+
+impl RewardEra {
+    /// Returns the base block reward of the era, or `None` after the merge.
+    pub fn base_block_reward(&self) -> Option<U256> {
+        match self {
+            RewardEra::Frontier => Some(U256::from(ETH_TO_WEI * 5)),
+            RewardEra::Byzantium => Some(U256::from(ETH_TO_WEI * 3)),
+            RewardEra::Constantinople => Some(U256::from(ETH_TO_WEI * 2)),
+            RewardEra::PostMerge => None,
+        }
+    }
+}
+
+/// Returns the reward regime that applies to the block.
+///
+/// `total_difficulty` is the total difficulty of the chain _before_ the block, i.e. the total
+/// difficulty of its parent, since a block is post merge once its parent reached the terminal total
+/// difficulty.
+///
+/// The era only depends on the activated forks, see [`base_block_reward`] for the reward of a block
+/// on chains without block rewards.
+pub fn reward_era(
+    chain_spec: &ChainSpec,
+    block_number: BlockNumber,
+    total_difficulty: U256,
+) -> RewardEra {
+    if chain_spec.fork(Hardfork::Paris).active_at_ttd(total_difficulty, U256::ZERO) {
+        RewardEra::PostMerge
     } else if chain_spec.fork(Hardfork::Petersburg).active_at_block(block_number) {
-        Some(ETH_TO_WEI * 2)
+        RewardEra::Constantinople
     } else if chain_spec.fork(Hardfork::Byzantium).active_at_block(block_number) {
-        Some(ETH_TO_WEI * 3)
+        RewardEra::Byzantium
     } else {
-        Some(ETH_TO_WEI * 5)
+        RewardEra::Frontier
     }
 }
 
@@ -61,7 +104,7 @@ pub fn base_block_reward(
 /// // The base block reward is 5 ETH, and the ommer inclusion reward is 1/32th of 5 ETH.
 /// assert_eq!(
 ///     reward.unwrap(),
-///      ETH_TO_WEI * 5 + ((ETH_TO_WEI * 5) >> 5)
+///     U256::from(ETH_TO_WEI * 5 + ((ETH_TO_WEI * 5) >> 5))
 /// );
 /// ```
 ///
@@ -70,12 +113,30 @@ pub fn base_block_reward(
 /// - Definition: [Yellow Paper][yp] (page 15, 11.3)
 ///
 /// [yp]: https://ethereum.github.io/yellowpaper/paper.pdf
-pub fn block_reward(base_block_reward: u128, ommers: usize) -> u128 {
-    base_block_reward + (base_block_reward >> 5) * ommers as u128
+pub fn block_reward(base_block_reward: U256, ommers: usize) -> U256 {
+    base_block_reward + ommer_inclusion_bonus(base_block_reward, ommers)
+}
+
+/// Calculates the bonus paid to the block beneficiary for including ommers.
+///
+/// Each included ommer adds 1/32th of the base reward. This is the part of [`block_reward`] on top
+/// of the base reward, and is distinct from the [`ommer_reward`] paid to the ommer beneficiaries.
+///
+/// # References
+///
+/// - Definition: [Yellow Paper][yp] (page 15, 11.3)
+///
+/// [yp]: https://ethereum.github.io/yellowpaper/paper.pdf
+pub fn ommer_inclusion_bonus(base_block_reward: U256, ommers: usize) -> U256 {
+    (base_block_reward >> 5) * U256::from(ommers)
 }
 
 /// Calculate the reward for an ommer.
 ///
+/// The reward is `(8 + ommer_block_number - block_number) * base_block_reward / 8`, decreasing
+/// with the distance of the ommer to the including block. Ommers at a distance of 8 or more, as
+/// well as ommers that are not below the including block, are not rewarded.
+///
 /// # Application
 ///
 /// Rewards are accumulative, so they should be added to the beneficiary addresses in addition to
@@ -95,28 +156,35 @@ pub fn block_reward(base_block_reward: u128, ommers: usize) -> u128 {
 /// [oe]: https://github.com/openethereum/openethereum/blob/6c2d392d867b058ff867c4373e40850ca3f96969/crates/ethcore/src/ethereum/ethash.rs#L319-L333
 /// [yp]: https://ethereum.github.io/yellowpaper/paper.pdf
 pub fn ommer_reward(
-    base_block_reward: u128,
+    base_block_reward: U256,
     block_number: BlockNumber,
     ommer_block_number: BlockNumber,
-) -> u128 {
-    ((8 + ommer_block_number - block_number) as u128 * base_block_reward) >> 3
+) -> U256 {
+    match block_number.checked_sub(ommer_block_number) {
+        Some(distance @ 1..=7) => U256::from(8 - distance) * base_block_reward / U256::from(8),
+        _ => U256::ZERO,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reth_primitives::{MAINNET, U256};
+    use reth_primitives::MAINNET;
+
+    fn eth(amount: u128) -> U256 {
+        U256::from(ETH_TO_WEI * amount)
+    }
 
     #[test]
     fn calc_base_block_reward() {
         // ((block number, td), reward)
         let cases = [
             // Pre-byzantium
-            ((0, U256::ZERO), Some(ETH_TO_WEI * 5)),
+            ((0, U256::ZERO), Some(eth(5))),
             // Byzantium
-            ((4370000, U256::ZERO), Some(ETH_TO_WEI * 3)),
+            ((4370000, U256::ZERO), Some(eth(3))),
             // Petersburg
-            ((7280000, U256::ZERO), Some(ETH_TO_WEI * 2)),
+            ((7280000, U256::ZERO), Some(eth(2))),
             // Merge
             ((10000000, U256::from(58_750_000_000_000_000_000_000_u128)), None),
         ];
@@ -126,20 +194,112 @@ mod tests {
         }
     }
 
+    #[test]
+    fn mainnet_reward_eras() {
+        let ttd = U256::from(58_750_000_000_000_000_000_000_u128);
+        // ((block number, parent td), era)
+        let cases = [
+            ((0, U256::ZERO), RewardEra::Frontier),
+            ((1_150_000, U256::ZERO), RewardEra::Frontier),
+            ((4_369_999, U256::ZERO), RewardEra::Frontier),
+            ((4_370_000, U256::ZERO), RewardEra::Byzantium),
+            ((7_279_999, U256::ZERO), RewardEra::Byzantium),
+            ((7_280_000, U256::ZERO), RewardEra::Constantinople),
+            // the last proof of work block
+            ((15_537_393, ttd - U256::from(1)), RewardEra::Constantinople),
+            ((15_537_394, ttd), RewardEra::PostMerge),
+            ((17_034_870, ttd), RewardEra::PostMerge),
+        ];
+
+        for ((block_number, td), era) in cases {
+            assert_eq!(reward_era(&MAINNET, block_number, td), era, "era at block {block_number}");
+            assert_eq!(
+                base_block_reward(&MAINNET, block_number, U256::ZERO, td),
+                era.base_block_reward()
+            );
+        }
+    }
+
     #[test]
     fn calc_full_block_reward() {
-        let base_reward = ETH_TO_WEI;
+        let base_reward = eth(1);
         let one_thirty_twoth_reward = base_reward >> 5;
 
         // (num_ommers, reward)
         let cases = [
             (0, base_reward),
             (1, base_reward + one_thirty_twoth_reward),
-            (2, base_reward + one_thirty_twoth_reward * 2),
+            (2, base_reward + one_thirty_twoth_reward * U256::from(2)),
         ];
 
         for (num_ommers, expected_reward) in cases {
             assert_eq!(block_reward(base_reward, num_ommers), expected_reward);
         }
     }
+
+    #[test]
+    fn block_reward_is_base_plus_inclusion_bonus() {
+        for base_reward in [eth(5), eth(3), eth(2), U256::from(31), U256::ZERO] {
+            assert_eq!(ommer_inclusion_bonus(base_reward, 0), U256::ZERO);
+            for num_ommers in 0..=2 {
+                assert_eq!(
+                    block_reward(base_reward, num_ommers),
+                    base_reward + ommer_inclusion_bonus(base_reward, num_ommers)
+                );
+            }
+        }
+        assert_eq!(ommer_inclusion_bonus(eth(2), 2), U256::from(ETH_TO_WEI / 8));
+    }
+
+    #[test]
+    fn ommer_reward_boundary_distances() {
+        let base_reward = eth(5);
+        for distance in 1..=7u64 {
+            assert_eq!(
+                ommer_reward(base_reward, 100, 100 - distance),
+                base_reward * U256::from(8 - distance) / U256::from(8)
+            );
+        }
+        // distances of 8 or more are not rewarded, and must not underflow
+        assert_eq!(ommer_reward(base_reward, 100, 92), U256::ZERO);
+        assert_eq!(ommer_reward(base_reward, 100, 0), U256::ZERO);
+        assert_eq!(ommer_reward(base_reward, 5, 10), U256::ZERO);
+        assert_eq!(ommer_reward(base_reward, 100, 100), U256::ZERO);
+    }
+
+    /// Miner and ommer rewards in the Frontier, Byzantium and Constantinople eras, as expected by
+    /// the Ethereum Foundation blockchain tests.
+    #[test]
+    fn reward_test_vectors() {
+        // (block number, ommer block numbers, miner reward, ommer rewards)
+        let cases: [(u64, &[u64], &str, &[&str]); 7] = [
+            // Frontier
+            (2, &[1], "5156250000000000000", &["4375000000000000000"]),
+            (10, &[8, 4], "5312500000000000000", &["3750000000000000000", "1250000000000000000"]),
+            // Byzantium
+            (4_370_000, &[4_369_999], "3093750000000000000", &["2625000000000000000"]),
+            (4_370_001, &[4_369_994], "3093750000000000000", &["375000000000000000"]),
+            // Constantinople
+            (7_280_000, &[7_279_999], "2062500000000000000", &["1750000000000000000"]),
+            (7_280_002, &[7_279_996], "2062500000000000000", &["500000000000000000"]),
+            (7_280_010, &[], "2000000000000000000", &[]),
+        ];
+
+        for (block_number, ommers, miner_reward, ommer_rewards) in cases {
+            let base_reward =
+                base_block_reward(&MAINNET, block_number, U256::ZERO, U256::ZERO).unwrap();
+            assert_eq!(
+                block_reward(base_reward, ommers.len()),
+                miner_reward.parse::<U256>().unwrap(),
+                "miner reward at block {block_number}"
+            );
+            for (ommer, expected) in ommers.iter().zip(ommer_rewards.iter()) {
+                assert_eq!(
+                    ommer_reward(base_reward, block_number, *ommer),
+                    expected.parse::<U256>().unwrap(),
+                    "reward of ommer {ommer} at block {block_number}"
+                );
+            }
+        }
+    }
 }
@@ -1,16 +1,15 @@
 //! Collection of methods for block validation.
+use rayon::prelude::*;
 use reth_interfaces::{consensus::ConsensusError, RethResult};
 use reth_primitives::{
-    constants::{
-        self,
-        eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK},
-    },
+    basefee::next_block_base_fee,
+    constants::eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK},
     eip4844::calculate_excess_blob_gas,
     BlockNumber, ChainSpec, Hardfork, Header, InvalidTransactionError, SealedBlock, SealedHeader,
-    Transaction, TransactionSignedEcRecovered, TxEip1559, TxEip2930, TxEip4844, TxLegacy,
+    Transaction, TransactionSignedEcRecovered, TxEip1559, TxEip2930, TxEip4844, TxLegacy, B256,
 };
 use reth_provider::{AccountReader, HeaderProvider, WithdrawalsProvider};
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 /// Validate header standalone
 pub fn validate_header_standalone(
@@ -249,6 +248,130 @@ pub fn validate_block_standalone(
     Ok(())
 }
 
+/// The maximum number of ommers of a block.
+const MAX_OMMERS: usize = 2;
+
+/// The intrinsic gas every transaction costs at least.
+const MIN_TRANSACTION_GAS: u64 = 21_000;
+
+/// Validate the body of a block against its header before the block is executed.
+///
+/// In addition to [validate_block_standalone] this checks:
+///
+/// - that there are no withdrawals and no withdrawals root before Shanghai
+/// - that there are no ommers after the merge, and at most [MAX_OMMERS] before
+/// - that the gas limit can pay for the intrinsic gas of all transactions
+///
+/// This is cheap compared to execution, so corrupted bodies, e.g. of peer responses, are rejected
+/// before they take up an execution slot.
+pub fn validate_block_pre_execution(
+    block: &SealedBlock,
+    chain_spec: &ChainSpec,
+) -> Result<(), ConsensusError> {
+    validate_block_standalone(block, chain_spec)?;
+
+    let is_shanghai = chain_spec.is_shanghai_active_at_timestamp(block.timestamp);
+    if !is_shanghai {
+        if block.withdrawals.is_some() {
+            return Err(ConsensusError::BodyWithdrawalsUnexpected)
+        }
+        if block.withdrawals_root.is_some() {
+            return Err(ConsensusError::WithdrawalsRootUnexpected)
+        }
+    }
+
+    let is_post_merge =
+        is_shanghai || chain_spec.fork(Hardfork::Paris).active_at_block(block.number);
+    if is_post_merge && !block.ommers.is_empty() {
+        return Err(ConsensusError::TheMergeOmmersNotEmpty)
+    }
+    if block.ommers.len() > MAX_OMMERS {
+        return Err(ConsensusError::TooManyOmmers { count: block.ommers.len(), max: MAX_OMMERS })
+    }
+
+    let max_transactions = block.gas_limit / MIN_TRANSACTION_GAS;
+    if block.body.len() as u64 > max_transactions {
+        return Err(ConsensusError::TooManyTransactions {
+            count: block.body.len(),
+            max: max_transactions,
+        })
+    }
+
+    Ok(())
+}
+
+/// The maximum number of generations an ommer can be older than the block that includes it.
+const MAX_OMMER_DEPTH: u64 = 6;
+
+/// Validates the ommers of a batch of blocks in parallel.
+///
+/// For every block this checks that:
+///
+/// - it includes at most [MAX_OMMERS] ommers
+/// - every ommer is one to [MAX_OMMER_DEPTH] generations older than the block
+/// - no ommer is included twice, neither in the same block nor in an earlier block of the batch
+///
+/// These are the ommers whose beneficiaries are rewarded when the blocks are executed, so this can
+/// run ahead of the serial execution of a range of pre-merge blocks.
+///
+/// The blocks are expected in ascending order. On failure the first invalid block of the batch is
+/// reported with its number, regardless of the order in which the blocks were validated.
+pub fn validate_ommer_rewards_batch(
+    blocks: &[SealedBlock],
+) -> Result<(), (BlockNumber, ConsensusError)> {
+    let ommer_hashes = blocks
+        .par_iter()
+        .map(|block| block.ommers.iter().map(Header::hash_slow).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // the index of the first block that includes the ommer
+    let mut first_inclusion = HashMap::new();
+    for (idx, hashes) in ommer_hashes.iter().enumerate() {
+        for hash in hashes {
+            first_inclusion.entry(*hash).or_insert(idx);
+        }
+    }
+
+    blocks
+        .par_iter()
+        .zip(&ommer_hashes)
+        .enumerate()
+        .map(|(idx, (block, hashes))| {
+            validate_block_ommers(block, hashes, |hash| first_inclusion[hash] == idx)
+                .map_err(|err| (block.number, err))
+        })
+        .find_first(Result::is_err)
+        .unwrap_or(Ok(()))
+}
+
+/// Validates the ommers of a single block, see [validate_ommer_rewards_batch].
+///
+/// `is_first_inclusion` returns false if the ommer was already included by an earlier block.
+fn validate_block_ommers(
+    block: &SealedBlock,
+    ommer_hashes: &[B256],
+    is_first_inclusion: impl Fn(&B256) -> bool,
+) -> Result<(), ConsensusError> {
+    if block.ommers.len() > MAX_OMMERS {
+        return Err(ConsensusError::TooManyOmmers { count: block.ommers.len(), max: MAX_OMMERS })
+    }
+
+    let mut seen = HashSet::with_capacity(ommer_hashes.len());
+    for (ommer, hash) in block.ommers.iter().zip(ommer_hashes) {
+        if ommer.number >= block.number || block.number - ommer.number > MAX_OMMER_DEPTH {
+            return Err(ConsensusError::OmmerDepthInvalid {
+                ommer_number: ommer.number,
+                block_number: block.number,
+            })
+        }
+        if !seen.insert(*hash) || !is_first_inclusion(hash) {
+            return Err(ConsensusError::DuplicateOmmer { hash: *hash })
+        }
+    }
+
+    Ok(())
+}
+
 /// Validate block in regards to parent
 pub fn validate_header_regarding_parent(
     parent: &SealedHeader,
@@ -308,15 +431,9 @@ pub fn validate_header_regarding_parent(
     if chain_spec.fork(Hardfork::London).active_at_block(child.number) {
         let base_fee = child.base_fee_per_gas.ok_or(ConsensusError::BaseFeeMissing)?;
 
+        // This BaseFeeMissing will not happen as previous blocks are checked to have them.
         let expected_base_fee =
-            if chain_spec.fork(Hardfork::London).transitions_at_block(child.number) {
-                constants::EIP1559_INITIAL_BASE_FEE
-            } else {
-                // This BaseFeeMissing will not happen as previous blocks are checked to have them.
-                parent
-                    .next_block_base_fee(chain_spec.base_fee_params)
-                    .ok_or(ConsensusError::BaseFeeMissing)?
-            };
+            next_block_base_fee(parent, chain_spec).ok_or(ConsensusError::BaseFeeMissing)?;
         if expected_base_fee != base_fee {
             return Err(ConsensusError::BaseFeeDiff { expected: expected_base_fee, got: base_fee })
         }
@@ -466,7 +583,7 @@ mod tests {
     use reth_primitives::{
         constants::eip4844::DATA_GAS_PER_BLOB, hex_literal::hex, proofs, Account, Address,
         BlockBody, BlockHash, BlockHashOrNumber, Bytes, ChainSpecBuilder, Header, Signature,
-        TransactionKind, TransactionSigned, Withdrawal, MAINNET, U256,
+        TransactionKind, TransactionSigned, Withdrawal, B256, MAINNET, U256,
     };
     use std::ops::RangeBounds;
 
@@ -824,4 +941,189 @@ mod tests {
             })
         );
     }
+
+    type BlockModification = fn(&mut Header, &mut BlockBody);
+
+    /// A valid Cancun block with a blob transaction and a withdrawal, after applying `modify`.
+    fn cancun_block(modify: BlockModification) -> SealedBlock {
+        let transaction = Transaction::Eip4844(TxEip4844 {
+            chain_id: 1u64,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(Address::default()),
+            blob_versioned_hashes: vec![B256::ZERO; 2],
+            ..Default::default()
+        });
+        let signature = Signature { odd_y_parity: true, r: U256::default(), s: U256::default() };
+        let transactions =
+            vec![TransactionSigned::from_transaction_and_signature(transaction, signature)];
+        let withdrawals = vec![Withdrawal {
+            index: 1,
+            address: Address::with_last_byte(1),
+            ..Default::default()
+        }];
+
+        let mut header = Header {
+            ommers_hash: proofs::calculate_ommers_root(&[]),
+            transactions_root: proofs::calculate_transaction_root(&transactions),
+            withdrawals_root: Some(proofs::calculate_withdrawals_root(&withdrawals)),
+            base_fee_per_gas: Some(1337u64),
+            gas_limit: 30_000_000,
+            blob_gas_used: Some(2 * DATA_GAS_PER_BLOB),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        };
+        let mut body = BlockBody { transactions, ommers: vec![], withdrawals: Some(withdrawals) };
+        modify(&mut header, &mut body);
+        SealedBlock::new(header.seal_slow(), body)
+    }
+
+    /// A valid pre-merge block, after applying `modify`.
+    fn frontier_block(modify: BlockModification) -> SealedBlock {
+        let mut header = Header {
+            number: 1,
+            ommers_hash: proofs::calculate_ommers_root(&[]),
+            transactions_root: proofs::calculate_transaction_root(&[]),
+            gas_limit: 5_000,
+            ..Default::default()
+        };
+        let mut body = BlockBody::default();
+        modify(&mut header, &mut body);
+        SealedBlock::new(header.seal_slow(), body)
+    }
+
+    fn set_ommers(header: &mut Header, body: &mut BlockBody, count: usize) {
+        body.ommers = vec![Header::default(); count];
+        header.ommers_hash = proofs::calculate_ommers_root(&body.ommers);
+    }
+
+    #[test]
+    fn pre_execution_validation() {
+        let chain_spec = ChainSpecBuilder::mainnet().cancun_activated().build();
+        let valid = cancun_block(|_, _| {});
+        assert_eq!(validate_block_pre_execution(&valid, &chain_spec), Ok(()));
+
+        let cases: [(BlockModification, ConsensusError); 9] = [
+            (
+                |header, _| header.ommers_hash = B256::ZERO,
+                ConsensusError::BodyOmmersHashDiff { got: valid.ommers_hash, expected: B256::ZERO },
+            ),
+            (
+                |header, _| header.transactions_root = B256::ZERO,
+                ConsensusError::BodyTransactionRootDiff {
+                    got: valid.transactions_root,
+                    expected: B256::ZERO,
+                },
+            ),
+            (
+                |header, _| header.withdrawals_root = Some(B256::ZERO),
+                ConsensusError::BodyWithdrawalsRootDiff {
+                    got: valid.withdrawals_root.unwrap(),
+                    expected: B256::ZERO,
+                },
+            ),
+            (|_, body| body.withdrawals = None, ConsensusError::BodyWithdrawalsMissing),
+            (|header, _| header.withdrawals_root = None, ConsensusError::WithdrawalsRootMissing),
+            (
+                |header, _| header.blob_gas_used = Some(DATA_GAS_PER_BLOB),
+                ConsensusError::BlobGasUsedDiff {
+                    header_blob_gas_used: DATA_GAS_PER_BLOB,
+                    expected_blob_gas_used: 2 * DATA_GAS_PER_BLOB,
+                },
+            ),
+            (|header, _| header.blob_gas_used = None, ConsensusError::BlobGasUsedMissing),
+            (|header, body| set_ommers(header, body, 1), ConsensusError::TheMergeOmmersNotEmpty),
+            (
+                |header, _| header.gas_limit = MIN_TRANSACTION_GAS - 1,
+                ConsensusError::TooManyTransactions { count: 1, max: 0 },
+            ),
+        ];
+        for (modify, expected) in cases {
+            assert_eq!(
+                validate_block_pre_execution(&cancun_block(modify), &chain_spec),
+                Err(expected)
+            );
+        }
+
+        // pre-merge
+        assert_eq!(validate_block_pre_execution(&frontier_block(|_, _| {}), &MAINNET), Ok(()));
+        assert_eq!(
+            validate_block_pre_execution(
+                &frontier_block(|header, body| set_ommers(header, body, MAX_OMMERS)),
+                &MAINNET
+            ),
+            Ok(())
+        );
+
+        let cases: [(BlockModification, ConsensusError); 3] = [
+            (|_, body| body.withdrawals = Some(vec![]), ConsensusError::BodyWithdrawalsUnexpected),
+            (
+                |header, _| header.withdrawals_root = Some(proofs::calculate_withdrawals_root(&[])),
+                ConsensusError::WithdrawalsRootUnexpected,
+            ),
+            (
+                |header, body| set_ommers(header, body, MAX_OMMERS + 1),
+                ConsensusError::TooManyOmmers { count: MAX_OMMERS + 1, max: MAX_OMMERS },
+            ),
+        ];
+        for (modify, expected) in cases {
+            assert_eq!(
+                validate_block_pre_execution(&frontier_block(modify), &MAINNET),
+                Err(expected)
+            );
+        }
+    }
+
+    /// Returns pre-merge blocks starting at the given number that each include an ommer that is
+    /// two generations older.
+    fn blocks_with_ommers(first: BlockNumber, count: u64) -> Vec<SealedBlock> {
+        (first..first + count)
+            .map(|number| {
+                let ommer = Header { number: number - 2, ..Default::default() };
+                SealedBlock::new(
+                    Header { number, ..Default::default() }.seal_slow(),
+                    BlockBody { ommers: vec![ommer], ..Default::default() },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ommer_rewards_batch() {
+        let mut blocks = blocks_with_ommers(10, 64);
+        assert_eq!(validate_ommer_rewards_batch(&blocks), Ok(()));
+
+        // an ommer that is seven generations older than the block
+        blocks[20].ommers[0].number = blocks[20].number - MAX_OMMER_DEPTH - 1;
+        // later blocks that are invalid as well
+        blocks[40].ommers.push(blocks[40].ommers[0].clone());
+        blocks[50].ommers[0].number = blocks[50].number;
+
+        let expected = Err((
+            blocks[20].number,
+            ConsensusError::OmmerDepthInvalid {
+                ommer_number: blocks[20].number - MAX_OMMER_DEPTH - 1,
+                block_number: blocks[20].number,
+            },
+        ));
+        for _ in 0..16 {
+            assert_eq!(validate_ommer_rewards_batch(&blocks), expected);
+        }
+
+        blocks[20].ommers[0].number = blocks[20].number - 1;
+        let duplicate = blocks[40].ommers[0].hash_slow();
+        assert_eq!(
+            validate_ommer_rewards_batch(&blocks),
+            Err((blocks[40].number, ConsensusError::DuplicateOmmer { hash: duplicate }))
+        );
+
+        // an ommer that was already included by an earlier block of the batch
+        let mut blocks = blocks_with_ommers(10, 4);
+        let ommer = blocks[0].ommers[0].clone();
+        blocks[2].ommers.push(ommer.clone());
+        assert_eq!(
+            validate_ommer_rewards_batch(&blocks),
+            Err((blocks[2].number, ConsensusError::DuplicateOmmer { hash: ommer.hash_slow() }))
+        );
+    }
 }
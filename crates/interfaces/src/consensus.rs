@@ -238,6 +238,10 @@ pub enum ConsensusError {
     #[error("Ommer root after merge is not empty")]
     TheMergeOmmerRootIsNotEmpty,
 
+    /// Error when the block body contains ommers after the merge.
+    #[error("Ommers after merge are not empty")]
+    TheMergeOmmersNotEmpty,
+
     /// Error when the withdrawals root is missing.
     #[error("Missing withdrawals root")]
     WithdrawalsRootMissing,
@@ -250,6 +254,44 @@ pub enum ConsensusError {
     #[error("Missing withdrawals")]
     BodyWithdrawalsMissing,
 
+    /// Error when the block body contains withdrawals before Shanghai.
+    #[error("Unexpected withdrawals")]
+    BodyWithdrawalsUnexpected,
+
+    /// Error when the block has more ommers than allowed.
+    #[error("Block has {count} ommers, the maximum is {max}")]
+    TooManyOmmers {
+        /// The number of ommers in the block.
+        count: usize,
+        /// The maximum number of ommers.
+        max: usize,
+    },
+
+    /// Error when an ommer is not one to six generations older than the block that includes it.
+    #[error("Ommer {ommer_number} is not within the allowed depth of block {block_number}")]
+    OmmerDepthInvalid {
+        /// The number of the ommer.
+        ommer_number: BlockNumber,
+        /// The number of the block that includes the ommer.
+        block_number: BlockNumber,
+    },
+
+    /// Error when an ommer is included more than once.
+    #[error("Ommer {hash:?} is included more than once")]
+    DuplicateOmmer {
+        /// The hash of the ommer.
+        hash: B256,
+    },
+
+    /// Error when the block has more transactions than its gas limit can pay for.
+    #[error("Block has {count} transactions, its gas limit allows at most {max}")]
+    TooManyTransactions {
+        /// The number of transactions in the block.
+        count: usize,
+        /// The maximum number of transactions within the gas limit.
+        max: u64,
+    },
+
     /// Error when blob gas used is missing.
     #[error("Missing blob gas used")]
     BlobGasUsedMissing,
@@ -43,6 +43,16 @@ pub enum DatabaseError {
     /// Failed to use the specified log level, as it's not available.
     #[error("Log level is not available: {0:?}")]
     LogLevelUnavailable(LogLevel),
+    /// The read transaction was open for longer than the maximum duration and was aborted.
+    #[error(
+        "Read transaction {txn_id} was aborted after being open for longer than {max_duration:?}"
+    )]
+    ReadTransactionTimeout {
+        /// The id of the aborted transaction.
+        txn_id: u64,
+        /// The maximum duration a read transaction may be open.
+        max_duration: std::time::Duration,
+    },
 }
 
 /// Database write operation type
@@ -1,4 +1,4 @@
-use reth_primitives::{BlockNumHash, Bloom, PruneSegmentError, B256};
+use reth_primitives::{Address, BlockNumHash, Bloom, PruneSegmentError, B256, U256};
 use thiserror::Error;
 
 /// Transaction validation errors
@@ -16,6 +16,58 @@ pub enum BlockValidationError {
     /// Error when recovering the sender for a transaction
     #[error("Failed to recover sender for transaction")]
     SenderRecoveryError,
+    /// Error when the nonce of a transaction is lower than the nonce of its sender
+    #[error("Transaction #{index} from {sender} has nonce {tx_nonce}, lower than the sender nonce {state_nonce}")]
+    TransactionNonceTooLow {
+        /// The index of the transaction in the block
+        index: usize,
+        /// The sender of the transaction
+        sender: Address,
+        /// The nonce of the transaction
+        tx_nonce: u64,
+        /// The nonce of the sender
+        state_nonce: u64,
+    },
+    /// Error when the nonce of a transaction is higher than the nonce of its sender
+    #[error("Transaction #{index} from {sender} has nonce {tx_nonce}, higher than the sender nonce {state_nonce}")]
+    TransactionNonceTooHigh {
+        /// The index of the transaction in the block
+        index: usize,
+        /// The sender of the transaction
+        sender: Address,
+        /// The nonce of the transaction
+        tx_nonce: u64,
+        /// The nonce of the sender
+        state_nonce: u64,
+    },
+    /// Error when the sender can not pay for the gas limit and value of a transaction
+    #[error("Sender {sender} of transaction #{index} has insufficient funds")]
+    TransactionInsufficientFunds {
+        /// The index of the transaction in the block
+        index: usize,
+        /// The sender of the transaction
+        sender: Address,
+    },
+    /// Error when a blob transaction fails the EIP-4844 checks
+    #[error("Blob transaction #{index} from {sender} is invalid: {message}")]
+    InvalidBlobTransaction {
+        /// The index of the transaction in the block
+        index: usize,
+        /// The sender of the transaction
+        sender: Address,
+        /// Error message
+        message: String,
+    },
+    /// Error when a transaction fails any other validation before its execution
+    #[error("Transaction #{index} from {sender} is invalid: {message}")]
+    InvalidTransaction {
+        /// The index of the transaction in the block
+        index: usize,
+        /// The sender of the transaction
+        sender: Address,
+        /// Error message
+        message: String,
+    },
     /// Error when incrementing balance in post execution
     #[error("Incrementing balance in post execution failed")]
     IncrementBalanceFailed,
@@ -35,6 +87,29 @@ pub enum BlockValidationError {
         /// The expected bloom filter
         expected: Box<Bloom>,
     },
+    /// Error when the wei amount a withdrawal was credited with is not a multiple of the
+    /// withdrawal amount scale, one gwei by default, which indicates a unit conversion bug
+    #[error("Withdrawal {index} amount is not a multiple of the withdrawal amount scale.")]
+    WithdrawalAmountNotGweiAligned {
+        /// The index of the withdrawal
+        index: u64,
+    },
+    /// Error when the root of the withdrawals doesn't match the withdrawals root of the header
+    #[error("Withdrawals root {got:?} is different than expected {expected:?}.")]
+    WithdrawalsRootMismatch {
+        /// The root of the withdrawals
+        got: B256,
+        /// The expected withdrawals root
+        expected: B256,
+    },
+    /// Error when the ether issued by a block doesn't match the expected issuance
+    #[error("Block issuance {got} is different than expected {expected}.")]
+    IssuanceMismatch {
+        /// The issuance of the block
+        got: u128,
+        /// The expected issuance
+        expected: u128,
+    },
     /// Error when transaction gas limit exceeds available block gas
     #[error("Transaction gas limit {transaction_gas_limit} is more than blocks available gas {block_available_gas}")]
     TransactionGasLimitMoreThanAvailableBlockGas {
@@ -61,12 +136,78 @@ pub enum BlockValidationError {
     },
     #[error("Missing total difficulty for block {hash:?}")]
     MissingTotalDifficulty { hash: B256 },
+    /// Error when the total difficulty of a block is unknown and the merge block is not
+    /// configured, so it can't be determined whether the block is post merge
+    #[error("Unknown total difficulty and no merge block configured for block {block_number}")]
+    UnknownMergeStatus {
+        /// The number of the block
+        block_number: u64,
+    },
+    /// Error when a post merge block has ommers
+    #[error("Post merge block {block_number} has {count} ommers")]
+    OmmersNotAllowed {
+        /// The number of the block
+        block_number: u64,
+        /// The number of ommers of the block
+        count: usize,
+    },
+    /// Error when a post merge block has a nonzero difficulty
+    #[error("Post merge block {block_number} has nonzero difficulty {difficulty}")]
+    NonZeroDifficultyPostMerge {
+        /// The number of the block
+        block_number: u64,
+        /// The difficulty of the block
+        difficulty: U256,
+    },
     /// Error for EIP-4788 when parent beacon block root is missing
     #[error("EIP-4788 Parent beacon block root missing for active Cancun block")]
     MissingParentBeaconBlockRoot,
     /// Error for Cancun genesis block when parent beacon block root is not zero
     #[error("The parent beacon block root is not zero for Cancun genesis block")]
     CancunGenesisParentBeaconBlockRootNotZero,
+    /// Error when repeated executions of a system call with the same input produce different
+    /// state changes
+    #[error("System call to {contract} is not deterministic")]
+    NondeterministicSystemCall {
+        /// The called system contract
+        contract: Address,
+    },
+    /// Error when a system call that is required to succeed reverts or halts
+    #[error("System call to {contract} failed")]
+    SystemCallFailed {
+        /// The called system contract
+        contract: Address,
+    },
+    /// Error when a system call returns more data than allowed by the chain spec
+    #[error("System call to {contract} returned {size} bytes, more than the maximum of {max}")]
+    SystemCallOutputTooLarge {
+        /// The called system contract
+        contract: Address,
+        /// The size of the returned data
+        size: usize,
+        /// The maximum size of the returned data
+        max: usize,
+    },
+    /// Error when the pre-block system calls of a block use more gas in total than allowed by the
+    /// chain spec
+    #[error("System calls used {gas_used} gas, more than the maximum of {max}")]
+    SystemCallGasExceeded {
+        /// The total gas used by the system calls
+        gas_used: u64,
+        /// The maximum total gas of the system calls
+        max: u64,
+    },
+    /// Error when the storage committed by the EIP-4788 beacon root call differs from the
+    /// natively derived storage
+    #[error("Beacon root contract slot {slot} is {got}, expected {expected}")]
+    BeaconRootMismatch {
+        /// The storage slot
+        slot: U256,
+        /// The natively derived value
+        expected: U256,
+        /// The committed value
+        got: U256,
+    },
 }
 
 /// BlockExecutor Errors
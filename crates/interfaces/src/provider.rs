@@ -67,6 +67,9 @@ pub enum ProviderError {
     /// Unable to compute state root on top of historical block
     #[error("Unable to compute state root on top of historical block")]
     StateRootNotAvailableForHistoricalBlock,
+    /// The state provider is unable to enumerate the storage of an account
+    #[error("Unable to enumerate the storage of an account")]
+    StorageEntriesNotAvailable,
     /// Unable to find the block number for a given transaction index
     #[error("Unable to find the block number for a given transaction index")]
     BlockNumberForTransactionIndexNotFound,
@@ -1,5 +1,5 @@
 //! Traits used when interacting with the sync status of the network.
-use reth_primitives::Head;
+use reth_primitives::{stage::StageId, BlockNumber, Head};
 
 /// A type that provides information about whether the node is currently syncing and the network is
 /// currently serving syncing related requests.
@@ -26,6 +26,12 @@ pub trait NetworkSyncUpdater: std::fmt::Debug + Send + Sync + 'static {
 
     /// Updates the status of the p2p node
     fn update_status(&self, head: Head);
+
+    /// Updates the committed checkpoints of all stages of the pipeline.
+    ///
+    /// The minimum of these checkpoints is the block up to which the pipeline fully synced the
+    /// chain.
+    fn update_stage_checkpoints(&self, checkpoints: Vec<(StageId, BlockNumber)>);
 }
 
 /// The state the network is currently in when it comes to synchronization.
@@ -65,4 +71,5 @@ impl SyncStateProvider for NoopSyncStateUpdater {
 impl NetworkSyncUpdater for NoopSyncStateUpdater {
     fn update_sync_state(&self, _state: SyncState) {}
     fn update_status(&self, _: Head) {}
+    fn update_stage_checkpoints(&self, _: Vec<(StageId, BlockNumber)>) {}
 }
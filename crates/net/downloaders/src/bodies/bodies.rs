@@ -1,4 +1,4 @@
-use super::queue::BodiesRequestQueue;
+use super::{queue::BodiesRequestQueue, validation::BodiesValidationPool};
 use crate::{bodies::task::TaskDownloader, metrics::BodyDownloaderMetrics};
 use futures::Stream;
 use futures_util::StreamExt;
@@ -59,6 +59,8 @@ pub struct BodiesDownloader<B: BodiesClient, DB> {
     latest_queued_block_number: Option<BlockNumber>,
     /// Requests in progress
     in_progress_queue: BodiesRequestQueue<B>,
+    /// The pool that validates the responses of the requests in progress.
+    validation_pool: BodiesValidationPool,
     /// Buffered responses
     buffered_responses: BinaryHeap<OrderedBodiesResponse>,
     /// Queued body responses that can be returned for insertion into the database.
@@ -178,6 +180,12 @@ where
         self.buffered_blocks_size_bytes < self.max_buffered_blocks_size_bytes
     }
 
+    /// Returns true if the number of responses waiting for validation is lower than the
+    /// configured maximum
+    fn has_validation_capacity(&self) -> bool {
+        self.validation_pool.has_capacity()
+    }
+
     // Check if the stream is terminated
     fn is_terminated(&self) -> bool {
         // There is nothing to request if the range is empty
@@ -393,7 +401,8 @@ where
             // Submit new requests
             let concurrent_requests_limit = this.concurrent_request_limit();
             'inner: while this.in_progress_queue.len() < concurrent_requests_limit &&
-                this.has_buffer_capacity()
+                this.has_buffer_capacity() &&
+                this.has_validation_capacity()
             {
                 match this.next_headers_request() {
                     Ok(Some(request)) => {
@@ -401,6 +410,7 @@ where
                         this.in_progress_queue.push_new_request(
                             Arc::clone(&this.client),
                             Arc::clone(&this.consensus),
+                            this.validation_pool.clone(),
                             request,
                         );
                         new_request_submitted = true;
@@ -512,6 +522,10 @@ pub struct BodiesDownloaderBuilder {
     pub max_buffered_blocks_size_bytes: usize,
     /// The maximum number of requests to send concurrently.
     pub concurrent_requests_range: RangeInclusive<usize>,
+    /// The number of threads that validate responses, one per CPU if zero.
+    pub validation_parallelism: usize,
+    /// The maximum number of responses waiting for validation before no new requests are sent.
+    pub max_pending_validations: usize,
 }
 
 impl Default for BodiesDownloaderBuilder {
@@ -521,6 +535,8 @@ impl Default for BodiesDownloaderBuilder {
             stream_batch_size: 10_000,
             max_buffered_blocks_size_bytes: 2 * 1024 * 1024 * 1024, // ~2GB
             concurrent_requests_range: 5..=100,
+            validation_parallelism: 0,
+            max_pending_validations: 50,
         }
     }
 }
@@ -556,7 +572,23 @@ impl BodiesDownloaderBuilder {
         self
     }
 
+    /// Set the number of threads that validate responses on the downloader, one per CPU if zero.
+    pub fn with_validation_parallelism(mut self, validation_parallelism: usize) -> Self {
+        self.validation_parallelism = validation_parallelism;
+        self
+    }
+
+    /// Set the maximum number of responses waiting for validation on the downloader.
+    pub fn with_max_pending_validations(mut self, max_pending_validations: usize) -> Self {
+        self.max_pending_validations = max_pending_validations;
+        self
+    }
+
     /// Consume self and return the concurrent downloader.
+    ///
+    /// # Panics
+    ///
+    /// If the thread pool for validating responses cannot be created.
     pub fn build<B, DB>(
         self,
         client: B,
@@ -572,9 +604,17 @@ impl BodiesDownloaderBuilder {
             stream_batch_size,
             concurrent_requests_range,
             max_buffered_blocks_size_bytes,
+            validation_parallelism,
+            max_pending_validations,
         } = self;
         let metrics = BodyDownloaderMetrics::default();
         let in_progress_queue = BodiesRequestQueue::new(metrics.clone());
+        let validation_pool = BodiesValidationPool::new(
+            validation_parallelism,
+            max_pending_validations,
+            metrics.clone(),
+        )
+        .expect("failed to build bodies validation pool");
         BodiesDownloader {
             client: Arc::new(client),
             consensus,
@@ -584,6 +624,7 @@ impl BodiesDownloaderBuilder {
             max_buffered_blocks_size_bytes,
             concurrent_requests_range,
             in_progress_queue,
+            validation_pool,
             metrics,
             download_range: RangeInclusive::new(1, 0),
             latest_queued_block_number: None,
@@ -604,10 +645,58 @@ mod tests {
     use assert_matches::assert_matches;
     use futures_util::stream::StreamExt;
     use reth_db::test_utils::create_test_rw_db;
-    use reth_interfaces::test_utils::{generators, generators::random_block_range, TestConsensus};
-    use reth_primitives::{BlockBody, B256};
+    use reth_interfaces::{
+        consensus::ConsensusError,
+        test_utils::{generators, generators::random_block_range, TestConsensus},
+    };
+    use reth_primitives::{proofs, BlockBody, Header, PeerId, SealedBlock, B256, U256};
     use std::{collections::HashMap, sync::Arc};
 
+    /// Consensus that only checks that the bodies match the ommers hash and transactions root of
+    /// their headers.
+    #[derive(Debug)]
+    struct BodyRootsConsensus;
+
+    impl Consensus for BodyRootsConsensus {
+        fn validate_header(&self, _header: &SealedHeader) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+
+        fn validate_header_against_parent(
+            &self,
+            _header: &SealedHeader,
+            _parent: &SealedHeader,
+        ) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+
+        fn validate_header_with_total_difficulty(
+            &self,
+            _header: &Header,
+            _total_difficulty: U256,
+        ) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+
+        fn validate_block(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+            let ommers_hash = proofs::calculate_ommers_root(&block.ommers);
+            if block.header.ommers_hash != ommers_hash {
+                return Err(ConsensusError::BodyOmmersHashDiff {
+                    got: ommers_hash,
+                    expected: block.header.ommers_hash,
+                })
+            }
+            let transactions_root = proofs::calculate_transaction_root(&block.body);
+            if block.header.transactions_root != transactions_root {
+                return Err(ConsensusError::BodyTransactionRootDiff {
+                    got: transactions_root,
+                    expected: block.header.transactions_root,
+                })
+            }
+            Ok(())
+        }
+    }
+
     // Check that the blocks are emitted in order of block number, not in order of
     // first-downloaded
     #[tokio::test]
@@ -735,4 +824,38 @@ mod tests {
             Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter().skip(100), &mut bodies))
         );
     }
+
+    // Check that bodies validated on the validation pool are emitted in request order, and that an
+    // invalid body is attributed to the peer that sent it.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn validates_bodies_in_parallel() {
+        let db = create_test_rw_db();
+        let (headers, mut bodies) = generate_bodies(0..=199);
+
+        insert_headers(&db, &headers);
+
+        let peers = (0..4).map(|_| PeerId::random()).collect::<Vec<_>>();
+        let bad_peer = peers[2];
+        let client = Arc::new(
+            TestBodiesClient::default()
+                .with_bodies(bodies.clone())
+                .with_should_delay(true)
+                .with_peers(peers)
+                .with_invalid_response_from(bad_peer),
+        );
+        let mut downloader = BodiesDownloaderBuilder::default()
+            .with_request_limit(10)
+            .with_stream_batch_size(200)
+            .with_validation_parallelism(4)
+            .with_max_pending_validations(2)
+            .build(client.clone(), Arc::new(BodyRootsConsensus), db);
+        downloader.set_download_range(0..=199).expect("failed to set download range");
+
+        assert_matches!(
+            downloader.next().await,
+            Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter(), &mut bodies))
+        );
+        assert!(downloader.next().await.is_none());
+        assert_eq!(client.reported_peers(), vec![bad_peer]);
+    }
 }
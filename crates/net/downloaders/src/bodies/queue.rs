@@ -1,4 +1,4 @@
-use super::request::BodiesRequestFuture;
+use super::{request::BodiesRequestFuture, validation::BodiesValidationPool};
 use crate::metrics::BodyDownloaderMetrics;
 use futures::{stream::FuturesUnordered, Stream};
 use futures_util::StreamExt;
@@ -26,6 +26,8 @@ pub(crate) struct BodiesRequestQueue<B: BodiesClient> {
     metrics: BodyDownloaderMetrics,
     /// Last requested block number.
     pub(crate) last_requested_block_number: Option<BlockNumber>,
+    /// The id of the next request.
+    next_request_id: u64,
 }
 
 impl<B> BodiesRequestQueue<B>
@@ -34,7 +36,12 @@ where
 {
     /// Create new instance of request queue.
     pub(crate) fn new(metrics: BodyDownloaderMetrics) -> Self {
-        Self { metrics, inner: Default::default(), last_requested_block_number: None }
+        Self {
+            metrics,
+            inner: Default::default(),
+            last_requested_block_number: None,
+            next_request_id: 0,
+        }
     }
 
     /// Returns `true` if the queue is empty.
@@ -59,6 +66,7 @@ where
         &mut self,
         client: Arc<B>,
         consensus: Arc<dyn Consensus>,
+        validation_pool: BodiesValidationPool,
         request: Vec<SealedHeader>,
     ) {
         // Set last max requested block number
@@ -69,9 +77,18 @@ where
                 None => last.number,
             })
             .or(self.last_requested_block_number);
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
         // Create request and push into the queue.
         self.inner.push(
-            BodiesRequestFuture::new(client, consensus, self.metrics.clone()).with_headers(request),
+            BodiesRequestFuture::new(
+                client,
+                consensus,
+                validation_pool,
+                request_id,
+                self.metrics.clone(),
+            )
+            .with_headers(request),
         )
     }
 }
@@ -1,3 +1,6 @@
+use super::validation::{
+    BodiesValidationPool, ValidatedResponse, ValidationHandle, ValidationRequest,
+};
 use crate::metrics::{BodyDownloaderMetrics, ResponseMetrics};
 use futures::{Future, FutureExt};
 use reth_interfaces::{
@@ -8,10 +11,9 @@ use reth_interfaces::{
         priority::Priority,
     },
 };
-use reth_primitives::{BlockBody, PeerId, SealedBlock, SealedHeader, WithPeerId, B256};
+use reth_primitives::{BlockBody, PeerId, SealedHeader, WithPeerId, B256};
 use std::{
     collections::VecDeque,
-    mem,
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
@@ -23,8 +25,8 @@ use std::{
 /// If the response arrived with insufficient number of bodies, the future
 /// will issue another request until all bodies are collected.
 ///
-/// It then proceeds to verify the downloaded bodies. In case of an validation error,
-/// the future will start over.
+/// It then proceeds to verify the downloaded bodies on the [BodiesValidationPool]. In case of an
+/// validation error, the future will start over.
 ///
 /// The future will filter out any empty headers (see [reth_primitives::Header::is_empty]) from the
 /// request. If [BodiesRequestFuture] was initialized with all empty headers, no request will be
@@ -39,6 +41,9 @@ use std::{
 pub(crate) struct BodiesRequestFuture<B: BodiesClient> {
     client: Arc<B>,
     consensus: Arc<dyn Consensus>,
+    validation_pool: BodiesValidationPool,
+    /// The id of the request, used to tag its responses for validation.
+    request_id: u64,
     metrics: BodyDownloaderMetrics,
     /// Metrics for individual responses. This can be used to observe how the size (in bytes) of
     /// responses change while bodies are being downloaded.
@@ -48,6 +53,8 @@ pub(crate) struct BodiesRequestFuture<B: BodiesClient> {
     /// Internal buffer for all blocks
     buffer: Vec<BlockResponse>,
     fut: Option<B::Output>,
+    /// The response that is being validated.
+    validating: Option<ValidationHandle>,
     /// Tracks how many bodies we requested in the last request.
     last_request_len: Option<usize>,
 }
@@ -60,17 +67,22 @@ where
     pub(crate) fn new(
         client: Arc<B>,
         consensus: Arc<dyn Consensus>,
+        validation_pool: BodiesValidationPool,
+        request_id: u64,
         metrics: BodyDownloaderMetrics,
     ) -> Self {
         Self {
             client,
             consensus,
+            validation_pool,
+            request_id,
             metrics,
             response_metrics: Default::default(),
             pending_headers: Default::default(),
             buffer: Default::default(),
             last_request_len: None,
             fut: None,
+            validating: None,
         }
     }
 
@@ -112,7 +124,7 @@ where
         self.fut = Some(client.get_block_bodies_with_priority(req, priority));
     }
 
-    /// Process block response.
+    /// Process block response and submit it for validation.
     /// Returns an error if the response is invalid.
     fn on_block_response(&mut self, response: WithPeerId<Vec<BlockBody>>) -> DownloadResult<()> {
         let (peer_id, bodies) = response.split();
@@ -139,64 +151,56 @@ where
             })
         }
 
-        // Buffer block responses
-        self.try_buffer_blocks(bodies)?;
-
-        // Submit next request if any
-        if let Some(req) = self.next_request() {
-            self.submit_request(req, Priority::High);
-        } else {
-            self.fut = None;
+        // Take the headers of the bodies, including any empty headers in between
+        let mut headers = Vec::with_capacity(response_len);
+        let mut non_empty_headers = 0;
+        while non_empty_headers < response_len {
+            let Some(header) = self.pending_headers.pop_front() else { break };
+            if !header.is_empty() {
+                non_empty_headers += 1;
+            }
+            headers.push(header);
         }
 
+        self.fut = None;
+        self.validating = Some(self.validation_pool.spawn(ValidationRequest {
+            request_id: self.request_id,
+            peer_id,
+            headers,
+            bodies,
+            consensus: Arc::clone(&self.consensus),
+        }));
+
         Ok(())
     }
 
-    /// Attempt to buffer body responses. Returns an error if body response fails validation.
-    /// Every body preceeding the failed one will be buffered.
+    /// Buffer the validated blocks of a response. Every block preceding the first invalid body is
+    /// buffered.
     ///
-    /// This method removes headers from the internal collection.
-    /// If the response fails validation, then the header will be put back.
-    fn try_buffer_blocks(&mut self, bodies: Vec<BlockBody>) -> DownloadResult<()> {
-        let bodies_capacity = bodies.capacity();
-        let bodies_len = bodies.len();
-        let mut bodies = bodies.into_iter().peekable();
-
-        let mut total_size = bodies_capacity * mem::size_of::<BlockBody>();
-        while bodies.peek().is_some() {
-            let next_header = match self.pending_headers.pop_front() {
-                Some(header) => header,
-                None => return Ok(()), // no more headers
-            };
-
-            if next_header.is_empty() {
-                // increment empty block body metric
-                total_size += mem::size_of::<BlockBody>();
-                self.buffer.push(BlockResponse::Empty(next_header));
-            } else {
-                let next_body = bodies.next().unwrap();
-
-                // increment full block body metric
-                total_size += next_body.size();
-
-                let block = SealedBlock::new(next_header, next_body);
-
-                if let Err(error) = self.consensus.validate_block(&block) {
-                    // Body is invalid, put the header back and return an error
-                    let hash = block.hash();
-                    self.pending_headers.push_front(block.header);
-                    return Err(DownloadError::BodyValidation { hash, error })
-                }
+    /// If the response failed validation, the headers starting with the header of the invalid
+    /// body are put back and the peer is penalized.
+    fn on_validated_response(&mut self, response: ValidatedResponse) {
+        let ValidatedResponse { request_id, peer_id, blocks, error, size } = response;
+        debug_assert_eq!(request_id, self.request_id, "response of another request");
+
+        // Increment per-response metric
+        self.response_metrics.response_size_bytes.set(size as f64);
+        self.response_metrics.response_length.set(blocks.len() as f64);
+
+        self.buffer.extend(blocks);
 
-                self.buffer.push(BlockResponse::Full(block));
+        if let Some((error, unprocessed)) = error {
+            for header in unprocessed.into_iter().rev() {
+                self.pending_headers.push_front(header);
             }
+            self.on_error(error, Some(peer_id));
+            return
         }
 
-        // Increment per-response metric
-        self.response_metrics.response_size_bytes.set(total_size as f64);
-        self.response_metrics.response_length.set(bodies_len as f64);
-
-        Ok(())
+        // Submit next request if any
+        if let Some(req) = self.next_request() {
+            self.submit_request(req, Priority::High);
+        }
     }
 }
 
@@ -210,10 +214,21 @@ where
         let this = self.get_mut();
 
         loop {
-            if this.pending_headers.is_empty() {
+            if this.pending_headers.is_empty() && this.validating.is_none() {
                 return Poll::Ready(Ok(std::mem::take(&mut this.buffer)))
             }
 
+            // Wait for the validation of the last response before anything else is buffered
+            if let Some(validating) = this.validating.as_mut() {
+                let response = ready!(validating.poll_unpin(cx));
+                this.validating = None;
+                match response {
+                    Ok(response) => this.on_validated_response(response),
+                    Err(error) => return Poll::Ready(Err(error)),
+                }
+                continue
+            }
+
             // Check if there is a pending requests. It might not exist if all
             // headers are empty and there is nothing to download.
             if let Some(fut) = this.fut.as_mut() {
@@ -234,8 +249,10 @@ where
                 }
             }
 
-            // Buffer any empty headers
-            while this.pending_headers.front().map(|h| h.is_empty()).unwrap_or_default() {
+            // Buffer any empty headers, unless they follow a response that is being validated
+            while this.validating.is_none() &&
+                this.pending_headers.front().map(|h| h.is_empty()).unwrap_or_default()
+            {
                 let header = this.pending_headers.pop_front().unwrap();
                 this.buffer.push(BlockResponse::Empty(header));
             }
@@ -257,6 +274,10 @@ mod tests {
     use reth_primitives::B256;
     use std::sync::Arc;
 
+    fn validation_pool() -> BodiesValidationPool {
+        BodiesValidationPool::new(2, 10, BodyDownloaderMetrics::default()).unwrap()
+    }
+
     /// Check if future returns empty bodies without dispathing any requests.
     #[tokio::test]
     async fn request_returns_empty_bodies() {
@@ -267,6 +288,8 @@ mod tests {
         let fut = BodiesRequestFuture::new(
             client.clone(),
             Arc::new(TestConsensus::default()),
+            validation_pool(),
+            0,
             BodyDownloaderMetrics::default(),
         )
         .with_headers(headers.clone());
@@ -291,6 +314,8 @@ mod tests {
         let fut = BodiesRequestFuture::new(
             client.clone(),
             Arc::new(TestConsensus::default()),
+            validation_pool(),
+            0,
             BodyDownloaderMetrics::default(),
         )
         .with_headers(headers.clone());
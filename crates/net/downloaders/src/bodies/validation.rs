@@ -0,0 +1,169 @@
+use crate::metrics::BodyDownloaderMetrics;
+use futures::Future;
+use reth_interfaces::{
+    consensus::Consensus,
+    p2p::{
+        bodies::response::BlockResponse,
+        error::{DownloadError, RequestError},
+    },
+};
+use reth_primitives::{BlockBody, PeerId, SealedBlock, SealedHeader};
+use std::{
+    mem,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+    time::Instant,
+};
+use tokio::sync::oneshot;
+
+/// Validates block bodies responses on a dedicated thread pool instead of the task that polls the
+/// downloader.
+///
+/// Matching a body to its header hashes the transactions, ommers and withdrawals of the body, which
+/// becomes the bottleneck of the downloader task at high download rates.
+#[derive(Debug, Clone)]
+pub(crate) struct BodiesValidationPool {
+    pool: Arc<rayon::ThreadPool>,
+    /// The number of responses that are queued for or in validation.
+    pending: Arc<AtomicUsize>,
+    /// The number of pending responses at which the downloader stops issuing new requests.
+    max_pending: usize,
+    metrics: BodyDownloaderMetrics,
+}
+
+impl BodiesValidationPool {
+    /// Creates a new pool with the given number of threads, or one thread per CPU if zero.
+    pub(crate) fn new(
+        parallelism: usize,
+        max_pending: usize,
+        metrics: BodyDownloaderMetrics,
+    ) -> Result<Self, rayon::ThreadPoolBuildError> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(parallelism)
+            .thread_name(|idx| format!("bodies-validation-{idx}"))
+            .build()?;
+        Ok(Self { pool: Arc::new(pool), pending: Default::default(), max_pending, metrics })
+    }
+
+    /// Returns `true` if the number of pending responses is below the configured maximum.
+    pub(crate) fn has_capacity(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) < self.max_pending
+    }
+
+    /// Matches the bodies to the headers and validates the resulting blocks on the pool.
+    ///
+    /// The headers are expected to contain exactly one non-empty header per body, in the order
+    /// of the bodies.
+    pub(crate) fn spawn(&self, request: ValidationRequest) -> ValidationHandle {
+        let (tx, rx) = oneshot::channel();
+        let pending = Arc::clone(&self.pending);
+        let metrics = self.metrics.clone();
+
+        let num_pending = pending.fetch_add(1, Ordering::Relaxed) + 1;
+        metrics.pending_validations.set(num_pending as f64);
+
+        self.pool.spawn(move || {
+            let started_at = Instant::now();
+            let response = request.validate();
+            metrics.validation_duration_seconds.record(started_at.elapsed());
+
+            let num_pending = pending.fetch_sub(1, Ordering::Relaxed) - 1;
+            metrics.pending_validations.set(num_pending as f64);
+            let _ = tx.send(response);
+        });
+
+        ValidationHandle { rx }
+    }
+}
+
+/// A bodies response to validate, see [BodiesValidationPool::spawn].
+#[derive(Debug)]
+pub(crate) struct ValidationRequest {
+    /// The id of the request the bodies were received for.
+    pub(crate) request_id: u64,
+    /// The peer that sent the bodies.
+    pub(crate) peer_id: PeerId,
+    /// The headers the bodies belong to, including any empty headers in between.
+    pub(crate) headers: Vec<SealedHeader>,
+    /// The received bodies.
+    pub(crate) bodies: Vec<BlockBody>,
+    /// The consensus to validate the blocks with.
+    pub(crate) consensus: Arc<dyn Consensus>,
+}
+
+impl ValidationRequest {
+    fn validate(self) -> ValidatedResponse {
+        let Self { request_id, peer_id, headers, bodies, consensus } = self;
+
+        let mut size = bodies.capacity() * mem::size_of::<BlockBody>();
+        let mut blocks = Vec::with_capacity(headers.len());
+        let mut headers = headers.into_iter();
+        let mut bodies = bodies.into_iter();
+        while let Some(header) = headers.next() {
+            if header.is_empty() {
+                size += mem::size_of::<BlockBody>();
+                blocks.push(BlockResponse::Empty(header));
+                continue
+            }
+
+            let body = bodies.next().expect("one body per non-empty header");
+            size += body.size();
+
+            let block = SealedBlock::new(header, body);
+            if let Err(error) = consensus.validate_block(&block) {
+                // Body is invalid, return its header and all following headers
+                let hash = block.hash();
+                let unprocessed = std::iter::once(block.header).chain(headers).collect();
+                return ValidatedResponse {
+                    request_id,
+                    peer_id,
+                    blocks,
+                    error: Some((DownloadError::BodyValidation { hash, error }, unprocessed)),
+                    size,
+                }
+            }
+
+            blocks.push(BlockResponse::Full(block));
+        }
+
+        ValidatedResponse { request_id, peer_id, blocks, error: None, size }
+    }
+}
+
+/// The outcome of a [ValidationRequest].
+#[derive(Debug)]
+pub(crate) struct ValidatedResponse {
+    /// The id of the request the bodies were received for.
+    pub(crate) request_id: u64,
+    /// The peer that sent the bodies.
+    pub(crate) peer_id: PeerId,
+    /// The blocks preceding the first invalid body, in the order of the request.
+    pub(crate) blocks: Vec<BlockResponse>,
+    /// The validation error of the first invalid body, and the headers starting with the header
+    /// of the invalid body that were not validated.
+    pub(crate) error: Option<(DownloadError, Vec<SealedHeader>)>,
+    /// The estimated size of the response in bytes.
+    pub(crate) size: usize,
+}
+
+/// Resolves with the [ValidatedResponse] of a [ValidationRequest] spawned on the
+/// [BodiesValidationPool].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub(crate) struct ValidationHandle {
+    rx: oneshot::Receiver<ValidatedResponse>,
+}
+
+impl Future for ValidationHandle {
+    type Output = Result<ValidatedResponse, DownloadError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // the sender is only dropped if the validation did not complete
+        let response = ready!(Pin::new(&mut self.rx).poll(cx));
+        Poll::Ready(response.map_err(|_| RequestError::ChannelClosed.into()))
+    }
+}
@@ -1,6 +1,6 @@
 use reth_interfaces::p2p::error::DownloadError;
 use reth_metrics::{
-    metrics::{Counter, Gauge},
+    metrics::{Counter, Gauge, Histogram},
     Metrics,
 };
 
@@ -37,6 +37,10 @@ pub struct BodyDownloaderMetrics {
     pub buffered_blocks_size_bytes: Gauge,
     /// The number blocks that are contiguous and are queued for insertion into the db.
     pub queued_blocks: Gauge,
+    /// The number of responses that are queued for or in validation.
+    pub pending_validations: Gauge,
+    /// The time it took to validate a response, i.e. to match the bodies to the headers.
+    pub validation_duration_seconds: Histogram,
     /// The number of out-of-order requests sent by the downloader.
     /// The consumer of the download stream is able to re-request data (bodies) in case
     /// it encountered a recoverable error (e.g. during insertion).
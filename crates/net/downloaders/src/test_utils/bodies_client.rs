@@ -3,12 +3,12 @@ use reth_interfaces::p2p::{
     download::DownloadClient,
     priority::Priority,
 };
-use reth_primitives::{BlockBody, PeerId, B256};
+use reth_primitives::{BlockBody, Header, PeerId, B256};
 use std::{
     collections::HashMap,
     fmt::Debug,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
     time::Duration,
@@ -22,6 +22,12 @@ pub struct TestBodiesClient {
     should_delay: bool,
     max_batch_size: Option<usize>,
     times_requested: AtomicU64,
+    /// The peers that respond to the requests in turn.
+    peers: Vec<PeerId>,
+    /// The peer that responds with an invalid body once.
+    invalid_response_from: Option<PeerId>,
+    sent_invalid_response: AtomicBool,
+    reported_peers: std::sync::Mutex<Vec<PeerId>>,
 }
 
 impl TestBodiesClient {
@@ -40,14 +46,32 @@ impl TestBodiesClient {
         self
     }
 
+    /// Responds to the requests from the given peers in turn.
+    pub(crate) fn with_peers(mut self, peers: Vec<PeerId>) -> Self {
+        self.peers = peers;
+        self
+    }
+
+    /// Responds once with an invalid body from the given peer. The body has an additional ommer,
+    /// which does not match the ommers hash of its header.
+    pub(crate) fn with_invalid_response_from(mut self, peer_id: PeerId) -> Self {
+        self.invalid_response_from = Some(peer_id);
+        self
+    }
+
     pub(crate) fn times_requested(&self) -> u64 {
         self.times_requested.load(Ordering::Relaxed)
     }
+
+    /// Returns the peers that were reported for bad messages, in order.
+    pub(crate) fn reported_peers(&self) -> Vec<PeerId> {
+        self.reported_peers.lock().unwrap().clone()
+    }
 }
 
 impl DownloadClient for TestBodiesClient {
-    fn report_bad_message(&self, _peer_id: PeerId) {
-        // noop
+    fn report_bad_message(&self, peer_id: PeerId) {
+        self.reported_peers.lock().unwrap().push(peer_id);
     }
 
     fn num_connected_peers(&self) -> usize {
@@ -67,7 +91,14 @@ impl BodiesClient for TestBodiesClient {
         let bodies = self.bodies.clone();
         let max_batch_size = self.max_batch_size;
 
-        self.times_requested.fetch_add(1, Ordering::Relaxed);
+        let request = self.times_requested.fetch_add(1, Ordering::Relaxed);
+        let peer_id = if self.peers.is_empty() {
+            PeerId::default()
+        } else {
+            self.peers[request as usize % self.peers.len()]
+        };
+        let invalid = self.invalid_response_from == Some(peer_id) &&
+            !self.sent_invalid_response.swap(true, Ordering::Relaxed);
 
         Box::pin(async move {
             if should_delay {
@@ -75,8 +106,19 @@ impl BodiesClient for TestBodiesClient {
             }
 
             let bodies = &mut *bodies.lock().await;
+            if invalid {
+                // the bodies are requested again after the invalid response
+                let mut response = hashes
+                    .into_iter()
+                    .take(max_batch_size.unwrap_or(usize::MAX))
+                    .map(|hash| bodies.get(&hash).cloned().expect("body exists"))
+                    .collect::<Vec<_>>();
+                response[0].ommers.push(Header::default());
+                return Ok((peer_id, response).into())
+            }
+
             Ok((
-                PeerId::default(),
+                peer_id,
                 hashes
                     .into_iter()
                     .take(max_batch_size.unwrap_or(usize::MAX))
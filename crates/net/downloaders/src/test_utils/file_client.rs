@@ -115,8 +115,10 @@ impl FileClient {
     }
 
     /// Get the tip hash of the chain.
+    ///
+    /// This is the hash of the highest block, the file does not need to start at block 1.
     pub fn tip(&self) -> Option<B256> {
-        self.headers.get(&(self.headers.len() as u64)).map(|h| h.hash_slow())
+        self.max_block().and_then(|number| self.headers.get(&number)).map(|h| h.hash_slow())
     }
 
     /// Returns the highest block number of this client has or `None` if empty
@@ -17,7 +17,7 @@
 
 use async_trait::async_trait;
 use reth_eth_wire::{DisconnectReason, EthVersion, Status};
-use reth_primitives::{NodeRecord, PeerId};
+use reth_primitives::{stage::StageId, BlockNumber, NodeRecord, PeerId};
 use reth_rpc_types::NetworkStatus;
 use std::{net::SocketAddr, sync::Arc};
 
@@ -50,6 +50,39 @@ pub trait NetworkInfo: Send + Sync {
 
     /// Returns `true` when the node is undergoing the very first Pipeline sync.
     fn is_initially_syncing(&self) -> bool;
+
+    /// Returns the current [SyncProgress] of the node.
+    fn sync_progress(&self) -> SyncProgress;
+}
+
+/// The maximum number of blocks a live syncing node can be behind the best peer head to be
+/// considered synced.
+pub const SYNCED_HEAD_DISTANCE: u64 = 4;
+
+/// The sync progress of the node, as tracked by the network.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncProgress {
+    /// Whether the node is live syncing, i.e. the pipeline is idle and the node follows the chain
+    /// by processing new blocks as they arrive.
+    pub is_live: bool,
+    /// The block up to which all stages of the pipeline committed their progress, or the
+    /// canonical head if it is ahead of that.
+    pub current_block: BlockNumber,
+    /// The best head advertised by the connected peers, if known.
+    pub highest_block: Option<BlockNumber>,
+    /// The committed checkpoint of every stage of the pipeline.
+    pub stages: Vec<(StageId, BlockNumber)>,
+}
+
+impl SyncProgress {
+    /// Returns `true` if the node is live syncing and at most [SYNCED_HEAD_DISTANCE] blocks behind
+    /// the best peer head.
+    pub fn is_synced(&self) -> bool {
+        self.is_live &&
+            self.highest_block.map_or(true, |highest| {
+                highest.saturating_sub(self.current_block) <= SYNCED_HEAD_DISTANCE
+            })
+    }
 }
 
 /// Provides general purpose information about Peers in the network.
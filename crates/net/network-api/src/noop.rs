@@ -5,7 +5,7 @@
 
 use crate::{
     NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
-    ReputationChangeKind,
+    ReputationChangeKind, SyncProgress,
 };
 use async_trait::async_trait;
 use reth_discv4::DEFAULT_DISCOVERY_PORT;
@@ -51,6 +51,10 @@ impl NetworkInfo for NoopNetwork {
     fn is_initially_syncing(&self) -> bool {
         false
     }
+
+    fn sync_progress(&self) -> SyncProgress {
+        SyncProgress { is_live: true, ..Default::default() }
+    }
 }
 
 impl PeersInfo for NoopNetwork {
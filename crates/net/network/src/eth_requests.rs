@@ -1,20 +1,24 @@
 //! Blocks/Headers management for the p2p network.
 
 use crate::{metrics::EthRequestHandlerMetrics, peers::PeersHandle};
+use alloy_rlp::Encodable;
 use futures::StreamExt;
 use reth_eth_wire::{
     BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, GetNodeData, GetReceipts, NodeData,
     Receipts,
 };
 use reth_interfaces::p2p::error::RequestResult;
+use reth_network_api::ReputationChangeKind;
 use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId};
 use reth_provider::{BlockReader, HeaderProvider, ReceiptProvider};
 use std::{
     borrow::Borrow,
+    collections::HashMap,
     future::Future,
     hash::Hash,
     pin::Pin,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc::Receiver, oneshot};
 use tokio_stream::wrappers::ReceiverStream;
@@ -37,9 +41,6 @@ const MAX_HEADERS_SERVE: usize = 1024;
 /// SOFT_RESPONSE_LIMIT.
 const MAX_BODIES_SERVE: usize = 1024;
 
-/// Estimated size in bytes of an RLP encoded receipt.
-const APPROX_RECEIPT_SIZE: usize = 24 * 1024;
-
 /// Estimated size in bytes of an RLP encoded body.
 // TODO: check 24kb blocksize assumption
 const APPROX_BODY_SIZE: usize = 24 * 1024;
@@ -50,6 +51,18 @@ const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
 /// Estimated size in bytes of an RLP encoded header.
 const APPROX_HEADER_SIZE: usize = 500;
 
+/// Maximum number of requests a peer can send within [`PEER_REQUEST_INTERVAL`].
+///
+/// Requests exceeding the limit are answered with empty responses.
+const MAX_PEER_REQUESTS_PER_INTERVAL: u32 = 500;
+
+/// The interval over which the requests of a peer are counted.
+const PEER_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of tracked peers after which peers without requests in the current interval are
+/// dropped.
+const MAX_TRACKED_PEERS: usize = 1024;
+
 /// Manages eth related requests on top of the p2p network.
 ///
 /// This can be spawned to another task and is supposed to be run as background service.
@@ -58,10 +71,10 @@ const APPROX_HEADER_SIZE: usize = 500;
 pub struct EthRequestHandler<C> {
     /// The client type that can interact with the chain.
     client: C,
-    /// Used for reporting peers.
-    #[allow(unused)]
-    // TODO use to report spammers
+    /// Used for reporting peers that exceed their request limit.
     peers: PeersHandle,
+    /// The number of requests of each peer in its current interval.
+    peer_requests: HashMap<PeerId, PeerRequestCount>,
     /// Incoming request from the [NetworkManager](crate::NetworkManager).
     incoming_requests: ReceiverStream<IncomingEthRequest>,
     /// Metrics for the eth request handler.
@@ -73,7 +86,42 @@ impl<C> EthRequestHandler<C> {
     /// Create a new instance
     pub fn new(client: C, peers: PeersHandle, incoming: Receiver<IncomingEthRequest>) -> Self {
         let metrics = Default::default();
-        Self { client, peers, incoming_requests: ReceiverStream::new(incoming), metrics }
+        Self {
+            client,
+            peers,
+            peer_requests: Default::default(),
+            incoming_requests: ReceiverStream::new(incoming),
+            metrics,
+        }
+    }
+
+    /// Records a request of the peer and returns `true` if the peer exceeded its request limit.
+    ///
+    /// A peer is reported once per interval in which it exceeds the limit.
+    fn is_rate_limited(&mut self, peer_id: PeerId, now: Instant) -> bool {
+        if self.peer_requests.len() >= MAX_TRACKED_PEERS &&
+            !self.peer_requests.contains_key(&peer_id)
+        {
+            self.peer_requests.retain(|_, count| !count.is_expired(now));
+        }
+
+        let count = self
+            .peer_requests
+            .entry(peer_id)
+            .or_insert(PeerRequestCount { interval_start: now, requests: 0 });
+        if count.is_expired(now) {
+            *count = PeerRequestCount { interval_start: now, requests: 0 };
+        }
+        count.requests += 1;
+
+        if count.requests <= MAX_PEER_REQUESTS_PER_INTERVAL {
+            return false
+        }
+        if count.requests == MAX_PEER_REQUESTS_PER_INTERVAL + 1 {
+            self.peers.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+        }
+        self.metrics.rate_limited_requests.increment(1);
+        true
     }
 }
 
@@ -195,41 +243,54 @@ where
         let _ = response.send(Ok(BlockBodies(bodies)));
     }
 
+    fn on_node_data_request(
+        &mut self,
+        _peer_id: PeerId,
+        _request: GetNodeData,
+        response: oneshot::Sender<RequestResult<NodeData>>,
+    ) {
+        self.metrics.received_node_data_requests.increment(1);
+        // state is not stored by hash, so it can't be served, this matches peers that dropped
+        // support for `GetNodeData`, which was removed in eth/67.
+        let _ = response.send(Ok(NodeData(Vec::new())));
+    }
+
     fn on_receipts_request(
         &mut self,
         _peer_id: PeerId,
         request: GetReceipts,
         response: oneshot::Sender<RequestResult<Receipts>>,
     ) {
+        self.metrics.received_receipts_requests.increment(1);
         let mut receipts = Vec::new();
 
-        let mut total_bytes = APPROX_RECEIPT_SIZE;
+        let mut total_bytes = 0;
 
         for hash in request.0 {
-            if let Some(receipts_by_block) =
-                self.client.receipts_by_block(BlockHashOrNumber::Hash(hash)).unwrap_or_default()
-            {
-                receipts.push(
-                    receipts_by_block
-                        .into_iter()
-                        .map(|receipt| receipt.with_bloom())
-                        .collect::<Vec<_>>(),
-                );
-
-                total_bytes += APPROX_RECEIPT_SIZE;
-
-                if total_bytes > SOFT_RESPONSE_LIMIT {
-                    break
-                }
+            // unknown blocks are served as empty entries, so that the receipts of the following
+            // blocks stay in position
+            let receipts_by_block = self
+                .client
+                .receipts_by_block(BlockHashOrNumber::Hash(hash))
+                .unwrap_or_default()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|receipt| receipt.with_bloom())
+                .collect::<Vec<_>>();
+
+            total_bytes += receipts_by_block.iter().map(Encodable::length).sum::<usize>();
+            receipts.push(receipts_by_block);
+
+            if total_bytes > SOFT_RESPONSE_LIMIT {
+                break
+            }
 
-                if receipts.len() >= MAX_RECEIPTS_SERVE {
-                    break
-                }
-            } else {
+            if receipts.len() >= MAX_RECEIPTS_SERVE {
                 break
             }
         }
 
+        self.metrics.served_receipts_bytes.increment(total_bytes as u64);
         let _ = response.send(Ok(Receipts(receipts)));
     }
 }
@@ -250,23 +311,47 @@ where
             match this.incoming_requests.poll_next_unpin(cx) {
                 Poll::Pending => return Poll::Pending,
                 Poll::Ready(None) => return Poll::Ready(()),
-                Poll::Ready(Some(incoming)) => match incoming {
-                    IncomingEthRequest::GetBlockHeaders { peer_id, request, response } => {
-                        this.on_headers_request(peer_id, request, response)
+                Poll::Ready(Some(incoming)) => {
+                    if this.is_rate_limited(incoming.peer_id(), Instant::now()) {
+                        incoming.send_empty_response();
+                        continue
                     }
-                    IncomingEthRequest::GetBlockBodies { peer_id, request, response } => {
-                        this.on_bodies_request(peer_id, request, response)
-                    }
-                    IncomingEthRequest::GetNodeData { .. } => {}
-                    IncomingEthRequest::GetReceipts { peer_id, request, response } => {
-                        this.on_receipts_request(peer_id, request, response)
+
+                    match incoming {
+                        IncomingEthRequest::GetBlockHeaders { peer_id, request, response } => {
+                            this.on_headers_request(peer_id, request, response)
+                        }
+                        IncomingEthRequest::GetBlockBodies { peer_id, request, response } => {
+                            this.on_bodies_request(peer_id, request, response)
+                        }
+                        IncomingEthRequest::GetNodeData { peer_id, request, response } => {
+                            this.on_node_data_request(peer_id, request, response)
+                        }
+                        IncomingEthRequest::GetReceipts { peer_id, request, response } => {
+                            this.on_receipts_request(peer_id, request, response)
+                        }
                     }
-                },
+                }
             }
         }
     }
 }
 
+/// The number of requests of a peer within an interval.
+#[derive(Debug)]
+struct PeerRequestCount {
+    /// When the interval started
+    interval_start: Instant,
+    /// The number of requests in the interval
+    requests: u32,
+}
+
+impl PeerRequestCount {
+    fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.interval_start) >= PEER_REQUEST_INTERVAL
+    }
+}
+
 /// Represents a handled [`GetBlockHeaders`] requests
 ///
 /// This is the key type for spam detection cache. The counter is ignored during `PartialEq` and
@@ -320,3 +405,62 @@ pub enum IncomingEthRequest {
         response: oneshot::Sender<RequestResult<Receipts>>,
     },
 }
+
+// === impl IncomingEthRequest ===
+
+impl IncomingEthRequest {
+    /// Returns the peer that sent the request.
+    pub fn peer_id(&self) -> PeerId {
+        match self {
+            IncomingEthRequest::GetBlockHeaders { peer_id, .. } |
+            IncomingEthRequest::GetBlockBodies { peer_id, .. } |
+            IncomingEthRequest::GetNodeData { peer_id, .. } |
+            IncomingEthRequest::GetReceipts { peer_id, .. } => *peer_id,
+        }
+    }
+
+    /// Answers the request without any data.
+    fn send_empty_response(self) {
+        match self {
+            IncomingEthRequest::GetBlockHeaders { response, .. } => {
+                let _ = response.send(Ok(BlockHeaders(Vec::new())));
+            }
+            IncomingEthRequest::GetBlockBodies { response, .. } => {
+                let _ = response.send(Ok(BlockBodies(Vec::new())));
+            }
+            IncomingEthRequest::GetNodeData { response, .. } => {
+                let _ = response.send(Ok(NodeData(Vec::new())));
+            }
+            IncomingEthRequest::GetReceipts { response, .. } => {
+                let _ = response.send(Ok(Receipts(Vec::new())));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::PeersManager;
+    use reth_provider::test_utils::NoopProvider;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn rate_limits_peers() {
+        let (_tx, rx) = mpsc::channel(1);
+        let peers = PeersManager::default();
+        let mut handler = EthRequestHandler::new(NoopProvider::default(), peers.handle(), rx);
+        let spammer = PeerId::random();
+        let other = PeerId::random();
+        let now = Instant::now();
+
+        for _ in 0..MAX_PEER_REQUESTS_PER_INTERVAL {
+            assert!(!handler.is_rate_limited(spammer, now));
+        }
+        assert!(handler.is_rate_limited(spammer, now));
+        assert!(!handler.is_rate_limited(other, now));
+
+        // the limit is reset in the next interval
+        assert!(!handler.is_rate_limited(spammer, now + PEER_REQUEST_INTERVAL));
+    }
+}
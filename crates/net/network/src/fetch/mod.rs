@@ -111,6 +111,11 @@ impl StateFetcher {
         false
     }
 
+    /// Returns the best block number of every connected peer.
+    pub(crate) fn peer_best_numbers(&self) -> impl Iterator<Item = u64> + '_ {
+        self.peers.values().map(|peer| peer.best_number)
+    }
+
     /// Invoked when an active session is about to be disconnected.
     pub(crate) fn on_pending_disconnect(&mut self, peer_id: &PeerId) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
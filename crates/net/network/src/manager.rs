@@ -207,6 +207,7 @@ where
         let local_peer_id = discovery.local_id();
 
         let num_active_peers = Arc::new(AtomicUsize::new(0));
+        let best_peer_head = Arc::new(AtomicU64::new(0));
         let bandwidth_meter: BandwidthMeter = BandwidthMeter::default();
 
         let sessions = SessionManager::new(
@@ -225,6 +226,7 @@ where
             peers_manager,
             chain_spec.genesis_hash(),
             Arc::clone(&num_active_peers),
+            Arc::clone(&best_peer_head),
         );
 
         let swarm = Swarm::new(incoming, sessions, state, NetworkConnectionState::default());
@@ -240,6 +242,7 @@ where
             network_mode,
             bandwidth_meter,
             Arc::new(AtomicU64::new(chain_spec.chain.id())),
+            best_peer_head,
         );
 
         Ok(Self {
@@ -482,7 +485,11 @@ where
             }
             PeerMessage::NewBlock(block) => {
                 self.within_pow_or_disconnect(peer_id, move |this| {
-                    this.swarm.state_mut().on_new_block(peer_id, block.hash);
+                    this.swarm.state_mut().on_new_block(
+                        peer_id,
+                        block.hash,
+                        block.block.block.header.number,
+                    );
                     // start block import process
                     this.block_import.on_new_block(peer_id, block);
                 });
@@ -151,4 +151,16 @@ pub struct EthRequestHandlerMetrics {
 
     /// Number of received bodies requests
     pub(crate) received_bodies_requests: Counter,
+
+    /// Number of received receipts requests
+    pub(crate) received_receipts_requests: Counter,
+
+    /// Number of received node data requests
+    pub(crate) received_node_data_requests: Counter,
+
+    /// Number of requests that were not served because the peer exceeded its request limit
+    pub(crate) rate_limited_requests: Counter,
+
+    /// Number of served bytes of RLP encoded receipts
+    pub(crate) served_receipts_bytes: Counter,
 }
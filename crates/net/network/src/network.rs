@@ -9,9 +9,11 @@ use reth_interfaces::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_net_common::bandwidth_meter::BandwidthMeter;
 use reth_network_api::{
     NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
-    ReputationChangeKind,
+    ReputationChangeKind, SyncProgress,
+};
+use reth_primitives::{
+    stage::StageId, BlockNumber, Head, NodeRecord, PeerId, TransactionSigned, B256,
 };
-use reth_primitives::{Head, NodeRecord, PeerId, TransactionSigned, B256};
 use reth_rpc_types::NetworkStatus;
 use std::{
     net::SocketAddr,
@@ -46,6 +48,7 @@ impl NetworkHandle {
         network_mode: NetworkMode,
         bandwidth_meter: BandwidthMeter,
         chain_id: Arc<AtomicU64>,
+        best_peer_head: Arc<AtomicU64>,
     ) -> Self {
         let inner = NetworkInner {
             num_active_peers,
@@ -58,6 +61,9 @@ impl NetworkHandle {
             is_syncing: Arc::new(AtomicBool::new(false)),
             initial_sync_done: Arc::new(AtomicBool::new(false)),
             chain_id,
+            best_peer_head,
+            head_number: AtomicU64::new(0),
+            stage_checkpoints: Default::default(),
         };
         Self { inner: Arc::new(inner) }
     }
@@ -264,6 +270,18 @@ impl NetworkInfo for NetworkHandle {
     fn is_initially_syncing(&self) -> bool {
         SyncStateProvider::is_initially_syncing(self)
     }
+
+    fn sync_progress(&self) -> SyncProgress {
+        let stages = self.inner.stage_checkpoints.lock().clone();
+        let pipeline_block = stages.iter().map(|(_, block)| *block).min().unwrap_or_default();
+        let highest_block = self.inner.best_peer_head.load(Ordering::Relaxed);
+        SyncProgress {
+            is_live: !SyncStateProvider::is_syncing(self),
+            current_block: pipeline_block.max(self.inner.head_number.load(Ordering::Relaxed)),
+            highest_block: (highest_block > 0).then_some(highest_block),
+            stages,
+        }
+    }
 }
 
 impl SyncStateProvider for NetworkHandle {
@@ -291,8 +309,13 @@ impl NetworkSyncUpdater for NetworkHandle {
 
     /// Update the status of the node.
     fn update_status(&self, head: Head) {
+        self.inner.head_number.store(head.number, Ordering::Relaxed);
         self.send_message(NetworkHandleMessage::StatusUpdate { head });
     }
+
+    fn update_stage_checkpoints(&self, checkpoints: Vec<(StageId, BlockNumber)>) {
+        *self.inner.stage_checkpoints.lock() = checkpoints;
+    }
 }
 
 #[derive(Debug)]
@@ -317,6 +340,12 @@ struct NetworkInner {
     initial_sync_done: Arc<AtomicBool>,
     /// The chain id
     chain_id: Arc<AtomicU64>,
+    /// The best head advertised by the connected peers, `0` if unknown.
+    best_peer_head: Arc<AtomicU64>,
+    /// The number of the canonical head the node's status was last updated with.
+    head_number: AtomicU64,
+    /// The committed checkpoints of all stages of the pipeline.
+    stage_checkpoints: Mutex<Vec<(StageId, BlockNumber)>>,
 }
 
 /// Internal messages that can be passed to the  [`NetworkManager`](crate::NetworkManager).
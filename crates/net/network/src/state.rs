@@ -23,7 +23,7 @@ use std::{
     net::{IpAddr, SocketAddr},
     num::NonZeroUsize,
     sync::{
-        atomic::{AtomicU64, AtomicUsize},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     task::{Context, Poll},
@@ -34,6 +34,10 @@ use tracing::debug;
 /// Cache limit of blocks to keep track of for a single peer.
 const PEER_BLOCK_CACHE_LIMIT: usize = 512;
 
+/// The number of peers that must advertise a head at or above a block for it to be considered the
+/// best peer head, so a single peer can't claim an arbitrarily high head.
+const BEST_PEER_HEAD_QUORUM: usize = 2;
+
 /// The [`NetworkState`] keeps track of the state of all peers in the network.
 ///
 /// This includes:
@@ -66,6 +70,10 @@ pub struct NetworkState<C> {
     /// The fetcher streams RLPx related requests on a per-peer basis to this type. This type will
     /// then queue in the request and notify the fetcher once the result has been received.
     state_fetcher: StateFetcher,
+    /// The best head advertised by the connected peers, `0` if unknown.
+    ///
+    /// This is shared with the [NetworkHandle](crate::NetworkHandle).
+    best_peer_head: Arc<AtomicU64>,
 }
 
 impl<C> NetworkState<C>
@@ -79,6 +87,7 @@ where
         peers_manager: PeersManager,
         genesis_hash: B256,
         num_active_peers: Arc<AtomicUsize>,
+        best_peer_head: Arc<AtomicU64>,
     ) -> Self {
         let state_fetcher = StateFetcher::new(peers_manager.handle(), num_active_peers);
         Self {
@@ -89,6 +98,7 @@ where
             discovery,
             genesis_hash,
             state_fetcher,
+            best_peer_head,
         }
     }
 
@@ -122,6 +132,17 @@ where
         self.active_peers.len()
     }
 
+    /// Returns the best head advertised by the connected peers, if known.
+    pub fn best_peer_head(&self) -> Option<u64> {
+        Some(self.best_peer_head.load(Ordering::Relaxed)).filter(|head| *head > 0)
+    }
+
+    /// Recomputes the best peer head after the head of a peer changed.
+    fn update_best_peer_head(&self) {
+        let head = best_peer_head(self.state_fetcher.peer_best_numbers());
+        self.best_peer_head.store(head, Ordering::Relaxed);
+    }
+
     /// Event hook for an activated session for the peer.
     ///
     /// Returns `Ok` if the session is valid, returns an `Err` if the session is not accepted and
@@ -151,6 +172,7 @@ where
                 blocks: LruCache::new(NonZeroUsize::new(PEER_BLOCK_CACHE_LIMIT).unwrap()),
             },
         );
+        self.update_best_peer_head();
     }
 
     /// Event hook for a disconnected session for the given peer.
@@ -159,6 +181,7 @@ where
     pub(crate) fn on_session_closed(&mut self, peer: PeerId) {
         self.active_peers.remove(&peer);
         self.state_fetcher.on_session_closed(&peer);
+        self.update_best_peer_head();
     }
 
     /// Starts propagating the new block to peers that haven't reported the block yet.
@@ -202,6 +225,7 @@ where
                 break
             }
         }
+        self.update_best_peer_head();
     }
 
     /// Completes the block propagation process started in [`NetworkState::announce_new_block()`]
@@ -224,6 +248,7 @@ where
                 hashes: hashes.clone(),
             });
         }
+        self.update_best_peer_head();
     }
 
     /// Updates the block information for the peer.
@@ -232,6 +257,7 @@ where
             peer.best_hash = hash;
         }
         self.state_fetcher.update_peer_block(peer_id, hash, number);
+        self.update_best_peer_head();
     }
 
     /// Invoked when a new [`ForkId`] is activated.
@@ -242,19 +268,29 @@ where
     /// Invoked after a `NewBlock` message was received by the peer.
     ///
     /// This will keep track of blocks we know a peer has
-    pub(crate) fn on_new_block(&mut self, peer_id: PeerId, hash: B256) {
+    pub(crate) fn on_new_block(&mut self, peer_id: PeerId, hash: B256, number: u64) {
         // Mark the blocks as seen
         if let Some(peer) = self.active_peers.get_mut(&peer_id) {
             peer.blocks.insert(hash);
+            if self.state_fetcher.update_peer_block(&peer_id, hash, number) {
+                peer.best_hash = hash;
+            }
         }
+        self.update_best_peer_head();
     }
 
     /// Invoked for a `NewBlockHashes` broadcast message.
     pub(crate) fn on_new_block_hashes(&mut self, peer_id: PeerId, hashes: Vec<BlockHashNumber>) {
         // Mark the blocks as seen
         if let Some(peer) = self.active_peers.get_mut(&peer_id) {
+            if let Some(best) = hashes.iter().max_by_key(|b| b.number) {
+                if self.state_fetcher.update_peer_block(&peer_id, best.hash, best.number) {
+                    peer.best_hash = best.hash;
+                }
+            }
             peer.blocks.extend(hashes.into_iter().map(|b| b.hash));
         }
+        self.update_best_peer_head();
     }
 
     /// Bans the [`IpAddr`] in the discovery service.
@@ -519,18 +555,36 @@ pub(crate) enum StateAction {
     PeerRemoved(PeerId),
 }
 
+/// Returns the highest block that at least [BEST_PEER_HEAD_QUORUM] peers advertise a head at or
+/// above, or the head of the only peer if there is a single one. Peers with an unknown head are
+/// ignored.
+///
+/// Returns `0` if no peer advertised a head.
+fn best_peer_head(heads: impl IntoIterator<Item = u64>) -> u64 {
+    let mut heads = heads.into_iter().filter(|head| *head > 0).collect::<Vec<_>>();
+    heads.sort_unstable_by(|a, b| b.cmp(a));
+    let quorum = BEST_PEER_HEAD_QUORUM.min(heads.len());
+    quorum.checked_sub(1).and_then(|index| heads.get(index).copied()).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        discovery::Discovery, fetch::StateFetcher, message::PeerRequestSender, peers::PeersManager,
-        state::NetworkState, PeerRequest,
+        config::NetworkMode, discovery::Discovery, fetch::StateFetcher, message::PeerRequestSender,
+        peers::PeersManager, state::NetworkState, NetworkHandle, PeerRequest,
     };
+    use parking_lot::Mutex;
     use reth_eth_wire::{
         capability::{Capabilities, Capability},
-        BlockBodies, EthVersion, Status,
+        BlockBodies, BlockHashNumber, EthVersion, Status,
+    };
+    use reth_interfaces::{
+        p2p::{bodies::client::BodiesClient, error::RequestError},
+        sync::{NetworkSyncUpdater, SyncState},
     };
-    use reth_interfaces::p2p::{bodies::client::BodiesClient, error::RequestError};
-    use reth_primitives::{BlockBody, Header, PeerId, B256};
+    use reth_net_common::bandwidth_meter::BandwidthMeter;
+    use reth_network_api::NetworkInfo;
+    use reth_primitives::{stage::StageId, BlockBody, Head, Header, PeerId, B256};
     use reth_provider::test_utils::NoopProvider;
     use std::{
         future::poll_fn,
@@ -551,6 +605,7 @@ mod tests {
             discovery: Discovery::noop(),
             genesis_hash: Default::default(),
             state_fetcher: StateFetcher::new(handle, Default::default()),
+            best_peer_head: Default::default(),
         }
     }
 
@@ -558,6 +613,113 @@ mod tests {
         Arc::new(vec![Capability::from(EthVersion::Eth67)].into())
     }
 
+    /// Returns a handle that shares the best peer head with the given state.
+    fn handle(state: &NetworkState<NoopProvider>) -> NetworkHandle {
+        let (to_manager_tx, _) = mpsc::unbounded_channel();
+        NetworkHandle::new(
+            Default::default(),
+            Arc::new(Mutex::new("127.0.0.1:30303".parse().unwrap())),
+            to_manager_tx,
+            PeerId::random(),
+            PeersManager::default().handle(),
+            NetworkMode::Stake,
+            BandwidthMeter::default(),
+            Default::default(),
+            Arc::clone(&state.best_peer_head),
+        )
+    }
+
+    /// Activates a session for a new peer with an unknown head.
+    fn connect_peer(state: &mut NetworkState<NoopProvider>) -> PeerId {
+        let peer_id = PeerId::random();
+        let (tx, _) = mpsc::channel(1);
+        state.on_session_activated(
+            peer_id,
+            capabilities(),
+            Status::default(),
+            PeerRequestSender::new(peer_id, tx),
+            Arc::new(AtomicU64::new(1)),
+        );
+        peer_id
+    }
+
+    /// Announces a new head of the peer.
+    fn announce_head(state: &mut NetworkState<NoopProvider>, peer_id: PeerId, number: u64) {
+        state.on_new_block_hashes(peer_id, vec![BlockHashNumber { hash: B256::random(), number }]);
+    }
+
+    #[test]
+    fn best_peer_head_ignores_single_outlier() {
+        let mut state = state();
+        assert_eq!(state.best_peer_head(), None);
+
+        let a = connect_peer(&mut state);
+        let b = connect_peer(&mut state);
+        assert_eq!(state.best_peer_head(), None);
+
+        // a single peer is trusted
+        announce_head(&mut state, a, 100);
+        assert_eq!(state.best_peer_head(), Some(100));
+
+        announce_head(&mut state, b, 90);
+        assert_eq!(state.best_peer_head(), Some(90));
+
+        // an outlier needs to be confirmed by another peer
+        let c = connect_peer(&mut state);
+        announce_head(&mut state, c, 1_000_000);
+        assert_eq!(state.best_peer_head(), Some(100));
+
+        // heads of a peer never decrease
+        announce_head(&mut state, a, 50);
+        assert_eq!(state.best_peer_head(), Some(100));
+
+        state.on_session_closed(a);
+        assert_eq!(state.best_peer_head(), Some(90));
+    }
+
+    #[test]
+    fn sync_progress_transitions_to_synced() {
+        let mut state = state();
+        let handle = handle(&state);
+        handle.update_sync_state(SyncState::Syncing);
+
+        let a = connect_peer(&mut state);
+        let b = connect_peer(&mut state);
+        announce_head(&mut state, a, 100);
+        announce_head(&mut state, b, 100);
+
+        handle.update_stage_checkpoints(vec![(StageId::Headers, 100), (StageId::Execution, 50)]);
+        let progress = handle.sync_progress();
+        assert_eq!(progress.current_block, 50);
+        assert_eq!(progress.highest_block, Some(100));
+        assert!(!progress.is_synced());
+
+        // the pipeline caught up, but didn't finish yet
+        handle.update_stage_checkpoints(vec![(StageId::Headers, 100), (StageId::Execution, 100)]);
+        assert_eq!(handle.sync_progress().current_block, 100);
+        assert!(!handle.sync_progress().is_synced());
+
+        handle.update_sync_state(SyncState::Idle);
+        assert!(handle.sync_progress().is_synced());
+
+        // a single peer claiming a far higher head doesn't affect the sync status
+        let c = connect_peer(&mut state);
+        announce_head(&mut state, c, 1_000_000);
+        assert_eq!(handle.sync_progress().highest_block, Some(100));
+        assert!(handle.sync_progress().is_synced());
+
+        // the chain advanced by more than the synced distance
+        announce_head(&mut state, a, 110);
+        announce_head(&mut state, b, 105);
+        assert_eq!(handle.sync_progress().highest_block, Some(110));
+        assert!(!handle.sync_progress().is_synced());
+
+        // the node followed the chain
+        handle.update_status(Head { number: 108, ..Default::default() });
+        assert_eq!(handle.sync_progress().current_block, 108);
+        assert!(handle.sync_progress().is_synced());
+    }
+
     // tests that ongoing requests are answered with connection dropped if the session that received
     // that request is drops the request object.
     #[tokio::test(flavor = "multi_thread")]
@@ -1,18 +1,23 @@
 //! Tests for eth related requests
 
 use rand::Rng;
+use reth_eth_wire::{GetReceipts, Receipts};
 use reth_interfaces::p2p::{
     bodies::client::BodiesClient,
     headers::client::{HeadersClient, HeadersRequest},
 };
-use reth_network::test_utils::{NetworkEventStream, Testnet};
+use reth_network::{
+    test_utils::{NetworkEventStream, Testnet},
+    PeerRequest,
+};
 use reth_network_api::{NetworkInfo, Peers};
 use reth_primitives::{
-    Block, BlockBody, Bytes, Header, HeadersDirection, Signature, Transaction, TransactionKind,
-    TransactionSigned, TxEip2930, U256,
+    Block, BlockBody, Bytes, Header, HeadersDirection, Log, Receipt, Signature, Transaction,
+    TransactionKind, TransactionSigned, TxEip2930, TxType, B256, U256,
 };
 use reth_provider::test_utils::MockEthProvider;
 use std::sync::Arc;
+use tokio::sync::oneshot;
 
 /// Returns a new [`TransactionSigned`] with some random parameters
 pub fn rng_transaction(rng: &mut impl rand::RngCore) -> TransactionSigned {
@@ -120,3 +125,69 @@ async fn test_get_header() {
         assert_eq!(headers[0], header);
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_receipts() {
+    reth_tracing::init_test_tracing();
+    let mut rng = rand::thread_rng();
+    let mock_provider = Arc::new(MockEthProvider::default());
+
+    let mut net = Testnet::create_with(2, mock_provider.clone()).await;
+
+    // install request handlers
+    net.for_each_mut(|peer| peer.install_request_handler());
+
+    let handle0 = net.peers()[0].handle();
+    let mut events0 = NetworkEventStream::new(handle0.event_listener());
+
+    let handle1 = net.peers()[1].handle();
+
+    let _handle = net.spawn();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let connected = events0.next_session_established().await.unwrap();
+    assert_eq!(connected, *handle1.peer_id());
+
+    // store the receipts of 5 blocks
+    let mut blocks = Vec::new();
+    for _ in 0..5 {
+        let block_hash: B256 = rng.gen();
+        let receipts = (0..rng.gen_range(1..4u64))
+            .map(|idx| Receipt {
+                tx_type: TxType::EIP1559,
+                success: rng.gen(),
+                cumulative_gas_used: 21_000 * (idx + 1),
+                logs: vec![Log {
+                    address: rng.gen(),
+                    topics: vec![rng.gen()],
+                    data: Bytes::from(vec![1, 2, 3]),
+                }],
+            })
+            .collect::<Vec<_>>();
+        mock_provider.add_receipts(block_hash, receipts.clone());
+        blocks.push((block_hash, receipts));
+    }
+
+    let get_receipts = |hashes: Vec<B256>| {
+        let (tx, rx) = oneshot::channel();
+        handle0.send_request(
+            *handle1.peer_id(),
+            PeerRequest::GetReceipts { request: GetReceipts(hashes), response: tx },
+        );
+        async move { rx.await.unwrap().unwrap() }
+    };
+    let with_bloom = |receipts: &[Receipt]| {
+        receipts.iter().cloned().map(Receipt::with_bloom).collect::<Vec<_>>()
+    };
+
+    let Receipts(receipts) = get_receipts(blocks.iter().map(|(hash, _)| *hash).collect()).await;
+    assert_eq!(receipts.len(), blocks.len());
+    for (receipts, (_, expected)) in receipts.iter().zip(&blocks) {
+        assert_eq!(*receipts, with_bloom(expected));
+    }
+
+    // unknown blocks are empty entries in position
+    let unknown: B256 = rng.gen();
+    let Receipts(receipts) = get_receipts(vec![blocks[0].0, unknown, blocks[1].0]).await;
+    assert_eq!(receipts, vec![with_bloom(&blocks[0].1), vec![], with_bloom(&blocks[1].1)]);
+}
@@ -27,8 +27,8 @@ use reth_primitives::{
     },
     proofs,
     revm::{compat::into_reth_log, env::tx_env_with_recovered},
-    Block, BlockNumberOrTag, Bytes, ChainSpec, Header, IntoRecoveredTransaction, Receipt, Receipts,
-    SealedBlock, Withdrawal, B256, EMPTY_OMMER_ROOT, U256,
+    Address, Block, BlockNumberOrTag, Bytes, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
+    Receipts, SealedBlock, Withdrawal, B256, EMPTY_OMMER_ROOT, U256,
 };
 use reth_provider::{BlockReaderIdExt, BlockSource, BundleStateWithReceipts, StateProviderFactory};
 use reth_revm::{
@@ -36,13 +36,14 @@ use reth_revm::{
     state_change::{apply_beacon_root_contract_call, post_block_withdrawals_balance_increments},
 };
 use reth_tasks::TaskSpawner;
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use revm::{
     db::states::bundle_state::BundleRetention,
     primitives::{BlockEnv, CfgEnv, EVMError, Env, InvalidTransaction, ResultAndState},
     Database, DatabaseCommit, State,
 };
 use std::{
+    collections::{HashMap, VecDeque},
     fmt::Debug,
     future::Future,
     pin::Pin,
@@ -689,9 +690,16 @@ where
     let mut sum_blob_gas_used = 0;
     let block_gas_limit: u64 = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
     let base_fee = initialized_block_env.basefee.to::<u64>();
+    // the blob fee of the new block, if cancun is active
+    let blob_fee = initialized_block_env
+        .get_blob_gasprice()
+        .map(|blob_gasprice| u64::try_from(blob_gasprice).unwrap_or(u64::MAX));
 
     let mut executed_txs = Vec::new();
-    let mut best_txs = pool.best_transactions_with_base_fee(base_fee);
+    let mut blob_sidecars = Vec::new();
+    let mut blob_candidates = BlobCandidates::default();
+    let mut best_txs =
+        pool.best_transactions_with_attributes(BestTransactionsAttributes::new(base_fee, blob_fee));
 
     let mut total_fees = U256::ZERO;
 
@@ -708,13 +716,45 @@ where
     )?;
 
     let mut receipts = Vec::new();
-    while let Some(pool_tx) = best_txs.next() {
+    loop {
+        // Blob transactions are deferred until all other transactions were considered, and are
+        // then included by their blob priority fee, so the transactions that pay the most for the
+        // limited blob space of the block win it.
+        let (pool_tx, tx) = match best_txs.next() {
+            Some(pool_tx) => {
+                let tx = pool_tx.to_recovered_transaction();
+                if let Some(blob_tx) = tx.transaction.as_eip4844() {
+                    let blob_gas = blob_tx.blob_gas();
+                    let blob_priority_fee = blob_tx
+                        .max_fee_per_blob_gas
+                        .saturating_sub(blob_fee.unwrap_or_default() as u128);
+                    blob_candidates.push(
+                        pool_tx.sender(),
+                        blob_gas,
+                        blob_priority_fee,
+                        (pool_tx, tx),
+                    );
+                    continue
+                }
+                (pool_tx, tx)
+            }
+            None => {
+                // Blob transactions that don't fit into the remaining blob space are not marked as
+                // invalid, so they remain in the pool for the next block.
+                match blob_candidates.pop_best(MAX_DATA_GAS_PER_BLOCK - sum_blob_gas_used) {
+                    Some(candidate) => candidate,
+                    None => break,
+                }
+            }
+        };
+
         // ensure we still have capacity for this transaction
         if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
             // we can't fit this transaction into the block, so we need to mark it as invalid
             // which also removes all dependent transaction from the iterator before we can
             // continue
             best_txs.mark_invalid(&pool_tx);
+            blob_candidates.remove_sender(pool_tx.sender());
             continue
         }
 
@@ -723,22 +763,20 @@ where
             return Ok(BuildOutcome::Cancelled)
         }
 
-        // convert tx to a signed transaction
-        let tx = pool_tx.to_recovered_transaction();
-
-        // There's only limited amount of blob space available per block, so we need to check if the
-        // EIP-4844 can still fit in the block
-        if let Some(blob_tx) = tx.transaction.as_eip4844() {
-            let tx_blob_gas = blob_tx.blob_gas();
-            if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
-                // we can't fit this _blob_ transaction into the block, so we mark it as invalid,
-                // which removes its dependent transactions from the iterator. This is similar to
-                // the gas limit condition for regular transactions above.
-                trace!(target: "payload_builder", tx=?tx.hash, ?sum_blob_gas_used, ?tx_blob_gas, "skipping blob transaction because it would exceed the max data gas per block");
-                best_txs.mark_invalid(&pool_tx);
-                continue
+        // The sidecar is fetched when the transaction is included, so the payload carries the
+        // blobs of all its transactions even if the pool evicts them while the payload is built.
+        let blob_sidecar = if tx.is_eip4844() {
+            match pool.get_blob(tx.hash)? {
+                Some(sidecar) => Some(sidecar),
+                None => {
+                    trace!(target: "payload_builder", tx=?tx.hash, "skipping blob transaction without sidecar");
+                    blob_candidates.remove_sender(pool_tx.sender());
+                    continue
+                }
             }
-        }
+        } else {
+            None
+        };
 
         // Configure the environment for the block.
         let env = Env {
@@ -763,6 +801,7 @@ where
                             // descendants
                             trace!(target: "payload_builder", ?err, ?tx, "skipping invalid transaction and its descendants");
                             best_txs.mark_invalid(&pool_tx);
+                            blob_candidates.remove_sender(pool_tx.sender());
                         }
 
                         continue
@@ -780,14 +819,9 @@ where
 
         // add to the total blob gas used if the transaction successfully executed
         if let Some(blob_tx) = tx.transaction.as_eip4844() {
-            let tx_blob_gas = blob_tx.blob_gas();
-            sum_blob_gas_used += tx_blob_gas;
-
-            // if we've reached the max data gas per block, we can skip blob txs entirely
-            if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
-                best_txs.skip_blobs();
-            }
+            sum_blob_gas_used += blob_tx.blob_gas();
         }
+        blob_sidecars.extend(blob_sidecar);
 
         let gas_used = result.gas_used();
 
@@ -838,18 +872,11 @@ where
     // create the block header
     let transactions_root = proofs::calculate_transaction_root(&executed_txs);
 
-    // initialize empty blob sidecars at first. If cancun is active then this will
-    let mut blob_sidecars = Vec::new();
     let mut excess_blob_gas = None;
     let mut blob_gas_used = None;
 
     // only determine cancun fields when active
     if chain_spec.is_cancun_active_at_timestamp(attributes.timestamp) {
-        // grab the blob sidecars from the executed txs
-        blob_sidecars = pool.get_all_blobs_exact(
-            executed_txs.iter().filter(|tx| tx.is_eip4844()).map(|tx| tx.hash).collect(),
-        )?;
-
         excess_blob_gas = if chain_spec.is_cancun_active_at_timestamp(parent_block.timestamp) {
             let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
             let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
@@ -894,7 +921,7 @@ where
 
     let mut payload = BuiltPayload::new(attributes.id, sealed_block, total_fees);
 
-    // extend the payload with the blob sidecars from the executed txs
+    // extend the payload with the blob sidecars of the executed txs, in the order of the txs
     payload.extend_sidecars(blob_sidecars);
 
     Ok(BuildOutcome::Better { payload, cached_reads })
@@ -1071,9 +1098,11 @@ where
         attributes.timestamp,
         block_number,
         attributes.parent_beacon_block_root,
+        false,
         &mut evm_pre_block,
     )
-    .map_err(|err| PayloadBuilderError::Internal(err.into()))
+    .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
+    Ok(())
 }
 
 /// Checks if the new payload is better than the current best.
@@ -1087,3 +1116,143 @@ fn is_better_payload(best_payload: Option<&BuiltPayload>, new_fees: U256) -> boo
         true
     }
 }
+
+/// The blob transactions that compete for the blob space of a payload.
+///
+/// Transactions of the same sender are kept in the order they were pushed, i.e. by nonce, and
+/// only the first transaction of every sender can be included.
+#[derive(Debug)]
+struct BlobCandidates<T> {
+    /// The candidates by sender.
+    by_sender: HashMap<Address, VecDeque<BlobCandidate<T>>>,
+    /// The number of pushed candidates, which breaks ties between equal blob priority fees.
+    pushed: usize,
+}
+
+impl<T> Default for BlobCandidates<T> {
+    fn default() -> Self {
+        Self { by_sender: HashMap::new(), pushed: 0 }
+    }
+}
+
+impl<T> BlobCandidates<T> {
+    /// Adds a blob transaction of the sender with the given blob gas and blob priority fee, i.e.
+    /// the max fee per blob gas above the blob gas price of the block.
+    fn push(&mut self, sender: Address, blob_gas: u64, blob_priority_fee: u128, tx: T) {
+        let candidate = BlobCandidate { blob_gas, blob_priority_fee, order: self.pushed, tx };
+        self.pushed += 1;
+        self.by_sender.entry(sender).or_default().push_back(candidate);
+    }
+
+    /// Removes and returns the candidate with the highest blob priority fee that fits into the
+    /// available blob gas, preferring the earlier pushed candidate if the fees are equal.
+    fn pop_best(&mut self, available_blob_gas: u64) -> Option<T> {
+        let sender = self
+            .by_sender
+            .iter()
+            .filter_map(|(sender, candidates)| Some((sender, candidates.front()?)))
+            .filter(|(_, candidate)| candidate.blob_gas <= available_blob_gas)
+            .max_by(|(_, a), (_, b)| {
+                a.blob_priority_fee.cmp(&b.blob_priority_fee).then(b.order.cmp(&a.order))
+            })
+            .map(|(sender, _)| *sender)?;
+
+        let candidates = self.by_sender.get_mut(&sender)?;
+        let candidate = candidates.pop_front()?;
+        if candidates.is_empty() {
+            self.by_sender.remove(&sender);
+        }
+        Some(candidate.tx)
+    }
+
+    /// Removes all candidates of the sender, e.g. because one of its transactions is invalid and
+    /// the following ones can't be included.
+    fn remove_sender(&mut self, sender: Address) {
+        self.by_sender.remove(&sender);
+    }
+}
+
+/// A blob transaction in [BlobCandidates].
+#[derive(Debug)]
+struct BlobCandidate<T> {
+    blob_gas: u64,
+    blob_priority_fee: u128,
+    order: usize,
+    tx: T,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::constants::eip4844::{DATA_GAS_PER_BLOB, MAX_BLOBS_PER_BLOCK};
+
+    /// Includes the best candidates, given as their blob gas and an identifier, until the blob
+    /// space of a block is used up.
+    fn include_all(candidates: &mut BlobCandidates<(u64, u128)>) -> Vec<(u64, u128)> {
+        let mut sum_blob_gas_used = 0;
+        let mut included = Vec::new();
+        while let Some(tx) = candidates.pop_best(MAX_DATA_GAS_PER_BLOCK - sum_blob_gas_used) {
+            sum_blob_gas_used += tx.0;
+            included.push(tx);
+        }
+        included
+    }
+
+    #[test]
+    fn selects_blob_txs_by_blob_priority_fee() {
+        let mut candidates = BlobCandidates::default();
+        // 9 single blob transactions of different senders, pushed in the order of their tip
+        let blob_priority_fees = [3, 9, 1, 7, 5, 8, 2, 6, 4];
+        for (i, blob_priority_fee) in blob_priority_fees.into_iter().enumerate() {
+            let sender = Address::with_last_byte(i as u8);
+            candidates.push(
+                sender,
+                DATA_GAS_PER_BLOB,
+                blob_priority_fee,
+                (DATA_GAS_PER_BLOB, blob_priority_fee),
+            );
+        }
+
+        let included = include_all(&mut candidates);
+        assert_eq!(included.len(), MAX_BLOBS_PER_BLOCK);
+        assert_eq!(
+            included.iter().map(|(_, fee)| *fee).collect::<Vec<_>>(),
+            vec![9, 8, 7, 6, 5, 4]
+        );
+        // the remaining transactions are kept for the next block
+        assert_eq!(candidates.by_sender.len(), 3);
+    }
+
+    #[test]
+    fn fills_remaining_blob_space() {
+        let mut candidates = BlobCandidates::default();
+        let blob_gas = |blobs: u64| blobs * DATA_GAS_PER_BLOB;
+        candidates.push(Address::with_last_byte(1), blob_gas(4), 10, (blob_gas(4), 1));
+        candidates.push(Address::with_last_byte(2), blob_gas(3), 9, (blob_gas(3), 2));
+        candidates.push(Address::with_last_byte(3), blob_gas(2), 1, (blob_gas(2), 3));
+
+        // the second transaction doesn't fit after the first one, but the third one does
+        assert_eq!(include_all(&mut candidates), vec![(blob_gas(4), 1), (blob_gas(2), 3)]);
+    }
+
+    #[test]
+    fn keeps_nonce_order_of_sender() {
+        let mut candidates = BlobCandidates::default();
+        let sender = Address::with_last_byte(1);
+        candidates.push(sender, DATA_GAS_PER_BLOB, 1, (DATA_GAS_PER_BLOB, 0));
+        candidates.push(sender, DATA_GAS_PER_BLOB, 5, (DATA_GAS_PER_BLOB, 1));
+        candidates.push(Address::with_last_byte(2), DATA_GAS_PER_BLOB, 3, (DATA_GAS_PER_BLOB, 2));
+        candidates.push(Address::with_last_byte(3), DATA_GAS_PER_BLOB, 3, (DATA_GAS_PER_BLOB, 3));
+
+        let included = include_all(&mut candidates);
+        // equal fees are included in push order, and the sender's second transaction only after
+        // its first one
+        assert_eq!(included.iter().map(|(_, nonce)| *nonce).collect::<Vec<_>>(), vec![2, 3, 0, 1]);
+
+        let mut candidates = BlobCandidates::default();
+        candidates.push(sender, DATA_GAS_PER_BLOB, 1, (DATA_GAS_PER_BLOB, 0));
+        candidates.push(sender, DATA_GAS_PER_BLOB, 5, (DATA_GAS_PER_BLOB, 1));
+        candidates.remove_sender(sender);
+        assert!(candidates.pop_best(MAX_DATA_GAS_PER_BLOCK).is_none());
+    }
+}
@@ -2,8 +2,9 @@
 
 use alloy_rlp::Encodable;
 use reth_primitives::{
-    revm::config::revm_spec_by_timestamp_after_merge, Address, BlobTransactionSidecar, ChainSpec,
-    Header, SealedBlock, Withdrawal, B256, U256,
+    basefee::{next_block_base_fee, next_block_excess_blob_gas},
+    revm::config::revm_spec_by_timestamp_after_merge,
+    Address, BlobTransactionSidecar, ChainSpec, Header, SealedBlock, Withdrawal, B256, U256,
 };
 use reth_rpc_types::engine::{
     ExecutionPayloadEnvelopeV2, ExecutionPayloadEnvelopeV3, ExecutionPayloadV1, PayloadAttributes,
@@ -55,6 +56,25 @@ impl BuiltPayload {
         self.fees
     }
 
+    /// Returns the blob sidecars of the blob transactions in the block, in the order of the
+    /// transactions.
+    ///
+    /// These are collected by the payload builder while the block is built, so the blobs bundle of
+    /// `engine_getPayloadV3` doesn't depend on the pool still holding the transactions.
+    pub fn sidecars(&self) -> &[BlobTransactionSidecar] {
+        &self.sidecars
+    }
+
+    /// Returns the total blob gas used by the transactions in the block, if Cancun is active.
+    pub fn blob_gas_used(&self) -> Option<u64> {
+        self.block.blob_gas_used
+    }
+
+    /// Returns the excess blob gas of the block, if Cancun is active.
+    pub fn excess_blob_gas(&self) -> Option<u64> {
+        self.block.excess_blob_gas
+    }
+
     /// Adds sidecars to the payload.
     pub fn extend_sidecars(&mut self, sidecars: Vec<BlobTransactionSidecar>) {
         self.sidecars.extend(sidecars)
@@ -183,8 +203,7 @@ impl PayloadBuilderAttributes {
 
         // if the parent block did not have excess blob gas (i.e. it was pre-cancun), but it is
         // cancun now, we need to set the excess blob gas to the default value
-        let blob_excess_gas_and_price = parent
-            .next_block_excess_blob_gas()
+        let blob_excess_gas_and_price = next_block_excess_blob_gas(parent)
             .map_or_else(
                 || {
                     if cfg.spec_id == SpecId::CANCUN {
@@ -206,9 +225,7 @@ impl PayloadBuilderAttributes {
             prevrandao: Some(self.prev_randao),
             gas_limit: U256::from(parent.gas_limit),
             // calculate basefee based on parent block's gas usage
-            basefee: U256::from(
-                parent.next_block_base_fee(chain_spec.base_fee_params).unwrap_or_default(),
-            ),
+            basefee: U256::from(next_block_base_fee(parent, chain_spec).unwrap_or_default()),
             // calculate excess gas based on parent block's blob gas usage
             blob_excess_gas_and_price,
         };
@@ -243,3 +260,43 @@ pub(crate) fn payload_id(parent: &B256, attributes: &PayloadAttributes) -> Paylo
     let out = hasher.finalize();
     PayloadId::new(out.as_slice()[..8].try_into().expect("sufficient length"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        constants::eip4844::DATA_GAS_PER_BLOB,
+        kzg::{Blob, Bytes48, BYTES_PER_BLOB},
+        Block,
+    };
+
+    #[test]
+    fn v3_payload_includes_blobs_bundle() {
+        let header = Header {
+            blob_gas_used: Some(3 * DATA_GAS_PER_BLOB),
+            excess_blob_gas: Some(DATA_GAS_PER_BLOB),
+            ..Default::default()
+        };
+        let block = Block { header, ..Default::default() }.seal_slow();
+        let sidecar = |blobs: usize| {
+            BlobTransactionSidecar::new(
+                vec![Blob::from([0u8; BYTES_PER_BLOB]); blobs],
+                vec![Bytes48::from([1u8; 48]); blobs],
+                vec![Bytes48::from([2u8; 48]); blobs],
+            )
+        };
+
+        let mut payload = BuiltPayload::new(PayloadId::new([0; 8]), block, U256::from(1));
+        payload.extend_sidecars(vec![sidecar(1), sidecar(2)]);
+        assert_eq!(payload.sidecars().len(), 2);
+        assert_eq!(payload.blob_gas_used(), Some(3 * DATA_GAS_PER_BLOB));
+        assert_eq!(payload.excess_blob_gas(), Some(DATA_GAS_PER_BLOB));
+
+        let envelope = payload.into_v3_payload();
+        assert_eq!(envelope.execution_payload.blob_gas_used.to::<u64>(), 3 * DATA_GAS_PER_BLOB);
+        assert_eq!(envelope.execution_payload.excess_blob_gas.to::<u64>(), DATA_GAS_PER_BLOB);
+        assert_eq!(envelope.blobs_bundle.blobs.len(), 3);
+        assert_eq!(envelope.blobs_bundle.commitments.len(), 3);
+        assert_eq!(envelope.blobs_bundle.proofs.len(), 3);
+    }
+}
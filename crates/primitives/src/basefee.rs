@@ -1,6 +1,16 @@
 //! Helpers for working with EIP-1559 base fee
 
+use crate::{
+    calculate_excess_blob_gas,
+    constants::{eip4844::MAX_DATA_GAS_PER_BLOCK, EIP1559_INITIAL_BASE_FEE},
+    eip4844::calc_blob_gasprice,
+    ChainSpec, Hardfork, Header,
+};
+
 /// Calculate base fee for next block. [EIP-1559](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-1559.md) spec
+///
+/// The base fee is unchanged if the gas target is zero, i.e. the gas limit is lower than the
+/// elasticity multiplier.
 pub fn calculate_next_block_base_fee(
     gas_used: u64,
     gas_limit: u64,
@@ -8,7 +18,7 @@ pub fn calculate_next_block_base_fee(
     base_fee_params: crate::BaseFeeParams,
 ) -> u64 {
     let gas_target = gas_limit / base_fee_params.elasticity_multiplier;
-    if gas_used == gas_target {
+    if gas_used == gas_target || gas_target == 0 {
         return base_fee
     }
     if gas_used > gas_target {
@@ -30,9 +40,84 @@ pub fn calculate_next_block_base_fee(
     }
 }
 
+/// Calculate the base fee of the block after `parent`, using the
+/// [BaseFeeParams](crate::BaseFeeParams) of the chain.
+///
+/// The first London block has the initial base fee of EIP-1559, regardless of its parent. Returns
+/// `None` if the parent has no base fee and the next block is not the first London block.
+pub fn next_block_base_fee(parent: &Header, chain_spec: &ChainSpec) -> Option<u64> {
+    if chain_spec.fork(Hardfork::London).transitions_at_block(parent.number + 1) {
+        return Some(EIP1559_INITIAL_BASE_FEE)
+    }
+    Some(calculate_next_block_base_fee(
+        parent.gas_used,
+        parent.gas_limit,
+        parent.base_fee_per_gas?,
+        chain_spec.base_fee_params,
+    ))
+}
+
+/// Calculate the excess blob gas of the block after `parent` according to EIP-4844.
+///
+/// Returns `None` if the parent has no excess blob gas or blob gas used.
+pub fn next_block_excess_blob_gas(parent: &Header) -> Option<u64> {
+    Some(calculate_excess_blob_gas(parent.excess_blob_gas?, parent.blob_gas_used?))
+}
+
+/// The projected fees of a future block, see [project_fees].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeProjection {
+    /// The number of the block.
+    pub number: u64,
+    /// The base fee per gas of the block.
+    pub base_fee_per_gas: u64,
+    /// The blob fee of the block, if the chain has blobs.
+    pub blob_fee: Option<u128>,
+}
+
+/// Projects the fees of the next `n_blocks` blocks after `header`.
+///
+/// Every projected block is assumed to use the fraction `assumed_fullness` of its gas limit and of
+/// the maximum blob gas, which is clamped to `0.0..=1.0`. The gas limit is assumed to stay the
+/// same. The projection ends early if the base fee of a block is unknown, see
+/// [next_block_base_fee].
+pub fn project_fees(
+    header: &Header,
+    chain_spec: &ChainSpec,
+    n_blocks: u64,
+    assumed_fullness: f64,
+) -> Vec<FeeProjection> {
+    let fullness = assumed_fullness.clamp(0.0, 1.0);
+    let mut projection = Vec::new();
+    let mut parent = header.clone();
+    for _ in 0..n_blocks {
+        let Some(base_fee_per_gas) = next_block_base_fee(&parent, chain_spec) else { break };
+        let excess_blob_gas = next_block_excess_blob_gas(&parent);
+        let number = parent.number + 1;
+        projection.push(FeeProjection {
+            number,
+            base_fee_per_gas,
+            blob_fee: excess_blob_gas.map(calc_blob_gasprice),
+        });
+
+        parent = Header {
+            number,
+            gas_limit: parent.gas_limit,
+            gas_used: (parent.gas_limit as f64 * fullness) as u64,
+            base_fee_per_gas: Some(base_fee_per_gas),
+            excess_blob_gas,
+            blob_gas_used: excess_blob_gas
+                .map(|_| (MAX_DATA_GAS_PER_BLOCK as f64 * fullness) as u64),
+            ..Default::default()
+        };
+    }
+    projection
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{constants::eip4844::TARGET_DATA_GAS_PER_BLOCK, ChainSpecBuilder, MAINNET};
 
     #[test]
     fn calculate_base_fee_success() {
@@ -65,4 +150,101 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn base_fee_at_london_transition() {
+        let london = 12_965_000;
+        let parent = Header {
+            number: london - 1,
+            gas_limit: 15_000_000,
+            gas_used: 15_000_000,
+            ..Default::default()
+        };
+        // the first London block has the initial base fee although its parent has none
+        assert_eq!(next_block_base_fee(&parent, &MAINNET), Some(EIP1559_INITIAL_BASE_FEE));
+
+        // no base fee before London
+        let parent = Header { number: london - 2, ..parent };
+        assert_eq!(next_block_base_fee(&parent, &MAINNET), None);
+
+        // the base fee of the parent is used afterwards
+        let parent = Header {
+            number: london,
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            base_fee_per_gas: Some(EIP1559_INITIAL_BASE_FEE),
+            ..Default::default()
+        };
+        assert_eq!(next_block_base_fee(&parent, &MAINNET), Some(1_125_000_000));
+    }
+
+    #[test]
+    fn base_fee_with_chain_params() {
+        let mut chain_spec = ChainSpecBuilder::mainnet().build();
+        chain_spec.base_fee_params =
+            crate::BaseFeeParams { max_change_denominator: 50, elasticity_multiplier: 6 };
+        let parent = Header {
+            number: 20_000_000,
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        // the gas used exceeds the target of 5M by 25M
+        assert_eq!(next_block_base_fee(&parent, &chain_spec), Some(1_100_000_000));
+        assert_eq!(next_block_base_fee(&parent, &MAINNET), Some(1_125_000_000));
+    }
+
+    #[test]
+    fn base_fee_of_zero_gas_parent() {
+        let parent = Header {
+            number: 20_000_000,
+            gas_limit: 30_000_000,
+            gas_used: 0,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        // an empty block lowers the base fee by the maximum of 1/8
+        assert_eq!(next_block_base_fee(&parent, &MAINNET), Some(875_000_000));
+
+        // a block without gas target keeps the base fee
+        let parent = Header { gas_limit: 1, gas_used: 1, ..parent };
+        assert_eq!(next_block_base_fee(&parent, &MAINNET), Some(1_000_000_000));
+        let parent = Header { gas_limit: 0, gas_used: 0, ..parent };
+        assert_eq!(next_block_base_fee(&parent, &MAINNET), Some(1_000_000_000));
+    }
+
+    #[test]
+    fn projected_fees() {
+        let header = Header {
+            number: 20_000_000,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            excess_blob_gas: Some(0),
+            blob_gas_used: Some(0),
+            ..Default::default()
+        };
+
+        // blocks at the target keep the fees constant
+        let projection = project_fees(&header, &MAINNET, 3, 0.5);
+        assert_eq!(projection.len(), 3);
+        for (fees, number) in projection.iter().zip(20_000_001..) {
+            assert_eq!(
+                *fees,
+                FeeProjection { number, base_fee_per_gas: 1_000_000_000, blob_fee: Some(1) }
+            );
+        }
+
+        // full blocks raise the base fee by 1/8 per block and accumulate excess blob gas
+        let projection = project_fees(&header, &MAINNET, 3, 1.0);
+        let base_fees = projection.iter().map(|fees| fees.base_fee_per_gas).collect::<Vec<_>>();
+        assert_eq!(base_fees, [1_000_000_000, 1_125_000_000, 1_265_625_000]);
+        let excess_blob_gas = 2 * (MAX_DATA_GAS_PER_BLOCK - TARGET_DATA_GAS_PER_BLOCK);
+        assert_eq!(projection[2].blob_fee, Some(calc_blob_gasprice(excess_blob_gas)));
+
+        // the projection stops without a base fee
+        let header = Header { base_fee_per_gas: None, ..header };
+        assert!(project_fees(&header, &MAINNET, 3, 1.0).is_empty());
+    }
 }
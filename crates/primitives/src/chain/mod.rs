@@ -13,8 +13,9 @@ use strum::{AsRefStr, EnumCount, EnumIter, EnumString, EnumVariantNames};
 // The chain spec module.
 mod spec;
 pub use spec::{
-    AllGenesisFormats, BaseFeeParams, ChainSpec, ChainSpecBuilder, DisplayHardforks, ForkCondition,
-    ForkTimestamps, DEV, GOERLI, HOLESKY, MAINNET, SEPOLIA,
+    AllGenesisFormats, BaseFeeParams, ChainSpec, ChainSpecBuilder, ChainSpecBuilderError,
+    ChainSpecError, DisplayHardforks, ForkCondition, ForkTimestamps, SystemCallKind, DEV, GOERLI,
+    HOLESKY, MAINNET, REWARD_SPLIT_TOTAL_BPS, SEPOLIA,
 };
 
 // The chain info module.
@@ -1,7 +1,9 @@
 use crate::{
     constants::{
+        BEACON_ROOTS_ADDRESS, DEFAULT_MAX_SYSTEM_CALL_GAS, DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
         EIP1559_DEFAULT_BASE_FEE_MAX_CHANGE_DENOMINATOR, EIP1559_DEFAULT_ELASTICITY_MULTIPLIER,
         EIP1559_INITIAL_BASE_FEE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS,
+        GWEI_TO_WEI,
     },
     forkid::ForkFilterKey,
     header::Head,
@@ -13,10 +15,11 @@ use once_cell::sync::Lazy;
 use revm_primitives::{address, b256};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashSet},
     fmt::{Display, Formatter},
     sync::Arc,
 };
+use thiserror::Error;
 
 /// The Ethereum mainnet spec
 pub static MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
@@ -66,6 +69,15 @@ pub static MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         base_fee_params: BaseFeeParams::ethereum(),
         prune_delete_limit: 3500,
         snapshot_block_interval: 500_000,
+        reward_split: None,
+        max_ommer_reward_per_block: None,
+        system_call_gas_price: None,
+        base_fee_recycle_bps: None,
+        max_system_call_return_size: DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
+        max_system_call_gas: DEFAULT_MAX_SYSTEM_CALL_GAS,
+        withdrawal_amount_scale: U256::from(GWEI_TO_WEI),
+        disabled_system_calls: HashSet::new(),
+        withdrawal_vault: None,
     }
     .into()
 });
@@ -109,6 +121,15 @@ pub static GOERLI: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         base_fee_params: BaseFeeParams::ethereum(),
         prune_delete_limit: 1700,
         snapshot_block_interval: 1_000_000,
+        reward_split: None,
+        max_ommer_reward_per_block: None,
+        system_call_gas_price: None,
+        base_fee_recycle_bps: None,
+        max_system_call_return_size: DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
+        max_system_call_gas: DEFAULT_MAX_SYSTEM_CALL_GAS,
+        withdrawal_amount_scale: U256::from(GWEI_TO_WEI),
+        disabled_system_calls: HashSet::new(),
+        withdrawal_vault: None,
     }
     .into()
 });
@@ -156,6 +177,15 @@ pub static SEPOLIA: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         base_fee_params: BaseFeeParams::ethereum(),
         prune_delete_limit: 1700,
         snapshot_block_interval: 1_000_000,
+        reward_split: None,
+        max_ommer_reward_per_block: None,
+        system_call_gas_price: None,
+        base_fee_recycle_bps: None,
+        max_system_call_return_size: DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
+        max_system_call_gas: DEFAULT_MAX_SYSTEM_CALL_GAS,
+        withdrawal_amount_scale: U256::from(GWEI_TO_WEI),
+        disabled_system_calls: HashSet::new(),
+        withdrawal_vault: None,
     }
     .into()
 });
@@ -198,6 +228,15 @@ pub static HOLESKY: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         base_fee_params: BaseFeeParams::ethereum(),
         prune_delete_limit: 1700,
         snapshot_block_interval: 1_000_000,
+        reward_split: None,
+        max_ommer_reward_per_block: None,
+        system_call_gas_price: None,
+        base_fee_recycle_bps: None,
+        max_system_call_return_size: DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
+        max_system_call_gas: DEFAULT_MAX_SYSTEM_CALL_GAS,
+        withdrawal_amount_scale: U256::from(GWEI_TO_WEI),
+        disabled_system_calls: HashSet::new(),
+        withdrawal_vault: None,
     }
     .into()
 });
@@ -310,6 +349,83 @@ pub struct ChainSpec {
 
     /// The block interval for creating snapshots. Each snapshot will have that much blocks in it.
     pub snapshot_block_interval: u64,
+
+    /// Splits the block reward among the given addresses instead of crediting it to the block
+    /// beneficiary, with each address receiving its share in basis points.
+    ///
+    /// The shares must sum up to [REWARD_SPLIT_TOTAL_BPS]. Ommer rewards are not affected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward_split: Option<Vec<(Address, u16)>>,
+
+    /// The maximum total reward in wei that is paid to the ommer beneficiaries of a block,
+    /// regardless of the number of ommers.
+    ///
+    /// Ommer rewards that exceed the cap are scaled down proportionally. Uncapped if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ommer_reward_per_block: Option<u128>,
+
+    /// The gas price of system calls, e.g. the EIP-4788 beacon root call.
+    ///
+    /// System calls are free by default and their changes to the block beneficiary are discarded.
+    /// If set, the system call pays this gas price and the fee is credited to the block
+    /// beneficiary, so the system address must be able to pay for the gas limit of the call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_call_gas_price: Option<U256>,
+
+    /// The share of the burned base fee of a block, in basis points, that is recycled into the
+    /// reward of the block beneficiary.
+    ///
+    /// Must not exceed [REWARD_SPLIT_TOTAL_BPS]. No base fee is recycled if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_recycle_bps: Option<u16>,
+
+    /// The maximum size in bytes of the return data of a system call.
+    ///
+    /// Blocks whose system calls return more data are invalid, which bounds the memory used to
+    /// process the output.
+    #[serde(default = "default_max_system_call_return_size")]
+    pub max_system_call_return_size: usize,
+
+    /// The maximum total gas used by the pre-block system calls of a block.
+    ///
+    /// System calls don't consume block gas, but are executed with their own gas limit each. This
+    /// bounds the sum over all system calls active in a block, blocks that exceed it are invalid.
+    #[serde(default = "default_max_system_call_gas")]
+    pub max_system_call_gas: u64,
+
+    /// The factor the withdrawal amounts are multiplied with to get the wei value they are
+    /// credited with.
+    ///
+    /// Withdrawal amounts are denominated in gwei on Ethereum, so this defaults to one gwei in
+    /// wei.
+    #[serde(default = "default_withdrawal_amount_scale")]
+    pub withdrawal_amount_scale: U256,
+
+    /// The system calls that are skipped even if their hardfork is active.
+    ///
+    /// This supports custom chains with non-standard fork layouts, and should be empty for
+    /// Ethereum networks.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub disabled_system_calls: HashSet<SystemCallKind>,
+
+    /// The contract that is credited with the sum of all withdrawals of a block, which it then
+    /// distributes to the withdrawal addresses.
+    ///
+    /// The withdrawal addresses are credited directly if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawal_vault: Option<Address>,
+}
+
+fn default_max_system_call_return_size() -> usize {
+    DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE
+}
+
+fn default_max_system_call_gas() -> u64 {
+    DEFAULT_MAX_SYSTEM_CALL_GAS
+}
+
+fn default_withdrawal_amount_scale() -> U256 {
+    U256::from(GWEI_TO_WEI)
 }
 
 impl Default for ChainSpec {
@@ -325,6 +441,15 @@ impl Default for ChainSpec {
             base_fee_params: BaseFeeParams::ethereum(),
             prune_delete_limit: MAINNET.prune_delete_limit,
             snapshot_block_interval: Default::default(),
+            reward_split: Default::default(),
+            max_ommer_reward_per_block: Default::default(),
+            system_call_gas_price: Default::default(),
+            base_fee_recycle_bps: Default::default(),
+            max_system_call_return_size: DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
+            max_system_call_gas: DEFAULT_MAX_SYSTEM_CALL_GAS,
+            withdrawal_amount_scale: default_withdrawal_amount_scale(),
+            disabled_system_calls: Default::default(),
+            withdrawal_vault: Default::default(),
         }
     }
 }
@@ -457,6 +582,36 @@ impl ChainSpec {
         self.hardforks.iter().map(|(f, b)| (*f, *b))
     }
 
+    /// Returns all hardforks that are active at the given block number and timestamp.
+    ///
+    /// Forks that are activated by total difficulty are only reported as active if their fork
+    /// block is reached, or if the block is after the known merge block of the chain, see
+    /// [ChainSpec::final_paris_total_difficulty].
+    pub fn active_hardforks_at(&self, block_number: BlockNumber, timestamp: u64) -> Vec<Hardfork> {
+        let head = Head {
+            number: block_number,
+            timestamp,
+            total_difficulty: self.final_paris_total_difficulty(block_number).unwrap_or_default(),
+            ..Default::default()
+        };
+        self.forks_iter()
+            .filter(|(_, condition)| condition.active_at_head(&head))
+            .map(|(fork, _)| fork)
+            .collect()
+    }
+
+    /// Returns the first timestamp based hardfork that activates after the given timestamp, and
+    /// its activation timestamp.
+    ///
+    /// This is the fork that is announced as `FORK_NEXT` in the [ForkId] of a post merge head at
+    /// the given timestamp.
+    pub fn next_fork_after(&self, timestamp: u64) -> Option<(Hardfork, u64)> {
+        self.forks_iter()
+            .filter_map(|(fork, condition)| Some((fork, condition.as_timestamp()?)))
+            .filter(|(_, activation)| *activation > timestamp)
+            .min_by_key(|(_, activation)| *activation)
+    }
+
     /// Convenience method to check if a fork is active at a given timestamp.
     #[inline]
     pub fn is_fork_active_at_timestamp(&self, fork: Hardfork, timestamp: u64) -> bool {
@@ -481,6 +636,43 @@ impl ChainSpec {
             .unwrap_or_else(|| self.is_fork_active_at_timestamp(Hardfork::Cancun, timestamp))
     }
 
+    /// Returns `true` if the given system call is executed at the given timestamp, i.e. its
+    /// hardfork is active and it is not one of the [ChainSpec::disabled_system_calls].
+    #[inline]
+    pub fn is_system_call_active_at_timestamp(&self, kind: SystemCallKind, timestamp: u64) -> bool {
+        if self.disabled_system_calls.contains(&kind) {
+            return false
+        }
+        match kind {
+            SystemCallKind::BeaconRoot => self.is_cancun_active_at_timestamp(timestamp),
+        }
+    }
+
+    /// Returns an error if the reward configuration of the spec is invalid: the shares of the
+    /// [ChainSpec::reward_split] must sum up to [REWARD_SPLIT_TOTAL_BPS] and the
+    /// [ChainSpec::base_fee_recycle_bps] must not exceed it.
+    ///
+    /// This is checked by [ChainSpecBuilder::try_build] and when loading a custom chain spec.
+    pub fn validate_rewards(&self) -> Result<(), ChainSpecError> {
+        if let Some(reward_split) = &self.reward_split {
+            let total_bps: u32 = reward_split.iter().map(|(_, bps)| *bps as u32).sum();
+            if total_bps != REWARD_SPLIT_TOTAL_BPS as u32 {
+                return Err(ChainSpecError::InvalidRewardSplit { total_bps })
+            }
+        }
+        if let Some(bps) = self.base_fee_recycle_bps {
+            if bps > REWARD_SPLIT_TOTAL_BPS {
+                return Err(ChainSpecError::InvalidBaseFeeRecycleBps { bps })
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the spec, see [ChainSpec::validate_rewards].
+    pub fn validate(&self) -> Result<(), ChainSpecError> {
+        self.validate_rewards()
+    }
+
     /// Creates a [`ForkFilter`] for the block described by [Head].
     pub fn fork_filter(&self, head: Head) -> ForkFilter {
         let forks = self.forks_iter().filter_map(|(_, condition)| {
@@ -605,6 +797,48 @@ impl ChainSpec {
     pub fn builder() -> ChainSpecBuilder {
         ChainSpecBuilder::default()
     }
+
+    /// Returns the genesis with the chain ID and the hardforks of this spec in its config, as it
+    /// is written to a geth compatible genesis JSON file.
+    ///
+    /// This is the inverse of the [`From<Genesis>`] conversion, except that [Hardfork::Frontier]
+    /// is implied by the config.
+    pub fn to_genesis(&self) -> Genesis {
+        let block = |fork| match self.fork(fork) {
+            ForkCondition::Block(block) => Some(block),
+            _ => None,
+        };
+        let timestamp = |fork| self.fork(fork).as_timestamp();
+
+        let mut genesis = self.genesis.clone();
+        let config = &mut genesis.config;
+        config.chain_id = self.chain.id();
+        config.homestead_block = block(Hardfork::Homestead);
+        config.dao_fork_block = block(Hardfork::Dao);
+        config.dao_fork_support = config.dao_fork_block.is_some();
+        config.eip150_block = block(Hardfork::Tangerine);
+        config.eip155_block = block(Hardfork::SpuriousDragon);
+        config.eip158_block = block(Hardfork::SpuriousDragon);
+        config.byzantium_block = block(Hardfork::Byzantium);
+        config.constantinople_block = block(Hardfork::Constantinople);
+        config.petersburg_block = block(Hardfork::Petersburg);
+        config.istanbul_block = block(Hardfork::Istanbul);
+        config.muir_glacier_block = block(Hardfork::MuirGlacier);
+        config.berlin_block = block(Hardfork::Berlin);
+        config.london_block = block(Hardfork::London);
+        config.arrow_glacier_block = block(Hardfork::ArrowGlacier);
+        config.gray_glacier_block = block(Hardfork::GrayGlacier);
+        (config.terminal_total_difficulty, config.merge_netsplit_block) =
+            match self.fork(Hardfork::Paris) {
+                ForkCondition::TTD { total_difficulty, fork_block } => {
+                    (Some(total_difficulty), fork_block)
+                }
+                _ => (None, None),
+            };
+        config.shanghai_time = timestamp(Hardfork::Shanghai);
+        config.cancun_time = timestamp(Hardfork::Cancun);
+        genesis
+    }
 }
 
 impl From<Genesis> for ChainSpec {
@@ -741,21 +975,86 @@ impl From<AllGenesisFormats> for ChainSpec {
     }
 }
 
+/// The system calls that are executed as part of block processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SystemCallKind {
+    /// The pre-block call to the EIP-4788 beacon block root contract.
+    BeaconRoot,
+}
+
+impl SystemCallKind {
+    /// Returns the hardfork that activates this system call.
+    pub const fn hardfork(&self) -> Hardfork {
+        match self {
+            SystemCallKind::BeaconRoot => Hardfork::Cancun,
+        }
+    }
+
+    /// Returns the address of the system contract that is called.
+    pub const fn address(&self) -> Address {
+        match self {
+            SystemCallKind::BeaconRoot => BEACON_ROOTS_ADDRESS,
+        }
+    }
+}
+
+/// The total of basis points, e.g. the sum of the shares of a [ChainSpec::reward_split] or the
+/// upper bound of [ChainSpec::base_fee_recycle_bps].
+pub const REWARD_SPLIT_TOTAL_BPS: u16 = 10_000;
+
 /// A helper to build custom chain specs
 #[derive(Debug, Default, Clone)]
 pub struct ChainSpecBuilder {
     chain: Option<Chain>,
     genesis: Option<Genesis>,
     hardforks: BTreeMap<Hardfork, ForkCondition>,
+    reward_split: Option<Vec<(Address, u16)>>,
+    max_ommer_reward_per_block: Option<u128>,
+    system_call_gas_price: Option<U256>,
+    base_fee_recycle_bps: Option<u16>,
+    max_system_call_return_size: Option<usize>,
+    max_system_call_gas: Option<u64>,
+    withdrawal_amount_scale: Option<U256>,
+    disabled_system_calls: HashSet<SystemCallKind>,
+    withdrawal_vault: Option<Address>,
 }
 
 impl ChainSpecBuilder {
+    /// Construct a new builder from the given chain spec, to derive a spec with different
+    /// hardforks, chain ID or genesis from it.
+    pub fn from_existing(spec: &ChainSpec) -> Self {
+        Self {
+            chain: Some(spec.chain),
+            genesis: Some(spec.genesis.clone()),
+            hardforks: spec.hardforks.clone(),
+            reward_split: spec.reward_split.clone(),
+            max_ommer_reward_per_block: spec.max_ommer_reward_per_block,
+            system_call_gas_price: spec.system_call_gas_price,
+            base_fee_recycle_bps: spec.base_fee_recycle_bps,
+            max_system_call_return_size: Some(spec.max_system_call_return_size),
+            max_system_call_gas: Some(spec.max_system_call_gas),
+            withdrawal_amount_scale: Some(spec.withdrawal_amount_scale),
+            disabled_system_calls: spec.disabled_system_calls.clone(),
+            withdrawal_vault: spec.withdrawal_vault,
+        }
+    }
+
     /// Construct a new builder from the mainnet chain spec.
     pub fn mainnet() -> Self {
         Self {
             chain: Some(MAINNET.chain),
             genesis: Some(MAINNET.genesis.clone()),
             hardforks: MAINNET.hardforks.clone(),
+
+            reward_split: None,
+            max_ommer_reward_per_block: None,
+            system_call_gas_price: None,
+            base_fee_recycle_bps: None,
+            max_system_call_return_size: None,
+            max_system_call_gas: None,
+            withdrawal_amount_scale: None,
+            disabled_system_calls: HashSet::new(),
+            withdrawal_vault: None,
         }
     }
 
@@ -765,18 +1064,95 @@ impl ChainSpecBuilder {
         self
     }
 
+    /// Set the chain ID from its numeric value.
+    pub fn with_chain_id(self, chain_id: u64) -> Self {
+        self.chain(chain_id.into())
+    }
+
     /// Set the genesis block.
     pub fn genesis(mut self, genesis: Genesis) -> Self {
         self.genesis = Some(genesis);
         self
     }
 
+    /// Split the block reward among the given addresses, see [ChainSpec::reward_split].
+    ///
+    /// The shares must sum up to [REWARD_SPLIT_TOTAL_BPS], which is checked by
+    /// [ChainSpecBuilder::try_build].
+    pub fn reward_split(mut self, reward_split: Vec<(Address, u16)>) -> Self {
+        self.reward_split = Some(reward_split);
+        self
+    }
+
+    /// Cap the total reward paid to the ommer beneficiaries of a block, see
+    /// [ChainSpec::max_ommer_reward_per_block].
+    pub fn max_ommer_reward_per_block(mut self, max_ommer_reward: u128) -> Self {
+        self.max_ommer_reward_per_block = Some(max_ommer_reward);
+        self
+    }
+
+    /// Set the gas price of system calls, see [ChainSpec::system_call_gas_price].
+    pub fn system_call_gas_price(mut self, gas_price: U256) -> Self {
+        self.system_call_gas_price = Some(gas_price);
+        self
+    }
+
+    /// Recycle the given share of the burned base fee into the block reward, see
+    /// [ChainSpec::base_fee_recycle_bps].
+    ///
+    /// The share must not exceed [REWARD_SPLIT_TOTAL_BPS], which is checked by
+    /// [ChainSpecBuilder::try_build].
+    pub fn base_fee_recycle_bps(mut self, bps: u16) -> Self {
+        self.base_fee_recycle_bps = Some(bps);
+        self
+    }
+
+    /// Set the maximum size of the return data of system calls, see
+    /// [ChainSpec::max_system_call_return_size].
+    pub fn max_system_call_return_size(mut self, max_size: usize) -> Self {
+        self.max_system_call_return_size = Some(max_size);
+        self
+    }
+
+    /// Set the maximum total gas used by the system calls of a block, see
+    /// [ChainSpec::max_system_call_gas].
+    pub fn max_system_call_gas(mut self, max_gas: u64) -> Self {
+        self.max_system_call_gas = Some(max_gas);
+        self
+    }
+
+    /// Set the factor the withdrawal amounts are multiplied with, see
+    /// [ChainSpec::withdrawal_amount_scale].
+    pub fn withdrawal_amount_scale(mut self, scale: U256) -> Self {
+        self.withdrawal_amount_scale = Some(scale);
+        self
+    }
+
+    /// Skip the given system call even if its hardfork is active, see
+    /// [ChainSpec::disabled_system_calls].
+    pub fn disable_system_call(mut self, kind: SystemCallKind) -> Self {
+        self.disabled_system_calls.insert(kind);
+        self
+    }
+
+    /// Credit all withdrawals to the given vault contract, see [ChainSpec::withdrawal_vault].
+    pub fn withdrawal_vault(mut self, vault: Address) -> Self {
+        self.withdrawal_vault = Some(vault);
+        self
+    }
+
     /// Add the given fork with the given activation condition to the spec.
     pub fn with_fork(mut self, fork: Hardfork, condition: ForkCondition) -> Self {
         self.hardforks.insert(fork, condition);
         self
     }
 
+    /// Remove the given fork from the spec, so it is never activated.
+    pub fn without_fork(mut self, fork: Hardfork) -> Self {
+        self.hardforks.remove(&fork);
+        self
+    }
+
     /// Enable the Paris hardfork at the given TTD.
     ///
     /// Does not set the merge netsplit block.
@@ -888,21 +1264,108 @@ impl ChainSpecBuilder {
             hardforks: self.hardforks,
             paris_block_and_final_difficulty: None,
             deposit_contract: None,
+            reward_split: self.reward_split,
+            max_ommer_reward_per_block: self.max_ommer_reward_per_block,
+            system_call_gas_price: self.system_call_gas_price,
+            base_fee_recycle_bps: self.base_fee_recycle_bps,
+            max_system_call_return_size: self
+                .max_system_call_return_size
+                .unwrap_or(DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE),
+            max_system_call_gas: self.max_system_call_gas.unwrap_or(DEFAULT_MAX_SYSTEM_CALL_GAS),
+            withdrawal_amount_scale: self
+                .withdrawal_amount_scale
+                .unwrap_or_else(default_withdrawal_amount_scale),
+            disabled_system_calls: self.disabled_system_calls,
+            withdrawal_vault: self.withdrawal_vault,
             ..Default::default()
         }
     }
+
+    /// Build the resulting [`ChainSpec`], if the chain ID and genesis are set and the hardforks
+    /// activate in order.
+    ///
+    /// Every hardfork must activate no earlier than the hardforks that precede it, and a fork
+    /// activated by block number can't follow a fork activated by total difficulty or timestamp.
+    /// Forks that are never activated are ignored. The resulting spec must pass
+    /// [ChainSpec::validate].
+    pub fn try_build(self) -> Result<ChainSpec, ChainSpecBuilderError> {
+        if self.chain.is_none() {
+            return Err(ChainSpecBuilderError::MissingChain)
+        }
+        if self.genesis.is_none() {
+            return Err(ChainSpecBuilderError::MissingGenesis)
+        }
+
+        let mut previous: Option<(Hardfork, (u8, u64))> = None;
+        for (fork, condition) in &self.hardforks {
+            let activation = match *condition {
+                ForkCondition::Block(block) |
+                ForkCondition::TTD { fork_block: Some(block), .. } => (0, block),
+                ForkCondition::TTD { fork_block: None, .. } => (1, 0),
+                ForkCondition::Timestamp(timestamp) => (2, timestamp),
+                ForkCondition::Never => continue,
+            };
+            if let Some((previous, previous_activation)) = previous {
+                if activation < previous_activation {
+                    return Err(ChainSpecBuilderError::ForkOutOfOrder { fork: *fork, previous })
+                }
+            }
+            previous = Some((*fork, activation));
+        }
+
+        let spec = self.build();
+        spec.validate()?;
+        Ok(spec)
+    }
 }
 
 impl From<&Arc<ChainSpec>> for ChainSpecBuilder {
     fn from(value: &Arc<ChainSpec>) -> Self {
-        Self {
-            chain: Some(value.chain),
-            genesis: Some(value.genesis.clone()),
-            hardforks: value.hardforks.clone(),
-        }
+        Self::from_existing(value)
     }
 }
 
+/// Errors of [ChainSpecBuilder::try_build].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ChainSpecBuilderError {
+    /// The chain ID is not set.
+    #[error("the chain is required")]
+    MissingChain,
+    /// The genesis is not set.
+    #[error("the genesis is required")]
+    MissingGenesis,
+    /// A hardfork activates before a hardfork that precedes it.
+    #[error("hardfork {fork} activates before the preceding hardfork {previous}")]
+    ForkOutOfOrder {
+        /// The hardfork that activates too early.
+        fork: Hardfork,
+        /// The preceding hardfork.
+        previous: Hardfork,
+    },
+    /// The chain spec is invalid.
+    #[error(transparent)]
+    ChainSpec(#[from] ChainSpecError),
+}
+
+/// Errors of an invalid [ChainSpec].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ChainSpecError {
+    /// The shares of the reward split do not sum up to [REWARD_SPLIT_TOTAL_BPS].
+    #[error("reward split must sum up to {REWARD_SPLIT_TOTAL_BPS} basis points, got {total_bps}")]
+    InvalidRewardSplit {
+        /// The sum of the shares in basis points.
+        total_bps: u32,
+    },
+    /// The recycled share of the base fee exceeds [REWARD_SPLIT_TOTAL_BPS].
+    #[error(
+        "base fee recycle share must not exceed {REWARD_SPLIT_TOTAL_BPS} basis points, got {bps}"
+    )]
+    InvalidBaseFeeRecycleBps {
+        /// The share in basis points.
+        bps: u16,
+    },
+}
+
 /// The condition at which a fork is activated.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ForkCondition {
@@ -2293,4 +2756,127 @@ Post-merge hard forks (timestamp based):
             .fork(Hardfork::Paris)
             .active_at_ttd(HOLESKY.genesis.difficulty, HOLESKY.genesis.difficulty));
     }
+
+    #[test]
+    fn invalid_rewards_rejected() {
+        let builder = ChainSpecBuilder::mainnet();
+        assert_eq!(
+            builder.clone().reward_split(vec![(Address::ZERO, 5_000)]).try_build().unwrap_err(),
+            ChainSpecError::InvalidRewardSplit { total_bps: 5_000 }.into()
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .reward_split(vec![(Address::ZERO, 10_000), (Address::with_last_byte(1), 1)])
+                .try_build()
+                .unwrap_err(),
+            ChainSpecError::InvalidRewardSplit { total_bps: 10_001 }.into()
+        );
+        assert_eq!(
+            builder.clone().base_fee_recycle_bps(10_001).try_build().unwrap_err(),
+            ChainSpecError::InvalidBaseFeeRecycleBps { bps: 10_001 }.into()
+        );
+        assert!(builder
+            .reward_split(vec![(Address::ZERO, 5_000), (Address::with_last_byte(1), 5_000)])
+            .base_fee_recycle_bps(10_000)
+            .try_build()
+            .is_ok());
+
+        // deserialized specs skip the builder
+        let mut spec = MAINNET.as_ref().clone();
+        spec.reward_split = Some(vec![(Address::ZERO, u16::MAX)]);
+        assert_eq!(
+            spec.validate(),
+            Err(ChainSpecError::InvalidRewardSplit { total_bps: u16::MAX as u32 })
+        );
+    }
+
+    #[test]
+    fn derived_spec_forks_in_order() {
+        for spec in [&MAINNET, &GOERLI, &SEPOLIA, &HOLESKY, &DEV] {
+            assert!(ChainSpecBuilder::from_existing(spec).try_build().is_ok());
+        }
+
+        // cancun before shanghai
+        let res = ChainSpecBuilder::from_existing(&MAINNET)
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1_000))
+            .try_build();
+        assert_eq!(
+            res.unwrap_err(),
+            ChainSpecBuilderError::ForkOutOfOrder {
+                fork: Hardfork::Cancun,
+                previous: Hardfork::Shanghai
+            }
+        );
+
+        // block based fork after the merge
+        let res = ChainSpecBuilder::from_existing(&MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Block(20_000_000))
+            .try_build();
+        assert_eq!(
+            res.unwrap_err(),
+            ChainSpecBuilderError::ForkOutOfOrder {
+                fork: Hardfork::Shanghai,
+                previous: Hardfork::Paris
+            }
+        );
+
+        let spec = ChainSpecBuilder::from_existing(&MAINNET)
+            .without_fork(Hardfork::Dao)
+            .with_chain_id(1337)
+            .try_build()
+            .unwrap();
+        assert_eq!(spec.chain, Chain::from(1337));
+        assert_eq!(spec.fork(Hardfork::Dao), ForkCondition::Never);
+    }
+
+    #[test]
+    fn derived_spec_forkid_changes_after_override() {
+        let cancun = 1_710_000_000;
+        let spec = ChainSpecBuilder::from_existing(&MAINNET)
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(cancun))
+            .try_build()
+            .unwrap();
+        assert_eq!(spec.genesis_hash(), MAINNET.genesis_hash());
+
+        let before = Head { number: 19_000_000, timestamp: cancun - 1, ..Default::default() };
+        let after = Head { timestamp: cancun, ..before };
+        assert_eq!(spec.fork_id(&before), ForkId { next: cancun, ..MAINNET.fork_id(&before) });
+        assert_eq!(MAINNET.fork_id(&after).hash, MAINNET.fork_id(&before).hash);
+        assert_ne!(spec.fork_id(&after).hash, MAINNET.fork_id(&after).hash);
+        assert_eq!(spec.fork_id(&after).next, 0);
+
+        assert_eq!(spec.next_fork_after(before.timestamp), Some((Hardfork::Cancun, cancun)));
+        assert_eq!(spec.next_fork_after(after.timestamp), None);
+        assert_eq!(MAINNET.next_fork_after(0), Some((Hardfork::Shanghai, 1681338455)));
+
+        let active = spec.active_hardforks_at(before.number, before.timestamp);
+        assert_eq!(active.last(), Some(&Hardfork::Shanghai));
+        let active = spec.active_hardforks_at(after.number, after.timestamp);
+        assert_eq!(active.last(), Some(&Hardfork::Cancun));
+
+        // the merge is known for mainnet
+        assert!(MAINNET.active_hardforks_at(15537394, 0).contains(&Hardfork::Paris));
+        assert!(!MAINNET.active_hardforks_at(15537393, 0).contains(&Hardfork::Paris));
+    }
+
+    #[test]
+    fn derived_spec_genesis_json_roundtrip() {
+        let spec = ChainSpecBuilder::from_existing(&SEPOLIA)
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1_706_655_072))
+            .with_chain_id(1337)
+            .try_build()
+            .unwrap();
+
+        let json = serde_json::to_string(&spec.to_genesis()).unwrap();
+        let genesis: Genesis = serde_json::from_str(&json).unwrap();
+        let decoded = ChainSpec::from(genesis);
+
+        assert_eq!(decoded.chain, spec.chain);
+        assert_eq!(decoded.genesis_hash(), spec.genesis_hash());
+        let mut hardforks = spec.hardforks.clone();
+        hardforks.remove(&Hardfork::Frontier);
+        assert_eq!(decoded.hardforks, hardforks);
+        assert_eq!(decoded.to_genesis(), spec.to_genesis());
+    }
 }
@@ -31,6 +31,9 @@ pub const EPOCH_DURATION: Duration = Duration::from_secs(12 * EPOCH_SLOTS);
 /// The default block nonce in the beacon consensus
 pub const BEACON_NONCE: u64 = 0u64;
 
+/// The number of ancestor block hashes available to the `BLOCKHASH` opcode.
+pub const BLOCK_HASH_HISTORY: u64 = 256;
+
 /// The default Ethereum block gas limit.
 ///
 /// TODO: This should be a chain spec parameter.
@@ -139,6 +142,16 @@ pub const BEACON_ROOTS_ADDRESS: Address = address!("000F3df6D732807Ef1319fB7B8bB
 /// block.
 pub const SYSTEM_ADDRESS: Address = address!("fffffffffffffffffffffffffffffffffffffffe");
 
+/// The default maximum size in bytes of the return data of a system call, see
+/// [ChainSpec::max_system_call_return_size](crate::ChainSpec::max_system_call_return_size).
+pub const DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE: usize = 1024 * 1024;
+
+/// The default maximum total gas used by the pre-block system calls of a block, see
+/// [ChainSpec::max_system_call_gas](crate::ChainSpec::max_system_call_gas).
+///
+/// This is the gas limit of a single system call.
+pub const DEFAULT_MAX_SYSTEM_CALL_GAS: u64 = 30_000_000;
+
 #[cfg(test)]
 mod tests {
     use super::*;
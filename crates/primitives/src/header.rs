@@ -1,6 +1,6 @@
 use crate::{
-    basefee::calculate_next_block_base_fee,
-    eip4844::{calc_blob_gasprice, calculate_excess_blob_gas},
+    basefee::{calculate_next_block_base_fee, next_block_excess_blob_gas},
+    eip4844::calc_blob_gasprice,
     keccak256,
     proofs::{EMPTY_LIST_HASH, EMPTY_ROOT},
     Address, BaseFeeParams, BlockBodyRoots, BlockHash, BlockNumHash, BlockNumber, Bloom, Bytes,
@@ -200,7 +200,8 @@ impl Header {
 
     /// Calculate base fee for next block according to the EIP-1559 spec.
     ///
-    /// Returns a `None` if no base fee is set, no EIP-1559 support
+    /// Returns a `None` if no base fee is set, no EIP-1559 support. This does not account for the
+    /// London transition, see [next_block_base_fee](crate::basefee::next_block_base_fee).
     pub fn next_block_base_fee(&self, base_fee_params: BaseFeeParams) -> Option<u64> {
         Some(calculate_next_block_base_fee(
             self.gas_used,
@@ -214,7 +215,7 @@ impl Header {
     ///
     /// Returns a `None` if no excess blob gas is set, no EIP-4844 support
     pub fn next_block_excess_blob_gas(&self) -> Option<u64> {
-        Some(calculate_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
+        next_block_excess_blob_gas(self)
     }
 
     /// Seal the header with a known hash.
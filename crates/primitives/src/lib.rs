@@ -46,6 +46,7 @@ mod storage;
 /// Helpers for working with transactions
 pub mod transaction;
 pub mod trie;
+mod units;
 mod withdrawal;
 
 pub use account::{Account, Bytecode};
@@ -56,8 +57,8 @@ pub use block::{
 pub use bytes::{Buf, BufMut, BytesMut};
 pub use chain::{
     AllGenesisFormats, BaseFeeParams, Chain, ChainInfo, ChainSpec, ChainSpecBuilder,
-    DisplayHardforks, ForkCondition, ForkTimestamps, NamedChain, DEV, GOERLI, HOLESKY, MAINNET,
-    SEPOLIA,
+    ChainSpecBuilderError, ChainSpecError, DisplayHardforks, ForkCondition, ForkTimestamps,
+    NamedChain, SystemCallKind, DEV, GOERLI, HOLESKY, MAINNET, REWARD_SPLIT_TOTAL_BPS, SEPOLIA,
 };
 pub use compression::*;
 pub use constants::{
@@ -94,6 +95,7 @@ pub use transaction::{
     TxEip4844, TxLegacy, TxType, TxValue, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
     EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
 };
+pub use units::{Gwei, Wei};
 pub use withdrawal::Withdrawal;
 
 // Re-exports
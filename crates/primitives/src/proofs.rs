@@ -3,8 +3,8 @@
 use crate::{
     b256, keccak256,
     trie::{HashBuilder, Nibbles},
-    Address, GenesisAccount, Header, Log, ReceiptWithBloom, ReceiptWithBloomRef, TransactionSigned,
-    Withdrawal, B256,
+    Address, Bytes, GenesisAccount, Header, Log, ReceiptWithBloom, ReceiptWithBloomRef,
+    TransactionSigned, Withdrawal, B256,
 };
 use alloy_rlp::Encodable;
 use bytes::{BufMut, BytesMut};
@@ -60,6 +60,36 @@ where
     hb.root()
 }
 
+/// Returns the proof of the item at `index` in the trie of [ordered_trie_root_with_encoder].
+///
+/// The proof contains the RLP encoded trie nodes on the path from the root to the leaf of the
+/// item, whose key is the RLP encoded index. The item must be within the bounds of the slice.
+pub fn ordered_trie_proof_with_encoder<T, F>(items: &[T], index: usize, mut encode: F) -> Vec<Bytes>
+where
+    F: FnMut(&T, &mut dyn BufMut),
+{
+    let mut index_buffer = BytesMut::new();
+    let mut value_buffer = BytesMut::new();
+
+    index.encode(&mut index_buffer);
+    let mut hb = HashBuilder::default().with_proof_retainer(vec![Nibbles::unpack(&index_buffer)]);
+    let items_len = items.len();
+    for i in 0..items_len {
+        let index = adjust_index_for_rlp(i, items_len);
+
+        index_buffer.clear();
+        index.encode(&mut index_buffer);
+
+        value_buffer.clear();
+        encode(&items[index], &mut value_buffer);
+
+        hb.add_leaf(Nibbles::unpack(&index_buffer), &value_buffer);
+    }
+
+    let _ = hb.root();
+    hb.take_proofs().into_values().collect()
+}
+
 /// Calculate a transaction root.
 ///
 /// `(rlp(index), encoded(tx))` pairs.
@@ -70,6 +100,16 @@ where
     ordered_trie_root_with_encoder(transactions, |tx: &T, buf| tx.as_ref().encode_inner(buf, false))
 }
 
+/// Returns the proof of the transaction at `index` in the trie of [calculate_transaction_root].
+pub fn transaction_inclusion_proof<T>(transactions: &[T], index: usize) -> Vec<Bytes>
+where
+    T: AsRef<TransactionSigned>,
+{
+    ordered_trie_proof_with_encoder(transactions, index, |tx: &T, buf| {
+        tx.as_ref().encode_inner(buf, false)
+    })
+}
+
 /// Calculates the root hash of the withdrawals.
 pub fn calculate_withdrawals_root(withdrawals: &[Withdrawal]) -> B256 {
     ordered_trie_root(withdrawals)
@@ -80,6 +120,13 @@ pub fn calculate_receipt_root(receipts: &[ReceiptWithBloom]) -> B256 {
     ordered_trie_root_with_encoder(receipts, |r, buf| r.encode_inner(buf, false))
 }
 
+/// Returns the proof of the receipt at `index` in the trie of [calculate_receipt_root].
+///
+/// Typed receipts are encoded with their type prefix, the same way as for the receipts root.
+pub fn receipt_inclusion_proof(receipts: &[ReceiptWithBloom], index: usize) -> Vec<Bytes> {
+    ordered_trie_proof_with_encoder(receipts, index, |r, buf| r.encode_inner(buf, false))
+}
+
 /// Calculates the receipt root for a header for the reference type of [ReceiptWithBloom].
 ///
 /// NOTE: Prefer [calculate_receipt_root] if you have log blooms memoized.
@@ -189,6 +236,133 @@ mod tests {
         assert_eq!(root, b256!("fe70ae4a136d98944951b2123859698d59ad251a381abc9960fa81cae3d0d4a0"));
     }
 
+    /// Verifies the proof of the key against the root independently of the hash builder, and
+    /// returns the proven value.
+    fn verify_proof(root: B256, key: &[u8], proof: &[Bytes]) -> Option<Vec<u8>> {
+        let nibbles = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect::<Vec<_>>();
+        let mut path = nibbles.as_slice();
+        let mut reference = root.to_vec();
+        for node in proof {
+            // nodes shorter than 32 bytes are embedded in their parent instead of hashed
+            if reference.len() == 32 {
+                assert_eq!(keccak256(node).as_slice(), reference.as_slice());
+            } else {
+                assert_eq!(node.as_ref(), reference.as_slice());
+            }
+
+            let items = rlp_list_items(node);
+            match items.len() {
+                17 => {
+                    let Some((nibble, rest)) = path.split_first() else {
+                        return Some(items[16].clone())
+                    };
+                    reference = items[*nibble as usize].clone();
+                    path = rest;
+                }
+                2 => {
+                    // hex prefix encoded path, the flags are in the high nibble of the first byte
+                    let encoded = &items[0];
+                    let mut node_path = Vec::new();
+                    if encoded[0] & 0x10 != 0 {
+                        node_path.push(encoded[0] & 0x0f);
+                    }
+                    node_path.extend(encoded[1..].iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+                    let rest = path.strip_prefix(node_path.as_slice())?;
+                    if encoded[0] & 0x20 != 0 {
+                        return rest.is_empty().then(|| items[1].clone())
+                    }
+                    reference = items[1].clone();
+                    path = rest;
+                }
+                _ => panic!("invalid trie node"),
+            }
+            if reference.is_empty() {
+                return None
+            }
+        }
+        None
+    }
+
+    /// Returns the payloads of the strings and the encodings of the embedded lists in the RLP
+    /// list.
+    fn rlp_list_items(mut buf: &[u8]) -> Vec<Vec<u8>> {
+        let header = alloy_rlp::Header::decode(&mut buf).unwrap();
+        assert!(header.list);
+        let mut items = Vec::new();
+        while !buf.is_empty() {
+            let item = buf;
+            let header = alloy_rlp::Header::decode(&mut buf).unwrap();
+            let (payload, rest) = buf.split_at(header.payload_length);
+            let encoded = &item[..item.len() - rest.len()];
+            items.push(if header.list { encoded.to_vec() } else { payload.to_vec() });
+            buf = rest;
+        }
+        items
+    }
+
+    #[test]
+    fn receipt_inclusion_proofs() {
+        let tx_types = [TxType::Legacy, TxType::EIP2930, TxType::EIP1559, TxType::EIP4844];
+        for len in [1, 3, 130] {
+            let receipts = (0..len)
+                .map(|i| {
+                    Receipt {
+                        tx_type: tx_types[i % tx_types.len()],
+                        success: i % 3 != 0,
+                        cumulative_gas_used: 21_000 * (i as u64 + 1),
+                        logs: vec![Log {
+                            address: Address::with_last_byte(i as u8),
+                            topics: vec![B256::with_last_byte(i as u8)],
+                            data: Default::default(),
+                        }],
+                    }
+                    .with_bloom()
+                })
+                .collect::<Vec<_>>();
+            let root = calculate_receipt_root(&receipts);
+
+            // first, middle and last receipt
+            for index in [0, len / 2, len - 1] {
+                let proof = receipt_inclusion_proof(&receipts, index);
+                let value = verify_proof(root, &alloy_rlp::encode(index), &proof).unwrap();
+
+                let mut expected = Vec::new();
+                receipts[index].encode_inner(&mut expected, false);
+                assert_eq!(value, expected);
+
+                let tx_type = receipts[index].receipt.tx_type;
+                if tx_type != TxType::Legacy {
+                    assert_eq!(value[0], u8::from(tx_type));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transaction_inclusion_proofs() {
+        let data = &hex!("f90262f901f9a092230ce5476ae868e98c7979cfc165a93f8b6ad1922acf2df62e340916efd49da01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa02307107a867056ca33b5087e77c4174f47625e48fb49f1c70ced34890ddd88f3a08151d548273f6683169524b66ca9fe338b9ce42bc3540046c828fd939ae23bcba0c598f69a5674cae9337261b669970e24abc0b46e6d284372a239ec8ccbf20b0ab901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000018502540be40082a8618203e800a00000000000000000000000000000000000000000000000000000000000000000880000000000000000f863f861800a8405f5e10094100000000000000000000000000000000000000080801ba07e09e26678ed4fac08a249ebe8ed680bf9051a5e14ad223e4b2b9d26e0208f37a05f6e3f188e3e6eab7d7d3b6568f5eac7d687b08d307d3154ccd8c87b4630509bc0");
+        let block = Block::decode(&mut data.as_slice()).unwrap();
+        // single transaction block, proven against the transactions root of the header
+        let proof = transaction_inclusion_proof(&block.body, 0);
+        let value = verify_proof(block.transactions_root, &alloy_rlp::encode(0usize), &proof);
+        let mut expected = Vec::new();
+        block.body[0].encode_inner(&mut expected, false);
+        assert_eq!(value, Some(expected));
+
+        for len in [3, 130] {
+            let transactions = vec![block.body[0].clone(); len];
+            let root = calculate_transaction_root(&transactions);
+            for index in [0, len / 2, len - 1] {
+                let proof = transaction_inclusion_proof(&transactions, index);
+                let value = verify_proof(root, &alloy_rlp::encode(index), &proof).unwrap();
+
+                let mut expected = Vec::new();
+                transactions[index].encode_inner(&mut expected, false);
+                assert_eq!(value, expected);
+            }
+        }
+    }
+
     #[test]
     fn check_withdrawals_root() {
         // Single withdrawal, amount 0
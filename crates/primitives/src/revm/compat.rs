@@ -1,10 +1,6 @@
 use crate::{
-    revm_primitives::{AccountInfo, Log},
-    Account, Address, Log as RethLog, TransactionKind, KECCAK_EMPTY, U256,
-};
-use revm::{
-    interpreter::gas::initial_tx_gas,
-    primitives::{MergeSpec, ShanghaiSpec},
+    revm_primitives::{AccountInfo, InvalidTransaction, Log, SpecId, MAX_INITCODE_SIZE},
+    Account, Address, Log as RethLog, KECCAK_EMPTY, U256,
 };
 
 /// Check equality between Revm and Reth `Log`s.
@@ -43,19 +39,286 @@ pub fn into_revm_acc(reth_acc: Account) -> AccountInfo {
     }
 }
 
-/// Calculates the Intrinsic Gas usage for a Transaction
+/// Gas charged for every transaction.
+const TRANSACTION_GAS: u64 = 21_000;
+
+/// Gas charged additionally for contract creations since Homestead.
+const CREATE_GAS: u64 = 32_000;
+
+/// Gas charged per storage key of the access list since Berlin (EIP-2930).
+const ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1_900;
+
+/// Gas charged per address of the access list since Berlin (EIP-2930).
+const ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+
+/// Gas charged per 32-byte word of init code since Shanghai (EIP-3860).
+const INITCODE_WORD_GAS: u64 = 2;
+
+/// Gas charged per token of calldata by the calldata floor pricing (EIP-7623).
+pub const TOTAL_COST_FLOOR_PER_TOKEN: u64 = 10;
+
+/// Calculates the intrinsic gas of a transaction for the given spec, i.e. the gas that is charged
+/// before the transaction is executed:
+///  - 21000 gas, and 32000 additional gas for contract creations since Homestead
+///  - 4 gas per zero byte and 68 gas per non-zero byte of input, 16 gas since Istanbul (EIP-2028)
+///  - 2400 gas per address and 1900 gas per storage key of the access list since Berlin, including
+///    duplicate entries
+///  - 2 gas per 32-byte word of init code since Shanghai (EIP-3860)
+///
+/// If the calldata floor pricing is active for the spec and the [calldata_floor_gas] exceeds the
+/// intrinsic gas, the floor is returned instead, so the result is always the minimum gas limit of
+/// the transaction.
 ///
-/// Caution: This only checks past the Merge hardfork.
-#[inline]
-pub fn calculate_intrinsic_gas_after_merge(
+/// Returns an error if the init code of a contract creation exceeds the maximum init code size
+/// since Shanghai.
+pub fn intrinsic_gas(
     input: &[u8],
-    kind: &TransactionKind,
+    is_create: bool,
     access_list: &[(Address, Vec<U256>)],
-    is_shanghai: bool,
-) -> u64 {
-    if is_shanghai {
-        initial_tx_gas::<ShanghaiSpec>(input, kind.is_create(), access_list)
-    } else {
-        initial_tx_gas::<MergeSpec>(input, kind.is_create(), access_list)
+    spec_id: SpecId,
+) -> Result<u64, InvalidTransaction> {
+    let zero_bytes = input.iter().filter(|byte| **byte == 0).count() as u64;
+    let non_zero_bytes = input.len() as u64 - zero_bytes;
+    let non_zero_byte_gas = if spec_id >= SpecId::ISTANBUL { 16 } else { 68 };
+
+    let mut gas = TRANSACTION_GAS + zero_bytes * 4 + non_zero_bytes * non_zero_byte_gas;
+
+    if is_create && spec_id >= SpecId::HOMESTEAD {
+        gas += CREATE_GAS;
+    }
+
+    if spec_id >= SpecId::BERLIN {
+        let storage_keys = access_list.iter().map(|(_, keys)| keys.len() as u64).sum::<u64>();
+        gas += access_list.len() as u64 * ACCESS_LIST_ADDRESS_GAS +
+            storage_keys * ACCESS_LIST_STORAGE_KEY_GAS;
+    }
+
+    if is_create && spec_id >= SpecId::SHANGHAI {
+        if input.len() > MAX_INITCODE_SIZE {
+            return Err(InvalidTransaction::CreateInitcodeSizeLimit)
+        }
+        gas += (input.len() as u64 + 31) / 32 * INITCODE_WORD_GAS;
+    }
+
+    Ok(gas.max(calldata_floor_gas(input, spec_id)))
+}
+
+/// Calculates the minimum gas that a transaction with the given input uses under the calldata
+/// floor pricing (EIP-7623), which is 21000 gas and 10 gas per token of calldata, where a zero
+/// byte counts as one token and a non-zero byte as four tokens.
+///
+/// Returns zero if the floor pricing is not active for the spec, see
+/// [is_calldata_floor_active].
+pub fn calldata_floor_gas(input: &[u8], spec_id: SpecId) -> u64 {
+    if !is_calldata_floor_active(spec_id) {
+        return 0
+    }
+    let zero_bytes = input.iter().filter(|byte| **byte == 0).count() as u64;
+    let tokens = zero_bytes + (input.len() as u64 - zero_bytes) * 4;
+    TRANSACTION_GAS + tokens * TOTAL_COST_FLOOR_PER_TOKEN
+}
+
+/// Returns whether the calldata floor pricing (EIP-7623) is active for the given spec.
+///
+/// The floor pricing is not scheduled for any hardfork known to revm yet, so it is only active for
+/// [SpecId::LATEST].
+pub fn is_calldata_floor_active(spec_id: SpecId) -> bool {
+    spec_id == SpecId::LATEST
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bytes, B256};
+    use rand::{thread_rng, Rng};
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        interpreter::gas::initial_tx_gas,
+        primitives::{
+            BerlinSpec, ByzantiumSpec, CancunSpec, EVMError, FrontierSpec, HomesteadSpec,
+            IstanbulSpec, LondonSpec, MergeSpec, PetersburgSpec, ShanghaiSpec, SpuriousDragonSpec,
+            TangerineSpec, TransactTo,
+        },
+        EVM,
+    };
+
+    /// All specs up to Cancun, i.e. the hardforks known to revm.
+    const SPECS: [SpecId; 12] = [
+        SpecId::FRONTIER,
+        SpecId::HOMESTEAD,
+        SpecId::TANGERINE,
+        SpecId::SPURIOUS_DRAGON,
+        SpecId::BYZANTIUM,
+        SpecId::PETERSBURG,
+        SpecId::ISTANBUL,
+        SpecId::BERLIN,
+        SpecId::LONDON,
+        SpecId::MERGE,
+        SpecId::SHANGHAI,
+        SpecId::CANCUN,
+    ];
+
+    fn revm_initial_tx_gas(
+        input: &[u8],
+        is_create: bool,
+        access_list: &[(Address, Vec<U256>)],
+        spec_id: SpecId,
+    ) -> u64 {
+        match spec_id {
+            SpecId::FRONTIER => initial_tx_gas::<FrontierSpec>(input, is_create, access_list),
+            SpecId::HOMESTEAD => initial_tx_gas::<HomesteadSpec>(input, is_create, access_list),
+            SpecId::TANGERINE => initial_tx_gas::<TangerineSpec>(input, is_create, access_list),
+            SpecId::SPURIOUS_DRAGON => {
+                initial_tx_gas::<SpuriousDragonSpec>(input, is_create, access_list)
+            }
+            SpecId::BYZANTIUM => initial_tx_gas::<ByzantiumSpec>(input, is_create, access_list),
+            SpecId::PETERSBURG => initial_tx_gas::<PetersburgSpec>(input, is_create, access_list),
+            SpecId::ISTANBUL => initial_tx_gas::<IstanbulSpec>(input, is_create, access_list),
+            SpecId::BERLIN => initial_tx_gas::<BerlinSpec>(input, is_create, access_list),
+            SpecId::LONDON => initial_tx_gas::<LondonSpec>(input, is_create, access_list),
+            SpecId::MERGE => initial_tx_gas::<MergeSpec>(input, is_create, access_list),
+            SpecId::SHANGHAI => initial_tx_gas::<ShanghaiSpec>(input, is_create, access_list),
+            SpecId::CANCUN => initial_tx_gas::<CancunSpec>(input, is_create, access_list),
+            spec_id => unreachable!("unexpected spec {spec_id:?}"),
+        }
+    }
+
+    /// Executes the transaction with revm and returns whether it passed the intrinsic gas check.
+    fn passes_revm_validation(
+        input: &[u8],
+        is_create: bool,
+        access_list: &[(Address, Vec<U256>)],
+        gas_limit: u64,
+        spec_id: SpecId,
+    ) -> bool {
+        let mut evm = EVM::new();
+        evm.database(CacheDB::new(EmptyDB::default()));
+        evm.env.cfg.spec_id = spec_id;
+        evm.env.block.prevrandao = Some(B256::ZERO);
+        evm.env.block.set_blob_excess_gas_and_price(0);
+        evm.env.tx.transact_to = if is_create {
+            TransactTo::create()
+        } else {
+            TransactTo::Call(Address::with_last_byte(0x42))
+        };
+        evm.env.tx.data = Bytes::copy_from_slice(input);
+        evm.env.tx.access_list = access_list.to_vec();
+        evm.env.tx.gas_limit = gas_limit;
+
+        match evm.transact() {
+            Ok(_) => true,
+            Err(EVMError::Transaction(InvalidTransaction::CallGasCostMoreThanGasLimit)) => false,
+            Err(err) => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn intrinsic_gas_per_fork() {
+        // two zero bytes and three non-zero bytes
+        let input = [0, 0, 1, 2, 0xff];
+        let address = Address::with_last_byte(1);
+        // duplicate storage keys and addresses are charged for every entry
+        let access_list =
+            vec![(address, vec![U256::from(1), U256::from(1)]), (address, Vec::new())];
+
+        let call = |spec_id| intrinsic_gas(&input, false, &access_list, spec_id).unwrap();
+        let create = |spec_id| intrinsic_gas(&input, true, &access_list, spec_id).unwrap();
+
+        assert_eq!(call(SpecId::FRONTIER), 21_000 + 2 * 4 + 3 * 68);
+        assert_eq!(create(SpecId::FRONTIER), 21_000 + 2 * 4 + 3 * 68);
+        assert_eq!(create(SpecId::HOMESTEAD), 53_000 + 2 * 4 + 3 * 68);
+        assert_eq!(call(SpecId::PETERSBURG), 21_000 + 2 * 4 + 3 * 68);
+        assert_eq!(call(SpecId::ISTANBUL), 21_000 + 2 * 4 + 3 * 16);
+        assert_eq!(create(SpecId::ISTANBUL), 53_000 + 2 * 4 + 3 * 16);
+
+        let access_list_gas = 2 * 2_400 + 2 * 1_900;
+        assert_eq!(call(SpecId::BERLIN), 21_000 + 2 * 4 + 3 * 16 + access_list_gas);
+        assert_eq!(call(SpecId::LONDON), call(SpecId::BERLIN));
+        assert_eq!(call(SpecId::MERGE), call(SpecId::BERLIN));
+        assert_eq!(create(SpecId::MERGE), 53_000 + 2 * 4 + 3 * 16 + access_list_gas);
+
+        // one word of init code
+        assert_eq!(create(SpecId::SHANGHAI), create(SpecId::MERGE) + 2);
+        assert_eq!(call(SpecId::SHANGHAI), call(SpecId::MERGE));
+        assert_eq!(create(SpecId::CANCUN), create(SpecId::SHANGHAI));
+        assert_eq!(call(SpecId::CANCUN), call(SpecId::SHANGHAI));
+    }
+
+    #[test]
+    fn intrinsic_gas_max_initcode() {
+        let max_initcode = vec![0; MAX_INITCODE_SIZE];
+        assert_eq!(
+            intrinsic_gas(&max_initcode, true, &[], SpecId::SHANGHAI),
+            Ok(53_000 + MAX_INITCODE_SIZE as u64 * 4 + MAX_INITCODE_SIZE as u64 / 32 * 2)
+        );
+
+        let too_large = vec![0; MAX_INITCODE_SIZE + 1];
+        assert_eq!(
+            intrinsic_gas(&too_large, true, &[], SpecId::SHANGHAI),
+            Err(InvalidTransaction::CreateInitcodeSizeLimit)
+        );
+        assert_eq!(
+            intrinsic_gas(&too_large, true, &[], SpecId::MERGE),
+            Ok(53_000 + (MAX_INITCODE_SIZE as u64 + 1) * 4)
+        );
+        // the limit only applies to init code
+        assert!(intrinsic_gas(&too_large, false, &[], SpecId::SHANGHAI).is_ok());
+    }
+
+    #[test]
+    fn calldata_floor() {
+        let non_zero = vec![1; 100];
+        let zero = vec![0; 100];
+
+        for spec_id in SPECS {
+            assert!(!is_calldata_floor_active(spec_id));
+            assert_eq!(calldata_floor_gas(&non_zero, spec_id), 0);
+        }
+        assert_eq!(intrinsic_gas(&non_zero, false, &[], SpecId::CANCUN), Ok(21_000 + 100 * 16));
+
+        assert_eq!(calldata_floor_gas(&non_zero, SpecId::LATEST), 21_000 + 400 * 10);
+        assert_eq!(calldata_floor_gas(&zero, SpecId::LATEST), 21_000 + 100 * 10);
+        assert_eq!(calldata_floor_gas(&[], SpecId::LATEST), 21_000);
+
+        // the floor applies if it exceeds the intrinsic gas
+        assert_eq!(intrinsic_gas(&non_zero, false, &[], SpecId::LATEST), Ok(21_000 + 400 * 10));
+        assert_eq!(intrinsic_gas(&zero, false, &[], SpecId::LATEST), Ok(21_000 + 100 * 10));
+        // but not if the intrinsic gas is higher, e.g. for creates
+        assert_eq!(
+            intrinsic_gas(&non_zero, true, &[], SpecId::LATEST),
+            Ok(53_000 + 100 * 16 + 4 * 2)
+        );
+    }
+
+    /// The pool and the gas estimation use [intrinsic_gas], and the execution of blocks uses revm,
+    /// so both have to agree for all transactions.
+    #[test]
+    fn intrinsic_gas_matches_revm() {
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let input: Vec<u8> = (0..rng.gen_range(0..100))
+                .map(|_| if rng.gen_bool(0.5) { 0 } else { rng.gen() })
+                .collect();
+            let is_create = rng.gen_bool(0.3);
+            let access_list: Vec<(Address, Vec<U256>)> = (0..rng.gen_range(0..3))
+                .map(|_| {
+                    // few distinct values to produce duplicate entries
+                    let address = Address::with_last_byte(rng.gen_range(0..2));
+                    let keys = (0..rng.gen_range(0..3)).map(|_| U256::from(rng.gen_range(0..2)));
+                    (address, keys.collect())
+                })
+                .collect();
+
+            for spec_id in SPECS {
+                let gas = intrinsic_gas(&input, is_create, &access_list, spec_id).unwrap();
+                assert_eq!(
+                    gas,
+                    revm_initial_tx_gas(&input, is_create, &access_list, spec_id),
+                    "{spec_id:?}"
+                );
+                assert!(passes_revm_validation(&input, is_create, &access_list, gas, spec_id));
+                assert!(!passes_revm_validation(&input, is_create, &access_list, gas - 1, spec_id));
+            }
+        }
     }
 }
@@ -41,6 +41,8 @@ pub fn fill_cfg_env(
     cfg_env.chain_id = chain_spec.chain().id();
     cfg_env.spec_id = spec_id;
     cfg_env.perf_analyse_created_bytecodes = AnalysisKind::Analyse;
+    // the memory of consensus execution is only bounded by gas
+    cfg_env.memory_limit = u64::MAX;
 }
 
 /// Fill block environment from Block.
@@ -129,14 +131,51 @@ pub fn tx_env_with_recovered(transaction: &TransactionSignedEcRecovered) -> TxEn
 ///  part of the call
 ///  * if no code exists at `BEACON_ROOTS_ADDRESS`, the call must fail silently
 pub fn fill_tx_env_with_beacon_root_contract_call(env: &mut Env, parent_beacon_block_root: B256) {
-    env.tx = TxEnv {
+    fill_tx_env_with_system_call(
+        env,
+        BEACON_ROOTS_ADDRESS,
+        Bytes::copy_from_slice(parent_beacon_block_root.as_slice()),
+    )
+}
+
+/// Fill transaction environment with a system call of the given contract, see
+/// [system_call_tx].
+///
+/// The block gas limit is raised to the gas limit of the call and the base fee is disabled.
+pub fn fill_tx_env_with_system_call(env: &mut Env, contract: Address, input: Bytes) {
+    env.tx = system_call_tx(contract, input);
+
+    // ensure the block gas limit is >= the tx
+    env.block.gas_limit = U256::from(env.tx.gas_limit);
+
+    // disable the base fee check for this call by setting the base fee to zero
+    env.block.basefee = U256::ZERO;
+}
+
+/// Returns the [TxEnv] of the system call to `BEACON_ROOTS_ADDRESS` that stores the
+/// `parent_beacon_block_root`.
+///
+/// See [fill_tx_env_with_beacon_root_contract_call] for the block env changes the call requires.
+pub fn beacon_root_call_tx(parent_beacon_block_root: B256) -> TxEnv {
+    system_call_tx(
+        BEACON_ROOTS_ADDRESS,
+        Bytes::copy_from_slice(parent_beacon_block_root.as_slice()),
+    )
+}
+
+/// Returns the [TxEnv] of a system call to the given contract with the given input.
+///
+/// The call is made by `SYSTEM_ADDRESS` with a gas limit of `30_000_000`, no value and no gas
+/// price.
+pub fn system_call_tx(contract: Address, input: Bytes) -> TxEnv {
+    TxEnv {
         caller: SYSTEM_ADDRESS,
-        transact_to: TransactTo::Call(BEACON_ROOTS_ADDRESS),
+        transact_to: TransactTo::Call(contract),
         // Explicitly set nonce to None so revm does not do any nonce checks
         nonce: None,
         gas_limit: 30_000_000,
         value: U256::ZERO,
-        data: parent_beacon_block_root.0.to_vec().into(),
+        data: input,
         // Setting the gas price to zero enforces that no value is transferred as part of the call,
         // and that the call will not count against the block's gas limit
         gas_price: U256::ZERO,
@@ -149,13 +188,7 @@ pub fn fill_tx_env_with_beacon_root_contract_call(env: &mut Env, parent_beacon_b
         // blob fields can be None for this tx
         blob_hashes: Vec::new(),
         max_fee_per_blob_gas: None,
-    };
-
-    // ensure the block gas limit is >= the tx
-    env.block.gas_limit = U256::from(env.tx.gas_limit);
-
-    // disable the base fee check for this call by setting the base fee to zero
-    env.block.basefee = U256::ZERO;
+    }
 }
 
 /// Fill transaction environment from [TransactionSignedEcRecovered].
@@ -293,3 +326,20 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beacon_root_call_tx_env() {
+        let root = B256::with_last_byte(0x42);
+        let tx = beacon_root_call_tx(root);
+        assert_eq!(tx.caller, SYSTEM_ADDRESS);
+        assert_eq!(tx.transact_to, TransactTo::Call(BEACON_ROOTS_ADDRESS));
+        assert_eq!(tx.data.len(), 32);
+        assert_eq!(tx.data.as_ref(), root.as_slice());
+        assert_eq!(tx.value, U256::ZERO);
+        assert_eq!(tx.nonce, None);
+    }
+}
@@ -4,8 +4,8 @@
 /// These functions enable the conversion of data structures between the two implementations, such
 /// as converting `Log` structures, `AccountInfo`, and `Account` objects.
 ///
-/// Additionally, it provides a function to calculate intrinsic gas usage for transactions beyond
-/// the Merge hardfork, offering compatibility for both Shanghai and Merge Ethereum specifications.
+/// Additionally, it provides functions to calculate the intrinsic gas and the calldata floor gas
+/// of transactions for all Ethereum specifications.
 ///
 /// These utilities facilitate interoperability and data exchange between Revm and Reth
 /// implementations.
@@ -5,6 +5,23 @@ use bytes::Buf;
 use reth_codecs::{derive_arbitrary, Compact};
 use serde::{Deserialize, Serialize};
 
+/// The order of the secp256k1 curve.
+const SECP256K1N: U256 = U256::from_limbs([
+    0xbfd25e8cd0364141,
+    0xbaaedce6af48a03b,
+    0xfffffffffffffffe,
+    0xffffffffffffffff,
+]);
+
+/// The order of the secp256k1 curve divided by two, the upper bound of `s` since
+/// [EIP-2](https://eips.ethereum.org/EIPS/eip-2).
+const SECP256K1N_HALF: U256 = U256::from_limbs([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
 /// r, s: Values corresponding to the signature of the
 /// transaction and used to determine the sender of
 /// the transaction; formally Tr and Ts. This is expanded in Appendix F of yellow paper.
@@ -114,6 +131,16 @@ impl Signature {
         })
     }
 
+    /// Returns true if `r` and `s` are in the range of valid secp256k1 signatures and `s` is in the
+    /// lower half of the curve order, as required since
+    /// [EIP-2](https://eips.ethereum.org/EIPS/eip-2).
+    pub fn is_in_range(&self) -> bool {
+        self.r > U256::ZERO &&
+            self.r < SECP256K1N &&
+            self.s > U256::ZERO &&
+            self.s <= SECP256K1N_HALF
+    }
+
     /// Recover signer address from message hash.
     pub fn recover_signer(&self, hash: B256) -> Option<Address> {
         let mut sig: [u8; 65] = [0; 65];
@@ -249,4 +276,15 @@ mod tests {
 
         assert!(signature.size() >= 65);
     }
+
+    #[test]
+    fn test_is_in_range() {
+        let signature = Signature { r: U256::from(1), s: U256::from(1), odd_y_parity: false };
+        assert!(signature.is_in_range());
+
+        assert!(!Signature { r: U256::ZERO, ..signature }.is_in_range());
+        assert!(!Signature { r: super::SECP256K1N, ..signature }.is_in_range());
+        assert!(Signature { s: super::SECP256K1N_HALF, ..signature }.is_in_range());
+        assert!(!Signature { s: super::SECP256K1N_HALF + U256::from(1), ..signature }.is_in_range());
+    }
 }
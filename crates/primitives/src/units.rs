@@ -0,0 +1,65 @@
+use crate::{constants::GWEI_TO_WEI, U256};
+use std::ops::{Add, AddAssign};
+
+/// An amount of ether denominated in gwei.
+///
+/// Withdrawals are denominated in gwei, see [Gwei::to_wei] for the amount that is credited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gwei(pub u64);
+
+impl Gwei {
+    /// Returns true if the amount is zero.
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Converts the amount to wei.
+    pub fn to_wei(self) -> Wei {
+        Wei(U256::from(self.0) * U256::from(GWEI_TO_WEI))
+    }
+}
+
+/// An amount of ether denominated in wei.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Wei(pub U256);
+
+impl Wei {
+    /// Returns the amount as [u128], saturating at [u128::MAX].
+    ///
+    /// Any amount converted from [Gwei] fits into a [u128].
+    pub fn to_u128(self) -> u128 {
+        self.0.try_into().unwrap_or(u128::MAX)
+    }
+}
+
+impl From<Gwei> for Wei {
+    fn from(gwei: Gwei) -> Self {
+        gwei.to_wei()
+    }
+}
+
+impl Add for Wei {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Wei {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gwei_to_wei() {
+        assert_eq!(Gwei(0).to_wei(), Wei(U256::ZERO));
+        assert_eq!(Gwei(3).to_wei(), Wei(U256::from(3_000_000_000u64)));
+        assert_eq!(Gwei(u64::MAX).to_wei().to_u128(), u64::MAX as u128 * GWEI_TO_WEI as u128);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{constants::GWEI_TO_WEI, serde_helper::u64_hex, Address};
+use crate::{serde_helper::u64_hex, Address, Gwei, U256};
 use alloy_rlp::{RlpDecodable, RlpEncodable};
 use reth_codecs::{main_codec, Compact};
 use std::mem;
@@ -21,9 +21,22 @@ pub struct Withdrawal {
 }
 
 impl Withdrawal {
+    /// Return the withdrawal amount in gwei.
+    pub fn amount_gwei(&self) -> Gwei {
+        Gwei(self.amount)
+    }
+
     /// Return the withdrawal amount in wei.
     pub fn amount_wei(&self) -> u128 {
-        self.amount as u128 * GWEI_TO_WEI as u128
+        self.amount_gwei().to_wei().to_u128()
+    }
+
+    /// Return the withdrawal amount in wei, with the given number of wei per unit of the amount,
+    /// saturating at [U256::MAX].
+    ///
+    /// See [ChainSpec::withdrawal_amount_scale](crate::ChainSpec::withdrawal_amount_scale).
+    pub fn amount_wei_scaled(&self, scale: U256) -> U256 {
+        U256::from(self.amount).saturating_mul(scale)
     }
 
     /// Calculate a heuristic for the in-memory size of the [Withdrawal].
@@ -46,4 +59,15 @@ mod tests {
         let s = serde_json::to_string(&withdrawals).unwrap();
         assert_eq!(input, s);
     }
+
+    #[test]
+    fn scaled_withdrawal_amount() {
+        let withdrawal = Withdrawal { amount: 3, ..Default::default() };
+        assert_eq!(withdrawal.amount_wei_scaled(U256::from(1)), U256::from(3));
+        assert_eq!(
+            withdrawal.amount_wei_scaled(U256::from(crate::constants::GWEI_TO_WEI)),
+            withdrawal.amount_gwei().to_wei().0
+        );
+        assert_eq!(withdrawal.amount_wei_scaled(U256::MAX), U256::MAX);
+    }
 }
@@ -0,0 +1,65 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use reth_primitives::{Address, Bytes};
+use reth_revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+use reth_rpc_types::trace::geth::GethDefaultTracingOptions;
+use revm::{
+    db::{CacheDB, EmptyDB},
+    primitives::{AccountInfo, Bytecode, Env, TransactTo},
+    EVM,
+};
+
+/// Number of storage slots written by the benchmarked transaction.
+const SLOTS: u16 = 1_000;
+
+/// Benchmarks the `callTracer` config against the opcode level config it replaced on a
+/// transaction that writes many storage slots.
+pub fn call_tracer_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Tracing inspector");
+    let (db, env) = storage_heavy_tx();
+
+    let configs = [
+        ("opcode steps", TracingInspectorConfig::from_geth_config(&Default::default())),
+        ("callTracer", TracingInspectorConfig::default_call_tracer()),
+        ("prestateTracer", TracingInspectorConfig::default_prestate_tracer()),
+    ];
+    for (name, config) in configs {
+        group.bench_function(format!("storage heavy tx | {name}"), |b| {
+            b.iter(|| inspect(black_box(&db), env.clone(), config));
+        });
+    }
+}
+
+fn inspect(db: &CacheDB<EmptyDB>, env: Env, config: TracingInspectorConfig) -> TracingInspector {
+    let mut evm = EVM::with_env(env);
+    evm.database(db.clone());
+    let mut inspector = TracingInspector::new(config);
+    evm.inspect(&mut inspector).unwrap();
+    inspector
+}
+
+/// Returns a database with a contract that writes [SLOTS] storage slots and a call to it.
+fn storage_heavy_tx() -> (CacheDB<EmptyDB>, Env) {
+    let [hi, lo] = SLOTS.to_be_bytes();
+    // PUSH2 SLOTS; loop: JUMPDEST DUP1 DUP1 SSTORE PUSH1 1 SWAP1 SUB DUP1 PUSH1 3 JUMPI; STOP
+    let code = Bytecode::new_raw(Bytes::from(vec![
+        0x61, hi, lo, 0x5b, 0x80, 0x80, 0x55, 0x60, 0x01, 0x90, 0x03, 0x80, 0x60, 0x03, 0x57, 0x00,
+    ]));
+    let contract = Address::with_last_byte(0x42);
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        contract,
+        AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() },
+    );
+
+    let mut env = Env::default();
+    env.tx.transact_to = TransactTo::Call(contract);
+    env.tx.gas_limit = 30_000_000;
+    (db, env)
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = call_tracer_benchmark
+}
+criterion_main!(benches);
@@ -0,0 +1,118 @@
+use reth_primitives::Address;
+use revm::{
+    interpreter::{opcode, Interpreter},
+    Database, EVMData, Inspector,
+};
+use std::collections::HashSet;
+
+/// The opcodes that the validation of an ERC-4337 user operation must not use, because their
+/// result can differ between the simulation and the inclusion of the user operation.
+///
+/// `GAS` is banned unless it is immediately followed by a call, see
+/// [BannedOpcodesInspector].
+///
+/// See also <https://eips.ethereum.org/EIPS/eip-4337#forbidden-opcodes>
+pub const ERC4337_BANNED_OPCODES: [u8; 15] = [
+    opcode::GASPRICE,
+    opcode::GASLIMIT,
+    opcode::DIFFICULTY,
+    opcode::TIMESTAMP,
+    opcode::BASEFEE,
+    opcode::BLOCKHASH,
+    opcode::NUMBER,
+    opcode::SELFBALANCE,
+    opcode::BALANCE,
+    opcode::ORIGIN,
+    opcode::GAS,
+    opcode::CREATE,
+    opcode::COINBASE,
+    opcode::SELFDESTRUCT,
+    opcode::BLOBHASH,
+];
+
+/// A use of a banned opcode recorded by the [BannedOpcodesInspector].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BannedOpcodeUse {
+    /// The address of the contract whose code used the opcode.
+    pub address: Address,
+    /// The program counter of the opcode.
+    pub pc: usize,
+    /// The opcode.
+    pub opcode: u8,
+    /// The call depth at which the opcode was used, starting at 1 for the transaction's call.
+    pub depth: u64,
+}
+
+/// An [Inspector] that records every use of a set of banned opcodes, e.g. to enforce the
+/// validation rules of ERC-4337 user operations via a simulated call.
+///
+/// `GAS` is only recorded if it is not immediately followed by one of the `*CALL` opcodes, which
+/// is the only permitted use of `GAS` during the validation of a user operation.
+#[derive(Debug, Clone)]
+pub struct BannedOpcodesInspector {
+    /// The opcodes to record.
+    banned: HashSet<u8>,
+    /// All recorded uses of banned opcodes, in the order of execution.
+    uses: Vec<BannedOpcodeUse>,
+    /// A `GAS` use that is only banned if the next opcode is not a call.
+    pending_gas: Option<BannedOpcodeUse>,
+}
+
+impl BannedOpcodesInspector {
+    /// Creates a new inspector that records the given opcodes.
+    pub fn new(banned: impl IntoIterator<Item = u8>) -> Self {
+        Self { banned: banned.into_iter().collect(), uses: Vec::new(), pending_gas: None }
+    }
+
+    /// Creates a new inspector that records the [ERC4337_BANNED_OPCODES].
+    pub fn erc4337() -> Self {
+        Self::new(ERC4337_BANNED_OPCODES)
+    }
+
+    /// Consumes the inspector and returns all recorded uses of banned opcodes.
+    pub fn into_uses(mut self) -> Vec<BannedOpcodeUse> {
+        // a `GAS` at the end of the execution is not followed by a call
+        self.uses.extend(self.pending_gas.take());
+        self.uses
+    }
+}
+
+impl Default for BannedOpcodesInspector {
+    fn default() -> Self {
+        Self::erc4337()
+    }
+}
+
+impl<DB> Inspector<DB> for BannedOpcodesInspector
+where
+    DB: Database,
+{
+    fn step(&mut self, interpreter: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        let op = interpreter.current_opcode();
+
+        if let Some(gas) = self.pending_gas.take() {
+            if !matches!(
+                op,
+                opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL
+            ) {
+                self.uses.push(gas);
+            }
+        }
+
+        if !self.banned.contains(&op) {
+            return
+        }
+
+        let banned_use = BannedOpcodeUse {
+            address: interpreter.contract.address,
+            pc: interpreter.program_counter(),
+            opcode: op,
+            depth: data.journaled_state.depth(),
+        };
+        if op == opcode::GAS {
+            self.pending_gas = Some(banned_use);
+        } else {
+            self.uses.push(banned_use);
+        }
+    }
+}
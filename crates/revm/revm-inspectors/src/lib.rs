@@ -17,6 +17,9 @@
 /// An inspector implementation for an EIP2930 Accesslist
 pub mod access_list;
 
+/// An inspector that records banned opcodes, e.g. for the validation of ERC-4337 user operations
+pub mod banned_opcodes;
+
 /// An inspector stack abstracting the implementation details of
 /// each inspector and allowing to hook on block/transaction execution,
 /// used in the main RETH executor.
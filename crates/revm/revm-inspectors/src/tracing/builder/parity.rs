@@ -3,12 +3,12 @@ use crate::tracing::{
     types::{CallTraceNode, CallTraceStep},
     TracingInspectorConfig,
 };
-use reth_primitives::{Address, U64};
+use reth_primitives::{Address, Bytes, U256, U64};
 use reth_rpc_types::{trace::parity::*, TransactionInfo};
 use revm::{
     db::DatabaseRef,
     interpreter::opcode::{self, spec_opcode_gas},
-    primitives::{Account, ExecutionResult, ResultAndState, SpecId, KECCAK_EMPTY},
+    primitives::{Account, AccountInfo, ExecutionResult, ResultAndState, SpecId, KECCAK_EMPTY},
 };
 use std::collections::{HashSet, VecDeque};
 
@@ -189,7 +189,24 @@ impl ParityTraceBuilder {
         DB: DatabaseRef,
     {
         let ResultAndState { ref result, ref state } = res;
+        self.into_trace_results_with_account_diffs(result, state.iter(), trace_types, db)
+    }
 
+    /// Same as [Self::into_trace_results_with_state] but takes the execution result and the
+    /// account diffs of the transaction separately.
+    ///
+    /// The `db` is expected to point to the state before the transaction.
+    pub fn into_trace_results_with_account_diffs<'a, DB, I>(
+        self,
+        result: &ExecutionResult,
+        account_diffs: I,
+        trace_types: &HashSet<TraceType>,
+        db: DB,
+    ) -> Result<TraceResults, DB::Error>
+    where
+        DB: DatabaseRef,
+        I: IntoIterator<Item = (&'a Address, &'a Account)>,
+    {
         let breadth_first_addresses = if trace_types.contains(&TraceType::VmTrace) {
             CallTraceNodeWalkerBF::new(&self.nodes)
                 .map(|node| node.trace.address)
@@ -202,7 +219,7 @@ impl ParityTraceBuilder {
 
         // check the state diff case
         if let Some(ref mut state_diff) = trace_res.state_diff {
-            populate_state_diff(state_diff, &db, state.iter())?;
+            populate_state_diff(state_diff, &db, account_diffs)?;
         }
 
         // check the vm trace case
@@ -559,8 +576,12 @@ where
 }
 
 /// Loops over all state accounts in the accounts diff that contains all accounts that are included
-/// in the [ExecutionResult] state map and compares the balance and nonce against what's in the
-/// `db`, which should point to the beginning of the transaction.
+/// in the [ExecutionResult] state map and compares the balance, nonce, code and storage against
+/// what's in the `db`, which should point to the beginning of the transaction.
+///
+/// Accounts that were created are marked as added, accounts that were selfdestructed are marked as
+/// removed. The state map also contains the gas fee payment of the sender and the fee credited to
+/// the block's beneficiary, so these are included as well.
 ///
 /// It's expected that `DB` is a revm [Database](revm::db::Database) which at this point already
 /// contains all the accounts that are in the state map and never has to fetch them from disk.
@@ -580,64 +601,148 @@ where
         }
 
         let addr = *addr;
-        let entry = state_diff.entry(addr).or_default();
 
         // we check if this account was created during the transaction
         if changed_acc.is_created() || changed_acc.is_loaded_as_not_existing() {
+            // an account that was only touched still doesn't exist after the transaction
+            if changed_acc.info.is_empty() {
+                continue
+            }
+
+            let entry = state_diff.entry(addr).or_default();
             entry.balance = Delta::Added(changed_acc.info.balance);
             entry.nonce = Delta::Added(U64::from(changed_acc.info.nonce));
-            if changed_acc.info.code_hash == KECCAK_EMPTY {
-                // this is an additional check to ensure new accounts always get the empty code
-                // marked as added
-                entry.code = Delta::Added(Default::default());
-            }
+            entry.code = Delta::Added(account_code(&db, &changed_acc.info)?);
 
             // new storage values
-            for (key, slot) in changed_acc.storage.iter() {
+            for (key, slot) in
+                changed_acc.storage.iter().filter(|(_, slot)| slot.present_value != U256::ZERO)
+            {
                 entry.storage.insert((*key).into(), Delta::Added(slot.present_value.into()));
             }
-        } else {
-            // account already exists, we need to fetch the account from the db
-            let db_acc = db.basic_ref(addr)?.unwrap_or_default();
-
-            // update _changed_ storage values
-            for (key, slot) in changed_acc.storage.iter().filter(|(_, slot)| slot.is_changed()) {
-                entry.storage.insert(
-                    (*key).into(),
-                    Delta::changed(
-                        slot.previous_or_original_value.into(),
-                        slot.present_value.into(),
-                    ),
-                );
-            }
+            continue
+        }
+
+        // account already exists, we need to fetch the account from the db
+        let db_acc = db.basic_ref(addr)?.unwrap_or_default();
+        let entry = state_diff.entry(addr).or_default();
 
-            // check if the account was changed at all
-            if entry.storage.is_empty() &&
-                db_acc == changed_acc.info &&
-                !changed_acc.is_selfdestructed()
+        if changed_acc.is_selfdestructed() {
+            entry.balance = Delta::Removed(db_acc.balance);
+            entry.nonce = Delta::Removed(U64::from(db_acc.nonce));
+            entry.code = Delta::Removed(account_code(&db, &db_acc)?);
+            for (key, slot) in changed_acc
+                .storage
+                .iter()
+                .filter(|(_, slot)| slot.previous_or_original_value != U256::ZERO)
             {
-                // clear the entry if the account was not changed
-                state_diff.remove(&addr);
-                continue
+                entry
+                    .storage
+                    .insert((*key).into(), Delta::Removed(slot.previous_or_original_value.into()));
             }
+            continue
+        }
 
-            entry.balance = if db_acc.balance == changed_acc.info.balance {
-                Delta::Unchanged
-            } else {
-                Delta::Changed(ChangedType { from: db_acc.balance, to: changed_acc.info.balance })
-            };
+        // update _changed_ storage values
+        for (key, slot) in changed_acc.storage.iter().filter(|(_, slot)| slot.is_changed()) {
+            entry.storage.insert(
+                (*key).into(),
+                Delta::changed(slot.previous_or_original_value.into(), slot.present_value.into()),
+            );
+        }
 
-            // this is relevant for the caller and contracts
-            entry.nonce = if db_acc.nonce == changed_acc.info.nonce {
-                Delta::Unchanged
-            } else {
-                Delta::Changed(ChangedType {
-                    from: U64::from(db_acc.nonce),
-                    to: U64::from(changed_acc.info.nonce),
-                })
-            };
+        // check if the account was changed at all
+        if entry.storage.is_empty() && db_acc == changed_acc.info {
+            // clear the entry if the account was not changed
+            state_diff.remove(&addr);
+            continue
         }
+
+        entry.balance = if db_acc.balance == changed_acc.info.balance {
+            Delta::Unchanged
+        } else {
+            Delta::Changed(ChangedType { from: db_acc.balance, to: changed_acc.info.balance })
+        };
+
+        // this is relevant for the caller and contracts
+        entry.nonce = if db_acc.nonce == changed_acc.info.nonce {
+            Delta::Unchanged
+        } else {
+            Delta::Changed(ChangedType {
+                from: U64::from(db_acc.nonce),
+                to: U64::from(changed_acc.info.nonce),
+            })
+        };
+
+        entry.code = if db_acc.code_hash == changed_acc.info.code_hash {
+            Delta::Unchanged
+        } else {
+            Delta::changed(account_code(&db, &db_acc)?, account_code(&db, &changed_acc.info)?)
+        };
     }
 
     Ok(())
 }
+
+/// Returns the code of the account, which is fetched from the `db` if it isn't loaded.
+fn account_code<DB: DatabaseRef>(db: &DB, info: &AccountInfo) -> Result<Bytes, DB::Error> {
+    if info.code_hash == KECCAK_EMPTY {
+        return Ok(Bytes::new())
+    }
+    match info.code {
+        Some(ref code) => Ok(code.original_bytes()),
+        None => Ok(db.code_by_hash_ref(info.code_hash)?.original_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracing::TracingInspector;
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::TransactTo,
+        EVM,
+    };
+
+    const CREATE: &str = include_str!("../../../test_data/state_diff/create.json");
+
+    #[test]
+    fn state_diff_of_contract_creation() {
+        let sender = Address::repeat_byte(0xaa);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            sender,
+            AccountInfo {
+                balance: U256::from(1_000_000_000_000_000_000u128),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = EVM::new();
+        evm.database(db);
+        evm.env.cfg.spec_id = SpecId::SHANGHAI;
+        evm.env.block.coinbase = Address::repeat_byte(0xcc);
+        evm.env.tx.caller = sender;
+        evm.env.tx.transact_to = TransactTo::create();
+        evm.env.tx.gas_limit = 100_000;
+        evm.env.tx.gas_price = U256::from(10);
+        // PUSH1 0x01 PUSH1 0x00 SSTORE PUSH1 0x01 PUSH1 0x00 RETURN
+        evm.env.tx.data =
+            Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x01, 0x60, 0x00, 0xf3]);
+
+        let mut inspector = TracingInspector::new(TracingInspectorConfig::default_parity());
+        let res = evm.inspect(&mut inspector).unwrap();
+        assert!(res.result.is_success());
+
+        let trace_types = HashSet::from([TraceType::Trace, TraceType::StateDiff]);
+        let trace_res = inspector
+            .into_parity_builder()
+            .into_trace_results_with_state(&res, &trace_types, &*evm.db().unwrap())
+            .unwrap();
+        assert!(matches!(trace_res.trace[0].action, Action::Create(_)));
+
+        let expected: StateDiff = serde_json::from_str(CREATE).unwrap();
+        assert_eq!(trace_res.state_diff.unwrap(), expected);
+    }
+}
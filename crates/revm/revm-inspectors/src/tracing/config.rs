@@ -39,6 +39,12 @@ pub struct TracingInspectorConfig {
     pub record_call_return_data: bool,
     /// Whether to record logs
     pub record_logs: bool,
+    /// Whether to record the input and output data of calls.
+    pub record_call_data: bool,
+    /// The maximum number of bytes of the input and output of a call that are recorded.
+    ///
+    /// Data that exceeds this limit is truncated and the trace is flagged as truncated.
+    pub max_call_data_bytes: Option<usize>,
 }
 
 impl TracingInspectorConfig {
@@ -52,6 +58,8 @@ impl TracingInspectorConfig {
             exclude_precompile_calls: false,
             record_call_return_data: false,
             record_logs: true,
+            record_call_data: true,
+            max_call_data_bytes: None,
         }
     }
 
@@ -67,6 +75,8 @@ impl TracingInspectorConfig {
             exclude_precompile_calls: true,
             record_call_return_data: false,
             record_logs: false,
+            record_call_data: true,
+            max_call_data_bytes: None,
         }
     }
 
@@ -82,6 +92,44 @@ impl TracingInspectorConfig {
             exclude_precompile_calls: false,
             record_call_return_data: false,
             record_logs: false,
+            record_call_data: true,
+            max_call_data_bytes: None,
+        }
+    }
+
+    /// Returns a config for the geth `callTracer`.
+    ///
+    /// This config only records the call frames, without opcode level steps or storage changes.
+    /// Logs are not recorded unless enabled via [TracingInspectorConfig::set_record_logs].
+    pub const fn default_call_tracer() -> Self {
+        Self {
+            record_steps: false,
+            record_memory_snapshots: false,
+            record_stack_snapshots: false,
+            record_state_diff: false,
+            exclude_precompile_calls: false,
+            record_call_return_data: false,
+            record_logs: false,
+            record_call_data: true,
+            max_call_data_bytes: None,
+        }
+    }
+
+    /// Returns a config for the geth `prestateTracer`.
+    ///
+    /// The prestate is derived from the state changes of the transaction and the database, so
+    /// this config records nothing but the bare call frames.
+    pub const fn default_prestate_tracer() -> Self {
+        Self {
+            record_steps: false,
+            record_memory_snapshots: false,
+            record_stack_snapshots: false,
+            record_state_diff: false,
+            exclude_precompile_calls: false,
+            record_call_return_data: false,
+            record_logs: false,
+            record_call_data: false,
+            max_call_data_bytes: None,
         }
     }
 
@@ -147,4 +195,24 @@ impl TracingInspectorConfig {
         self.record_logs = record_logs;
         self
     }
+
+    /// Configure whether the tracer should record the return data of the last call
+    pub fn set_call_return_data(mut self, record_call_return_data: bool) -> Self {
+        self.record_call_return_data = record_call_return_data;
+        self
+    }
+
+    /// Configure whether the tracer should record the input and output data of calls
+    pub fn set_call_data(mut self, record_call_data: bool) -> Self {
+        self.record_call_data = record_call_data;
+        self
+    }
+
+    /// Configure the maximum number of recorded bytes of the input and output of a call.
+    ///
+    /// Call data that exceeds the limit is truncated.
+    pub fn set_max_call_data_bytes(mut self, max_call_data_bytes: Option<usize>) -> Self {
+        self.max_call_data_bytes = max_call_data_bytes;
+        self
+    }
 }
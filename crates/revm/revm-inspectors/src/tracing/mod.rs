@@ -115,6 +115,19 @@ impl TracingInspector {
         GethTraceBuilder::new(self.traces.arena, self.config)
     }
 
+    /// Returns the call data that is recorded according to the config and whether it was
+    /// truncated.
+    #[inline]
+    fn recorded_call_data(&self, data: Bytes) -> (Bytes, bool) {
+        if !self.config.record_call_data {
+            return (Bytes::new(), false)
+        }
+        match self.config.max_call_data_bytes {
+            Some(max) if data.len() > max => (data.0.slice(..max).into(), true),
+            _ => (data, false),
+        }
+    }
+
     /// Returns true if we're no longer in the context of the root call.
     fn is_deep(&self) -> bool {
         // the root call will always be the first entry in the trace stack
@@ -207,6 +220,7 @@ impl TracingInspector {
             self.spec_id = Some(data.env.cfg.spec_id);
         }
 
+        let (input_data, data_truncated) = self.recorded_call_data(input_data);
         self.trace_stack.push(self.traces.push_trace(
             0,
             push_kind,
@@ -215,6 +229,7 @@ impl TracingInspector {
                 address,
                 kind,
                 data: input_data,
+                data_truncated,
                 value,
                 status: InstructionResult::Continue,
                 caller,
@@ -240,6 +255,11 @@ impl TracingInspector {
         output: Bytes,
         created_address: Option<Address>,
     ) {
+        if self.config.record_call_return_data {
+            self.last_call_return_data = Some(output.clone());
+        }
+        let (output, output_truncated) = self.recorded_call_data(output);
+
         let trace_idx = self.pop_trace_idx();
         let trace = &mut self.traces.arena[trace_idx].trace;
 
@@ -253,9 +273,8 @@ impl TracingInspector {
 
         trace.status = status;
         trace.success = matches!(status, return_ok!());
-        trace.output = output.clone();
-
-        self.last_call_return_data = Some(output);
+        trace.output = output;
+        trace.output_truncated = output_truncated;
 
         if let Some(address) = created_address {
             // A new contract was created via CREATE
@@ -514,8 +533,9 @@ where
     ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
         self.gas_inspector.create_end(data, inputs, status, address, gas, retdata.clone());
 
-        // get the code of the created contract
+        // get the code of the created contract, copying it is skipped if call data isn't recorded
         let code = address
+            .filter(|_| self.config.record_call_data)
             .and_then(|address| {
                 data.journaled_state
                     .account(address)
@@ -543,3 +563,70 @@ struct StackStep {
     trace_idx: usize,
     step_idx: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{AccountInfo, Bytecode, TransactTo},
+        EVM,
+    };
+
+    /// Calls a contract that returns 64 bytes with 100 bytes of input.
+    fn inspect_call(config: TracingInspectorConfig) -> CallTrace {
+        let contract = Address::with_last_byte(0x42);
+        // PUSH1 0x40 PUSH1 0x00 RETURN
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x40, 0x60, 0x00, 0xf3]));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract,
+            AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() },
+        );
+
+        let mut evm = EVM::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        evm.env.tx.data = Bytes::from(vec![1u8; 100]);
+        evm.env.tx.gas_limit = 100_000;
+
+        let mut inspector = TracingInspector::new(config);
+        evm.inspect(&mut inspector).unwrap();
+        inspector.traces.arena.swap_remove(0).trace
+    }
+
+    #[test]
+    fn call_data_truncated_at_limit() {
+        let config =
+            TracingInspectorConfig::default_call_tracer().set_max_call_data_bytes(Some(32));
+        let trace = inspect_call(config);
+        assert_eq!(trace.data, Bytes::from(vec![1u8; 32]));
+        assert!(trace.data_truncated);
+        assert_eq!(trace.output.len(), 32);
+        assert!(trace.output_truncated);
+
+        let config = config.set_max_call_data_bytes(Some(64));
+        let trace = inspect_call(config);
+        assert_eq!(trace.data.len(), 64);
+        assert!(trace.data_truncated);
+        assert_eq!(trace.output.len(), 64);
+        assert!(!trace.output_truncated);
+    }
+
+    #[test]
+    fn call_data_within_limit() {
+        let trace = inspect_call(TracingInspectorConfig::default_call_tracer());
+        assert_eq!(trace.data.len(), 100);
+        assert_eq!(trace.output.len(), 64);
+        assert!(!trace.data_truncated && !trace.output_truncated);
+        assert!(trace.steps.is_empty());
+    }
+
+    #[test]
+    fn call_data_not_recorded() {
+        let trace = inspect_call(TracingInspectorConfig::default_prestate_tracer());
+        assert!(trace.data.is_empty() && trace.output.is_empty());
+        assert!(!trace.data_truncated && !trace.output_truncated);
+        assert!(trace.success);
+    }
+}
@@ -142,6 +142,10 @@ pub(crate) struct CallTrace {
     /// The return data of the call if this was not a contract creation, otherwise it is the
     /// runtime bytecode of the created contract
     pub(crate) output: Bytes,
+    /// Whether `data` was truncated because it exceeded the configured limit
+    pub(crate) data_truncated: bool,
+    /// Whether `output` was truncated because it exceeded the configured limit
+    pub(crate) output_truncated: bool,
     /// The gas cost of the call
     pub(crate) gas_used: u64,
     /// The gas limit of the call
@@ -204,6 +208,8 @@ impl Default for CallTrace {
             data: Default::default(),
             maybe_precompile: None,
             output: Default::default(),
+            data_truncated: false,
+            output_truncated: false,
             gas_used: Default::default(),
             gas_limit: Default::default(),
             status: InstructionResult::Continue,
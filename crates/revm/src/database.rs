@@ -1,11 +1,14 @@
 use reth_interfaces::RethError;
-use reth_primitives::{Address, B256, KECCAK_EMPTY, U256};
+use reth_primitives::{
+    constants::BLOCK_HASH_HISTORY, Address, BlockNumber, B256, KECCAK_EMPTY, U256,
+};
 use reth_provider::StateProvider;
 use revm::{
     db::{CacheDB, DatabaseRef},
     primitives::{AccountInfo, Bytecode},
     Database, StateDBBox,
 };
+use std::collections::BTreeMap;
 
 /// SubState of database. Uses revm internal cache with binding to reth StateProvider trait.
 pub type SubState<DB> = CacheDB<StateProviderDatabase<DB>>;
@@ -13,14 +16,79 @@ pub type SubState<DB> = CacheDB<StateProviderDatabase<DB>>;
 /// State boxed database with reth Error.
 pub type RethStateDBBox<'a> = StateDBBox<'a, RethError>;
 
+/// Cache of the block hashes served to the `BLOCKHASH` opcode and the pre-block system calls.
+///
+/// Only the hashes of the [BLOCK_HASH_HISTORY] most recent blocks are accessible during the
+/// execution of a block, so the cache retains the hashes within that window of the highest cached
+/// block, and all transactions of a block share its entries.
+#[derive(Debug, Clone, Default)]
+pub struct BlockHashCache {
+    hashes: BTreeMap<BlockNumber, B256>,
+}
+
+impl BlockHashCache {
+    /// Returns the cached hash of the given block.
+    pub fn get(&self, number: BlockNumber) -> Option<B256> {
+        self.hashes.get(&number).copied()
+    }
+
+    /// Caches the hash of the given block, evicting hashes that fell out of the window.
+    pub fn insert(&mut self, number: BlockNumber, hash: B256) {
+        self.hashes.insert(number, hash);
+        self.evict();
+    }
+
+    /// Caches the given block hashes, evicting hashes that fell out of the window.
+    pub fn extend(&mut self, hashes: impl IntoIterator<Item = (BlockNumber, B256)>) {
+        self.hashes.extend(hashes);
+        self.evict();
+    }
+
+    /// Returns the number of cached hashes.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if no hashes are cached.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    fn evict(&mut self) {
+        if let Some((&highest, _)) = self.hashes.last_key_value() {
+            let lowest = (highest + 1).saturating_sub(BLOCK_HASH_HISTORY);
+            self.hashes = self.hashes.split_off(&lowest);
+        }
+    }
+}
+
 /// Wrapper around StateProvider that implements revm database trait
+///
+/// Block hashes are looked up lazily and cached in a [BlockHashCache].
 #[derive(Debug, Clone)]
-pub struct StateProviderDatabase<DB: StateProvider>(pub DB);
+pub struct StateProviderDatabase<DB: StateProvider>(pub DB, BlockHashCache);
 
 impl<DB: StateProvider> StateProviderDatabase<DB> {
     /// Create new State with generic StateProvider.
     pub fn new(db: DB) -> Self {
-        Self(db)
+        Self(db, BlockHashCache::default())
+    }
+
+    /// Prefills the block hash cache with the given hashes.
+    ///
+    /// This allows to serve the hashes of ancestors that are not known to the state provider, e.g.
+    /// the blocks of a sidechain that only exist in memory.
+    pub fn with_block_hashes(
+        mut self,
+        block_hashes: impl IntoIterator<Item = (BlockNumber, B256)>,
+    ) -> Self {
+        self.1.extend(block_hashes);
+        self
+    }
+
+    /// Returns the block hash cache.
+    pub fn block_hashes(&self) -> &BlockHashCache {
+        &self.1
     }
 
     /// Return inner state reference
@@ -65,7 +133,13 @@ impl<DB: StateProvider> Database for StateProviderDatabase<DB> {
 
     fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
         // The `number` represents the block number, so it is safe to cast it to u64.
-        Ok(self.0.block_hash(number.try_into().unwrap())?.unwrap_or_default())
+        let number = number.try_into().unwrap();
+        if let Some(hash) = self.1.get(number) {
+            return Ok(hash)
+        }
+        let Some(hash) = self.0.block_hash(number)? else { return Ok(B256::ZERO) };
+        self.1.insert(number, hash);
+        Ok(hash)
     }
 }
 
@@ -99,6 +173,10 @@ impl<DB: StateProvider> DatabaseRef for StateProviderDatabase<DB> {
 
     fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
         // Note: this unwrap is potentially unsafe
-        Ok(self.0.block_hash(number.try_into().unwrap())?.unwrap_or_default())
+        let number = number.try_into().unwrap();
+        if let Some(hash) = self.1.get(number) {
+            return Ok(hash)
+        }
+        Ok(self.0.block_hash(number)?.unwrap_or_default())
     }
 }
@@ -1,23 +1,37 @@
 use crate::{
     database::StateProviderDatabase,
     processor::EVMProcessor,
+    replay::{BlockRangeReplay, ReplayOptions},
     stack::{InspectorStack, InspectorStackConfig},
+    state_change::{PostBlockHooks, SystemCallActivationTracker, SystemCallRecorder},
 };
-use reth_primitives::ChainSpec;
-use reth_provider::{ExecutorFactory, PrunableBlockExecutor, StateProvider};
-use std::sync::Arc;
+use reth_interfaces::RethResult;
+use reth_primitives::{BlockHash, BlockNumber, ChainSpec};
+use reth_provider::{
+    BlockReader, ExecutorFactory, PrunableBlockExecutor, StateProvider, StateProviderFactory,
+};
+use std::{collections::BTreeMap, ops::RangeInclusive, sync::Arc};
 
 /// Factory that spawn Executor.
 #[derive(Clone, Debug)]
 pub struct Factory {
     chain_spec: Arc<ChainSpec>,
     stack: Option<InspectorStack>,
+    post_block_hooks: PostBlockHooks,
+    system_call_activation: Option<SystemCallActivationTracker>,
+    system_call_recorder: Option<SystemCallRecorder>,
 }
 
 impl Factory {
     /// Create new factory
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
-        Self { chain_spec, stack: None }
+        Self {
+            chain_spec,
+            stack: None,
+            post_block_hooks: PostBlockHooks::default(),
+            system_call_activation: None,
+            system_call_recorder: None,
+        }
     }
 
     /// Sets the inspector stack for all generated executors.
@@ -31,20 +45,82 @@ impl Factory {
         self.stack = Some(InspectorStack::new(config));
         self
     }
-}
 
-impl ExecutorFactory for Factory {
-    fn with_state<'a, SP: StateProvider + 'a>(
+    /// Sets the hooks that run at the end of every block for all generated executors.
+    pub fn with_post_block_hooks(mut self, hooks: PostBlockHooks) -> Self {
+        self.post_block_hooks = hooks;
+        self
+    }
+
+    /// Sets the tracker that is notified of the blocks executed by all generated executors, see
+    /// [EVMProcessor::set_system_call_activation_tracker].
+    ///
+    /// The tracker is shared by the executors, so the activation is detected across executors.
+    pub fn with_system_call_activation_tracker(
+        mut self,
+        tracker: SystemCallActivationTracker,
+    ) -> Self {
+        self.system_call_activation = Some(tracker);
+        self
+    }
+
+    /// Sets the recorder of the system calls applied by all generated executors, see
+    /// [EVMProcessor::set_system_call_recorder].
+    pub fn with_system_call_recorder(mut self, recorder: SystemCallRecorder) -> Self {
+        self.system_call_recorder = Some(recorder);
+        self
+    }
+
+    /// Re-executes the canonical blocks of the range on top of the state of the parent of the
+    /// first block and yields the block, receipts and state changes of each block.
+    ///
+    /// See [BlockRangeReplay] for details.
+    pub fn replay_block_range<'a, Provider>(
+        &self,
+        provider: &'a Provider,
+        range: RangeInclusive<BlockNumber>,
+        options: ReplayOptions<'a>,
+    ) -> RethResult<BlockRangeReplay<'a, Provider>>
+    where
+        Provider: BlockReader + StateProviderFactory,
+    {
+        BlockRangeReplay::new(self.chain_spec.clone(), provider, range, options)
+    }
+
+    fn executor<'a, SP: StateProvider + 'a>(
         &'a self,
-        sp: SP,
+        database_state: StateProviderDatabase<SP>,
     ) -> Box<dyn PrunableBlockExecutor + 'a> {
-        let database_state = StateProviderDatabase::new(sp);
         let mut evm = Box::new(EVMProcessor::new_with_db(self.chain_spec.clone(), database_state));
         if let Some(ref stack) = self.stack {
             evm.set_stack(stack.clone());
         }
+        evm.set_post_block_hooks(self.post_block_hooks.clone());
+        if let Some(ref tracker) = self.system_call_activation {
+            evm.set_system_call_activation_tracker(tracker.clone());
+        }
+        if let Some(ref recorder) = self.system_call_recorder {
+            evm.set_system_call_recorder(recorder.clone());
+        }
         evm
     }
+}
+
+impl ExecutorFactory for Factory {
+    fn with_state<'a, SP: StateProvider + 'a>(
+        &'a self,
+        sp: SP,
+    ) -> Box<dyn PrunableBlockExecutor + 'a> {
+        self.executor(StateProviderDatabase::new(sp))
+    }
+
+    fn with_state_and_block_hashes<'a, SP: StateProvider + 'a>(
+        &'a self,
+        sp: SP,
+        block_hashes: BTreeMap<BlockNumber, BlockHash>,
+    ) -> Box<dyn PrunableBlockExecutor + 'a> {
+        self.executor(StateProviderDatabase::new(sp).with_block_hashes(block_hashes))
+    }
 
     /// Return internal chainspec
     fn chain_spec(&self) -> &ChainSpec {
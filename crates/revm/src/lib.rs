@@ -21,6 +21,9 @@ pub mod processor;
 /// State changes that are not related to transactions.
 pub mod state_change;
 
+/// Replay of canonical block ranges.
+pub mod replay;
+
 /// revm executor factory.
 pub use factory::Factory;
 
@@ -2,7 +2,11 @@ use crate::{
     database::StateProviderDatabase,
     eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
     stack::{InspectorStack, InspectorStackConfig},
-    state_change::{apply_beacon_root_contract_call, post_block_balance_increments},
+    state_change::{
+        apply_beacon_root_contract_call, validate_withdrawals_root, BlockRewardContext,
+        PostBlockHooks, SystemCallActivationTracker, SystemCallKind, SystemCallRecord,
+        SystemCallRecorder,
+    },
 };
 use reth_interfaces::{
     executor::{BlockExecutionError, BlockValidationError},
@@ -11,7 +15,7 @@ use reth_interfaces::{
 use reth_primitives::{
     revm::{
         compat::into_reth_log,
-        env::{fill_cfg_and_block_env, fill_tx_env},
+        env::{beacon_root_call_tx, fill_cfg_and_block_env, fill_tx_env},
     },
     Address, Block, BlockNumber, Bloom, ChainSpec, Hardfork, Header, PruneMode, PruneModes,
     PruneSegmentError, Receipt, ReceiptWithBloom, Receipts, TransactionSigned, B256,
@@ -23,7 +27,7 @@ use reth_provider::{
 };
 use revm::{
     db::{states::bundle_state::BundleRetention, StateDBBox},
-    primitives::ResultAndState,
+    primitives::{EVMError, InvalidTransaction, ResultAndState},
     DatabaseCommit, State, EVM,
 };
 use std::{sync::Arc, time::Instant};
@@ -73,6 +77,22 @@ pub struct EVMProcessor<'a> {
     pruning_address_filter: Option<(u64, Vec<Address>)>,
     /// Execution stats
     stats: BlockExecutorStats,
+    /// Whether the executor only simulates the transactions of blocks, see
+    /// [EVMProcessor::set_simulation].
+    simulation: bool,
+    /// Whether the storage committed by system calls is verified, see
+    /// [EVMProcessor::set_verify_system_calls].
+    verify_system_calls: bool,
+    /// Whether the withdrawals of a block are checked against its withdrawals root, see
+    /// [EVMProcessor::set_verify_withdrawals_root].
+    verify_withdrawals_root: bool,
+    /// Hooks that run after the post execution state changes of every block.
+    post_block_hooks: PostBlockHooks,
+    /// Tracks the activation of the pre-block system calls, see
+    /// [EVMProcessor::set_system_call_activation_tracker].
+    system_call_activation: Option<SystemCallActivationTracker>,
+    /// Records the applied pre-block system calls, see [EVMProcessor::set_system_call_recorder].
+    system_call_recorder: Option<SystemCallRecorder>,
 }
 
 impl<'a> EVMProcessor<'a> {
@@ -94,6 +114,12 @@ impl<'a> EVMProcessor<'a> {
             prune_modes: PruneModes::none(),
             pruning_address_filter: None,
             stats: BlockExecutorStats::default(),
+            simulation: false,
+            verify_system_calls: false,
+            verify_withdrawals_root: false,
+            post_block_hooks: PostBlockHooks::default(),
+            system_call_activation: None,
+            system_call_recorder: None,
         }
     }
 
@@ -127,6 +153,12 @@ impl<'a> EVMProcessor<'a> {
             prune_modes: PruneModes::none(),
             pruning_address_filter: None,
             stats: BlockExecutorStats::default(),
+            simulation: false,
+            verify_system_calls: false,
+            verify_withdrawals_root: false,
+            post_block_hooks: PostBlockHooks::default(),
+            system_call_activation: None,
+            system_call_recorder: None,
         }
     }
 
@@ -135,6 +167,53 @@ impl<'a> EVMProcessor<'a> {
         self.stack = stack;
     }
 
+    /// Configures whether the executor only simulates the transactions of blocks, e.g. on top of
+    /// state overrides.
+    ///
+    /// A simulation never applies the pre-block system calls or the post execution state changes,
+    /// because they are part of the block, not of the simulated transactions. Invoking them on a
+    /// simulating executor is a bug.
+    pub fn set_simulation(&mut self, simulation: bool) {
+        self.simulation = simulation;
+    }
+
+    /// Configures whether the storage committed by the pre-block system calls is compared to the
+    /// natively derived storage, see [apply_beacon_root_contract_call].
+    ///
+    /// This catches discrepancies between the EVM or the deployed contract and the EIP, at the
+    /// cost of additional storage reads.
+    pub fn set_verify_system_calls(&mut self, verify: bool) {
+        self.verify_system_calls = verify;
+    }
+
+    /// Configures whether the withdrawals of a block are checked against the withdrawals root of
+    /// its header before they are credited, see [validate_withdrawals_root].
+    ///
+    /// The root is already validated by consensus before execution, so this is only useful for
+    /// blocks that bypass it, at the cost of hashing the withdrawals again.
+    pub fn set_verify_withdrawals_root(&mut self, verify: bool) {
+        self.verify_withdrawals_root = verify;
+    }
+
+    /// Configures the hooks that run at the end of every block, after the post execution state
+    /// changes were applied.
+    pub fn set_post_block_hooks(&mut self, hooks: PostBlockHooks) {
+        self.post_block_hooks = hooks;
+    }
+
+    /// Configures a tracker that is notified of every block that applies the pre-block system
+    /// calls, and invokes its callback for the first block after the activating hardfork.
+    pub fn set_system_call_activation_tracker(&mut self, tracker: SystemCallActivationTracker) {
+        self.system_call_activation = Some(tracker);
+    }
+
+    /// Configures a recorder that records the contract and input of every pre-block system call
+    /// the executor applies, so the calls can be replayed with
+    /// [replay_system_calls](crate::state_change::replay_system_calls).
+    pub fn set_system_call_recorder(&mut self, recorder: SystemCallRecorder) {
+        self.system_call_recorder = Some(recorder);
+    }
+
     /// Returns a reference to the database
     pub fn db_mut(&mut self) -> &mut StateDBBox<'a, RethError> {
         // Option will be removed from EVM in the future.
@@ -184,37 +263,98 @@ impl<'a> EVMProcessor<'a> {
     ///
     /// If cancun is not activated or the block is the genesis block, then this is a no-op, and no
     /// state changes are made.
+    ///
+    /// If a [SystemCallActivationTracker] is configured, it is notified of the block after the call
+    /// was applied.
+    ///
+    /// Returns the gas used by the call.
+    ///
+    /// Must not be called on a simulating executor, see [EVMProcessor::set_simulation].
     pub fn apply_beacon_root_contract_call(
         &mut self,
         block: &Block,
-    ) -> Result<(), BlockExecutionError> {
-        apply_beacon_root_contract_call(
+    ) -> Result<u64, BlockExecutionError> {
+        debug_assert!(!self.simulation, "system call applied during simulation");
+        let gas_used = apply_beacon_root_contract_call(
             &self.chain_spec,
             block.timestamp,
             block.number,
             block.parent_beacon_block_root,
+            self.verify_system_calls,
             &mut self.evm,
         )?;
+
+        if let Some(tracker) = &self.system_call_activation {
+            tracker.on_block(
+                SystemCallKind::BeaconRoot,
+                &block.header,
+                self.chain_spec.is_system_call_active_at_timestamp(
+                    SystemCallKind::BeaconRoot,
+                    block.timestamp,
+                ),
+            );
+        }
+        Ok(gas_used)
+    }
+
+    /// Applies all pre-block system calls of the block.
+    ///
+    /// If a [SystemCallRecorder] is configured, every applied call is recorded.
+    ///
+    /// Fails with [BlockValidationError::SystemCallGasExceeded] if the system calls use more gas
+    /// in total than [ChainSpec::max_system_call_gas].
+    ///
+    /// Must not be called on a simulating executor, see [EVMProcessor::set_simulation].
+    pub fn apply_pre_block_system_calls(
+        &mut self,
+        block: &Block,
+    ) -> Result<(), BlockExecutionError> {
+        let gas_used = self.apply_beacon_root_contract_call(block)?;
+
+        if let (Some(recorder), Some(root)) =
+            (&self.system_call_recorder, block.parent_beacon_block_root)
+        {
+            // the beacon root contract is not called for the genesis block
+            if block.number != 0 &&
+                self.chain_spec.is_system_call_active_at_timestamp(
+                    SystemCallKind::BeaconRoot,
+                    block.timestamp,
+                )
+            {
+                recorder.record(SystemCallRecord {
+                    contract: SystemCallKind::BeaconRoot.address(),
+                    input: beacon_root_call_tx(root).data,
+                });
+            }
+        }
+
+        let max = self.chain_spec.max_system_call_gas;
+        if gas_used > max {
+            return Err(BlockValidationError::SystemCallGasExceeded { gas_used, max }.into())
+        }
         Ok(())
     }
 
     /// Apply post execution state changes, including block rewards, withdrawals, and irregular DAO
     /// hardfork state change.
+    ///
+    /// Afterwards, the configured post block hooks are run with the applied balance increments,
+    /// see [EVMProcessor::set_post_block_hooks].
+    ///
+    /// Must not be called on a simulating executor, see [EVMProcessor::set_simulation].
     pub fn apply_post_execution_state_change(
         &mut self,
         block: &Block,
         total_difficulty: U256,
     ) -> Result<(), BlockExecutionError> {
-        let mut balance_increments = post_block_balance_increments(
-            &self.chain_spec,
-            block.number,
-            block.difficulty,
-            block.beneficiary,
-            block.timestamp,
-            total_difficulty,
-            &block.ommers,
-            block.withdrawals.as_deref(),
-        );
+        debug_assert!(!self.simulation, "post execution state change applied during simulation");
+        if self.verify_withdrawals_root {
+            if let Some(withdrawals) = &block.withdrawals {
+                validate_withdrawals_root(withdrawals, block.withdrawals_root)?;
+            }
+        }
+        let mut balance_increments = BlockRewardContext::new(block, total_difficulty)
+            .try_balance_increments(&self.chain_spec)?;
 
         // Irregular state change at Ethereum DAO hardfork
         if self.chain_spec.fork(Hardfork::Dao).transitions_at_block(block.number) {
@@ -231,9 +371,20 @@ impl<'a> EVMProcessor<'a> {
         }
         // increment balances
         self.db_mut()
-            .increment_balances(balance_increments.into_iter().map(|(k, v)| (k, v)))
+            .increment_balances(
+                balance_increments.iter().map(|(address, increment)| (*address, *increment)),
+            )
             .map_err(|_| BlockValidationError::IncrementBalanceFailed)?;
 
+        if !self.post_block_hooks.is_empty() {
+            let hooks = self.post_block_hooks.clone();
+            hooks.run(
+                &BlockRewardContext::new(block, total_difficulty),
+                &balance_increments,
+                self.db_mut(),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -246,11 +397,21 @@ impl<'a> EVMProcessor<'a> {
         transaction: &TransactionSigned,
         sender: Address,
     ) -> Result<ResultAndState, BlockExecutionError> {
+        let hash = transaction.hash();
+        self.transact_inner(transaction, sender)
+            .map_err(|e| BlockValidationError::EVM { hash, message: format!("{e:?}") }.into())
+    }
+
+    fn transact_inner(
+        &mut self,
+        transaction: &TransactionSigned,
+        sender: Address,
+    ) -> Result<ResultAndState, EVMError<RethError>> {
         // Fill revm structure.
         fill_tx_env(&mut self.evm.env.tx, transaction, sender);
 
         let hash = transaction.hash();
-        let out = if self.stack.should_inspect(&self.evm.env, hash) {
+        if self.stack.should_inspect(&self.evm.env, hash) {
             // execution with inspector.
             let output = self.evm.inspect(&mut self.stack);
             tracing::trace!(
@@ -262,8 +423,7 @@ impl<'a> EVMProcessor<'a> {
         } else {
             // main execution.
             self.evm.transact()
-        };
-        out.map_err(|e| BlockValidationError::EVM { hash, message: format!("{e:?}") }.into())
+        }
     }
 
     /// Runs the provided transactions and commits their state to the run-time database.
@@ -276,6 +436,10 @@ impl<'a> EVMProcessor<'a> {
     /// 0, and so on).
     ///
     /// The second returned value represents the total gas used by this block of transactions.
+    ///
+    /// Transactions that revert or halt, e.g. by running out of gas, are valid and included with
+    /// an unsuccessful receipt. Only transactions failing the checks before their execution, like
+    /// a nonce mismatch or insufficient funds, invalidate the block.
     pub fn execute_transactions(
         &mut self,
         block: &Block,
@@ -293,7 +457,7 @@ impl<'a> EVMProcessor<'a> {
 
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.len());
-        for (transaction, sender) in block.body.iter().zip(senders) {
+        for (index, (transaction, sender)) in block.body.iter().zip(senders).enumerate() {
             let time = Instant::now();
             // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
             // must be no greater than the block’s gasLimit.
@@ -306,7 +470,9 @@ impl<'a> EVMProcessor<'a> {
                 .into())
             }
             // Execute transaction.
-            let ResultAndState { result, state } = self.transact(transaction, sender)?;
+            let ResultAndState { result, state } = self
+                .transact_inner(transaction, sender)
+                .map_err(|err| transaction_error(err, index, sender, transaction.hash()))?;
             trace!(
                 target: "evm",
                 ?transaction, ?result, ?state,
@@ -345,7 +511,9 @@ impl<'a> EVMProcessor<'a> {
         senders: Option<Vec<Address>>,
     ) -> Result<Vec<Receipt>, BlockExecutionError> {
         self.init_env(&block.header, total_difficulty);
-        self.apply_beacon_root_contract_call(block)?;
+        if !self.simulation {
+            self.apply_pre_block_system_calls(block)?;
+        }
         let (receipts, cumulative_gas_used) =
             self.execute_transactions(block, total_difficulty, senders)?;
 
@@ -359,9 +527,11 @@ impl<'a> EVMProcessor<'a> {
             }
             .into())
         }
-        let time = Instant::now();
-        self.apply_post_execution_state_change(block, total_difficulty)?;
-        self.stats.apply_post_execution_state_changes_duration += time.elapsed();
+        if !self.simulation {
+            let time = Instant::now();
+            self.apply_post_execution_state_change(block, total_difficulty)?;
+            self.stats.apply_post_execution_state_changes_duration += time.elapsed();
+        }
 
         let time = Instant::now();
         let retention = if self.tip.map_or(true, |tip| {
@@ -518,6 +688,57 @@ impl<'a> PrunableBlockExecutor for EVMProcessor<'a> {
     }
 }
 
+/// Converts an error returned by the EVM for the transaction at `index` of a block into the
+/// matching [BlockValidationError].
+fn transaction_error(
+    err: EVMError<RethError>,
+    index: usize,
+    sender: Address,
+    hash: B256,
+) -> BlockExecutionError {
+    let EVMError::Transaction(err) = err else {
+        return BlockValidationError::EVM { hash, message: format!("{err:?}") }.into()
+    };
+    match err {
+        InvalidTransaction::NonceTooLow { tx, state } => {
+            BlockValidationError::TransactionNonceTooLow {
+                index,
+                sender,
+                tx_nonce: tx,
+                state_nonce: state,
+            }
+        }
+        InvalidTransaction::NonceTooHigh { tx, state } => {
+            BlockValidationError::TransactionNonceTooHigh {
+                index,
+                sender,
+                tx_nonce: tx,
+                state_nonce: state,
+            }
+        }
+        InvalidTransaction::LackOfFundForMaxFee { .. } => {
+            BlockValidationError::TransactionInsufficientFunds { index, sender }
+        }
+        err @ (InvalidTransaction::MaxFeePerBlobGasNotSupported |
+        InvalidTransaction::BlobVersionedHashesNotSupported |
+        InvalidTransaction::BlobGasPriceGreaterThanMax |
+        InvalidTransaction::EmptyBlobs |
+        InvalidTransaction::BlobCreateTransaction |
+        InvalidTransaction::TooManyBlobs |
+        InvalidTransaction::BlobVersionNotSupported) => {
+            BlockValidationError::InvalidBlobTransaction {
+                index,
+                sender,
+                message: format!("{err:?}"),
+            }
+        }
+        err => {
+            BlockValidationError::InvalidTransaction { index, sender, message: format!("{err:?}") }
+        }
+    }
+    .into()
+}
+
 /// Verify receipts
 pub fn verify_receipt<'a>(
     expected_receipts_root: B256,
@@ -551,15 +772,18 @@ pub fn verify_receipt<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state_change::replay_system_calls;
     use reth_interfaces::RethResult;
     use reth_primitives::{
         bytes,
-        constants::{BEACON_ROOTS_ADDRESS, SYSTEM_ADDRESS},
-        keccak256,
+        constants::{BEACON_ROOTS_ADDRESS, ETH_TO_WEI, GWEI_TO_WEI, SYSTEM_ADDRESS},
+        keccak256, proofs,
         trie::AccountProof,
-        Account, Bytecode, Bytes, ChainSpecBuilder, ForkCondition, StorageKey, MAINNET,
+        Account, Bytecode, Bytes, ChainSpecBuilder, ForkCondition, Signature, StorageKey,
+        Transaction, TransactionKind, TxLegacy, Withdrawal, MAINNET,
     };
     use reth_provider::{AccountReader, BlockHashReader, StateRootProvider};
+    use reth_trie::updates::TrieUpdates;
     use revm::{Database, TransitionState};
     use std::collections::HashMap;
 
@@ -620,6 +844,13 @@ mod tests {
         fn state_root(&self, _bundle_state: &BundleStateWithReceipts) -> RethResult<B256> {
             unimplemented!("state root computation is not supported")
         }
+
+        fn state_root_with_updates(
+            &self,
+            _bundle_state: &BundleStateWithReceipts,
+        ) -> RethResult<(B256, TrieUpdates)> {
+            unimplemented!("state root computation is not supported")
+        }
     }
 
     impl StateProvider for StateProviderTest {
@@ -964,4 +1195,599 @@ mod tests {
             .unwrap();
         assert_eq!(parent_beacon_block_root_storage, U256::from(0x69));
     }
+
+    #[test]
+    fn failed_transactions_are_included() {
+        let sender = Address::with_last_byte(0x10);
+        let recipient = Address::with_last_byte(0x20);
+        let reverter = Address::with_last_byte(0x30);
+        let looper = Address::with_last_byte(0x40);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            sender,
+            Account { balance: U256::from(ETH_TO_WEI), ..Default::default() },
+            None,
+            HashMap::new(),
+        );
+        // PUSH1 0 PUSH1 0 REVERT
+        db.insert_account(reverter, Account::default(), Some(bytes!("60006000fd")), HashMap::new());
+        // JUMPDEST PUSH1 0 JUMP
+        db.insert_account(looper, Account::default(), Some(bytes!("5b600056")), HashMap::new());
+
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().berlin_activated().build());
+        let transaction = |nonce, to, gas_limit| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_price: 1,
+                    gas_limit,
+                    to: TransactionKind::Call(to),
+                    value: 1u64.into(),
+                    input: Bytes::default(),
+                }),
+                Signature::default(),
+            )
+        };
+        let block = |body: Vec<TransactionSigned>| Block {
+            header: Header { number: 1, gas_limit: 1_000_000, ..Header::default() },
+            body,
+            ommers: vec![],
+            withdrawals: None,
+        };
+
+        let success = transaction(0, recipient, 21_000);
+        let revert = transaction(1, reverter, 30_000);
+        let out_of_gas = transaction(2, looper, 50_000);
+        let mut executor =
+            EVMProcessor::new_with_db(chain_spec.clone(), StateProviderDatabase::new(db.clone()));
+        let (receipts, gas_used) = executor
+            .execute_transactions(
+                &block(vec![success.clone(), revert, out_of_gas]),
+                U256::ZERO,
+                Some(vec![sender; 3]),
+            )
+            .unwrap();
+
+        assert_eq!(
+            receipts.iter().map(|receipt| receipt.success).collect::<Vec<_>>(),
+            vec![true, false, false]
+        );
+        // the revert costs two pushes, the halt consumes the entire gas limit
+        assert_eq!(
+            receipts.iter().map(|receipt| receipt.cumulative_gas_used).collect::<Vec<_>>(),
+            vec![21_000, 42_006, 92_006]
+        );
+        assert_eq!(gas_used, 92_006);
+
+        // a gap in the nonces of the sender invalidates the block
+        let nonce_gap = transaction(2, recipient, 21_000);
+        let mut executor = EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(db));
+        let err = executor
+            .execute_transactions(
+                &block(vec![success, nonce_gap]),
+                U256::ZERO,
+                Some(vec![sender; 2]),
+            )
+            .expect_err("block with a nonce gap should be invalid");
+        assert_eq!(
+            err,
+            BlockExecutionError::Validation(BlockValidationError::TransactionNonceTooHigh {
+                index: 1,
+                sender,
+                tx_nonce: 2,
+                state_nonce: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn simulation_skips_system_calls() {
+        let header = Header {
+            timestamp: 1,
+            number: 1,
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::with_last_byte(0x69)),
+            ..Header::default()
+        };
+        let withdrawal_recipient = Address::with_last_byte(0x42);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            BEACON_ROOTS_ADDRESS,
+            Account {
+                balance: U256::ZERO,
+                bytecode_hash: Some(keccak256(BEACON_ROOT_CONTRACT_CODE.clone())),
+                nonce: 1,
+            },
+            Some(BEACON_ROOT_CONTRACT_CODE.clone()),
+            HashMap::new(),
+        );
+        // state override of the simulated call
+        db.insert_account(
+            withdrawal_recipient,
+            Account { balance: U256::from(1), ..Default::default() },
+            None,
+            HashMap::new(),
+        );
+
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(&*MAINNET)
+                .shanghai_activated()
+                .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+                .build(),
+        );
+
+        let mut executor = EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(db));
+        executor.set_simulation(true);
+        executor
+            .execute(
+                &Block {
+                    header: header.clone(),
+                    body: vec![],
+                    ommers: vec![],
+                    withdrawals: Some(vec![Withdrawal {
+                        index: 0,
+                        validator_index: 0,
+                        address: withdrawal_recipient,
+                        amount: 1,
+                    }]),
+                },
+                U256::ZERO,
+                None,
+            )
+            .unwrap();
+
+        // neither the beacon root call nor the withdrawal were applied
+        let timestamp_index = header.timestamp % 8191;
+        assert_eq!(
+            executor.db_mut().storage(BEACON_ROOTS_ADDRESS, U256::from(timestamp_index)).unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            executor
+                .db_mut()
+                .storage(BEACON_ROOTS_ADDRESS, U256::from(timestamp_index + 8191))
+                .unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            executor.db_mut().basic(withdrawal_recipient).unwrap().map(|account| account.balance),
+            Some(U256::from(1))
+        );
+    }
+
+    #[test]
+    fn disabled_system_call_is_skipped() {
+        let header = Header {
+            timestamp: 1,
+            number: 1,
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::with_last_byte(0x69)),
+            ..Header::default()
+        };
+        let withdrawal_recipient = Address::with_last_byte(0x42);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            BEACON_ROOTS_ADDRESS,
+            Account {
+                balance: U256::ZERO,
+                bytecode_hash: Some(keccak256(BEACON_ROOT_CONTRACT_CODE.clone())),
+                nonce: 1,
+            },
+            Some(BEACON_ROOT_CONTRACT_CODE.clone()),
+            HashMap::new(),
+        );
+
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(&*MAINNET)
+                .shanghai_activated()
+                .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+                .disable_system_call(SystemCallKind::BeaconRoot)
+                .build(),
+        );
+
+        let mut executor = EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(db));
+        executor
+            .execute(
+                &Block {
+                    header: header.clone(),
+                    body: vec![],
+                    ommers: vec![],
+                    withdrawals: Some(vec![Withdrawal {
+                        index: 0,
+                        validator_index: 0,
+                        address: withdrawal_recipient,
+                        amount: 1,
+                    }]),
+                },
+                U256::ZERO,
+                None,
+            )
+            .unwrap();
+
+        // the beacon root call was skipped, but the withdrawal was applied
+        let timestamp_index = header.timestamp % 8191;
+        assert_eq!(
+            executor.db_mut().storage(BEACON_ROOTS_ADDRESS, U256::from(timestamp_index)).unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            executor
+                .db_mut()
+                .storage(BEACON_ROOTS_ADDRESS, U256::from(timestamp_index + 8191))
+                .unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            executor.db_mut().basic(withdrawal_recipient).unwrap().map(|account| account.balance),
+            Some(U256::from(GWEI_TO_WEI))
+        );
+    }
+
+    #[test]
+    fn post_block_hooks_receive_increments() {
+        let recipient = Address::with_last_byte(0x42);
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+
+        let observed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_observed = observed.clone();
+        let hooks = PostBlockHooks::default().with_hook(Arc::new(
+            move |ctx: &BlockRewardContext<'_>,
+                  increments: &HashMap<Address, u128>,
+                  state: &mut StateDBBox<'_, RethError>| {
+                // the increments are already applied when the hook runs
+                let balance = state.basic(recipient).unwrap().map(|account| account.balance);
+                hook_observed.lock().unwrap().push((ctx.block_number, increments.clone(), balance));
+                Ok(())
+            },
+        ));
+
+        let mut executor = EVMProcessor::new_with_db(
+            chain_spec,
+            StateProviderDatabase::new(StateProviderTest::default()),
+        );
+        executor.set_post_block_hooks(hooks);
+        let withdrawals = [1, 2].map(|amount| Withdrawal {
+            index: amount,
+            validator_index: 0,
+            address: recipient,
+            amount,
+        });
+        executor
+            .execute(
+                &Block {
+                    header: Header { number: 1, timestamp: 1, ..Header::default() },
+                    body: vec![],
+                    ommers: vec![],
+                    withdrawals: Some(withdrawals.to_vec()),
+                },
+                U256::ZERO,
+                None,
+            )
+            .unwrap();
+
+        let expected = 3 * GWEI_TO_WEI as u128;
+        assert_eq!(
+            *observed.lock().unwrap(),
+            vec![(1, HashMap::from([(recipient, expected)]), Some(U256::from(expected)))]
+        );
+    }
+
+    #[test]
+    fn system_call_activation_fires_once() {
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            BEACON_ROOTS_ADDRESS,
+            Account { nonce: 1, ..Default::default() },
+            Some(BEACON_ROOT_CONTRACT_CODE.clone()),
+            HashMap::new(),
+        );
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(&*MAINNET)
+                .shanghai_activated()
+                .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(2))
+                .build(),
+        );
+
+        let activations = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let observed = activations.clone();
+        let tracker = SystemCallActivationTracker::new(Arc::new(move |kind, header: &Header| {
+            observed.lock().unwrap().push((kind, header.number))
+        }));
+
+        let mut executor = EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(db));
+        executor.set_system_call_activation_tracker(tracker);
+
+        // block 1 is pre-cancun, blocks 2 and 3 are post-cancun
+        for (number, timestamp) in [(1, 1), (2, 2), (3, 3)] {
+            let cancun = timestamp >= 2;
+            let header = Header {
+                number,
+                timestamp,
+                parent_beacon_block_root: cancun.then(|| B256::with_last_byte(0x69)),
+                excess_blob_gas: cancun.then_some(0),
+                ..Header::default()
+            };
+            executor
+                .execute(
+                    &Block { header, body: vec![], ommers: vec![], withdrawals: None },
+                    U256::ZERO,
+                    None,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(*activations.lock().unwrap(), vec![(SystemCallKind::BeaconRoot, 2)]);
+    }
+
+    #[test]
+    fn recorded_system_calls_replay() {
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            BEACON_ROOTS_ADDRESS,
+            Account { nonce: 1, ..Default::default() },
+            Some(BEACON_ROOT_CONTRACT_CODE.clone()),
+            HashMap::new(),
+        );
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(&*MAINNET)
+                .shanghai_activated()
+                .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+                .build(),
+        );
+        let root = B256::with_last_byte(0x69);
+        let header = Header {
+            number: 1,
+            timestamp: 12,
+            parent_beacon_block_root: Some(root),
+            excess_blob_gas: Some(0),
+            ..Header::default()
+        };
+
+        let recorder = SystemCallRecorder::default();
+        let mut executor =
+            EVMProcessor::new_with_db(chain_spec.clone(), StateProviderDatabase::new(db.clone()));
+        executor.set_system_call_recorder(recorder.clone());
+        executor
+            .execute(
+                &Block { header: header.clone(), body: vec![], ommers: vec![], withdrawals: None },
+                U256::ZERO,
+                None,
+            )
+            .unwrap();
+
+        let records = recorder.take();
+        assert_eq!(
+            records,
+            vec![SystemCallRecord {
+                contract: BEACON_ROOTS_ADDRESS,
+                input: Bytes::copy_from_slice(root.as_slice())
+            }]
+        );
+
+        // replay the recorded calls in the environment of the block onto a fresh database
+        let mut replayed = EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(db));
+        replayed.init_env(&header, U256::ZERO);
+        replay_system_calls(&records, &mut replayed.evm).unwrap();
+
+        let timestamp_index = header.timestamp % 8191;
+        for slot in [timestamp_index, timestamp_index + 8191] {
+            let slot = U256::from(slot);
+            let executed = executor.db_mut().storage(BEACON_ROOTS_ADDRESS, slot).unwrap();
+            assert_ne!(executed, U256::ZERO);
+            assert_eq!(replayed.db_mut().storage(BEACON_ROOTS_ADDRESS, slot).unwrap(), executed);
+        }
+    }
+
+    #[test]
+    fn system_call_gas_exceeded() {
+        let mut db = StateProviderTest::default();
+        // JUMPDEST PUSH0 JUMP, loops until the call runs out of gas
+        db.insert_account(
+            BEACON_ROOTS_ADDRESS,
+            Account { nonce: 1, ..Default::default() },
+            Some(bytes!("5b5f56")),
+            HashMap::new(),
+        );
+        let base = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1));
+        let header = Header {
+            number: 1,
+            timestamp: 1,
+            parent_beacon_block_root: Some(B256::with_last_byte(0x69)),
+            excess_blob_gas: Some(0),
+            ..Header::default()
+        };
+        let block = Block { header, body: vec![], ommers: vec![], withdrawals: None };
+
+        let chain_spec = Arc::new(base.clone().max_system_call_gas(1_000_000).build());
+        let mut executor =
+            EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(db.clone()));
+        assert_eq!(
+            executor.execute(&block, U256::ZERO, None),
+            Err(BlockValidationError::SystemCallGasExceeded {
+                gas_used: 30_000_000,
+                max: 1_000_000
+            }
+            .into())
+        );
+
+        // the default ceiling allows the entire gas limit of the call
+        let mut executor =
+            EVMProcessor::new_with_db(Arc::new(base.build()), StateProviderDatabase::new(db));
+        executor.execute(&block, U256::ZERO, None).unwrap();
+    }
+
+    #[test]
+    fn withdrawals_root_checked_before_increments() {
+        let recipient = Address::with_last_byte(0x42);
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+
+        let hook_calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let hook_counter = hook_calls.clone();
+        let hooks = PostBlockHooks::default().with_hook(Arc::new(
+            move |_: &BlockRewardContext<'_>,
+                  _: &HashMap<Address, u128>,
+                  _: &mut StateDBBox<'_, RethError>| {
+                hook_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Ok(())
+            },
+        ));
+
+        let mut executor = EVMProcessor::new_with_db(
+            chain_spec,
+            StateProviderDatabase::new(StateProviderTest::default()),
+        );
+        executor.set_post_block_hooks(hooks);
+        executor.set_verify_withdrawals_root(true);
+
+        let mut withdrawals =
+            vec![Withdrawal { index: 0, validator_index: 0, address: recipient, amount: 1 }];
+        let withdrawals_root = proofs::calculate_withdrawals_root(&withdrawals);
+        // the withdrawal is tampered with after the header committed to it
+        withdrawals[0].amount = 1_000;
+        let block = Block {
+            header: Header {
+                number: 1,
+                timestamp: 1,
+                withdrawals_root: Some(withdrawals_root),
+                ..Header::default()
+            },
+            body: vec![],
+            ommers: vec![],
+            withdrawals: Some(withdrawals.clone()),
+        };
+
+        let err = executor.execute(&block, U256::ZERO, None).unwrap_err();
+        assert_eq!(
+            err,
+            BlockValidationError::WithdrawalsRootMismatch {
+                got: proofs::calculate_withdrawals_root(&withdrawals),
+                expected: withdrawals_root,
+            }
+            .into()
+        );
+
+        // no increments were computed or applied
+        assert_eq!(hook_calls.load(std::sync::atomic::Ordering::Relaxed), 0);
+        assert_eq!(executor.db_mut().basic(recipient).unwrap(), None);
+
+        // the root is not checked by default
+        let mut executor = EVMProcessor::new_with_db(
+            executor.chain_spec().clone(),
+            StateProviderDatabase::new(StateProviderTest::default()),
+        );
+        executor.execute(&block, U256::ZERO, None).unwrap();
+        assert_eq!(
+            executor.db_mut().basic(recipient).unwrap().map(|account| account.balance),
+            Some(U256::from(1_000 * GWEI_TO_WEI))
+        );
+    }
+
+    #[test]
+    fn misaligned_withdrawal_amount() {
+        let recipient = Address::with_last_byte(0x42);
+        // the credited amount of a gwei saturates at u128::MAX, which is not a multiple of the
+        // scale
+        let scale = U256::from(10).pow(U256::from(30));
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(&*MAINNET)
+                .shanghai_activated()
+                .withdrawal_amount_scale(scale)
+                .build(),
+        );
+
+        let mut executor = EVMProcessor::new_with_db(
+            chain_spec,
+            StateProviderDatabase::new(StateProviderTest::default()),
+        );
+        let withdrawals = vec![
+            Withdrawal { index: 4, validator_index: 0, address: recipient, amount: 0 },
+            Withdrawal { index: 5, validator_index: 0, address: recipient, amount: 1 },
+            Withdrawal { index: 6, validator_index: 0, address: recipient, amount: GWEI_TO_WEI },
+        ];
+        let res = executor.execute(
+            &Block {
+                header: Header { number: 1, timestamp: 1, ..Header::default() },
+                body: vec![],
+                ommers: vec![],
+                withdrawals: Some(withdrawals),
+            },
+            U256::ZERO,
+            None,
+        );
+        if cfg!(debug_assertions) {
+            assert_eq!(
+                res.unwrap_err(),
+                BlockValidationError::WithdrawalAmountNotGweiAligned { index: 6 }.into()
+            );
+        } else {
+            assert!(res.is_ok());
+        }
+    }
+
+    #[test]
+    fn blockhash_of_sidechain_ancestor() {
+        let sender = Address::with_last_byte(0x10);
+        let contract = Address::with_last_byte(0x20);
+        let canonical_hash = B256::with_last_byte(0xca);
+        let sidechain_hash = B256::with_last_byte(0x5c);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            sender,
+            Account { balance: U256::from(ETH_TO_WEI), ..Default::default() },
+            None,
+            HashMap::new(),
+        );
+        // PUSH2 256 BLOCKHASH PUSH1 0 SSTORE
+        db.insert_account(
+            contract,
+            Account::default(),
+            Some(bytes!("61010040600055")),
+            HashMap::new(),
+        );
+        db.block_hash.insert(256, canonical_hash);
+
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().berlin_activated().build());
+        let block = Block {
+            header: Header { number: 300, gas_limit: 1_000_000, ..Header::default() },
+            body: vec![TransactionSigned::from_transaction_and_signature(
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce: 0,
+                    gas_price: 1,
+                    gas_limit: 100_000,
+                    to: TransactionKind::Call(contract),
+                    value: 0u64.into(),
+                    input: Bytes::default(),
+                }),
+                Signature::default(),
+            )],
+            ommers: vec![],
+            withdrawals: None,
+        };
+
+        for (database, expected) in [
+            (StateProviderDatabase::new(db.clone()), canonical_hash),
+            // the ancestor at block 256 only exists on the sidechain
+            (
+                StateProviderDatabase::new(db).with_block_hashes([(256, sidechain_hash)]),
+                sidechain_hash,
+            ),
+        ] {
+            let mut executor = EVMProcessor::new_with_db(chain_spec.clone(), database);
+            let (receipts, _) =
+                executor.execute_transactions(&block, U256::ZERO, Some(vec![sender])).unwrap();
+            assert!(receipts[0].success);
+            assert_eq!(
+                executor.db_mut().storage(contract, U256::ZERO).unwrap(),
+                U256::from_be_bytes(expected.0)
+            );
+        }
+    }
 }
@@ -0,0 +1,254 @@
+use crate::{database::StateProviderDatabase, processor::EVMProcessor, stack::InspectorStack};
+use reth_interfaces::{provider::ProviderError, RethResult};
+use reth_primitives::{Block, BlockNumber, ChainSpec, Receipt};
+use reth_provider::{BlockExecutor, BlockReader, StateProviderFactory};
+use revm::db::BundleState;
+use std::{ops::RangeInclusive, sync::Arc};
+
+/// Creates the inspector stack that is attached to the execution of a replayed block.
+pub type InspectorFactory<'a> = Box<dyn FnMut(&Block) -> InspectorStack + 'a>;
+
+/// Options of a [BlockRangeReplay].
+#[allow(missing_debug_implementations)]
+pub struct ReplayOptions<'a> {
+    /// Whether the receipts root and logs bloom of every block are verified.
+    verify_receipts: bool,
+    /// Creates the inspector stack of every block, if set.
+    inspector_factory: Option<InspectorFactory<'a>>,
+}
+
+// === impl ReplayOptions ===
+
+impl<'a> ReplayOptions<'a> {
+    /// Skips the verification of the receipts root and logs bloom of the replayed blocks.
+    pub fn skip_receipt_verification(mut self) -> Self {
+        self.verify_receipts = false;
+        self
+    }
+
+    /// Attaches the inspector stack returned by the factory to the execution of every block.
+    pub fn with_inspector_factory(
+        mut self,
+        factory: impl FnMut(&Block) -> InspectorStack + 'a,
+    ) -> Self {
+        self.inspector_factory = Some(Box::new(factory));
+        self
+    }
+}
+
+impl Default for ReplayOptions<'_> {
+    fn default() -> Self {
+        Self { verify_receipts: true, inspector_factory: None }
+    }
+}
+
+/// Re-executes a range of canonical blocks and yields the receipts and state changes of every
+/// block.
+///
+/// Only the state before the first block is read from a historical state provider, every
+/// following block is executed on top of the post state of its parent that is kept in the cache
+/// of the executor. The receipts and bundle state are handed out after each block, so the memory
+/// usage does not grow with the length of the range.
+///
+/// The genesis block can't be executed, so replay starts at block 1 at the earliest. The iterator
+/// ends after the first error.
+#[allow(missing_debug_implementations)]
+pub struct BlockRangeReplay<'a, Provider> {
+    provider: &'a Provider,
+    executor: EVMProcessor<'a>,
+    options: ReplayOptions<'a>,
+    /// The blocks that are left to replay.
+    blocks: RangeInclusive<BlockNumber>,
+}
+
+// === impl BlockRangeReplay ===
+
+impl<'a, Provider> BlockRangeReplay<'a, Provider>
+where
+    Provider: BlockReader + StateProviderFactory,
+{
+    /// Creates a new replay of the given range on top of the state of the parent of its first
+    /// block.
+    pub fn new(
+        chain_spec: Arc<ChainSpec>,
+        provider: &'a Provider,
+        range: RangeInclusive<BlockNumber>,
+        options: ReplayOptions<'a>,
+    ) -> RethResult<Self> {
+        let blocks = (*range.start()).max(1)..=*range.end();
+        let state = provider.history_by_block_number(blocks.start() - 1)?;
+        let executor = EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(state));
+        Ok(Self { provider, executor, options, blocks })
+    }
+
+    /// Executes the block and takes its receipts and state changes.
+    fn replay_block(
+        &mut self,
+        number: BlockNumber,
+    ) -> RethResult<(Block, Vec<Receipt>, BundleState)> {
+        let block = self
+            .provider
+            .block(number.into())?
+            .ok_or(ProviderError::BlockNotFound(number.into()))?;
+        let total_difficulty = self
+            .provider
+            .header_td_by_number(number)?
+            .ok_or(ProviderError::TotalDifficultyNotFound { number })?;
+
+        if let Some(factory) = self.options.inspector_factory.as_mut() {
+            self.executor.set_stack(factory(&block));
+        }
+
+        if self.options.verify_receipts {
+            self.executor.execute_and_verify_receipt(&block, total_difficulty, None)?;
+        } else {
+            self.executor.execute(&block, total_difficulty, None)?;
+        }
+
+        // the post state remains in the cache of the executor for the next block
+        let (bundle, receipts, _) = self.executor.take_output_state().into_inner();
+        let receipts = receipts.receipt_vec.into_iter().flatten().flatten().collect();
+        Ok((block, receipts, bundle))
+    }
+}
+
+impl<'a, Provider> Iterator for BlockRangeReplay<'a, Provider>
+where
+    Provider: BlockReader + StateProviderFactory,
+{
+    type Item = RethResult<(Block, Vec<Receipt>, BundleState)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let number = self.blocks.next()?;
+        let result = self.replay_block(number);
+        if result.is_err() {
+            // the state of the executor is unusable after a failed block
+            self.blocks = 1..=0;
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Factory;
+    use reth_interfaces::{
+        executor::{BlockExecutionError, BlockValidationError},
+        test_utils::generators::{self, generate_keys, sign_tx_with_key_pair},
+        RethError,
+    };
+    use reth_primitives::{
+        proofs::calculate_receipt_root, public_key_to_address, Address, ChainSpecBuilder, Header,
+        Transaction, TransactionKind, TxLegacy, TxType, U256,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    const BLOCKS: u64 = 20;
+    const VALUE: u64 = 1_000;
+
+    /// Returns a provider with [BLOCKS] blocks that each transfer [VALUE] wei to the recipient,
+    /// and their stored receipts.
+    fn transfer_chain(recipient: Address) -> (MockEthProvider, Vec<Vec<Receipt>>) {
+        let mut rng = generators::rng();
+        let key_pair = generate_keys(&mut rng, 1)[0];
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            public_key_to_address(key_pair.public_key()),
+            ExtendedAccount::new(0, U256::from(u64::MAX)),
+        );
+
+        let mut stored = Vec::new();
+        let mut parent_hash = Default::default();
+        for number in 0..=BLOCKS {
+            let mut block = Block {
+                header: Header {
+                    number,
+                    parent_hash,
+                    gas_limit: 30_000_000,
+                    base_fee_per_gas: Some(7),
+                    timestamp: number * 12,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            if number > 0 {
+                let tx = Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce: number - 1,
+                    gas_price: 10,
+                    gas_limit: 21_000,
+                    to: TransactionKind::Call(recipient),
+                    value: U256::from(VALUE).into(),
+                    ..Default::default()
+                });
+                block.body.push(sign_tx_with_key_pair(key_pair, tx));
+                let receipts = vec![Receipt {
+                    tx_type: TxType::Legacy,
+                    success: true,
+                    cumulative_gas_used: 21_000,
+                    logs: vec![],
+                }];
+                block.header.gas_used = 21_000;
+                block.header.receipts_root =
+                    calculate_receipt_root(&[receipts[0].clone().with_bloom()]);
+                stored.push(receipts);
+            }
+            let hash = block.header.hash_slow();
+            parent_hash = hash;
+            provider.add_block(hash, block);
+        }
+        (provider, stored)
+    }
+
+    #[test]
+    fn replay_matches_stored_receipts() {
+        let recipient = Address::random();
+        let (provider, stored) = transfer_chain(recipient);
+        let factory = Factory::new(Arc::new(ChainSpecBuilder::mainnet().paris_activated().build()));
+
+        let replayed = factory
+            .replay_block_range(&provider, 0..=BLOCKS, ReplayOptions::default())
+            .unwrap()
+            .collect::<RethResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(replayed.len(), BLOCKS as usize);
+
+        for (idx, (block, receipts, bundle)) in replayed.into_iter().enumerate() {
+            assert_eq!(block.number, idx as u64 + 1);
+            assert_eq!(receipts, stored[idx]);
+
+            // every block builds on the post state of its parent
+            let balance = bundle.account(&recipient).and_then(|account| account.info.clone());
+            assert_eq!(balance.unwrap().balance, U256::from(VALUE * block.number));
+        }
+    }
+
+    #[test]
+    fn replay_verifies_receipts_unless_skipped() {
+        let (provider, _) = transfer_chain(Address::random());
+        {
+            let mut blocks = provider.blocks.lock();
+            let block = blocks.values_mut().find(|block| block.number == 5).unwrap();
+            block.header.receipts_root = Default::default();
+        }
+        let factory = Factory::new(Arc::new(ChainSpecBuilder::mainnet().paris_activated().build()));
+
+        let mut replay =
+            factory.replay_block_range(&provider, 1..=BLOCKS, ReplayOptions::default()).unwrap();
+        for _ in 1..5 {
+            assert!(replay.next().unwrap().is_ok());
+        }
+        assert!(matches!(
+            replay.next(),
+            Some(Err(RethError::Execution(BlockExecutionError::Validation(
+                BlockValidationError::ReceiptRootDiff { .. }
+            ))))
+        ));
+        assert!(replay.next().is_none());
+
+        let options = ReplayOptions::default().skip_receipt_verification();
+        let replay = factory.replay_block_range(&provider, 1..=BLOCKS, options).unwrap();
+        assert_eq!(replay.filter(Result::is_ok).count(), BLOCKS as usize);
+    }
+}
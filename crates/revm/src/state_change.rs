@@ -1,16 +1,50 @@
+use crate::{
+    eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
+    processor::verify_receipt,
+};
 use reth_consensus_common::calc;
-use reth_interfaces::executor::{BlockExecutionError, BlockValidationError};
+use reth_interfaces::{
+    executor::{BlockExecutionError, BlockValidationError},
+    RethError,
+};
 use reth_primitives::{
-    constants::SYSTEM_ADDRESS, revm::env::fill_tx_env_with_beacon_root_contract_call, Address,
-    ChainSpec, Header, Withdrawal, B256, U256,
+    constants::{
+        BEACON_ROOTS_ADDRESS, DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE, GWEI_TO_WEI, SYSTEM_ADDRESS,
+    },
+    keccak256,
+    proofs::calculate_withdrawals_root,
+    revm::{compat::into_reth_acc, env::fill_tx_env_with_system_call},
+    Address, Block, Bytes, ChainSpec, ForkCondition, Gwei, Hardfork, Header, Receipt, Withdrawal,
+    B256, REWARD_SPLIT_TOTAL_BPS, U256,
+};
+use revm::{
+    db::StateDBBox,
+    primitives::{Account, AccountInfo, Env, ExecutionResult, ResultAndState, State},
+    Database, DatabaseCommit, EVM,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    fmt::Debug,
+    hash::Hash,
+    sync::Arc,
 };
-use revm::{primitives::ResultAndState, Database, DatabaseCommit, EVM};
-use std::{collections::HashMap, fmt::Debug};
+use tracing::trace;
+
+pub use reth_primitives::SystemCallKind;
 
 /// Collect all balance changes at the end of the block.
 ///
 /// Balance changes might include the block reward, uncle rewards, withdrawals, or irregular
 /// state changes (DAO fork).
+///
+/// No base fee is recycled into the block reward, see [BlockRewardContext::burned_base_fee].
+///
+/// # Panics
+///
+/// If debug assertions are enabled, this panics if a withdrawal amount is misaligned, see
+/// [withdrawal_amount_wei]. [BlockRewardContext::try_balance_increments] returns the error instead.
 #[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn post_block_balance_increments(
@@ -23,132 +57,3894 @@ pub fn post_block_balance_increments(
     ommers: &[Header],
     withdrawals: Option<&[Withdrawal]>,
 ) -> HashMap<Address, u128> {
+    let ctx = BlockRewardContext {
+        block_number,
+        block_difficulty,
+        beneficiary,
+        block_timestamp,
+        total_difficulty,
+        ommers,
+        withdrawals,
+        reward_override: None,
+        burned_base_fee: 0,
+    };
     let mut balance_increments = HashMap::new();
+    compute_post_block_balance_increments_into(chain_spec, &ctx, &mut balance_increments);
+    balance_increments
+}
 
-    // Add block rewards if they are enabled.
-    if let Some(base_block_reward) =
-        calc::base_block_reward(chain_spec, block_number, block_difficulty, total_difficulty)
-    {
-        // Ommer rewards
-        for ommer in ommers {
-            *balance_increments.entry(ommer.beneficiary).or_default() +=
-                calc::ommer_reward(base_block_reward, block_number, ommer.number);
-        }
+/// Same as [post_block_balance_increments], but writes the increments of the block into the given
+/// map instead of allocating a new one.
+///
+/// The map is cleared first, so it can be reused across blocks.
+pub fn compute_post_block_balance_increments_into(
+    chain_spec: &ChainSpec,
+    ctx: &BlockRewardContext<'_>,
+    out: &mut HashMap<Address, u128>,
+) {
+    out.clear();
+    add_post_block_balance_increments(chain_spec, ctx, out, &mut |_, _, _| {});
+}
+
+/// Same as [post_block_balance_increments], but for a block whose total difficulty may not be
+/// known, e.g. during historical sync.
+///
+/// If the total difficulty is `None`, whether the block is post merge is determined by comparing
+/// its number with the merge block of the chain spec. If the chain spec does not configure a merge
+/// block, this returns [BlockValidationError::UnknownMergeStatus].
+#[allow(clippy::too_many_arguments)]
+pub fn post_block_balance_increments_with_optional_td(
+    chain_spec: &ChainSpec,
+    block_number: u64,
+    block_difficulty: U256,
+    beneficiary: Address,
+    block_timestamp: u64,
+    total_difficulty: Option<U256>,
+    ommers: &[Header],
+    withdrawals: Option<&[Withdrawal]>,
+) -> Result<HashMap<Address, u128>, BlockValidationError> {
+    let total_difficulty = match total_difficulty {
+        Some(total_difficulty) => total_difficulty,
+        None => merge_block_total_difficulty(chain_spec, block_number, block_difficulty)?,
+    };
+    Ok(post_block_balance_increments(
+        chain_spec,
+        block_number,
+        block_difficulty,
+        beneficiary,
+        block_timestamp,
+        total_difficulty,
+        ommers,
+        withdrawals,
+    ))
+}
 
-        // Full block reward
-        *balance_increments.entry(beneficiary).or_default() +=
-            calc::block_reward(base_block_reward, ommers.len());
+/// Returns a total difficulty for the block that is at or above the terminal total difficulty if
+/// the block is at or after the merge block of the chain spec, and below it otherwise.
+fn merge_block_total_difficulty(
+    chain_spec: &ChainSpec,
+    block_number: u64,
+    block_difficulty: U256,
+) -> Result<U256, BlockValidationError> {
+    match chain_spec.fork(Hardfork::Paris) {
+        ForkCondition::TTD { fork_block: Some(merge_block), total_difficulty } => {
+            if block_number >= merge_block {
+                Ok(total_difficulty.saturating_add(block_difficulty))
+            } else {
+                Ok(U256::ZERO)
+            }
+        }
+        _ => Err(BlockValidationError::UnknownMergeStatus { block_number }),
     }
+}
 
-    // process withdrawals
-    insert_post_block_withdrawals_balance_increments(
-        chain_spec,
+/// The source of a balance increment at the end of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncrementSource {
+    /// The block reward, including the ommer inclusion reward.
+    BlockReward,
+    /// The reward of an ommer.
+    OmmerReward,
+    /// A withdrawal.
+    Withdrawal,
+}
+
+/// Same as [post_block_balance_increments], but additionally invokes `on_increment` with the
+/// address, the amount and the source of every increment as it is added to the returned map.
+///
+/// An address credited by multiple sources is reported once per source, while the map contains
+/// the sum of its increments.
+#[allow(clippy::too_many_arguments)]
+pub fn post_block_balance_increments_with_callback(
+    chain_spec: &ChainSpec,
+    block_number: u64,
+    block_difficulty: U256,
+    beneficiary: Address,
+    block_timestamp: u64,
+    total_difficulty: U256,
+    ommers: &[Header],
+    withdrawals: Option<&[Withdrawal]>,
+    mut on_increment: impl FnMut(Address, u128, IncrementSource),
+) -> HashMap<Address, u128> {
+    let ctx = BlockRewardContext {
+        block_number,
+        block_difficulty,
+        beneficiary,
         block_timestamp,
+        total_difficulty,
+        ommers,
         withdrawals,
+        reward_override: None,
+        burned_base_fee: 0,
+    };
+    let mut balance_increments = HashMap::new();
+    add_post_block_balance_increments(chain_spec, &ctx, &mut balance_increments, &mut on_increment);
+    balance_increments
+}
+
+/// Same as [post_block_balance_increments], but resolves the fork dependent rules from the given
+/// [Hardforks] instead of a [ChainSpec].
+pub fn post_block_balance_increments_with_hardforks(
+    hardforks: &Hardforks,
+    ctx: &BlockRewardContext<'_>,
+) -> HashMap<Address, u128> {
+    let mut balance_increments = HashMap::new();
+    add_post_block_balance_increments_with_hardforks(
+        hardforks,
+        ctx,
         &mut balance_increments,
+        &mut |_, _, _| {},
     );
-
     balance_increments
 }
 
-/// Applies the pre-block call to the EIP-4788 beacon block root contract, using the given block,
-/// [ChainSpec], EVM.
+/// Adds all balance increments at the end of the block to the given map.
 ///
-/// If cancun is not activated or the block is the genesis block, then this is a no-op, and no
-/// state changes are made.
-#[inline]
-pub fn apply_beacon_root_contract_call<DB: Database + DatabaseCommit>(
+/// Panics on a misaligned withdrawal amount, see [try_add_post_block_balance_increments].
+fn add_post_block_balance_increments(
     chain_spec: &ChainSpec,
-    block_timestamp: u64,
-    block_number: u64,
-    block_parent_beacon_block_root: Option<B256>,
-    evm: &mut EVM<DB>,
-) -> Result<(), BlockExecutionError>
-where
-    <DB as Database>::Error: Debug,
-{
-    if chain_spec.is_cancun_active_at_timestamp(block_timestamp) {
-        // if the block number is zero (genesis block) then the parent beacon block root must
-        // be 0x0 and no system transaction may occur as per EIP-4788
-        if block_number == 0 {
-            if block_parent_beacon_block_root != Some(B256::ZERO) {
-                return Err(BlockValidationError::CancunGenesisParentBeaconBlockRootNotZero.into())
+    ctx: &BlockRewardContext<'_>,
+    balance_increments: &mut HashMap<Address, u128>,
+    on_increment: &mut impl FnMut(Address, u128, IncrementSource),
+) {
+    try_add_post_block_balance_increments(chain_spec, ctx, balance_increments, on_increment)
+        .expect("withdrawal amounts are aligned")
+}
+
+/// Adds all balance increments at the end of the block to the given map, failing on a misaligned
+/// withdrawal amount, see [withdrawal_amount_wei].
+fn try_add_post_block_balance_increments(
+    chain_spec: &ChainSpec,
+    ctx: &BlockRewardContext<'_>,
+    balance_increments: &mut HashMap<Address, u128>,
+    on_increment: &mut impl FnMut(Address, u128, IncrementSource),
+) -> Result<(), BlockValidationError> {
+    trace!(
+        target: "evm",
+        block_number = ctx.block_number,
+        reward_era = ?calc::reward_era(
+            chain_spec,
+            ctx.block_number,
+            ctx.total_difficulty.saturating_sub(ctx.block_difficulty)
+        ),
+        "Applying post block balance increments"
+    );
+
+    let hardforks = Hardforks::new(
+        chain_spec,
+        ctx.block_number,
+        ctx.block_difficulty,
+        ctx.block_timestamp,
+        ctx.total_difficulty,
+    );
+    try_add_post_block_balance_increments_with_hardforks(
+        &hardforks,
+        ctx,
+        balance_increments,
+        on_increment,
+    )
+}
+
+fn add_post_block_balance_increments_with_hardforks(
+    hardforks: &Hardforks,
+    ctx: &BlockRewardContext<'_>,
+    balance_increments: &mut HashMap<Address, u128>,
+    on_increment: &mut impl FnMut(Address, u128, IncrementSource),
+) {
+    try_add_post_block_balance_increments_with_hardforks(
+        hardforks,
+        ctx,
+        balance_increments,
+        on_increment,
+    )
+    .expect("withdrawal amounts are aligned")
+}
+
+fn try_add_post_block_balance_increments_with_hardforks(
+    hardforks: &Hardforks,
+    ctx: &BlockRewardContext<'_>,
+    balance_increments: &mut HashMap<Address, u128>,
+    on_increment: &mut impl FnMut(Address, u128, IncrementSource),
+) -> Result<(), BlockValidationError> {
+    let RewardSchedule { base_block_reward, reward_split, max_ommer_reward, base_fee_recycle_bps } =
+        &hardforks.reward_schedule;
+    match ctx.reward_override {
+        // the reward is supplied by the consensus, there are no ommers to reward
+        Some(reward) => {
+            if reward > 0 {
+                *balance_increments.entry(ctx.beneficiary).or_default() += reward;
+                on_increment(ctx.beneficiary, reward, IncrementSource::BlockReward);
             }
-        } else {
-            let parent_beacon_block_root = block_parent_beacon_block_root.ok_or(
-                BlockExecutionError::from(BlockValidationError::MissingParentBeaconBlockRoot),
-            )?;
+        }
+        // Add block rewards if they are enabled.
+        None => add_block_reward_increments(
+            *base_block_reward,
+            ctx.block_number,
+            ctx.beneficiary,
+            reward_split.as_deref(),
+            *max_ommer_reward,
+            ctx.ommers,
+            balance_increments,
+            on_increment,
+        ),
+    }
 
-            // get previous env
-            let previous_env = evm.env.clone();
+    // recycle a share of the burned base fee into the reward of the beneficiary
+    let recycled = recycled_base_fee(ctx.burned_base_fee, *base_fee_recycle_bps);
+    if recycled > 0 {
+        *balance_increments.entry(ctx.beneficiary).or_default() += recycled;
+        on_increment(ctx.beneficiary, recycled, IncrementSource::BlockReward);
+    }
 
-            // modify env for pre block call
-            fill_tx_env_with_beacon_root_contract_call(&mut evm.env, parent_beacon_block_root);
+    // process withdrawals
+    try_add_withdrawal_increments(
+        hardforks.shanghai,
+        hardforks.withdrawal_amount_scale,
+        hardforks.withdrawal_vault,
+        ctx.withdrawals.into_iter().flatten(),
+        balance_increments,
+        on_increment,
+    )
+}
 
-            let ResultAndState { mut state, .. } = match evm.transact() {
-                Ok(res) => res,
-                Err(e) => {
-                    evm.env = previous_env;
-                    return Err(BlockExecutionError::from(BlockValidationError::EVM {
-                        hash: Default::default(),
-                        message: format!("{e:?}"),
-                    }))
-                }
-            };
+/// The block reward rules that apply to a block, see [Hardforks].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RewardSchedule {
+    /// The base block reward, or `None` if the block is not rewarded, see
+    /// [calc::base_block_reward].
+    pub base_block_reward: Option<U256>,
+    /// The addresses the block reward is split among, see [ChainSpec::reward_split].
+    pub reward_split: Option<Vec<(Address, u16)>>,
+    /// The cap of the ommer rewards, see [ChainSpec::max_ommer_reward_per_block].
+    pub max_ommer_reward: Option<u128>,
+    /// The share of the burned base fee that is added to the reward of the beneficiary, see
+    /// [ChainSpec::base_fee_recycle_bps].
+    pub base_fee_recycle_bps: Option<u16>,
+}
 
-            state.remove(&SYSTEM_ADDRESS);
-            state.remove(&evm.env.block.coinbase);
+/// A snapshot of the fork dependent rules that apply to a block.
+///
+/// This is resolved from the [ChainSpec] once per block with [Hardforks::new], and allows to
+/// compute the balance increments and system calls of the block without further lookups in the
+/// [ChainSpec], or without a [ChainSpec] at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hardforks {
+    /// Whether Shanghai is active, i.e. withdrawals are processed.
+    pub shanghai: bool,
+    /// Whether Cancun is active, i.e. the beacon root contract is called.
+    pub cancun: bool,
+    /// The block reward rules.
+    pub reward_schedule: RewardSchedule,
+    /// The gas price of system calls, see [ChainSpec::system_call_gas_price].
+    pub system_call_gas_price: Option<U256>,
+    /// The maximum size of the return data of system calls, see
+    /// [ChainSpec::max_system_call_return_size].
+    pub max_system_call_return_size: usize,
+    /// The number of wei per unit of a withdrawal amount, see
+    /// [ChainSpec::withdrawal_amount_scale].
+    pub withdrawal_amount_scale: U256,
+    /// The system calls that are skipped even if their hardfork is active, see
+    /// [ChainSpec::disabled_system_calls].
+    pub disabled_system_calls: HashSet<SystemCallKind>,
+    /// The contract that is credited with all withdrawals, see [ChainSpec::withdrawal_vault].
+    pub withdrawal_vault: Option<Address>,
+}
 
-            let db = evm.db().expect("db to not be moved");
-            db.commit(state);
+impl Default for Hardforks {
+    fn default() -> Self {
+        Self {
+            shanghai: false,
+            cancun: false,
+            reward_schedule: Default::default(),
+            system_call_gas_price: None,
+            max_system_call_return_size: DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
+            withdrawal_amount_scale: U256::from(GWEI_TO_WEI),
+            disabled_system_calls: HashSet::new(),
+            withdrawal_vault: None,
+        }
+    }
+}
 
-            // re-set the previous env
-            evm.env = previous_env;
+impl Hardforks {
+    /// Resolves the rules that apply to the given block from the [ChainSpec].
+    pub fn new(
+        chain_spec: &ChainSpec,
+        block_number: u64,
+        block_difficulty: U256,
+        block_timestamp: u64,
+        total_difficulty: U256,
+    ) -> Self {
+        Self {
+            shanghai: chain_spec.is_shanghai_active_at_timestamp(block_timestamp),
+            cancun: chain_spec.is_cancun_active_at_timestamp(block_timestamp),
+            reward_schedule: RewardSchedule {
+                base_block_reward: calc::base_block_reward(
+                    chain_spec,
+                    block_number,
+                    block_difficulty,
+                    total_difficulty,
+                ),
+                reward_split: chain_spec.reward_split.clone(),
+                max_ommer_reward: chain_spec.max_ommer_reward_per_block,
+                base_fee_recycle_bps: chain_spec.base_fee_recycle_bps,
+            },
+            system_call_gas_price: chain_spec.system_call_gas_price,
+            max_system_call_return_size: chain_spec.max_system_call_return_size,
+            withdrawal_amount_scale: chain_spec.withdrawal_amount_scale,
+            disabled_system_calls: chain_spec.disabled_system_calls.clone(),
+            withdrawal_vault: chain_spec.withdrawal_vault,
         }
     }
-    Ok(())
+
+    /// Returns `true` if the given system call is executed, i.e. its hardfork is active and it is
+    /// not disabled.
+    pub fn is_system_call_active(&self, kind: SystemCallKind) -> bool {
+        let fork_active = match kind {
+            SystemCallKind::BeaconRoot => self.cancun,
+        };
+        fork_active && !self.disabled_system_calls.contains(&kind)
+    }
 }
 
-/// Returns a map of addresses to their balance increments if the Shanghai hardfork is active at the
-/// given timestamp.
+/// The block properties that determine the balance increments at the end of a block, see
+/// [post_block_balance_increments].
+#[derive(Debug, Clone, Copy)]
+pub struct BlockRewardContext<'a> {
+    /// The number of the block.
+    pub block_number: u64,
+    /// The difficulty of the block.
+    pub block_difficulty: U256,
+    /// The beneficiary of the block.
+    pub beneficiary: Address,
+    /// The timestamp of the block.
+    pub block_timestamp: u64,
+    /// The total difficulty at the block.
+    pub total_difficulty: U256,
+    /// The ommers of the block.
+    pub ommers: &'a [Header],
+    /// The withdrawals of the block.
+    pub withdrawals: Option<&'a [Withdrawal]>,
+    /// The block reward supplied by the consensus for this block, e.g. on proof of authority
+    /// chains.
+    ///
+    /// If set, it is credited to the beneficiary instead of the reward of the schedule in
+    /// [calc::base_block_reward], and the ommers are not rewarded.
+    pub reward_override: Option<u128>,
+    /// The base fee burned by the transactions of the block, i.e. the base fee per gas times the
+    /// gas used.
+    ///
+    /// The [ChainSpec::base_fee_recycle_bps] share of it is added to the reward of the
+    /// beneficiary, regardless of whether the block is otherwise rewarded.
+    pub burned_base_fee: u128,
+}
+
+impl<'a> BlockRewardContext<'a> {
+    /// Creates the context of the given block.
+    pub fn new(block: &'a Block, total_difficulty: U256) -> Self {
+        Self {
+            block_number: block.number,
+            block_difficulty: block.difficulty,
+            beneficiary: block.beneficiary,
+            block_timestamp: block.timestamp,
+            total_difficulty,
+            ommers: &block.ommers,
+            withdrawals: block.withdrawals.as_deref(),
+            reward_override: None,
+            burned_base_fee: burned_base_fee(block),
+        }
+    }
+
+    /// Credits the given block reward to the beneficiary instead of the reward of the schedule,
+    /// see [BlockRewardContext::reward_override].
+    pub fn with_reward_override(mut self, reward: u128) -> Self {
+        self.reward_override = Some(reward);
+        self
+    }
+
+    /// Returns the balance increments at the end of the block.
+    pub fn balance_increments(&self, chain_spec: &ChainSpec) -> HashMap<Address, u128> {
+        let mut balance_increments = HashMap::new();
+        compute_post_block_balance_increments_into(chain_spec, self, &mut balance_increments);
+        balance_increments
+    }
+
+    /// Same as [BlockRewardContext::balance_increments], but returns
+    /// [BlockValidationError::WithdrawalAmountNotGweiAligned] for a misaligned withdrawal amount
+    /// instead of panicking, see [withdrawal_amount_wei].
+    pub fn try_balance_increments(
+        &self,
+        chain_spec: &ChainSpec,
+    ) -> Result<HashMap<Address, u128>, BlockValidationError> {
+        let mut balance_increments = HashMap::new();
+        try_add_post_block_balance_increments(
+            chain_spec,
+            self,
+            &mut balance_increments,
+            &mut |_, _, _| {},
+        )?;
+        Ok(balance_increments)
+    }
+
+    /// Same as [BlockRewardContext::balance_increments], but additionally invokes `on_increment`
+    /// like [post_block_balance_increments_with_callback].
+    pub fn balance_increments_with_callback(
+        &self,
+        chain_spec: &ChainSpec,
+        mut on_increment: impl FnMut(Address, u128, IncrementSource),
+    ) -> HashMap<Address, u128> {
+        let mut balance_increments = HashMap::new();
+        add_post_block_balance_increments(
+            chain_spec,
+            self,
+            &mut balance_increments,
+            &mut on_increment,
+        );
+        balance_increments
+    }
+}
+
+/// Returns the ether issued by the block, i.e. the sum of its block reward and ommer rewards.
 ///
-/// Zero-valued withdrawals are filtered out.
-#[inline]
-pub fn post_block_withdrawals_balance_increments(
-    chain_spec: &ChainSpec,
-    block_timestamp: u64,
-    withdrawals: &[Withdrawal],
-) -> HashMap<Address, u128> {
-    let mut balance_increments = HashMap::with_capacity(withdrawals.len());
-    insert_post_block_withdrawals_balance_increments(
+/// This includes the recycled share of the burned base fee, which is credited as part of the block
+/// reward, but not the withdrawals, which are issued by the consensus layer.
+pub fn block_issuance(chain_spec: &ChainSpec, ctx: &BlockRewardContext<'_>) -> u128 {
+    let mut issuance = 0u128;
+    add_post_block_balance_increments(
         chain_spec,
-        block_timestamp,
-        Some(withdrawals),
-        &mut balance_increments,
+        ctx,
+        &mut HashMap::new(),
+        &mut |_, amount, source| {
+            if source != IncrementSource::Withdrawal {
+                issuance += amount;
+            }
+        },
     );
-    balance_increments
+    issuance
 }
 
-/// Applies all withdrawal balance increments if shanghai is active at the given timestamp to the
-/// given `balance_increments` map.
+/// Returns the balance increment of a single address at the end of the block, i.e. its entry of
+/// [BlockRewardContext::balance_increments], or zero if it has none.
 ///
-/// Zero-valued withdrawals are filtered out.
-#[inline]
-pub fn insert_post_block_withdrawals_balance_increments(
+/// This sums the block reward (or its share of a reward split), the recycled base fee of the
+/// beneficiary, the rewards of the ommers the address mined and its withdrawals, without building
+/// the map of all increments.
+pub fn address_block_increment(
     chain_spec: &ChainSpec,
-    block_timestamp: u64,
-    withdrawals: Option<&[Withdrawal]>,
+    ctx: &BlockRewardContext<'_>,
+    address: Address,
+) -> u128 {
+    let mut increment = 0u128;
+
+    if let Some(reward) = ctx.reward_override {
+        if ctx.beneficiary == address {
+            increment += reward;
+        }
+    } else if let Some(base_block_reward) = calc::base_block_reward(
+        chain_spec,
+        ctx.block_number,
+        ctx.block_difficulty,
+        ctx.total_difficulty,
+    ) {
+        increment += ommer_rewards(
+            base_block_reward,
+            ctx.block_number,
+            ctx.ommers,
+            chain_spec.max_ommer_reward_per_block,
+        )
+        .into_iter()
+        .filter(|credit| credit.address == address)
+        .map(|credit| credit.amount)
+        .sum::<u128>();
+
+        let block_reward = reward_to_u128(calc::block_reward(base_block_reward, ctx.ommers.len()));
+        match chain_spec.reward_split.as_deref() {
+            Some(reward_split) => {
+                increment += split_block_reward(block_reward, reward_split)
+                    .into_iter()
+                    .filter(|credit| credit.address == address)
+                    .map(|credit| credit.amount)
+                    .sum::<u128>();
+            }
+            None if ctx.beneficiary == address => increment += block_reward,
+            None => {}
+        }
+    }
+
+    if ctx.beneficiary == address {
+        increment += recycled_base_fee(ctx.burned_base_fee, chain_spec.base_fee_recycle_bps);
+    }
+
+    if chain_spec.is_shanghai_active_at_timestamp(ctx.block_timestamp) {
+        if let Some(withdrawals) = ctx.withdrawals {
+            increment += withdrawals
+                .iter()
+                .filter(|withdrawal| {
+                    chain_spec.withdrawal_vault.unwrap_or(withdrawal.address) == address
+                })
+                .map(|withdrawal| {
+                    withdrawal
+                        .amount_wei_scaled(chain_spec.withdrawal_amount_scale)
+                        .saturating_to::<u128>()
+                })
+                .sum::<u128>();
+        }
+    }
+
+    increment
+}
+
+/// A hook that runs at the end of a block, after all post block state changes were applied.
+///
+/// It receives the context of the block, the balance increments that were applied and the state,
+/// so it can make additional changes, e.g. sweep a balance into a treasury.
+pub type PostBlockHook = Arc<
+    dyn Fn(
+            &BlockRewardContext<'_>,
+            &HashMap<Address, u128>,
+            &mut StateDBBox<'_, RethError>,
+        ) -> Result<(), BlockExecutionError>
+        + Send
+        + Sync,
+>;
+
+/// The [PostBlockHook]s of an executor, which run in the order they were added.
+#[derive(Clone, Default)]
+pub struct PostBlockHooks {
+    hooks: Vec<PostBlockHook>,
+}
+
+impl PostBlockHooks {
+    /// Adds a hook that runs after all previously added hooks.
+    pub fn with_hook(mut self, hook: PostBlockHook) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Returns true if there are no hooks.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Runs all hooks in order, stopping at the first error.
+    pub fn run(
+        &self,
+        ctx: &BlockRewardContext<'_>,
+        balance_increments: &HashMap<Address, u128>,
+        state: &mut StateDBBox<'_, RethError>,
+    ) -> Result<(), BlockExecutionError> {
+        for hook in &self.hooks {
+            hook(ctx, balance_increments, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PostBlockHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostBlockHooks").field("len", &self.hooks.len()).finish()
+    }
+}
+
+/// Adds the balance increments of a reverted block to the net `balance_changes`, as decrements.
+///
+/// Addresses whose net change becomes zero are removed.
+pub fn revert_balance_increments(
+    balance_increments: HashMap<Address, u128>,
+    balance_changes: &mut HashMap<Address, i128>,
+) {
+    for (address, increment) in balance_increments {
+        add_balance_change(balance_changes, address, -signed_increment(increment));
+    }
+}
+
+/// Returns the net balance change per address caused by the balance increments at the end of the
+/// blocks of a reorg: the increments of the `applied` blocks minus the increments of the
+/// `reverted` blocks.
+///
+/// Changes to balances by transactions are not included. Addresses without a net change are
+/// omitted.
+pub fn net_reorg_increments(
+    reverted: &[BlockRewardContext<'_>],
+    applied: &[BlockRewardContext<'_>],
+    chain_spec: &ChainSpec,
+) -> HashMap<Address, i128> {
+    let mut balance_changes = HashMap::new();
+    for block in reverted {
+        revert_balance_increments(block.balance_increments(chain_spec), &mut balance_changes);
+    }
+    for block in applied {
+        for (address, increment) in block.balance_increments(chain_spec) {
+            add_balance_change(&mut balance_changes, address, signed_increment(increment));
+        }
+    }
+    balance_changes
+}
+
+fn signed_increment(increment: u128) -> i128 {
+    i128::try_from(increment).unwrap_or(i128::MAX)
+}
+
+fn add_balance_change(
+    balance_changes: &mut HashMap<Address, i128>,
+    address: Address,
+    change: i128,
+) {
+    let net = balance_changes.entry(address).or_default();
+    *net = net.saturating_add(change);
+    if *net == 0 {
+        balance_changes.remove(&address);
+    }
+}
+
+/// Applies the block reward and the ommer rewards to the given `balance_increments` map.
+///
+/// `base_block_reward` is the reward schedule at the given block, as returned by
+/// [calc::base_block_reward]. No rewards are applied if it is `None`.
+///
+/// If a `reward_split` is given, the block reward is split among its addresses instead of being
+/// credited to the `beneficiary`, see [split_block_reward]. If a `max_ommer_reward` is given, the
+/// ommer rewards are capped, see [ommer_rewards].
+#[inline]
+pub fn block_reward_increments(
+    base_block_reward: Option<U256>,
+    block_number: u64,
+    beneficiary: Address,
+    reward_split: Option<&[(Address, u16)]>,
+    max_ommer_reward: Option<u128>,
+    ommers: &[Header],
     balance_increments: &mut HashMap<Address, u128>,
 ) {
-    // Process withdrawals
-    if chain_spec.is_shanghai_active_at_timestamp(block_timestamp) {
-        if let Some(withdrawals) = withdrawals {
-            for withdrawal in withdrawals {
-                if withdrawal.amount > 0 {
-                    *balance_increments.entry(withdrawal.address).or_default() +=
-                        withdrawal.amount_wei();
-                }
+    add_block_reward_increments(
+        base_block_reward,
+        block_number,
+        beneficiary,
+        reward_split,
+        max_ommer_reward,
+        ommers,
+        balance_increments,
+        &mut |_, _, _| {},
+    )
+}
+
+fn add_block_reward_increments(
+    base_block_reward: Option<U256>,
+    block_number: u64,
+    beneficiary: Address,
+    reward_split: Option<&[(Address, u16)]>,
+    max_ommer_reward: Option<u128>,
+    ommers: &[Header],
+    balance_increments: &mut HashMap<Address, u128>,
+    on_increment: &mut impl FnMut(Address, u128, IncrementSource),
+) {
+    let Some(base_block_reward) = base_block_reward else { return };
+
+    // zero credits are omitted, so that no account is touched without a balance change
+    let mut credit = |address: Address, amount: u128, source: IncrementSource| {
+        if amount > 0 {
+            *balance_increments.entry(address).or_default() += amount;
+            on_increment(address, amount, source);
+        }
+    };
+
+    // Ommer rewards
+    for BalanceCredit { address, amount } in
+        ommer_rewards(base_block_reward, block_number, ommers, max_ommer_reward)
+    {
+        credit(address, amount, IncrementSource::OmmerReward);
+    }
+
+    // Full block reward
+    let block_reward = reward_to_u128(calc::block_reward(base_block_reward, ommers.len()));
+    match reward_split {
+        Some(reward_split) => {
+            for BalanceCredit { address, amount } in split_block_reward(block_reward, reward_split)
+            {
+                credit(address, amount, IncrementSource::BlockReward);
             }
         }
+        None => credit(beneficiary, block_reward, IncrementSource::BlockReward),
+    }
+}
+
+/// Returns the base fee burned by the transactions of the block, i.e. the base fee per gas times
+/// the gas used, or zero before London.
+pub fn burned_base_fee(header: &Header) -> u128 {
+    header.base_fee_per_gas.map_or(0, |base_fee| base_fee as u128 * header.gas_used as u128)
+}
+
+/// Returns the share of the burned base fee that is recycled into the block reward, see
+/// [ChainSpec::base_fee_recycle_bps].
+///
+/// The share is capped at [REWARD_SPLIT_TOTAL_BPS], so never more than the burned base fee is
+/// recycled.
+fn recycled_base_fee(burned_base_fee: u128, base_fee_recycle_bps: Option<u16>) -> u128 {
+    let Some(bps) = base_fee_recycle_bps else { return 0 };
+    let bps = bps.min(REWARD_SPLIT_TOTAL_BPS);
+    reward_to_u128(
+        U256::from(burned_base_fee) * U256::from(bps) / U256::from(REWARD_SPLIT_TOTAL_BPS),
+    )
+}
+
+/// Converts a reward to a balance increment.
+///
+/// Rewards are computed as [U256], but never come close to exceeding [u128].
+#[inline]
+fn reward_to_u128(reward: U256) -> u128 {
+    reward.try_into().unwrap_or(u128::MAX)
+}
+
+/// Returns the rewards of the ommers of a block, in the order of the ommers.
+///
+/// If the rewards sum up to more than `max_ommer_reward`, they are scaled down proportionally so
+/// that exactly `max_ommer_reward` is distributed. Scaled rewards are rounded down, and the
+/// remainder is credited to the first ommer. The ommer inclusion reward of the block beneficiary is
+/// not affected.
+pub fn ommer_rewards(
+    base_block_reward: U256,
+    block_number: u64,
+    ommers: &[Header],
+    max_ommer_reward: Option<u128>,
+) -> Vec<BalanceCredit> {
+    let mut credits = ommers
+        .iter()
+        .map(|ommer| BalanceCredit {
+            address: ommer.beneficiary,
+            amount: reward_to_u128(calc::ommer_reward(
+                base_block_reward,
+                block_number,
+                ommer.number,
+            )),
+        })
+        .collect::<Vec<_>>();
+
+    let total = credits.iter().fold(U256::ZERO, |total, credit| total + U256::from(credit.amount));
+    if let Some(cap) = max_ommer_reward.filter(|cap| U256::from(*cap) < total) {
+        for credit in credits.iter_mut() {
+            credit.amount = reward_to_u128(U256::from(credit.amount) * U256::from(cap) / total);
+        }
+        let distributed: u128 = credits.iter().map(|credit| credit.amount).sum();
+        if let Some(first) = credits.first_mut() {
+            first.amount += cap - distributed;
+        }
+    }
+    credits
+}
+
+/// Splits the block reward among the addresses of the reward split, proportionally to their share
+/// in basis points.
+///
+/// Shares are rounded down, and the remainder is credited to the first address, so that the full
+/// block reward is always distributed. Addresses with a zero share are omitted.
+///
+/// Never more than the block reward is distributed: if the shares exceed
+/// [REWARD_SPLIT_TOTAL_BPS], which [ChainSpec::validate_rewards] rejects, the addresses only
+/// receive what is left of the reward, in order.
+pub fn split_block_reward(
+    block_reward: u128,
+    reward_split: &[(Address, u16)],
+) -> Vec<BalanceCredit> {
+    let mut remaining = block_reward;
+    let mut credits = reward_split
+        .iter()
+        .map(|(address, bps)| {
+            let share = reward_to_u128(
+                U256::from(block_reward) * U256::from(*bps) / U256::from(REWARD_SPLIT_TOTAL_BPS),
+            )
+            .min(remaining);
+            remaining -= share;
+            BalanceCredit { address: *address, amount: share }
+        })
+        .collect::<Vec<_>>();
+    if let Some(first) = credits.first_mut() {
+        first.amount += remaining;
+    }
+    credits.retain(|credit| credit.amount > 0);
+    credits
+}
+
+/// A description of all state changes that are applied around the execution of a block's
+/// transactions, see [post_block_change_plan].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostBlockChangePlan {
+    /// The block reward credited to the block beneficiary, or to the addresses of the reward
+    /// split, including the ommer inclusion reward.
+    pub block_rewards: Vec<BalanceCredit>,
+    /// The rewards credited to the ommer beneficiaries.
+    pub ommer_rewards: Vec<BalanceCredit>,
+    /// The non-zero withdrawals credited to their recipients.
+    pub withdrawals: Vec<BalanceCredit>,
+    /// The system calls that will be executed for the block.
+    pub system_calls: Vec<PlannedSystemCall>,
+}
+
+/// A balance credit to an address, in wei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceCredit {
+    /// The credited address.
+    pub address: Address,
+    /// The credited amount in wei.
+    pub amount: u128,
+}
+
+/// A system call that will be executed for a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedSystemCall {
+    /// The kind of the system call.
+    pub kind: SystemCallKind,
+    /// The address of the called system contract.
+    pub target: Address,
+}
+
+/// Returns the [PostBlockChangePlan] for the given block, without executing anything.
+///
+/// This is intended for debugging state root mismatches: all credits are listed separately, even if
+/// they target the same address. Irregular state changes (DAO fork) are not included since they
+/// depend on the state.
+pub fn post_block_change_plan(
+    chain_spec: &ChainSpec,
+    block: &Block,
+    total_difficulty: U256,
+) -> PostBlockChangePlan {
+    let mut plan = PostBlockChangePlan::default();
+
+    if let Some(base_block_reward) =
+        calc::base_block_reward(chain_spec, block.number, block.difficulty, total_difficulty)
+    {
+        plan.ommer_rewards = ommer_rewards(
+            base_block_reward,
+            block.number,
+            &block.ommers,
+            chain_spec.max_ommer_reward_per_block,
+        );
+        let block_reward =
+            reward_to_u128(calc::block_reward(base_block_reward, block.ommers.len()));
+        plan.block_rewards = match &chain_spec.reward_split {
+            Some(reward_split) => split_block_reward(block_reward, reward_split),
+            None => vec![BalanceCredit { address: block.beneficiary, amount: block_reward }],
+        };
+    }
+
+    if chain_spec.is_shanghai_active_at_timestamp(block.timestamp) {
+        plan.withdrawals = block
+            .withdrawals
+            .iter()
+            .flatten()
+            .filter(|withdrawal| withdrawal.amount > 0)
+            .map(|withdrawal| BalanceCredit {
+                address: chain_spec.withdrawal_vault.unwrap_or(withdrawal.address),
+                amount: withdrawal
+                    .amount_wei_scaled(chain_spec.withdrawal_amount_scale)
+                    .saturating_to(),
+            })
+            .collect();
+    }
+
+    // the beacon root contract is not called for the genesis block
+    if chain_spec.is_system_call_active_at_timestamp(SystemCallKind::BeaconRoot, block.timestamp) &&
+        block.number != 0
+    {
+        plan.system_calls.push(PlannedSystemCall {
+            kind: SystemCallKind::BeaconRoot,
+            target: SystemCallKind::BeaconRoot.address(),
+        });
+    }
+
+    plan
+}
+
+/// Returns the timestamp at which the given system call activates on the given [ChainSpec].
+///
+/// Returns `None` if the activating hardfork is not configured, or is not timestamp based, or if
+/// the system call is disabled.
+pub fn system_call_activation_timestamp(
+    chain_spec: &ChainSpec,
+    which: SystemCallKind,
+) -> Option<u64> {
+    if chain_spec.disabled_system_calls.contains(&which) {
+        return None
+    }
+    match which {
+        SystemCallKind::BeaconRoot => chain_spec.fork_timestamps.cancun,
+    }
+    .or_else(|| chain_spec.fork(which.hardfork()).as_timestamp())
+}
+
+/// A callback that is invoked with the header of the first block that executes a system call,
+/// see [SystemCallActivationTracker].
+pub type SystemCallActivationCallback = Arc<dyn Fn(SystemCallKind, &Header) + Send + Sync>;
+
+/// Detects the block at which a system call activates, i.e. a block whose hardfork is active
+/// following a block whose hardfork is not, and invokes a callback once for it.
+///
+/// This allows operators to log or alert when the node crosses a hardfork boundary during sync,
+/// e.g. the first Cancun block. The activation state of the last block is shared by all clones, so
+/// the same tracker can be configured on every executor that executes consecutive blocks.
+#[derive(Clone)]
+pub struct SystemCallActivationTracker {
+    callback: SystemCallActivationCallback,
+    state: Arc<std::sync::Mutex<ActivationState>>,
+}
+
+/// The tracked state of a [SystemCallActivationTracker].
+#[derive(Debug, Default)]
+struct ActivationState {
+    /// The number of the last block and whether the system call was active for it.
+    last_block: Option<(u64, bool)>,
+    /// Whether the callback was invoked.
+    notified: bool,
+}
+
+impl SystemCallActivationTracker {
+    /// Creates a new tracker that invokes the given callback.
+    pub fn new(callback: SystemCallActivationCallback) -> Self {
+        Self { callback, state: Default::default() }
+    }
+
+    /// Records whether the system call is active for the block with the given header, and invokes
+    /// the callback if the system call was not active for the previous block.
+    ///
+    /// The transition is only detected between consecutive blocks: the first block, or a block
+    /// that does not follow the last recorded block, only records its activation state.
+    pub fn on_block(&self, kind: SystemCallKind, header: &Header, active: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let activated = !state.notified &&
+            active &&
+            matches!(
+                state.last_block,
+                Some((number, false)) if number.checked_add(1) == Some(header.number)
+            );
+        state.last_block = Some((header.number, active));
+
+        if activated {
+            state.notified = true;
+            // release the lock before invoking user code
+            drop(state);
+            (self.callback)(kind, header);
+        }
+    }
+}
+
+impl fmt::Debug for SystemCallActivationTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SystemCallActivationTracker").field("state", &self.state).finish()
+    }
+}
+
+/// Returns the addresses of all system contracts that are called when executing a block with the
+/// given header.
+///
+/// This is intended for prefetching state before execution, which should also load the
+/// `SYSTEM_ADDRESS` account that all system calls are sent from.
+pub fn system_contracts_touched(chain_spec: &ChainSpec, header: &Header) -> Vec<Address> {
+    system_contracts_touched_by(
+        chain_spec.is_system_call_active_at_timestamp(SystemCallKind::BeaconRoot, header.timestamp),
+        header,
+    )
+}
+
+/// Same as [system_contracts_touched], but resolves the active forks from the given [Hardforks]
+/// instead of a [ChainSpec].
+pub fn system_contracts_touched_with_hardforks(
+    hardforks: &Hardforks,
+    header: &Header,
+) -> Vec<Address> {
+    system_contracts_touched_by(hardforks.is_system_call_active(SystemCallKind::BeaconRoot), header)
+}
+
+fn system_contracts_touched_by(beacon_root_active: bool, header: &Header) -> Vec<Address> {
+    let mut contracts = Vec::new();
+    // the beacon root contract is not called for the genesis block
+    if beacon_root_active && header.number != 0 {
+        contracts.push(SystemCallKind::BeaconRoot.address());
+    }
+    contracts
+}
+
+/// Applies the pre-block call to the EIP-4788 beacon block root contract, using the given block,
+/// [ChainSpec], EVM.
+///
+/// If cancun is not activated or the block is the genesis block, then this is a no-op, and no
+/// state changes are made.
+///
+/// The call is sent from `SYSTEM_ADDRESS`, which does not have to exist in the database: it is
+/// treated as an empty account for the duration of the call, and its changes, e.g. the nonce
+/// increment, are discarded, so the account is never created by the call.
+///
+/// If the [ChainSpec::system_call_gas_price] is configured, `SYSTEM_ADDRESS` pays it to the block
+/// beneficiary instead, so it must hold a balance for the gas limit of the call. The call fails
+/// with [BlockValidationError::SystemCallOutputTooLarge] if it returns more than
+/// [ChainSpec::max_system_call_return_size] bytes.
+///
+/// If `verify` is set, the committed storage of the contract is compared to the storage derived
+/// by [beacon_root_write_native], and a mismatch is reported as
+/// [BlockValidationError::BeaconRootMismatch].
+///
+/// Returns the gas used by the call, which is zero if no call was made.
+#[inline]
+pub fn apply_beacon_root_contract_call<DB: Database + DatabaseCommit>(
+    chain_spec: &ChainSpec,
+    block_timestamp: u64,
+    block_number: u64,
+    block_parent_beacon_block_root: Option<B256>,
+    verify: bool,
+    evm: &mut EVM<DB>,
+) -> Result<u64, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    apply_beacon_root_call(
+        chain_spec.is_system_call_active_at_timestamp(SystemCallKind::BeaconRoot, block_timestamp),
+        chain_spec.system_call_gas_price,
+        chain_spec.max_system_call_return_size,
+        block_number,
+        block_parent_beacon_block_root,
+        verify,
+        evm,
+    )
+}
+
+/// Same as [apply_beacon_root_contract_call], but resolves the active forks and the system call
+/// parameters from the given [Hardforks] instead of a [ChainSpec].
+#[inline]
+pub fn apply_beacon_root_contract_call_with_hardforks<DB: Database + DatabaseCommit>(
+    hardforks: &Hardforks,
+    block_number: u64,
+    block_parent_beacon_block_root: Option<B256>,
+    verify: bool,
+    evm: &mut EVM<DB>,
+) -> Result<u64, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    apply_beacon_root_call(
+        hardforks.is_system_call_active(SystemCallKind::BeaconRoot),
+        hardforks.system_call_gas_price,
+        hardforks.max_system_call_return_size,
+        block_number,
+        block_parent_beacon_block_root,
+        verify,
+        evm,
+    )
+}
+
+fn apply_beacon_root_call<DB: Database + DatabaseCommit>(
+    active: bool,
+    gas_price: Option<U256>,
+    max_return_size: usize,
+    block_number: u64,
+    block_parent_beacon_block_root: Option<B256>,
+    verify: bool,
+    evm: &mut EVM<DB>,
+) -> Result<u64, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let mut gas_used = 0;
+    if active {
+        // if the block number is zero (genesis block) then the parent beacon block root must
+        // be 0x0 and no system transaction may occur as per EIP-4788
+        if block_number == 0 {
+            if block_parent_beacon_block_root != Some(B256::ZERO) {
+                return Err(BlockValidationError::CancunGenesisParentBeaconBlockRootNotZero.into())
+            }
+        } else {
+            let parent_beacon_block_root = block_parent_beacon_block_root.ok_or(
+                BlockExecutionError::from(BlockValidationError::MissingParentBeaconBlockRoot),
+            )?;
+
+            gas_used = transact_system_call(
+                evm,
+                BEACON_ROOTS_ADDRESS,
+                Bytes::copy_from_slice(parent_beacon_block_root.as_slice()),
+                gas_price,
+                max_return_size,
+            )?;
+
+            if verify {
+                let block_timestamp = evm.env.block.timestamp.saturating_to::<u64>();
+                verify_beacon_root_write(
+                    evm,
+                    &State::default(),
+                    block_timestamp,
+                    parent_beacon_block_root,
+                )?;
+            }
+        }
+    }
+    Ok(gas_used)
+}
+
+/// The length of the ring buffers of the EIP-4788 beacon root contract.
+const BEACON_ROOTS_HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+/// Returns the storage slots and values the EIP-4788 beacon root contract writes for the block
+/// timestamp, without executing the contract.
+///
+/// The timestamp is stored at `timestamp % HISTORY_BUFFER_LENGTH` and the root in the second ring
+/// buffer right after it.
+pub fn beacon_root_write_native(
+    block_timestamp: u64,
+    parent_beacon_block_root: B256,
+) -> [(U256, U256); 2] {
+    let timestamp_index = block_timestamp % BEACON_ROOTS_HISTORY_BUFFER_LENGTH;
+    [
+        (U256::from(timestamp_index), U256::from(block_timestamp)),
+        (
+            U256::from(timestamp_index + BEACON_ROOTS_HISTORY_BUFFER_LENGTH),
+            U256::from_be_bytes(parent_beacon_block_root.0),
+        ),
+    ]
+}
+
+/// Checks that the storage written by the beacon root call matches [beacon_root_write_native].
+///
+/// The storage is read from the `pending` state changes of the call, falling back to the database
+/// of the EVM if they are already committed.
+fn verify_beacon_root_write<DB: Database>(
+    evm: &mut EVM<DB>,
+    pending: &State,
+    block_timestamp: u64,
+    parent_beacon_block_root: B256,
+) -> Result<(), BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let db = evm.db().expect("db to not be moved");
+    let pending = pending.get(&BEACON_ROOTS_ADDRESS).map(|account| &account.storage);
+    for (slot, expected) in beacon_root_write_native(block_timestamp, parent_beacon_block_root) {
+        let got = match pending.and_then(|storage| storage.get(&slot)) {
+            Some(value) => value.present_value,
+            None => db.storage(BEACON_ROOTS_ADDRESS, slot).map_err(|err| {
+                trace!(target: "evm", ?err, %slot, "Failed to read beacon root contract storage");
+                BlockExecutionError::ProviderError
+            })?,
+        };
+        if got != expected {
+            return Err(BlockValidationError::BeaconRootMismatch { slot, expected, got }.into())
+        }
+    }
+    Ok(())
+}
+
+/// A system call that was executed for a block, the input of [replay_system_calls].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemCallRecord {
+    /// The address of the called system contract.
+    pub contract: Address,
+    /// The input of the call.
+    pub input: Bytes,
+}
+
+/// Collects a [SystemCallRecord] for every pre-block system call an executor applies, see
+/// [EVMProcessor::set_system_call_recorder](crate::processor::EVMProcessor::set_system_call_recorder).
+///
+/// The records are shared by all clones, so a clone can be configured on the executor and the
+/// records read afterwards, e.g. to reproduce the calls with [replay_system_calls].
+#[derive(Debug, Clone, Default)]
+pub struct SystemCallRecorder {
+    records: Arc<std::sync::Mutex<Vec<SystemCallRecord>>>,
+}
+
+impl SystemCallRecorder {
+    /// Appends the record of an executed system call.
+    pub fn record(&self, record: SystemCallRecord) {
+        self.records.lock().unwrap_or_else(|err| err.into_inner()).push(record);
+    }
+
+    /// Returns the recorded system calls in the order they were executed.
+    pub fn records(&self) -> Vec<SystemCallRecord> {
+        self.records.lock().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// Removes and returns the recorded system calls.
+    pub fn take(&self) -> Vec<SystemCallRecord> {
+        std::mem::take(&mut *self.records.lock().unwrap_or_else(|err| err.into_inner()))
+    }
+}
+
+/// Re-executes the recorded system calls in order and commits their state changes to the
+/// database of the EVM.
+///
+/// This reproduces the system calls of a block, e.g. on top of a fresh database, without the
+/// block's header or [ChainSpec]. Every call is executed like the EIP-4788 beacon root call: as
+/// `SYSTEM_ADDRESS`, without gas price, and the changes to the system address and the block
+/// beneficiary are discarded. The return data is limited to
+/// [DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE] bytes.
+pub fn replay_system_calls<DB: Database + DatabaseCommit>(
+    records: &[SystemCallRecord],
+    evm: &mut EVM<DB>,
+) -> Result<(), BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    for record in records {
+        transact_system_call(
+            evm,
+            record.contract,
+            record.input.clone(),
+            None,
+            DEFAULT_MAX_SYSTEM_CALL_RETURN_SIZE,
+        )?;
+    }
+    Ok(())
+}
+
+/// Executes the system call twice on clones of the database of the EVM and returns an error if the
+/// resulting state changes differ.
+///
+/// The second execution runs with a different timestamp, coinbase, prevrandao and difficulty, so a
+/// system contract that reads any of them is reported as nondeterministic. Contracts that depend
+/// on the block by design, e.g. the beacon root contract that stores the root by timestamp, can be
+/// checked with [assert_system_call_deterministic_with_env]. The database of the EVM is not
+/// modified.
+///
+/// This is intended as a debugging aid for system contracts.
+pub fn assert_system_call_deterministic<DB: Database + Clone>(
+    contract: Address,
+    input: Bytes,
+    evm: &EVM<DB>,
+) -> Result<(), BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let mut perturbed = evm.env.clone();
+    perturbed.block.timestamp ^= U256::from(1);
+    perturbed.block.coinbase = Address::from_word(keccak256(perturbed.block.coinbase));
+    perturbed.block.prevrandao = Some(keccak256(perturbed.block.prevrandao.unwrap_or_default()));
+    perturbed.block.difficulty ^= U256::from(1);
+
+    assert_system_call_deterministic_with_env(contract, input, evm, perturbed)
+}
+
+/// Same as [assert_system_call_deterministic], but the second execution runs in the given `env`
+/// instead of a perturbed env of the EVM.
+///
+/// With the env of the EVM, this checks that repeated executions in the same block have the same
+/// state changes.
+pub fn assert_system_call_deterministic_with_env<DB: Database + Clone>(
+    contract: Address,
+    input: Bytes,
+    evm: &EVM<DB>,
+    env: Env,
+) -> Result<(), BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let db = evm.db.as_ref().expect("db to not be moved");
+
+    let first = system_call_state(db.clone(), evm.env.clone(), contract, input.clone())?;
+    let second = system_call_state(db.clone(), env, contract, input)?;
+    if first != second {
+        return Err(BlockValidationError::NondeterministicSystemCall { contract }.into())
+    }
+    Ok(())
+}
+
+/// Executes a system call of the contract on top of the database and returns its state changes,
+/// without the changes to the system address and the block beneficiary.
+fn system_call_state<DB: Database>(
+    db: DB,
+    mut env: Env,
+    contract: Address,
+    input: Bytes,
+) -> Result<State, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    fill_tx_env_with_system_call(&mut env, contract, input);
+    let mut evm = EVM::with_env(env);
+    evm.database(db);
+
+    let ResultAndState { mut state, .. } = evm.transact().map_err(|e| {
+        BlockExecutionError::from(BlockValidationError::EVM {
+            hash: Default::default(),
+            message: format!("{e:?}"),
+        })
+    })?;
+    state.remove(&SYSTEM_ADDRESS);
+    state.remove(&evm.env.block.coinbase);
+    Ok(state)
+}
+
+/// Executes a system call of the contract and commits its state changes, the env of the EVM is
+/// restored afterwards. Returns the gas used by the call.
+///
+/// Without a gas price the call is free and the changes to the block beneficiary are discarded,
+/// otherwise the fee is credited to the block beneficiary.
+fn transact_system_call<DB: Database + DatabaseCommit>(
+    evm: &mut EVM<DB>,
+    contract: Address,
+    input: Bytes,
+    gas_price: Option<U256>,
+    max_return_size: usize,
+) -> Result<u64, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let ResultAndState { result, state } =
+        execute_system_call(evm, contract, input, gas_price, max_return_size)?;
+    let db = evm.db().expect("db to not be moved");
+    db.commit(state);
+    Ok(result.gas_used())
+}
+
+/// Same as [transact_system_call], but returns the state changes instead of committing them.
+///
+/// Fails with [BlockValidationError::SystemCallFailed] if the call reverts or halts.
+fn simulate_system_call<DB: Database>(
+    evm: &mut EVM<DB>,
+    contract: Address,
+    input: Bytes,
+    gas_price: Option<U256>,
+    max_return_size: usize,
+) -> Result<State, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let ResultAndState { result, state } =
+        execute_system_call(evm, contract, input, gas_price, max_return_size)?;
+    if !result.is_success() {
+        return Err(BlockValidationError::SystemCallFailed { contract }.into())
+    }
+    Ok(state)
+}
+
+/// Executes a system call of the contract and returns its result and state changes, the env of the
+/// EVM is restored afterwards.
+///
+/// Without a `gas_price`, the call is free, so `SYSTEM_ADDRESS` neither has to exist nor hold a
+/// balance, and the changes to the block beneficiary are discarded as well. With a `gas_price`,
+/// `SYSTEM_ADDRESS` pays the fee to the beneficiary and the changes to both are kept.
+///
+/// Fails with [BlockValidationError::SystemCallOutputTooLarge] if the call returns more than
+/// `max_return_size` bytes, before the output is handed to the caller.
+fn execute_system_call<DB: Database>(
+    evm: &mut EVM<DB>,
+    contract: Address,
+    input: Bytes,
+    gas_price: Option<U256>,
+    max_return_size: usize,
+) -> Result<ResultAndState, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    // get previous env
+    let previous_env = evm.env.clone();
+
+    // modify env for the system call
+    fill_tx_env_with_system_call(&mut evm.env, contract, input);
+    if let Some(gas_price) = gas_price {
+        // the base fee is zero, so the entire price is paid to the beneficiary
+        evm.env.tx.gas_price = gas_price;
+    }
+
+    let ResultAndState { result, mut state } = match evm.transact() {
+        Ok(res) => res,
+        Err(e) => {
+            evm.env = previous_env;
+            return Err(BlockExecutionError::from(BlockValidationError::EVM {
+                hash: Default::default(),
+                message: format!("{e:?}"),
+            }))
+        }
+    };
+
+    if gas_price.is_none() {
+        state.remove(&SYSTEM_ADDRESS);
+        state.remove(&evm.env.block.coinbase);
+    }
+
+    // re-set the previous env
+    evm.env = previous_env;
+
+    let size = match &result {
+        ExecutionResult::Success { output, .. } => output.data().len(),
+        ExecutionResult::Revert { output, .. } => output.len(),
+        ExecutionResult::Halt { .. } => 0,
+    };
+    if size > max_return_size {
+        return Err(BlockValidationError::SystemCallOutputTooLarge {
+            contract,
+            size,
+            max: max_return_size,
+        }
+        .into())
+    }
+
+    Ok(ResultAndState { result, state })
+}
+
+/// Returns a map of addresses to their balance increments if the Shanghai hardfork is active at the
+/// given timestamp.
+///
+/// Zero-valued withdrawals are filtered out.
+#[inline]
+pub fn post_block_withdrawals_balance_increments(
+    chain_spec: &ChainSpec,
+    block_timestamp: u64,
+    withdrawals: &[Withdrawal],
+) -> HashMap<Address, u128> {
+    post_block_withdrawals_balance_increments_iter(chain_spec, block_timestamp, withdrawals)
+}
+
+/// Same as [post_block_withdrawals_balance_increments], but takes the withdrawals from an
+/// iterator, so they don't need to be collected into a slice first.
+pub fn post_block_withdrawals_balance_increments_iter<'a, I>(
+    chain_spec: &ChainSpec,
+    block_timestamp: u64,
+    withdrawals: I,
+) -> HashMap<Address, u128>
+where
+    I: IntoIterator<Item = &'a Withdrawal>,
+{
+    let withdrawals = withdrawals.into_iter();
+    let mut balance_increments = HashMap::with_capacity(withdrawals.size_hint().0);
+    add_withdrawal_increments(
+        chain_spec.is_shanghai_active_at_timestamp(block_timestamp),
+        chain_spec.withdrawal_amount_scale,
+        chain_spec.withdrawal_vault,
+        withdrawals,
+        &mut balance_increments,
+        &mut |_, _, _| {},
+    );
+    balance_increments
+}
+
+/// The balance increments and the root of the withdrawals of a block, see [process_withdrawals].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalsOutcome {
+    /// The balance increments of the withdrawals, see
+    /// [post_block_withdrawals_balance_increments].
+    pub increments: HashMap<Address, u128>,
+    /// The root of the withdrawals, the empty root if there are none.
+    pub root: B256,
+}
+
+/// Computes the balance increments and the root of the withdrawals of a block in a single call.
+///
+/// The increments are empty if Shanghai is not active at the given timestamp. Missing withdrawals
+/// are treated as an empty list, so their root is the empty root.
+pub fn process_withdrawals(
+    chain_spec: &ChainSpec,
+    block_timestamp: u64,
+    withdrawals: Option<&[Withdrawal]>,
+) -> WithdrawalsOutcome {
+    let withdrawals = withdrawals.unwrap_or_default();
+    WithdrawalsOutcome {
+        increments: post_block_withdrawals_balance_increments(
+            chain_spec,
+            block_timestamp,
+            withdrawals,
+        ),
+        root: calculate_withdrawals_root(withdrawals),
+    }
+}
+
+/// Applies all withdrawal balance increments if shanghai is active at the given timestamp to the
+/// given `balance_increments` map.
+///
+/// Zero-valued withdrawals are filtered out.
+#[inline]
+pub fn insert_post_block_withdrawals_balance_increments(
+    chain_spec: &ChainSpec,
+    block_timestamp: u64,
+    withdrawals: Option<&[Withdrawal]>,
+    balance_increments: &mut HashMap<Address, u128>,
+) {
+    add_withdrawal_increments(
+        chain_spec.is_shanghai_active_at_timestamp(block_timestamp),
+        chain_spec.withdrawal_amount_scale,
+        chain_spec.withdrawal_vault,
+        withdrawals.into_iter().flatten(),
+        balance_increments,
+        &mut |_, _, _| {},
+    )
+}
+
+/// Applies all withdrawal balance increments to the given `balance_increments` map if
+/// `shanghai_active` is true.
+///
+/// Zero-valued withdrawals are filtered out.
+#[inline]
+pub fn withdrawal_increments(
+    shanghai_active: bool,
+    withdrawals: Option<&[Withdrawal]>,
+    balance_increments: &mut HashMap<Address, u128>,
+) {
+    scaled_withdrawal_increments(
+        shanghai_active,
+        U256::from(GWEI_TO_WEI),
+        withdrawals,
+        balance_increments,
+    )
+}
+
+/// Like [withdrawal_increments], but credits every unit of the withdrawal amounts with `scale`
+/// wei instead of one gwei, see [ChainSpec::withdrawal_amount_scale].
+#[inline]
+pub fn scaled_withdrawal_increments(
+    shanghai_active: bool,
+    scale: U256,
+    withdrawals: Option<&[Withdrawal]>,
+    balance_increments: &mut HashMap<Address, u128>,
+) {
+    add_withdrawal_increments(
+        shanghai_active,
+        scale,
+        None,
+        withdrawals.into_iter().flatten(),
+        balance_increments,
+        &mut |_, _, _| {},
+    )
+}
+
+/// Credits the withdrawals to their addresses, or all of them to the `vault` if set.
+///
+/// Panics on a misaligned withdrawal amount, see [try_add_withdrawal_increments].
+fn add_withdrawal_increments<'a>(
+    shanghai_active: bool,
+    scale: U256,
+    vault: Option<Address>,
+    withdrawals: impl IntoIterator<Item = &'a Withdrawal>,
+    balance_increments: &mut HashMap<Address, u128>,
+    on_increment: &mut impl FnMut(Address, u128, IncrementSource),
+) {
+    try_add_withdrawal_increments(
+        shanghai_active,
+        scale,
+        vault,
+        withdrawals,
+        balance_increments,
+        on_increment,
+    )
+    .expect("withdrawal amounts are aligned")
+}
+
+/// Credits the withdrawals to their addresses, or all of them to the `vault` if set, failing on a
+/// misaligned withdrawal amount, see [withdrawal_amount_wei].
+///
+/// Zero-valued withdrawals are filtered out.
+fn try_add_withdrawal_increments<'a>(
+    shanghai_active: bool,
+    scale: U256,
+    vault: Option<Address>,
+    withdrawals: impl IntoIterator<Item = &'a Withdrawal>,
+    balance_increments: &mut HashMap<Address, u128>,
+    on_increment: &mut impl FnMut(Address, u128, IncrementSource),
+) -> Result<(), BlockValidationError> {
+    // Process withdrawals
+    if shanghai_active {
+        for withdrawal in withdrawals {
+            if withdrawal.amount_gwei().is_zero() {
+                continue
+            }
+            let address = vault.unwrap_or(withdrawal.address);
+            let amount = withdrawal_amount_wei(withdrawal, scale)?;
+            on_increment(address, amount, IncrementSource::Withdrawal);
+            *balance_increments.entry(address).or_default() += amount;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the wei a withdrawal is credited with, i.e. `scale` wei per unit of its amount,
+/// saturating at [u128::MAX]. The scale is one gwei unless [ChainSpec::withdrawal_amount_scale] is
+/// configured.
+///
+/// Since withdrawals are denominated in gwei, the credited amount must be a multiple of the scale.
+/// This is a canary for unit conversion bugs and only checked if debug assertions are enabled:
+/// [BlockValidationError::WithdrawalAmountNotGweiAligned] is returned with the index of the
+/// misaligned withdrawal.
+pub fn withdrawal_amount_wei(
+    withdrawal: &Withdrawal,
+    scale: U256,
+) -> Result<u128, BlockValidationError> {
+    let amount = withdrawal.amount_wei_scaled(scale).saturating_to::<u128>();
+    if cfg!(debug_assertions) && !scale.is_zero() && U256::from(amount) % scale != U256::ZERO {
+        return Err(BlockValidationError::WithdrawalAmountNotGweiAligned { index: withdrawal.index })
+    }
+    Ok(amount)
+}
+
+/// Sums the wei value of the withdrawals by the key returned by `group_by`, e.g. by validator
+/// index, by address or by a custom epoch function.
+///
+/// Zero-valued withdrawals are filtered out, like for the balance increments.
+pub fn group_withdrawals<K: Hash + Eq>(
+    withdrawals: &[Withdrawal],
+    group_by: impl Fn(&Withdrawal) -> K,
+) -> HashMap<K, u128> {
+    let mut groups = HashMap::new();
+    sum_gwei(
+        withdrawals.iter().map(|withdrawal| (group_by(withdrawal), withdrawal.amount_gwei())),
+        &mut groups,
+        |_, _| {},
+    );
+    groups
+}
+
+/// Adds the wei value of the given gwei amounts to the sums of their keys, skipping zero amounts.
+fn sum_gwei<K: Hash + Eq>(
+    amounts: impl IntoIterator<Item = (K, Gwei)>,
+    sums: &mut HashMap<K, u128>,
+    mut on_sum: impl FnMut(&K, u128),
+) {
+    for (key, amount) in amounts {
+        if !amount.is_zero() {
+            let amount = amount.to_wei().to_u128();
+            on_sum(&key, amount);
+            *sums.entry(key).or_default() += amount;
+        }
+    }
+}
+
+/// Returns [BlockValidationError::OmmersNotAllowed] if a post merge block has ommers.
+///
+/// [post_block_balance_increments] rewards all ommers of pre merge blocks, so this must be checked
+/// before the increments are applied.
+///
+/// Post merge blocks have no difficulty, so a block is post merge once its total difficulty
+/// reached the terminal total difficulty. If the merge block is configured, the block number is
+/// compared instead, which also tells the terminal proof of work block apart.
+pub fn validate_no_ommers_post_merge(
+    chain_spec: &ChainSpec,
+    block_number: u64,
+    total_difficulty: U256,
+    ommers: &[Header],
+) -> Result<(), BlockValidationError> {
+    if is_post_merge(chain_spec, block_number, total_difficulty) && !ommers.is_empty() {
+        return Err(BlockValidationError::OmmersNotAllowed { block_number, count: ommers.len() })
+    }
+    Ok(())
+}
+
+/// Returns [BlockValidationError::NonZeroDifficultyPostMerge] if a post merge block has a nonzero
+/// difficulty.
+///
+/// [post_block_balance_increments] only rewards blocks with a difficulty, so this must be checked
+/// before the increments are applied.
+///
+/// Unlike for [validate_no_ommers_post_merge], a block is post merge once the total difficulty of
+/// its parent reached the terminal total difficulty, so the terminal proof of work block, whose
+/// own difficulty reaches it, is not rejected if the merge block is not configured.
+pub fn validate_post_merge_difficulty(
+    chain_spec: &ChainSpec,
+    block_number: u64,
+    total_difficulty: U256,
+    block_difficulty: U256,
+) -> Result<(), BlockValidationError> {
+    let parent_total_difficulty = total_difficulty.saturating_sub(block_difficulty);
+    if block_difficulty != U256::ZERO &&
+        is_post_merge(chain_spec, block_number, parent_total_difficulty)
+    {
+        return Err(BlockValidationError::NonZeroDifficultyPostMerge {
+            block_number,
+            difficulty: block_difficulty,
+        })
+    }
+    Ok(())
+}
+
+/// Returns true if the block is post merge, i.e. its number is at least the configured merge
+/// block, or else the given total difficulty reached the terminal total difficulty.
+fn is_post_merge(chain_spec: &ChainSpec, block_number: u64, total_difficulty: U256) -> bool {
+    match chain_spec.fork(Hardfork::Paris) {
+        ForkCondition::TTD { fork_block: Some(merge_block), .. } => block_number >= merge_block,
+        paris => paris.active_at_ttd(total_difficulty, U256::ZERO),
+    }
+}
+
+/// Returns [BlockValidationError::IssuanceMismatch] if the [block_issuance] of the block is not
+/// the expected issuance, e.g. the one a chain commits to in its headers or the one reported by
+/// another client.
+pub fn assert_issuance_matches(
+    chain_spec: &ChainSpec,
+    ctx: &BlockRewardContext<'_>,
+    expected: u128,
+) -> Result<(), BlockValidationError> {
+    let got = block_issuance(chain_spec, ctx);
+    if got != expected {
+        return Err(BlockValidationError::IssuanceMismatch { got, expected })
+    }
+    Ok(())
+}
+
+/// Returns [BlockValidationError::WithdrawalsRootMismatch] if the root of the withdrawals is not
+/// the expected withdrawals root, e.g. the one of the block header.
+///
+/// Nothing is checked if no root is expected. Executors can call this before the balance
+/// increments are computed, to fail fast on withdrawals that don't match the header.
+pub fn validate_withdrawals_root(
+    withdrawals: &[Withdrawal],
+    expected_withdrawals_root: Option<B256>,
+) -> Result<(), BlockValidationError> {
+    let Some(expected) = expected_withdrawals_root else { return Ok(()) };
+    let got = calculate_withdrawals_root(withdrawals);
+    if got != expected {
+        return Err(BlockValidationError::WithdrawalsRootMismatch { got, expected })
+    }
+    Ok(())
+}
+
+/// The values a block builder needs to finalize a block, see [block_finalization].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockFinalization {
+    /// The balance increments at the end of the block, see [post_block_balance_increments].
+    pub increments: HashMap<Address, u128>,
+    /// The root of the withdrawals of the block, if Shanghai is active.
+    pub withdrawals_root: Option<B256>,
+    /// The receipts of the transactions of the block, see [execute_block_with].
+    ///
+    /// Empty if the block was not executed, e.g. for [block_finalization].
+    pub receipts: Vec<Receipt>,
+}
+
+/// Computes the balance increments and the withdrawals root of the block in a single call.
+///
+/// The increments are not applied to any state. There are no EIP-7685 requests before Prague, so
+/// no requests or requests root are computed.
+pub fn block_finalization(
+    chain_spec: &ChainSpec,
+    block: &Block,
+    total_difficulty: U256,
+) -> BlockFinalization {
+    let increments =
+        BlockRewardContext::new(block, total_difficulty).balance_increments(chain_spec);
+    let withdrawals_root = chain_spec
+        .is_shanghai_active_at_timestamp(block.timestamp)
+        .then(|| calculate_withdrawals_root(block.withdrawals.as_deref().unwrap_or_default()));
+    BlockFinalization { increments, withdrawals_root, receipts: Vec::new() }
+}
+
+/// Executes the whole lifecycle of a block around the given transaction execution.
+///
+/// This runs the pre-block system calls, then `exec_txs` to execute the transactions of the block,
+/// and finally applies the balance increments of [block_finalization] to the database of the EVM.
+/// Custom executors only need to provide the transaction loop, the receipts it returns are not
+/// checked, but returned with the [BlockFinalization].
+///
+/// The irregular state change of the DAO fork needs to drain balances from the state, so it is not
+/// applied here, see [execute_block_with_hooks].
+///
+/// Fails with [BlockValidationError::SystemCallGasExceeded] if the system calls use more gas in
+/// total than [ChainSpec::max_system_call_gas].
+///
+/// The block env of the EVM is expected to be filled for the block.
+pub fn execute_block_with<DB: Database + DatabaseCommit>(
+    chain_spec: &ChainSpec,
+    block: &Block,
+    total_difficulty: U256,
+    evm: &mut EVM<DB>,
+    exec_txs: impl FnOnce(&mut EVM<DB>) -> Result<Vec<Receipt>, BlockExecutionError>,
+) -> Result<BlockFinalization, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let finalization = execute_block_phases(chain_spec, block, total_difficulty, evm, exec_txs)?;
+    let db = evm.db().expect("db to not be moved");
+    apply_balance_increments(db, finalization.increments.clone()).map_err(|err| {
+        trace!(target: "evm", ?err, "Failed to apply balance increments");
+        BlockExecutionError::ProviderError
+    })?;
+    Ok(finalization)
+}
+
+/// Same as [execute_block_with], but for the [StateDBBox] of an executor, which additionally
+/// applies the irregular state change of the DAO fork and runs the `hooks` with the applied
+/// increments.
+///
+/// The increments of the returned [BlockFinalization] include the balances drained by the DAO
+/// fork.
+pub fn execute_block_with_hooks<'a>(
+    chain_spec: &ChainSpec,
+    block: &Block,
+    total_difficulty: U256,
+    hooks: &PostBlockHooks,
+    evm: &mut EVM<StateDBBox<'a, RethError>>,
+    exec_txs: impl FnOnce(
+        &mut EVM<StateDBBox<'a, RethError>>,
+    ) -> Result<Vec<Receipt>, BlockExecutionError>,
+) -> Result<BlockFinalization, BlockExecutionError> {
+    let mut finalization =
+        execute_block_phases(chain_spec, block, total_difficulty, evm, exec_txs)?;
+    let db = evm.db().expect("db to not be moved");
+
+    // Irregular state change at Ethereum DAO hardfork
+    if chain_spec.fork(Hardfork::Dao).transitions_at_block(block.number) {
+        // drain balances from hardcoded addresses.
+        let drained_balance: u128 = db
+            .drain_balances(DAO_HARDKFORK_ACCOUNTS)
+            .map_err(|_| BlockValidationError::IncrementBalanceFailed)?
+            .into_iter()
+            .sum();
+
+        // return balance to DAO beneficiary.
+        *finalization.increments.entry(DAO_HARDFORK_BENEFICIARY).or_default() += drained_balance;
+    }
+
+    apply_balance_increments(db, finalization.increments.clone()).map_err(|err| {
+        trace!(target: "evm", ?err, "Failed to apply balance increments");
+        BlockExecutionError::ProviderError
+    })?;
+
+    if !hooks.is_empty() {
+        hooks.run(
+            &BlockRewardContext::new(block, total_difficulty),
+            &finalization.increments,
+            db,
+        )?;
+    }
+
+    Ok(finalization)
+}
+
+/// Runs the pre-block system calls and `exec_txs`, and returns the [block_finalization] of the
+/// block with the receipts, without applying the increments.
+fn execute_block_phases<DB: Database + DatabaseCommit>(
+    chain_spec: &ChainSpec,
+    block: &Block,
+    total_difficulty: U256,
+    evm: &mut EVM<DB>,
+    exec_txs: impl FnOnce(&mut EVM<DB>) -> Result<Vec<Receipt>, BlockExecutionError>,
+) -> Result<BlockFinalization, BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    let gas_used = apply_beacon_root_contract_call(
+        chain_spec,
+        block.timestamp,
+        block.number,
+        block.parent_beacon_block_root,
+        false,
+        evm,
+    )?;
+    let max = chain_spec.max_system_call_gas;
+    if gas_used > max {
+        return Err(BlockValidationError::SystemCallGasExceeded { gas_used, max }.into())
+    }
+
+    let receipts = exec_txs(evm)?;
+
+    Ok(BlockFinalization { receipts, ..block_finalization(chain_spec, block, total_difficulty) })
+}
+
+/// Validates the post-block phase of the block without changing any state.
+///
+/// This runs the EIP-4788 beacon root call, checks that the balance increments can be credited to
+/// the current balances and that the withdrawals root, the receipts root and the logs bloom match
+/// the header. The system call is executed in simulation: it must succeed and write the expected
+/// storage, but its state changes are discarded, so the database only needs to implement
+/// [Database]. Importers can use this to reject invalid blocks before anything is committed.
+///
+/// No requests root is checked: EIP-7685 requests and the requests root of the header are
+/// introduced by Prague, which is not supported yet, so the header has no requests root to check.
+///
+/// The block env of the EVM is expected to be filled for the block.
+pub fn validate_post_block<DB: Database>(
+    chain_spec: &ChainSpec,
+    block: &Block,
+    total_difficulty: U256,
+    receipts: &[Receipt],
+    evm: &mut EVM<DB>,
+) -> Result<(), BlockExecutionError>
+where
+    <DB as Database>::Error: Debug,
+{
+    validate_no_ommers_post_merge(chain_spec, block.number, total_difficulty, &block.ommers)?;
+    validate_post_merge_difficulty(chain_spec, block.number, total_difficulty, block.difficulty)?;
+
+    if chain_spec.is_system_call_active_at_timestamp(SystemCallKind::BeaconRoot, block.timestamp) {
+        if block.number == 0 {
+            if block.parent_beacon_block_root != Some(B256::ZERO) {
+                return Err(BlockValidationError::CancunGenesisParentBeaconBlockRootNotZero.into())
+            }
+        } else {
+            let parent_beacon_block_root = block.parent_beacon_block_root.ok_or(
+                BlockExecutionError::from(BlockValidationError::MissingParentBeaconBlockRoot),
+            )?;
+            let pending = simulate_system_call(
+                evm,
+                BEACON_ROOTS_ADDRESS,
+                Bytes::copy_from_slice(parent_beacon_block_root.as_slice()),
+                chain_spec.system_call_gas_price,
+                chain_spec.max_system_call_return_size,
+            )?;
+            verify_beacon_root_write(evm, &pending, block.timestamp, parent_beacon_block_root)?;
+        }
+    }
+
+    let db = evm.db().expect("db to not be moved");
+    let increments =
+        BlockRewardContext::new(block, total_difficulty).balance_increments(chain_spec);
+    for (address, increment) in increments {
+        let balance = db
+            .basic(address)
+            .map_err(|err| {
+                trace!(target: "evm", ?err, %address, "Failed to read account");
+                BlockExecutionError::ProviderError
+            })?
+            .map(|info| info.balance)
+            .unwrap_or_default();
+        if balance.checked_add(U256::from(increment)).is_none() {
+            return Err(BlockValidationError::IncrementBalanceFailed.into())
+        }
+    }
+
+    if chain_spec.is_shanghai_active_at_timestamp(block.timestamp) {
+        validate_withdrawals_root(
+            block.withdrawals.as_deref().unwrap_or_default(),
+            block.withdrawals_root,
+        )?;
+    }
+    verify_receipt(block.receipts_root, block.logs_bloom, receipts.iter())
+}
+
+/// Applies the given balance increments to the database.
+///
+/// Every incremented account is marked as touched, and accounts that do not exist yet are marked as
+/// created, before the changes are committed. This way the database applies the same EIP-158 state
+/// clear rules as for accounts changed by transactions: an account that is still empty after the
+/// increment is removed, and an account that was self-destructed earlier in the block is
+/// recreated with the incremented balance only.
+pub fn apply_balance_increments<DB: Database + DatabaseCommit>(
+    db: &mut DB,
+    balance_increments: impl IntoIterator<Item = (Address, u128)>,
+) -> Result<(), DB::Error> {
+    let mut changes = HashMap::new();
+    for (address, increment) in balance_increments {
+        changes.insert(address, increment_account(db, address, increment)?.new);
+    }
+    db.commit(changes);
+    Ok(())
+}
+
+/// The transition of an account by a balance increment, see [balance_increments_as_transitions].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountTransition {
+    /// The account before the increment, or `None` if it did not exist.
+    pub original: Option<AccountInfo>,
+    /// The account after the increment, marked as touched, and as created if it did not exist.
+    pub new: Account,
+}
+
+/// Returns the transitions of all accounts credited by the given balance increments, sorted by
+/// address, without committing them.
+///
+/// The new accounts are the same that [apply_balance_increments] commits, so they can be appended
+/// to a transition state, while the original accounts provide the reverts of the increments.
+pub fn balance_increments_as_transitions<DB: Database>(
+    balance_increments: impl IntoIterator<Item = (Address, u128)>,
+    db: &mut DB,
+) -> Result<Vec<(Address, AccountTransition)>, BlockExecutionError> {
+    let mut transitions = balance_increments
+        .into_iter()
+        .map(|(address, increment)| {
+            increment_account(db, address, increment)
+                .map(|transition| (address, transition))
+                .map_err(|_| BlockValidationError::IncrementBalanceFailed.into())
+        })
+        .collect::<Result<Vec<_>, BlockExecutionError>>()?;
+    transitions.sort_unstable_by_key(|(address, _)| *address);
+    Ok(transitions)
+}
+
+/// Loads the account from the database and returns its transition by the balance increment.
+fn increment_account<DB: Database>(
+    db: &mut DB,
+    address: Address,
+    increment: u128,
+) -> Result<AccountTransition, DB::Error> {
+    let original = db.basic(address)?;
+    let mut new = match original.clone() {
+        Some(info) => Account::from(info),
+        None => {
+            let mut account = Account::new_not_existing();
+            account.mark_created();
+            account
+        }
+    };
+    new.info.balance = new.info.balance.saturating_add(U256::from(increment));
+    new.mark_touch();
+    Ok(AccountTransition { original, new })
+}
+
+/// The change of an account by the balance increments at the end of a block, see
+/// [reward_state_updates].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountUpdate {
+    /// The address of the account.
+    pub address: Address,
+    /// The account before the increment, or `None` if it did not exist.
+    pub previous: Option<reth_primitives::Account>,
+    /// The account after the increment.
+    pub account: reth_primitives::Account,
+}
+
+/// Returns the account updates of the balance increments at the end of the block, i.e. the block
+/// and ommer rewards and the withdrawals, keyed by the hashed address and sorted by it.
+///
+/// Each affected account is loaded from the database and credited without committing anything,
+/// so the result can be used as input of the incremental state root independently of applying the
+/// increments with [apply_balance_increments].
+pub fn reward_state_updates<DB: Database>(
+    chain_spec: &ChainSpec,
+    ctx: &BlockRewardContext<'_>,
+    db: &mut DB,
+) -> Result<Vec<(B256, AccountUpdate)>, BlockExecutionError> {
+    let mut updates = Vec::new();
+    for (address, increment) in ctx.balance_increments(chain_spec) {
+        let previous = db
+            .basic(address)
+            .map_err(|_| BlockValidationError::IncrementBalanceFailed)?
+            .map(into_reth_acc);
+        let mut account = previous.unwrap_or_default();
+        account.balance = account.balance.saturating_add(U256::from(increment));
+        updates.push((keccak256(address), AccountUpdate { address, previous, account }));
+    }
+    updates.sort_unstable_by_key(|(hashed_address, _)| *hashed_address);
+    Ok(updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::StateProviderDatabase;
+    use reth_primitives::{
+        bytes,
+        constants::{EMPTY_WITHDRAWALS, ETH_TO_WEI, GWEI_TO_WEI},
+        revm::env::fill_cfg_and_block_env,
+        Bytes, ChainSpecBuilder, ForkCondition, Wei, MAINNET,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use revm::{
+        db::{states::bundle_state::BundleRetention, CacheDB, EmptyDB},
+        primitives::Bytecode,
+        State,
+    };
+
+    static BEACON_ROOT_CONTRACT_CODE: Bytes = bytes!("3373fffffffffffffffffffffffffffffffffffffffe14604d57602036146024575f5ffd5b5f35801560495762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b62001fff42064281555f359062001fff015500");
+
+    #[test]
+    fn beacon_root_call_activation_timestamp() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1_700_000_000))
+            .build();
+        assert_eq!(
+            system_call_activation_timestamp(&chain_spec, SystemCallKind::BeaconRoot),
+            Some(1_700_000_000)
+        );
+
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        assert_eq!(system_call_activation_timestamp(&chain_spec, SystemCallKind::BeaconRoot), None);
+    }
+
+    #[test]
+    fn system_contracts_touched_by_header() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1_700_000_000))
+            .build();
+
+        let header = Header { number: 1, timestamp: 1_700_000_000, ..Default::default() };
+        assert_eq!(system_contracts_touched(&chain_spec, &header), vec![BEACON_ROOTS_ADDRESS]);
+
+        let shanghai = Header { timestamp: 1_699_999_999, ..header.clone() };
+        assert!(system_contracts_touched(&chain_spec, &shanghai).is_empty());
+
+        let genesis = Header { number: 0, ..header };
+        assert!(system_contracts_touched(&chain_spec, &genesis).is_empty());
+    }
+
+    #[test]
+    fn block_reward_and_withdrawal_to_beneficiary() {
+        // PoW chain with withdrawals enabled
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .build();
+
+        let beneficiary = Address::with_last_byte(0x42);
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 1 }];
+
+        let increments = post_block_balance_increments(
+            &chain_spec,
+            1,
+            U256::ZERO,
+            beneficiary,
+            0,
+            U256::ZERO,
+            &[],
+            Some(&withdrawals),
+        );
+
+        // pre-byzantium block reward of 5 ETH plus 1 gwei withdrawal
+        assert_eq!(increments.len(), 1);
+        assert_eq!(increments[&beneficiary], ETH_TO_WEI * 5 + withdrawals[0].amount_wei());
+    }
+
+    #[test]
+    fn recycled_base_fee_added_to_block_reward() {
+        let beneficiary = Address::with_last_byte(0x42);
+        let ctx = BlockRewardContext {
+            block_number: 1,
+            block_difficulty: U256::ZERO,
+            beneficiary,
+            block_timestamp: 0,
+            total_difficulty: U256::ZERO,
+            ommers: &[],
+            withdrawals: None,
+            reward_override: None,
+            burned_base_fee: ETH_TO_WEI * 3 + 1,
+        };
+        let increments = |chain_spec: &ChainSpec| ctx.balance_increments(chain_spec);
+
+        // no base fee is recycled by default, nor by the positional functions
+        assert_eq!(
+            post_block_balance_increments(
+                &ChainSpecBuilder::from(&*MAINNET).base_fee_recycle_bps(2_500).build(),
+                1,
+                U256::ZERO,
+                beneficiary,
+                0,
+                U256::ZERO,
+                &[],
+                None,
+            ),
+            HashMap::from([(beneficiary, ETH_TO_WEI * 5)])
+        );
+        assert_eq!(increments(&MAINNET), HashMap::from([(beneficiary, ETH_TO_WEI * 5)]));
+
+        // pre-byzantium block reward of 5 ETH plus a quarter of the burned base fee, rounded down
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).base_fee_recycle_bps(2_500).build();
+        assert_eq!(
+            increments(&chain_spec),
+            HashMap::from([(beneficiary, ETH_TO_WEI * 5 + ETH_TO_WEI * 3 / 4)])
+        );
+
+        // the recycled base fee is credited even if the block is not rewarded
+        assert_eq!(
+            BlockRewardContext { total_difficulty: U256::MAX, ..ctx }
+                .balance_increments(&chain_spec),
+            HashMap::from([(beneficiary, ETH_TO_WEI * 3 / 4)])
+        );
+    }
+
+    #[test]
+    fn ommer_reward_and_withdrawal_to_same_address() {
+        // PoW chain with withdrawals enabled
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .build();
+
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommer_beneficiary = Address::with_last_byte(0x43);
+        let ommers = [Header { number: 9, beneficiary: ommer_beneficiary, ..Default::default() }];
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 0, address: ommer_beneficiary, amount: 3 }];
+
+        let increments = post_block_balance_increments(
+            &chain_spec,
+            10,
+            U256::ZERO,
+            beneficiary,
+            0,
+            U256::ZERO,
+            &ommers,
+            Some(&withdrawals),
+        );
+
+        // the ommer reward of 7/8 of the 5 ETH base reward and the withdrawal are summed up
+        assert_eq!(increments.len(), 2);
+        assert_eq!(
+            increments[&ommer_beneficiary],
+            ETH_TO_WEI * 5 * 7 / 8 + withdrawals[0].amount_wei()
+        );
+        assert_eq!(increments[&beneficiary], ETH_TO_WEI * 5 + ETH_TO_WEI * 5 / 32);
+    }
+
+    #[test]
+    fn shanghai_block_finalization() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build();
+        let recipient = Address::with_last_byte(0xaa);
+        let withdrawals =
+            vec![Withdrawal { index: 0, validator_index: 1, address: recipient, amount: 2 }];
+        let block = Block {
+            header: Header { number: 1, timestamp: 12, ..Default::default() },
+            withdrawals: Some(withdrawals.clone()),
+            ..Default::default()
+        };
+
+        let finalization = block_finalization(&chain_spec, &block, U256::ZERO);
+        assert_eq!(finalization.increments, HashMap::from([(recipient, 2 * GWEI_TO_WEI as u128)]));
+        assert_eq!(finalization.withdrawals_root, Some(calculate_withdrawals_root(&withdrawals)));
+
+        // no withdrawals root before Shanghai
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).paris_activated().build();
+        let finalization = block_finalization(&chain_spec, &block, U256::ZERO);
+        assert_eq!(finalization.withdrawals_root, None);
+        assert!(finalization.increments.is_empty());
+    }
+
+    #[test]
+    fn process_two_withdrawals() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build();
+        let first = Address::with_last_byte(0xaa);
+        let second = Address::with_last_byte(0xbb);
+        let withdrawals = vec![
+            Withdrawal { index: 0, validator_index: 1, address: first, amount: 2 },
+            Withdrawal { index: 1, validator_index: 2, address: second, amount: 3 },
+        ];
+
+        let outcome = process_withdrawals(&chain_spec, 12, Some(&withdrawals));
+        assert_eq!(
+            outcome.increments,
+            HashMap::from([(first, 2 * GWEI_TO_WEI as u128), (second, 3 * GWEI_TO_WEI as u128)])
+        );
+        assert_eq!(outcome.root, calculate_withdrawals_root(&withdrawals));
+        assert_ne!(outcome.root, EMPTY_WITHDRAWALS);
+
+        // no increments and the empty root before Shanghai
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).paris_activated().build();
+        let outcome = process_withdrawals(&chain_spec, 12, None);
+        assert!(outcome.increments.is_empty());
+        assert_eq!(outcome.root, EMPTY_WITHDRAWALS);
+    }
+
+    #[test]
+    fn withdrawals_increments_from_iterator() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build();
+        let first = Address::with_last_byte(0xaa);
+        let second = Address::with_last_byte(0xbb);
+        let withdrawals = vec![
+            Withdrawal { index: 0, validator_index: 1, address: first, amount: 2 },
+            Withdrawal { index: 1, validator_index: 2, address: second, amount: 3 },
+            Withdrawal { index: 2, validator_index: 1, address: first, amount: 4 },
+        ];
+
+        let increments = post_block_withdrawals_balance_increments_iter(
+            &chain_spec,
+            12,
+            withdrawals.iter().filter(|withdrawal| withdrawal.validator_index == 1),
+        );
+        assert_eq!(increments, HashMap::from([(first, 6 * GWEI_TO_WEI as u128)]));
+
+        // the slice version is the same as iterating all withdrawals
+        assert_eq!(
+            post_block_withdrawals_balance_increments_iter(&chain_spec, 12, &withdrawals),
+            post_block_withdrawals_balance_increments(&chain_spec, 12, &withdrawals)
+        );
+    }
+
+    #[test]
+    fn withdrawals_credited_to_vault() {
+        let vault = Address::with_last_byte(0xff);
+        let chain_spec =
+            ChainSpecBuilder::from(&*MAINNET).shanghai_activated().withdrawal_vault(vault).build();
+        let first = Address::with_last_byte(0xaa);
+        let second = Address::with_last_byte(0xbb);
+        let withdrawals = vec![
+            Withdrawal { index: 0, validator_index: 1, address: first, amount: 2 },
+            Withdrawal { index: 1, validator_index: 2, address: second, amount: 3 },
+        ];
+        let total = 5 * GWEI_TO_WEI as u128;
+
+        assert_eq!(
+            post_block_withdrawals_balance_increments(&chain_spec, 12, &withdrawals),
+            HashMap::from([(vault, total)])
+        );
+        let ctx = BlockRewardContext {
+            block_number: 1,
+            block_difficulty: U256::ZERO,
+            beneficiary: Address::ZERO,
+            block_timestamp: 12,
+            total_difficulty: U256::MAX,
+            ommers: &[],
+            withdrawals: Some(&withdrawals),
+            reward_override: None,
+            burned_base_fee: 0,
+        };
+        assert_eq!(ctx.balance_increments(&chain_spec), HashMap::from([(vault, total)]));
+        assert_eq!(address_block_increment(&chain_spec, &ctx, vault), total);
+        assert_eq!(address_block_increment(&chain_spec, &ctx, first), 0);
+
+        // without a vault, the withdrawal addresses are credited
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build();
+        assert_eq!(
+            post_block_withdrawals_balance_increments(&chain_spec, 12, &withdrawals),
+            HashMap::from([(first, 2 * GWEI_TO_WEI as u128), (second, 3 * GWEI_TO_WEI as u128)])
+        );
+    }
+
+    #[test]
+    fn execute_block_with_runs_all_phases() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+            .build();
+        let root = B256::with_last_byte(0x69);
+        let recipient = Address::with_last_byte(0xaa);
+        let withdrawals =
+            vec![Withdrawal { index: 0, validator_index: 1, address: recipient, amount: 2 }];
+        let block = Block {
+            header: Header {
+                timestamp: 12,
+                number: 1,
+                parent_beacon_block_root: Some(root),
+                ..Header::default()
+            },
+            withdrawals: Some(withdrawals.clone()),
+            ..Default::default()
+        };
+        let [(slot, value), _] = beacon_root_write_native(12, root);
+        let receipts =
+            vec![Receipt { success: true, cumulative_gas_used: 21_000, ..Default::default() }];
+
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        let finalization = execute_block_with(&chain_spec, &block, U256::ZERO, &mut evm, |evm| {
+            // the system call ran before the transactions, the withdrawals run after them
+            let db = evm.db().unwrap();
+            assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, slot).unwrap(), value);
+            assert_eq!(db.basic(recipient).unwrap(), None);
+            Ok(receipts.clone())
+        })
+        .unwrap();
+
+        assert_eq!(
+            finalization,
+            BlockFinalization { receipts, ..block_finalization(&chain_spec, &block, U256::ZERO) }
+        );
+        assert_eq!(finalization.withdrawals_root, Some(calculate_withdrawals_root(&withdrawals)));
+        let db = evm.db().unwrap();
+        assert_eq!(
+            db.basic(recipient).unwrap().map(|info| info.balance),
+            Some(U256::from(2 * GWEI_TO_WEI))
+        );
+
+        // an error of the transaction execution aborts the block
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        assert_eq!(
+            execute_block_with(&chain_spec, &block, U256::ZERO, &mut evm, |_| {
+                Err(BlockValidationError::SenderRecoveryError.into())
+            }),
+            Err(BlockValidationError::SenderRecoveryError.into())
+        );
+        assert_eq!(evm.db().unwrap().basic(recipient).unwrap(), None);
+    }
+
+    #[test]
+    fn execute_block_with_hooks_applies_dao_fork() {
+        let dao_account = DAO_HARDKFORK_ACCOUNTS[0];
+        let chain_spec =
+            ChainSpecBuilder::mainnet().with_fork(Hardfork::Dao, ForkCondition::Block(1)).build();
+        let block =
+            Block { header: Header { number: 1, ..Header::default() }, ..Default::default() };
+
+        let observed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_observed = observed.clone();
+        let hooks = PostBlockHooks::default().with_hook(Arc::new(
+            move |_: &BlockRewardContext<'_>,
+                  increments: &HashMap<Address, u128>,
+                  _: &mut StateDBBox<'_, RethError>| {
+                hook_observed.lock().unwrap().push(increments.clone());
+                Ok(())
+            },
+        ));
+
+        // the balances of the DAO accounts are drained to the beneficiary at the DAO fork
+        let mut evm = executor_evm([(dao_account, ExtendedAccount::new(0, U256::from(5)))]);
+        let finalization =
+            execute_block_with_hooks(&chain_spec, &block, U256::ZERO, &hooks, &mut evm, |_| {
+                Ok(Vec::new())
+            })
+            .unwrap();
+        assert_eq!(finalization.increments.get(&DAO_HARDFORK_BENEFICIARY), Some(&5));
+        assert_eq!(*observed.lock().unwrap(), vec![finalization.increments.clone()]);
+        let db = evm.db().unwrap();
+        assert_eq!(db.basic(dao_account).unwrap().map(|info| info.balance), Some(U256::ZERO));
+        assert_eq!(
+            db.basic(DAO_HARDFORK_BENEFICIARY).unwrap().map(|info| info.balance),
+            Some(U256::from(5))
+        );
+
+        // an error of the transaction execution aborts the block before the hooks run
+        let mut evm = executor_evm([(dao_account, ExtendedAccount::new(0, U256::from(5)))]);
+        assert_eq!(
+            execute_block_with_hooks(&chain_spec, &block, U256::ZERO, &hooks, &mut evm, |_| {
+                Err(BlockValidationError::SenderRecoveryError.into())
+            }),
+            Err(BlockValidationError::SenderRecoveryError.into())
+        );
+        assert_eq!(observed.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn split_block_reward_between_signers() {
+        let signer_a = Address::with_last_byte(0x0a);
+        let signer_b = Address::with_last_byte(0x0b);
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .reward_split(vec![(signer_a, 5_000), (signer_b, 5_000)])
+            .build();
+
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommer =
+            Header { number: 0, beneficiary: Address::with_last_byte(0x43), ..Default::default() };
+        let increments = post_block_balance_increments(
+            &chain_spec,
+            1,
+            U256::ZERO,
+            beneficiary,
+            0,
+            U256::ZERO,
+            &[ommer.clone()],
+            None,
+        );
+
+        // pre-byzantium block reward of 5 ETH plus the ommer inclusion reward
+        let block_reward = reward_to_u128(calc::block_reward(U256::from(ETH_TO_WEI * 5), 1));
+        assert_eq!(increments.len(), 3);
+        assert_eq!(increments[&signer_a], block_reward / 2);
+        assert_eq!(increments[&signer_b], block_reward / 2);
+        assert_eq!(
+            U256::from(increments[&ommer.beneficiary]),
+            calc::ommer_reward(U256::from(ETH_TO_WEI * 5), 1, 0)
+        );
+        assert!(!increments.contains_key(&beneficiary));
+    }
+
+    #[test]
+    fn split_block_reward_remainder() {
+        let signers =
+            [Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3)];
+        let credits = split_block_reward(
+            100,
+            &[(signers[0], 3_334), (signers[1], 3_333), (signers[2], 3_333)],
+        );
+        assert_eq!(
+            credits.iter().map(|credit| credit.amount).collect::<Vec<_>>(),
+            vec![34, 33, 33]
+        );
+    }
+
+    #[test]
+    fn ommer_rewards_capped() {
+        let cap = ETH_TO_WEI * 5;
+        let chain_spec = ChainSpecBuilder::mainnet().max_ommer_reward_per_block(cap).build();
+
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommers = [
+            Header { number: 9, beneficiary: Address::with_last_byte(0x43), ..Default::default() },
+            Header { number: 8, beneficiary: Address::with_last_byte(0x44), ..Default::default() },
+        ];
+        let increments = post_block_balance_increments(
+            &chain_spec,
+            10,
+            U256::ZERO,
+            beneficiary,
+            0,
+            U256::ZERO,
+            &ommers,
+            None,
+        );
+
+        // the uncapped rewards of 7/8 and 6/8 of the 5 ETH base reward exceed the cap
+        let uncapped = ommer_rewards(U256::from(ETH_TO_WEI * 5), 10, &ommers, None);
+        assert!(uncapped.iter().map(|credit| credit.amount).sum::<u128>() > cap);
+        assert_eq!(increments[&ommers[0].beneficiary] + increments[&ommers[1].beneficiary], cap);
+        assert!(increments[&ommers[0].beneficiary] > increments[&ommers[1].beneficiary]);
+
+        // the block reward including the ommer inclusion reward is not capped
+        assert_eq!(
+            increments[&beneficiary],
+            reward_to_u128(calc::block_reward(U256::from(ETH_TO_WEI * 5), 2))
+        );
+        assert_eq!(
+            post_block_change_plan(
+                &chain_spec,
+                &Block {
+                    header: Header { number: 10, beneficiary, ..Default::default() },
+                    ommers: ommers.to_vec(),
+                    ..Default::default()
+                },
+                U256::ZERO
+            )
+            .ommer_rewards,
+            ommer_rewards(U256::from(ETH_TO_WEI * 5), 10, &ommers, Some(cap))
+        );
+
+        // rewards within the cap are unchanged
+        let capped = ommer_rewards(U256::from(ETH_TO_WEI * 5), 10, &ommers, Some(cap * 2));
+        assert_eq!(capped, uncapped);
+    }
+
+    #[test]
+    fn invalid_reward_split() {
+        let signers = [Address::with_last_byte(1), Address::with_last_byte(2)];
+        let credits = split_block_reward(100, &[(signers[0], 8_000), (signers[1], 8_000)]);
+        assert_eq!(
+            credits,
+            vec![
+                BalanceCredit { address: signers[0], amount: 80 },
+                BalanceCredit { address: signers[1], amount: 20 },
+            ]
+        );
+
+        let credits = split_block_reward(u128::MAX, &[(signers[0], u16::MAX)]);
+        assert_eq!(credits, vec![BalanceCredit { address: signers[0], amount: u128::MAX }]);
+
+        assert_eq!(recycled_base_fee(100, Some(u16::MAX)), 100);
+    }
+
+    #[test]
+    fn increments_never_contain_zero() {
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommer =
+            Header { number: 9, beneficiary: Address::with_last_byte(0x43), ..Default::default() };
+        let zero_withdrawals = [
+            Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 0 },
+            Withdrawal { index: 1, validator_index: 0, address: Address::ZERO, amount: 0 },
+        ];
+        let post_merge = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        let pre_merge = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .reward_split(vec![(beneficiary, 0), (Address::with_last_byte(0x44), 10_000)])
+            .build();
+
+        // (chain spec, ommers, withdrawals)
+        let cases: [(&ChainSpec, &[Header], Option<&[Withdrawal]>); 6] = [
+            (&post_merge, &[], None),
+            (&post_merge, &[], Some(&[])),
+            (&post_merge, &[], Some(&zero_withdrawals)),
+            (&post_merge, std::slice::from_ref(&ommer), Some(&zero_withdrawals)),
+            (&pre_merge, &[], Some(&zero_withdrawals)),
+            (&pre_merge, std::slice::from_ref(&ommer), None),
+        ];
+        for (chain_spec, ommers, withdrawals) in cases {
+            let increments = post_block_balance_increments(
+                chain_spec,
+                10,
+                U256::ZERO,
+                beneficiary,
+                0,
+                U256::ZERO,
+                ommers,
+                withdrawals,
+            );
+            assert!(
+                increments.values().all(|increment| *increment > 0),
+                "zero increment in {increments:?}"
+            );
+        }
+
+        let mut increments = HashMap::new();
+        block_reward_increments(
+            Some(U256::ZERO),
+            10,
+            beneficiary,
+            None,
+            None,
+            &[ommer],
+            &mut increments,
+        );
+        assert!(increments.is_empty());
+    }
+
+    #[test]
+    fn low_level_increments() {
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommer =
+            Header { number: 9, beneficiary: Address::with_last_byte(0x43), ..Default::default() };
+
+        let mut increments = HashMap::new();
+        block_reward_increments(
+            Some(U256::from(ETH_TO_WEI * 2)),
+            10,
+            beneficiary,
+            None,
+            None,
+            &[ommer.clone()],
+            &mut increments,
+        );
+        assert_eq!(increments[&beneficiary], ETH_TO_WEI * 2 + ETH_TO_WEI * 2 / 32);
+        assert_eq!(increments[&ommer.beneficiary], ETH_TO_WEI * 2 * 7 / 8);
+
+        let mut increments = HashMap::new();
+        block_reward_increments(None, 10, beneficiary, None, None, &[ommer], &mut increments);
+        assert!(increments.is_empty());
+
+        let withdrawals = [
+            Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 2 },
+            Withdrawal { index: 1, validator_index: 0, address: beneficiary, amount: 3 },
+            Withdrawal { index: 2, validator_index: 0, address: Address::ZERO, amount: 0 },
+        ];
+        let mut increments = HashMap::new();
+        withdrawal_increments(false, Some(&withdrawals), &mut increments);
+        assert!(increments.is_empty());
+
+        withdrawal_increments(true, Some(&withdrawals), &mut increments);
+        assert_eq!(increments, HashMap::from([(beneficiary, 5 * GWEI_TO_WEI as u128)]));
+    }
+
+    #[test]
+    fn withdrawal_amount_scale_of_one() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .withdrawal_amount_scale(U256::from(1))
+            .build();
+        let first = Address::with_last_byte(1);
+        let second = Address::with_last_byte(2);
+        let withdrawals = [
+            Withdrawal { index: 0, validator_index: 0, address: first, amount: 2 },
+            Withdrawal { index: 1, validator_index: 1, address: second, amount: 7 },
+            Withdrawal { index: 2, validator_index: 0, address: first, amount: 3 },
+        ];
+
+        let mut increments = HashMap::new();
+        insert_post_block_withdrawals_balance_increments(
+            &chain_spec,
+            0,
+            Some(&withdrawals),
+            &mut increments,
+        );
+        assert_eq!(increments, HashMap::from([(first, 5), (second, 7)]));
+
+        let hardforks = Hardforks::new(&chain_spec, 1, U256::ZERO, 0, U256::ZERO);
+        assert_eq!(hardforks.withdrawal_amount_scale, U256::from(1));
+
+        let block = Block { withdrawals: Some(withdrawals.to_vec()), ..Default::default() };
+        let plan = post_block_change_plan(&chain_spec, &block, U256::ZERO);
+        assert_eq!(
+            plan.withdrawals.iter().map(|credit| credit.amount).collect::<Vec<_>>(),
+            vec![2, 7, 3]
+        );
+    }
+
+    fn state_with_state_clear<DB: Database>(db: DB) -> State<DB> {
+        let mut state = State::builder().with_database(db).with_bundle_update().build();
+        state.set_state_clear_flag(true);
+        state
+    }
+
+    #[test]
+    fn zero_withdrawal_does_not_touch_account() {
+        let address = Address::with_last_byte(0x42);
+        let withdrawals = [Withdrawal { index: 0, validator_index: 0, address, amount: 0 }];
+
+        let mut increments = HashMap::new();
+        withdrawal_increments(true, Some(&withdrawals), &mut increments);
+        assert!(increments.is_empty());
+
+        let mut state = state_with_state_clear(EmptyDB::default());
+        apply_balance_increments(&mut state, increments).unwrap();
+        state.merge_transitions(BundleRetention::Reverts);
+        assert!(state.take_bundle().account(&address).is_none());
+    }
+
+    #[test]
+    fn withdrawal_creates_fresh_account() {
+        let address = Address::with_last_byte(0x42);
+        let withdrawals = [Withdrawal { index: 0, validator_index: 0, address, amount: 1 }];
+
+        let mut increments = HashMap::new();
+        withdrawal_increments(true, Some(&withdrawals), &mut increments);
+
+        let mut state = state_with_state_clear(EmptyDB::default());
+        apply_balance_increments(&mut state, increments).unwrap();
+        assert_eq!(
+            state.basic(address).unwrap().map(|info| info.balance),
+            Some(U256::from(GWEI_TO_WEI))
+        );
+
+        state.merge_transitions(BundleRetention::Reverts);
+        let bundle = state.take_bundle();
+        let account = bundle.account(&address).expect("account is created");
+        assert_eq!(account.info.as_ref().map(|info| info.balance), Some(U256::from(GWEI_TO_WEI)));
+        assert_eq!(account.original_info, None);
+    }
+
+    #[test]
+    fn zero_increment_to_fresh_account_is_cleared() {
+        let address = Address::with_last_byte(0x42);
+
+        let mut state = state_with_state_clear(EmptyDB::default());
+        apply_balance_increments(&mut state, [(address, 0)]).unwrap();
+        assert_eq!(state.basic(address).unwrap(), None);
+
+        state.merge_transitions(BundleRetention::Reverts);
+        assert!(state.take_bundle().account(&address).is_none());
+    }
+
+    #[test]
+    fn withdrawal_to_self_destructed_account() {
+        let address = Address::with_last_byte(0x42);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            address,
+            AccountInfo { balance: U256::from(10), ..Default::default() },
+        );
+        db.insert_account_storage(address, U256::from(1), U256::from(1)).unwrap();
+
+        let mut state = state_with_state_clear(db);
+
+        // self-destructed by a transaction earlier in the block
+        let mut account = Account::from(state.basic(address).unwrap().unwrap());
+        account.mark_selfdestruct();
+        account.mark_touch();
+        state.commit(HashMap::from([(address, account)]));
+        assert_eq!(state.basic(address).unwrap(), None);
+
+        let withdrawals = [Withdrawal { index: 0, validator_index: 0, address, amount: 1 }];
+        let mut increments = HashMap::new();
+        withdrawal_increments(true, Some(&withdrawals), &mut increments);
+        apply_balance_increments(&mut state, increments).unwrap();
+
+        // the account is recreated with the withdrawn amount only and without storage
+        assert_eq!(
+            state.basic(address).unwrap().map(|info| info.balance),
+            Some(U256::from(GWEI_TO_WEI))
+        );
+        assert_eq!(state.storage(address, U256::from(1)).unwrap(), U256::ZERO);
+
+        state.merge_transitions(BundleRetention::Reverts);
+        let bundle = state.take_bundle();
+        let account = bundle.account(&address).unwrap();
+        assert!(account.was_destroyed());
+        assert_eq!(account.info.as_ref().map(|info| info.balance), Some(U256::from(GWEI_TO_WEI)));
+    }
+
+    #[test]
+    fn change_plan_lists_all_components() {
+        // Cancun is the latest supported fork, Paris is not reached so block rewards are active
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(0))
+            .build();
+
+        let beneficiary = Address::with_last_byte(0x01);
+        let ommer_beneficiary = Address::with_last_byte(0x02);
+        let recipient = Address::with_last_byte(0x03);
+        let block = Block {
+            header: Header { number: 10, beneficiary, timestamp: 1, ..Default::default() },
+            ommers: vec![Header {
+                number: 9,
+                beneficiary: ommer_beneficiary,
+                ..Default::default()
+            }],
+            withdrawals: Some(vec![
+                Withdrawal { index: 0, validator_index: 0, address: recipient, amount: 2 },
+                Withdrawal { index: 1, validator_index: 1, address: recipient, amount: 0 },
+            ]),
+            ..Default::default()
+        };
+
+        let plan = post_block_change_plan(&chain_spec, &block, U256::ZERO);
+        let base_reward = U256::from(ETH_TO_WEI * 5);
+        assert_eq!(
+            plan,
+            PostBlockChangePlan {
+                block_rewards: vec![BalanceCredit {
+                    address: beneficiary,
+                    amount: reward_to_u128(calc::block_reward(base_reward, 1))
+                }],
+                ommer_rewards: vec![BalanceCredit {
+                    address: ommer_beneficiary,
+                    amount: reward_to_u128(calc::ommer_reward(base_reward, 10, 9))
+                }],
+                withdrawals: vec![BalanceCredit {
+                    address: recipient,
+                    amount: block.withdrawals.as_ref().unwrap()[0].amount_wei()
+                }],
+                system_calls: vec![PlannedSystemCall {
+                    kind: SystemCallKind::BeaconRoot,
+                    target: BEACON_ROOTS_ADDRESS
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn beacon_root_call_sees_overlay_state() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+            .build();
+        let header = Header {
+            timestamp: 1,
+            number: 1,
+            parent_beacon_block_root: Some(B256::with_last_byte(0x69)),
+            excess_blob_gas: Some(0),
+            ..Header::default()
+        };
+
+        // the beacon root contract does not exist in the underlying database
+        let db = State::builder().with_database(EmptyDB::default()).with_bundle_update().build();
+        let mut evm = EVM::new();
+        evm.database(db);
+        fill_cfg_and_block_env(
+            &mut evm.env.cfg,
+            &mut evm.env.block,
+            &chain_spec,
+            &header,
+            U256::ZERO,
+        );
+
+        // deploy the contract in the overlay, like a prior transaction of the block would
+        let code = Bytecode::new_raw(BEACON_ROOT_CONTRACT_CODE.clone());
+        let mut account = Account::from(AccountInfo {
+            balance: U256::ZERO,
+            nonce: 1,
+            code_hash: code.hash_slow(),
+            code: Some(code),
+        });
+        account.mark_touch();
+        account.mark_created();
+        evm.db().unwrap().commit(HashMap::from([(BEACON_ROOTS_ADDRESS, account)]));
+
+        apply_beacon_root_contract_call(
+            &chain_spec,
+            header.timestamp,
+            header.number,
+            header.parent_beacon_block_root,
+            true,
+            &mut evm,
+        )
+        .unwrap();
+
+        // the call must have executed against the overlay and committed its changes into it
+        let history_buffer_length = 8191u64;
+        let timestamp_index = header.timestamp % history_buffer_length;
+        let db = evm.db().unwrap();
+        assert_eq!(
+            db.storage(BEACON_ROOTS_ADDRESS, U256::from(timestamp_index)).unwrap(),
+            U256::from(header.timestamp)
+        );
+        assert_eq!(
+            db.storage(BEACON_ROOTS_ADDRESS, U256::from(timestamp_index + history_buffer_length))
+                .unwrap(),
+            U256::from(0x69)
+        );
+    }
+
+    #[test]
+    fn replayed_beacon_root_call_matches_recorded() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+            .build();
+        let header = Header {
+            timestamp: 1,
+            number: 1,
+            parent_beacon_block_root: Some(B256::with_last_byte(0x69)),
+            excess_blob_gas: Some(0),
+            ..Header::default()
+        };
+
+        // returns an EVM with the beacon root contract deployed on an empty database
+        let new_evm = || {
+            let mut evm = EVM::new();
+            evm.database(State::builder().with_database(EmptyDB::default()).build());
+            fill_cfg_and_block_env(
+                &mut evm.env.cfg,
+                &mut evm.env.block,
+                &chain_spec,
+                &header,
+                U256::ZERO,
+            );
+            let code = Bytecode::new_raw(BEACON_ROOT_CONTRACT_CODE.clone());
+            let mut account = Account::from(AccountInfo {
+                balance: U256::ZERO,
+                nonce: 1,
+                code_hash: code.hash_slow(),
+                code: Some(code),
+            });
+            account.mark_touch();
+            account.mark_created();
+            evm.db().unwrap().commit(HashMap::from([(BEACON_ROOTS_ADDRESS, account)]));
+            evm
+        };
+
+        let mut executed = new_evm();
+        apply_beacon_root_contract_call(
+            &chain_spec,
+            header.timestamp,
+            header.number,
+            header.parent_beacon_block_root,
+            false,
+            &mut executed,
+        )
+        .unwrap();
+        let tx = reth_primitives::revm::env::beacon_root_call_tx(B256::with_last_byte(0x69));
+        let records = [SystemCallRecord { contract: BEACON_ROOTS_ADDRESS, input: tx.data }];
+
+        let mut replayed = new_evm();
+        replay_system_calls(&records, &mut replayed).unwrap();
+
+        let storage = |evm: &mut EVM<State<EmptyDB>>| {
+            let account = evm.db().unwrap().cache.accounts.get(&BEACON_ROOTS_ADDRESS).cloned();
+            account.and_then(|account| account.account).map(|account| account.storage)
+        };
+        let executed_storage = storage(&mut executed).unwrap();
+        assert_eq!(executed_storage.len(), 2);
+        assert_eq!(Some(executed_storage), storage(&mut replayed));
+    }
+
+    /// Returns an EVM for a cancun block on top of a database with the given contract deployed.
+    fn system_contract_evm(contract: Address, code: Bytes) -> EVM<CacheDB<EmptyDB>> {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+            .build();
+        let header = Header {
+            timestamp: 12,
+            number: 1,
+            mix_hash: B256::with_last_byte(0x42),
+            excess_blob_gas: Some(0),
+            ..Header::default()
+        };
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        let code = Bytecode::new_raw(code);
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                balance: U256::ZERO,
+                nonce: 1,
+                code_hash: code.hash_slow(),
+                code: Some(code),
+            },
+        );
+        let mut evm = EVM::new();
+        evm.database(db);
+        fill_cfg_and_block_env(
+            &mut evm.env.cfg,
+            &mut evm.env.block,
+            &chain_spec,
+            &header,
+            U256::ZERO,
+        );
+        evm
+    }
+
+    /// Same env as [system_contract_evm], but with the boxed state database of the executor,
+    /// backed by the given accounts.
+    fn executor_evm(
+        accounts: impl IntoIterator<Item = (Address, ExtendedAccount)>,
+    ) -> EVM<StateDBBox<'static, RethError>> {
+        let provider = MockEthProvider::default();
+        provider.extend_accounts(accounts);
+        let state = State::builder()
+            .with_database_boxed(Box::new(StateProviderDatabase::new(provider)))
+            .with_bundle_update()
+            .without_state_clear()
+            .build();
+
+        let mut evm = EVM::new();
+        evm.database(state);
+        evm.env = system_contract_evm(Address::ZERO, Bytes::new()).env;
+        evm
+    }
+
+    #[test]
+    fn beacon_root_call_is_deterministic() {
+        let evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        let input = Bytes::copy_from_slice(B256::with_last_byte(0x69).as_slice());
+
+        assert_system_call_deterministic_with_env(
+            BEACON_ROOTS_ADDRESS,
+            input.clone(),
+            &evm,
+            evm.env.clone(),
+        )
+        .unwrap();
+        // the root is stored by timestamp, so the call depends on the perturbed timestamp
+        assert_eq!(
+            assert_system_call_deterministic(BEACON_ROOTS_ADDRESS, input, &evm),
+            Err(BlockValidationError::NondeterministicSystemCall {
+                contract: BEACON_ROOTS_ADDRESS
+            }
+            .into())
+        );
+        // the database of the EVM is not modified
+        assert!(evm.db.unwrap().accounts[&BEACON_ROOTS_ADDRESS].storage.is_empty());
+    }
+
+    #[test]
+    fn system_call_reading_timestamp_is_nondeterministic() {
+        // TIMESTAMP PUSH0 SSTORE STOP
+        let contract = Address::with_last_byte(0x77);
+        let evm = system_contract_evm(contract, bytes!("425f5500"));
+
+        assert_eq!(
+            assert_system_call_deterministic(contract, Bytes::new(), &evm),
+            Err(BlockValidationError::NondeterministicSystemCall { contract }.into())
+        );
+        // repeated executions in the same block agree
+        assert_eq!(
+            assert_system_call_deterministic_with_env(
+                contract,
+                Bytes::new(),
+                &evm,
+                evm.env.clone()
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn system_call_reading_prevrandao_is_nondeterministic() {
+        // PREVRANDAO PUSH1 0 SSTORE STOP
+        let contract = Address::with_last_byte(0x77);
+        let evm = system_contract_evm(contract, bytes!("4460005500"));
+
+        assert_eq!(
+            assert_system_call_deterministic(contract, Bytes::new(), &evm),
+            Err(BlockValidationError::NondeterministicSystemCall { contract }.into())
+        );
+    }
+
+    #[test]
+    fn verified_beacon_root_call_detects_wrong_contract() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+            .build();
+        let root = B256::with_last_byte(0x69);
+
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        apply_beacon_root_contract_call(&chain_spec, 12, 1, Some(root), true, &mut evm).unwrap();
+
+        // PUSH0 CALLDATALOAD PUSH0 SSTORE STOP, stores the root at slot 0
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, bytes!("5f355f5500"));
+        let [(slot, expected), _] = beacon_root_write_native(12, root);
+        assert_eq!(
+            apply_beacon_root_contract_call(&chain_spec, 12, 1, Some(root), true, &mut evm),
+            Err(BlockValidationError::BeaconRootMismatch { slot, expected, got: U256::ZERO }.into())
+        );
+
+        // without verification the wrong storage is committed
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, bytes!("5f355f5500"));
+        apply_beacon_root_contract_call(&chain_spec, 12, 1, Some(root), false, &mut evm).unwrap();
+    }
+
+    #[test]
+    fn post_block_validation_does_not_mutate_state() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+            .build();
+        let root = B256::with_last_byte(0x69);
+        let recipient = Address::with_last_byte(0xaa);
+        let withdrawals =
+            vec![Withdrawal { index: 0, validator_index: 1, address: recipient, amount: 2 }];
+        let mut block = Block {
+            header: Header {
+                timestamp: 12,
+                number: 1,
+                parent_beacon_block_root: Some(root),
+                withdrawals_root: Some(calculate_withdrawals_root(&withdrawals)),
+                ..Header::default()
+            },
+            withdrawals: Some(withdrawals),
+            ..Default::default()
+        };
+        let assert_unchanged = |evm: &mut EVM<CacheDB<EmptyDB>>| {
+            let db = evm.db().unwrap();
+            for (slot, _) in beacon_root_write_native(12, root) {
+                assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, slot).unwrap(), U256::ZERO);
+            }
+            assert_eq!(
+                db.basic(recipient).unwrap().map_or(U256::ZERO, |acc| acc.balance),
+                U256::ZERO
+            );
+        };
+
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        validate_post_block(&chain_spec, &block, U256::ZERO, &[], &mut evm).unwrap();
+        assert_unchanged(&mut evm);
+
+        // the root is checked after the beacon root call was simulated
+        let expected = B256::with_last_byte(1);
+        block.header.withdrawals_root = Some(expected);
+        assert_eq!(
+            validate_post_block(&chain_spec, &block, U256::ZERO, &[], &mut evm),
+            Err(BlockValidationError::WithdrawalsRootMismatch {
+                got: calculate_withdrawals_root(block.withdrawals.as_ref().unwrap()),
+                expected
+            }
+            .into())
+        );
+        assert_unchanged(&mut evm);
+
+        // PUSH0 PUSH0 REVERT
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, bytes!("5f5ffd"));
+        assert_eq!(
+            validate_post_block(&chain_spec, &block, U256::ZERO, &[], &mut evm),
+            Err(BlockValidationError::SystemCallFailed { contract: BEACON_ROOTS_ADDRESS }.into())
+        );
+    }
+
+    #[test]
+    fn shanghai_and_cancun_at_same_timestamp() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .paris_activated()
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(12))
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(12))
+            .build();
+        let root = B256::with_last_byte(0x69);
+        let recipient = Address::with_last_byte(0xaa);
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 1, address: recipient, amount: 2 }];
+        let [(slot, _), _] = beacon_root_write_native(12, root);
+
+        // neither fork is active right before the shared timestamp
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        evm.env.block.timestamp = U256::from(11);
+        apply_beacon_root_contract_call(&chain_spec, 11, 1, Some(root), true, &mut evm).unwrap();
+        let mut increments = HashMap::new();
+        insert_post_block_withdrawals_balance_increments(
+            &chain_spec,
+            11,
+            Some(&withdrawals),
+            &mut increments,
+        );
+        assert!(increments.is_empty());
+        assert_eq!(evm.db().unwrap().storage(BEACON_ROOTS_ADDRESS, slot).unwrap(), U256::ZERO);
+
+        // both take effect at the shared timestamp, in the order of block execution
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        apply_beacon_root_contract_call(&chain_spec, 12, 1, Some(root), true, &mut evm).unwrap();
+        insert_post_block_withdrawals_balance_increments(
+            &chain_spec,
+            12,
+            Some(&withdrawals),
+            &mut increments,
+        );
+        assert_eq!(increments, HashMap::from([(recipient, 2 * GWEI_TO_WEI as u128)]));
+
+        let db = evm.db().unwrap();
+        apply_balance_increments(db, increments).unwrap();
+        for (slot, value) in beacon_root_write_native(12, root) {
+            assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, slot).unwrap(), value);
+        }
+        assert_eq!(db.basic(recipient).unwrap().unwrap().balance, U256::from(2 * GWEI_TO_WEI));
+    }
+
+    #[test]
+    fn beacon_root_call_with_untouched_system_address() {
+        let root = B256::with_last_byte(0x69);
+        let base = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1));
+        let chain_spec = base.clone().build();
+
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        assert_eq!(evm.db().unwrap().basic(SYSTEM_ADDRESS).unwrap(), None);
+
+        apply_beacon_root_contract_call(&chain_spec, 12, 1, Some(root), true, &mut evm).unwrap();
+
+        let db = evm.db().unwrap();
+        for (slot, value) in beacon_root_write_native(12, root) {
+            assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, slot).unwrap(), value);
+        }
+        // the call does not create the system address
+        assert_eq!(db.basic(SYSTEM_ADDRESS).unwrap(), None);
+
+        // with a gas price the system address has to pay, so the call fails without a balance
+        let chain_spec = base.system_call_gas_price(U256::from(7)).build();
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+        assert!(matches!(
+            apply_beacon_root_contract_call(&chain_spec, 12, 1, Some(root), false, &mut evm),
+            Err(BlockExecutionError::Validation(BlockValidationError::EVM { .. }))
+        ));
+        let db = evm.db().unwrap();
+        for (slot, _) in beacon_root_write_native(12, root) {
+            assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, slot).unwrap(), U256::ZERO);
+        }
+        assert_eq!(db.basic(SYSTEM_ADDRESS).unwrap(), None);
+    }
+
+    #[test]
+    fn system_call_gas_price_is_paid_to_beneficiary() {
+        let gas_price = U256::from(7);
+        let coinbase = Address::with_last_byte(0xcb);
+        let base = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1));
+        let root = Some(B256::with_last_byte(0x69));
+
+        let system_balance = U256::from(u64::MAX);
+        let balances = |chain_spec: &ChainSpec| {
+            let mut evm =
+                system_contract_evm(BEACON_ROOTS_ADDRESS, BEACON_ROOT_CONTRACT_CODE.clone());
+            evm.env.block.coinbase = coinbase;
+            evm.db().unwrap().insert_account_info(
+                SYSTEM_ADDRESS,
+                AccountInfo { balance: system_balance, ..Default::default() },
+            );
+            apply_beacon_root_contract_call(chain_spec, 12, 1, root, false, &mut evm).unwrap();
+            let db = evm.db().unwrap();
+            let balance =
+                |address| db.basic(address).unwrap().map_or(U256::ZERO, |acc| acc.balance);
+            (balance(coinbase), balance(SYSTEM_ADDRESS))
+        };
+
+        // free by default
+        assert_eq!(balances(&base.clone().build()), (U256::ZERO, system_balance));
+
+        // the fee is paid by the system address
+        let (fee, remaining) = balances(&base.system_call_gas_price(gas_price).build());
+        assert!(fee > U256::ZERO);
+        assert_eq!(fee % gas_price, U256::ZERO);
+        assert_eq!(remaining, system_balance - fee);
+    }
+
+    #[test]
+    fn system_call_output_too_large() {
+        // returns 256 zero bytes
+        let code = bytes!("6101006000f3");
+        let root = Some(B256::with_last_byte(0x69));
+        let base = ChainSpecBuilder::from(&*MAINNET)
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1));
+
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, code.clone());
+        let chain_spec = base.clone().max_system_call_return_size(255).build();
+        assert_eq!(
+            apply_beacon_root_contract_call(&chain_spec, 12, 1, root, false, &mut evm),
+            Err(BlockValidationError::SystemCallOutputTooLarge {
+                contract: BEACON_ROOTS_ADDRESS,
+                size: 256,
+                max: 255,
+            }
+            .into())
+        );
+
+        let mut evm = system_contract_evm(BEACON_ROOTS_ADDRESS, code);
+        let chain_spec = base.max_system_call_return_size(256).build();
+        apply_beacon_root_contract_call(&chain_spec, 12, 1, root, false, &mut evm).unwrap();
+    }
+
+    #[test]
+    fn net_increments_of_reorg() {
+        let chain_spec = MAINNET.clone();
+        let old_miner = Address::with_last_byte(1);
+        let new_miner = Address::with_last_byte(2);
+        let ommer_miner = Address::with_last_byte(3);
+        let ommer = Header { number: 9_999_999, beneficiary: ommer_miner, ..Default::default() };
+
+        // Petersburg block reward of 2 ETH
+        let block = |beneficiary, ommers| BlockRewardContext {
+            block_number: 10_000_000,
+            block_difficulty: U256::ZERO,
+            beneficiary,
+            block_timestamp: 0,
+            total_difficulty: U256::ZERO,
+            ommers,
+            withdrawals: None,
+            reward_override: None,
+            burned_base_fee: 0,
+        };
+        let ommers = [ommer];
+        let reverted = [block(old_miner, &ommers[..])];
+        let applied = [block(new_miner, &ommers[..])];
+
+        let net = net_reorg_increments(&reverted, &applied, &chain_spec);
+        let block_reward = (ETH_TO_WEI * 2 + ((ETH_TO_WEI * 2) >> 5)) as i128;
+        // the ommer reward is included by both blocks
+        assert_eq!(net, HashMap::from([(old_miner, -block_reward), (new_miner, block_reward)]));
+        assert!(net_reorg_increments(&reverted, &reverted, &chain_spec).is_empty());
+    }
+
+    #[test]
+    fn increment_callback() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .build();
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommers = [
+            Header { number: 9, beneficiary, ..Default::default() },
+            Header { number: 8, beneficiary: Address::with_last_byte(0x43), ..Default::default() },
+        ];
+        let withdrawals = [
+            Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 1 },
+            Withdrawal { index: 1, validator_index: 1, address: Address::ZERO, amount: 0 },
+            Withdrawal { index: 2, validator_index: 2, address: Address::ZERO, amount: 2 },
+        ];
+
+        let mut observed = Vec::new();
+        let increments = post_block_balance_increments_with_callback(
+            &chain_spec,
+            10,
+            U256::ZERO,
+            beneficiary,
+            0,
+            U256::ZERO,
+            &ommers,
+            Some(&withdrawals),
+            0,
+            |address, amount, source| observed.push((address, amount, source)),
+        );
+
+        // the block reward, two ommer rewards and the two non-zero withdrawals
+        assert_eq!(observed.len(), 5);
+        assert_eq!(
+            observed
+                .iter()
+                .filter(|(_, _, source)| *source == IncrementSource::OmmerReward)
+                .count(),
+            2
+        );
+        assert_eq!(
+            observed.iter().filter(|(_, _, source)| *source == IncrementSource::Withdrawal).count(),
+            2
+        );
+
+        // the reported increments add up to the returned map
+        let mut summed = HashMap::<Address, u128>::new();
+        for (address, amount, _) in observed {
+            *summed.entry(address).or_default() += amount;
+        }
+        assert_eq!(summed, increments);
+    }
+
+    #[test]
+    fn no_ommers_post_merge() {
+        let ommers = [Header { number: 9, ..Default::default() }];
+        let ttd = U256::from(100);
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .with_fork(
+                Hardfork::Paris,
+                ForkCondition::TTD { fork_block: None, total_difficulty: ttd },
+            )
+            .build();
+
+        assert_eq!(validate_no_ommers_post_merge(&chain_spec, 10, U256::from(99), &ommers), Ok(()));
+        assert_eq!(validate_no_ommers_post_merge(&chain_spec, 10, ttd, &[]), Ok(()));
+        assert_eq!(
+            validate_no_ommers_post_merge(&chain_spec, 10, ttd, &ommers),
+            Err(BlockValidationError::OmmersNotAllowed { block_number: 10, count: 1 })
+        );
+
+        // the merge block is known, so the total difficulty is not relevant
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .with_fork(
+                Hardfork::Paris,
+                ForkCondition::TTD { fork_block: Some(11), total_difficulty: ttd },
+            )
+            .build();
+        assert_eq!(validate_no_ommers_post_merge(&chain_spec, 10, ttd, &ommers), Ok(()));
+        assert_eq!(
+            validate_no_ommers_post_merge(&chain_spec, 11, ttd, &ommers),
+            Err(BlockValidationError::OmmersNotAllowed { block_number: 11, count: 1 })
+        );
+    }
+
+    #[test]
+    fn nonzero_difficulty_post_merge() {
+        let ttd = U256::from(100);
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .with_fork(
+                Hardfork::Paris,
+                ForkCondition::TTD { fork_block: None, total_difficulty: ttd },
+            )
+            .build();
+
+        // the terminal proof of work block reaches the terminal total difficulty
+        assert_eq!(
+            validate_post_merge_difficulty(&chain_spec, 10, ttd + U256::from(5), U256::from(10)),
+            Ok(())
+        );
+        assert_eq!(validate_post_merge_difficulty(&chain_spec, 11, ttd, U256::ZERO), Ok(()));
+        assert_eq!(
+            validate_post_merge_difficulty(&chain_spec, 11, ttd + U256::from(1), U256::from(1)),
+            Err(BlockValidationError::NonZeroDifficultyPostMerge {
+                block_number: 11,
+                difficulty: U256::from(1)
+            })
+        );
+
+        // the merge block is known, so the total difficulty is not relevant
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .with_fork(
+                Hardfork::Paris,
+                ForkCondition::TTD { fork_block: Some(11), total_difficulty: ttd },
+            )
+            .build();
+        assert_eq!(
+            validate_post_merge_difficulty(&chain_spec, 10, ttd * U256::from(2), U256::from(1)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_post_merge_difficulty(&chain_spec, 11, U256::ZERO, U256::from(1)),
+            Err(BlockValidationError::NonZeroDifficultyPostMerge {
+                block_number: 11,
+                difficulty: U256::from(1)
+            })
+        );
+    }
+
+    #[test]
+    fn withdrawals_root_mismatch() {
+        let mut withdrawals = vec![Withdrawal {
+            index: 0,
+            validator_index: 0,
+            address: Address::with_last_byte(0x42),
+            amount: 1,
+        }];
+        let expected = calculate_withdrawals_root(&withdrawals);
+        assert_eq!(validate_withdrawals_root(&withdrawals, Some(expected)), Ok(()));
+
+        withdrawals[0].amount = 2;
+        assert_eq!(validate_withdrawals_root(&withdrawals, None), Ok(()));
+        assert_eq!(
+            validate_withdrawals_root(&withdrawals, Some(expected)),
+            Err(BlockValidationError::WithdrawalsRootMismatch {
+                got: calculate_withdrawals_root(&withdrawals),
+                expected
+            })
+        );
+    }
+
+    #[test]
+    fn gwei_increments_sum_to_wei() {
+        let first = Address::with_last_byte(1);
+        let second = Address::with_last_byte(2);
+        let amounts = [(first, Gwei(1)), (second, Gwei(0)), (first, Gwei(2)), (second, Gwei(5))];
+
+        let mut increments = HashMap::new();
+        let mut credited = Vec::new();
+        sum_gwei(amounts, &mut increments, |address, amount| credited.push((*address, amount)));
+
+        let expected = |amounts: &[Gwei]| {
+            amounts.iter().fold(Wei::default(), |sum, amount| sum + amount.to_wei()).to_u128()
+        };
+        assert_eq!(
+            increments,
+            HashMap::from([(first, expected(&[Gwei(1), Gwei(2)])), (second, expected(&[Gwei(5)]))])
+        );
+        assert_eq!(increments[&first], 3 * GWEI_TO_WEI as u128);
+        // zero amounts are not credited
+        assert_eq!(credited.len(), 3);
+    }
+
+    #[test]
+    fn misaligned_withdrawal_amount_wei() {
+        let withdrawal =
+            |amount| Withdrawal { index: 3, validator_index: 0, address: Address::ZERO, amount };
+
+        assert_eq!(
+            withdrawal_amount_wei(&withdrawal(2), U256::from(GWEI_TO_WEI)),
+            Ok(2 * GWEI_TO_WEI as u128)
+        );
+        assert_eq!(withdrawal_amount_wei(&withdrawal(2), U256::from(1)), Ok(2));
+
+        // the converted amount saturates at u128::MAX, which is not a multiple of the scale
+        let scale = U256::from(10).pow(U256::from(30));
+        let converted = withdrawal_amount_wei(&withdrawal(u64::MAX), scale);
+        if cfg!(debug_assertions) {
+            assert_eq!(
+                converted,
+                Err(BlockValidationError::WithdrawalAmountNotGweiAligned { index: 3 })
+            );
+        } else {
+            assert_eq!(converted, Ok(u128::MAX));
+        }
+    }
+
+    #[test]
+    fn group_withdrawals_by_validator() {
+        let address = Address::with_last_byte(1);
+        let withdrawal =
+            |index, validator_index, amount| Withdrawal { index, validator_index, address, amount };
+        let withdrawals =
+            [withdrawal(0, 7, 1), withdrawal(1, 8, 2), withdrawal(2, 7, 3), withdrawal(3, 9, 0)];
+
+        let groups = group_withdrawals(&withdrawals, |withdrawal| withdrawal.validator_index);
+        assert_eq!(
+            groups,
+            HashMap::from([(7, 4 * GWEI_TO_WEI as u128), (8, 2 * GWEI_TO_WEI as u128)])
+        );
+
+        // grouping by address sums like the balance increments
+        let mut increments = HashMap::new();
+        withdrawal_increments(true, Some(&withdrawals), &mut increments);
+        assert_eq!(group_withdrawals(&withdrawals, |withdrawal| withdrawal.address), increments);
+    }
+
+    #[test]
+    fn single_address_increment() {
+        let base = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0));
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommer_beneficiary = Address::with_last_byte(0x43);
+        let ommers = [
+            Header { number: 9, beneficiary, ..Default::default() },
+            Header { number: 8, beneficiary: ommer_beneficiary, ..Default::default() },
+        ];
+        let withdrawals = [
+            Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 1 },
+            Withdrawal { index: 1, validator_index: 1, address: ommer_beneficiary, amount: 0 },
+            Withdrawal { index: 2, validator_index: 2, address: Address::ZERO, amount: 2 },
+            Withdrawal { index: 3, validator_index: 3, address: Address::ZERO, amount: 3 },
+        ];
+        let ctx = BlockRewardContext {
+            block_number: 10,
+            block_difficulty: U256::ZERO,
+            beneficiary,
+            block_timestamp: 0,
+            total_difficulty: U256::ZERO,
+            ommers: &ommers,
+            withdrawals: Some(&withdrawals),
+            reward_override: None,
+            burned_base_fee: 1_000_000,
+        };
+
+        for chain_spec in [base.clone().build(), base.base_fee_recycle_bps(2_500).build()] {
+            let increments = ctx.balance_increments(&chain_spec);
+            for address in [beneficiary, ommer_beneficiary, Address::ZERO, Address::random()] {
+                assert_eq!(
+                    address_block_increment(&chain_spec, &ctx, address),
+                    increments.get(&address).copied().unwrap_or_default()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn increments_into_reused_buffer() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .build();
+        let ommers = [Header { number: 9, beneficiary: Address::random(), ..Default::default() }];
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 0, address: Address::random(), amount: 1 }];
+        let first = BlockRewardContext {
+            block_number: 10,
+            block_difficulty: U256::ZERO,
+            beneficiary: Address::random(),
+            block_timestamp: 0,
+            total_difficulty: U256::ZERO,
+            ommers: &ommers,
+            withdrawals: Some(&withdrawals),
+            reward_override: None,
+            burned_base_fee: 0,
+        };
+        let second = BlockRewardContext {
+            block_number: 11,
+            beneficiary: Address::random(),
+            ommers: &[],
+            withdrawals: None,
+            ..first
+        };
+
+        let mut buffer = HashMap::new();
+        for ctx in [first, second] {
+            compute_post_block_balance_increments_into(&chain_spec, &ctx, &mut buffer);
+            assert_eq!(buffer, ctx.balance_increments(&chain_spec));
+        }
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[test]
+    fn increments_without_total_difficulty() {
+        let beneficiary = Address::with_last_byte(0x42);
+        let merge_block = 15_537_394;
+        let increments = |block_number| {
+            post_block_balance_increments_with_optional_td(
+                &MAINNET,
+                block_number,
+                U256::ZERO,
+                beneficiary,
+                0,
+                None,
+                &[],
+                None,
+            )
+        };
+
+        // the merge block of the chain spec decides whether there is a block reward
+        assert_eq!(increments(merge_block - 1), Ok(HashMap::from([(beneficiary, ETH_TO_WEI * 2)])));
+        assert_eq!(increments(merge_block), Ok(HashMap::new()));
+
+        // a known total difficulty takes precedence
+        assert_eq!(
+            post_block_balance_increments_with_optional_td(
+                &MAINNET,
+                merge_block,
+                U256::ZERO,
+                beneficiary,
+                0,
+                Some(U256::ZERO),
+                &[],
+                None,
+            ),
+            Ok(HashMap::from([(beneficiary, ETH_TO_WEI * 2)]))
+        );
+
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(
+                Hardfork::Paris,
+                ForkCondition::TTD { fork_block: None, total_difficulty: U256::from(100) },
+            )
+            .build();
+        assert_eq!(
+            post_block_balance_increments_with_optional_td(
+                &chain_spec,
+                1,
+                U256::ZERO,
+                beneficiary,
+                0,
+                None,
+                &[],
+                None,
+            ),
+            Err(BlockValidationError::UnknownMergeStatus { block_number: 1 })
+        );
+    }
+
+    #[test]
+    fn reward_override_bypasses_schedule() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .build();
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommer_beneficiary = Address::with_last_byte(0x43);
+        let ommers = [Header { number: 9, beneficiary: ommer_beneficiary, ..Default::default() }];
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 1 }];
+        let ctx = BlockRewardContext {
+            block_number: 10,
+            block_difficulty: U256::ZERO,
+            beneficiary,
+            block_timestamp: 0,
+            total_difficulty: U256::ZERO,
+            ommers: &ommers,
+            withdrawals: Some(&withdrawals),
+            reward_override: None,
+            burned_base_fee: 0,
+        };
+        let reward = 3 * ETH_TO_WEI;
+
+        // the schedule pays 5 ETH plus the ommer inclusion reward, and rewards the ommer
+        let scheduled = ctx.balance_increments(&chain_spec);
+        assert!(scheduled[&beneficiary] > ETH_TO_WEI * 5);
+        assert!(scheduled.contains_key(&ommer_beneficiary));
+
+        let ctx = ctx.with_reward_override(reward);
+        let mut credited = Vec::new();
+        let mut increments = HashMap::new();
+        add_post_block_balance_increments(
+            &chain_spec,
+            &ctx,
+            &mut increments,
+            &mut |address, amount, source| credited.push((address, amount, source)),
+        );
+        assert_eq!(
+            increments,
+            HashMap::from([(beneficiary, reward + withdrawals[0].amount_wei())])
+        );
+        assert_eq!(credited[0], (beneficiary, reward, IncrementSource::BlockReward));
+        assert_eq!(
+            address_block_increment(&chain_spec, &ctx, beneficiary),
+            increments[&beneficiary]
+        );
+        assert_eq!(address_block_increment(&chain_spec, &ctx, ommer_beneficiary), 0);
+
+        // the override is credited even if the schedule has no reward, e.g. after the merge
+        let ctx = BlockRewardContext { total_difficulty: U256::MAX, withdrawals: None, ..ctx };
+        assert_eq!(ctx.balance_increments(&chain_spec), HashMap::from([(beneficiary, reward)]));
+        let ctx = ctx.with_reward_override(0);
+        assert!(ctx.balance_increments(&chain_spec).is_empty());
+    }
+
+    #[test]
+    fn issuance_matches_expected() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .build();
+        let beneficiary = Address::with_last_byte(0x42);
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 1 }];
+        let ctx = BlockRewardContext {
+            block_number: 10,
+            block_difficulty: U256::ZERO,
+            beneficiary,
+            block_timestamp: 0,
+            total_difficulty: U256::ZERO,
+            ommers: &[],
+            withdrawals: Some(&withdrawals),
+            reward_override: None,
+            burned_base_fee: 0,
+        };
+
+        // the withdrawal is credited, but not issued
+        let reward = ETH_TO_WEI * 5;
+        assert_eq!(block_issuance(&chain_spec, &ctx), reward);
+        assert_eq!(assert_issuance_matches(&chain_spec, &ctx, reward), Ok(()));
+        assert_eq!(
+            assert_issuance_matches(&chain_spec, &ctx, reward + withdrawals[0].amount_wei()),
+            Err(BlockValidationError::IssuanceMismatch {
+                got: reward,
+                expected: reward + withdrawals[0].amount_wei()
+            })
+        );
+    }
+
+    #[test]
+    fn increments_from_hardforks_snapshot() {
+        let beneficiary = Address::with_last_byte(0x42);
+        let ommer_beneficiary = Address::with_last_byte(0x43);
+        let ommers = [Header { number: 9, beneficiary: ommer_beneficiary, ..Default::default() }];
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 0, address: beneficiary, amount: 1 }];
+        let ctx = BlockRewardContext {
+            block_number: 10,
+            block_difficulty: U256::ZERO,
+            beneficiary,
+            block_timestamp: 0,
+            total_difficulty: U256::ZERO,
+            ommers: &ommers,
+            withdrawals: Some(&withdrawals),
+            reward_override: None,
+            burned_base_fee: 0,
+        };
+
+        // built without a chain spec
+        let hardforks = Hardforks {
+            shanghai: true,
+            reward_schedule: RewardSchedule {
+                base_block_reward: Some(U256::from(ETH_TO_WEI * 2)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let increments = post_block_balance_increments_with_hardforks(&hardforks, &ctx);
+        // 2 ETH plus 1/32 for the ommer, and 7/8 of 2 ETH for the ommer one block behind
+        assert_eq!(
+            increments,
+            HashMap::from([
+                (beneficiary, ETH_TO_WEI * 2 + ETH_TO_WEI / 16 + withdrawals[0].amount_wei()),
+                (ommer_beneficiary, ETH_TO_WEI * 2 / 8 * 7),
+            ])
+        );
+
+        // the snapshot resolved from a chain spec yields the same increments
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(0))
+            .build();
+        let hardforks = Hardforks::new(&chain_spec, 10, U256::ZERO, 0, U256::ZERO);
+        assert!(hardforks.shanghai && hardforks.cancun);
+        assert_eq!(
+            post_block_balance_increments_with_hardforks(&hardforks, &ctx),
+            ctx.balance_increments(&chain_spec)
+        );
+
+        let header = Header { number: 1, ..Default::default() };
+        assert_eq!(
+            system_contracts_touched_with_hardforks(&hardforks, &header),
+            system_contracts_touched(&chain_spec, &header)
+        );
+        assert!(system_contracts_touched_with_hardforks(&Hardforks::default(), &header).is_empty());
+    }
+
+    #[test]
+    fn increments_as_transitions() {
+        let existing = Address::with_last_byte(1);
+        let created = Address::with_last_byte(2);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            existing,
+            AccountInfo { balance: U256::from(5), nonce: 1, ..Default::default() },
+        );
+        let increments = HashMap::from([(created, 7), (existing, 3)]);
+
+        let transitions = balance_increments_as_transitions(increments.clone(), &mut db).unwrap();
+
+        assert_eq!(
+            transitions.iter().map(|(address, _)| *address).collect::<Vec<_>>(),
+            vec![existing, created]
+        );
+        for (address, AccountTransition { original, new }) in &transitions {
+            let original_balance = original.as_ref().map_or(U256::ZERO, |info| info.balance);
+            assert_eq!(original_balance + U256::from(increments[address]), new.info.balance);
+            assert!(new.is_touched());
+        }
+        assert_eq!(transitions[0].1.new.info.nonce, 1);
+        assert_eq!(transitions[1].1.original, None);
+        assert!(transitions[1].1.new.is_created());
+
+        // nothing is committed
+        assert_eq!(db.basic(existing).unwrap().unwrap().balance, U256::from(5));
+        assert_eq!(db.basic(created).unwrap(), None);
+    }
+
+    #[test]
+    fn reward_state_updates_without_commit() {
+        let existing = Address::with_last_byte(1);
+        let created = Address::with_last_byte(2);
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build();
+        let withdrawals = [
+            Withdrawal { index: 0, validator_index: 0, address: existing, amount: 1 },
+            Withdrawal { index: 1, validator_index: 0, address: created, amount: 2 },
+            Withdrawal { index: 2, validator_index: 0, address: existing, amount: 3 },
+        ];
+        let ctx = BlockRewardContext {
+            block_number: 1,
+            block_difficulty: U256::ZERO,
+            beneficiary: Address::ZERO,
+            block_timestamp: 1,
+            total_difficulty: U256::ZERO,
+            ommers: &[],
+            withdrawals: Some(&withdrawals),
+            reward_override: None,
+            burned_base_fee: 0,
+        };
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            existing,
+            AccountInfo { balance: U256::from(5), nonce: 1, ..Default::default() },
+        );
+
+        let updates = reward_state_updates(&chain_spec, &ctx, &mut db).unwrap();
+
+        let gwei = U256::from(GWEI_TO_WEI);
+        let mut expected = vec![
+            (
+                keccak256(existing),
+                AccountUpdate {
+                    address: existing,
+                    previous: Some(reth_primitives::Account {
+                        balance: U256::from(5),
+                        nonce: 1,
+                        bytecode_hash: None,
+                    }),
+                    account: reth_primitives::Account {
+                        balance: U256::from(5) + gwei * U256::from(4),
+                        nonce: 1,
+                        bytecode_hash: None,
+                    },
+                },
+            ),
+            (
+                keccak256(created),
+                AccountUpdate {
+                    address: created,
+                    previous: None,
+                    account: reth_primitives::Account {
+                        balance: gwei * U256::from(2),
+                        ..Default::default()
+                    },
+                },
+            ),
+        ];
+        expected.sort_unstable_by_key(|(hashed_address, _)| *hashed_address);
+        assert_eq!(updates, expected);
+
+        // nothing was committed
+        assert_eq!(db.basic(existing).unwrap().map(|info| info.balance), Some(U256::from(5)));
+        assert_eq!(db.basic(created).unwrap(), None);
     }
 }
@@ -7,7 +7,7 @@ use reth_rpc_types::{
         ForkchoiceUpdated, PayloadAttributes, PayloadId, PayloadStatus, TransitionConfiguration,
     },
     state::StateOverride,
-    BlockOverrides, CallRequest, Filter, Log, RichBlock, SyncStatus,
+    BlockOverrides, CallRequest, Filter, Log, RichBlock, SyncStatus, TransactionReceipt,
 };
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "engine"))]
@@ -187,4 +187,9 @@ pub trait EngineEthApi {
     /// Returns logs matching given filter object.
     #[method(name = "getLogs")]
     async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>>;
+
+    /// Returns all transaction receipts for a given block.
+    #[method(name = "getBlockReceipts")]
+    async fn block_receipts(&self, block_id: BlockId)
+        -> RpcResult<Option<Vec<TransactionReceipt>>>;
 }
@@ -4,9 +4,9 @@ use reth_primitives::{
     Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64,
 };
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, BlockOverrides, Bundle, CallRequest,
-    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock, StateContext,
-    SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
+    state::StateOverride, AccessListWithGasUsed, BaseFeeProjection, BlockOverrides, Bundle,
+    CallRequest, EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock,
+    StateContext, SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
 };
 
 /// Eth rpc interface: <https://ethereum.github.io/execution-apis/api-documentation/>
@@ -215,6 +215,18 @@ pub trait EthApi {
         reward_percentiles: Option<Vec<f64>>,
     ) -> RpcResult<FeeHistory>;
 
+    /// Returns the projected base fee and blob fee of the next `block_count` blocks after the
+    /// latest block.
+    ///
+    /// This is a non-standard method. Every projected block is assumed to use the fraction
+    /// `assumed_fullness`, between 0 and 1, of its gas limit and of the maximum blob gas.
+    #[method(name = "baseFeeHistoryProjection")]
+    async fn base_fee_history_projection(
+        &self,
+        block_count: U64HexOrNumber,
+        assumed_fullness: f64,
+    ) -> RpcResult<Vec<BaseFeeProjection>>;
+
     /// Returns whether the client is actively mining new blocks.
     #[method(name = "mining")]
     async fn is_mining(&self) -> RpcResult<bool>;
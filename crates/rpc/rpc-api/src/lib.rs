@@ -47,7 +47,7 @@ pub mod servers {
         mev::MevApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
-        reth::RethApiServer,
+        reth::{RethApiServer, RethPubSubApiServer},
         rpc::RpcApiServer,
         trace::TraceApiServer,
         txpool::TxPoolApiServer,
@@ -1,5 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, U256};
+use reth_primitives::{Address, BlockId, TxHash, U256};
+use reth_rpc_types::{
+    pubsub::{PendingTransactionFilter, RethSubscriptionKind},
+    ReceiptProof,
+};
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -12,4 +16,25 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the proof that the receipt of the transaction is included in the receipts root of
+    /// its block, or `None` if the transaction is unknown.
+    #[method(name = "getReceiptProof")]
+    async fn reth_get_receipt_proof(&self, tx_hash: TxHash) -> RpcResult<Option<ReceiptProof>>;
+}
+
+/// Reth pub-sub rpc interface.
+#[rpc(server, namespace = "reth")]
+pub trait RethPubSubApi {
+    /// Create a reth subscription for the given kind and filter
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = reth_rpc_types::pubsub::PendingTransactionSubscriptionResult
+    )]
+    async fn subscribe(
+        &self,
+        kind: RethSubscriptionKind,
+        filter: Option<PendingTransactionFilter>,
+    ) -> jsonrpsee::core::SubscriptionResult;
 }
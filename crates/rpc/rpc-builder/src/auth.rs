@@ -2,7 +2,7 @@ use crate::{
     constants,
     constants::DEFAULT_MAX_LOGS_PER_RESPONSE,
     error::{RpcError, ServerKind},
-    EthConfig,
+    EthConfig, MethodScope, ENGINE_ETH_METHODS,
 };
 use hyper::header::AUTHORIZATION;
 pub use jsonrpsee::server::ServerBuilder;
@@ -65,6 +65,7 @@ where
         eth_cache.clone(),
         gas_oracle,
         EthConfig::default().rpc_gas_cap,
+        EthConfig::default().call_limits(),
         Box::new(executor.clone()),
         BlockingTaskPool::build().expect("failed to build tracing pool"),
     );
@@ -103,8 +104,7 @@ where
     // Configure the module and start the server.
     let mut module = RpcModule::new(());
     module.merge(engine_api.into_rpc()).expect("No conflicting methods");
-    let engine_eth = EngineEthApi::new(eth_api, eth_filter);
-    module.merge(engine_eth.into_rpc()).expect("No conflicting methods");
+    module.merge(engine_eth_module(eth_api, eth_filter)).expect("No conflicting methods");
 
     // Create auth middleware.
     let middleware =
@@ -123,6 +123,27 @@ where
     Ok(AuthServerHandle { handle, local_addr, secret })
 }
 
+/// Returns the module with the `eth` methods of the auth server.
+///
+/// Only the [ENGINE_ETH_METHODS] are exposed, not the whole `eth` namespace.
+pub(crate) fn engine_eth_module<Eth, EthFilter>(eth: Eth, eth_filter: EthFilter) -> RpcModule<()>
+where
+    Eth: EthApiServer,
+    EthFilter: EthFilterApiServer,
+{
+    let mut module = RpcModule::new(());
+    module.merge(EngineEthApi::new(eth, eth_filter).into_rpc()).expect("No conflicting methods");
+
+    let unlisted = module
+        .method_names()
+        .filter(|method| !ENGINE_ETH_METHODS.contains(method))
+        .collect::<Vec<_>>();
+    for method in unlisted {
+        module.remove_method(method);
+    }
+    module
+}
+
 /// Server configuration for the auth server.
 #[derive(Debug)]
 pub struct AuthServerConfig {
@@ -132,6 +153,8 @@ pub struct AuthServerConfig {
     pub(crate) secret: JwtSecret,
     /// Configs for JSON-RPC Http.
     pub(crate) server_config: ServerBuilder,
+    /// Restricts the methods that can be called, if set.
+    pub(crate) method_scope: Option<MethodScope>,
 }
 
 // === impl AuthServerConfig ===
@@ -149,7 +172,7 @@ impl AuthServerConfig {
 
     /// Convenience function to start a server in one step.
     pub async fn start(self, module: AuthRpcModule) -> Result<AuthServerHandle, RpcError> {
-        let Self { socket_addr, secret, server_config } = self;
+        let Self { socket_addr, secret, server_config, method_scope } = self;
 
         // Create auth middleware.
         let middleware = tower::ServiceBuilder::new()
@@ -163,7 +186,12 @@ impl AuthServerConfig {
 
         let local_addr = server.local_addr()?;
 
-        let handle = server.start(module.inner);
+        let mut module = module.inner;
+        if let Some(scope) = method_scope {
+            scope.restrict(&mut module);
+        }
+
+        let handle = server.start(module);
         Ok(AuthServerHandle { handle, local_addr, secret })
     }
 }
@@ -174,6 +202,7 @@ pub struct AuthServerConfigBuilder {
     socket_addr: Option<SocketAddr>,
     secret: JwtSecret,
     server_config: Option<ServerBuilder>,
+    method_scope: Option<MethodScope>,
 }
 
 // === impl AuthServerConfigBuilder ===
@@ -181,7 +210,7 @@ pub struct AuthServerConfigBuilder {
 impl AuthServerConfigBuilder {
     /// Create a new `AuthServerConfigBuilder` with the given `secret`.
     pub fn new(secret: JwtSecret) -> Self {
-        Self { socket_addr: None, secret, server_config: None }
+        Self { socket_addr: None, secret, server_config: None, method_scope: None }
     }
 
     /// Set the socket address for the server.
@@ -211,6 +240,15 @@ impl AuthServerConfigBuilder {
         self
     }
 
+    /// Restricts the methods that can be called on the server to the given [MethodScope].
+    ///
+    /// For example, this can limit the server to the `engine` namespace and the
+    /// [ENGINE_ETH_METHODS](crate::ENGINE_ETH_METHODS).
+    pub fn with_method_scope(mut self, scope: MethodScope) -> Self {
+        self.method_scope = Some(scope);
+        self
+    }
+
     /// Build the `AuthServerConfig`.
     pub fn build(self) -> AuthServerConfig {
         AuthServerConfig {
@@ -234,6 +272,7 @@ impl AuthServerConfigBuilder {
                     .max_request_body_size(25 * 1024 * 1024)
                     .set_id_provider(EthSubscriptionIdProvider::default())
             }),
+            method_scope: self.method_scope,
         }
     }
 }
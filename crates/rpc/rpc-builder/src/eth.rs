@@ -3,8 +3,10 @@ use reth_rpc::{
     eth::{
         cache::{EthStateCache, EthStateCacheConfig},
         gas_oracle::GasPriceOracleConfig,
-        RPC_DEFAULT_GAS_CAP,
+        revm_utils::EvmCallLimits,
+        DevSignerConfig, RPC_DEFAULT_GAS_CAP, RPC_DEFAULT_MEMORY_LIMIT,
     },
+    trace_cache::{TraceCache, TraceCacheConfig},
     BlockingTaskPool, EthApi, EthFilter, EthPubSub,
 };
 use serde::{Deserialize, Serialize};
@@ -22,6 +24,8 @@ pub struct EthHandlers<Provider, Pool, Network, Events> {
     pub pubsub: EthPubSub<Provider, Pool, Events, Network>,
     /// The configured tracing call pool
     pub blocking_task_pool: BlockingTaskPool,
+    /// The cache for the traces of recently traced blocks
+    pub trace_cache: TraceCache,
 }
 
 /// Additional config values for the eth namespace
@@ -31,6 +35,9 @@ pub struct EthConfig {
     pub cache: EthStateCacheConfig,
     /// Settings for the gas price oracle
     pub gas_oracle: GasPriceOracleConfig,
+    /// Settings for the cache of block traces
+    #[serde(default)]
+    pub trace_cache: TraceCacheConfig,
     /// The maximum number of tracing calls that can be executed in concurrently.
     pub max_tracing_requests: u32,
     /// Maximum number of logs that can be returned in a single response in `eth_getLogs` calls.
@@ -39,9 +46,20 @@ pub struct EthConfig {
     ///
     /// Defaults to [RPC_DEFAULT_GAS_CAP]
     pub rpc_gas_cap: u64,
+    /// Maximum memory in bytes that `eth_call` and call tracing RPC methods can allocate.
+    ///
+    /// Defaults to [RPC_DEFAULT_MEMORY_LIMIT]
+    pub rpc_memory_limit: u64,
+    /// Overrides the maximum depth of nested calls for `eth_call`, `eth_callMany` and
+    /// `eth_estimateGas`.
+    pub rpc_max_call_depth: Option<u64>,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: std::time::Duration,
+    /// The keys of the accounts the node signs with, e.g. for `eth_sendTransaction`.
+    ///
+    /// Empty by default, so the node has no accounts.
+    pub dev_signer: DevSignerConfig,
 }
 
 /// Default value for stale filter ttl
@@ -52,10 +70,14 @@ impl Default for EthConfig {
         Self {
             cache: EthStateCacheConfig::default(),
             gas_oracle: GasPriceOracleConfig::default(),
+            trace_cache: TraceCacheConfig::default(),
             max_tracing_requests: DEFAULT_MAX_TRACING_REQUESTS,
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            rpc_memory_limit: RPC_DEFAULT_MEMORY_LIMIT,
+            rpc_max_call_depth: None,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
+            dev_signer: DevSignerConfig::default(),
         }
     }
 }
@@ -73,6 +95,12 @@ impl EthConfig {
         self
     }
 
+    /// Configures the cache of block traces
+    pub fn trace_cache(mut self, trace_cache: TraceCacheConfig) -> Self {
+        self.trace_cache = trace_cache;
+        self
+    }
+
     /// Configures the maximum number of tracing requests
     pub fn max_tracing_requests(mut self, max_requests: u32) -> Self {
         self.max_tracing_requests = max_requests;
@@ -90,4 +118,30 @@ impl EthConfig {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures the keys of the accounts the node signs with
+    pub fn dev_signer(mut self, dev_signer: DevSignerConfig) -> Self {
+        self.dev_signer = dev_signer;
+        self
+    }
+
+    /// Configures the maximum memory in bytes for `eth_call` and call tracing RPC methods
+    pub fn rpc_memory_limit(mut self, rpc_memory_limit: u64) -> Self {
+        self.rpc_memory_limit = rpc_memory_limit;
+        self
+    }
+
+    /// Configures the maximum depth of nested calls for `eth_call` and adjacent methods
+    pub fn rpc_max_call_depth(mut self, rpc_max_call_depth: Option<u64>) -> Self {
+        self.rpc_max_call_depth = rpc_max_call_depth;
+        self
+    }
+
+    /// Returns the limits of the EVM for `eth_call` and call tracing RPC methods
+    pub fn call_limits(&self) -> EvmCallLimits {
+        EvmCallLimits {
+            memory_limit: self.rpc_memory_limit,
+            max_call_depth: self.rpc_max_call_depth,
+        }
+    }
 }
@@ -116,10 +116,12 @@ use reth_rpc::{
     eth::{
         cache::{cache_new_blocks_task, EthStateCache},
         gas_oracle::GasPriceOracle,
+        DevSigner,
     },
-    AdminApi, BlockingTaskGuard, BlockingTaskPool, DebugApi, EngineEthApi, EthApi, EthFilter,
+    trace_cache::{invalidate_reorged_traces_task, TraceCache},
+    AdminApi, BlockTraceStreamer, BlockingTaskGuard, BlockingTaskPool, DebugApi, EthApi, EthFilter,
     EthPubSub, EthSubscriptionIdProvider, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi,
-    TxPoolApi, Web3Api,
+    TraceStreamLayer, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::{servers::*, EngineApiServer};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
@@ -130,11 +132,12 @@ use std::{
     fmt,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     str::FromStr,
+    sync::Arc,
 };
 use strum::{AsRefStr, EnumString, EnumVariantNames, ParseError, VariantNames};
 use tower::layer::util::{Identity, Stack};
 use tower_http::cors::CorsLayer;
-use tracing::{instrument, trace};
+use tracing::{instrument, trace, warn};
 
 /// Auth server utilities.
 pub mod auth;
@@ -154,12 +157,16 @@ pub mod constants;
 // Rpc server metrics
 mod metrics;
 
+/// Method authorization scopes.
+mod scope;
+
 // re-export for convenience
 pub use crate::eth::{EthConfig, EthHandlers};
 pub use jsonrpsee::server::ServerBuilder;
 pub use reth_ipc::server::{Builder as IpcServerBuilder, Endpoint};
 use reth_network_api::noop::NoopNetwork;
 use reth_transaction_pool::noop::NoopTransactionPool;
+pub use scope::{MethodScope, ENGINE_ETH_METHODS, METHOD_NOT_ALLOWED_CODE};
 
 /// Convenience function for starting a server in one step.
 pub async fn launch<Provider, Pool, Network, Tasks, Events>(
@@ -863,7 +870,7 @@ where
 
     /// Configures the auth module that includes the
     ///   * `engine_` namespace
-    ///   * `api_` namespace
+    ///   * [ENGINE_ETH_METHODS] of the `eth_` namespace
     ///
     /// Note: This does _not_ register the `engine_` in this registry.
     pub fn create_auth_module<EngineApi>(&mut self, engine_api: EngineApi) -> AuthRpcModule
@@ -875,9 +882,10 @@ where
 
         module.merge(engine_api.into_rpc()).expect("No conflicting methods");
 
-        // also merge a subset of `eth_` handlers
-        let engine_eth = EngineEthApi::new(eth_handlers.api.clone(), eth_handlers.filter);
-        module.merge(engine_eth.into_rpc()).expect("No conflicting methods");
+        // also merge the subset of `eth_` handlers required by the engine API
+        module
+            .merge(auth::engine_eth_module(eth_handlers.api.clone(), eth_handlers.filter))
+            .expect("No conflicting methods");
 
         AuthRpcModule { inner: module }
     }
@@ -928,6 +936,7 @@ where
             pubsub: eth_pubsub,
             cache: _,
             blocking_task_pool: _,
+            trace_cache,
         } = self.with_eth(|eth| eth.clone());
 
         // Create a copy, so we can list out all the methods for rpc_ api
@@ -947,6 +956,7 @@ where
                             eth_api.clone(),
                             Box::new(self.executor.clone()),
                             self.blocking_pool_guard.clone(),
+                            trace_cache.clone(),
                         )
                         .into_rpc()
                         .into(),
@@ -954,7 +964,9 @@ where
                             // merge all eth handlers
                             let mut module = eth_api.clone().into_rpc();
                             module.merge(eth_filter.clone().into_rpc()).expect("No conflicts");
-                            module.merge(eth_pubsub.clone().into_rpc()).expect("No conflicts");
+                            module
+                                .merge(EthPubSubApiServer::into_rpc(eth_pubsub.clone()))
+                                .expect("No conflicts");
 
                             module.into()
                         }
@@ -982,9 +994,15 @@ where
                         .into(),
                         RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
                         RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
-                                .into_rpc()
-                                .into()
+                            let mut module = RethApi::new(
+                                self.provider.clone(),
+                                Box::new(self.executor.clone()),
+                            )
+                            .into_rpc();
+                            module
+                                .merge(RethPubSubApiServer::into_rpc(eth_pubsub.clone()))
+                                .expect("No conflicts");
+                            module.into()
                         }
                     })
                     .clone()
@@ -1035,9 +1053,15 @@ where
                 cache.clone(),
                 gas_oracle,
                 self.config.eth.rpc_gas_cap,
+                self.config.eth.call_limits(),
                 executor.clone(),
                 blocking_task_pool.clone(),
             );
+            match DevSigner::from_config(&self.config.eth.dev_signer) {
+                Ok(Some(signer)) => api.add_signer(Arc::new(signer)),
+                Ok(None) => {}
+                Err(err) => warn!(target: "rpc", %err, "Failed to create the dev signer"),
+            }
             let filter = EthFilter::new(
                 self.provider.clone(),
                 self.pool.clone(),
@@ -1046,6 +1070,14 @@ where
                 executor.clone(),
                 self.config.eth.stale_filter_ttl,
             );
+            let reorgs = self.events.canonical_state_stream();
+            let f = filter.clone();
+            self.executor.spawn_critical(
+                "eth filter reorgs task",
+                Box::pin(async move {
+                    f.watch_reorgs(reorgs).await;
+                }),
+            );
 
             let pubsub = EthPubSub::with_spawner(
                 self.provider.clone(),
@@ -1055,7 +1087,17 @@ where
                 executor,
             );
 
-            let eth = EthHandlers { api, cache, filter, pubsub, blocking_task_pool };
+            let trace_cache = TraceCache::new(self.config.eth.trace_cache.clone());
+            let reorgs = self.events.canonical_state_stream();
+            let t = trace_cache.clone();
+            self.executor.spawn_critical(
+                "trace cache reorgs task",
+                Box::pin(async move {
+                    invalidate_reorged_traces_task(t, reorgs).await;
+                }),
+            );
+
+            let eth = EthHandlers { api, cache, filter, pubsub, blocking_task_pool, trace_cache };
             self.eth = Some(eth);
         }
         f(self.eth.as_ref().expect("exists; qed"))
@@ -1084,12 +1126,13 @@ where
 
     /// Instantiates DebugApi
     pub fn debug_api(&mut self) -> DebugApi<Provider, EthApi<Provider, Pool, Network>> {
-        let eth_api = self.eth_api();
+        let eth = self.eth_handlers();
         DebugApi::new(
             self.provider.clone(),
-            eth_api,
+            eth.api,
             Box::new(self.executor.clone()),
             self.blocking_pool_guard.clone(),
+            eth.trace_cache,
         )
     }
 
@@ -1124,12 +1167,18 @@ pub struct RpcServerConfig {
     http_cors_domains: Option<String>,
     /// Address where to bind the http server to
     http_addr: Option<SocketAddr>,
+    /// Streams `debug_traceBlock*` responses over http, if set
+    http_trace_streamer: Option<Arc<dyn BlockTraceStreamer>>,
+    /// Restricts the methods callable over http, if set
+    http_method_scope: Option<MethodScope>,
     /// Configs for WS server
     ws_server_config: Option<ServerBuilder>,
     /// Allowed CORS Domains for ws.
     ws_cors_domains: Option<String>,
     /// Address where to bind the ws server to
     ws_addr: Option<SocketAddr>,
+    /// Restricts the methods callable over ws, if set
+    ws_method_scope: Option<MethodScope>,
     /// Configs for JSON-RPC IPC server
     ipc_server_config: Option<IpcServerBuilder>,
     /// The Endpoint where to launch the ipc server
@@ -1142,8 +1191,11 @@ impl fmt::Debug for RpcServerConfig {
             .field("http_server_config", &self.http_server_config)
             .field("http_cors_domains", &self.http_cors_domains)
             .field("http_addr", &self.http_addr)
+            .field("http_trace_streaming", &self.http_trace_streamer.is_some())
+            .field("http_method_scope", &self.http_method_scope)
             .field("ws_server_config", &self.ws_server_config)
             .field("ws_addr", &self.ws_addr)
+            .field("ws_method_scope", &self.ws_method_scope)
             .field("ipc_server_config", &self.ipc_server_config)
             .field("ipc_endpoint", &self.ipc_endpoint.as_ref().map(|endpoint| endpoint.path()))
             .finish()
@@ -1189,6 +1241,27 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the http server to stream the responses of `debug_traceBlockByNumber` and
+    /// `debug_traceBlockByHash` one transaction trace at a time, see [TraceStreamLayer].
+    pub fn with_http_trace_streaming(mut self, streamer: Arc<dyn BlockTraceStreamer>) -> Self {
+        self.http_trace_streamer = Some(streamer);
+        self
+    }
+
+    /// Restricts the methods that can be called over http to the given [MethodScope].
+    ///
+    /// If http and ws run on the same port, this scope applies to both.
+    pub fn with_http_method_scope(mut self, scope: MethodScope) -> Self {
+        self.http_method_scope = Some(scope);
+        self
+    }
+
+    /// Restricts the methods that can be called over ws to the given [MethodScope].
+    pub fn with_ws_method_scope(mut self, scope: MethodScope) -> Self {
+        self.ws_method_scope = Some(scope);
+        self
+    }
+
     /// Configure the cors domains for WS
     pub fn with_ws_cors(mut self, cors_domain: Option<String>) -> Self {
         self.ws_cors_domains = cors_domain;
@@ -1286,6 +1359,11 @@ impl RpcServerConfig {
         self.build().await?.start(modules).await
     }
 
+    /// Returns the [TraceStreamLayer] of the http server.
+    fn http_trace_layer(&self) -> TraceStreamLayer {
+        self.http_trace_streamer.clone().map(TraceStreamLayer::new).unwrap_or_default()
+    }
+
     /// Builds the ws and http server(s).
     ///
     /// If both are on the same port, they are combined into one server.
@@ -1329,6 +1407,7 @@ impl RpcServerConfig {
                 builder,
                 http_socket_addr,
                 cors,
+                self.http_trace_layer(),
                 ServerKind::WsHttp(http_socket_addr),
                 metrics.clone(),
             )
@@ -1351,6 +1430,7 @@ impl RpcServerConfig {
                 builder,
                 ws_socket_addr,
                 self.ws_cors_domains.take(),
+                TraceStreamLayer::disabled(),
                 ServerKind::WS(ws_socket_addr),
                 metrics.clone(),
             )
@@ -1365,6 +1445,7 @@ impl RpcServerConfig {
                 builder,
                 http_socket_addr,
                 self.http_cors_domains.take(),
+                self.http_trace_layer(),
                 ServerKind::Http(http_socket_addr),
                 metrics.clone(),
             )
@@ -1388,6 +1469,8 @@ impl RpcServerConfig {
     pub async fn build(mut self) -> Result<RpcServer, RpcError> {
         let mut server = RpcServer::empty();
         server.ws_http = self.build_ws_http().await?;
+        server.http_method_scope = self.http_method_scope.take();
+        server.ws_method_scope = self.ws_method_scope.take();
 
         if let Some(builder) = self.ipc_server_config {
             let ipc_path = self
@@ -1657,9 +1740,9 @@ impl Default for WsHttpServers {
 /// Http Servers Enum
 enum WsHttpServerKind {
     /// Http server
-    Plain(Server<Identity, RpcServerMetrics>),
+    Plain(Server<Stack<TraceStreamLayer, Identity>, RpcServerMetrics>),
     /// Http server with cors
-    WithCors(Server<Stack<CorsLayer, Identity>, RpcServerMetrics>),
+    WithCors(Server<Stack<TraceStreamLayer, Stack<CorsLayer, Identity>>, RpcServerMetrics>),
 }
 
 // === impl WsHttpServerKind ===
@@ -1678,12 +1761,13 @@ impl WsHttpServerKind {
         builder: ServerBuilder,
         socket_addr: SocketAddr,
         cors_domains: Option<String>,
+        trace_stream: TraceStreamLayer,
         server_kind: ServerKind,
         metrics: RpcServerMetrics,
     ) -> Result<(Self, SocketAddr), RpcError> {
         if let Some(cors) = cors_domains.as_deref().map(cors::create_cors_layer) {
             let cors = cors.map_err(|err| RpcError::Custom(err.to_string()))?;
-            let middleware = tower::ServiceBuilder::new().layer(cors);
+            let middleware = tower::ServiceBuilder::new().layer(cors).layer(trace_stream);
             let server = builder
                 .set_middleware(middleware)
                 .set_logger(metrics)
@@ -1694,7 +1778,9 @@ impl WsHttpServerKind {
             let server = WsHttpServerKind::WithCors(server);
             Ok((server, local_addr))
         } else {
+            let middleware = tower::ServiceBuilder::new().layer(trace_stream);
             let server = builder
+                .set_middleware(middleware)
                 .set_logger(metrics)
                 .build(socket_addr)
                 .await
@@ -1712,13 +1798,22 @@ pub struct RpcServer {
     ws_http: WsHttpServer,
     /// ipc server
     ipc: Option<IpcServer>,
+    /// Restricts the methods of the http module
+    http_method_scope: Option<MethodScope>,
+    /// Restricts the methods of the ws module
+    ws_method_scope: Option<MethodScope>,
 }
 
 // === impl RpcServer ===
 
 impl RpcServer {
     fn empty() -> RpcServer {
-        RpcServer { ws_http: Default::default(), ipc: None }
+        RpcServer {
+            ws_http: Default::default(),
+            ipc: None,
+            http_method_scope: None,
+            ws_method_scope: None,
+        }
     }
 
     /// Returns the [`SocketAddr`] of the http server if started.
@@ -1743,8 +1838,13 @@ impl RpcServer {
     #[instrument(name = "start", skip_all, fields(http = ?self.http_local_addr(), ws = ?self.ws_local_addr(), ipc = ?self.ipc_endpoint().map(|ipc|ipc.path())), target = "rpc", level = "TRACE")]
     pub async fn start(self, modules: TransportRpcModules) -> Result<RpcServerHandle, RpcError> {
         trace!(target: "rpc", "staring RPC server");
-        let Self { ws_http, ipc: ipc_server } = self;
-        let TransportRpcModules { config, http, ws, ipc } = modules;
+        let Self { ws_http, ipc: ipc_server, http_method_scope, ws_method_scope } = self;
+        let TransportRpcModules { config, mut http, mut ws, ipc } = modules;
+        for (module, scope) in [(&mut http, http_method_scope), (&mut ws, ws_method_scope)] {
+            if let (Some(module), Some(scope)) = (module, scope) {
+                scope.restrict(module);
+            }
+        }
         let mut handle = RpcServerHandle {
             http_local_addr: ws_http.http_local_addr,
             ws_local_addr: ws_http.ws_local_addr,
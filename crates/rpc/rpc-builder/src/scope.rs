@@ -0,0 +1,116 @@
+use jsonrpsee::{core::RpcResult, server::RpcModule, types::ErrorObject};
+use std::collections::HashSet;
+
+/// Error code returned for methods that are installed but not allowed on the transport.
+pub const METHOD_NOT_ALLOWED_CODE: i32 = -32604;
+
+/// The `eth` methods the consensus layer client requires on the auth server.
+///
+/// The auth server always exposes exactly these `eth` methods, independent of the modules selected
+/// for the other transports.
+///
+/// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/common.md#underlying-protocol>
+pub const ENGINE_ETH_METHODS: [&str; 10] = [
+    "eth_blockNumber",
+    "eth_call",
+    "eth_chainId",
+    "eth_getBlockByHash",
+    "eth_getBlockByNumber",
+    "eth_getBlockReceipts",
+    "eth_getCode",
+    "eth_getLogs",
+    "eth_sendRawTransaction",
+    "eth_syncing",
+];
+
+/// The set of RPC namespaces and methods that are callable on a server.
+///
+/// Methods of a module that are not in the scope are still installed, but calling them returns an
+/// error with [METHOD_NOT_ALLOWED_CODE], so they can be distinguished from unknown methods.
+///
+/// # Example
+///
+/// Restrict the auth server to the `engine` namespace and the minimal set of `eth` methods:
+///
+/// ```
+/// use reth_rpc_builder::{MethodScope, ENGINE_ETH_METHODS};
+/// let scope = MethodScope::default().with_namespace("engine").with_methods(ENGINE_ETH_METHODS);
+/// assert!(scope.is_allowed("engine_forkchoiceUpdatedV2"));
+/// assert!(scope.is_allowed("eth_chainId"));
+/// assert!(!scope.is_allowed("eth_getBalance"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MethodScope {
+    /// All methods of these namespaces are allowed.
+    namespaces: HashSet<String>,
+    /// Individually allowed methods.
+    methods: HashSet<String>,
+}
+
+// === impl MethodScope ===
+
+impl MethodScope {
+    /// Allows all methods of the namespace, e.g. `engine` for all `engine_` methods.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespaces.insert(namespace.into());
+        self
+    }
+
+    /// Allows the given method.
+    pub fn with_method(mut self, method: impl Into<String>) -> Self {
+        self.methods.insert(method.into());
+        self
+    }
+
+    /// Allows all the given methods.
+    pub fn with_methods(mut self, methods: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.methods.extend(methods.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns true if the method can be called.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        if self.methods.contains(method) {
+            return true
+        }
+        method.split_once('_').map_or(false, |(namespace, _)| self.namespaces.contains(namespace))
+    }
+
+    /// Replaces all methods of the module that are not in the scope with a method that rejects
+    /// every call.
+    pub fn restrict(&self, module: &mut RpcModule<()>) {
+        let forbidden =
+            module.method_names().filter(|method| !self.is_allowed(method)).collect::<Vec<_>>();
+        for method in forbidden {
+            module.remove_method(method);
+            module
+                .register_method(method, |_, _| method_not_allowed())
+                .expect("method was removed");
+        }
+    }
+}
+
+/// Returns the error for a method that is not allowed on the transport.
+fn method_not_allowed() -> RpcResult<()> {
+    Err(ErrorObject::owned(
+        METHOD_NOT_ALLOWED_CODE,
+        "method not allowed on this transport",
+        None::<()>,
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_allows_namespaces_and_methods() {
+        let scope = MethodScope::default().with_namespace("engine").with_method("eth_chainId");
+        assert!(scope.is_allowed("engine_newPayloadV2"));
+        assert!(scope.is_allowed("eth_chainId"));
+        assert!(!scope.is_allowed("eth_syncing"));
+        assert!(!scope.is_allowed("engine"));
+        assert!(!MethodScope::default().is_allowed("eth_chainId"));
+    }
+}
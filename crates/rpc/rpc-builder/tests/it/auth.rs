@@ -1,10 +1,23 @@
 //! Auth server tests
 
-use crate::utils::launch_auth;
-use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
+use crate::utils::{
+    launch_auth, test_address, test_auth_module, test_engine_api, test_rpc_builder,
+};
+use jsonrpsee::{
+    core::{
+        client::{ClientT, SubscriptionClientT},
+        Error,
+    },
+    rpc_params,
+    types::error::ErrorCode,
+};
 use reth_primitives::{Block, U64};
 use reth_rpc::JwtSecret;
 use reth_rpc_api::clients::EngineApiClient;
+use reth_rpc_builder::{
+    auth::AuthServerConfig, MethodScope, TransportRpcModuleConfig, ENGINE_ETH_METHODS,
+    METHOD_NOT_ALLOWED_CODE,
+};
 use reth_rpc_types::engine::{ForkchoiceState, PayloadId, TransitionConfiguration};
 use reth_rpc_types_compat::engine::payload::{
     convert_block_to_payload_input_v2, try_block_to_payload_v1,
@@ -44,3 +57,63 @@ async fn test_auth_endpoints_ws() {
     let client = handle.ws_client().await;
     test_basic_engine_calls(&client).await
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_method_scope() {
+    reth_tracing::init_test_tracing();
+    let secret = JwtSecret::random();
+    let config = AuthServerConfig::builder(secret)
+        .socket_addr(test_address())
+        .with_method_scope(
+            MethodScope::default().with_namespace("engine").with_method("eth_chainId"),
+        )
+        .build();
+    let mut module = test_auth_module();
+    module.module_mut().register_method("eth_chainId", |_, _| "0x1").unwrap();
+    module.module_mut().register_method("eth_getBalance", |_, _| "0x0").unwrap();
+    let handle = module.start_server(config).await.unwrap();
+    let client = handle.http_client();
+
+    let chain_id: String = client.request("eth_chainId", rpc_params![]).await.unwrap();
+    assert_eq!(chain_id, "0x1");
+    let capabilities = EngineApiClient::exchange_capabilities(&client, vec![]).await;
+    assert!(capabilities.is_ok());
+
+    match client.request::<String, _>("eth_getBalance", rpc_params![]).await {
+        Err(Error::Call(err)) => assert_eq!(err.code(), METHOD_NOT_ALLOWED_CODE),
+        res => panic!("expected method not allowed error, got {res:?}"),
+    }
+
+    // unknown methods are still reported as such
+    match client.request::<String, _>("eth_getCode", rpc_params![]).await {
+        Err(Error::Call(err)) => assert_ne!(err.code(), METHOD_NOT_ALLOWED_CODE),
+        res => panic!("expected method not found error, got {res:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_server_exposes_engine_eth_methods() {
+    reth_tracing::init_test_tracing();
+    // no modules are selected for the other transports
+    let (_, auth_module, _) = test_rpc_builder()
+        .build_with_auth_server(TransportRpcModuleConfig::default(), test_engine_api());
+    let config = AuthServerConfig::builder(JwtSecret::random()).socket_addr(test_address()).build();
+    let handle = auth_module.start_server(config).await.unwrap();
+    let client = handle.http_client();
+
+    // every required method is installed, even if the call itself fails
+    for method in ENGINE_ETH_METHODS {
+        match client.request::<serde_json::Value, _>(method, rpc_params![]).await {
+            Err(Error::Call(err)) => {
+                assert_ne!(err.code(), ErrorCode::MethodNotFound.code(), "{method}")
+            }
+            Ok(_) => {}
+            res => panic!("unexpected response for {method}: {res:?}"),
+        }
+    }
+
+    match client.request::<serde_json::Value, _>("eth_accounts", rpc_params![]).await {
+        Err(Error::Call(err)) => assert_eq!(err.code(), ErrorCode::MethodNotFound.code()),
+        res => panic!("expected method not found error, got {res:?}"),
+    }
+}
@@ -23,17 +23,25 @@ pub fn test_address() -> SocketAddr {
 /// Launches a new server for the auth module
 pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
     let config = AuthServerConfig::builder(secret).socket_addr(test_address()).build();
+    test_auth_module().start_server(config).await.unwrap()
+}
+
+/// Returns an auth module with the `engine` namespace
+pub fn test_auth_module() -> AuthRpcModule {
+    AuthRpcModule::new(test_engine_api())
+}
+
+/// Returns an [EngineApi] with testing components.
+pub fn test_engine_api() -> EngineApi<NoopProvider> {
     let (tx, _rx) = unbounded_channel();
     let beacon_engine_handle = BeaconConsensusEngineHandle::new(tx);
-    let engine_api = EngineApi::new(
+    EngineApi::new(
         NoopProvider::default(),
         MAINNET.clone(),
         beacon_engine_handle,
         spawn_test_payload_service().into(),
         Box::<TokioTaskExecutor>::default(),
-    );
-    let module = AuthRpcModule::new(engine_api);
-    module.start_server(config).await.unwrap()
+    )
 }
 
 /// Launches a new server with http only with the given modules
@@ -1,6 +1,6 @@
 //! Some payload tests
 
-use alloy_rlp::{Decodable, Error as RlpError};
+use alloy_rlp::Decodable;
 use assert_matches::assert_matches;
 use reth_interfaces::test_utils::generators::{
     self, random_block, random_block_range, random_header, Rng,
@@ -10,7 +10,8 @@ use reth_primitives::{
     proofs, Block, SealedBlock, TransactionSigned, B256, U256,
 };
 use reth_rpc_types::engine::{
-    ExecutionPayload, ExecutionPayloadBodyV1, ExecutionPayloadV1, PayloadError,
+    ExecutionPayload, ExecutionPayloadBodyV1, ExecutionPayloadV1, PayloadError, PayloadStatusEnum,
+    TransactionDecodeErrorKind,
 };
 use reth_rpc_types_compat::engine::payload::{
     convert_standalone_withdraw_to_withdrawal, convert_to_payload_body_v1, try_block_to_payload,
@@ -100,7 +101,13 @@ fn payload_validation() {
         *tx = Bytes::new().into();
     });
     let payload_with_invalid_txs = try_payload_v1_to_block(payload_with_invalid_txs);
-    assert_matches!(payload_with_invalid_txs, Err(PayloadError::Decode(RlpError::InputTooShort)));
+    assert_matches!(
+        payload_with_invalid_txs,
+        Err(PayloadError::InvalidTransaction {
+            index: 0,
+            kind: TransactionDecodeErrorKind::TruncatedRlp
+        })
+    );
 
     // Non empty ommers
     let block_with_ommers = transform_block(block.clone(), |mut b| {
@@ -140,3 +147,41 @@ fn payload_validation() {
     let valid_block = block;
     assert_matches!(TryInto::<SealedBlock>::try_into(valid_block), Ok(_));
 }
+
+#[test]
+fn payload_invalid_transaction_reports_index_and_category() {
+    let mut rng = generators::rng();
+    let block = random_block(&mut rng, 100, None, Some(5), Some(0));
+    let valid = try_block_to_payload_v1(block);
+
+    let corruptions: [(fn(Bytes) -> Bytes, TransactionDecodeErrorKind); 4] = [
+        (|tx| [&[0x05][..], &tx[1..]].concat().into(), TransactionDecodeErrorKind::UnknownType),
+        (|tx| tx.slice(..tx.len() - 1), TransactionDecodeErrorKind::TruncatedRlp),
+        (
+            |tx| {
+                let mut decoded = TransactionSigned::decode_enveloped(tx.into()).unwrap();
+                decoded.signature.s = U256::MAX;
+                decoded.envelope_encoded().into()
+            },
+            TransactionDecodeErrorKind::SignatureOutOfRange,
+        ),
+        (
+            |tx| [&tx[..], &[0x00][..]].concat().into(),
+            TransactionDecodeErrorKind::NonCanonicalEncoding,
+        ),
+    ];
+
+    for (corrupt, kind) in corruptions {
+        let mut payload = valid.clone();
+        payload.transactions[3] = corrupt(payload.transactions[3].clone().into()).into();
+
+        let err = try_payload_v1_to_block(payload).unwrap_err();
+        assert_matches!(err, PayloadError::InvalidTransaction { index: 3, kind: k } if k == kind);
+
+        let PayloadStatusEnum::Invalid { validation_error } = PayloadStatusEnum::from(err) else {
+            panic!("expected invalid status")
+        };
+        assert!(validation_error.contains("index 3"), "{validation_error}");
+        assert!(validation_error.contains(kind.as_str()), "{validation_error}");
+    }
+}
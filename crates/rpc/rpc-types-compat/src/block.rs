@@ -2,16 +2,21 @@
 
 use crate::transaction::from_recovered_with_block_context;
 use alloy_rlp::Encodable;
-use reth_primitives::{Block as PrimitiveBlock, Header as PrimitiveHeader, B256, U256};
+use reth_primitives::{
+    Block as PrimitiveBlock, Header as PrimitiveHeader, SealedHeader, Withdrawal, B256, U256,
+};
 use reth_rpc_types::{Block, BlockError, BlockTransactions, BlockTransactionsKind, Header};
 
 /// Converts the given primitive block into a [Block] response with the given
 /// [BlockTransactionsKind]
 ///
 /// If a `block_hash` is provided, then this is used, otherwise the block hash is computed.
+///
+/// The `total_difficulty` is only known for canonical blocks and should be `None` for any other
+/// block, e.g. a side block of the tree.
 pub fn from_block(
     block: PrimitiveBlock,
-    total_difficulty: U256,
+    total_difficulty: Option<U256>,
     kind: BlockTransactionsKind,
     block_hash: Option<B256>,
 ) -> Result<Block, BlockError> {
@@ -30,7 +35,7 @@ pub fn from_block(
 /// block: [BlockTransactions::Hashes]
 pub fn from_block_with_tx_hashes(
     block: PrimitiveBlock,
-    total_difficulty: U256,
+    total_difficulty: Option<U256>,
     block_hash: Option<B256>,
 ) -> Block {
     let block_hash = block_hash.unwrap_or_else(|| block.header.hash_slow());
@@ -52,7 +57,7 @@ pub fn from_block_with_tx_hashes(
 /// [Transaction](reth_rpc_types::Transaction) objects: [BlockTransactions::Full]
 pub fn from_block_full(
     mut block: PrimitiveBlock,
-    total_difficulty: U256,
+    total_difficulty: Option<U256>,
     block_hash: Option<B256>,
 ) -> Result<Block, BlockError> {
     let block_hash = block_hash.unwrap_or_else(|| block.header.hash_slow());
@@ -90,35 +95,132 @@ fn from_block_with_transactions(
     block_length: usize,
     block_hash: B256,
     block: PrimitiveBlock,
-    total_difficulty: U256,
+    total_difficulty: Option<U256>,
     transactions: BlockTransactions,
 ) -> Block {
     let uncles = block.ommers.into_iter().map(|h| h.hash_slow()).collect();
-    let header = Header::from_primitive_with_hash(block.header.seal(block_hash));
-    let withdrawals = if header.withdrawals_root.is_some() { block.withdrawals } else { None };
-    Block {
-        header,
+    from_header_with_parts(
+        block.header.seal(block_hash),
+        block_length,
+        total_difficulty,
         uncles,
         transactions,
-        total_difficulty: Some(total_difficulty),
-        size: Some(U256::from(block_length)),
-        withdrawals,
-    }
+        block.withdrawals,
+    )
 }
 
 /// Build an RPC block response representing
 /// an Uncle from its header.
 pub fn uncle_block_from_header(header: PrimitiveHeader) -> Block {
     let hash = header.hash_slow();
-    let rpc_header = Header::from_primitive_with_hash(header.clone().seal(hash));
     let uncle_block = PrimitiveBlock { header, ..Default::default() };
-    let size = Some(U256::from(uncle_block.length()));
-    Block {
-        uncles: vec![],
-        header: rpc_header,
-        transactions: BlockTransactions::Uncle,
-        withdrawals: Some(vec![]),
+    let size = uncle_block.length();
+    from_header_with_parts(
+        uncle_block.header.seal(hash),
         size,
-        total_difficulty: None,
+        None,
+        vec![],
+        BlockTransactions::Uncle,
+        None,
+    )
+}
+
+/// Creates a new [Block] response from the sealed header of a block and the remaining parts of
+/// the response.
+///
+/// This is the conversion shared by canonical blocks, blocks of the tree and uncles, so that all
+/// of them carry every header field, including the fields added after the merge.
+///
+/// The `block_length` is the length of the RLP encoded block. The `total_difficulty` should be
+/// `None` for blocks that are not canonical. The withdrawals are only included if the header has a
+/// withdrawals root, and default to an empty list.
+pub fn from_header_with_parts(
+    header: SealedHeader,
+    block_length: usize,
+    total_difficulty: Option<U256>,
+    uncles: Vec<B256>,
+    transactions: BlockTransactions,
+    withdrawals: Option<Vec<Withdrawal>>,
+) -> Block {
+    let header = Header::from_primitive_with_hash(header);
+    let withdrawals = header.withdrawals_root.map(|_| withdrawals.unwrap_or_default());
+    Block {
+        header,
+        uncles,
+        transactions,
+        total_difficulty,
+        size: Some(U256::from(block_length)),
+        withdrawals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        b256, bytes,
+        constants::{EMPTY_OMMER_ROOT, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS},
+        Address,
+    };
+    use serde_json::Value;
+
+    const CANONICAL: &str = include_str!("../test_data/block/cancun_canonical.json");
+    const SIDE_BLOCK: &str = include_str!("../test_data/block/cancun_side_block.json");
+
+    /// A Cancun block without transactions, ommers and withdrawals.
+    fn cancun_block() -> PrimitiveBlock {
+        let header = PrimitiveHeader {
+            parent_hash: B256::from([0x11; 32]),
+            ommers_hash: EMPTY_OMMER_ROOT,
+            beneficiary: Address::from([0x22; 20]),
+            state_root: B256::from([0x33; 32]),
+            transactions_root: EMPTY_TRANSACTIONS,
+            receipts_root: EMPTY_RECEIPTS,
+            number: 0x10,
+            gas_limit: 30_000_000,
+            timestamp: 0x65f1b057,
+            extra_data: bytes!("72657468"),
+            mix_hash: B256::from([0x44; 32]),
+            base_fee_per_gas: Some(7),
+            withdrawals_root: Some(EMPTY_WITHDRAWALS),
+            blob_gas_used: Some(0x20000),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::from([0x55; 32])),
+            ..Default::default()
+        };
+        PrimitiveBlock { header, body: vec![], ommers: vec![], withdrawals: Some(vec![]) }
+    }
+
+    #[test]
+    fn cancun_block_as_canonical_block() {
+        let total_difficulty = U256::from(58_750_000_000_000_000_000_000u128);
+        let block = cancun_block();
+        assert_eq!(
+            block.header.hash_slow(),
+            b256!("f9a03ab2e240c14583112284b8d98e253f0b5f0a9196f2818659c120654b0085")
+        );
+
+        for kind in [BlockTransactionsKind::Hashes, BlockTransactionsKind::Full] {
+            let rpc_block = from_block(block.clone(), Some(total_difficulty), kind, None).unwrap();
+            assert_eq!(
+                serde_json::to_value(&rpc_block).unwrap(),
+                serde_json::from_str::<Value>(CANONICAL).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn cancun_block_as_side_block() {
+        let block = cancun_block();
+        let block_hash = block.header.hash_slow();
+
+        // queried by hash while the block was still a side block of the tree, without total
+        // difficulty
+        let rpc_block =
+            from_block(block, None, BlockTransactionsKind::Hashes, Some(block_hash)).unwrap();
+        assert_eq!(
+            serde_json::to_value(&rpc_block).unwrap(),
+            serde_json::from_str::<Value>(SIDE_BLOCK).unwrap()
+        );
     }
 }
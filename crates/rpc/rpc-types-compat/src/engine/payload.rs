@@ -1,13 +1,16 @@
 //! Standalone Conversion Functions for Handling Different Versions of Execution Payloads in
 //! Ethereum's Engine
+use alloy_rlp::{Error as RlpError, EMPTY_LIST_CODE};
 use reth_primitives::{
     constants::{MAXIMUM_EXTRA_DATA_SIZE, MIN_PROTOCOL_BASE_FEE_U256},
     proofs::{self, EMPTY_LIST_HASH},
-    Block, Header, SealedBlock, TransactionSigned, UintTryTo, Withdrawal, B256, U256, U64,
+    Block, Bytes, Header, SealedBlock, TransactionSigned, UintTryTo, Withdrawal, B256,
+    EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256, U64,
 };
 use reth_rpc_types::engine::{
     payload::{ExecutionPayloadBodyV1, ExecutionPayloadFieldV2, ExecutionPayloadInputV2},
     ExecutionPayload, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadError,
+    TransactionDecodeErrorKind,
 };
 
 /// Converts [ExecutionPayloadV1] to [Block]
@@ -23,7 +26,11 @@ pub fn try_payload_v1_to_block(payload: ExecutionPayloadV1) -> Result<Block, Pay
     let transactions = payload
         .transactions
         .into_iter()
-        .map(TransactionSigned::decode_enveloped)
+        .enumerate()
+        .map(|(index, tx)| {
+            try_decode_payload_transaction(tx)
+                .map_err(|kind| PayloadError::InvalidTransaction { index, kind })
+        })
         .collect::<Result<Vec<_>, _>>()?;
     let transactions_root = proofs::calculate_transaction_root(&transactions);
 
@@ -59,6 +66,39 @@ pub fn try_payload_v1_to_block(payload: ExecutionPayloadV1) -> Result<Block, Pay
     Ok(Block { header, body: transactions, withdrawals: None, ommers: Default::default() })
 }
 
+/// Decodes a transaction of a payload and ensures that the given bytes are its canonical
+/// encoding.
+///
+/// If the transaction is invalid, the returned [TransactionDecodeErrorKind] describes why.
+pub fn try_decode_payload_transaction(
+    tx: Bytes,
+) -> Result<TransactionSigned, TransactionDecodeErrorKind> {
+    match tx.first() {
+        None => return Err(TransactionDecodeErrorKind::TruncatedRlp),
+        Some(&(EIP2930_TX_TYPE_ID | EIP1559_TX_TYPE_ID | EIP4844_TX_TYPE_ID)) => {}
+        Some(&ty) if ty < EMPTY_LIST_CODE => return Err(TransactionDecodeErrorKind::UnknownType),
+        _ => {}
+    }
+
+    let decoded = TransactionSigned::decode_enveloped(tx.clone()).map_err(|err| match err {
+        RlpError::InputTooShort |
+        RlpError::UnexpectedLength |
+        RlpError::ListLengthMismatch { .. } => TransactionDecodeErrorKind::TruncatedRlp,
+        RlpError::LeadingZero | RlpError::NonCanonicalSingleByte | RlpError::NonCanonicalSize => {
+            TransactionDecodeErrorKind::NonCanonicalEncoding
+        }
+        _ => TransactionDecodeErrorKind::MalformedRlp,
+    })?;
+
+    if !decoded.signature.is_in_range() {
+        return Err(TransactionDecodeErrorKind::SignatureOutOfRange)
+    }
+    if decoded.envelope_encoded() != tx {
+        return Err(TransactionDecodeErrorKind::NonCanonicalEncoding)
+    }
+    Ok(decoded)
+}
+
 /// Converts [ExecutionPayloadV2] to [Block]
 pub fn try_payload_v2_to_block(payload: ExecutionPayloadV2) -> Result<Block, PayloadError> {
     // this performs the same conversion as the underlying V1 payload, but calculates the
@@ -0,0 +1,37 @@
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Response of the `debug_storageRangeAt` RPC: a page of the storage of an account, ordered by
+/// the hashes of the storage keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots of the page, keyed by the hash of the storage key.
+    pub storage: BTreeMap<B256, StorageRangeEntry>,
+    /// The hashed key of the first slot of the next page, if there is one.
+    pub next_key: Option<B256>,
+}
+
+/// A single storage slot returned by `debug_storageRangeAt`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageRangeEntry {
+    /// The preimage of the hashed storage key, if it is known.
+    pub key: Option<B256>,
+    /// The value of the storage slot.
+    pub value: B256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_storage_range_result() {
+        let s = r#"{"storage":{"0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563":{"key":"0x0000000000000000000000000000000000000000000000000000000000000000","value":"0x0000000000000000000000000000000000000000000000000000000000000001"}},"nextKey":null}"#;
+        let res: StorageRangeResult = serde_json::from_str(s).unwrap();
+        assert_eq!(res.next_key, None);
+        assert_eq!(res.storage.len(), 1);
+        assert_eq!(serde_json::to_string(&res).unwrap(), s);
+    }
+}
@@ -103,8 +103,8 @@ pub struct Block {
     /// Header of the block
     #[serde(flatten)]
     pub header: Header,
-    /// Total difficulty, this field is None only if representing
-    /// an Uncle block.
+    /// Total difficulty, this field is None if representing an Uncle block or a block that is not
+    /// canonical.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_difficulty: Option<U256>,
     /// Uncles' hashes
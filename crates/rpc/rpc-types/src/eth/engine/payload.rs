@@ -364,6 +364,14 @@ pub enum PayloadError {
     /// Expected blob versioned hashes do not match the given transactions.
     #[error("Expected blob versioned hashes do not match the given transactions")]
     InvalidVersionedHashes,
+    /// A transaction of the payload could not be decoded.
+    #[error("Invalid transaction at index {index}: {kind}")]
+    InvalidTransaction {
+        /// The index of the transaction in the payload.
+        index: usize,
+        /// Why the transaction was rejected.
+        kind: TransactionDecodeErrorKind,
+    },
     /// Encountered decoding error.
     #[error(transparent)]
     Decode(#[from] alloy_rlp::Error),
@@ -376,6 +384,40 @@ impl PayloadError {
     }
 }
 
+/// The category of a transaction of a payload that could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransactionDecodeErrorKind {
+    /// The transaction starts with a type byte of an unknown transaction type.
+    UnknownType,
+    /// The RLP of the transaction ends before all fields were decoded.
+    TruncatedRlp,
+    /// The RLP of the transaction is invalid for another reason.
+    MalformedRlp,
+    /// The `r` or `s` value of the signature is out of the range of valid secp256k1 signatures.
+    SignatureOutOfRange,
+    /// The transaction decodes, but its canonical encoding differs from the given bytes.
+    NonCanonicalEncoding,
+}
+
+impl TransactionDecodeErrorKind {
+    /// Returns the name of the category.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            TransactionDecodeErrorKind::UnknownType => "unknown transaction type",
+            TransactionDecodeErrorKind::TruncatedRlp => "truncated RLP",
+            TransactionDecodeErrorKind::MalformedRlp => "malformed RLP",
+            TransactionDecodeErrorKind::SignatureOutOfRange => "signature out of range",
+            TransactionDecodeErrorKind::NonCanonicalEncoding => "non-canonical encoding",
+        }
+    }
+}
+
+impl std::fmt::Display for TransactionDecodeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// This structure contains a body of an execution payload.
 ///
 /// See also: <https://github.com/ethereum/execution-apis/blob/6452a6b194d7db269bf1dbd087a267251d3cc7f8/src/engine/shanghai.md#executionpayloadbodyv1>
@@ -25,6 +25,19 @@ impl Ord for TxGasAndReward {
     }
 }
 
+/// A projected block of the response of `eth_baseFeeHistoryProjection`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseFeeProjection {
+    /// The number of the block.
+    pub block_number: U256,
+    /// The projected base fee per gas of the block.
+    pub base_fee_per_gas: U256,
+    /// The projected blob base fee of the block, if the chain has blobs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_base_fee: Option<U256>,
+}
+
 /// Response type for `eth_feeHistory`
 #[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -5,7 +5,7 @@ use crate::{
     Log, RichHeader,
 };
 
-use alloy_primitives::B256;
+use alloy_primitives::{Address, Selector, B256, U256, U8};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
 /// Subscription result.
@@ -156,6 +156,57 @@ impl<'a> Deserialize<'a> for Params {
     }
 }
 
+/// Subscription kind of the `reth` namespace.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub enum RethSubscriptionKind {
+    /// Pending transactions subscription.
+    ///
+    /// Returns the full transactions that are added to the pending state and match the given
+    /// [PendingTransactionFilter]. If the subscriber can't keep up with the matching transactions,
+    /// the subscription ends with [PendingTransactionSubscriptionResult::Lagged].
+    PendingTransactions,
+}
+
+/// Selects the transactions of a [RethSubscriptionKind::PendingTransactions] subscription.
+///
+/// A transaction matches if it satisfies all criteria, an empty criterion matches every
+/// transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PendingTransactionFilter {
+    /// Only transactions of these senders.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub senders: Vec<Address>,
+    /// Only transactions that call one of these recipients.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<Address>,
+    /// Only transactions whose input starts with one of these function selectors.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub selectors: Vec<Selector>,
+    /// Only transactions that transfer at least this value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<U256>,
+    /// Only transactions of these types.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tx_types: Vec<U8>,
+}
+
+/// Subscription result of a [RethSubscriptionKind::PendingTransactions] subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PendingTransactionSubscriptionResult {
+    /// Full transaction that matches the filter.
+    Transaction(Box<Transaction>),
+    /// Matching transactions were dropped because the subscriber didn't keep up, this is the
+    /// last message of the subscription.
+    Lagged {
+        /// The number of dropped transactions.
+        dropped: u64,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,4 +218,18 @@ mod tests {
         let s: Params = serde_json::from_str("null").unwrap();
         assert_eq!(s, Params::None);
     }
+
+    #[test]
+    fn pending_transaction_filter_serde() {
+        let s = r#"{"selectors":["0xa9059cbb"],"minValue":"0x64","txTypes":["0x2"]}"#;
+        let filter: PendingTransactionFilter = serde_json::from_str(s).unwrap();
+        assert_eq!(filter.selectors, vec![Selector::from([0xa9, 0x05, 0x9c, 0xbb])]);
+        assert_eq!(filter.min_value, Some(U256::from(100)));
+        assert_eq!(filter.tx_types, vec![U8::from(2)]);
+        assert!(filter.senders.is_empty());
+        assert_eq!(serde_json::to_string(&filter).unwrap(), s);
+
+        let lagged = PendingTransactionSubscriptionResult::Lagged { dropped: 3 };
+        assert_eq!(serde_json::to_string(&lagged).unwrap(), r#"{"dropped":3}"#);
+    }
 }
@@ -16,6 +16,21 @@ pub struct SyncInfo {
     pub warp_chunks_amount: Option<U256>,
     /// Warp sync snapshot chunks processed.
     pub warp_chunks_processed: Option<U256>,
+    /// The progress of every sync stage.
+    ///
+    /// Note: this is not part of the standard `eth_syncing` response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stages: Option<Vec<StageProgress>>,
+}
+
+/// The progress of a single sync stage.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StageProgress {
+    /// The name of the stage.
+    pub name: String,
+    /// The block number up to which the stage committed its progress.
+    pub block: U64,
 }
 
 /// Peers info
@@ -244,6 +244,8 @@ pub struct CreateAction {
 pub enum RewardType {
     Block,
     Uncle,
+    /// A withdrawal from the beacon chain, after Shanghai.
+    Withdrawal,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
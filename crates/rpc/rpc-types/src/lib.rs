@@ -12,13 +12,17 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 mod admin;
+mod debug;
 mod eth;
 mod mev;
 mod otterscan;
+mod reth;
 mod rpc;
 
 pub use admin::*;
+pub use debug::*;
 pub use eth::*;
 pub use mev::*;
 pub use otterscan::*;
+pub use reth::*;
 pub use rpc::*;
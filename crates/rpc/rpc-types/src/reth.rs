@@ -0,0 +1,27 @@
+use alloy_primitives::{BlockHash, Bytes, TxHash, B256, U64};
+use serde::{Deserialize, Serialize};
+
+/// Response of the `reth_getReceiptProof` RPC: the proof that the receipt of a transaction is
+/// included in the receipts trie of its block.
+///
+/// The proof is verified by checking that `header` hashes to `blockHash`, that `receiptsRoot` is
+/// the receipts root of the header, and that the proof nodes lead from `receiptsRoot` to the
+/// receipt with the key `rlp(transactionIndex)`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptProof {
+    /// The hash of the transaction.
+    pub transaction_hash: TxHash,
+    /// The index of the transaction in the block.
+    pub transaction_index: U64,
+    /// The hash of the block.
+    pub block_hash: BlockHash,
+    /// The number of the block.
+    pub block_number: U64,
+    /// The receipts root of the block.
+    pub receipts_root: B256,
+    /// The RLP encoded header of the block.
+    pub header: Bytes,
+    /// The RLP encoded trie nodes from the receipts root to the receipt.
+    pub proof: Vec<Bytes>,
+}
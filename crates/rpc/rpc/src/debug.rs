@@ -8,16 +8,18 @@ use crate::{
         EthTransactions, TransactionSource,
     },
     result::{internal_rpc_err, ToRpcResult},
-    BlockingTaskGuard, EthApiSpec,
+    trace_cache::{TraceCache, TraceCacheKey},
+    BlockTraceStreamer, BlockingTaskGuard, EthApiSpec, TraceResultStream,
 };
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
+use futures::future::BoxFuture;
 use jsonrpsee::core::RpcResult;
 use reth_primitives::{
-    revm::env::tx_env_with_recovered, Account, Address, Block, BlockId, BlockNumberOrTag, Bytes,
-    TransactionSigned, B256,
+    keccak256, revm::env::tx_env_with_recovered, Account, Address, Block, BlockId,
+    BlockNumberOrTag, Bytes, StorageEntry, TransactionSigned, B256, U256,
 };
-use reth_provider::{BlockReaderIdExt, HeaderProvider, StateProviderBox};
+use reth_provider::{BlockReaderIdExt, HeaderProvider, StateProvider, StateProviderBox};
 use reth_revm::{
     database::{StateProviderDatabase, SubState},
     tracing::{
@@ -31,18 +33,19 @@ use reth_rpc_types::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
         GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
     },
-    BlockError, Bundle, CallRequest, RichBlock, StateContext,
+    BlockError, Bundle, CallRequest, RichBlock, StateContext, StorageRangeEntry,
+    StorageRangeResult,
 };
 use reth_tasks::TaskSpawner;
 use revm::{
-    db::{CacheDB, EmptyDB},
+    db::{AccountState, CacheDB, EmptyDB},
     primitives::Env,
 };
 use revm_primitives::{
     db::{DatabaseCommit, DatabaseRef},
     BlockEnv, CfgEnv,
 };
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 use tokio::sync::{mpsc, AcquireError, OwnedSemaphorePermit};
 use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 
@@ -62,9 +65,15 @@ impl<Provider, Eth> DebugApi<Provider, Eth> {
         eth: Eth,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_guard: BlockingTaskGuard,
+        trace_cache: TraceCache,
     ) -> Self {
-        let inner =
-            Arc::new(DebugApiInner { provider, eth_api: eth, task_spawner, blocking_task_guard });
+        let inner = Arc::new(DebugApiInner {
+            provider,
+            eth_api: eth,
+            task_spawner,
+            blocking_task_guard,
+            trace_cache,
+        });
         Self { inner }
     }
 }
@@ -96,29 +105,62 @@ where
             .eth_api
             .spawn_with_state_at_block(at, move |state| {
                 let mut results = Vec::with_capacity(transactions.len());
-                let mut db = CacheDB::new(StateProviderDatabase::new(state));
-
-                let mut transactions = transactions.into_iter().peekable();
-                while let Some(tx) = transactions.next() {
-                    let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
-                    let tx = tx_env_with_recovered(&tx);
-                    let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
-                    let (result, state_changes) =
-                        this.trace_transaction(opts.clone(), env, at, &mut db)?;
-                    results.push(TraceResult::Success { result });
-
-                    if transactions.peek().is_some() {
-                        // need to apply the state changes of this transaction before executing the
-                        // next transaction
-                        db.commit(state_changes)
-                    }
-                }
-
+                this.replay_block_transactions(
+                    state,
+                    at,
+                    transactions,
+                    cfg,
+                    block_env,
+                    opts,
+                    |result| {
+                        results.push(result);
+                        true
+                    },
+                )?;
                 Ok(results)
             })
             .await
     }
 
+    /// Replays the transactions on top of the given state and passes the trace of each transaction
+    /// to `on_result` as soon as it is available.
+    ///
+    /// Stops early if `on_result` returns `false`.
+    ///
+    /// Caution: this is blocking and should be performed on a blocking task.
+    #[allow(clippy::too_many_arguments)]
+    fn replay_block_transactions(
+        &self,
+        state: StateProviderBox<'_>,
+        at: BlockId,
+        transactions: Vec<TransactionSigned>,
+        cfg: CfgEnv,
+        block_env: BlockEnv,
+        opts: GethDebugTracingOptions,
+        mut on_result: impl FnMut(TraceResult) -> bool,
+    ) -> EthResult<()> {
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+        let mut transactions = transactions.into_iter().peekable();
+        while let Some(tx) = transactions.next() {
+            let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
+            let tx = tx_env_with_recovered(&tx);
+            let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
+            let (result, state_changes) = self.trace_transaction(opts.clone(), env, at, &mut db)?;
+            if !on_result(TraceResult::Success { result }) {
+                break
+            }
+
+            if transactions.peek().is_some() {
+                // need to apply the state changes of this transaction before executing the
+                // next transaction
+                db.commit(state_changes)
+            }
+        }
+
+        Ok(())
+    }
+
     /// Replays the given block and returns the trace of each transaction.
     ///
     /// This expects a rlp encoded block
@@ -140,11 +182,79 @@ where
     }
 
     /// Replays a block and returns the trace of each transaction.
+    ///
+    /// The traces are served from the [TraceCache] if the block was recently traced with the same
+    /// options.
     pub async fn debug_trace_block(
         &self,
         block_id: BlockId,
         opts: GethDebugTracingOptions,
     ) -> EthResult<Vec<TraceResult>> {
+        let block_hash = self
+            .inner
+            .provider
+            .block_hash_for_id(block_id)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let key = TraceCacheKey::new(block_hash, &opts);
+
+        self.inner
+            .trace_cache
+            .get_or_trace(key, || async move {
+                let (cfg, block_env, state_at, transactions) =
+                    self.block_trace_env(block_hash.into()).await?;
+                self.trace_block_with(state_at, transactions, cfg, block_env, opts).await
+            })
+            .await
+    }
+
+    /// Replays a block and streams the trace of each transaction as soon as it is available.
+    ///
+    /// Unlike [Self::debug_trace_block], this only holds a bounded number of traces in memory:
+    /// replaying the block is paused until the stream is polled. Dropping the stream aborts the
+    /// replay.
+    pub async fn debug_trace_block_stream(
+        &self,
+        block_id: BlockId,
+        opts: GethDebugTracingOptions,
+    ) -> EthResult<ReceiverStream<EthResult<TraceResult>>> {
+        let permit = self.acquire_trace_permit().await;
+        let (cfg, block_env, state_at, transactions) = self.block_trace_env(block_id).await?;
+
+        let (tx, rx) = mpsc::channel(1);
+        let this = self.clone();
+        self.inner.task_spawner.spawn(Box::pin(async move {
+            let _permit = permit;
+            let replay = this.clone();
+            let results = tx.clone();
+            let res = this
+                .inner
+                .eth_api
+                .spawn_with_state_at_block(state_at, move |state| {
+                    replay.replay_block_transactions(
+                        state,
+                        state_at,
+                        transactions,
+                        cfg,
+                        block_env,
+                        opts,
+                        |result| results.blocking_send(Ok(result)).is_ok(),
+                    )
+                })
+                .await;
+            if let Err(err) = res {
+                let _ = tx.send(Err(err)).await;
+            }
+        }));
+
+        Ok(ReceiverStream::new(rx))
+    }
+
+    /// Returns the evm env and the transactions of the block, and the block to replay them on,
+    /// which is the parent of the block.
+    async fn block_trace_env(
+        &self,
+        block_id: BlockId,
+    ) -> EthResult<(CfgEnv, BlockEnv, BlockId, Vec<TransactionSigned>)> {
         let block_hash = self
             .inner
             .provider
@@ -161,7 +271,7 @@ where
         // its parent block's state
         let state_at = block.parent_hash;
 
-        self.trace_block_with(state_at.into(), block.body, cfg, block_env, opts).await
+        Ok((cfg, block_env, state_at.into(), block.body))
     }
 
     /// Trace the transaction according to the provided options.
@@ -241,7 +351,7 @@ where
                             .map_err(|_| EthApiError::InvalidTracerConfig)?;
 
                         let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_config(&config)
+                            TracingInspectorConfig::default_call_tracer()
                                 .set_record_logs(call_config.with_log.unwrap_or_default()),
                         );
 
@@ -263,7 +373,7 @@ where
                             .into_pre_state_config()
                             .map_err(|_| EthApiError::InvalidTracerConfig)?;
                         let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_config(&config),
+                            TracingInspectorConfig::default_prestate_tracer(),
                         );
 
                         let frame =
@@ -361,6 +471,7 @@ where
         let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
         let GethDebugTracingCallOptions { tracing_options, mut state_overrides, .. } = opts;
         let gas_limit = self.inner.eth_api.call_gas_limit();
+        let call_limits = self.inner.eth_api.call_limits();
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -416,6 +527,7 @@ where
                             block_env.clone(),
                             tx,
                             gas_limit,
+                            &call_limits,
                             &mut db,
                             overrides,
                         )?;
@@ -442,6 +554,51 @@ where
             .await
     }
 
+    /// Returns a page of the storage of the `contract` at the given block, after the first
+    /// `tx_idx` transactions of the block have been executed.
+    ///
+    /// The storage is ordered by the hashes of the storage keys, the page starts at the first
+    /// slot whose hashed key is not below `key_start` and contains at most `max_result` slots.
+    pub async fn debug_storage_range_at(
+        &self,
+        block_hash: B256,
+        tx_idx: usize,
+        contract: Address,
+        key_start: B256,
+        max_result: usize,
+    ) -> EthResult<StorageRangeResult> {
+        let block_id = BlockId::from(block_hash);
+        let ((cfg, block_env, _), block) = futures::try_join!(
+            self.inner.eth_api.evm_env_at(block_id),
+            self.inner.eth_api.block_by_id(block_id),
+        )?;
+        let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        if tx_idx > block.body.len() {
+            return Err(EthApiError::InvalidParams(format!(
+                "transaction index {tx_idx} out of range for block with {} transactions",
+                block.body.len()
+            )))
+        }
+
+        // we're replaying the transactions in the block here, hence we need the state that points
+        // to the beginning of the block, which is the state at the parent block
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(block.parent_hash.into(), move |state| {
+                let transactions = block.body.into_iter().take(tx_idx);
+                storage_range_at(
+                    state,
+                    cfg,
+                    block_env,
+                    transactions,
+                    contract,
+                    key_start,
+                    max_result,
+                )
+            })
+            .await
+    }
+
     /// Executes the configured transaction with the environment on the given database.
     ///
     /// Returns the trace frame and the state that got updated after executing the transaction.
@@ -472,7 +629,7 @@ where
                             .map_err(|_| EthApiError::InvalidTracerConfig)?;
 
                         let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_config(&config)
+                            TracingInspectorConfig::default_call_tracer()
                                 .set_record_logs(call_config.with_log.unwrap_or_default()),
                         );
 
@@ -490,7 +647,7 @@ where
                             .map_err(|_| EthApiError::InvalidTracerConfig)?;
 
                         let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_config(&config),
+                            TracingInspectorConfig::default_prestate_tracer(),
                         );
                         let (res, _) = inspect(&mut *db, env, &mut inspector)?;
 
@@ -631,6 +788,24 @@ where
     }
 }
 
+impl<Provider, Eth> BlockTraceStreamer for DebugApi<Provider, Eth>
+where
+    Provider: BlockReaderIdExt + HeaderProvider + 'static,
+    Eth: EthTransactions + 'static,
+{
+    fn trace_block_stream(
+        &self,
+        block_id: BlockId,
+        opts: GethDebugTracingOptions,
+    ) -> BoxFuture<'static, EthResult<TraceResultStream>> {
+        let this = self.clone();
+        Box::pin(async move {
+            let traces = this.debug_trace_block_stream(block_id, opts).await?;
+            Ok(Box::pin(traces) as TraceResultStream)
+        })
+    }
+}
+
 #[async_trait]
 impl<Provider, Eth> DebugApiServer for DebugApi<Provider, Eth>
 where
@@ -832,15 +1007,25 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_storageRangeAt`
     async fn debug_storage_range_at(
         &self,
-        _block_hash: B256,
-        _tx_idx: usize,
-        _contract_address: Address,
-        _key_start: B256,
-        _max_result: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> RpcResult<StorageRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(DebugApi::debug_storage_range_at(
+            self,
+            block_hash,
+            tx_idx,
+            contract_address,
+            key_start,
+            max_result.try_into().unwrap_or(usize::MAX),
+        )
+        .await?)
     }
 
     async fn debug_trace_bad_block(
@@ -993,6 +1178,70 @@ where
     }
 }
 
+/// Executes the `transactions` on top of the `state` and returns a page of the storage of the
+/// `contract` afterwards, see [DebugApi::debug_storage_range_at].
+///
+/// The storage of the `contract` in the `state` is overlaid with the writes of the executed
+/// transactions. Because the storage is enumerated by plain storage keys, the preimages of all
+/// returned hashed keys are known.
+///
+/// Caution: this is blocking and should be performed on a blocking task.
+fn storage_range_at<S, I>(
+    state: S,
+    cfg: CfgEnv,
+    block_env: BlockEnv,
+    transactions: I,
+    contract: Address,
+    key_start: B256,
+    max_result: usize,
+) -> EthResult<StorageRangeResult>
+where
+    S: StateProvider,
+    I: IntoIterator<Item = TransactionSigned>,
+{
+    let mut db = CacheDB::new(StateProviderDatabase::new(state));
+    for tx in transactions {
+        let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
+        let tx = tx_env_with_recovered(&tx);
+        let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
+        let (res, _) = transact(&mut db, env)?;
+        db.commit(res.state);
+    }
+
+    // storage slots keyed by the hashed storage key
+    let mut storage = BTreeMap::new();
+    let changed = db.accounts.get(&contract);
+
+    // the storage in the state is discarded if the contract was destroyed or (re)created by one
+    // of the transactions
+    let cleared = changed.map_or(false, |account| {
+        matches!(account.account_state, AccountState::StorageCleared | AccountState::NotExisting)
+    });
+    if !cleared {
+        for StorageEntry { key, value } in db.db.0.storage_entries(contract)? {
+            storage.insert(keccak256(key), (key, value));
+        }
+    }
+    if let Some(account) = changed {
+        for (slot, value) in &account.storage {
+            let key = B256::from(slot.to_be_bytes());
+            storage.insert(keccak256(key), (key, *value));
+        }
+    }
+
+    let mut slots = storage.range(key_start..).filter(|(_, (_, value))| *value != U256::ZERO);
+    let mut result = StorageRangeResult::default();
+    for (hashed_key, (key, value)) in slots.by_ref().take(max_result) {
+        result.storage.insert(
+            *hashed_key,
+            StorageRangeEntry { key: Some(*key), value: B256::from(value.to_be_bytes()) },
+        );
+    }
+    result.next_key = slots.next().map(|(hashed_key, _)| *hashed_key);
+
+    Ok(result)
+}
+
 impl<Provider, Eth> std::fmt::Debug for DebugApi<Provider, Eth> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DebugApi").finish_non_exhaustive()
@@ -1014,4 +1263,136 @@ struct DebugApiInner<Provider, Eth> {
     blocking_task_guard: BlockingTaskGuard,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
+    /// The cache for the traces of recently traced blocks.
+    trace_cache: TraceCache,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_interfaces::test_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_primitives::{address, hex, Transaction, TransactionKind, TxLegacy};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use secp256k1::{KeyPair, Secp256k1};
+
+    const CONTRACT: Address = address!("1111111111111111111111111111111111111111");
+
+    /// Returns a provider with a contract that stores the second word of the calldata in the slot
+    /// given by the first word.
+    fn provider_with_contract(storage: impl IntoIterator<Item = (B256, U256)>) -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        let code = Bytes::from(hex!("6020356000355500").to_vec());
+        provider.add_account(
+            CONTRACT,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(code).extend_storage(storage),
+        );
+        provider
+    }
+
+    /// Signs transactions that write the given slots of the contract, in order.
+    fn store_txs(key_pair: KeyPair, writes: &[(B256, U256)]) -> Vec<TransactionSigned> {
+        writes
+            .iter()
+            .enumerate()
+            .map(|(nonce, (key, value))| {
+                let mut input = key.to_vec();
+                input.extend_from_slice(&value.to_be_bytes::<32>());
+                let tx = Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce: nonce as u64,
+                    gas_price: 0,
+                    gas_limit: 100_000,
+                    to: TransactionKind::Call(CONTRACT),
+                    value: Default::default(),
+                    input: input.into(),
+                });
+                sign_tx_with_key_pair(key_pair, tx)
+            })
+            .collect()
+    }
+
+    fn slot(n: u8) -> B256 {
+        B256::with_last_byte(n)
+    }
+
+    fn storage_range(
+        provider: &MockEthProvider,
+        transactions: &[TransactionSigned],
+        tx_idx: usize,
+        key_start: B256,
+        max_result: usize,
+    ) -> StorageRangeResult {
+        storage_range_at(
+            provider.clone(),
+            CfgEnv::default(),
+            BlockEnv::default(),
+            transactions.iter().take(tx_idx).cloned(),
+            CONTRACT,
+            key_start,
+            max_result,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn storage_range_includes_earlier_transactions_of_block() {
+        let mut rng = generators::rng();
+        let key_pair = KeyPair::new(&Secp256k1::new(), &mut rng);
+        let provider = provider_with_contract([(slot(1), U256::from(1)), (slot(2), U256::from(2))]);
+        // the first transaction clears slot 2 and the second one writes slot 3
+        let transactions = store_txs(key_pair, &[(slot(2), U256::ZERO), (slot(3), U256::from(3))]);
+
+        let result = storage_range(&provider, &transactions, 0, B256::ZERO, 10);
+        assert_eq!(result.storage.len(), 2);
+        assert!(result.storage.contains_key(&keccak256(slot(2))));
+
+        // after the first transaction, slot 2 is cleared but slot 3 is not written yet
+        let result = storage_range(&provider, &transactions, 1, B256::ZERO, 10);
+        assert_eq!(
+            result.storage,
+            BTreeMap::from([(
+                keccak256(slot(1)),
+                StorageRangeEntry { key: Some(slot(1)), value: B256::with_last_byte(1) }
+            )])
+        );
+        assert_eq!(result.next_key, None);
+
+        // after the second transaction, slot 3 is visible
+        let result = storage_range(&provider, &transactions, 2, B256::ZERO, 10);
+        assert_eq!(result.storage.len(), 2);
+        assert_eq!(
+            result.storage.get(&keccak256(slot(3))),
+            Some(&StorageRangeEntry { key: Some(slot(3)), value: B256::with_last_byte(3) })
+        );
+    }
+
+    #[test]
+    fn storage_range_pagination() {
+        let mut rng = generators::rng();
+        let key_pair = KeyPair::new(&Secp256k1::new(), &mut rng);
+        let provider = provider_with_contract((1..=4).map(|n| (slot(n), U256::from(n))));
+        let writes = (5..=9).map(|n| (slot(n), U256::from(n))).collect::<Vec<_>>();
+        let transactions = store_txs(key_pair, &writes);
+
+        let mut pages = Vec::new();
+        let mut key_start = B256::ZERO;
+        loop {
+            let result = storage_range(&provider, &transactions, writes.len(), key_start, 3);
+            pages.push(result.storage);
+            match result.next_key {
+                Some(next_key) => key_start = next_key,
+                None => break,
+            }
+        }
+
+        assert_eq!(pages.len(), 3);
+        assert!(pages.iter().all(|page| page.len() == 3));
+        let hashed_keys = pages.iter().flat_map(|page| page.keys().copied()).collect::<Vec<_>>();
+        let mut expected = (1..=9).map(|n| keccak256(slot(n))).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(hashed_keys, expected);
+        for (hashed_key, entry) in pages.iter().flatten() {
+            assert_eq!(keccak256(entry.key.unwrap()), *hashed_key);
+        }
+    }
 }
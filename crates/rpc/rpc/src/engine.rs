@@ -5,6 +5,7 @@ use reth_rpc_api::{EngineEthApiServer, EthApiServer, EthFilterApiServer};
 pub use reth_rpc_engine_api::EngineApi;
 use reth_rpc_types::{
     state::StateOverride, BlockOverrides, CallRequest, Filter, Log, RichBlock, SyncStatus,
+    TransactionReceipt,
 };
 use tracing_futures::Instrument;
 
@@ -98,4 +99,9 @@ where
     async fn logs(&self, filter: Filter) -> Result<Vec<Log>> {
         self.eth_filter.logs(filter).instrument(engine_span!()).await
     }
+
+    /// Handler for: `eth_getBlockReceipts`
+    async fn block_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>> {
+        self.eth.block_receipts(block_id).instrument(engine_span!()).await
+    }
 }
@@ -158,10 +158,18 @@ where
             None => return Ok(None),
         };
         let block_hash = block.hash;
-        let total_difficulty = self
-            .provider()
-            .header_td_by_number(block.number)?
-            .ok_or(EthApiError::UnknownBlockNumber)?;
+
+        // the total difficulty is only known for canonical blocks, it's omitted for the pending
+        // block and blocks of the tree that are queried by hash
+        let total_difficulty = if self.provider().block_hash(block.number)? == Some(block_hash) {
+            Some(
+                self.provider()
+                    .header_td_by_number(block.number)?
+                    .ok_or(EthApiError::UnknownBlockNumber)?,
+            )
+        } else {
+            None
+        };
         let block = from_block(block.into(), total_difficulty, full.into(), Some(block_hash))?;
         Ok(Some(block.into()))
     }
@@ -2,17 +2,22 @@
 
 use crate::{
     eth::{
+        api::transactions::StateCacheDB,
         error::{ensure_success, EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
         revm_utils::{
             build_call_evm_env, caller_gas_allowance, cap_tx_gas_limit_with_caller_allowance,
-            get_precompiles, inspect, prepare_call_env, transact, EvmOverrides,
+            get_precompiles, inspect, prepare_call_env, transact, transact_call, EvmCallLimits,
+            EvmOverrides,
         },
         EthTransactions,
     },
     EthApi,
 };
 use reth_network_api::NetworkInfo;
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, U256};
+use reth_primitives::{
+    revm::{compat::intrinsic_gas, env::tx_env_with_recovered},
+    BlockId, BlockNumberOrTag, Bytes, U256,
+};
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
 };
@@ -25,15 +30,11 @@ use reth_rpc_types_compat::log::from_primitive_access_list;
 use reth_transaction_pool::TransactionPool;
 use revm::{
     db::{CacheDB, DatabaseRef},
-    primitives::{BlockEnv, CfgEnv, Env, ExecutionResult, Halt, TransactTo},
-    DatabaseCommit,
+    primitives::{BlockEnv, CfgEnv, Env, ExecutionResult, Halt, ResultAndState, TransactTo},
+    DatabaseCommit, Inspector,
 };
 use tracing::trace;
 
-// Gas per transaction not creating a contract.
-const MIN_TRANSACTION_GAS: u64 = 21_000u64;
-const MIN_CREATE_GAS: u64 = 53_000u64;
-
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
 where
     Pool: TransactionPool + Clone + 'static,
@@ -70,6 +71,32 @@ where
         ensure_success(res.result)
     }
 
+    /// Simulates the call request at the given [BlockId] like `eth_call` does, but executes it
+    /// with the [Inspector] created by the `inspector_factory` for the prepared [Env].
+    ///
+    /// Returns the inspector after the execution, so that external tracers, e.g. the validation
+    /// rules of ERC-4337 bundlers, can be run on simulated calls. See also
+    /// [simulate_with_inspector](crate::eth::revm_utils::simulate_with_inspector) for simulations
+    /// on top of an existing database.
+    pub async fn simulate_with_tracer<F, I>(
+        &self,
+        request: CallRequest,
+        at: BlockId,
+        overrides: EvmOverrides,
+        inspector_factory: F,
+    ) -> EthResult<(ResultAndState, Env, I)>
+    where
+        F: FnOnce(&Env) -> I + Send + 'static,
+        I: for<'r> Inspector<StateCacheDB<'r>> + Send + 'static,
+    {
+        self.spawn_with_call_at(request, at, overrides, move |db, env| {
+            let mut inspector = inspector_factory(&env);
+            let (res, env) = inspect(db, env, &mut inspector)?;
+            Ok((res, env, inspector))
+        })
+        .await
+    }
+
     /// Simulate arbitrary number of transactions at an arbitrary blockchain index, with the
     /// optionality of state overrides
     pub async fn call_many(
@@ -92,6 +119,7 @@ where
 
         let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
         let gas_limit = self.inner.gas_cap;
+        let call_limits = self.inner.call_limits;
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -137,10 +165,11 @@ where
                     block_env.clone(),
                     tx,
                     gas_limit,
+                    &call_limits,
                     &mut db,
                     overrides,
                 )?;
-                let (res, _) = transact(&mut db, env)?;
+                let (res, _) = transact_call(&mut db, env, &call_limits)?;
 
                 match ensure_success(res.result) {
                     Ok(output) => {
@@ -185,6 +214,9 @@ where
         // <https://github.com/ethereum/go-ethereum/blob/ee8e83fa5f6cb261dad2ed0a7bbcde4930c41e6c/internal/ethapi/api.go#L985>
         cfg.disable_base_fee = true;
 
+        let call_limits = self.inner.call_limits;
+        call_limits.apply(&mut cfg);
+
         // keep a copy of gas related request values
         let request_gas = request.gas;
         let request_gas_price = request.gas_price;
@@ -198,6 +230,15 @@ where
         let mut env = build_call_evm_env(cfg, block, request)?;
         let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
+        // the gas limit can't be lower than the intrinsic gas of the transaction
+        let min_gas_limit = intrinsic_gas(
+            &env.tx.data,
+            env.tx.transact_to.is_create(),
+            &env.tx.access_list,
+            env.cfg.spec_id,
+        )
+        .map_err(RpcInvalidTransactionError::from)?;
+
         // if the request is a simple transfer we can optimize
         if env.tx.data.is_empty() {
             if let TransactTo::Call(to) = env.tx.transact_to {
@@ -212,7 +253,7 @@ where
                                 RpcInvalidTransactionError::InsufficientFundsForTransfer.into()
                             )
                         }
-                        return Ok(U256::from(MIN_TRANSACTION_GAS))
+                        return Ok(U256::from(min_gas_limit))
                     }
                 }
             }
@@ -235,7 +276,7 @@ where
         trace!(target: "rpc::eth::estimate", ?env, "Starting gas estimation");
 
         // execute the call without writing to db
-        let ethres = transact(&mut db, env.clone());
+        let ethres = transact_call(&mut db, env.clone(), &call_limits);
 
         // Exceptional case: init used too much gas, we need to increase the gas limit and try
         // again
@@ -244,7 +285,7 @@ where
             // if price or limit was included in the request then we can execute the request
             // again with the block's gas limit to check if revert is gas related or not
             if request_gas.is_some() || request_gas_price.is_some() {
-                return Err(map_out_of_gas_err(env_gas_limit, env, &mut db))
+                return Err(map_out_of_gas_err(env_gas_limit, env, &call_limits, &mut db))
             }
         }
 
@@ -260,7 +301,7 @@ where
                 // if price or limit was included in the request then we can execute the request
                 // again with the block's gas limit to check if revert is gas related or not
                 return if request_gas.is_some() || request_gas_price.is_some() {
-                    Err(map_out_of_gas_err(env_gas_limit, env, &mut db))
+                    Err(map_out_of_gas_err(env_gas_limit, env, &call_limits, &mut db))
                 } else {
                     // the transaction did revert
                     Err(RpcInvalidTransactionError::Revert(RevertError::new(output)).into())
@@ -273,9 +314,8 @@ where
         // possible range NOTE: this is the gas the transaction used, which is less than the
         // transaction requires to succeed
         let gas_used = res.result.gas_used();
-        // the lowest value is capped by the gas it takes for a transfer
-        let mut lowest_gas_limit =
-            if env.tx.transact_to.is_create() { MIN_CREATE_GAS } else { MIN_TRANSACTION_GAS };
+        // the lowest value is capped by the intrinsic gas of the transaction
+        let mut lowest_gas_limit = min_gas_limit;
         let mut highest_gas_limit: u64 = highest_gas_limit.try_into().unwrap_or(u64::MAX);
         // pick a point that's close to the estimated gas
         let mut mid_gas_limit = std::cmp::min(
@@ -289,7 +329,7 @@ where
         while (highest_gas_limit - lowest_gas_limit) > 1 {
             let mut env = env.clone();
             env.tx.gas_limit = mid_gas_limit;
-            let ethres = transact(&mut db, env);
+            let ethres = transact_call(&mut db, env, &call_limits);
 
             // Exceptional case: init used too much gas, we need to increase the gas limit and try
             // again
@@ -367,6 +407,8 @@ where
         // <https://github.com/ethereum/go-ethereum/blob/8990c92aea01ca07801597b00c0d83d4e2d9b811/internal/ethapi/api.go#L1476-L1476>
         env.cfg.disable_base_fee = true;
 
+        self.inner.call_limits.apply(&mut env.cfg);
+
         let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
         if request.gas.is_none() && env.tx.gas_price > U256::ZERO {
@@ -416,6 +458,7 @@ where
 fn map_out_of_gas_err<S>(
     env_gas_limit: U256,
     mut env: Env,
+    call_limits: &EvmCallLimits,
     mut db: &mut CacheDB<StateProviderDatabase<S>>,
 ) -> EthApiError
 where
@@ -423,7 +466,7 @@ where
 {
     let req_gas_limit = env.tx.gas_limit;
     env.tx.gas_limit = env_gas_limit.try_into().unwrap_or(u64::MAX);
-    let (res, _) = match transact(&mut db, env) {
+    let (res, _) = match transact_call(&mut db, env, call_limits) {
         Ok(res) => res,
         Err(err) => return err,
     };
@@ -6,10 +6,11 @@ use crate::{
 };
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
-    basefee::calculate_next_block_base_fee, BlockNumberOrTag, SealedHeader, U256,
+    basefee::{next_block_base_fee, project_fees},
+    BlockNumberOrTag, SealedHeader, U256,
 };
 use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
-use reth_rpc_types::{FeeHistory, TxGasAndReward};
+use reth_rpc_types::{BaseFeeProjection, FeeHistory, TxGasAndReward};
 use reth_transaction_pool::TransactionPool;
 use tracing::debug;
 
@@ -117,12 +118,8 @@ where
         // The unwrap is safe since we checked earlier that we got at least 1 header.
         let last_header = headers.last().unwrap();
         let chain_spec = self.provider().chain_spec();
-        base_fee_per_gas.push(U256::from(calculate_next_block_base_fee(
-            last_header.gas_used,
-            last_header.gas_limit,
-            last_header.base_fee_per_gas.unwrap_or_default(),
-            chain_spec.base_fee_params,
-        )));
+        base_fee_per_gas
+            .push(U256::from(next_block_base_fee(last_header, &chain_spec).unwrap_or_default()));
 
         Ok(FeeHistory {
             base_fee_per_gas,
@@ -132,6 +129,37 @@ where
         })
     }
 
+    /// Projects the fees of the given amount of blocks after the latest block, assuming that every
+    /// block uses the `assumed_fullness` fraction of its gas limit.
+    pub(crate) async fn base_fee_projection(
+        &self,
+        mut block_count: u64,
+        assumed_fullness: f64,
+    ) -> EthResult<Vec<BaseFeeProjection>> {
+        let max_block_count = self.gas_oracle().config().max_header_history;
+        if block_count > max_block_count {
+            debug!(
+                requested = block_count,
+                truncated = max_block_count,
+                "Sanitizing base fee projection block count"
+            );
+            block_count = max_block_count
+        }
+
+        let latest =
+            self.provider().latest_header()?.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let chain_spec = self.provider().chain_spec();
+        let projection = project_fees(&latest, &chain_spec, block_count, assumed_fullness)
+            .into_iter()
+            .map(|fees| BaseFeeProjection {
+                block_number: U256::from(fees.number),
+                base_fee_per_gas: U256::from(fees.base_fee_per_gas),
+                blob_base_fee: fees.blob_fee.map(U256::from),
+            })
+            .collect();
+        Ok(projection)
+    }
+
     /// Calculates reward percentiles for transactions in a block header.
     /// Given a list of percentiles and a sealed block header, this function computes
     /// the corresponding rewards for the transactions at each percentile.
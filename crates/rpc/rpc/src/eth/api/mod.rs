@@ -8,18 +8,21 @@ use crate::eth::{
     cache::EthStateCache,
     error::{EthApiError, EthResult},
     gas_oracle::GasPriceOracle,
+    revm_utils::EvmCallLimits,
     signer::EthSigner,
 };
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use reth_interfaces::RethResult;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
-    Address, BlockId, BlockNumberOrTag, ChainInfo, SealedBlock, B256, U256, U64,
+    basefee::next_block_base_fee, Address, BlockId, BlockNumberOrTag, ChainInfo, SealedBlock, B256,
+    U256, U64,
 };
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
 };
-use reth_rpc_types::{SyncInfo, SyncStatus};
+use reth_rpc_types::{StageProgress, SyncInfo, SyncStatus};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::TransactionPool;
 use revm_primitives::{BlockEnv, CfgEnv};
@@ -100,6 +103,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap.into().into(),
+            EvmCallLimits::default(),
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
         )
@@ -114,6 +118,7 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: u64,
+        call_limits: EvmCallLimits,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
     ) -> Self {
@@ -133,6 +138,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap,
+            call_limits,
             starting_block: U256::from(latest_block),
             task_spawner,
             pending_block: Default::default(),
@@ -190,6 +196,12 @@ where
     pub fn pool(&self) -> &Pool {
         &self.inner.pool
     }
+
+    /// Adds a signer, whose accounts are returned by `eth_accounts` and can be used with the
+    /// signing methods, e.g. `eth_sign` and `eth_sendTransaction`.
+    pub fn add_signer(&self, signer: Arc<dyn EthSigner>) {
+        self.inner.signers.write().push(signer);
+    }
 }
 
 // === State access helpers ===
@@ -250,13 +262,13 @@ where
             let mut latest =
                 self.provider().latest_header()?.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
 
+            // base fee of the child block
+            let chain_spec = self.provider().chain_spec();
+            latest.base_fee_per_gas = next_block_base_fee(&latest, &chain_spec);
             // child block
             latest.number += 1;
             // assumed child block is in the next slot
             latest.timestamp += 12;
-            // base fee of the child block
-            let chain_spec = self.provider().chain_spec();
-            latest.base_fee_per_gas = latest.next_block_base_fee(chain_spec.base_fee_params);
 
             PendingBlockEnvOrigin::DerivedFromLatest(latest)
         };
@@ -358,7 +370,7 @@ where
     }
 
     fn accounts(&self) -> Vec<Address> {
-        self.inner.signers.iter().flat_map(|s| s.accounts()).collect()
+        self.inner.signers.read().iter().flat_map(|s| s.accounts()).collect()
     }
 
     fn is_syncing(&self) -> bool {
@@ -366,22 +378,33 @@ where
     }
 
     /// Returns the [SyncStatus] of the network
+    ///
+    /// The node is only considered synced if it is live syncing and close to the best head
+    /// advertised by its peers.
     fn sync_status(&self) -> RethResult<SyncStatus> {
-        let status = if self.is_syncing() {
-            let current_block = U256::from(
-                self.provider().chain_info().map(|info| info.best_number).unwrap_or_default(),
-            );
-            SyncStatus::Info(SyncInfo {
-                starting_block: self.inner.starting_block,
-                current_block,
-                highest_block: current_block,
-                warp_chunks_amount: None,
-                warp_chunks_processed: None,
+        let progress = self.network().sync_progress();
+        if progress.is_synced() {
+            return Ok(SyncStatus::None)
+        }
+
+        let current_block = progress.current_block;
+        let highest_block = progress.highest_block.unwrap_or_default().max(current_block);
+        let stages = progress
+            .stages
+            .into_iter()
+            .map(|(stage_id, block)| StageProgress {
+                name: stage_id.to_string(),
+                block: U64::from(block),
             })
-        } else {
-            SyncStatus::None
-        };
-        Ok(status)
+            .collect();
+        Ok(SyncStatus::Info(SyncInfo {
+            starting_block: self.inner.starting_block,
+            current_block: U256::from(current_block),
+            highest_block: U256::from(highest_block),
+            warp_chunks_amount: None,
+            warp_chunks_processed: None,
+            stages: Some(stages),
+        }))
     }
 }
 
@@ -392,6 +415,9 @@ where
 /// more complex calls.
 pub const RPC_DEFAULT_GAS_CAP: GasCap = GasCap(50_000_000);
 
+/// The default maximum memory in bytes that `eth_call` and adjacent calls can allocate.
+pub const RPC_DEFAULT_MEMORY_LIMIT: u64 = 64 * 1024 * 1024;
+
 /// The wrapper type for gas limit
 #[derive(Debug, Clone, Copy)]
 pub struct GasCap(u64);
@@ -423,13 +449,15 @@ struct EthApiInner<Provider, Pool, Network> {
     /// An interface to interact with the network
     network: Network,
     /// All configured Signers
-    signers: Vec<Box<dyn EthSigner>>,
+    signers: RwLock<Vec<Arc<dyn EthSigner>>>,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
     /// The async gas oracle frontend for gas price suggestions
     gas_oracle: GasPriceOracle<Provider>,
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     gas_cap: u64,
+    /// Limits of the EVM for `eth_call` and call tracing RPC methods.
+    call_limits: EvmCallLimits,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
@@ -14,7 +14,7 @@ use reth_revm::{
     database::StateProviderDatabase,
     state_change::{apply_beacon_root_contract_call, post_block_withdrawals_balance_increments},
 };
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use revm::{db::states::bundle_state::BundleRetention, Database, DatabaseCommit, State};
 use revm_primitives::{
     BlockEnv, CfgEnv, EVMError, Env, InvalidTransaction, ResultAndState, SpecId,
@@ -59,10 +59,14 @@ impl PendingBlockEnv {
         let mut sum_blob_gas_used = 0;
         let block_gas_limit: u64 = block_env.gas_limit.to::<u64>();
         let base_fee = block_env.basefee.to::<u64>();
+        let blob_fee = block_env
+            .get_blob_gasprice()
+            .map(|blob_gasprice| u64::try_from(blob_gasprice).unwrap_or(u64::MAX));
         let block_number = block_env.number.to::<u64>();
 
         let mut executed_txs = Vec::new();
-        let mut best_txs = pool.best_transactions_with_base_fee(base_fee);
+        let mut best_txs = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::new(base_fee, blob_fee));
 
         let (withdrawals, withdrawals_root) = match origin {
             PendingBlockEnvOrigin::ActualPending(ref block) => {
@@ -277,9 +281,11 @@ where
         initialized_block_env.timestamp.to::<u64>(),
         block_number,
         parent_beacon_block_root,
+        false,
         &mut evm_pre_block,
     )
-    .map_err(|err| EthApiError::Internal(err.into()))
+    .map_err(|err| EthApiError::Internal(err.into()))?;
+    Ok(())
 }
 
 /// The origin for a configured [PendingBlockEnv]
@@ -4,7 +4,7 @@
 use super::EthApiSpec;
 use crate::{
     eth::{
-        api::{EthApi, EthTransactions},
+        api::{sign::call_request_into_transaction_request, EthApi, EthTransactions},
         error::EthApiError,
         revm_utils::EvmOverrides,
     },
@@ -22,9 +22,9 @@ use reth_provider::{
 };
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, BlockOverrides, Bundle, CallRequest,
-    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock, StateContext,
-    SyncStatus, TransactionReceipt, TransactionRequest, Work,
+    state::StateOverride, AccessListWithGasUsed, BaseFeeProjection, BlockOverrides, Bundle,
+    CallRequest, EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock,
+    StateContext, SyncStatus, TransactionReceipt, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use serde_json::Value;
@@ -59,7 +59,12 @@ where
 
     /// Handler for: `eth_coinbase`
     async fn author(&self) -> Result<Address> {
-        Err(internal_rpc_err("unimplemented"))
+        trace!(target: "rpc::eth", "Serving eth_coinbase");
+        // the first account of the configured signers, like the etherbase of a dev node
+        EthApiSpec::accounts(self)
+            .into_iter()
+            .next()
+            .ok_or_else(|| internal_rpc_err("unimplemented"))
     }
 
     /// Handler for: `eth_accounts`
@@ -309,6 +314,16 @@ where
         )
     }
 
+    /// Handler for: `eth_baseFeeHistoryProjection`
+    async fn base_fee_history_projection(
+        &self,
+        block_count: U64HexOrNumber,
+        assumed_fullness: f64,
+    ) -> Result<Vec<BaseFeeProjection>> {
+        trace!(target: "rpc::eth", ?block_count, assumed_fullness, "Serving eth_baseFeeHistoryProjection");
+        return Ok(EthApi::base_fee_projection(self, block_count.to(), assumed_fullness).await?)
+    }
+
     /// Handler for: `eth_mining`
     async fn is_mining(&self) -> Result<bool> {
         Err(internal_rpc_err("unimplemented"))
@@ -353,8 +368,11 @@ where
     }
 
     /// Handler for: `eth_signTransaction`
-    async fn sign_transaction(&self, _transaction: CallRequest) -> Result<Bytes> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn sign_transaction(&self, transaction: CallRequest) -> Result<Bytes> {
+        trace!(target: "rpc::eth", ?transaction, "Serving eth_signTransaction");
+        let request = call_request_into_transaction_request(transaction)?;
+        let signed_tx = EthTransactions::sign_transaction_request(self, request).await?;
+        Ok(signed_tx.envelope_encoded())
     }
 
     /// Handler for: `eth_signTypedData`
@@ -501,12 +519,14 @@ mod tests {
 
         // Add final base fee (for the next block outside of the request)
         let last_header = last_header.unwrap();
-        base_fees_per_gas.push(U256::from(calculate_next_block_base_fee(
-            last_header.gas_used,
-            last_header.gas_limit,
-            last_header.base_fee_per_gas.unwrap_or_default(),
-            BaseFeeParams::ethereum(),
-        )));
+        base_fees_per_gas.push(U256::from(last_header.base_fee_per_gas.map_or(0, |base_fee| {
+            calculate_next_block_base_fee(
+                last_header.gas_used,
+                last_header.gas_limit,
+                base_fee,
+                BaseFeeParams::ethereum(),
+            )
+        })));
 
         let eth_api = build_test_eth_api(mock_provider);
 
@@ -7,9 +7,10 @@ use crate::{
     EthApi,
 };
 use alloy_dyn_abi::TypedData;
-use reth_primitives::{Address, Bytes};
+use reth_primitives::{Address, Bytes, U128, U256};
+use reth_rpc_types::{CallRequest, TransactionRequest};
 use serde_json::Value;
-use std::ops::Deref;
+use std::sync::Arc;
 
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network> {
     pub(crate) async fn sign(&self, account: Address, message: Bytes) -> EthResult<Bytes> {
@@ -26,15 +27,33 @@ impl<Provider, Pool, Network> EthApi<Provider, Pool, Network> {
         Ok(signature.to_hex_bytes())
     }
 
-    pub(crate) fn find_signer(
-        &self,
-        account: &Address,
-    ) -> Result<&(dyn EthSigner + 'static), SignError> {
+    pub(crate) fn find_signer(&self, account: &Address) -> Result<Arc<dyn EthSigner>, SignError> {
         self.inner
             .signers
+            .read()
             .iter()
             .find(|signer| signer.is_signer_for(account))
-            .map(|signer| signer.deref())
+            .cloned()
             .ok_or(SignError::NoAccount)
     }
 }
+
+/// Converts the request of `eth_signTransaction` into the request of `eth_sendTransaction`.
+pub(crate) fn call_request_into_transaction_request(
+    request: CallRequest,
+) -> EthResult<TransactionRequest> {
+    let to_u128 = |value: U256| U128::from(value.saturating_to::<u128>());
+    Ok(TransactionRequest {
+        from: request.from,
+        to: request.to,
+        gas_price: request.gas_price.map(to_u128),
+        max_fee_per_gas: request.max_fee_per_gas.map(to_u128),
+        max_priority_fee_per_gas: request.max_priority_fee_per_gas.map(to_u128),
+        gas: request.gas,
+        value: request.value,
+        data: request.input.try_into_unique_input()?,
+        nonce: request.nonce,
+        access_list: request.access_list,
+        transaction_type: request.transaction_type,
+    })
+}
@@ -4,8 +4,8 @@ use crate::{
         api::pending_block::PendingBlockEnv,
         error::{EthApiError, EthResult, SignError},
         revm_utils::{
-            inspect, inspect_and_return_db, prepare_call_env, replay_transactions_until, transact,
-            EvmOverrides,
+            inspect, inspect_and_return_db, prepare_call_env, replay_transactions_until,
+            transact_call, EvmCallLimits, EvmOverrides,
         },
         utils::recover_raw_transaction,
     },
@@ -53,6 +53,9 @@ pub trait EthTransactions: Send + Sync {
     /// Returns default gas limit to use for `eth_call` and tracing RPC methods.
     fn call_gas_limit(&self) -> u64;
 
+    /// Returns the limits of the EVM for `eth_call` and tracing RPC methods.
+    fn call_limits(&self) -> EvmCallLimits;
+
     /// Returns the state at the given [BlockId]
     fn state_at(&self, at: BlockId) -> EthResult<StateProviderBox<'_>>;
 
@@ -135,6 +138,16 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256>;
 
+    /// Signs the transaction with the signer of its `from` account.
+    ///
+    /// The nonce, the gas limit and the fees are filled in if they are not set: the nonce is the
+    /// next nonce of the account including its pending transactions, the gas limit is estimated
+    /// and the fees are suggested by the gas price oracle.
+    async fn sign_transaction_request(
+        &self,
+        request: TransactionRequest,
+    ) -> EthResult<TransactionSigned>;
+
     /// Prepares the state and env for the given [CallRequest] at the given [BlockId] and executes
     /// the closure on a new task returning the result of the closure.
     async fn spawn_with_call_at<F, R>(
@@ -303,6 +316,10 @@ where
         self.inner.gas_cap
     }
 
+    fn call_limits(&self) -> EvmCallLimits {
+        self.inner.call_limits
+    }
+
     fn state_at(&self, at: BlockId) -> EthResult<StateProviderBox<'_>> {
         self.state_at_block_id(at)
     }
@@ -490,11 +507,30 @@ where
         Ok(hash)
     }
 
-    async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<B256> {
+    async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256> {
+        let signed_tx = self.sign_transaction_request(request).await?;
+
+        let recovered =
+            signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+
+        let pool_transaction = <Pool::Transaction>::from_recovered_transaction(recovered.into());
+
+        // submit the transaction to the pool with a `Local` origin
+        let hash = self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
+
+        Ok(hash)
+    }
+
+    async fn sign_transaction_request(
+        &self,
+        mut request: TransactionRequest,
+    ) -> EthResult<TransactionSigned> {
         let from = match request.from {
             Some(from) => from,
             None => return Err(SignError::NoAccount.into()),
         };
+        // fail early if the account can't be signed for
+        self.find_signer(&from)?;
 
         // set nonce if not already set before
         if request.nonce.is_none() {
@@ -504,77 +540,83 @@ where
             request.nonce = Some(U64::from(nonce.to::<u64>()));
         }
 
+        // fill in the fees of the transaction type of the request
+        match (request.gas_price, request.max_fee_per_gas) {
+            // EIP-2930 transactions pay a legacy gas price
+            (None, None) if request.access_list.is_some() => {
+                let gas_price = self.gas_price().await?;
+                request.gas_price = Some(U128::from(gas_price.saturating_to::<u128>()));
+            }
+            (None, max_fee_per_gas) => {
+                let tip = match request.max_priority_fee_per_gas {
+                    Some(tip) => tip,
+                    None => {
+                        let tip = self.suggested_priority_fee().await?;
+                        U128::from(tip.saturating_to::<u128>())
+                    }
+                };
+                if max_fee_per_gas.is_none() {
+                    // leave room for the base fee to double until the transaction is included
+                    let base_fee = self
+                        .block(BlockNumberOrTag::Latest)
+                        .await?
+                        .and_then(|block| block.base_fee_per_gas)
+                        .unwrap_or_default();
+                    request.max_fee_per_gas =
+                        Some(U128::from(base_fee as u128 * 2).saturating_add(tip));
+                }
+                request.max_priority_fee_per_gas = Some(tip);
+            }
+            _ => {}
+        }
+
         let chain_id = self.chain_id();
-        // TODO: we need an oracle to fetch the gas price of the current chain
-        let gas_price = request.gas_price.unwrap_or_default();
-        let max_fee_per_gas = request.max_fee_per_gas.unwrap_or_default();
-
-        let estimated_gas = self
-            .estimate_gas_at(
-                CallRequest {
-                    from: Some(from),
-                    to: request.to,
-                    gas: request.gas,
-                    gas_price: Some(U256::from(gas_price)),
-                    max_fee_per_gas: Some(U256::from(max_fee_per_gas)),
-                    value: request.value,
-                    input: request.data.clone().into(),
-                    nonce: request.nonce,
-                    chain_id: Some(chain_id),
-                    access_list: request.access_list.clone(),
-                    max_priority_fee_per_gas: Some(U256::from(max_fee_per_gas)),
-                    transaction_type: None,
-                    blob_versioned_hashes: None,
-                    max_fee_per_blob_gas: None,
-                },
-                BlockId::Number(BlockNumberOrTag::Pending),
-            )
-            .await?;
-        let gas_limit = estimated_gas;
+        if request.gas.is_none() {
+            let estimated_gas = self
+                .estimate_gas_at(
+                    CallRequest {
+                        from: Some(from),
+                        to: request.to,
+                        gas: None,
+                        gas_price: request.gas_price.map(U256::from),
+                        max_fee_per_gas: request.max_fee_per_gas.map(U256::from),
+                        max_priority_fee_per_gas: request.max_priority_fee_per_gas.map(U256::from),
+                        value: request.value,
+                        input: request.data.clone().into(),
+                        nonce: request.nonce,
+                        chain_id: Some(chain_id),
+                        access_list: request.access_list.clone(),
+                        transaction_type: None,
+                        blob_versioned_hashes: None,
+                        max_fee_per_blob_gas: None,
+                    },
+                    BlockId::Number(BlockNumberOrTag::Pending),
+                )
+                .await?;
+            request.gas = Some(estimated_gas);
+        }
 
         let transaction = match request.into_typed_request() {
             Some(TypedTransactionRequest::Legacy(mut m)) => {
                 m.chain_id = Some(chain_id.to());
-                m.gas_limit = gas_limit;
-                m.gas_price = gas_price;
-
                 TypedTransactionRequest::Legacy(m)
             }
             Some(TypedTransactionRequest::EIP2930(mut m)) => {
                 m.chain_id = chain_id.to();
-                m.gas_limit = gas_limit;
-                m.gas_price = gas_price;
-
                 TypedTransactionRequest::EIP2930(m)
             }
             Some(TypedTransactionRequest::EIP1559(mut m)) => {
                 m.chain_id = chain_id.to();
-                m.gas_limit = gas_limit;
-                m.max_fee_per_gas = max_fee_per_gas;
-
                 TypedTransactionRequest::EIP1559(m)
             }
             Some(TypedTransactionRequest::EIP4844(mut m)) => {
                 m.chain_id = chain_id.to();
-                m.gas_limit = gas_limit;
-                m.gas_price = gas_price;
-
                 TypedTransactionRequest::EIP4844(m)
             }
             None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
         };
 
-        let signed_tx = self.sign_request(&from, transaction)?;
-
-        let recovered =
-            signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
-
-        let pool_transaction = <Pool::Transaction>::from_recovered_transaction(recovered.into());
-
-        // submit the transaction to the pool with a `Local` origin
-        let hash = self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
-
-        Ok(hash)
+        self.sign_request(&from, transaction)
     }
 
     async fn spawn_with_call_at<F, R>(
@@ -601,6 +643,7 @@ where
                     block_env,
                     request,
                     this.call_gas_limit(),
+                    &this.call_limits(),
                     &mut db,
                     overrides,
                 )?;
@@ -616,8 +659,11 @@ where
         at: BlockId,
         overrides: EvmOverrides,
     ) -> EthResult<(ResultAndState, Env)> {
-        self.spawn_with_call_at(request, at, overrides, move |mut db, env| transact(&mut db, env))
-            .await
+        let call_limits = self.call_limits();
+        self.spawn_with_call_at(request, at, overrides, move |mut db, env| {
+            transact_call(&mut db, env, &call_limits)
+        })
+        .await
     }
 
     async fn spawn_inspect_call_at<I>(
@@ -797,6 +843,11 @@ where
         let block_hash = block.hash;
         let transactions = block.body;
 
+        // there is nothing to replay, this also covers the genesis block which has no parent state
+        if transactions.is_empty() {
+            return Ok(Some(Vec::new()))
+        }
+
         // replay all transactions of the block
         self.spawn_with_state_at_block(state_at.into(), move |state| {
             let mut results = Vec::with_capacity(transactions.len());
@@ -879,15 +930,8 @@ where
         from: &Address,
         request: TypedTransactionRequest,
     ) -> EthResult<TransactionSigned> {
-        for signer in self.inner.signers.iter() {
-            if signer.is_signer_for(from) {
-                return match signer.sign_transaction(request, from) {
-                    Ok(tx) => Ok(tx),
-                    Err(e) => Err(e.into()),
-                }
-            }
-        }
-        Err(EthApiError::InvalidTransactionSignature)
+        let signer = self.find_signer(from)?;
+        Ok(signer.sign_transaction(request, from)?)
     }
 
     /// Get Transaction by [BlockId] and the index of the transaction within that Block.
@@ -1090,13 +1134,16 @@ pub(crate) fn build_transaction_receipt_with_block_receipts(
 mod tests {
     use super::*;
     use crate::{
-        eth::{cache::EthStateCache, gas_oracle::GasPriceOracle},
+        eth::{
+            cache::EthStateCache, gas_oracle::GasPriceOracle, DevSigner, EthApiSpec, DEV_MNEMONIC,
+        },
         BlockingTaskPool, EthApi,
     };
     use reth_network_api::noop::NoopNetwork;
     use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, hex_literal::hex, Bytes};
     use reth_provider::test_utils::NoopProvider;
     use reth_transaction_pool::{test_utils::testing_pool, TransactionPool};
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn send_raw_transaction() {
@@ -1141,4 +1188,48 @@ mod tests {
         assert!(pool.get(&tx_1_result).is_some(), "tx1 not found in the pool");
         assert!(pool.get(&tx_2_result).is_some(), "tx2 not found in the pool");
     }
+
+    #[tokio::test]
+    async fn send_transaction_with_dev_signer() {
+        let noop_provider = NoopProvider::default();
+        let pool = testing_pool();
+        let cache = EthStateCache::spawn(noop_provider, Default::default());
+        let eth_api = EthApi::new(
+            noop_provider,
+            pool.clone(),
+            NoopNetwork::default(),
+            cache.clone(),
+            GasPriceOracle::new(noop_provider, Default::default(), cache),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+        );
+        let request = TransactionRequest {
+            to: Some(Address::with_last_byte(0x42)),
+            gas: Some(U256::from(21_000)),
+            max_fee_per_gas: Some(U128::from(2_000_000_000u64)),
+            max_priority_fee_per_gas: Some(U128::from(1_000_000_000u64)),
+            value: Some(U256::from(1)),
+            ..Default::default()
+        };
+
+        // no signers are configured by default
+        assert!(EthApiSpec::accounts(&eth_api).is_empty());
+        let err = eth_api.send_transaction(request.clone()).await.unwrap_err();
+        assert!(matches!(err, EthApiError::Signing(SignError::NoAccount)));
+
+        let signer = DevSigner::from_mnemonic(DEV_MNEMONIC, 1).unwrap();
+        eth_api.add_signer(Arc::new(signer));
+        let from = EthApiSpec::accounts(&eth_api)[0];
+
+        let request = TransactionRequest { from: Some(from), ..request };
+        let first = eth_api.send_transaction(request.clone()).await.unwrap();
+        let second = eth_api.send_transaction(request).await.unwrap();
+
+        // the transactions are signed by the account and the nonce follows the pending ones
+        for (hash, nonce) in [(first, 0), (second, 1)] {
+            let tx = pool.get(&hash).expect("transaction not found in the pool");
+            assert_eq!(tx.sender(), from);
+            assert_eq!(tx.nonce(), nonce);
+        }
+    }
 }
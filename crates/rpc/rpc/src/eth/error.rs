@@ -273,6 +273,9 @@ pub enum RpcInvalidTransactionError {
     /// As BasicOutOfGas but thrown when gas exhausts during precompiled contract execution.
     #[error("Out of gas: gas exhausts during precompiled contract execution: {0:?}")]
     PrecompileOutOfGas(U256),
+    /// Thrown if a call exceeds the memory limit of the EVM for calls.
+    #[error("out of memory limit")]
+    MemoryLimitExceeded,
     /// revm's Type cast error, U256 casts down to a u64 with overflow
     #[error("Out of gas: revm's Type cast error, U256 casts down to a u64 with overflow {0:?}")]
     InvalidOperandOutOfGas(U256),
@@ -348,7 +351,7 @@ impl RpcInvalidTransactionError {
             OutOfGasError::InvalidOperand => {
                 RpcInvalidTransactionError::InvalidOperandOutOfGas(gas_limit)
             }
-            OutOfGasError::MemoryLimit => RpcInvalidTransactionError::MemoryOutOfGas(gas_limit),
+            OutOfGasError::MemoryLimit => RpcInvalidTransactionError::MemoryLimitExceeded,
         }
     }
 }
@@ -557,6 +560,7 @@ impl From<PoolError> for RpcPoolError {
             PoolError::ReplacementUnderpriced(_) => RpcPoolError::ReplaceUnderpriced,
             PoolError::FeeCapBelowMinimumProtocolFeeCap(_, _) => RpcPoolError::Underpriced,
             PoolError::SpammerExceededCapacity(_, _) => RpcPoolError::TxPoolOverflow,
+            PoolError::RateLimited(_, _) => RpcPoolError::TxPoolOverflow,
             PoolError::DiscardedOnInsert(_) => RpcPoolError::TxPoolOverflow,
             PoolError::InvalidTransaction(_, err) => err.into(),
             PoolError::Other(_, err) => RpcPoolError::Other(err),
@@ -614,6 +618,12 @@ pub enum SignError {
     /// No chain ID was given.
     #[error("No chainid")]
     NoChainId,
+    /// A configured private key is not a valid secp256k1 key.
+    #[error("Invalid private key")]
+    InvalidKey,
+    /// A configured mnemonic is not a valid BIP-39 mnemonic.
+    #[error("Invalid mnemonic")]
+    InvalidMnemonic,
 }
 
 /// Converts the evm [ExecutionResult] into a result where `Ok` variant is the output bytes if it is
@@ -9,16 +9,17 @@ use crate::{
 };
 use alloy_primitives::B256;
 use async_trait::async_trait;
+use futures::{Stream, StreamExt};
 use jsonrpsee::{core::RpcResult, server::IdProvider};
 use reth_interfaces::RethError;
-use reth_primitives::{BlockHashOrNumber, Receipt, SealedBlock, TxHash};
-use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider};
+use reth_primitives::{BlockHashOrNumber, BlockNumHash, Receipt, SealedBlock, TxHash};
+use reth_provider::{BlockIdReader, BlockReader, CanonStateNotification, Chain, EvmEnvProvider};
 use reth_rpc_api::EthFilterApiServer;
 use reth_rpc_types::{Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log};
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::TransactionPool;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     iter::StepBy,
     ops::RangeInclusive,
     sync::Arc,
@@ -33,6 +34,9 @@ use tracing::trace;
 /// The maximum number of headers we read at once when handling a range filter.
 const MAX_HEADERS_RANGE: u64 = 1_000; // with ~530bytes per header this is ~500kb
 
+/// The maximum number of reverted chains we keep to report the removed logs of reorgs.
+const MAX_REVERTED_CHAINS: usize = 64;
+
 /// `Eth` filter RPC implementation.
 pub struct EthFilter<Provider, Pool> {
     /// All nested fields bundled together.
@@ -70,6 +74,7 @@ where
             max_headers_range: MAX_HEADERS_RANGE,
             task_spawner,
             stale_filter_ttl,
+            reverted_chains: Default::default(),
         };
 
         let eth_filter = Self { inner: Arc::new(inner) };
@@ -100,6 +105,21 @@ where
         }
     }
 
+    /// Endless future that records the chains that are reverted by reorgs, so that the logs of
+    /// their blocks are reported as removed by [Self::filter_changes].
+    ///
+    /// Without this, log filters do not report removed logs.
+    pub async fn watch_reorgs<St>(&self, mut events: St)
+    where
+        St: Stream<Item = CanonStateNotification> + Unpin,
+    {
+        while let Some(event) = events.next().await {
+            if let Some(reverted) = event.reverted() {
+                self.inner.reverted_chains.insert(reverted).await;
+            }
+        }
+    }
+
     /// Clears all filters that have not been polled for longer than the configured
     /// `stale_filter_ttl` at the given instant.
     pub async fn clear_stale_filters(&self, now: Instant) {
@@ -122,31 +142,45 @@ where
     Pool: TransactionPool + 'static,
 {
     /// Returns all the filter changes for the given id, if any
+    ///
+    /// If blocks that were reported by a previous poll have been reorged out since, the matching
+    /// logs of these blocks are returned with `removed: true`, followed by the logs of the new
+    /// canonical blocks.
     pub async fn filter_changes(&self, id: FilterId) -> Result<FilterChanges, FilterError> {
         let info = self.inner.provider.chain_info()?;
         let best_number = info.best_number;
 
         // start_block is the block from which we should start fetching changes, the next block from
         // the last time changes were polled, in other words the best block at last poll + 1
-        let (start_block, kind) = {
+        let (start_block, last_head, kind) = {
             let mut filters = self.inner.active_filters.inner.lock().await;
             let filter = filters.get_mut(&id).ok_or(FilterError::FilterNotFound(id))?;
 
-            if filter.block > best_number {
-                // no new blocks since the last poll
-                return Ok(FilterChanges::Empty)
-            }
-
             // update filter
             // we fetch all changes from [filter.block..best_block], so we advance the filter's
             // block to `best_block +1`, the next from which we should start fetching changes again
-            let mut block = best_number + 1;
-            std::mem::swap(&mut filter.block, &mut block);
+            let block = std::mem::replace(&mut filter.block, best_number + 1);
+            let head = filter.head.replace(BlockNumHash::new(best_number, info.best_hash));
             filter.last_poll_timestamp = Instant::now();
 
-            (block, filter.kind.clone())
+            (block, head, filter.kind.clone())
+        };
+
+        // blocks that were reported by a previous poll but are no longer canonical
+        let reverted = match last_head {
+            Some(head) => self.inner.reverted_blocks(head).await?,
+            None => Vec::new(),
         };
 
+        // the changes of the new canonical chain start right after the fork
+        let start_block =
+            reverted.first().map_or(start_block, |(block, _)| block.number.min(start_block));
+
+        if start_block > best_number && reverted.is_empty() {
+            // no new blocks since the last poll
+            return Ok(FilterChanges::Empty)
+        }
+
         match kind {
             FilterKind::PendingTransaction(receiver) => {
                 let pending_txs = receiver.drain().await;
@@ -184,10 +218,24 @@ where
                     }
                 };
 
-                let logs = self
-                    .inner
-                    .get_logs_in_block_range(&filter, from_block_number, to_block_number)
-                    .await?;
+                // the removed logs are reported first
+                let mut logs = Vec::new();
+                let filter_params = FilteredParams::new(Some(*filter.clone()));
+                for (block, receipts) in reverted {
+                    logs_utils::append_matching_block_logs(
+                        &mut logs,
+                        &filter_params,
+                        (block.number, block.hash).into(),
+                        block.body.into_iter().map(|tx| tx.hash()).zip(receipts),
+                        true,
+                    );
+                }
+
+                logs.extend(
+                    self.inner
+                        .get_logs_in_block_range(&filter, from_block_number, to_block_number)
+                        .await?,
+                );
                 Ok(FilterChanges::Logs(logs))
             }
         }
@@ -314,6 +362,8 @@ struct EthFilterInner<Provider, Pool> {
     task_spawner: Box<dyn TaskSpawner>,
     /// Duration since the last filter poll, after which the filter is considered stale
     stale_filter_ttl: Duration,
+    /// The most recent chains that were reverted by reorgs
+    reverted_chains: RevertedChains,
 }
 
 impl<Provider, Pool> EthFilterInner<Provider, Pool>
@@ -371,6 +421,7 @@ where
             id.clone(),
             ActiveFilter {
                 block: last_poll_block_number,
+                head: None,
                 last_poll_timestamp: Instant::now(),
                 kind,
             },
@@ -378,6 +429,30 @@ where
         Ok(id)
     }
 
+    /// Returns the blocks with their receipts that were reverted since `head` was the canonical
+    /// tip, oldest first.
+    ///
+    /// This walks back from `head` until it reaches a canonical block. Only the blocks of the
+    /// recently reverted chains are known, so the walk also ends at a block that is no longer
+    /// tracked.
+    async fn reverted_blocks(
+        &self,
+        head: BlockNumHash,
+    ) -> Result<Vec<(SealedBlock, Vec<Receipt>)>, FilterError> {
+        let mut reverted = Vec::new();
+        let mut hash = head.hash;
+        while let Some((block, receipts)) = self.reverted_chains.block_and_receipts(hash).await {
+            if self.provider.block_hash(block.number)? == Some(hash) {
+                // the block is canonical again
+                break
+            }
+            hash = block.parent_hash;
+            reverted.push((block, receipts));
+        }
+        reverted.reverse();
+        Ok(reverted)
+    }
+
     /// Fetches both receipts and block for the given block number.
     async fn block_and_receipts_by_number(
         &self,
@@ -467,11 +542,40 @@ pub struct ActiveFilters {
     inner: Arc<Mutex<HashMap<FilterId, ActiveFilter>>>,
 }
 
+/// The most recent chains that were reverted by reorgs, oldest first.
+#[derive(Debug, Default)]
+struct RevertedChains {
+    chains: Mutex<VecDeque<Arc<Chain>>>,
+}
+
+impl RevertedChains {
+    /// Records a reverted chain, evicting the oldest chain if the limit is reached.
+    async fn insert(&self, chain: Arc<Chain>) {
+        let mut chains = self.chains.lock().await;
+        if chains.len() == MAX_REVERTED_CHAINS {
+            chains.pop_front();
+        }
+        chains.push_back(chain);
+    }
+
+    /// Returns the reverted block with the given hash and its receipts, if known.
+    async fn block_and_receipts(&self, hash: B256) -> Option<(SealedBlock, Vec<Receipt>)> {
+        let chains = self.chains.lock().await;
+        chains.iter().rev().find_map(|chain| {
+            let block = chain.block(hash)?;
+            let receipts = chain.receipts_by_block_hash(hash)?;
+            Some((block.clone(), receipts.into_iter().cloned().collect()))
+        })
+    }
+}
+
 /// An installed filter
 #[derive(Debug)]
 struct ActiveFilter {
     /// At which block the filter was polled last.
     block: u64,
+    /// The best block at the last poll, `None` if the filter was not polled yet.
+    head: Option<BlockNumHash>,
     /// Last time this filter was polled.
     last_poll_timestamp: Instant,
     /// What kind of filter it is.
@@ -577,7 +681,13 @@ impl Iterator for BlockRangeInclusiveIter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::eth::cache::EthStateCache;
     use rand::{thread_rng, Rng};
+    use reth_interfaces::test_utils::generators::{self, random_block, random_receipt};
+    use reth_primitives::{Address, Receipts, SealedBlockWithSenders};
+    use reth_provider::{test_utils::MockEthProvider, BundleStateWithReceipts};
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::test_utils::testing_pool;
 
     #[test]
     fn test_block_range_iter() {
@@ -601,4 +711,75 @@ mod tests {
             assert_eq!(end, *range.end());
         }
     }
+
+    #[tokio::test]
+    async fn filter_changes_report_removed_logs() {
+        let mut rng = generators::rng();
+        let provider = MockEthProvider::default();
+        let genesis = random_block(&mut rng, 0, None, Some(0), Some(0));
+        let block1 = random_block(&mut rng, 1, Some(genesis.hash), Some(0), Some(0));
+        for block in [&genesis, &block1] {
+            provider.add_block(block.hash, block.clone().unseal());
+        }
+
+        let filter = EthFilter::new(
+            provider.clone(),
+            testing_pool(),
+            EthStateCache::spawn(provider.clone(), Default::default()),
+            1_000,
+            Box::<TokioTaskExecutor>::default(),
+            Duration::from_secs(60),
+        );
+        let id = EthFilterApiServer::new_filter(&filter, Filter::default()).await.unwrap();
+
+        // the old chain has a log in block 2
+        let old2 = random_block(&mut rng, 2, Some(block1.hash), Some(1), Some(0));
+        let old3 = random_block(&mut rng, 3, Some(old2.hash), Some(0), Some(0));
+        let old_receipts = vec![random_receipt(&mut rng, &old2.body[0], Some(1))];
+        provider.add_block(old2.hash, old2.clone().unseal());
+        provider.add_receipts(old2.hash, old_receipts.clone());
+        provider.add_block(old3.hash, old3.clone().unseal());
+
+        let FilterChanges::Logs(logs) = filter.filter_changes(id.clone()).await.unwrap() else {
+            panic!("expected logs")
+        };
+        assert_eq!(logs.len(), 1);
+        assert!(!logs[0].removed);
+
+        // reorg the two blocks of the old chain to a longer chain without logs
+        for block in [&old2, &old3] {
+            provider.blocks.lock().remove(&block.hash);
+            provider.headers.lock().remove(&block.hash);
+        }
+        let new2 = random_block(&mut rng, 2, Some(block1.hash), Some(0), Some(0));
+        let new3 = random_block(&mut rng, 3, Some(new2.hash), Some(0), Some(0));
+        let new4 = random_block(&mut rng, 4, Some(new3.hash), Some(0), Some(0));
+        for block in [&new2, &new3, &new4] {
+            provider.add_block(block.hash, block.clone().unseal());
+        }
+        let old = Chain::new(
+            vec![
+                SealedBlockWithSenders { block: old2.clone(), senders: vec![Address::ZERO] },
+                SealedBlockWithSenders { block: old3, senders: vec![] },
+            ],
+            BundleStateWithReceipts::new(
+                Default::default(),
+                Receipts::from_vec(vec![old_receipts.into_iter().map(Some).collect(), vec![]]),
+                2,
+            ),
+        );
+        let reorg =
+            CanonStateNotification::Reorg { old: Arc::new(old), new: Arc::new(Chain::default()) };
+        filter.watch_reorgs(futures::stream::iter([reorg])).await;
+
+        // the log of the old chain is reported as removed exactly once
+        let FilterChanges::Logs(logs) = filter.filter_changes(id.clone()).await.unwrap() else {
+            panic!("expected logs")
+        };
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].removed);
+        assert_eq!(logs[0].block_hash, Some(old2.hash));
+
+        assert_eq!(filter.filter_changes(id).await.unwrap(), FilterChanges::Empty);
+    }
 }
@@ -13,8 +13,12 @@ pub mod revm_utils;
 mod signer;
 pub(crate) mod utils;
 
-pub use api::{EthApi, EthApiSpec, EthTransactions, TransactionSource, RPC_DEFAULT_GAS_CAP};
+pub use api::{
+    EthApi, EthApiSpec, EthTransactions, TransactionSource, RPC_DEFAULT_GAS_CAP,
+    RPC_DEFAULT_MEMORY_LIMIT,
+};
 pub use bundle::EthBundle;
 pub use filter::EthFilter;
 pub use id_provider::EthSubscriptionIdProvider;
 pub use pubsub::EthPubSub;
+pub use signer::{DevSigner, DevSignerConfig, EthSigner, DEFAULT_DEV_ACCOUNTS, DEV_MNEMONIC};
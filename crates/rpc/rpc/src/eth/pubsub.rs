@@ -5,16 +5,19 @@ use jsonrpsee::{server::SubscriptionMessage, PendingSubscriptionSink, Subscripti
 use reth_network_api::NetworkInfo;
 use reth_primitives::{IntoRecoveredTransaction, TxHash};
 use reth_provider::{BlockReader, CanonStateSubscriptions, EvmEnvProvider};
-use reth_rpc_api::EthPubSubApiServer;
+use reth_rpc_api::{EthPubSubApiServer, RethPubSubApiServer};
 use reth_rpc_types::{
     pubsub::{
-        Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
+        Params, PendingTransactionFilter, PendingTransactionSubscriptionResult, PubSubSyncStatus,
+        RethSubscriptionKind, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
     FilteredParams, Header, Log,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
+use reth_transaction_pool::{
+    FilteredTransactionEvent, NewTransactionEvent, SubPool, TransactionFilter, TransactionPool,
+};
 use serde::Serialize;
 use std::sync::Arc;
 use tokio_stream::{
@@ -88,7 +91,65 @@ where
     }
 }
 
-/// The actual handler for an accepted [`EthPubSub::subscribe`] call.
+#[async_trait::async_trait]
+impl<Provider, Pool, Events, Network> RethPubSubApiServer
+    for EthPubSub<Provider, Pool, Events, Network>
+where
+    Provider: Send + Sync + 'static,
+    Pool: TransactionPool + 'static,
+    Events: Send + Sync + 'static,
+    Network: Send + Sync + 'static,
+{
+    /// Handler for `reth_subscribe`
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: RethSubscriptionKind,
+        filter: Option<PendingTransactionFilter>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let sink = pending.accept().await?;
+        match kind {
+            RethSubscriptionKind::PendingTransactions => {
+                let filter = pool_transaction_filter(filter.unwrap_or_default());
+                let stream =
+                    self.inner.filtered_pending_transaction_stream(filter).map(
+                        |event| match event {
+                            FilteredTransactionEvent::Transaction(tx) => {
+                                PendingTransactionSubscriptionResult::Transaction(Box::new(
+                                    reth_rpc_types_compat::transaction::from_recovered(
+                                        tx.transaction.to_recovered_transaction(),
+                                    ),
+                                ))
+                            }
+                            FilteredTransactionEvent::Lagged { dropped } => {
+                                PendingTransactionSubscriptionResult::Lagged { dropped }
+                            }
+                        },
+                    );
+                self.subscription_task_spawner.spawn(Box::pin(async move {
+                    // the stream ends after it lagged, which closes the subscription
+                    let _ = pipe_from_stream(sink, stream).await;
+                }));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts the filter of a `reth_subscribe("pendingTransactions")` call into a pool filter.
+fn pool_transaction_filter(filter: PendingTransactionFilter) -> TransactionFilter {
+    let PendingTransactionFilter { senders, recipients, selectors, min_value, tx_types } = filter;
+    TransactionFilter {
+        senders: senders.into_iter().collect(),
+        recipients: recipients.into_iter().collect(),
+        selectors: selectors.into_iter().collect(),
+        min_value,
+        tx_types: tx_types.into_iter().map(|tx_type| tx_type.to()).collect(),
+    }
+}
+
+/// The actual handler for an accepted [`EthPubSubApiServer::subscribe`] call.
 async fn handle_accepted<Provider, Pool, Events, Network>(
     pubsub: Arc<EthPubSubInner<Provider, Pool, Events, Network>>,
     accepted_sink: SubscriptionSink,
@@ -278,6 +339,20 @@ where
     ) -> impl Stream<Item = NewTransactionEvent<<Pool as TransactionPool>::Transaction>> {
         self.pool.new_pending_pool_transactions_listener()
     }
+
+    /// Returns a stream that yields the transactions of the pending sub-pool that match the
+    /// filter, until the stream lagged.
+    fn filtered_pending_transaction_stream(
+        &self,
+        filter: TransactionFilter,
+    ) -> impl Stream<Item = FilteredTransactionEvent<<Pool as TransactionPool>::Transaction>> {
+        self.pool.new_transactions_filtered(filter).filter(|event| {
+            futures::future::ready(match event {
+                FilteredTransactionEvent::Transaction(tx) => tx.subpool == SubPool::Pending,
+                FilteredTransactionEvent::Lagged { .. } => true,
+            })
+        })
+    }
 }
 
 impl<Provider, Pool, Events, Network> EthPubSubInner<Provider, Pool, Events, Network>
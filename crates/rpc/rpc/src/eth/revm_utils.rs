@@ -1,9 +1,13 @@
 //! utilities for working with revm
 
-use crate::eth::error::{EthApiError, EthResult, RpcInvalidTransactionError};
+use crate::eth::{
+    error::{EthApiError, EthResult, RpcInvalidTransactionError},
+    RPC_DEFAULT_MEMORY_LIMIT,
+};
 use reth_primitives::{
     revm::env::{fill_tx_env, fill_tx_env_with_recovered},
-    AccessList, Address, TransactionSigned, TransactionSignedEcRecovered, TxHash, B256, U256,
+    AccessList, Address, Bytes, TransactionSigned, TransactionSignedEcRecovered, TxHash, B256,
+    U256,
 };
 use reth_rpc_types::{
     state::{AccountOverride, StateOverride},
@@ -11,9 +15,10 @@ use reth_rpc_types::{
 };
 use revm::{
     db::{CacheDB, EmptyDB},
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult},
     precompile::{Precompiles, SpecId as PrecompilesSpecId},
     primitives::{BlockEnv, CfgEnv, Env, ResultAndState, SpecId, TransactTo, TxEnv},
-    Database, Inspector,
+    Database, EVMData, Inspector,
 };
 use revm_primitives::{
     db::{DatabaseCommit, DatabaseRef},
@@ -57,6 +62,70 @@ impl From<Option<StateOverride>> for EvmOverrides {
     }
 }
 
+/// Limits of the EVM that only apply to simulated calls, e.g. `eth_call` or `debug_traceCall`.
+///
+/// The execution of canonical blocks is only bounded by gas, these limits protect the node from
+/// calls that allocate enormous amounts of memory or nest calls deeply within the gas cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvmCallLimits {
+    /// The maximum memory in bytes that a call can allocate.
+    pub memory_limit: u64,
+    /// Overrides the maximum depth of nested calls of `eth_call`, `eth_callMany` and
+    /// `eth_estimateGas`, the protocol limit is 1024.
+    pub max_call_depth: Option<u64>,
+}
+
+impl EvmCallLimits {
+    /// Applies the memory limit to the config of the EVM.
+    pub(crate) fn apply(&self, cfg: &mut CfgEnv) {
+        cfg.memory_limit = self.memory_limit;
+    }
+}
+
+impl Default for EvmCallLimits {
+    fn default() -> Self {
+        Self { memory_limit: RPC_DEFAULT_MEMORY_LIMIT, max_call_depth: None }
+    }
+}
+
+/// An [Inspector] that fails every call or create above the configured depth, like the EVM does
+/// above the protocol limit.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CallDepthGuard {
+    max_call_depth: u64,
+}
+
+impl CallDepthGuard {
+    /// Creates a new guard with the given maximum depth.
+    pub(crate) fn new(max_call_depth: u64) -> Self {
+        Self { max_call_depth }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallDepthGuard {
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if data.journaled_state.depth() > self.max_call_depth {
+            return (InstructionResult::CallTooDeep, Gas::new(inputs.gas_limit), Bytes::new())
+        }
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if data.journaled_state.depth() > self.max_call_depth {
+            return (InstructionResult::CallTooDeep, None, Gas::new(inputs.gas_limit), Bytes::new())
+        }
+        (InstructionResult::Continue, None, Gas::new(0), Bytes::new())
+    }
+}
+
 /// Helper type to work with different transaction types when configuring the EVM env.
 ///
 /// This makes it easier to handle errors.
@@ -110,6 +179,23 @@ where
     Ok((res, evm.env))
 }
 
+/// Same as [transact], but fails nested calls above the maximum call depth of the
+/// [EvmCallLimits], if configured.
+pub(crate) fn transact_call<DB>(
+    db: DB,
+    env: Env,
+    limits: &EvmCallLimits,
+) -> EthResult<(ResultAndState, Env)>
+where
+    DB: Database,
+    <DB as Database>::Error: Into<EthApiError>,
+{
+    match limits.max_call_depth {
+        Some(max_call_depth) => inspect(db, env, CallDepthGuard::new(max_call_depth)),
+        None => transact(db, env),
+    }
+}
+
 /// Executes the [Env] against the given [Database] without committing state changes.
 pub(crate) fn inspect<DB, I>(db: DB, env: Env, inspector: I) -> EthResult<(ResultAndState, Env)>
 where
@@ -182,11 +268,15 @@ where
 /// Prepares the [Env] for execution.
 ///
 /// Does not commit any changes to the underlying database.
+///
+/// The call is simulated on top of the state of the block, so neither the pre-block system calls
+/// nor the post execution state changes of the block are applied, regardless of any overrides.
 pub(crate) fn prepare_call_env<DB>(
     mut cfg: CfgEnv,
     block: BlockEnv,
     request: CallRequest,
     gas_limit: u64,
+    limits: &EvmCallLimits,
     db: &mut CacheDB<DB>,
     overrides: EvmOverrides,
 ) -> EthResult<Env>
@@ -194,6 +284,8 @@ where
     DB: DatabaseRef,
     EthApiError: From<<DB as DatabaseRef>::Error>,
 {
+    limits.apply(&mut cfg);
+
     // we want to disable this in eth_call, since this is common practice used by other node
     // impls and providers <https://github.com/foundry-rs/foundry/issues/4388>
     cfg.disable_block_gas_limit = true;
@@ -246,6 +338,39 @@ where
     Ok(env)
 }
 
+/// Simulates the [CallRequest] on top of the given database like `eth_call` does, but executes it
+/// with the [Inspector] created by the `inspector_factory` for the prepared [Env].
+///
+/// The [Env] is prepared via [prepare_call_env], so the overrides, the gas cap and the memory limit
+/// of the [EvmCallLimits] apply as for `eth_call`. The `max_call_depth` of the limits is not
+/// enforced, because it is enforced by an inspector itself.
+///
+/// This allows other crates to run their own tracers on simulated calls, e.g. the validation rules
+/// of ERC-4337 bundlers. Returns the inspector after the execution. Does not commit any changes to
+/// the underlying database.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_with_inspector<DB, F, I>(
+    cfg: CfgEnv,
+    block: BlockEnv,
+    request: CallRequest,
+    gas_limit: u64,
+    limits: &EvmCallLimits,
+    mut db: CacheDB<DB>,
+    overrides: EvmOverrides,
+    inspector_factory: F,
+) -> EthResult<(ResultAndState, Env, I)>
+where
+    DB: DatabaseRef,
+    EthApiError: From<<DB as DatabaseRef>::Error>,
+    F: FnOnce(&Env) -> I,
+    I: Inspector<CacheDB<DB>>,
+{
+    let env = prepare_call_env(cfg, block, request, gas_limit, limits, &mut db, overrides)?;
+    let mut inspector = inspector_factory(&env);
+    let (res, env) = inspect(db, env, &mut inspector)?;
+    Ok((res, env, inspector))
+}
+
 /// Creates a new [Env] to be used for executing the [CallRequest] in `eth_call`.
 ///
 /// Note: this does _not_ access the Database to check the sender.
@@ -583,6 +708,16 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_primitives::{revm::env::fill_cfg_env, Header, MAINNET};
+    use reth_provider::test_utils::NoopProvider;
+    use reth_revm::{
+        banned_opcodes::{BannedOpcodeUse, BannedOpcodesInspector},
+        database::StateProviderDatabase,
+    };
+    use revm::{
+        interpreter::opcode,
+        primitives::{AccountInfo, ExecutionResult},
+    };
 
     #[test]
     fn test_ensure_0_fallback() {
@@ -613,4 +748,101 @@ mod tests {
         assert_eq!(gas_price, U256::ZERO);
         assert_eq!(max_fee_per_blob_gas, Some(U256::from(99)));
     }
+
+    #[test]
+    fn memory_limit_applies_to_calls_only() {
+        // PUSH1 1, PUSH3 0x200000, MSTORE, STOP: expands the memory to 2MiB
+        let code = Bytes::from_static(&[0x60, 0x01, 0x62, 0x20, 0x00, 0x00, 0x52, 0x00]);
+        let contract = Address::random();
+        let mut db = CacheDB::new(StateProviderDatabase::new(NoopProvider::default()));
+        db.insert_account_info(
+            contract,
+            AccountInfo { code: Some(Bytecode::new_raw(code)), ..Default::default() },
+        );
+
+        let mut cfg = CfgEnv::default();
+        fill_cfg_env(&mut cfg, &MAINNET, &Header::default(), U256::ZERO);
+        let gas_limit = 10_000_000;
+
+        // the memory of consensus execution is only bounded by gas
+        let env = Env {
+            cfg: cfg.clone(),
+            block: BlockEnv { gas_limit: U256::from(gas_limit), ..Default::default() },
+            tx: TxEnv {
+                transact_to: TransactTo::Call(contract),
+                gas_limit,
+                gas_price: U256::ZERO,
+                ..Default::default()
+            },
+        };
+        let (res, _) = transact(&mut db, env).unwrap();
+        assert!(
+            matches!(res.result, ExecutionResult::Success { gas_used, .. } if gas_used > 8_000_000)
+        );
+
+        let limits = EvmCallLimits { memory_limit: 1024 * 1024, max_call_depth: None };
+        let request = CallRequest {
+            to: Some(contract),
+            gas: Some(U256::from(gas_limit)),
+            ..Default::default()
+        };
+        let env = prepare_call_env(
+            cfg,
+            BlockEnv::default(),
+            request,
+            gas_limit,
+            &limits,
+            &mut db,
+            Default::default(),
+        )
+        .unwrap();
+        let (res, _) = transact_call(&mut db, env, &limits).unwrap();
+        assert!(matches!(
+            crate::eth::error::ensure_success(res.result),
+            Err(EthApiError::InvalidTransaction(RpcInvalidTransactionError::MemoryLimitExceeded))
+        ));
+    }
+
+    #[test]
+    fn simulation_flags_banned_opcodes() {
+        let contract = Address::random();
+        let mut cfg = CfgEnv::default();
+        fill_cfg_env(&mut cfg, &MAINNET, &Header::default(), U256::ZERO);
+
+        let simulate = |code: &'static [u8]| {
+            let mut db = CacheDB::new(StateProviderDatabase::new(NoopProvider::default()));
+            db.insert_account_info(
+                contract,
+                AccountInfo {
+                    code: Some(Bytecode::new_raw(Bytes::from_static(code))),
+                    ..Default::default()
+                },
+            );
+            let request = CallRequest { to: Some(contract), ..Default::default() };
+            let (res, _, inspector) = simulate_with_inspector(
+                cfg.clone(),
+                BlockEnv::default(),
+                request,
+                1_000_000,
+                &EvmCallLimits::default(),
+                db,
+                Default::default(),
+                |_| BannedOpcodesInspector::erc4337(),
+            )
+            .unwrap();
+            assert!(res.result.is_success());
+            inspector.into_uses()
+        };
+
+        // TIMESTAMP, POP, STOP
+        let uses = simulate(&[opcode::TIMESTAMP, opcode::POP, opcode::STOP]);
+        assert_eq!(
+            uses,
+            vec![BannedOpcodeUse { address: contract, pc: 0, opcode: opcode::TIMESTAMP, depth: 1 }]
+        );
+
+        // PUSH1 1, POP, STOP
+        let uses = simulate(&[opcode::PUSH1, 0x01, opcode::POP, opcode::STOP]);
+        assert!(uses.is_empty());
+    }
 }
@@ -2,19 +2,28 @@
 
 use crate::eth::error::SignError;
 use alloy_dyn_abi::TypedData;
+use ethers_signers::{coins_bip39::English, MnemonicBuilder};
 use reth_primitives::{
-    eip191_hash_message, sign_message, Address, Signature, TransactionSigned, B256,
+    eip191_hash_message, public_key_to_address, sign_message, Address, Signature,
+    TransactionSigned, B256,
 };
 use reth_rpc_types::TypedTransactionRequest;
 
-use secp256k1::SecretKey;
+use secp256k1::{PublicKey, SecretKey, SECP256K1};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 type Result<T> = std::result::Result<T, SignError>;
 
+/// The mnemonic of the accounts that are prefunded in dev mode.
+pub const DEV_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+/// The default number of accounts derived from a [DevSignerConfig::mnemonic].
+pub const DEFAULT_DEV_ACCOUNTS: u32 = 20;
+
 /// An Ethereum Signer used via RPC.
 #[async_trait::async_trait]
-pub(crate) trait EthSigner: Send + Sync {
+pub trait EthSigner: Send + Sync {
     /// Returns the available accounts for this signer.
     fn accounts(&self) -> Vec<Address>;
 
@@ -37,13 +46,86 @@ pub(crate) trait EthSigner: Send + Sync {
     fn sign_typed_data(&self, address: Address, payload: &TypedData) -> Result<Signature>;
 }
 
+/// The keys of a [DevSigner].
+///
+/// This is empty by default, so nodes have no accounts to sign with unless keys are configured
+/// explicitly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevSignerConfig {
+    /// The mnemonic to derive accounts from, with the default derivation path
+    /// `m/44'/60'/0'/0/{index}`.
+    pub mnemonic: Option<String>,
+    /// The number of accounts derived from the mnemonic.
+    pub mnemonic_accounts: u32,
+    /// Additional private keys.
+    pub private_keys: Vec<B256>,
+}
+
+impl Default for DevSignerConfig {
+    fn default() -> Self {
+        Self { mnemonic: None, mnemonic_accounts: DEFAULT_DEV_ACCOUNTS, private_keys: Vec::new() }
+    }
+}
+
+impl DevSignerConfig {
+    /// Returns true if no keys are configured.
+    pub fn is_empty(&self) -> bool {
+        (self.mnemonic.is_none() || self.mnemonic_accounts == 0) && self.private_keys.is_empty()
+    }
+}
+
 /// Holds developer keys
-pub(crate) struct DevSigner {
+pub struct DevSigner {
     addresses: Vec<Address>,
     accounts: HashMap<Address, SecretKey>,
 }
 
+impl std::fmt::Debug for DevSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // don't leak the keys
+        f.debug_struct("DevSigner").field("addresses", &self.addresses).finish_non_exhaustive()
+    }
+}
+
 impl DevSigner {
+    /// Creates a signer for the given private keys.
+    pub fn from_private_keys(keys: &[B256]) -> Result<Self> {
+        Ok(Self::from_secret_keys(private_keys(keys)?))
+    }
+
+    /// Creates a signer for the first `count` accounts derived from the given mnemonic.
+    pub fn from_mnemonic(mnemonic: &str, count: u32) -> Result<Self> {
+        Ok(Self::from_secret_keys(mnemonic_keys(mnemonic, count)?))
+    }
+
+    /// Creates a signer for all keys of the given config, with the accounts derived from the
+    /// mnemonic first.
+    ///
+    /// Returns `None` if the config has no keys.
+    pub fn from_config(config: &DevSignerConfig) -> Result<Option<Self>> {
+        if config.is_empty() {
+            return Ok(None)
+        }
+        let mut keys = match &config.mnemonic {
+            Some(mnemonic) => mnemonic_keys(mnemonic, config.mnemonic_accounts)?,
+            None => Vec::new(),
+        };
+        keys.extend(private_keys(&config.private_keys)?);
+        Ok(Some(Self::from_secret_keys(keys)))
+    }
+
+    fn from_secret_keys(keys: Vec<SecretKey>) -> Self {
+        let mut addresses = Vec::with_capacity(keys.len());
+        let mut accounts = HashMap::with_capacity(keys.len());
+        for key in keys {
+            let address = public_key_to_address(PublicKey::from_secret_key(SECP256K1, &key));
+            if accounts.insert(address, key).is_none() {
+                addresses.push(address);
+            }
+        }
+        Self { addresses, accounts }
+    }
+
     fn get_key(&self, account: Address) -> Result<&SecretKey> {
         self.accounts.get(&account).ok_or(SignError::NoAccount)
     }
@@ -55,6 +137,28 @@ impl DevSigner {
     }
 }
 
+/// Parses the given private keys.
+fn private_keys(keys: &[B256]) -> Result<Vec<SecretKey>> {
+    keys.iter()
+        .map(|key| SecretKey::from_slice(key.as_slice()).map_err(|_| SignError::InvalidKey))
+        .collect()
+}
+
+/// Derives the keys of the first `count` accounts from the given mnemonic.
+fn mnemonic_keys(mnemonic: &str, count: u32) -> Result<Vec<SecretKey>> {
+    (0..count)
+        .map(|index| {
+            let wallet = MnemonicBuilder::<English>::default()
+                .phrase(mnemonic)
+                .index(index)
+                .and_then(|builder| builder.build())
+                .map_err(|_| SignError::InvalidMnemonic)?;
+            SecretKey::from_slice(&wallet.signer().to_bytes())
+                .map_err(|_| SignError::InvalidMnemonic)
+        })
+        .collect()
+}
+
 #[async_trait::async_trait]
 impl EthSigner for DevSigner {
     fn accounts(&self) -> Vec<Address> {
@@ -213,4 +317,118 @@ mod test {
         };
         assert_eq!(sig, expected)
     }
+
+    #[test]
+    fn dev_signer_from_mnemonic_and_keys() {
+        let first = Address::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap();
+        let second = Address::from_str("0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap();
+        let second_key =
+            B256::from_str("0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d")
+                .unwrap();
+
+        let signer = DevSigner::from_mnemonic(DEV_MNEMONIC, 2).unwrap();
+        assert_eq!(signer.accounts(), vec![first, second]);
+
+        let signer = DevSigner::from_private_keys(&[second_key]).unwrap();
+        assert_eq!(signer.accounts(), vec![second]);
+
+        // the mnemonic accounts come first, and keys of derived accounts are not duplicated
+        let config = DevSignerConfig {
+            mnemonic: Some(DEV_MNEMONIC.to_string()),
+            mnemonic_accounts: 1,
+            private_keys: vec![second_key, second_key],
+        };
+        let signer = DevSigner::from_config(&config).unwrap().unwrap();
+        assert_eq!(signer.accounts(), vec![first, second]);
+
+        assert!(DevSigner::from_config(&DevSignerConfig::default()).unwrap().is_none());
+        assert!(matches!(
+            DevSigner::from_mnemonic("not a mnemonic", 1),
+            Err(SignError::InvalidMnemonic)
+        ));
+        assert!(matches!(DevSigner::from_private_keys(&[B256::ZERO]), Err(SignError::InvalidKey)));
+    }
+
+    #[test]
+    fn sign_typed_data_with_nested_arrays() {
+        // the `signTypedData_v4` example with arrays of structs and addresses
+        let data = serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallets", "type": "address[]" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person[]" },
+                    { "name": "contents", "type": "string" }
+                ],
+                "Group": [
+                    { "name": "name", "type": "string" },
+                    { "name": "members", "type": "Person[]" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {
+                    "name": "Cow",
+                    "wallets": [
+                        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+                        "0xDeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF"
+                    ]
+                },
+                "to": [{
+                    "name": "Bob",
+                    "wallets": [
+                        "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+                        "0xB0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57",
+                        "0xB0B0b0b0b0b0B000000000000000000000000000"
+                    ]
+                }],
+                "contents": "Hello, Bob!"
+            }
+        });
+        let data: TypedData = serde_json::from_value(data).unwrap();
+        assert_eq!(
+            data.eip712_signing_hash().unwrap(),
+            B256::from_str("0xa85c2e2b118698e88db68a8105b794a8cc7cec074e89ef991cb4f5f533819cc2")
+                .unwrap()
+        );
+
+        // keccak256("cow")
+        let key =
+            B256::from_str("0xc85ef7d79691fe79573b1a7064c19c1a9819ebdbd1faaab1a8ec92344438aaf4")
+                .unwrap();
+        let signer = DevSigner::from_private_keys(&[key]).unwrap();
+        let cow = Address::from_str("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826").unwrap();
+        assert_eq!(signer.accounts(), vec![cow]);
+
+        let sig = signer.sign_typed_data(cow, &data).unwrap();
+        let expected = Signature {
+            r: U256::from_str_radix(
+                "65cbd956f2fae28a601bebc9b906cea0191744bd4c4247bcd27cd08f8eb6b71c",
+                16,
+            )
+            .unwrap(),
+            s: U256::from_str_radix(
+                "78efdf7a31dc9abee78f492292721f362d296cf86b4538e07b51303b67f74906",
+                16,
+            )
+            .unwrap(),
+            odd_y_parity: false,
+        };
+        assert_eq!(sig, expected);
+    }
 }
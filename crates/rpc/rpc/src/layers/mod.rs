@@ -3,9 +3,13 @@ use http::{HeaderMap, Response};
 mod auth_layer;
 mod jwt_secret;
 mod jwt_validator;
+mod trace_stream;
 pub use auth_layer::AuthLayer;
 pub use jwt_secret::{Claims, JwtError, JwtSecret};
 pub use jwt_validator::JwtAuthValidator;
+pub use trace_stream::{
+    BlockTraceStreamer, TraceResultStream, TraceStreamLayer, TraceStreamService,
+};
 
 /// General purpose trait to validate Http Authorization headers. It's supposed to be integrated as
 /// a validator trait into an [`AuthLayer`].
@@ -0,0 +1,256 @@
+use crate::eth::error::{EthApiError, EthResult};
+use bytes::Bytes;
+use futures::{
+    future::BoxFuture,
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use http::{header, HeaderValue, Method, Request, Response};
+use hyper::Body;
+use jsonrpsee::types::ErrorObject;
+use reth_primitives::{BlockId, BlockNumberOrTag, B256};
+use reth_rpc_types::trace::geth::{GethDebugTracingOptions, TraceResult};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// A stream of the traces of all transactions of a block, in block order.
+pub type TraceResultStream = BoxStream<'static, EthResult<TraceResult>>;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Marker of the methods that are served by the [`TraceStreamLayer`].
+const TRACE_BLOCK_METHOD_PREFIX: &[u8] = b"debug_traceBlockBy";
+
+/// A type that traces the transactions of a block one at a time.
+pub trait BlockTraceStreamer: Send + Sync + 'static {
+    /// Returns a stream that yields the trace of each transaction of the block as soon as it is
+    /// available.
+    fn trace_block_stream(
+        &self,
+        block_id: BlockId,
+        opts: GethDebugTracingOptions,
+    ) -> BoxFuture<'static, EthResult<TraceResultStream>>;
+}
+
+/// This is an Http middleware layer that serves `debug_traceBlockByNumber` and
+/// `debug_traceBlockByHash` requests with a streamed response body.
+///
+/// The server serializes the entire response before writing it, which for full block traces can
+/// be hundreds of MB. Instead, this writes the JSON-RPC response envelope first and then flushes
+/// the trace of each transaction into the body as soon as it is available, so that only a few
+/// traces are held in memory at any time.
+///
+/// Only single Http requests are intercepted, batch requests and websocket connections are
+/// dispatched to the next layer, so websocket responses remain subject to the max response size
+/// of the server.
+///
+/// Errors that occur before the first trace is available, like an unknown block, are returned as
+/// regular JSON-RPC error responses. If tracing fails after that, the response body is aborted.
+#[derive(Clone, Default)]
+#[allow(missing_debug_implementations)]
+pub struct TraceStreamLayer {
+    streamer: Option<Arc<dyn BlockTraceStreamer>>,
+}
+
+impl TraceStreamLayer {
+    /// Creates an instance of [`TraceStreamLayer`] that streams the traces produced by the given
+    /// [`BlockTraceStreamer`].
+    pub fn new(streamer: Arc<dyn BlockTraceStreamer>) -> Self {
+        Self { streamer: Some(streamer) }
+    }
+
+    /// Creates an instance of [`TraceStreamLayer`] that dispatches all requests to the next layer.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+}
+
+impl<S> Layer<S> for TraceStreamLayer {
+    type Service = TraceStreamService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceStreamService { streamer: self.streamer.clone(), inner }
+    }
+}
+
+/// The [`Service`] of the [`TraceStreamLayer`].
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct TraceStreamService<S> {
+    /// Produces the streamed traces, if enabled
+    streamer: Option<Arc<dyn BlockTraceStreamer>>,
+    /// Recipient of all requests that are not streamed
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for TraceStreamService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: From<hyper::Error> + Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let Some(streamer) = self.streamer.clone().filter(|_| req.method() == Method::POST) else {
+            return Box::pin(self.inner.call(req))
+        };
+
+        // the service that was polled ready must handle the request
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            if let Some(request) = TraceBlockRequest::parse(&body) {
+                return Ok(request.stream_response(streamer.as_ref()).await)
+            }
+            inner.call(Request::from_parts(parts, Body::from(body))).await
+        })
+    }
+}
+
+/// A `debug_traceBlockByNumber` or `debug_traceBlockByHash` request.
+#[derive(Debug)]
+struct TraceBlockRequest {
+    id: Value,
+    block_id: BlockId,
+    opts: GethDebugTracingOptions,
+}
+
+impl TraceBlockRequest {
+    /// Parses the request from the Http body.
+    ///
+    /// Returns `None` if this is not a well formed trace block request, those are left to the
+    /// server to handle.
+    fn parse(body: &[u8]) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct RawRequest {
+            jsonrpc: String,
+            #[serde(default)]
+            id: Value,
+            method: String,
+            #[serde(default)]
+            params: Vec<Value>,
+        }
+
+        if !body.windows(TRACE_BLOCK_METHOD_PREFIX.len()).any(|w| w == TRACE_BLOCK_METHOD_PREFIX) {
+            return None
+        }
+
+        let RawRequest { jsonrpc, id, method, params } = serde_json::from_slice(body).ok()?;
+        if jsonrpc != "2.0" {
+            return None
+        }
+
+        let mut params = params.into_iter();
+        let block = params.next()?;
+        let block_id = match method.as_str() {
+            "debug_traceBlockByNumber" => {
+                serde_json::from_value::<BlockNumberOrTag>(block).ok()?.into()
+            }
+            "debug_traceBlockByHash" => serde_json::from_value::<B256>(block).ok()?.into(),
+            _ => return None,
+        };
+        let opts = match params.next() {
+            Some(opts) => serde_json::from_value::<Option<GethDebugTracingOptions>>(opts).ok()?,
+            None => None,
+        };
+        if params.next().is_some() {
+            return None
+        }
+
+        Some(Self { id, block_id, opts: opts.unwrap_or_default() })
+    }
+
+    /// Traces the block and returns a response whose body yields the traces as they are produced.
+    async fn stream_response(self, streamer: &dyn BlockTraceStreamer) -> Response<Body> {
+        let Self { id, block_id, opts } = self;
+
+        let mut traces = match streamer.trace_block_stream(block_id, opts).await {
+            Ok(traces) => traces,
+            Err(err) => return error_response(id, err),
+        };
+        let first = match traces.next().await {
+            Some(Err(err)) => return error_response(id, err),
+            first => first,
+        };
+
+        let head = Bytes::from(format!(r#"{{"jsonrpc":"2.0","id":{id},"result":["#));
+        let results = stream::iter(first).chain(traces).enumerate().map(
+            |(idx, trace)| -> Result<_, BoxError> {
+                let trace = trace.map_err(|err| err.to_string())?;
+                let mut buf = if idx == 0 { Vec::new() } else { vec![b','] };
+                serde_json::to_writer(&mut buf, &trace)?;
+                Ok(Bytes::from(buf))
+            },
+        );
+        let body = stream::once(async move { Ok(head) })
+            .chain(results)
+            .chain(stream::once(async { Ok(Bytes::from_static(b"]}")) }));
+
+        json_response(Body::wrap_stream(body))
+    }
+}
+
+/// Returns the JSON-RPC error response for the request with the given id.
+fn error_response(id: Value, err: EthApiError) -> Response<Body> {
+    let error: ErrorObject<'static> = err.into();
+    let response = serde_json::json!({ "jsonrpc": "2.0", "id": id, "error": error });
+    json_response(Body::from(response.to_string()))
+}
+
+fn json_response(body: Body) -> Response<Body> {
+    let mut response = Response::new(body);
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trace_block_requests() {
+        let req = TraceBlockRequest::parse(
+            br#"{"jsonrpc":"2.0","id":1,"method":"debug_traceBlockByNumber","params":["0x1b4"]}"#,
+        )
+        .unwrap();
+        assert_eq!(req.id, Value::from(1));
+        assert_eq!(req.block_id, BlockId::Number(BlockNumberOrTag::Number(0x1b4)));
+
+        let req = TraceBlockRequest::parse(
+            br#"{"jsonrpc":"2.0","id":"a","method":"debug_traceBlockByHash","params":["0x0000000000000000000000000000000000000000000000000000000000000001",{"tracer":"callTracer"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(req.block_id, BlockId::from(B256::with_last_byte(1)));
+        assert!(req.opts.tracer.is_some());
+
+        // left to the server
+        assert!(TraceBlockRequest::parse(
+            br#"{"jsonrpc":"2.0","id":1,"method":"debug_traceTransaction","params":["0x1b4"]}"#
+        )
+        .is_none());
+        assert!(TraceBlockRequest::parse(
+            br#"[{"jsonrpc":"2.0","id":1,"method":"debug_traceBlockByNumber","params":["0x1b4"]}]"#
+        )
+        .is_none());
+        assert!(TraceBlockRequest::parse(
+            br#"{"jsonrpc":"2.0","id":1,"method":"debug_traceBlockByNumber","params":[]}"#
+        )
+        .is_none());
+    }
+}
@@ -43,7 +43,10 @@ pub use blocking_pool::{BlockingTaskGuard, BlockingTaskPool};
 pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
-pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
+pub use layers::{
+    AuthLayer, AuthValidator, BlockTraceStreamer, Claims, JwtAuthValidator, JwtError, JwtSecret,
+    TraceResultStream, TraceStreamLayer, TraceStreamService,
+};
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
@@ -53,3 +56,4 @@ pub use txpool::TxPoolApi;
 pub use web3::Web3Api;
 pub mod blocking_pool;
 pub mod result;
+pub mod trace_cache;
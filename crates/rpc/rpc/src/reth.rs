@@ -2,9 +2,12 @@ use crate::eth::error::{EthApiError, EthResult};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_interfaces::RethResult;
-use reth_primitives::{Address, BlockId, U256};
+use reth_primitives::{
+    proofs::receipt_inclusion_proof, Address, BlockId, Receipt, TxHash, U256, U64,
+};
 use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
 use reth_rpc_api::RethApiServer;
+use reth_rpc_types::ReceiptProof;
 use reth_tasks::TaskSpawner;
 use std::{collections::HashMap, future::Future, sync::Arc};
 use tokio::sync::oneshot;
@@ -81,6 +84,35 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the proof that the receipt of the transaction is included in its block.
+    pub async fn receipt_proof(&self, tx_hash: TxHash) -> EthResult<Option<ReceiptProof>> {
+        self.on_blocking_task(|this| async move { this.try_receipt_proof(tx_hash) }).await
+    }
+
+    fn try_receipt_proof(&self, tx_hash: TxHash) -> EthResult<Option<ReceiptProof>> {
+        let Some((_, meta)) = self.provider().transaction_by_hash_with_meta(tx_hash)? else {
+            return Ok(None)
+        };
+        let Some(header) = self.provider().sealed_header(meta.block_number)? else {
+            return Err(EthApiError::UnknownBlockNumber)
+        };
+        let Some(receipts) = self.provider().receipts_by_block(meta.block_hash.into())? else {
+            return Err(EthApiError::UnknownBlockNumber)
+        };
+
+        let receipts = receipts.into_iter().map(Receipt::with_bloom).collect::<Vec<_>>();
+        let proof = receipt_inclusion_proof(&receipts, meta.index as usize);
+        Ok(Some(ReceiptProof {
+            transaction_hash: tx_hash,
+            transaction_index: U64::from(meta.index),
+            block_hash: header.hash,
+            block_number: U64::from(header.number),
+            receipts_root: header.receipts_root,
+            header: alloy_rlp::encode(header.header).into(),
+            proof,
+        }))
+    }
 }
 
 #[async_trait]
@@ -95,6 +127,11 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(RethApi::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_getReceiptProof`
+    async fn reth_get_receipt_proof(&self, tx_hash: TxHash) -> RpcResult<Option<ReceiptProof>> {
+        Ok(RethApi::receipt_proof(self, tx_hash).await?)
+    }
 }
 
 impl<Provider> std::fmt::Debug for RethApi<Provider> {
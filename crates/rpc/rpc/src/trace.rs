@@ -9,14 +9,15 @@ use crate::{
 };
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult as Result;
-use reth_consensus_common::calc::{base_block_reward, block_reward};
 use reth_primitives::{
-    revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, SealedHeader, B256, U256,
+    revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, ChainSpec, SealedBlock,
+    SealedHeader, B256, U256,
 };
 use reth_provider::{BlockReader, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
 use reth_revm::{
     database::StateProviderDatabase,
-    tracing::{parity::populate_state_diff, TracingInspector, TracingInspectorConfig},
+    state_change::{burned_base_fee, BlockRewardContext, IncrementSource},
+    tracing::{TracingInspector, TracingInspectorConfig},
 };
 use reth_rpc_api::TraceApiServer;
 use reth_rpc_types::{
@@ -129,6 +130,7 @@ where
         let (cfg, block_env, at) = self.inner.eth_api.evm_env_at(at).await?;
 
         let gas_limit = self.inner.eth_api.call_gas_limit();
+        let call_limits = self.inner.eth_api.call_limits();
         // execute all transactions on top of each other and record the traces
         self.inner
             .eth_api
@@ -144,6 +146,7 @@ where
                         block_env.clone(),
                         call,
                         gas_limit,
+                        &call_limits,
                         &mut db,
                         Default::default(),
                     )?;
@@ -357,35 +360,11 @@ where
 
         if let (Some(block), Some(traces)) = (maybe_block, maybe_traces.as_mut()) {
             if let Some(header_td) = self.provider().header_td(&block.header.hash)? {
-                if let Some(base_block_reward) = base_block_reward(
+                traces.extend(reward_traces(
                     self.provider().chain_spec().as_ref(),
-                    block.header.number,
-                    block.header.difficulty,
+                    &block,
                     header_td,
-                ) {
-                    traces.push(reward_trace(
-                        &block.header,
-                        RewardAction {
-                            author: block.header.beneficiary,
-                            reward_type: RewardType::Block,
-                            value: U256::from(base_block_reward),
-                        },
-                    ));
-
-                    if !block.ommers.is_empty() {
-                        traces.push(reward_trace(
-                            &block.header,
-                            RewardAction {
-                                author: block.header.beneficiary,
-                                reward_type: RewardType::Uncle,
-                                value: U256::from(
-                                    block_reward(base_block_reward, block.ommers.len()) -
-                                        base_block_reward,
-                                ),
-                            },
-                        ));
-                    }
-                }
+                ));
             }
         }
 
@@ -404,14 +383,15 @@ where
                 block_id,
                 tracing_config(&trace_types),
                 move |tx_info, inspector, res, state, db| {
-                    let mut full_trace =
-                        inspector.into_parity_builder().into_trace_results(&res, &trace_types);
-
-                    // If statediffs were requested, populate them with the account balance and
-                    // nonce from pre-state
-                    if let Some(ref mut state_diff) = full_trace.state_diff {
-                        populate_state_diff(state_diff, db, state.iter())?;
-                    }
+                    // the state diff and the bytecodes of the vm trace are populated from the
+                    // pre-state of the transaction
+                    let full_trace =
+                        inspector.into_parity_builder().into_trace_results_with_account_diffs(
+                            &res,
+                            state.iter(),
+                            &trace_types,
+                            db,
+                        )?;
 
                     let trace = TraceResultsWithTransactionHash {
                         transaction_hash: tx_info.hash.expect("tx hash is set"),
@@ -567,9 +547,64 @@ fn reward_trace(header: &SealedHeader, reward: RewardAction) -> LocalizedTransac
     }
 }
 
+/// Returns the [RewardType] of a balance increment at the end of a block.
+fn reward_type(source: IncrementSource) -> RewardType {
+    match source {
+        IncrementSource::BlockReward => RewardType::Block,
+        IncrementSource::OmmerReward => RewardType::Uncle,
+        IncrementSource::Withdrawal => RewardType::Withdrawal,
+    }
+}
+
+/// Returns a reward trace for every balance increment at the end of the block, i.e. the block
+/// reward, the reward of every ommer and every withdrawal.
+///
+/// The block rewards come first, followed by the ommer rewards and the withdrawals in the order of
+/// the block. The genesis block has no rewards, and post-merge blocks only have withdrawals.
+fn reward_traces(
+    chain_spec: &ChainSpec,
+    block: &SealedBlock,
+    total_difficulty: U256,
+) -> Vec<LocalizedTransactionTrace> {
+    if block.number == 0 {
+        return Vec::new()
+    }
+
+    let ctx = BlockRewardContext {
+        block_number: block.number,
+        block_difficulty: block.difficulty,
+        beneficiary: block.beneficiary,
+        block_timestamp: block.timestamp,
+        total_difficulty,
+        ommers: &block.ommers,
+        withdrawals: block.withdrawals.as_deref(),
+        reward_override: None,
+        burned_base_fee: burned_base_fee(&block.header),
+    };
+    let mut rewards = Vec::new();
+    ctx.balance_increments_with_callback(chain_spec, |author, value, source| {
+        rewards.push(RewardAction {
+            author,
+            value: U256::from(value),
+            reward_type: reward_type(source),
+        })
+    });
+    // the increments of the ommers are added before the block reward
+    rewards.sort_by_key(|reward| match reward.reward_type {
+        RewardType::Block => 0,
+        RewardType::Uncle => 1,
+        RewardType::Withdrawal => 2,
+    });
+    rewards.into_iter().map(|reward| reward_trace(&block.header, reward)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_primitives::{
+        constants::{ETH_TO_WEI, GWEI_TO_WEI},
+        Address, Block, ChainSpecBuilder, ForkCondition, Hardfork, Header, Withdrawal, MAINNET,
+    };
 
     #[test]
     fn test_parity_config() {
@@ -594,4 +629,99 @@ mod tests {
         // not required for StateDiff
         assert!(!config.record_state_diff);
     }
+
+    #[test]
+    fn reward_traces_of_block() {
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET)
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .build();
+        let beneficiary = Address::with_last_byte(1);
+        let ommer_beneficiary = Address::with_last_byte(2);
+        let recipient = Address::with_last_byte(3);
+        let block = Block {
+            header: Header { number: 10, beneficiary, ..Default::default() },
+            body: vec![],
+            ommers: vec![Header {
+                number: 9,
+                beneficiary: ommer_beneficiary,
+                ..Default::default()
+            }],
+            withdrawals: Some(vec![Withdrawal {
+                index: 0,
+                validator_index: 0,
+                address: recipient,
+                amount: 1,
+            }]),
+        }
+        .seal_slow();
+
+        let traces = reward_traces(&chain_spec, &block, U256::ZERO);
+        assert!(traces
+            .iter()
+            .all(|trace| trace.block_hash == Some(block.hash) && trace.transaction_hash.is_none()));
+        let actions = traces.into_iter().map(|trace| trace.trace.action).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![
+                // the block reward includes the ommer inclusion reward
+                Action::Reward(RewardAction {
+                    author: beneficiary,
+                    value: U256::from(ETH_TO_WEI * 5 + ETH_TO_WEI * 5 / 32),
+                    reward_type: RewardType::Block,
+                }),
+                Action::Reward(RewardAction {
+                    author: ommer_beneficiary,
+                    value: U256::from(ETH_TO_WEI * 5 * 7 / 8),
+                    reward_type: RewardType::Uncle,
+                }),
+                Action::Reward(RewardAction {
+                    author: recipient,
+                    value: U256::from(GWEI_TO_WEI),
+                    reward_type: RewardType::Withdrawal,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn reward_traces_match_fixture() {
+        const REWARDS: &str = include_str!("../test_data/trace/byzantium_two_uncles_rewards.json");
+        let expected: Vec<LocalizedTransactionTrace> = serde_json::from_str(REWARDS).unwrap();
+
+        let ommer = |number, beneficiary: &str| Header {
+            number,
+            beneficiary: beneficiary.parse().unwrap(),
+            ..Default::default()
+        };
+        let block = Block {
+            header: Header {
+                number: 4_370_001,
+                beneficiary: "0xea674fdde714fd979de3edf0f56aa9716b898ec8".parse().unwrap(),
+                difficulty: U256::from(1_000_000),
+                ..Default::default()
+            },
+            body: vec![],
+            ommers: vec![
+                ommer(4_370_000, "0x52bc44d5378309ee2abf1539bf71de1b7d7be3b5"),
+                ommer(4_369_999, "0x829bd824b016326a401d083b33d092293333a830"),
+            ],
+            withdrawals: None,
+        }
+        .seal_slow();
+
+        assert_eq!(reward_traces(&MAINNET, &block, U256::ZERO), expected);
+
+        // no rewards once the total difficulty is reached
+        assert_eq!(reward_traces(&MAINNET, &block, U256::MAX), vec![]);
+    }
+
+    #[test]
+    fn no_reward_traces_for_genesis() {
+        let genesis = Block {
+            header: Header { beneficiary: Address::with_last_byte(1), ..Default::default() },
+            ..Default::default()
+        }
+        .seal_slow();
+        assert_eq!(reward_traces(&MAINNET, &genesis, U256::ZERO), vec![]);
+    }
 }
@@ -0,0 +1,325 @@
+//! Cache for the geth traces of recently traced blocks.
+
+use crate::eth::error::EthResult;
+use futures::{Future, Stream, StreamExt};
+use metrics::Counter;
+use reth_metrics::{metrics::Gauge, Metrics};
+use reth_primitives::{keccak256, B256};
+use reth_provider::CanonStateNotification;
+use reth_rpc_types::trace::geth::{GethDebugTracingOptions, TraceResult};
+use schnellru::{ByLength, LruMap};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{fmt, sync::Arc};
+use tokio::sync::Mutex;
+
+/// Default maximum number of cached block traces.
+pub const DEFAULT_TRACE_CACHE_MAX_ENTRIES: u32 = 128;
+
+/// Default maximum serialized size of the traces of a single block: 16MB
+pub const DEFAULT_TRACE_CACHE_MAX_ENTRY_SIZE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Settings for the [TraceCache].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCacheConfig {
+    /// Whether block traces are cached.
+    ///
+    /// Default is true.
+    pub enabled: bool,
+    /// Max number of cached block traces.
+    ///
+    /// Default is 128.
+    pub max_entries: u32,
+    /// Max serialized size in bytes of the traces of a block, larger traces are not cached.
+    ///
+    /// Default is 16MB.
+    pub max_entry_size: usize,
+}
+
+impl Default for TraceCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: DEFAULT_TRACE_CACHE_MAX_ENTRIES,
+            max_entry_size: DEFAULT_TRACE_CACHE_MAX_ENTRY_SIZE_BYTES,
+        }
+    }
+}
+
+/// The key of the traces of a block in the [TraceCache].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceCacheKey {
+    /// The hash of the traced block.
+    pub block_hash: B256,
+    /// The serialized tracer, empty for the default struct logger.
+    pub tracer: String,
+    /// The hash of the canonicalized tracing options.
+    pub config_hash: B256,
+}
+
+impl TraceCacheKey {
+    /// Creates the key of the traces of the block with the given tracing options.
+    ///
+    /// The options are canonicalized before they are hashed, so tracer configs that only differ in
+    /// the order of their fields share the same key.
+    pub fn new(block_hash: B256, opts: &GethDebugTracingOptions) -> Self {
+        let tracer = opts
+            .tracer
+            .as_ref()
+            .map(|tracer| serde_json::to_string(tracer).unwrap_or_default())
+            .unwrap_or_default();
+        let config = serde_json::to_value(opts).map(canonicalize).unwrap_or_default();
+        let config_hash = keccak256(config.to_string());
+        Self { block_hash, tracer, config_hash }
+    }
+}
+
+/// Returns the value with the fields of all objects sorted by their name.
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut fields = object.into_iter().collect::<Vec<_>>();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                fields.into_iter().map(|(name, value)| (name, canonicalize(value))).collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        value => value,
+    }
+}
+
+/// A bounded LRU cache for the geth traces of blocks, e.g. of `debug_traceBlockByNumber`.
+///
+/// Explorers tend to request the same traces of recent blocks repeatedly, which are served from
+/// the cache without executing the block again. The traces of blocks that are reorged out are
+/// removed from the cache, see [invalidate_reorged_traces_task].
+///
+/// The traces are kept deserialized, because [GethTrace](reth_rpc_types::trace::geth::GethTrace)
+/// deserializes untagged and can't be restored reliably from its JSON. Their serialized size is
+/// only used to skip caching giant traces.
+#[derive(Debug, Clone)]
+pub struct TraceCache {
+    inner: Arc<TraceCacheInner>,
+}
+
+struct TraceCacheInner {
+    config: TraceCacheConfig,
+    traces: Mutex<LruMap<TraceCacheKey, Arc<Vec<TraceResult>>, ByLength>>,
+    metrics: TraceCacheMetrics,
+}
+
+impl fmt::Debug for TraceCacheInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceCacheInner").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl TraceCache {
+    /// Creates a new cache with the given config.
+    pub fn new(config: TraceCacheConfig) -> Self {
+        let traces = Mutex::new(LruMap::new(ByLength::new(config.max_entries)));
+        Self { inner: Arc::new(TraceCacheInner { config, traces, metrics: Default::default() }) }
+    }
+
+    /// Creates a cache that never caches any traces.
+    pub fn disabled() -> Self {
+        Self::new(TraceCacheConfig { enabled: false, ..Default::default() })
+    }
+
+    /// Returns true if traces are cached.
+    pub fn is_enabled(&self) -> bool {
+        self.inner.config.enabled
+    }
+
+    /// Returns the cached traces for the key, or traces the block with the given function and
+    /// caches the result.
+    ///
+    /// On a cache hit, the function is not invoked at all. Errors are never cached.
+    pub async fn get_or_trace<F, Fut>(
+        &self,
+        key: TraceCacheKey,
+        trace: F,
+    ) -> EthResult<Vec<TraceResult>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = EthResult<Vec<TraceResult>>>,
+    {
+        if !self.is_enabled() {
+            return trace().await
+        }
+
+        if let Some(traces) = self.inner.traces.lock().await.get(&key) {
+            self.inner.metrics.hits.increment(1);
+            return Ok(traces.as_ref().clone())
+        }
+        self.inner.metrics.misses.increment(1);
+
+        let traces = trace().await?;
+
+        let size = serde_json::to_vec(&traces).map(|traces| traces.len()).unwrap_or(usize::MAX);
+        if size > self.inner.config.max_entry_size {
+            self.inner.metrics.oversized.increment(1);
+            return Ok(traces)
+        }
+
+        let mut cache = self.inner.traces.lock().await;
+        cache.insert(key, Arc::new(traces.clone()));
+        self.inner.metrics.cached_count.set(cache.len() as f64);
+        Ok(traces)
+    }
+
+    /// Removes all cached traces of the block.
+    pub async fn invalidate_block(&self, block_hash: B256) {
+        let mut cache = self.inner.traces.lock().await;
+        let keys = cache
+            .iter()
+            .filter(|(key, _)| key.block_hash == block_hash)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in keys {
+            cache.remove(&key);
+        }
+        self.inner.metrics.cached_count.set(cache.len() as f64);
+    }
+
+    /// Removes all cached traces of the blocks that the notification reverted.
+    pub async fn on_canon_state_notification(&self, notification: &CanonStateNotification) {
+        if let Some(reverted) = notification.reverted() {
+            for block in reverted.blocks().values() {
+                self.invalidate_block(block.hash()).await;
+            }
+        }
+    }
+}
+
+impl Default for TraceCache {
+    fn default() -> Self {
+        Self::new(TraceCacheConfig::default())
+    }
+}
+
+/// Removes the traces of reorged blocks from the [TraceCache] as soon as the reorg becomes
+/// canonical.
+pub async fn invalidate_reorged_traces_task<St>(trace_cache: TraceCache, mut events: St)
+where
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+{
+    while let Some(event) = events.next().await {
+        trace_cache.on_canon_state_notification(&event).await;
+    }
+}
+
+#[derive(Metrics)]
+#[metrics(scope = "rpc.trace_cache")]
+struct TraceCacheMetrics {
+    /// The number of cached block traces.
+    cached_count: Gauge,
+    /// The number of cache hits.
+    hits: Counter,
+    /// The number of cache misses.
+    misses: Counter,
+    /// The number of block traces that were not cached because they exceeded the maximum size.
+    oversized: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Block, Header, SealedBlockWithSenders};
+    use reth_provider::{BundleStateWithReceipts, Chain};
+    use reth_rpc_types::trace::geth::{GethDebugBuiltInTracerType, GethTrace, NoopFrame};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn call_tracer_opts(tracer_config: Value) -> GethDebugTracingOptions {
+        GethDebugTracingOptions {
+            tracer: Some(GethDebugBuiltInTracerType::CallTracer.into()),
+            tracer_config: tracer_config.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a tracing function that counts its executions.
+    fn counting_tracer(
+        executions: &AtomicUsize,
+    ) -> impl FnOnce() -> futures::future::Ready<EthResult<Vec<TraceResult>>> + '_ {
+        move || {
+            executions.fetch_add(1, Ordering::SeqCst);
+            futures::future::ready(Ok(vec![TraceResult::Success {
+                result: GethTrace::NoopTracer(NoopFrame::default()),
+            }]))
+        }
+    }
+
+    fn chain_with_block(block_hash: B256) -> Arc<Chain> {
+        let block =
+            Block { header: Header { number: 1, ..Default::default() }, ..Default::default() }
+                .seal(block_hash);
+        let block = SealedBlockWithSenders { block, senders: Vec::new() };
+        Arc::new(Chain::new(vec![block], BundleStateWithReceipts::default()))
+    }
+
+    #[tokio::test]
+    async fn traces_again_after_reorg() {
+        let cache = TraceCache::default();
+        let block_hash = B256::with_last_byte(1);
+        let key = TraceCacheKey::new(block_hash, &call_tracer_opts(Value::Null));
+        let executions = AtomicUsize::new(0);
+
+        let first = cache.get_or_trace(key.clone(), counting_tracer(&executions)).await.unwrap();
+        let second = cache.get_or_trace(key.clone(), counting_tracer(&executions)).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+
+        // a commit of a new block doesn't affect the cached traces
+        let new = chain_with_block(B256::with_last_byte(2));
+        cache.on_canon_state_notification(&CanonStateNotification::Commit { new }).await;
+        cache.get_or_trace(key.clone(), counting_tracer(&executions)).await.unwrap();
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+
+        let old = chain_with_block(block_hash);
+        let new = chain_with_block(B256::with_last_byte(2));
+        cache.on_canon_state_notification(&CanonStateNotification::Reorg { old, new }).await;
+        cache.get_or_trace(key, counting_tracer(&executions)).await.unwrap();
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn oversized_and_disabled_are_not_cached() {
+        let key = TraceCacheKey::new(B256::with_last_byte(1), &Default::default());
+
+        let cache = TraceCache::new(TraceCacheConfig { max_entry_size: 10, ..Default::default() });
+        let executions = AtomicUsize::new(0);
+        cache.get_or_trace(key.clone(), counting_tracer(&executions)).await.unwrap();
+        cache.get_or_trace(key.clone(), counting_tracer(&executions)).await.unwrap();
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+
+        let cache = TraceCache::disabled();
+        let executions = AtomicUsize::new(0);
+        cache.get_or_trace(key.clone(), counting_tracer(&executions)).await.unwrap();
+        cache.get_or_trace(key, counting_tracer(&executions)).await.unwrap();
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn key_canonicalizes_tracer_config() {
+        let block_hash = B256::with_last_byte(1);
+        let a: Value = serde_json::from_str(r#"{"onlyTopCall":true,"withLog":false}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{"withLog":false,"onlyTopCall":true}"#).unwrap();
+        assert_eq!(
+            TraceCacheKey::new(block_hash, &call_tracer_opts(a.clone())),
+            TraceCacheKey::new(block_hash, &call_tracer_opts(b))
+        );
+
+        let c: Value = serde_json::from_str(r#"{"onlyTopCall":false,"withLog":false}"#).unwrap();
+        assert_ne!(
+            TraceCacheKey::new(block_hash, &call_tracer_opts(a.clone())),
+            TraceCacheKey::new(block_hash, &call_tracer_opts(c))
+        );
+        assert_ne!(
+            TraceCacheKey::new(block_hash, &call_tracer_opts(a)),
+            TraceCacheKey::new(block_hash, &Default::default())
+        );
+    }
+}
@@ -0,0 +1,3 @@
+mod trace_stream;
+
+fn main() {}
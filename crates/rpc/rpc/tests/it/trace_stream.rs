@@ -0,0 +1,147 @@
+use futures::{future::BoxFuture, stream, StreamExt};
+use hyper::{Body, Request, Response};
+use reth_primitives::{BlockId, Bytes, U256};
+use reth_rpc::{
+    eth::error::{EthApiError, EthResult},
+    BlockTraceStreamer, TraceResultStream, TraceStreamLayer,
+};
+use reth_rpc_types::trace::geth::{
+    DefaultFrame, GethDebugTracingOptions, GethTrace, StructLog, TraceResult,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tower::{service_fn, Layer, Service};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Number of transactions in the traced block.
+const BLOCK_TRANSACTIONS: usize = 50;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Allocator that keeps track of the currently allocated bytes and their peak.
+struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK_ALLOCATED.fetch_max(allocated, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: TrackingAllocator = TrackingAllocator;
+
+/// Lazily produces the struct log traces of a block with [BLOCK_TRANSACTIONS] medium sized
+/// transactions.
+struct MockStreamer;
+
+impl BlockTraceStreamer for MockStreamer {
+    fn trace_block_stream(
+        &self,
+        block_id: BlockId,
+        _opts: GethDebugTracingOptions,
+    ) -> BoxFuture<'static, EthResult<TraceResultStream>> {
+        Box::pin(async move {
+            if block_id != BlockId::from(1u64) {
+                return Err(EthApiError::UnknownBlockNumber)
+            }
+            Ok(stream::iter(0..BLOCK_TRANSACTIONS).map(|_| Ok(medium_trace())).boxed())
+        })
+    }
+}
+
+fn medium_trace() -> TraceResult {
+    let struct_logs = (0..1_000)
+        .map(|pc| StructLog {
+            pc,
+            op: "SSTORE".to_string(),
+            gas: 1_000_000,
+            gas_cost: 20_000,
+            stack: Some(vec![U256::MAX; 8]),
+            depth: 1,
+            ..Default::default()
+        })
+        .collect();
+    TraceResult::Success {
+        result: GethTrace::Default(DefaultFrame {
+            failed: false,
+            gas: 21_000,
+            return_value: Bytes::default(),
+            struct_logs,
+        }),
+    }
+}
+
+fn trace_block_request(block: &str) -> Request<Body> {
+    let body = format!(
+        r#"{{"jsonrpc":"2.0","id":7,"method":"debug_traceBlockByNumber","params":["{block}"]}}"#
+    );
+    Request::post("/").body(Body::from(body)).unwrap()
+}
+
+fn service() -> impl Service<Request<Body>, Response = Response<Body>, Error = BoxError> {
+    let inner = service_fn(|_req: Request<Body>| async {
+        Ok::<_, BoxError>(Response::new(Body::from("not streamed")))
+    });
+    TraceStreamLayer::new(Arc::new(MockStreamer)).layer(inner)
+}
+
+// all checks are in a single test, since the allocations of concurrent tests would distort the
+// measured memory
+#[tokio::test]
+async fn streams_block_traces() {
+    let mut service = service();
+
+    // the response is assembled as the body is consumed
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    PEAK_ALLOCATED.store(before, Ordering::SeqCst);
+
+    let mut body = service.call(trace_block_request("0x1")).await.unwrap().into_body();
+    let mut response_size = 0;
+    while let Some(chunk) = body.next().await {
+        response_size += chunk.unwrap().len();
+    }
+    let peak = PEAK_ALLOCATED.load(Ordering::SeqCst) - before;
+
+    let trace_size = serde_json::to_vec(&medium_trace()).unwrap().len();
+    assert!(response_size > BLOCK_TRANSACTIONS * trace_size);
+    assert!(peak < response_size / 10, "peak memory {peak} of response with {response_size} bytes");
+
+    let body = service.call(trace_block_request("0x1")).await.unwrap().into_body();
+    let response: serde_json::Value =
+        serde_json::from_slice(&hyper::body::to_bytes(body).await.unwrap()).unwrap();
+    assert_eq!(response["id"], 7);
+    let results = response["result"].as_array().unwrap();
+    assert_eq!(results.len(), BLOCK_TRANSACTIONS);
+
+    // errors before the first trace are regular error responses
+    let body = service.call(trace_block_request("0x2")).await.unwrap().into_body();
+    let response: serde_json::Value =
+        serde_json::from_slice(&hyper::body::to_bytes(body).await.unwrap()).unwrap();
+    assert_eq!(response["id"], 7);
+    assert!(response["error"]["message"].is_string());
+    assert!(response.get("result").is_none());
+
+    // other methods are left to the server
+    let req = Request::post("/")
+        .body(Body::from(r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}"#))
+        .unwrap();
+    let body = service.call(req).await.unwrap().into_body();
+    assert_eq!(hyper::body::to_bytes(body).await.unwrap(), "not streamed");
+}
@@ -0,0 +1,186 @@
+//! Chunked writes of a stage that are committed together with an intra-batch sub-checkpoint.
+
+use reth_primitives::{stage::CheckpointBlockRange, BlockNumber};
+use std::ops::RangeInclusive;
+
+/// Splits the writes of a stage over a sorted set of keys into chunks of bounded size.
+///
+/// Every [Stage::execute](crate::Stage::execute) call writes a single chunk and reports the first
+/// key of the next chunk as a sub-checkpoint ([BatchCheckpoint]) in its stage checkpoint. The
+/// pipeline commits the chunk together with the checkpoint, so if the node stops in the middle of
+/// a batch, the next execution resumes after the last committed chunk instead of redoing the whole
+/// batch.
+#[derive(Debug, Clone, Copy)]
+pub struct StageWriteBatcher {
+    /// The maximum number of keys written per chunk.
+    chunk_size: usize,
+}
+
+impl StageWriteBatcher {
+    /// Creates a new batcher that writes at most `chunk_size` keys per chunk.
+    pub fn new(chunk_size: u64) -> Self {
+        Self { chunk_size: chunk_size.max(1) as usize }
+    }
+
+    /// Writes the chunk of `keys` that starts at `start` and returns the first key of the next
+    /// chunk, or `None` if the last chunk was written.
+    ///
+    /// The keys must be sorted in ascending order.
+    pub fn write_chunk<K: Ord, E>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        start: Option<&K>,
+        write: impl FnOnce(Vec<K>) -> Result<(), E>,
+    ) -> Result<Option<K>, E> {
+        let mut keys = keys.into_iter().skip_while(|key| start.map_or(false, |start| key < start));
+        let chunk = keys.by_ref().take(self.chunk_size).collect::<Vec<_>>();
+        if !chunk.is_empty() {
+            write(chunk)?;
+        }
+        Ok(keys.next())
+    }
+}
+
+/// The sub-checkpoint of a partially written batch, see [StageWriteBatcher].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchCheckpoint<K> {
+    /// The first key of the next chunk.
+    pub next_key: K,
+    /// The block range the batch was written for.
+    pub block_range: CheckpointBlockRange,
+}
+
+impl<K> BatchCheckpoint<K> {
+    /// Extends the block range to unwind by the range of the partially written batch.
+    ///
+    /// The committed chunks of the batch were written on top of the stage checkpoint, so they are
+    /// only reverted if the unwind covers the whole batch.
+    pub fn unwind_range(&self, range: RangeInclusive<BlockNumber>) -> RangeInclusive<BlockNumber> {
+        if self.block_range.from == range.end() + 1 {
+            *range.start()..=self.block_range.to
+        } else {
+            range
+        }
+    }
+}
+
+/// Where the batch of an execution starts, see [BatchStart::new].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchStart<K> {
+    /// Start at the first key.
+    First,
+    /// Continue at the first key after the last committed chunk.
+    Resume(K),
+    /// Start at the first key after reverting the committed chunks of a batch for another block
+    /// range.
+    Revert(RangeInclusive<BlockNumber>),
+}
+
+impl<K> BatchStart<K> {
+    /// Determines where the batch for the block range starts, given the sub-checkpoint of the last
+    /// committed chunk.
+    ///
+    /// A sub-checkpoint is only valid for the block range it was created for. The chunks of a
+    /// batch for another range that starts at the same block were written on top of the current
+    /// state and have to be reverted first, since the new batch doesn't necessarily rewrite the
+    /// same keys.
+    pub fn new(
+        checkpoint: Option<BatchCheckpoint<K>>,
+        block_range: &RangeInclusive<BlockNumber>,
+    ) -> Self {
+        match checkpoint {
+            Some(BatchCheckpoint { next_key, block_range: range })
+                if range == CheckpointBlockRange::from(block_range) =>
+            {
+                BatchStart::Resume(next_key)
+            }
+            Some(BatchCheckpoint { block_range: range, .. })
+                if range.from == *block_range.start() =>
+            {
+                BatchStart::Revert(range.from..=range.to)
+            }
+            _ => BatchStart::First,
+        }
+    }
+
+    /// Returns the key to resume at, if any.
+    pub fn key(&self) -> Option<&K> {
+        match self {
+            BatchStart::Resume(key) => Some(key),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{keccak256, B256};
+
+    /// Writes the chunks of the batch until it is done or a chunk fails, committing the hashes of
+    /// every successful chunk together with its sub-checkpoint like the pipeline does.
+    fn run_batch(
+        keys: &[u64],
+        next: &mut Option<u64>,
+        fail_at: Option<u64>,
+        hashed: &mut usize,
+        committed: &mut Vec<B256>,
+    ) {
+        let batcher = StageWriteBatcher::new(3);
+        loop {
+            let mut hashes = Vec::new();
+            let result = batcher.write_chunk(keys.iter().copied(), next.as_ref(), |chunk| {
+                *hashed += chunk.len();
+                hashes = chunk.iter().map(|key| keccak256(key.to_be_bytes())).collect();
+                if chunk.first() == fail_at.as_ref() {
+                    return Err("killed")
+                }
+                Ok(())
+            });
+            match result {
+                Ok(key) => {
+                    committed.extend(hashes);
+                    *next = key;
+                    if next.is_none() {
+                        return
+                    }
+                }
+                Err(_) => return,
+            }
+        }
+    }
+
+    #[test]
+    fn resume_after_failed_chunk() {
+        let keys = (0u64..10).collect::<Vec<_>>();
+        let (mut next, mut hashed, mut committed) = (None, 0, Vec::new());
+
+        run_batch(&keys, &mut next, Some(6), &mut hashed, &mut committed);
+        assert_eq!(next, Some(6));
+        assert_eq!(committed.len(), 6);
+        assert_eq!(hashed, 9);
+
+        // resume from the sub-checkpoint of the last committed chunk
+        run_batch(&keys, &mut next, None, &mut hashed, &mut committed);
+        assert_eq!(next, None);
+        assert_eq!(
+            committed,
+            keys.iter().map(|key| keccak256(key.to_be_bytes())).collect::<Vec<_>>()
+        );
+        // only the keys of the failed chunk were hashed twice
+        assert_eq!(hashed, keys.len() + 3);
+    }
+
+    #[test]
+    fn batch_start() {
+        let checkpoint =
+            BatchCheckpoint { next_key: 5, block_range: CheckpointBlockRange { from: 11, to: 20 } };
+        assert_eq!(BatchStart::new(Some(checkpoint), &(11..=20)), BatchStart::Resume(5));
+        assert_eq!(BatchStart::new(Some(checkpoint), &(11..=30)), BatchStart::Revert(11..=20));
+        assert_eq!(BatchStart::new(Some(checkpoint), &(21..=30)), BatchStart::First);
+        assert_eq!(BatchStart::<u64>::new(None, &(11..=20)), BatchStart::First);
+
+        assert_eq!(checkpoint.unwind_range(6..=10), 6..=20);
+        assert_eq!(checkpoint.unwind_range(6..=9), 6..=9);
+    }
+}
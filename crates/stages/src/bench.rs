@@ -0,0 +1,355 @@
+//! A harness for benchmarking single stages on realistic data.
+//!
+//! Stages are run directly through [ExecInput] and [UnwindInput], without the
+//! [Pipeline](crate::Pipeline), against a throwaway copy of an existing database or a
+//! deterministic synthetic chain. The resulting [StageBenchReport] can be serialized to JSON to
+//! track regressions in CI.
+
+use crate::{test_utils::TestTransaction, ExecInput, ExecOutput, Stage, StageError, UnwindInput};
+use rand::{rngs::StdRng, SeedableRng};
+use reth_db::{
+    database::Database, mdbx::EnvKind, table::Table, transaction::DbTx, DatabaseEnv, DatabaseError,
+    TableViewer, Tables,
+};
+use reth_interfaces::{test_utils::generators::random_block_range, RethError};
+use reth_primitives::{stage::StageCheckpoint, BlockNumber, ChainSpec, B256, MAINNET};
+use reth_provider::{ProviderFactory, StageCheckpointWriter};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    ops::RangeInclusive,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The name of the database file inside a database directory.
+const MDBX_DAT: &str = "mdbx.dat";
+
+/// Errors that can occur while running a stage benchmark.
+#[derive(Debug, thiserror::Error)]
+pub enum StageBenchError {
+    /// Failed to prepare the output environment.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The stage failed.
+    #[error(transparent)]
+    Stage(#[from] StageError),
+    /// A database error occurred.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    /// A provider error occurred.
+    #[error(transparent)]
+    Provider(#[from] RethError),
+}
+
+/// Whether the execution or the unwind of a stage is benchmarked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageBenchMode {
+    /// Execute the stage from the start to the end of the block range.
+    Execute,
+    /// Unwind the stage from the end to the start of the block range.
+    ///
+    /// The stage is expected to be executed up to the end of the range already.
+    Unwind,
+}
+
+/// The result of a single stage benchmark run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageBenchReport {
+    /// The benchmarked stage.
+    pub stage: String,
+    /// The benchmark mode.
+    pub mode: StageBenchMode,
+    /// The first block of the benchmarked range.
+    pub from_block: BlockNumber,
+    /// The last block of the benchmarked range.
+    pub to_block: BlockNumber,
+    /// How many times the stage was invoked until it was done.
+    pub iterations: u64,
+    /// Wall time spent in the stage and committing its changes, in seconds.
+    pub elapsed_secs: f64,
+    /// Blocks processed per second.
+    pub blocks_per_second: f64,
+    /// Entities processed by the stage, as reported by its final checkpoint.
+    pub entities_processed: Option<u64>,
+    /// Total entities to be processed by the stage, as reported by its final checkpoint.
+    pub entities_total: Option<u64>,
+    /// The change of the number of entries per table that was modified by the stage.
+    pub table_entries_changed: BTreeMap<String, i64>,
+    /// Database entries written or removed per second.
+    pub entries_per_second: f64,
+    /// Peak resident set size of the process in bytes, if available on this platform.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+impl StageBenchReport {
+    /// Returns the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is serializable")
+    }
+}
+
+/// The environment a stage benchmark runs in.
+#[derive(Debug)]
+pub struct StageBenchEnv {
+    db: Arc<DatabaseEnv>,
+    chain_spec: Arc<ChainSpec>,
+}
+
+impl StageBenchEnv {
+    /// Creates an environment with a copy of the database at `source`, placed in the `out`
+    /// directory.
+    ///
+    /// The source database is only read, so it is safe to point this at the database of a
+    /// stopped node. The copy is modified by the benchmarked stages.
+    pub fn from_datadir(
+        source: &Path,
+        out: &Path,
+        chain_spec: Arc<ChainSpec>,
+    ) -> Result<Self, StageBenchError> {
+        std::fs::create_dir_all(out)?;
+        std::fs::copy(source.join(MDBX_DAT), out.join(MDBX_DAT))?;
+        let db = DatabaseEnv::open(out, EnvKind::RW, None)?;
+        Ok(Self { db: Arc::new(db), chain_spec })
+    }
+
+    /// Creates an environment with a synthetic chain of `num_blocks` blocks on top of a genesis
+    /// block, with random transactions.
+    ///
+    /// The chain is fully determined by the `seed`.
+    pub fn synthetic(num_blocks: u64, seed: u64) -> Result<Self, StageBenchError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let tx = TestTransaction::default();
+
+        let blocks = random_block_range(&mut rng, 0..=num_blocks, B256::ZERO, 0..32);
+        tx.insert_headers_with_td(blocks.iter().map(|block| &block.header))?;
+        tx.insert_blocks(blocks.iter(), None)?;
+
+        Ok(Self { db: tx.inner_raw(), chain_spec: MAINNET.clone() })
+    }
+
+    /// Returns the database of the environment.
+    pub fn db(&self) -> &Arc<DatabaseEnv> {
+        &self.db
+    }
+
+    /// Runs the stage over the given block range in the given mode and returns the report.
+    ///
+    /// Changes are committed after every invocation of the stage, same as in the
+    /// [Pipeline](crate::Pipeline).
+    pub async fn run<S: Stage<Arc<DatabaseEnv>>>(
+        &self,
+        stage: &mut S,
+        mode: StageBenchMode,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageBenchReport, StageBenchError> {
+        let (from_block, to_block) = range.into_inner();
+        let stage_id = stage.id();
+        let factory = ProviderFactory::new(&self.db, self.chain_spec.clone());
+
+        let entries_before = self.table_entries()?;
+
+        let mut iterations = 0;
+        let mut elapsed = Duration::ZERO;
+        let checkpoint = match mode {
+            StageBenchMode::Execute => {
+                let mut checkpoint = StageCheckpoint::new(from_block);
+                loop {
+                    let provider_rw = factory.provider_rw()?;
+                    let input = ExecInput { target: Some(to_block), checkpoint: Some(checkpoint) };
+
+                    let start = Instant::now();
+                    let ExecOutput { checkpoint: next, done } =
+                        stage.execute(&provider_rw, input).await?;
+                    provider_rw.save_stage_checkpoint(stage_id, next)?;
+                    provider_rw.commit()?;
+                    elapsed += start.elapsed();
+
+                    iterations += 1;
+                    checkpoint = next;
+                    if done {
+                        break checkpoint
+                    }
+                }
+            }
+            StageBenchMode::Unwind => {
+                let mut checkpoint = StageCheckpoint::new(to_block);
+                while checkpoint.block_number > from_block {
+                    let provider_rw = factory.provider_rw()?;
+                    let input = UnwindInput { checkpoint, unwind_to: from_block, bad_block: None };
+
+                    let start = Instant::now();
+                    checkpoint = stage.unwind(&provider_rw, input).await?.checkpoint;
+                    provider_rw.save_stage_checkpoint(stage_id, checkpoint)?;
+                    provider_rw.commit()?;
+                    elapsed += start.elapsed();
+
+                    iterations += 1;
+                }
+                checkpoint
+            }
+        };
+
+        let entries_after = self.table_entries()?;
+        let table_entries_changed = entries_after
+            .into_iter()
+            .filter_map(|(table, after)| {
+                let change =
+                    after as i64 - entries_before.get(&table).copied().unwrap_or_default() as i64;
+                (change != 0).then_some((table, change))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let elapsed_secs = elapsed.as_secs_f64();
+        let per_second =
+            |count: u64| if elapsed_secs > 0.0 { count as f64 / elapsed_secs } else { 0.0 };
+        let entities = checkpoint.entities();
+
+        Ok(StageBenchReport {
+            stage: stage_id.to_string(),
+            mode,
+            from_block,
+            to_block,
+            iterations,
+            elapsed_secs,
+            blocks_per_second: per_second(to_block.saturating_sub(from_block)),
+            entities_processed: entities.map(|entities| entities.processed),
+            entities_total: entities.map(|entities| entities.total),
+            entries_per_second: per_second(
+                table_entries_changed.values().map(|change| change.unsigned_abs()).sum(),
+            ),
+            table_entries_changed,
+            peak_rss_bytes: peak_rss_bytes(),
+        })
+    }
+
+    /// Returns the number of entries of every table.
+    fn table_entries(&self) -> Result<BTreeMap<String, usize>, StageBenchError> {
+        let tx = self.db.tx()?;
+        let viewer = EntriesViewer { tx: &tx };
+        let mut entries = BTreeMap::new();
+        for table in Tables::ALL {
+            entries.insert(table.name().to_string(), table.view(&viewer)?);
+        }
+        Ok(entries)
+    }
+}
+
+/// Counts the entries of a table.
+struct EntriesViewer<'a, TX> {
+    tx: &'a TX,
+}
+
+impl<TX: DbTx> TableViewer<usize> for EntriesViewer<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        self.tx.entries::<T>()
+    }
+}
+
+/// Returns the peak resident set size of the current process in bytes.
+///
+/// Only supported on Linux.
+fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kb = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    kb.trim().trim_end_matches("kB").trim().parse::<u64>().ok().map(|kb| kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stages::{SenderRecoveryStage, TransactionLookupStage},
+        Pipeline,
+    };
+    use reth_db::{cursor::DbCursorRO, tables};
+
+    const NUM_BLOCKS: u64 = 200;
+    const SEED: u64 = 42;
+
+    fn table_rows<T: Table>(db: &Arc<DatabaseEnv>) -> Vec<(T::Key, T::Value)> {
+        db.view(|tx| tx.cursor_read::<T>()?.walk(None)?.collect::<Result<Vec<_>, DatabaseError>>())
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn bench_matches_pipeline_run() {
+        let env = StageBenchEnv::synthetic(NUM_BLOCKS, SEED).unwrap();
+
+        let sender_recovery = env
+            .run(&mut SenderRecoveryStage::new(50), StageBenchMode::Execute, 0..=NUM_BLOCKS)
+            .await
+            .unwrap();
+        let tx_lookup = env
+            .run(
+                &mut TransactionLookupStage::new(u64::MAX, None),
+                StageBenchMode::Execute,
+                0..=NUM_BLOCKS,
+            )
+            .await
+            .unwrap();
+
+        // same chain, synced with the pipeline
+        let pipeline_env = StageBenchEnv::synthetic(NUM_BLOCKS, SEED).unwrap();
+        let mut pipeline = Pipeline::builder()
+            .add_stage(SenderRecoveryStage::new(50))
+            .add_stage(TransactionLookupStage::new(u64::MAX, None))
+            .with_max_block(NUM_BLOCKS)
+            .build(pipeline_env.db().clone(), MAINNET.clone());
+        pipeline.run().await.unwrap();
+
+        let senders = table_rows::<tables::TxSenders>(env.db());
+        assert!(!senders.is_empty());
+        assert_eq!(senders, table_rows::<tables::TxSenders>(pipeline_env.db()));
+        assert_eq!(
+            table_rows::<tables::TxHashNumber>(env.db()),
+            table_rows::<tables::TxHashNumber>(pipeline_env.db())
+        );
+
+        // the sender recovery stage commits every 50 transactions
+        assert!(sender_recovery.iterations > 1);
+        assert_eq!(
+            sender_recovery.table_entries_changed,
+            BTreeMap::from([(tables::TxSenders::NAME.to_string(), senders.len() as i64)])
+        );
+        assert_eq!(tx_lookup.iterations, 1);
+
+        for report in [&sender_recovery, &tx_lookup] {
+            let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+            let object = json.as_object().unwrap();
+            for key in [
+                "stage",
+                "mode",
+                "from_block",
+                "to_block",
+                "iterations",
+                "elapsed_secs",
+                "blocks_per_second",
+                "entities_processed",
+                "entities_total",
+                "table_entries_changed",
+                "entries_per_second",
+                "peak_rss_bytes",
+            ] {
+                assert!(object.contains_key(key), "missing key {key}");
+            }
+            assert_eq!(object["mode"], "execute");
+            assert_eq!(object["to_block"], NUM_BLOCKS);
+        }
+
+        let unwind = env
+            .run(&mut SenderRecoveryStage::new(50), StageBenchMode::Unwind, 0..=NUM_BLOCKS)
+            .await
+            .unwrap();
+        assert_eq!(unwind.mode, StageBenchMode::Unwind);
+        assert_eq!(
+            unwind.table_entries_changed,
+            BTreeMap::from([(tables::TxSenders::NAME.to_string(), -(senders.len() as i64))])
+        );
+    }
+}
@@ -61,6 +61,7 @@
 #![deny(unused_must_use, rust_2018_idioms)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod batcher;
 mod error;
 mod metrics;
 mod pipeline;
@@ -71,6 +72,10 @@ mod util;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
+/// Single stage benchmarking.
+#[cfg(any(test, feature = "test-utils"))]
+pub mod bench;
+
 /// A re-export of common structs and traits.
 pub mod prelude;
 
@@ -80,6 +85,7 @@ pub mod stages;
 pub mod sets;
 
 pub use crate::metrics::*;
+pub use batcher::*;
 pub use error::*;
 pub use pipeline::*;
 pub use stage::*;
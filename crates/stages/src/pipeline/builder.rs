@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
-use crate::{pipeline::BoxedStage, MetricEventsSender, Pipeline, Stage, StageSet};
+use crate::{
+    pipeline::{BoxedStage, StageCheckpointReporter},
+    MetricEventsSender, Pipeline, Stage, StageSet,
+};
 use reth_db::database::Database;
+use reth_interfaces::sync::NetworkSyncUpdater;
 use reth_primitives::{stage::StageId, BlockNumber, ChainSpec, B256};
 use tokio::sync::watch;
 
@@ -18,6 +22,8 @@ where
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Receives the committed checkpoints of all stages.
+    sync_state_updater: Option<Box<dyn NetworkSyncUpdater>>,
 }
 
 impl<DB> PipelineBuilder<DB>
@@ -67,11 +73,17 @@ where
         self
     }
 
+    /// Set the updater that is notified about the committed checkpoints of all stages.
+    pub fn with_sync_state_updater(mut self, updater: impl NetworkSyncUpdater) -> Self {
+        self.sync_state_updater = Some(Box::new(updater));
+        self
+    }
+
     /// Builds the final [`Pipeline`] using the given database.
     ///
     /// Note: it's expected that this is either an [Arc] or an Arc wrapper type.
     pub fn build(self, db: DB, chain_spec: Arc<ChainSpec>) -> Pipeline<DB> {
-        let Self { stages, max_block, tip_tx, metrics_tx } = self;
+        let Self { stages, max_block, tip_tx, metrics_tx, sync_state_updater } = self;
         Pipeline {
             db,
             chain_spec,
@@ -81,13 +93,20 @@ where
             listeners: Default::default(),
             progress: Default::default(),
             metrics_tx,
+            checkpoint_reporter: sync_state_updater.map(StageCheckpointReporter::new),
         }
     }
 }
 
 impl<DB: Database> Default for PipelineBuilder<DB> {
     fn default() -> Self {
-        Self { stages: Vec::new(), max_block: None, tip_tx: None, metrics_tx: None }
+        Self {
+            stages: Vec::new(),
+            max_block: None,
+            tip_tx: None,
+            metrics_tx: None,
+            sync_state_updater: None,
+        }
     }
 }
 
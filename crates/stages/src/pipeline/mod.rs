@@ -6,7 +6,9 @@ use futures_util::Future;
 use reth_db::database::Database;
 use reth_interfaces::executor::BlockExecutionError;
 use reth_primitives::{
-    constants::BEACON_CONSENSUS_REORG_UNWIND_DEPTH, stage::StageId, BlockNumber, ChainSpec, B256,
+    constants::BEACON_CONSENSUS_REORG_UNWIND_DEPTH,
+    stage::{StageCheckpoint, StageId},
+    BlockNumber, ChainSpec, B256,
 };
 use reth_provider::{ProviderFactory, StageCheckpointReader, StageCheckpointWriter};
 use reth_tokio_util::EventListeners;
@@ -19,12 +21,14 @@ mod builder;
 mod ctrl;
 mod event;
 mod progress;
+mod reconcile;
 mod set;
 
 pub use crate::pipeline::ctrl::ControlFlow;
 pub use builder::*;
 pub use event::*;
 use progress::*;
+pub use reconcile::*;
 pub use set::*;
 
 /// A container for a queued stage.
@@ -107,6 +111,8 @@ pub struct Pipeline<DB: Database> {
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Reports the committed checkpoints of all stages, if a sync state updater is set.
+    checkpoint_reporter: Option<StageCheckpointReporter>,
 }
 
 impl<DB> Pipeline<DB>
@@ -154,6 +160,22 @@ where
         Ok(())
     }
 
+    /// Reports the stored checkpoints of all stages to the sync state updater, if set.
+    pub fn report_stage_checkpoints(&mut self) -> Result<(), PipelineError> {
+        let Some(reporter) = &mut self.checkpoint_reporter else { return Ok(()) };
+        let factory = ProviderFactory::new(&self.db, self.chain_spec.clone());
+        let provider = factory.provider()?;
+
+        let mut checkpoints = Vec::with_capacity(self.stages.len());
+        for stage in &self.stages {
+            let stage_id = stage.id();
+            let checkpoint = provider.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+            checkpoints.push((stage_id, checkpoint.block_number));
+        }
+        reporter.set_checkpoints(checkpoints);
+        Ok(())
+    }
+
     /// Consume the pipeline and run it until it reaches the provided tip, if set. Return the
     /// pipeline and its result as a future.
     #[track_caller]
@@ -161,6 +183,7 @@ where
         // TODO: fix this in a follow up PR. ideally, consensus engine would be responsible for
         // updating metrics.
         let _ = self.register_metrics(); // ignore error
+        let _ = self.report_stage_checkpoints(); // ignore error
         Box::pin(async move {
             // NOTE: the tip should only be None if we are in continuous sync mode.
             if let Some(tip) = tip {
@@ -176,6 +199,7 @@ where
     /// a `max_block` in the pipeline.
     pub async fn run(&mut self) -> Result<(), PipelineError> {
         let _ = self.register_metrics(); // ignore error
+        let _ = self.report_stage_checkpoints(); // ignore error
 
         loop {
             let next_action = self.run_loop().await?;
@@ -311,6 +335,9 @@ where
 
                         provider_rw.commit()?;
                         provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+                        if let Some(reporter) = &mut self.checkpoint_reporter {
+                            reporter.on_checkpoint(stage_id, checkpoint.block_number);
+                        }
                     }
                     Err(err) => {
                         self.listeners.notify(PipelineEvent::Error { stage_id });
@@ -323,6 +350,70 @@ where
         Ok(())
     }
 
+    /// Rolls back the checkpoints of all stages that are ahead of any stage they depend on, e.g.
+    /// after a crash during a multi-stage commit or when restoring a copied database.
+    ///
+    /// Checkpoints of idempotent stages are reset without touching the stage data, all other
+    /// stages are unwound to the highest consistent block.
+    ///
+    /// Returns the adjustments that were made.
+    pub async fn reconcile_checkpoints(
+        &mut self,
+    ) -> Result<Vec<CheckpointAdjustment>, PipelineError> {
+        let factory = ProviderFactory::new(&self.db, self.chain_spec.clone());
+        let mut provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+
+        let adjustments =
+            inconsistent_checkpoints(&*provider_rw, self.stages.iter().map(|stage| stage.id()))?;
+
+        // Adjust stages in reverse order of execution, the same way they are unwound
+        for adjustment in adjustments.iter().rev() {
+            let CheckpointAdjustment { stage_id, from, to } = *adjustment;
+
+            if adjustment.is_idempotent() {
+                provider_rw.save_stage_checkpoint(stage_id, StageCheckpoint::new(to))?;
+                provider_rw.commit()?;
+                provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+                if let Some(reporter) = &mut self.checkpoint_reporter {
+                    reporter.on_checkpoint(stage_id, to);
+                }
+                info!(target: "sync::pipeline", stage = %stage_id, from, to, "Reset inconsistent stage checkpoint");
+                continue
+            }
+
+            let stage = self
+                .stages
+                .iter_mut()
+                .find(|stage| stage.id() == stage_id)
+                .expect("adjustments only contain pipeline stages");
+            let mut checkpoint = provider_rw.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+            while checkpoint.block_number > to {
+                let input = UnwindInput { checkpoint, unwind_to: to, bad_block: None };
+                self.listeners.notify(PipelineEvent::Unwinding { stage_id, input });
+
+                let unwind_output = match stage.unwind(&provider_rw, input).await {
+                    Ok(output) => output,
+                    Err(err) => {
+                        self.listeners.notify(PipelineEvent::Error { stage_id });
+                        return Err(PipelineError::Stage(StageError::Fatal(Box::new(err))))
+                    }
+                };
+                checkpoint = unwind_output.checkpoint;
+                provider_rw.save_stage_checkpoint(stage_id, checkpoint)?;
+                self.listeners.notify(PipelineEvent::Unwound { stage_id, result: unwind_output });
+
+                provider_rw.commit()?;
+                provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+                if let Some(reporter) = &mut self.checkpoint_reporter {
+                    reporter.on_checkpoint(stage_id, checkpoint.block_number);
+                }
+            }
+            info!(target: "sync::pipeline", stage = %stage_id, from, to, "Unwound inconsistent stage checkpoint");
+        }
+
+        Ok(adjustments)
+    }
+
     async fn execute_stage_to_completion(
         &mut self,
         previous_stage: Option<BlockNumber>,
@@ -401,6 +492,9 @@ where
                     // TODO: Make the commit interval configurable
                     provider_rw.commit()?;
                     provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+                    if let Some(reporter) = &mut self.checkpoint_reporter {
+                        reporter.on_checkpoint(stage_id, checkpoint.block_number);
+                    }
 
                     if done {
                         let block_number = checkpoint.block_number;
@@ -513,11 +607,25 @@ mod tests {
     use reth_interfaces::{
         consensus,
         provider::ProviderError,
+        sync::{NetworkSyncUpdater, SyncState},
         test_utils::{generators, generators::random_header},
     };
-    use reth_primitives::{stage::StageCheckpoint, MAINNET};
+    use reth_primitives::{stage::StageCheckpoint, Head, MAINNET};
+    use std::sync::Mutex;
     use tokio_stream::StreamExt;
 
+    /// Records the stage checkpoints reported by the pipeline.
+    #[derive(Debug, Clone, Default)]
+    struct CheckpointRecorder(Arc<Mutex<Vec<Vec<(StageId, BlockNumber)>>>>);
+
+    impl NetworkSyncUpdater for CheckpointRecorder {
+        fn update_sync_state(&self, _: SyncState) {}
+        fn update_status(&self, _: Head) {}
+        fn update_stage_checkpoints(&self, checkpoints: Vec<(StageId, BlockNumber)>) {
+            self.0.lock().unwrap().push(checkpoints);
+        }
+    }
+
     #[test]
     fn record_progress_calculates_outliers() {
         let mut progress = PipelineProgress::default();
@@ -600,6 +708,36 @@ mod tests {
         );
     }
 
+    /// Reports the committed checkpoints of all stages to the sync state updater.
+    #[tokio::test]
+    async fn reports_stage_checkpoints() {
+        let db = create_test_rw_db();
+        let recorder = CheckpointRecorder::default();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(20), done: true })),
+            )
+            .add_stage(
+                TestStage::new(StageId::Other("B"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(10), done: true })),
+            )
+            .with_max_block(10)
+            .with_sync_state_updater(recorder.clone())
+            .build(db, MAINNET.clone());
+        pipeline.run().await.unwrap();
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                vec![(StageId::Other("A"), 0), (StageId::Other("B"), 0)],
+                vec![(StageId::Other("A"), 20), (StageId::Other("B"), 0)],
+                vec![(StageId::Other("A"), 20), (StageId::Other("B"), 10)],
+            ]
+        );
+    }
+
     /// Unwinds a simple pipeline.
     #[tokio::test]
     async fn unwind_pipeline() {
@@ -716,6 +854,69 @@ mod tests {
         );
     }
 
+    /// Reconciles inconsistent stage checkpoints and syncs to the tip afterwards.
+    #[tokio::test]
+    async fn reconcile_inconsistent_checkpoints() {
+        let db = create_test_rw_db();
+
+        let factory = ProviderFactory::new(&db, MAINNET.clone());
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Other("A"), StageCheckpoint::new(20)).unwrap();
+        provider_rw
+            .save_stage_checkpoint(StageId::SenderRecovery, StageCheckpoint::new(30))
+            .unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Other("C"), StageCheckpoint::new(25)).unwrap();
+        provider_rw.commit().unwrap();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(30), done: true })),
+            )
+            .add_stage(
+                TestStage::new(StageId::SenderRecovery)
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(30), done: true })),
+            )
+            .add_stage(
+                TestStage::new(StageId::Other("C"))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(20) }))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(30), done: true })),
+            )
+            .with_max_block(30)
+            .build(db.clone(), MAINNET.clone());
+
+        let adjustments = pipeline.reconcile_checkpoints().await.unwrap();
+        assert_eq!(
+            adjustments,
+            vec![
+                CheckpointAdjustment { stage_id: StageId::SenderRecovery, from: 30, to: 20 },
+                CheckpointAdjustment { stage_id: StageId::Other("C"), from: 25, to: 20 },
+            ]
+        );
+
+        let provider = factory.provider().unwrap();
+        for stage_id in [StageId::Other("A"), StageId::SenderRecovery, StageId::Other("C")] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage_id).unwrap(),
+                Some(StageCheckpoint::new(20))
+            );
+        }
+        drop(provider);
+
+        // Reconciled checkpoints are consistent, so nothing is adjusted anymore
+        assert_eq!(pipeline.reconcile_checkpoints().await.unwrap(), vec![]);
+
+        pipeline.run().await.unwrap();
+
+        let provider = factory.provider().unwrap();
+        for stage_id in [StageId::Other("A"), StageId::SenderRecovery, StageId::Other("C")] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage_id).unwrap(),
+                Some(StageCheckpoint::new(30))
+            );
+        }
+    }
+
     /// Unwinds a pipeline with intermediate progress.
     #[tokio::test]
     async fn unwind_pipeline_with_intermediate_progress() {
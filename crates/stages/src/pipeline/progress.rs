@@ -1,6 +1,7 @@
 use super::ctrl::ControlFlow;
 use crate::util::opt;
-use reth_primitives::BlockNumber;
+use reth_interfaces::sync::NetworkSyncUpdater;
+use reth_primitives::{stage::StageId, BlockNumber};
 
 #[derive(Debug, Default)]
 pub(crate) struct PipelineProgress {
@@ -27,3 +28,36 @@ impl PipelineProgress {
         }
     }
 }
+
+/// Reports the committed checkpoints of all stages to a [NetworkSyncUpdater].
+#[derive(Debug)]
+pub(crate) struct StageCheckpointReporter {
+    updater: Box<dyn NetworkSyncUpdater>,
+    /// The last committed checkpoint of every stage, in the order of execution.
+    checkpoints: Vec<(StageId, BlockNumber)>,
+}
+
+impl StageCheckpointReporter {
+    pub(crate) fn new(updater: Box<dyn NetworkSyncUpdater>) -> Self {
+        Self { updater, checkpoints: Vec::new() }
+    }
+
+    /// Replaces the checkpoints of all stages, e.g. with the ones stored in the database.
+    pub(crate) fn set_checkpoints(&mut self, checkpoints: Vec<(StageId, BlockNumber)>) {
+        self.checkpoints = checkpoints;
+        self.report();
+    }
+
+    /// Invoked after the checkpoint of a stage was committed.
+    pub(crate) fn on_checkpoint(&mut self, stage_id: StageId, block_number: BlockNumber) {
+        match self.checkpoints.iter_mut().find(|(id, _)| *id == stage_id) {
+            Some((_, checkpoint)) => *checkpoint = block_number,
+            None => self.checkpoints.push((stage_id, block_number)),
+        }
+        self.report();
+    }
+
+    fn report(&self) {
+        self.updater.update_stage_checkpoints(self.checkpoints.clone());
+    }
+}
@@ -0,0 +1,105 @@
+use reth_interfaces::RethResult;
+use reth_primitives::{stage::StageId, BlockNumber};
+use reth_provider::StageCheckpointReader;
+
+/// An adjustment of a stage checkpoint that is ahead of the checkpoint of a stage it depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointAdjustment {
+    /// The stage with the inconsistent checkpoint.
+    pub stage_id: StageId,
+    /// The block number of the inconsistent checkpoint.
+    pub from: BlockNumber,
+    /// The block number the checkpoint needs to be rolled back to.
+    pub to: BlockNumber,
+}
+
+impl CheckpointAdjustment {
+    /// Returns true if the stage data can be left untouched when rolling back the checkpoint,
+    /// because the stage overwrites its data when it is executed again for the same blocks.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self.stage_id,
+            StageId::TotalDifficulty |
+                StageId::SenderRecovery |
+                StageId::MerkleUnwind |
+                StageId::TransactionLookup |
+                StageId::Finish
+        )
+    }
+}
+
+/// Returns the checkpoint adjustments required, so that no stage is ahead of any stage preceding
+/// it in `stages`.
+///
+/// Stages are expected to be in pipeline execution order, each stage depending on all stages
+/// before it. Missing checkpoints are treated as block `0`.
+pub fn inconsistent_checkpoints(
+    provider: impl StageCheckpointReader,
+    stages: impl IntoIterator<Item = StageId>,
+) -> RethResult<Vec<CheckpointAdjustment>> {
+    let mut adjustments = Vec::new();
+    let mut max_consistent: Option<BlockNumber> = None;
+
+    for stage_id in stages {
+        let block_number =
+            provider.get_stage_checkpoint(stage_id)?.unwrap_or_default().block_number;
+
+        let consistent = match max_consistent {
+            Some(max) if block_number > max => {
+                adjustments.push(CheckpointAdjustment { stage_id, from: block_number, to: max });
+                max
+            }
+            _ => block_number,
+        };
+        max_consistent = Some(consistent);
+    }
+
+    Ok(adjustments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_primitives::{stage::StageCheckpoint, MAINNET};
+    use reth_provider::{ProviderFactory, StageCheckpointWriter};
+
+    #[test]
+    fn detects_inconsistent_checkpoints() {
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(db.as_ref(), MAINNET.clone());
+        let provider = factory.provider_rw().unwrap();
+        for (stage_id, block_number) in [
+            (StageId::Headers, 100),
+            (StageId::Bodies, 100),
+            (StageId::Execution, 50),
+            (StageId::AccountHashing, 80),
+            (StageId::StorageHashing, 40),
+            (StageId::Finish, 60),
+        ] {
+            provider.save_stage_checkpoint(stage_id, StageCheckpoint::new(block_number)).unwrap();
+        }
+
+        let adjustments = inconsistent_checkpoints(
+            &*provider,
+            [
+                StageId::Headers,
+                StageId::Bodies,
+                StageId::Execution,
+                StageId::AccountHashing,
+                StageId::StorageHashing,
+                StageId::Finish,
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            adjustments,
+            vec![
+                CheckpointAdjustment { stage_id: StageId::AccountHashing, from: 80, to: 50 },
+                CheckpointAdjustment { stage_id: StageId::Finish, from: 60, to: 40 },
+            ]
+        );
+        assert!(!adjustments[0].is_idempotent());
+        assert!(adjustments[1].is_idempotent());
+    }
+}
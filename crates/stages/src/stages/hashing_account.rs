@@ -1,4 +1,7 @@
-use crate::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use crate::{
+    BatchCheckpoint, BatchStart, ExecInput, ExecOutput, Stage, StageError, StageWriteBatcher,
+    UnwindInput, UnwindOutput,
+};
 use itertools::Itertools;
 use rayon::slice::ParallelSliceMut;
 use reth_db::{
@@ -15,6 +18,7 @@ use reth_primitives::{
         AccountHashingCheckpoint, CheckpointBlockRange, EntitiesCheckpoint, StageCheckpoint,
         StageId,
     },
+    Address,
 };
 use reth_provider::{AccountExtReader, DatabaseProviderRW, HashingWriter};
 use std::{
@@ -242,15 +246,46 @@ impl<DB: Database> Stage<DB> for AccountHashingStage {
                 return Ok(ExecOutput { checkpoint, done: false })
             }
         } else {
+            let block_range = from_block..=to_block;
+            let start = BatchStart::new(
+                input
+                    .checkpoint
+                    .and_then(|checkpoint| checkpoint.account_hashing_stage_checkpoint())
+                    .and_then(batch_checkpoint),
+                &block_range,
+            );
+            if let BatchStart::Revert(range) = &start {
+                debug!(target: "sync::stages::account_hashing::exec", ?range, "Reverting stale account hashing batch");
+                provider.unwind_account_hashing(range.clone())?;
+            }
+
             // Aggregate all transition changesets and make a list of accounts that have been
             // changed.
-            let lists = provider.changed_accounts_with_range(from_block..=to_block)?;
-            // Iterate over plain state and get newest value.
-            // Assumption we are okay to make is that plainstate represent
-            // `previous_stage_progress` state.
-            let accounts = provider.basic_accounts(lists)?;
-            // Insert and hash accounts to hashing table
-            provider.insert_account_for_hashing(accounts)?;
+            let lists = provider.changed_accounts_with_range(block_range)?;
+            let next_address = StageWriteBatcher::new(self.commit_threshold).write_chunk(
+                lists,
+                start.key(),
+                |addresses| {
+                    // Iterate over plain state and get newest value.
+                    // Assumption we are okay to make is that plainstate represent
+                    // `previous_stage_progress` state.
+                    let accounts = provider.basic_accounts(addresses)?;
+                    // Insert and hash accounts to hashing table
+                    provider.insert_account_for_hashing(accounts).map(|_| ())
+                },
+            )?;
+
+            if let Some(next_address) = next_address {
+                let checkpoint = input.checkpoint().with_account_hashing_stage_checkpoint(
+                    AccountHashingCheckpoint {
+                        address: Some(next_address),
+                        block_range: CheckpointBlockRange { from: from_block, to: to_block },
+                        progress: stage_checkpoint_progress(provider)?,
+                    },
+                );
+
+                return Ok(ExecOutput { checkpoint, done: false })
+            }
         }
 
         // We finished the hashing stage, no future iterations is expected for the same block range,
@@ -270,15 +305,22 @@ impl<DB: Database> Stage<DB> for AccountHashingStage {
         provider: &DatabaseProviderRW<'_, &DB>,
         input: UnwindInput,
     ) -> Result<UnwindOutput, StageError> {
-        let (range, unwind_progress, _) =
+        let (mut range, unwind_progress, _) =
             input.unwind_block_range_with_threshold(self.commit_threshold);
 
-        // Aggregate all transition changesets and make a list of accounts that have been changed.
-        provider.unwind_account_hashing(range)?;
-
         let mut stage_checkpoint =
             input.checkpoint.account_hashing_stage_checkpoint().unwrap_or_default();
 
+        // Also revert the committed chunks of a partially written batch on top of the checkpoint.
+        if let Some(batch) = batch_checkpoint(stage_checkpoint) {
+            range = batch.unwind_range(range);
+            stage_checkpoint.address = None;
+            stage_checkpoint.block_range = CheckpointBlockRange::default();
+        }
+
+        // Aggregate all transition changesets and make a list of accounts that have been changed.
+        provider.unwind_account_hashing(range)?;
+
         stage_checkpoint.progress = stage_checkpoint_progress(provider)?;
 
         Ok(UnwindOutput {
@@ -288,6 +330,13 @@ impl<DB: Database> Stage<DB> for AccountHashingStage {
     }
 }
 
+/// Returns the sub-checkpoint of a partially written batch, if any.
+fn batch_checkpoint(checkpoint: AccountHashingCheckpoint) -> Option<BatchCheckpoint<Address>> {
+    checkpoint
+        .address
+        .map(|next_key| BatchCheckpoint { next_key, block_range: checkpoint.block_range })
+}
+
 fn stage_checkpoint_progress<DB: Database>(
     provider: &DatabaseProviderRW<'_, &DB>,
 ) -> Result<EntitiesCheckpoint, DatabaseError> {
@@ -301,10 +350,12 @@ fn stage_checkpoint_progress<DB: Database>(
 mod tests {
     use super::*;
     use crate::test_utils::{
-        stage_test_suite_ext, ExecuteStageTestRunner, TestRunnerError, UnwindStageTestRunner,
+        stage_test_suite_ext, ExecuteStageTestRunner, StageTestRunner, TestRunnerError,
+        UnwindStageTestRunner,
     };
     use assert_matches::assert_matches;
-    use reth_primitives::{stage::StageUnitCheckpoint, Account, U256};
+    use reth_primitives::{stage::StageUnitCheckpoint, Account, MAINNET, U256};
+    use reth_provider::ProviderFactory;
     use test_utils::*;
 
     stage_test_suite_ext!(AccountHashingTestRunner, account_hashing);
@@ -434,6 +485,63 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    #[tokio::test]
+    async fn execute_incremental_account_hashing_resumes_after_failure() {
+        // the accounts changed in blocks 3..=10 are hashed incrementally
+        let mut runner = AccountHashingTestRunner::default();
+        runner.set_commit_threshold(3);
+        let mut input = ExecInput { target: Some(20), checkpoint: Some(StageCheckpoint::new(2)) };
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let output = runner.execute(input).await.unwrap().unwrap();
+        assert!(!output.done);
+        assert_eq!(runner.tx.table::<tables::HashedAccount>().unwrap().len(), 3);
+        input.checkpoint = Some(output.checkpoint);
+
+        // the second chunk is lost, since the stage is killed before it is committed
+        {
+            let db = runner.tx.inner_raw();
+            let factory = ProviderFactory::new(db.as_ref(), MAINNET.clone());
+            let provider = factory.provider_rw().unwrap();
+            let output = runner.stage().execute(&provider, input).await.unwrap();
+            assert!(!output.done);
+        }
+        assert_eq!(runner.tx.table::<tables::HashedAccount>().unwrap().len(), 3);
+
+        // resume after the first chunk
+        let output = runner.execute(input).await.unwrap().unwrap();
+        assert!(!output.done);
+        assert_eq!(runner.tx.table::<tables::HashedAccount>().unwrap().len(), 6);
+        input.checkpoint = Some(output.checkpoint);
+
+        let output = runner.execute(input).await.unwrap().unwrap();
+        assert!(output.done);
+        assert_eq!(output.checkpoint.block_number, 20);
+        assert_eq!(runner.tx.table::<tables::HashedAccount>().unwrap().len(), 8);
+        assert!(runner.validate_execution(input, Some(output)).is_ok(), "execution validation");
+    }
+
+    #[tokio::test]
+    async fn unwind_partially_hashed_batch() {
+        let mut runner = AccountHashingTestRunner::default();
+        runner.set_commit_threshold(3);
+        let input = ExecInput { target: Some(20), checkpoint: Some(StageCheckpoint::new(2)) };
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let output = runner.execute(input).await.unwrap().unwrap();
+        assert!(!output.done);
+
+        // the chunk written for blocks 3..=20 is reverted, although the checkpoint is at block 2
+        let input = UnwindInput { checkpoint: output.checkpoint, unwind_to: 1, bad_block: None };
+        let output = runner.unwind(input).await.unwrap();
+        assert_eq!(output.checkpoint.block_number, 1);
+        assert_matches!(
+            output.checkpoint.account_hashing_stage_checkpoint(),
+            Some(AccountHashingCheckpoint { address: None, .. })
+        );
+        assert!(runner.validate_unwind(input).is_ok(), "unwind validation");
+    }
+
     mod test_utils {
         use super::*;
         use crate::{
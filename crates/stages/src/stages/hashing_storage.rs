@@ -1,4 +1,7 @@
-use crate::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use crate::{
+    BatchCheckpoint, BatchStart, ExecInput, ExecOutput, Stage, StageError, StageWriteBatcher,
+    UnwindInput, UnwindOutput,
+};
 use num_traits::Zero;
 use reth_db::{
     cursor::DbDupCursorRO,
@@ -14,7 +17,7 @@ use reth_primitives::{
         CheckpointBlockRange, EntitiesCheckpoint, StageCheckpoint, StageId,
         StorageHashingCheckpoint,
     },
-    StorageEntry,
+    Address, StorageEntry, B256,
 };
 use reth_provider::{DatabaseProviderRW, HashingWriter, StorageReader};
 use std::{collections::BTreeMap, fmt::Debug};
@@ -169,14 +172,53 @@ impl<DB: Database> Stage<DB> for StorageHashingStage {
                 return Ok(ExecOutput { checkpoint, done: false })
             }
         } else {
+            let block_range = from_block..=to_block;
+            let start = BatchStart::new(
+                input
+                    .checkpoint
+                    .and_then(|checkpoint| checkpoint.storage_hashing_stage_checkpoint())
+                    .and_then(batch_checkpoint),
+                &block_range,
+            );
+            if let BatchStart::Revert(range) = &start {
+                debug!(target: "sync::stages::storage_hashing::exec", ?range, "Reverting stale storage hashing batch");
+                provider.unwind_storage_hashing(BlockNumberAddress::range(range.clone()))?;
+            }
+
             // Aggregate all changesets and and make list of storages that have been
             // changed.
-            let lists = provider.changed_storages_with_range(from_block..=to_block)?;
-            // iterate over plain state and get newest storage value.
-            // Assumption we are okay with is that plain state represent
-            // `previous_stage_progress` state.
-            let storages = provider.plainstate_storages(lists)?;
-            provider.insert_storage_for_hashing(storages)?;
+            let lists = provider.changed_storages_with_range(block_range)?;
+            let slots = lists
+                .into_iter()
+                .flat_map(|(address, keys)| keys.into_iter().map(move |key| (address, key)));
+            let next_slot = StageWriteBatcher::new(self.commit_threshold).write_chunk(
+                slots,
+                start.key(),
+                |slots| {
+                    let mut lists = BTreeMap::<Address, Vec<B256>>::new();
+                    for (address, key) in slots {
+                        lists.entry(address).or_default().push(key);
+                    }
+                    // iterate over plain state and get newest storage value.
+                    // Assumption we are okay with is that plain state represent
+                    // `previous_stage_progress` state.
+                    let storages = provider.plainstate_storages(lists)?;
+                    provider.insert_storage_for_hashing(storages).map(|_| ())
+                },
+            )?;
+
+            if let Some((address, storage)) = next_slot {
+                let checkpoint = input.checkpoint().with_storage_hashing_stage_checkpoint(
+                    StorageHashingCheckpoint {
+                        address: Some(address),
+                        storage: Some(storage),
+                        block_range: CheckpointBlockRange { from: from_block, to: to_block },
+                        progress: stage_checkpoint_progress(provider)?,
+                    },
+                );
+
+                return Ok(ExecOutput { checkpoint, done: false })
+            }
         }
 
         // We finished the hashing stage, no future iterations is expected for the same block range,
@@ -196,14 +238,22 @@ impl<DB: Database> Stage<DB> for StorageHashingStage {
         provider: &DatabaseProviderRW<'_, &DB>,
         input: UnwindInput,
     ) -> Result<UnwindOutput, StageError> {
-        let (range, unwind_progress, _) =
+        let (mut range, unwind_progress, _) =
             input.unwind_block_range_with_threshold(self.commit_threshold);
 
-        provider.unwind_storage_hashing(BlockNumberAddress::range(range))?;
-
         let mut stage_checkpoint =
             input.checkpoint.storage_hashing_stage_checkpoint().unwrap_or_default();
 
+        // Also revert the committed chunks of a partially written batch on top of the checkpoint.
+        if let Some(batch) = batch_checkpoint(stage_checkpoint) {
+            range = batch.unwind_range(range);
+            stage_checkpoint.address = None;
+            stage_checkpoint.storage = None;
+            stage_checkpoint.block_range = CheckpointBlockRange::default();
+        }
+
+        provider.unwind_storage_hashing(BlockNumberAddress::range(range))?;
+
         stage_checkpoint.progress = stage_checkpoint_progress(provider)?;
 
         Ok(UnwindOutput {
@@ -213,6 +263,16 @@ impl<DB: Database> Stage<DB> for StorageHashingStage {
     }
 }
 
+/// Returns the sub-checkpoint of a partially written batch, if any.
+fn batch_checkpoint(
+    checkpoint: StorageHashingCheckpoint,
+) -> Option<BatchCheckpoint<(Address, B256)>> {
+    checkpoint
+        .address
+        .zip(checkpoint.storage)
+        .map(|next_key| BatchCheckpoint { next_key, block_range: checkpoint.block_range })
+}
+
 fn stage_checkpoint_progress<DB: Database>(
     provider: &DatabaseProviderRW<'_, &DB>,
 ) -> Result<EntitiesCheckpoint, DatabaseError> {
@@ -240,8 +300,9 @@ mod tests {
         generators::{random_block_range, random_contract_account_range},
     };
     use reth_primitives::{
-        stage::StageUnitCheckpoint, Address, SealedBlock, StorageEntry, B256, U256,
+        stage::StageUnitCheckpoint, Address, SealedBlock, StorageEntry, B256, MAINNET, U256,
     };
+    use reth_provider::ProviderFactory;
 
     stage_test_suite_ext!(StorageHashingTestRunner, storage_hashing);
 
@@ -453,6 +514,45 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    #[tokio::test]
+    async fn execute_incremental_storage_hashing_resumes_after_failure() {
+        let mut runner = StorageHashingTestRunner::default();
+        runner.set_commit_threshold(2);
+        let mut input =
+            ExecInput { target: Some(150), checkpoint: Some(StageCheckpoint::new(100)) };
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let output = runner.execute(input).await.unwrap().unwrap();
+        assert!(!output.done);
+        input.checkpoint = Some(output.checkpoint);
+        let committed = runner.tx.table::<tables::HashedStorage>().unwrap();
+
+        // the second chunk is lost, since the stage is killed before it is committed
+        {
+            let db = runner.tx.inner_raw();
+            let factory = ProviderFactory::new(db.as_ref(), MAINNET.clone());
+            let provider = factory.provider_rw().unwrap();
+            let output = runner.stage().execute(&provider, input).await.unwrap();
+            assert!(!output.done);
+        }
+        assert_eq!(runner.tx.table::<tables::HashedStorage>().unwrap(), committed);
+
+        // resume after the first chunk until all changed slots are hashed
+        loop {
+            let output = runner.execute(input).await.unwrap().unwrap();
+            let previous = input.checkpoint.and_then(|c| c.storage_hashing_stage_checkpoint());
+            input.checkpoint = Some(output.checkpoint);
+            if output.done {
+                assert_eq!(output.checkpoint.block_number, 150);
+                break
+            }
+            assert_ne!(output.checkpoint.storage_hashing_stage_checkpoint(), previous);
+        }
+
+        // Validate the stage execution
+        assert!(runner.check_hashed_storage().is_ok(), "execution validation");
+    }
+
     struct StorageHashingTestRunner {
         tx: TestTransaction,
         commit_threshold: u64,
@@ -170,6 +170,7 @@ where
 
             // NOTE: HeaderNumbers are not sorted and can't be inserted with cursor.
             tx.put::<tables::HeaderNumbers>(header_hash, header_number)?;
+            tx.put::<tables::TimestampBlockNumber>(header.timestamp, header_number)?;
             cursor_header.insert(header_number, header)?;
             cursor_canonical.insert(header_number, header_hash)?;
         }
@@ -330,6 +331,11 @@ where
         provider.unwind_table_by_walker::<tables::CanonicalHeaders, tables::HeaderNumbers>(
             input.unwind_to + 1,
         )?;
+        let tx = provider.tx_ref();
+        for entry in tx.cursor_read::<tables::Headers>()?.walk(Some(input.unwind_to + 1))? {
+            let (_, header) = entry?;
+            tx.delete::<tables::TimestampBlockNumber>(header.timestamp, None)?;
+        }
         provider.unwind_table_by_num::<tables::CanonicalHeaders>(input.unwind_to)?;
         let unwound_headers = provider.unwind_table_by_num::<tables::Headers>(input.unwind_to)?;
 
@@ -11,11 +11,13 @@ use reth_libmdbx::{
     DatabaseFlags, Environment, EnvironmentFlags, EnvironmentKind, Geometry, Mode, PageSize,
     SyncMode, RO, RW,
 };
-use std::{ops::Deref, path::Path};
+use std::{ops::Deref, path::Path, sync::Arc};
 use tx::Tx;
+use watchdog::{ReadTxnWatchdog, ReadTxnWatchdogConfig, TxnTracker};
 
 pub mod cursor;
 pub mod tx;
+pub mod watchdog;
 
 const GIGABYTE: usize = 1024 * 1024 * 1024;
 const TERABYTE: usize = GIGABYTE * 1024;
@@ -37,6 +39,8 @@ pub enum EnvKind {
 pub struct Env<E: EnvironmentKind> {
     /// Libmdbx-sys environment.
     pub inner: Environment<E>,
+    /// Watchdog of the long-lived read transactions, if enabled.
+    read_txn_watchdog: Option<Arc<ReadTxnWatchdog>>,
 }
 
 impl<'a, E: EnvironmentKind> DatabaseGAT<'a> for Env<E> {
@@ -46,14 +50,16 @@ impl<'a, E: EnvironmentKind> DatabaseGAT<'a> for Env<E> {
 
 impl<E: EnvironmentKind> Database for Env<E> {
     fn tx(&self) -> Result<<Self as DatabaseGAT<'_>>::TX, DatabaseError> {
-        Ok(Tx::new(
-            self.inner.begin_ro_txn().map_err(|e| DatabaseError::InitTransaction(e.into()))?,
-        ))
+        let inner =
+            self.inner.begin_ro_txn().map_err(|e| DatabaseError::InitTransaction(e.into()))?;
+        let tracker = TxnTracker::read(inner.id(), self.read_txn_watchdog.as_ref());
+        Ok(Tx::new_tracked(inner, tracker))
     }
 
     fn tx_mut(&self) -> Result<<Self as DatabaseGAT<'_>>::TXMut, DatabaseError> {
-        Ok(Tx::new(
+        Ok(Tx::new_tracked(
             self.inner.begin_rw_txn().map_err(|e| DatabaseError::InitTransaction(e.into()))?,
+            TxnTracker::read_write(),
         ))
     }
 }
@@ -120,12 +126,27 @@ impl<E: EnvironmentKind> Env<E> {
             }
         }
 
-        let env =
-            Env { inner: inner_env.open(path).map_err(|e| DatabaseError::FailedToOpen(e.into()))? };
+        let env = Env {
+            inner: inner_env.open(path).map_err(|e| DatabaseError::FailedToOpen(e.into()))?,
+            read_txn_watchdog: None,
+        };
 
         Ok(env)
     }
 
+    /// Enables the watchdog of the long-lived read transactions with the given configuration.
+    ///
+    /// Only read transactions opened after this call are tracked.
+    pub fn with_read_txn_watchdog(mut self, config: ReadTxnWatchdogConfig) -> Self {
+        self.read_txn_watchdog = Some(ReadTxnWatchdog::spawn(config));
+        self
+    }
+
+    /// Returns the watchdog of the long-lived read transactions, if enabled.
+    pub fn read_txn_watchdog(&self) -> Option<&Arc<ReadTxnWatchdog>> {
+        self.read_txn_watchdog.as_ref()
+    }
+
     /// Creates all the defined tables, if necessary.
     pub fn create_tables(&self) -> Result<(), DatabaseError> {
         let tx = self.inner.begin_rw_txn().map_err(|e| DatabaseError::InitTransaction(e.into()))?;
@@ -203,6 +224,35 @@ mod tests {
         create_test_db::<NoWriteMap>(EnvKind::RW);
     }
 
+    #[test]
+    fn db_read_txn_watchdog_aborts_long_lived_reader() {
+        let path = TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let env = create_test_db_with_path::<NoWriteMap>(EnvKind::RW, &path)
+            .with_read_txn_watchdog(ReadTxnWatchdogConfig {
+                max_duration: std::time::Duration::from_millis(100),
+                abort: true,
+            });
+        let watchdog = env.read_txn_watchdog().unwrap().clone();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(1), Ok(None));
+
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        assert_eq!(watchdog.reported(), 1);
+        assert_eq!(
+            tx.get::<Headers>(1),
+            Err(DatabaseError::ReadTransactionTimeout {
+                txn_id: tx.id(),
+                max_duration: std::time::Duration::from_millis(100),
+            })
+        );
+        drop(tx);
+
+        // new read transactions are not affected
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(1), Ok(None));
+    }
+
     #[test]
     fn db_manual_put_get() {
         let env = create_test_db::<NoWriteMap>(EnvKind::RW);
@@ -1,6 +1,6 @@
 //! Transaction wrapper for libmdbx-sys.
 
-use super::cursor::Cursor;
+use super::{cursor::Cursor, watchdog::TxnTracker};
 use crate::{
     table::{Compress, DupSort, Encode, Table, TableImporter},
     tables::{utils::decode_one, Tables, NUM_TABLES},
@@ -20,6 +20,8 @@ pub struct Tx<'a, K: TransactionKind, E: EnvironmentKind> {
     pub inner: Transaction<'a, K, E>,
     /// Database table handle cache
     pub db_handles: Arc<RwLock<[Option<DBI>; NUM_TABLES]>>,
+    /// Records the transaction metrics and tracks read transactions in the watchdog.
+    tracker: Option<TxnTracker>,
 }
 
 impl<'env, K: TransactionKind, E: EnvironmentKind> Tx<'env, K, E> {
@@ -28,7 +30,15 @@ impl<'env, K: TransactionKind, E: EnvironmentKind> Tx<'env, K, E> {
     where
         'a: 'env,
     {
-        Self { inner, db_handles: Default::default() }
+        Self { inner, db_handles: Default::default(), tracker: None }
+    }
+
+    /// Creates new `Tx` object that records its metrics with the given tracker.
+    pub(crate) fn new_tracked<'a>(inner: Transaction<'a, K, E>, tracker: TxnTracker) -> Self
+    where
+        'a: 'env,
+    {
+        Self { inner, db_handles: Default::default(), tracker: Some(tracker) }
     }
 
     /// Gets this transaction ID.
@@ -37,7 +47,14 @@ impl<'env, K: TransactionKind, E: EnvironmentKind> Tx<'env, K, E> {
     }
 
     /// Gets a table database handle if it exists, otherwise creates it.
+    ///
+    /// Returns [DatabaseError::ReadTransactionTimeout] if the transaction was aborted by the read
+    /// transaction watchdog.
     pub fn get_dbi<T: Table>(&self) -> Result<DBI, DatabaseError> {
+        if let Some(tracker) = &self.tracker {
+            tracker.ensure_not_timed_out()?;
+        }
+
         let mut handles = self.db_handles.write();
 
         let table = Tables::from_str(T::NAME).expect("Requested table should be part of `Tables`.");
@@ -0,0 +1,230 @@
+//! Watchdog for long-lived read transactions.
+
+use crate::DatabaseError;
+use parking_lot::Mutex;
+use reth_metrics::metrics::{decrement_gauge, histogram, increment_counter, increment_gauge};
+use reth_tracing::tracing::warn;
+use std::{
+    backtrace::Backtrace,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+/// The default duration after which a read transaction is considered long-lived.
+pub const DEFAULT_MAX_READ_TRANSACTION_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// The maximum interval at which the watchdog checks the open read transactions.
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration of the [ReadTxnWatchdog].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadTxnWatchdogConfig {
+    /// The duration after which an open read transaction is reported.
+    pub max_duration: Duration,
+    /// Whether a read transaction that was open longer than `max_duration` is aborted, i.e. all
+    /// further operations on it fail with [DatabaseError::ReadTransactionTimeout].
+    pub abort: bool,
+}
+
+impl Default for ReadTxnWatchdogConfig {
+    fn default() -> Self {
+        Self { max_duration: DEFAULT_MAX_READ_TRANSACTION_DURATION, abort: false }
+    }
+}
+
+/// Tracks the open read transactions of an environment and reports the ones that are open longer
+/// than the configured maximum duration.
+///
+/// Long-lived readers prevent MDBX from reusing the pages freed by later write transactions, which
+/// manifests as growth of the database file. The reports include the backtrace captured when the
+/// transaction was opened in debug builds.
+///
+/// MDBX transactions can only be used by the thread that opened them, so a timed out transaction is
+/// not aborted by the watchdog itself. Instead, it is marked as timed out and its next operation
+/// fails, so the caller drops it and can retry with a new transaction.
+#[derive(Debug)]
+pub struct ReadTxnWatchdog {
+    config: ReadTxnWatchdogConfig,
+    /// All open read transactions, by their key.
+    readers: Mutex<HashMap<u64, Arc<OpenReader>>>,
+    /// The key of the next registered read transaction.
+    next_key: AtomicU64,
+    /// The number of read transactions that were reported.
+    reported: AtomicU64,
+}
+
+impl ReadTxnWatchdog {
+    /// Creates a new watchdog and spawns the thread that periodically checks the open read
+    /// transactions.
+    ///
+    /// The thread exits once the returned watchdog is dropped.
+    pub fn spawn(config: ReadTxnWatchdogConfig) -> Arc<Self> {
+        let watchdog = Arc::new(Self {
+            config,
+            readers: Default::default(),
+            next_key: Default::default(),
+            reported: Default::default(),
+        });
+
+        let interval = (config.max_duration / 4).min(MAX_CHECK_INTERVAL);
+        let weak = Arc::downgrade(&watchdog);
+        std::thread::Builder::new()
+            .name("mdbx-read-txn-watchdog".to_string())
+            .spawn(move || Self::run(weak, interval))
+            .expect("failed to spawn read transaction watchdog thread");
+
+        watchdog
+    }
+
+    fn run(watchdog: Weak<Self>, interval: Duration) {
+        loop {
+            std::thread::sleep(interval);
+            match watchdog.upgrade() {
+                Some(watchdog) => watchdog.check(),
+                None => return,
+            }
+        }
+    }
+
+    /// Returns the configuration of the watchdog.
+    pub fn config(&self) -> &ReadTxnWatchdogConfig {
+        &self.config
+    }
+
+    /// Returns the number of read transactions that were reported as long-lived.
+    pub fn reported(&self) -> u64 {
+        self.reported.load(Ordering::Relaxed)
+    }
+
+    /// Reports all open read transactions that are open longer than the maximum duration and were
+    /// not reported yet, and marks them as timed out if configured.
+    pub fn check(&self) {
+        let readers = self.readers.lock();
+        for reader in readers.values() {
+            let open_for = reader.opened_at.elapsed();
+            if open_for < self.config.max_duration || reader.reported.swap(true, Ordering::Relaxed)
+            {
+                continue
+            }
+
+            self.reported.fetch_add(1, Ordering::Relaxed);
+            increment_counter!("tx.long_read_transactions");
+            match &reader.backtrace {
+                Some(backtrace) => warn!(
+                    target: "storage::db::mdbx",
+                    txn_id = reader.txn_id,
+                    ?open_for,
+                    abort = self.config.abort,
+                    %backtrace,
+                    "Long-lived read transaction"
+                ),
+                None => warn!(
+                    target: "storage::db::mdbx",
+                    txn_id = reader.txn_id,
+                    ?open_for,
+                    abort = self.config.abort,
+                    "Long-lived read transaction"
+                ),
+            }
+
+            if self.config.abort {
+                reader.timed_out.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Registers a new read transaction with the given id.
+    fn register(self: &Arc<Self>, txn_id: u64) -> ReaderGuard {
+        let reader = Arc::new(OpenReader {
+            txn_id,
+            opened_at: Instant::now(),
+            backtrace: cfg!(debug_assertions).then(Backtrace::force_capture),
+            reported: AtomicBool::new(false),
+            timed_out: AtomicBool::new(false),
+        });
+        let key = self.next_key.fetch_add(1, Ordering::Relaxed);
+        self.readers.lock().insert(key, reader.clone());
+        ReaderGuard { watchdog: self.clone(), key, reader }
+    }
+}
+
+/// A read transaction that is tracked by the [ReadTxnWatchdog].
+#[derive(Debug)]
+struct OpenReader {
+    txn_id: u64,
+    opened_at: Instant,
+    /// The backtrace of the code that opened the transaction, only captured in debug builds.
+    backtrace: Option<Backtrace>,
+    /// Whether the transaction was reported as long-lived.
+    reported: AtomicBool,
+    /// Whether the transaction was aborted by the watchdog.
+    timed_out: AtomicBool,
+}
+
+/// Unregisters a read transaction from the [ReadTxnWatchdog] when dropped.
+#[derive(Debug)]
+struct ReaderGuard {
+    watchdog: Arc<ReadTxnWatchdog>,
+    key: u64,
+    reader: Arc<OpenReader>,
+}
+
+impl Drop for ReaderGuard {
+    fn drop(&mut self) {
+        self.watchdog.readers.lock().remove(&self.key);
+    }
+}
+
+/// Records the metrics of a transaction while it is open, and registers read transactions with
+/// the [ReadTxnWatchdog], if any.
+#[derive(Debug)]
+pub(crate) struct TxnTracker {
+    /// Whether this is a read-write transaction.
+    is_read_write: bool,
+    opened_at: Instant,
+    reader: Option<ReaderGuard>,
+}
+
+impl TxnTracker {
+    /// Tracks a new read transaction with the given id.
+    pub(crate) fn read(txn_id: u64, watchdog: Option<&Arc<ReadTxnWatchdog>>) -> Self {
+        increment_gauge!("tx.open_read_transactions", 1.0);
+        Self {
+            is_read_write: false,
+            opened_at: Instant::now(),
+            reader: watchdog.map(|watchdog| watchdog.register(txn_id)),
+        }
+    }
+
+    /// Tracks a new read-write transaction.
+    pub(crate) fn read_write() -> Self {
+        Self { is_read_write: true, opened_at: Instant::now(), reader: None }
+    }
+
+    /// Returns an error if the watchdog aborted the transaction.
+    pub(crate) fn ensure_not_timed_out(&self) -> Result<(), DatabaseError> {
+        match &self.reader {
+            Some(guard) if guard.reader.timed_out.load(Ordering::Relaxed) => {
+                Err(DatabaseError::ReadTransactionTimeout {
+                    txn_id: guard.reader.txn_id,
+                    max_duration: guard.watchdog.config.max_duration,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for TxnTracker {
+    fn drop(&mut self) {
+        let mode = if self.is_read_write { "read-write" } else { "read" };
+        histogram!("tx.open_duration_seconds", self.opened_at.elapsed(), "mode" => mode);
+        if !self.is_read_write {
+            decrement_gauge!("tx.open_read_transactions", 1.0);
+        }
+    }
+}
@@ -119,7 +119,8 @@ pub fn init_db<P: AsRef<Path>>(path: P, log_level: Option<LogLevel>) -> eyre::Re
     }
     #[cfg(feature = "mdbx")]
     {
-        let db = DatabaseEnv::open(rpath, EnvKind::RW, log_level)?;
+        let db = DatabaseEnv::open(rpath, EnvKind::RW, log_level)?
+            .with_read_txn_watchdog(Default::default());
         db.create_tables()?;
         Ok(db)
     }
@@ -134,6 +135,7 @@ pub fn open_db_read_only(path: &Path, log_level: Option<LogLevel>) -> eyre::Resu
     #[cfg(feature = "mdbx")]
     {
         Env::<NoWriteMap>::open(path, EnvKind::RO, log_level)
+            .map(|db| db.with_read_txn_watchdog(Default::default()))
             .with_context(|| format!("Could not open database at path: {}", path.display()))
     }
     #[cfg(not(feature = "mdbx"))]
@@ -148,6 +150,7 @@ pub fn open_db(path: &Path, log_level: Option<LogLevel>) -> eyre::Result<Databas
     #[cfg(feature = "mdbx")]
     {
         Env::<WriteMap>::open(path, EnvKind::RW, log_level)
+            .map(|db| db.with_read_txn_watchdog(Default::default()))
             .with_context(|| format!("Could not open database at path: {}", path.display()))
     }
     #[cfg(not(feature = "mdbx"))]
@@ -51,7 +51,7 @@ pub enum TableType {
 }
 
 /// Number of tables that should be present inside database.
-pub const NUM_TABLES: usize = 26;
+pub const NUM_TABLES: usize = 28;
 
 /// The general purpose of this is to use with a combination of Tables enum,
 /// by implementing a `TableViewer` trait you can operate on db tables in an abstract way.
@@ -184,7 +184,9 @@ tables!([
     (TxSenders, TableType::Table),
     (SyncStage, TableType::Table),
     (SyncStageProgress, TableType::Table),
-    (PruneCheckpoints, TableType::Table)
+    (PruneCheckpoints, TableType::Table),
+    (TimestampBlockNumber, TableType::Table),
+    (StorageMetadata, TableType::Table)
 ]);
 
 #[macro_export]
@@ -421,12 +423,26 @@ table!(
     ( PruneCheckpoints ) PruneSegment | PruneCheckpoint
 );
 
+table!(
+    /// Stores the number of the canonical block with the given timestamp.
+    ( TimestampBlockNumber ) BlockTimestamp | BlockNumber
+);
+
+table!(
+    /// Stores the storage version of the database and the checkpoints of unfinished migrations.
+    ( StorageMetadata ) MetadataKey | Vec<u8>
+);
+
 /// Alias Types
 
 /// List with transaction numbers.
 pub type BlockNumberList = IntegerList;
 /// Encoded stage id.
 pub type StageId = String;
+/// Block timestamp.
+pub type BlockTimestamp = u64;
+/// Key of an entry in [StorageMetadata].
+pub type MetadataKey = String;
 
 #[cfg(test)]
 mod tests {
@@ -461,6 +477,8 @@ mod tests {
         (TableType::Table, SyncStage::const_name()),
         (TableType::Table, SyncStageProgress::const_name()),
         (TableType::Table, PruneCheckpoints::const_name()),
+        (TableType::Table, TimestampBlockNumber::const_name()),
+        (TableType::Table, StorageMetadata::const_name()),
     ];
 
     #[test]
@@ -13,6 +13,7 @@ use reth_primitives::{
 };
 use reth_trie::{
     hashed_cursor::{HashedPostState, HashedPostStateCursorFactory, HashedStorage},
+    updates::TrieUpdates,
     StateRoot, StateRootError,
 };
 use revm::{db::states::BundleState, primitives::AccountInfo};
@@ -94,6 +95,11 @@ impl BundleStateWithReceipts {
         &self.bundle
     }
 
+    /// Consumes the type and returns the bundle state, the receipts and the first block.
+    pub fn into_inner(self) -> (BundleState, Receipts, BlockNumber) {
+        (self.bundle, self.receipts, self.first_block)
+    }
+
     /// Set first block.
     pub fn set_first_block(&mut self, first_block: BlockNumber) {
         self.first_block = first_block;
@@ -205,6 +211,25 @@ impl BundleStateWithReceipts {
             .root()
     }
 
+    /// Calculate the state root for this [BundleState] like [Self::state_root_slow], and collect
+    /// the trie updates that lead to it.
+    ///
+    /// The trie updates can be written to the database together with this state, instead of
+    /// recomputing the changed parts of the trie.
+    pub fn state_root_slow_with_updates<TX: DbTx>(
+        &self,
+        tx: &TX,
+    ) -> Result<(B256, TrieUpdates), StateRootError> {
+        let hashed_post_state = self.hash_state_slow();
+        let (account_prefix_set, storage_prefix_set) = hashed_post_state.construct_prefix_sets();
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(tx, &hashed_post_state);
+        StateRoot::new(tx)
+            .with_hashed_cursor_factory(hashed_cursor_factory)
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_set)
+            .root_with_updates()
+    }
+
     /// Transform block number to the index of block.
     fn block_number_to_index(&self, block_number: BlockNumber) -> Option<usize> {
         if self.first_block > block_number {
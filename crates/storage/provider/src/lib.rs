@@ -31,8 +31,11 @@ pub use traits::{
 /// Provider trait implementations.
 pub mod providers;
 pub use providers::{
-    DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW, HistoricalStateProvider,
-    HistoricalStateProviderRef, LatestStateProvider, LatestStateProviderRef, ProviderFactory,
+    migrations, CachedStateProvider, DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW,
+    HistoricalStateProvider, HistoricalStateProviderRef, LatestStateProvider,
+    LatestStateProviderRef, Migration, MigrationError, MigrationProgress, ProviderFactory,
+    StateCache, StateSnapshotError, StateSnapshotHeader, TimestampIndexMigration,
+    STATE_SNAPSHOT_VERSION, STORAGE_VERSION,
 };
 
 #[cfg(any(test, feature = "test-utils"))]
@@ -3,7 +3,11 @@ use crate::{
     StateProvider, StateRootProvider,
 };
 use reth_interfaces::{provider::ProviderError, RethResult};
-use reth_primitives::{trie::AccountProof, Account, Address, BlockNumber, Bytecode, B256};
+use reth_primitives::{
+    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageEntry, B256, U256,
+};
+use reth_trie::updates::TrieUpdates;
+use std::collections::BTreeMap;
 
 /// A state provider that either resolves to data in a wrapped [`crate::BundleStateWithReceipts`],
 /// or an underlying state provider.
@@ -64,6 +68,15 @@ impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateRootProvider
         state.extend(post_state.clone());
         self.state_provider.state_root(&state)
     }
+
+    fn state_root_with_updates(
+        &self,
+        post_state: &BundleStateWithReceipts,
+    ) -> RethResult<(B256, TrieUpdates)> {
+        let mut state = self.post_state_data_provider.state().clone();
+        state.extend(post_state.clone());
+        self.state_provider.state_root_with_updates(&state)
+    }
 }
 
 impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateProvider
@@ -95,4 +108,33 @@ impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateProvider
     fn proof(&self, _address: Address, _keys: &[B256]) -> RethResult<AccountProof> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
     }
+
+    fn storage_entries(&self, account: Address) -> RethResult<Vec<StorageEntry>> {
+        let bundle_account = self.post_state_data_provider.state().state().account(&account);
+
+        // the storage of the underlying state is wiped if the account was destroyed
+        let mut storage = BTreeMap::new();
+        if !bundle_account.is_some_and(|account| account.status.was_destroyed()) {
+            storage.extend(
+                self.state_provider
+                    .storage_entries(account)?
+                    .into_iter()
+                    .map(|entry| (entry.key, entry.value)),
+            );
+        }
+        if let Some(bundle_account) = bundle_account {
+            storage.extend(
+                bundle_account
+                    .storage
+                    .iter()
+                    .map(|(slot, value)| (B256::from(slot.to_be_bytes()), value.present_value)),
+            );
+        }
+
+        Ok(storage
+            .into_iter()
+            .filter(|(_, value)| *value != U256::ZERO)
+            .map(|(key, value)| StorageEntry { key, value })
+            .collect())
+    }
 }
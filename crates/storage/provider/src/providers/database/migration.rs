@@ -0,0 +1,358 @@
+//! Migrations of the data stored in the database.
+//!
+//! The layout of the stored data is versioned by the storage version in the
+//! [StorageMetadata](tables::StorageMetadata) table, which is missing for databases created before
+//! the first migration, i.e. version zero. Every [Migration] upgrades the database to the next
+//! version, and all migrations of versions newer than the one on disk are run in order by
+//! [ProviderFactory::migrate].
+//!
+//! A migration runs in batches, each committed in its own transaction together with the progress
+//! checkpoint of the migration, so an interrupted migration resumes from the last committed batch.
+
+use crate::{DatabaseProvider, DatabaseProviderRW, ProviderFactory};
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_interfaces::{RethError, RethResult};
+use reth_primitives::BlockNumber;
+use std::fmt::Debug;
+use tracing::info;
+
+/// The storage version of a database that was fully migrated by this version of reth.
+pub const STORAGE_VERSION: u64 = 1;
+
+/// The key of the storage version in the [StorageMetadata](tables::StorageMetadata) table.
+const STORAGE_VERSION_KEY: &str = "StorageVersion";
+
+/// Errors that can occur while migrating the database.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    /// The database was written by a newer version of reth.
+    #[error(
+        "database storage version v{version} is newer than v{supported} supported by this version \
+         of reth, downgrades are not supported"
+    )]
+    Downgrade {
+        /// The storage version of the database.
+        version: u64,
+        /// The latest storage version supported by this version of reth.
+        supported: u64,
+    },
+    /// A migration failed, it resumes from its last checkpoint when run again.
+    #[error("migration to storage version v{version} failed: {error}")]
+    Failed {
+        /// The storage version the migration upgrades to.
+        version: u64,
+        /// The error of the migration.
+        error: RethError,
+    },
+    /// Failed to read or write the storage version.
+    #[error(transparent)]
+    Provider(#[from] RethError),
+}
+
+/// The progress of a [Migration] after a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationProgress {
+    /// The migration has more batches to run.
+    InProgress,
+    /// The migration is complete.
+    Done,
+}
+
+/// A migration of the stored data to the next storage version.
+pub trait Migration<DB: Database>: Debug + Send + Sync {
+    /// The storage version of the database after the migration.
+    fn version(&self) -> u64;
+
+    /// Runs the next batch of the migration.
+    ///
+    /// The migration must save its progress with [DatabaseProvider::save_migration_checkpoint]
+    /// using the given provider, which is committed together with the changes of the batch. The
+    /// batch must be idempotent, since the migration starts from the last committed checkpoint
+    /// after an interruption.
+    fn up(&self, provider: &DatabaseProviderRW<'_, DB>) -> RethResult<MigrationProgress>;
+}
+
+/// Returns all migrations, ordered by their storage version.
+pub fn migrations<DB: Database>() -> Vec<Box<dyn Migration<DB>>> {
+    vec![Box::<TimestampIndexMigration>::default()]
+}
+
+impl<TX: DbTx> DatabaseProvider<TX> {
+    /// Returns the storage version of the database, zero if none was written.
+    pub fn storage_version(&self) -> RethResult<u64> {
+        let Some(version) =
+            self.tx_ref().get::<tables::StorageMetadata>(STORAGE_VERSION_KEY.to_string())?
+        else {
+            return Ok(0)
+        };
+        let version = version
+            .try_into()
+            .map(u64::from_be_bytes)
+            .map_err(|_| RethError::Custom("malformed storage version".to_string()))?;
+        Ok(version)
+    }
+
+    /// Returns the checkpoint of the unfinished migration to the given storage version.
+    pub fn get_migration_checkpoint(&self, version: u64) -> RethResult<Option<Vec<u8>>> {
+        Ok(self.tx_ref().get::<tables::StorageMetadata>(migration_checkpoint_key(version))?)
+    }
+}
+
+impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
+    /// Saves the storage version of the database.
+    pub fn save_storage_version(&self, version: u64) -> RethResult<()> {
+        Ok(self.tx_ref().put::<tables::StorageMetadata>(
+            STORAGE_VERSION_KEY.to_string(),
+            version.to_be_bytes().to_vec(),
+        )?)
+    }
+
+    /// Saves the checkpoint of the migration to the given storage version.
+    pub fn save_migration_checkpoint(&self, version: u64, checkpoint: Vec<u8>) -> RethResult<()> {
+        Ok(self
+            .tx_ref()
+            .put::<tables::StorageMetadata>(migration_checkpoint_key(version), checkpoint)?)
+    }
+
+    /// Removes the checkpoint of the completed migration to the given storage version, and saves
+    /// the version as the storage version of the database.
+    fn finish_migration(&self, version: u64) -> RethResult<()> {
+        self.tx_ref().delete::<tables::StorageMetadata>(migration_checkpoint_key(version), None)?;
+        self.save_storage_version(version)
+    }
+}
+
+fn migration_checkpoint_key(version: u64) -> String {
+    format!("MigrationCheckpoint{version}")
+}
+
+impl<DB: Database> ProviderFactory<DB> {
+    /// Runs all [migrations] the database is behind, see [ProviderFactory::migrate_with].
+    pub fn migrate(&self) -> Result<u64, MigrationError> {
+        self.migrate_with(&migrations())
+    }
+
+    /// Runs the given migrations whose storage version is newer than the storage version of the
+    /// database, in order, and returns the storage version of the database afterwards.
+    ///
+    /// Fails with [MigrationError::Downgrade] if the database has a newer storage version than the
+    /// last migration, i.e. it was written by a newer version of reth.
+    pub fn migrate_with(
+        &self,
+        migrations: &[Box<dyn Migration<DB>>],
+    ) -> Result<u64, MigrationError> {
+        let mut version = self.provider()?.storage_version()?;
+        let supported = migrations.last().map_or(0, |migration| migration.version());
+        if version > supported {
+            return Err(MigrationError::Downgrade { version, supported })
+        }
+
+        for migration in migrations.iter().filter(|migration| migration.version() > version) {
+            let target = migration.version();
+            info!(target: "providers::db", from = version, to = target, "Migrating database");
+            loop {
+                let provider = self.provider_rw()?;
+                let progress = migration
+                    .up(&provider)
+                    .map_err(|error| MigrationError::Failed { version: target, error })?;
+                if progress == MigrationProgress::Done {
+                    provider.finish_migration(target)?;
+                }
+                provider.commit()?;
+
+                if progress == MigrationProgress::Done {
+                    break
+                }
+            }
+            info!(target: "providers::db", version = target, "Migrated database");
+            version = target;
+        }
+
+        Ok(version)
+    }
+}
+
+/// Populates the [TimestampBlockNumber](tables::TimestampBlockNumber) index from the headers of
+/// the canonical chain.
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampIndexMigration {
+    /// The number of blocks indexed per batch.
+    pub batch_size: u64,
+}
+
+impl Default for TimestampIndexMigration {
+    fn default() -> Self {
+        Self { batch_size: 100_000 }
+    }
+}
+
+impl<DB: Database> Migration<DB> for TimestampIndexMigration {
+    fn version(&self) -> u64 {
+        1
+    }
+
+    fn up(&self, provider: &DatabaseProviderRW<'_, DB>) -> RethResult<MigrationProgress> {
+        let version = Migration::<DB>::version(self);
+        let start = provider
+            .get_migration_checkpoint(version)?
+            .and_then(|checkpoint| checkpoint.try_into().ok())
+            .map_or(0, BlockNumber::from_be_bytes);
+
+        let tx = provider.tx_ref();
+        let mut headers = tx.cursor_read::<tables::Headers>()?;
+        let mut next = None;
+        for entry in headers.walk(Some(start))? {
+            let (number, header) = entry?;
+            if number >= start + self.batch_size {
+                next = Some(number);
+                break
+            }
+            if tx.get::<tables::CanonicalHeaders>(number)?.is_some() {
+                tx.put::<tables::TimestampBlockNumber>(header.timestamp, number)?;
+            }
+        }
+
+        match next {
+            Some(next) => {
+                provider.save_migration_checkpoint(version, next.to_be_bytes().to_vec())?;
+                Ok(MigrationProgress::InProgress)
+            }
+            None => Ok(MigrationProgress::Done),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{test_utils::create_test_rw_db, DatabaseEnv};
+    use reth_primitives::{Header, MAINNET};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Fails every batch after the given number of batches.
+    #[derive(Debug)]
+    struct InterruptedMigration {
+        inner: TimestampIndexMigration,
+        batches: AtomicUsize,
+        fail_after: usize,
+    }
+
+    impl<DB: Database> Migration<DB> for InterruptedMigration {
+        fn version(&self) -> u64 {
+            Migration::<DB>::version(&self.inner)
+        }
+
+        fn up(&self, provider: &DatabaseProviderRW<'_, DB>) -> RethResult<MigrationProgress> {
+            if self.batches.fetch_add(1, Ordering::SeqCst) >= self.fail_after {
+                return Err(RethError::Custom("interrupted".to_string()))
+            }
+            self.inner.up(provider)
+        }
+    }
+
+    fn insert_headers<DB: Database>(factory: &ProviderFactory<DB>, count: u64) {
+        let provider = factory.provider_rw().unwrap();
+        for number in 0..count {
+            let header = Header { number, timestamp: 1_000 + number * 12, ..Default::default() };
+            let hash = header.clone().seal_slow().hash;
+            provider.tx_ref().put::<tables::CanonicalHeaders>(number, hash).unwrap();
+            provider.tx_ref().put::<tables::Headers>(number, header).unwrap();
+        }
+        provider.commit().unwrap();
+    }
+
+    fn timestamp_index<DB: Database>(factory: &ProviderFactory<DB>) -> Vec<(u64, BlockNumber)> {
+        let provider = factory.provider().unwrap();
+        let mut cursor = provider.tx_ref().cursor_read::<tables::TimestampBlockNumber>().unwrap();
+        cursor.walk(None).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn resumes_interrupted_migration() {
+        let factory = ProviderFactory::new(create_test_rw_db(), MAINNET.clone());
+        insert_headers(&factory, 10);
+        let expected = (0..10).map(|number| (1_000 + number * 12, number)).collect::<Vec<_>>();
+
+        // the migration fails after the first of three batches
+        let interrupted: Vec<Box<dyn Migration<_>>> = vec![Box::new(InterruptedMigration {
+            inner: TimestampIndexMigration { batch_size: 4 },
+            batches: AtomicUsize::new(0),
+            fail_after: 1,
+        })];
+        assert!(matches!(
+            factory.migrate_with(&interrupted),
+            Err(MigrationError::Failed { version: 1, .. })
+        ));
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.storage_version().unwrap(), 0);
+        assert_eq!(
+            provider.get_migration_checkpoint(1).unwrap(),
+            Some(4u64.to_be_bytes().to_vec())
+        );
+        drop(provider);
+        assert_eq!(timestamp_index(&factory), expected[..4]);
+
+        // restarting completes the migration from the checkpoint
+        let migrations: Vec<Box<dyn Migration<_>>> =
+            vec![Box::new(TimestampIndexMigration { batch_size: 4 })];
+        assert_eq!(factory.migrate_with(&migrations).unwrap(), 1);
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.storage_version().unwrap(), 1);
+        assert_eq!(provider.get_migration_checkpoint(1).unwrap(), None);
+        drop(provider);
+        assert_eq!(timestamp_index(&factory), expected);
+
+        // running the migrations again doesn't run the completed migration
+        let interrupted: Vec<Box<dyn Migration<_>>> = vec![Box::new(InterruptedMigration {
+            inner: TimestampIndexMigration { batch_size: 4 },
+            batches: AtomicUsize::new(0),
+            fail_after: 0,
+        })];
+        assert_eq!(factory.migrate_with(&interrupted).unwrap(), 1);
+        assert_eq!(factory.migrate().unwrap(), STORAGE_VERSION);
+        assert_eq!(timestamp_index(&factory), expected);
+    }
+
+    #[test]
+    fn rerunning_a_batch_is_idempotent() {
+        let factory = ProviderFactory::new(create_test_rw_db(), MAINNET.clone());
+        insert_headers(&factory, 3);
+        let migration = TimestampIndexMigration::default();
+
+        for _ in 0..2 {
+            let provider = factory.provider_rw().unwrap();
+            assert_eq!(migration.up(&provider).unwrap(), MigrationProgress::Done);
+            provider.commit().unwrap();
+        }
+        assert_eq!(timestamp_index(&factory), vec![(1_000, 0), (1_012, 1), (1_024, 2)]);
+    }
+
+    #[test]
+    fn refuses_downgrade() {
+        let factory = ProviderFactory::new(create_test_rw_db(), MAINNET.clone());
+        let provider = factory.provider_rw().unwrap();
+        provider.save_storage_version(STORAGE_VERSION + 1).unwrap();
+        provider.commit().unwrap();
+
+        assert!(matches!(
+            factory.migrate(),
+            Err(MigrationError::Downgrade { version, supported: STORAGE_VERSION })
+                if version == STORAGE_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn migrations_are_ordered() {
+        let migrations = migrations::<Arc<DatabaseEnv>>();
+        assert!(migrations.windows(2).all(|pair| pair[0].version() < pair[1].version()));
+        assert_eq!(migrations.last().unwrap().version(), STORAGE_VERSION);
+    }
+}
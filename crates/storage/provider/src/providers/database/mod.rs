@@ -24,6 +24,15 @@ use tracing::trace;
 mod provider;
 pub use provider::{DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW};
 
+mod migration;
+pub use migration::{
+    migrations, Migration, MigrationError, MigrationProgress, TimestampIndexMigration,
+    STORAGE_VERSION,
+};
+
+mod state_snapshot;
+pub use state_snapshot::{StateSnapshotError, StateSnapshotHeader, STATE_SNAPSHOT_VERSION};
+
 /// A common provider that fetches data from a database.
 ///
 /// This provider implements most provider or provider factory traits.
@@ -40,7 +40,7 @@ use reth_primitives::{
     TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash, TxHash, TxNumber,
     Withdrawal, B256, U256,
 };
-use reth_trie::{prefix_set::PrefixSetMut, StateRoot};
+use reth_trie::{prefix_set::PrefixSetMut, updates::TrieUpdates, StateRoot};
 use revm::primitives::{BlockEnv, CfgEnv, SpecId};
 use std::{
     collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet},
@@ -559,6 +559,10 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
                     header_number_cursor.delete_current()?;
                 }
             }
+            // rm TimestampBlockNumber
+            for (_, header) in block_headers.iter() {
+                self.tx.delete::<tables::TimestampBlockNumber>(header.timestamp, None)?;
+            }
         }
 
         // merge all into block
@@ -730,6 +734,83 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         Ok((deleted, walker.next().transpose()?.is_none()))
     }
 
+    /// Hashes the accounts and storages changed in the range into the hashed state tables.
+    ///
+    /// Returns the prefix sets of the changed account and storage trie nodes, and the hashed
+    /// addresses of the destroyed accounts.
+    #[allow(clippy::type_complexity)]
+    fn insert_hashes_for_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> RethResult<(PrefixSetMut, HashMap<B256, PrefixSetMut>, HashSet<B256>)> {
+        // Initialize prefix sets.
+        let mut account_prefix_set = PrefixSetMut::default();
+        let mut storage_prefix_set: HashMap<B256, PrefixSetMut> = HashMap::default();
+        let mut destroyed_accounts = HashSet::default();
+
+        // storage hashing stage
+        {
+            let lists = self.changed_storages_with_range(range.clone())?;
+            let storages = self.plainstate_storages(lists)?;
+            let storage_entries = self.insert_storage_for_hashing(storages)?;
+            for (hashed_address, hashed_slots) in storage_entries {
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
+                for slot in hashed_slots {
+                    storage_prefix_set
+                        .entry(hashed_address)
+                        .or_default()
+                        .insert(Nibbles::unpack(slot));
+                }
+            }
+        }
+
+        // account hashing stage
+        {
+            let lists = self.changed_accounts_with_range(range.clone())?;
+            let accounts = self.basic_accounts(lists)?;
+            let hashed_addresses = self.insert_account_for_hashing(accounts)?;
+            for (hashed_address, account) in hashed_addresses {
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
+                if account.is_none() {
+                    destroyed_accounts.insert(hashed_address);
+                }
+            }
+        }
+
+        Ok((account_prefix_set, storage_prefix_set, destroyed_accounts))
+    }
+
+    /// Writes the hashed state of the range, and the trie updates that were computed for it
+    /// beforehand instead of recomputing them.
+    ///
+    /// The trie updates must lead from the trie of the parent of the range to the trie of
+    /// `end_block_hash`. The root of the written trie is verified against the expected state root.
+    pub fn insert_hashes_with_trie_updates(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        end_block_hash: B256,
+        expected_state_root: B256,
+        trie_updates: TrieUpdates,
+    ) -> RethResult<()> {
+        self.insert_hashes_for_range(range.clone())?;
+        trie_updates.flush(&self.tx)?;
+
+        // nothing is marked as changed, so only the nodes that are missing in the trie tables are
+        // computed
+        let state_root =
+            StateRoot::new(&self.tx).root().map_err(Into::<reth_db::DatabaseError>::into)?;
+        if state_root != expected_state_root {
+            return Err(ProviderError::StateRootMismatch {
+                got: state_root,
+                expected: expected_state_root,
+                block_number: *range.end(),
+                block_hash: end_block_hash,
+            }
+            .into())
+        }
+        Ok(())
+    }
+
     /// Load shard and remove it. If list is empty, last shard was full or
     /// there are no shards at all.
     fn take_shard<T>(&self, key: T::Key) -> RethResult<Vec<u64>>
@@ -1535,39 +1616,8 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
         end_block_hash: B256,
         expected_state_root: B256,
     ) -> RethResult<()> {
-        // Initialize prefix sets.
-        let mut account_prefix_set = PrefixSetMut::default();
-        let mut storage_prefix_set: HashMap<B256, PrefixSetMut> = HashMap::default();
-        let mut destroyed_accounts = HashSet::default();
-
-        // storage hashing stage
-        {
-            let lists = self.changed_storages_with_range(range.clone())?;
-            let storages = self.plainstate_storages(lists)?;
-            let storage_entries = self.insert_storage_for_hashing(storages)?;
-            for (hashed_address, hashed_slots) in storage_entries {
-                account_prefix_set.insert(Nibbles::unpack(hashed_address));
-                for slot in hashed_slots {
-                    storage_prefix_set
-                        .entry(hashed_address)
-                        .or_default()
-                        .insert(Nibbles::unpack(slot));
-                }
-            }
-        }
-
-        // account hashing stage
-        {
-            let lists = self.changed_accounts_with_range(range.clone())?;
-            let accounts = self.basic_accounts(lists)?;
-            let hashed_addresses = self.insert_account_for_hashing(accounts)?;
-            for (hashed_address, account) in hashed_addresses {
-                account_prefix_set.insert(Nibbles::unpack(hashed_address));
-                if account.is_none() {
-                    destroyed_accounts.insert(hashed_address);
-                }
-            }
-        }
+        let (account_prefix_set, storage_prefix_set, destroyed_accounts) =
+            self.insert_hashes_for_range(range.clone())?;
 
         // merkle tree
         {
@@ -2006,6 +2056,7 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         // Put header with canonical hashes.
         self.tx.put::<tables::Headers>(block.number, block.header.as_ref().clone())?;
         self.tx.put::<tables::HeaderNumbers>(block.hash(), block.number)?;
+        self.tx.put::<tables::TimestampBlockNumber>(block.timestamp, block.number)?;
 
         // total difficulty
         let ttd = if block.number == 0 {
@@ -2093,6 +2144,7 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         &self,
         blocks: Vec<SealedBlockWithSenders>,
         state: BundleStateWithReceipts,
+        trie_updates: Option<TrieUpdates>,
         prune_modes: Option<&PruneModes>,
     ) -> RethResult<()> {
         if blocks.is_empty() {
@@ -2118,7 +2170,16 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         // Must be written after blocks because of the receipt lookup.
         state.write_to_db(self.tx_ref(), OriginalValuesKnown::No)?;
 
-        self.insert_hashes(first_number..=last_block_number, last_block_hash, expected_state_root)?;
+        let range = first_number..=last_block_number;
+        match trie_updates {
+            Some(trie_updates) => self.insert_hashes_with_trie_updates(
+                range,
+                last_block_hash,
+                expected_state_root,
+                trie_updates,
+            )?,
+            None => self.insert_hashes(range, last_block_hash, expected_state_root)?,
+        }
 
         self.calculate_history_indices(first_number..=last_block_number)?;
 
@@ -0,0 +1,685 @@
+//! Export and import of the full state at a block.
+//!
+//! # Format
+//!
+//! A state snapshot starts with a header, followed by a sequence of chunks. All integers are
+//! big-endian.
+//!
+//! ```text
+//! header: magic (8 bytes, "RETHSTAT") | version (u32) | block number (u64) | block hash (32)
+//!         | state root (32)
+//! chunk:  payload length (u32) | payload | keccak256(payload) (32)
+//! ```
+//!
+//! The snapshot ends with a chunk of length zero, which has no payload and no checksum. A payload
+//! is a sequence of records, each starting with a one byte tag:
+//!
+//! ```text
+//! 0x00 account:  address (20) | nonce (u64) | balance (32) | code hash (32) | storage root (32)
+//! 0x01 storage:  slot (32) | value (32)
+//! 0x02 bytecode: length (u32) | bytes
+//! ```
+//!
+//! Accounts are ordered by address. Storage records belong to the last preceding account and are
+//! ordered by slot. The bytecode of an account is written once, after the first account that
+//! references it.
+
+use crate::{
+    AccountExtReader, AccountReader, DatabaseProvider, HistoricalStateProviderRef,
+    LatestStateProviderRef, ProviderError, StageCheckpointReader, StateProvider, StorageReader,
+};
+use alloy_rlp::encode_fixed_size;
+use itertools::{merge_join_by, EitherOrBoth, Itertools};
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_interfaces::RethError;
+use reth_primitives::{
+    keccak256,
+    stage::StageId,
+    trie::{HashBuilder, Nibbles},
+    Account, Address, BlockNumber, Bytecode, Bytes, StorageEntry, B256, KECCAK_EMPTY, U256,
+};
+use reth_trie::StateRoot;
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    io::{Read, Write},
+};
+
+/// The magic bytes every state snapshot starts with.
+const MAGIC: &[u8; 8] = b"RETHSTAT";
+
+/// The current version of the state snapshot format.
+pub const STATE_SNAPSHOT_VERSION: u32 = 1;
+
+/// The payload size after which a chunk is written.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+const ACCOUNT_TAG: u8 = 0x00;
+const STORAGE_TAG: u8 = 0x01;
+const BYTECODE_TAG: u8 = 0x02;
+
+/// Errors that can occur while exporting or importing a state snapshot.
+#[derive(Debug, thiserror::Error)]
+pub enum StateSnapshotError {
+    /// Failed to read or write the snapshot.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The snapshot does not start with the expected magic bytes.
+    #[error("not a state snapshot")]
+    InvalidMagic,
+    /// The snapshot was written with an unsupported format version.
+    #[error("unsupported state snapshot version {0}")]
+    UnsupportedVersion(u32),
+    /// The checksum of a chunk does not match its payload.
+    #[error("checksum mismatch in chunk #{0}")]
+    ChecksumMismatch(u64),
+    /// A chunk contains an invalid record.
+    #[error("malformed record in chunk #{chunk}: {reason}")]
+    MalformedRecord {
+        /// The index of the chunk.
+        chunk: u64,
+        /// What is wrong with the record.
+        reason: &'static str,
+    },
+    /// The state at the requested block is not available, because the block was not executed yet.
+    #[error("state at block #{0} is not available")]
+    StateNotAvailable(BlockNumber),
+    /// The snapshot can only be imported into a database without state.
+    #[error("database already contains state")]
+    DatabaseNotEmpty,
+    /// A database error occurred.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    /// A provider error occurred.
+    #[error(transparent)]
+    Provider(#[from] RethError),
+}
+
+/// The header of a state snapshot, describing the block the state belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateSnapshotHeader {
+    /// The number of the block.
+    pub block_number: BlockNumber,
+    /// The hash of the block.
+    pub block_hash: B256,
+    /// The state root of the block.
+    pub state_root: B256,
+}
+
+impl<TX: DbTx> DatabaseProvider<TX> {
+    /// Writes the state after the execution of the given block to the writer, in the format
+    /// described in the [module docs](self).
+    ///
+    /// For blocks below the latest executed block, the state is reconstructed from the changesets,
+    /// which requires the full history of the state, as kept by archive nodes.
+    ///
+    /// Accounts and storage slots are streamed from the database. Only the addresses and slots
+    /// changed after the given block, and the storage of a single account, are held in memory.
+    pub fn export_state_snapshot<W: Write>(
+        &self,
+        block_number: BlockNumber,
+        writer: W,
+    ) -> Result<StateSnapshotHeader, StateSnapshotError> {
+        let tip = self.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+        if block_number > tip {
+            return Err(StateSnapshotError::StateNotAvailable(block_number))
+        }
+        let block_header = self
+            .tx_ref()
+            .get::<tables::Headers>(block_number)?
+            .ok_or_else(|| RethError::from(ProviderError::HeaderNotFound(block_number.into())))?;
+        let header = StateSnapshotHeader {
+            block_number,
+            block_hash: block_header.hash_slow(),
+            state_root: block_header.state_root,
+        };
+
+        // accounts and slots that exist at the given block, but were removed since
+        let (changed_accounts, mut changed_storages, state): (_, _, Box<dyn StateProvider + '_>) =
+            if block_number == tip {
+                (
+                    Default::default(),
+                    Default::default(),
+                    Box::new(LatestStateProviderRef::new(self.tx_ref())),
+                )
+            } else {
+                let range = block_number + 1..=tip;
+                (
+                    self.changed_accounts_with_range(range.clone())?,
+                    self.changed_storages_with_range(range)?,
+                    Box::new(HistoricalStateProviderRef::new(self.tx_ref(), block_number + 1)),
+                )
+            };
+
+        let mut writer = ChunkWriter::new(writer);
+        writer.write_header(&header)?;
+
+        let mut accounts_cursor = self.tx_ref().cursor_read::<tables::PlainAccountState>()?;
+        let mut storage_cursor = self.tx_ref().cursor_dup_read::<tables::PlainStorageState>()?;
+        let mut written_bytecodes = HashSet::new();
+
+        let addresses = merge_sorted(
+            accounts_cursor.walk(None)?.map(|entry| entry.map(|(address, _)| address)),
+            changed_accounts,
+        );
+        for address in addresses {
+            let address = address?;
+            let Some(account) = state.basic_account(address)? else { continue };
+
+            let slots = merge_sorted(
+                storage_cursor
+                    .walk_dup(Some(address), None)?
+                    .map(|entry| entry.map(|(_, e)| e.key)),
+                changed_storages.remove(&address).unwrap_or_default(),
+            );
+            let mut storage = Vec::new();
+            for slot in slots {
+                let slot = slot?;
+                if let Some(value) =
+                    state.storage(address, slot)?.filter(|value| *value != U256::ZERO)
+                {
+                    storage.push((slot, value));
+                }
+            }
+
+            writer.write_account(address, &account, storage_root(&storage))?;
+            for (slot, value) in storage {
+                writer.write_storage(slot, value)?;
+            }
+
+            if let Some(code_hash) = account.bytecode_hash {
+                if written_bytecodes.insert(code_hash) {
+                    let bytecode = state
+                        .bytecode_by_hash(code_hash)?
+                        .ok_or(StateSnapshotError::StateNotAvailable(block_number))?;
+                    writer.write_bytecode(&bytecode.0.original_bytes())?;
+                }
+            }
+        }
+
+        writer.finish()?;
+        Ok(header)
+    }
+}
+
+impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
+    /// Initializes the plain and hashed state, the bytecodes and the state trie of a database
+    /// without state from a snapshot written by [DatabaseProvider::export_state_snapshot].
+    ///
+    /// The state root of the imported state is verified against the state root of the snapshot
+    /// header, which is returned on success.
+    pub fn import_state_snapshot<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<StateSnapshotHeader, StateSnapshotError> {
+        if self.tx_ref().entries::<tables::PlainAccountState>()? != 0 {
+            return Err(StateSnapshotError::DatabaseNotEmpty)
+        }
+
+        let mut reader = ChunkReader::new(reader);
+        let header = reader.read_header()?;
+
+        let mut current_account = None;
+        while let Some(record) = reader.next_record()? {
+            match record {
+                Record::Account { address, account } => {
+                    self.tx_ref().put::<tables::PlainAccountState>(address, account)?;
+                    self.tx_ref().put::<tables::HashedAccount>(keccak256(address), account)?;
+                    current_account = Some(address);
+                }
+                Record::Storage { slot, value } => {
+                    let address = current_account.ok_or(StateSnapshotError::MalformedRecord {
+                        chunk: reader.chunk,
+                        reason: "storage without account",
+                    })?;
+                    self.tx_ref().put::<tables::PlainStorageState>(
+                        address,
+                        StorageEntry { key: slot, value },
+                    )?;
+                    self.tx_ref().put::<tables::HashedStorage>(
+                        keccak256(address),
+                        StorageEntry { key: keccak256(slot), value },
+                    )?;
+                }
+                Record::Bytecode(bytes) => {
+                    let code_hash = keccak256(&bytes);
+                    self.tx_ref().put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(bytes))?;
+                }
+            }
+        }
+
+        let (state_root, trie_updates) = StateRoot::new(self.tx_ref())
+            .root_with_updates()
+            .map_err(Into::<DatabaseError>::into)?;
+        if state_root != header.state_root {
+            return Err(RethError::from(ProviderError::StateRootMismatch {
+                expected: header.state_root,
+                got: state_root,
+                block_number: header.block_number,
+                block_hash: header.block_hash,
+            })
+            .into())
+        }
+        trie_updates.flush(self.tx_ref())?;
+
+        Ok(header)
+    }
+}
+
+/// Merges the sorted entries of a database walker with a sorted set of additional keys.
+fn merge_sorted<K: Ord>(
+    walker: impl Iterator<Item = Result<K, DatabaseError>>,
+    additional: impl IntoIterator<Item = K>,
+) -> impl Iterator<Item = Result<K, DatabaseError>> {
+    merge_join_by(walker, additional, |entry, key| match entry {
+        Ok(entry) => entry.cmp(key),
+        // surface errors right away
+        Err(_) => Ordering::Less,
+    })
+    .map(|either| match either {
+        EitherOrBoth::Left(entry) => entry,
+        EitherOrBoth::Right(key) | EitherOrBoth::Both(_, key) => Ok(key),
+    })
+}
+
+/// Computes the storage root of the given non-zero storage slots.
+fn storage_root(storage: &[(B256, U256)]) -> B256 {
+    let mut hash_builder = HashBuilder::default();
+    for (hashed_slot, value) in
+        storage.iter().map(|(slot, value)| (keccak256(slot), value)).sorted_by_key(|(key, _)| *key)
+    {
+        hash_builder.add_leaf(Nibbles::unpack(hashed_slot), &encode_fixed_size(value));
+    }
+    hash_builder.root()
+}
+
+/// A record of a state snapshot.
+#[derive(Debug)]
+enum Record {
+    Account { address: Address, account: Account },
+    Storage { slot: B256, value: U256 },
+    Bytecode(Bytes),
+}
+
+/// Writes records into checksummed chunks.
+struct ChunkWriter<W> {
+    writer: W,
+    payload: Vec<u8>,
+}
+
+impl<W: Write> ChunkWriter<W> {
+    fn new(writer: W) -> Self {
+        Self { writer, payload: Vec::with_capacity(CHUNK_SIZE) }
+    }
+
+    fn write_header(&mut self, header: &StateSnapshotHeader) -> std::io::Result<()> {
+        self.writer.write_all(MAGIC)?;
+        self.writer.write_all(&STATE_SNAPSHOT_VERSION.to_be_bytes())?;
+        self.writer.write_all(&header.block_number.to_be_bytes())?;
+        self.writer.write_all(header.block_hash.as_slice())?;
+        self.writer.write_all(header.state_root.as_slice())
+    }
+
+    fn write_account(
+        &mut self,
+        address: Address,
+        account: &Account,
+        storage_root: B256,
+    ) -> std::io::Result<()> {
+        self.payload.push(ACCOUNT_TAG);
+        self.payload.extend_from_slice(address.as_slice());
+        self.payload.extend_from_slice(&account.nonce.to_be_bytes());
+        self.payload.extend_from_slice(&account.balance.to_be_bytes::<32>());
+        self.payload.extend_from_slice(account.bytecode_hash.unwrap_or(KECCAK_EMPTY).as_slice());
+        self.payload.extend_from_slice(storage_root.as_slice());
+        self.maybe_flush()
+    }
+
+    fn write_storage(&mut self, slot: B256, value: U256) -> std::io::Result<()> {
+        self.payload.push(STORAGE_TAG);
+        self.payload.extend_from_slice(slot.as_slice());
+        self.payload.extend_from_slice(&value.to_be_bytes::<32>());
+        self.maybe_flush()
+    }
+
+    fn write_bytecode(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.payload.push(BYTECODE_TAG);
+        self.payload.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        self.payload.extend_from_slice(bytes);
+        self.maybe_flush()
+    }
+
+    fn maybe_flush(&mut self) -> std::io::Result<()> {
+        if self.payload.len() >= CHUNK_SIZE {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn flush_chunk(&mut self) -> std::io::Result<()> {
+        if self.payload.is_empty() {
+            return Ok(())
+        }
+        self.writer.write_all(&(self.payload.len() as u32).to_be_bytes())?;
+        self.writer.write_all(&self.payload)?;
+        self.writer.write_all(keccak256(&self.payload).as_slice())?;
+        self.payload.clear();
+        Ok(())
+    }
+
+    /// Writes the remaining records and the terminating empty chunk.
+    fn finish(mut self) -> std::io::Result<()> {
+        self.flush_chunk()?;
+        self.writer.write_all(&0u32.to_be_bytes())?;
+        self.writer.flush()
+    }
+}
+
+/// Reads records from checksummed chunks.
+struct ChunkReader<R> {
+    reader: R,
+    /// The index of the current chunk.
+    chunk: u64,
+    payload: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> ChunkReader<R> {
+    fn new(reader: R) -> Self {
+        Self { reader, chunk: 0, payload: Vec::new(), position: 0 }
+    }
+
+    fn read_header(&mut self) -> Result<StateSnapshotHeader, StateSnapshotError> {
+        let mut magic = [0u8; 8];
+        self.reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(StateSnapshotError::InvalidMagic)
+        }
+        let version = u32::from_be_bytes(read_array(&mut self.reader)?);
+        if version != STATE_SNAPSHOT_VERSION {
+            return Err(StateSnapshotError::UnsupportedVersion(version))
+        }
+        Ok(StateSnapshotHeader {
+            block_number: u64::from_be_bytes(read_array(&mut self.reader)?),
+            block_hash: B256::from(read_array::<_, 32>(&mut self.reader)?),
+            state_root: B256::from(read_array::<_, 32>(&mut self.reader)?),
+        })
+    }
+
+    /// Reads the next chunk, returns `false` if the terminating chunk was reached.
+    fn read_chunk(&mut self) -> Result<bool, StateSnapshotError> {
+        let len = u32::from_be_bytes(read_array(&mut self.reader)?) as usize;
+        if len == 0 {
+            return Ok(false)
+        }
+        self.chunk += 1;
+        self.payload.resize(len, 0);
+        self.reader.read_exact(&mut self.payload)?;
+        let checksum = B256::from(read_array::<_, 32>(&mut self.reader)?);
+        if keccak256(&self.payload) != checksum {
+            return Err(StateSnapshotError::ChecksumMismatch(self.chunk))
+        }
+        self.position = 0;
+        Ok(true)
+    }
+
+    fn next_record(&mut self) -> Result<Option<Record>, StateSnapshotError> {
+        if self.position == self.payload.len() && !self.read_chunk()? {
+            return Ok(None)
+        }
+
+        let tag = self.take(1)?[0];
+        let record = match tag {
+            ACCOUNT_TAG => {
+                let address = Address::from_slice(self.take(20)?);
+                let nonce = u64::from_be_bytes(self.take(8)?.try_into().unwrap());
+                let balance = U256::from_be_slice(self.take(32)?);
+                let code_hash = B256::from_slice(self.take(32)?);
+                // the storage root is verified as part of the state root
+                self.take(32)?;
+                let bytecode_hash = (code_hash != KECCAK_EMPTY).then_some(code_hash);
+                Record::Account { address, account: Account { nonce, balance, bytecode_hash } }
+            }
+            STORAGE_TAG => Record::Storage {
+                slot: B256::from_slice(self.take(32)?),
+                value: U256::from_be_slice(self.take(32)?),
+            },
+            BYTECODE_TAG => {
+                let len = u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as usize;
+                Record::Bytecode(Bytes::copy_from_slice(self.take(len)?))
+            }
+            _ => return Err(self.malformed("unknown record tag")),
+        };
+        Ok(Some(record))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], StateSnapshotError> {
+        if self.payload.len() - self.position < len {
+            return Err(self.malformed("record exceeds chunk"))
+        }
+        let bytes = &self.payload[self.position..self.position + len];
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn malformed(&self, reason: &'static str) -> StateSnapshotError {
+        StateSnapshotError::MalformedRecord { chunk: self.chunk, reason }
+    }
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> std::io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HashingWriter, HistoryWriter, ProviderFactory, StageCheckpointWriter};
+    use reth_db::{
+        cursor::DbCursorRW,
+        models::{AccountBeforeTx, BlockNumberAddress},
+        test_utils::create_test_rw_db,
+    };
+    use reth_primitives::{stage::StageCheckpoint, Header, MAINNET};
+    use reth_trie::proof::Proof;
+    use std::collections::BTreeMap;
+
+    type State = [(Address, Account, Vec<(B256, U256)>)];
+
+    /// Writes the plain and hashed state, and returns the state root.
+    fn insert_state<TX: DbTxMut + DbTx>(provider: &DatabaseProvider<TX>, state: &State) -> B256 {
+        for (address, account, storage) in state {
+            provider.tx_ref().put::<tables::PlainAccountState>(*address, *account).unwrap();
+            for (key, value) in storage {
+                provider
+                    .tx_ref()
+                    .put::<tables::PlainStorageState>(
+                        *address,
+                        StorageEntry { key: *key, value: *value },
+                    )
+                    .unwrap();
+            }
+        }
+        provider
+            .insert_account_for_hashing(
+                state.iter().map(|(address, account, _)| (*address, Some(*account))),
+            )
+            .unwrap();
+        provider
+            .insert_storage_for_hashing(state.iter().map(|(address, _, storage)| {
+                (
+                    *address,
+                    storage.iter().map(|(key, value)| StorageEntry { key: *key, value: *value }),
+                )
+            }))
+            .unwrap();
+        StateRoot::new(provider.tx_ref()).root().unwrap()
+    }
+
+    fn insert_header<TX: DbTxMut + DbTx>(
+        provider: &DatabaseProvider<TX>,
+        number: u64,
+        state_root: B256,
+    ) {
+        let header = Header { number, state_root, ..Default::default() };
+        provider.tx_ref().put::<tables::CanonicalHeaders>(number, header.hash_slow()).unwrap();
+        provider.tx_ref().put::<tables::Headers>(number, header).unwrap();
+        provider.save_stage_checkpoint(StageId::Execution, StageCheckpoint::new(number)).unwrap();
+    }
+
+    #[test]
+    fn export_import_historical_state() {
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let code_hash = keccak256(&code);
+        let contract = Address::with_last_byte(1);
+        let eoa = Address::with_last_byte(2);
+        let removed = Address::with_last_byte(3);
+        let slot = B256::with_last_byte(1);
+        let removed_slot = B256::with_last_byte(2);
+
+        // state after block 1
+        let state_1 = [
+            (
+                contract,
+                Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(code_hash) },
+                vec![(slot, U256::from(1)), (removed_slot, U256::from(2))],
+            ),
+            (eoa, Account { nonce: 3, balance: U256::from(100), bytecode_hash: None }, vec![]),
+            (removed, Account { nonce: 0, balance: U256::from(1), bytecode_hash: None }, vec![]),
+        ];
+
+        // the source database has the state of block 1, and the changes of block 2 on top
+        let source = create_test_rw_db();
+        let factory = ProviderFactory::new(source.as_ref(), MAINNET.clone());
+        let provider = factory.provider_rw().unwrap();
+        let root_1 = insert_state(&*provider, &state_1);
+        provider
+            .tx_ref()
+            .put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code.clone()))
+            .unwrap();
+        insert_header(&*provider, 1, root_1);
+
+        // block 2 changes the contract storage, the eoa balance and removes an account
+        let tx = provider.tx_ref();
+        {
+            let mut storage_cursor = tx.cursor_dup_write::<tables::PlainStorageState>().unwrap();
+            for key in [slot, removed_slot] {
+                storage_cursor.seek_by_key_subkey(contract, key).unwrap();
+                storage_cursor.delete_current().unwrap();
+            }
+        }
+        tx.put::<tables::PlainStorageState>(
+            contract,
+            StorageEntry { key: slot, value: U256::from(5) },
+        )
+        .unwrap();
+        tx.put::<tables::PlainAccountState>(
+            eoa,
+            Account { nonce: 4, balance: U256::from(50), bytecode_hash: None },
+        )
+        .unwrap();
+        tx.delete::<tables::PlainAccountState>(removed, None).unwrap();
+        for (address, account) in [(eoa, state_1[1].1), (removed, state_1[2].1)] {
+            tx.put::<tables::AccountChangeSet>(2, AccountBeforeTx { address, info: Some(account) })
+                .unwrap();
+        }
+        for (key, value) in [(slot, U256::from(1)), (removed_slot, U256::from(2))] {
+            tx.put::<tables::StorageChangeSet>(
+                BlockNumberAddress((2, contract)),
+                StorageEntry { key, value },
+            )
+            .unwrap();
+        }
+        provider
+            .insert_account_history_index(BTreeMap::from([(eoa, vec![2]), (removed, vec![2])]))
+            .unwrap();
+        provider
+            .insert_storage_history_index(BTreeMap::from([
+                ((contract, slot), vec![2]),
+                ((contract, removed_slot), vec![2]),
+            ]))
+            .unwrap();
+        insert_header(&*provider, 2, B256::ZERO);
+        provider.commit().unwrap();
+
+        // export the state of block 1
+        let mut snapshot = Vec::new();
+        let header = factory.provider().unwrap().export_state_snapshot(1, &mut snapshot).unwrap();
+        assert_eq!(header.state_root, root_1);
+
+        // import into a fresh database
+        let imported = create_test_rw_db();
+        let imported_factory = ProviderFactory::new(imported.as_ref(), MAINNET.clone());
+        let provider = imported_factory.provider_rw().unwrap();
+        assert_eq!(provider.import_state_snapshot(snapshot.as_slice()).unwrap(), header);
+        provider.commit().unwrap();
+
+        // a database with the original state of block 1 to compare proofs against
+        let expected = create_test_rw_db();
+        let expected_factory = ProviderFactory::new(expected.as_ref(), MAINNET.clone());
+        let provider = expected_factory.provider_rw().unwrap();
+        insert_state(&*provider, &state_1);
+        let (_, updates) = StateRoot::new(provider.tx_ref()).root_with_updates().unwrap();
+        updates.flush(provider.tx_ref()).unwrap();
+        provider.commit().unwrap();
+
+        let imported_provider = imported_factory.provider().unwrap();
+        let expected_provider = expected_factory.provider().unwrap();
+        for (address, keys) in
+            [(contract, vec![slot, removed_slot]), (eoa, vec![]), (removed, vec![])]
+        {
+            assert_eq!(
+                Proof::new(imported_provider.tx_ref()).account_proof(address, &keys).unwrap(),
+                Proof::new(expected_provider.tx_ref()).account_proof(address, &keys).unwrap()
+            );
+        }
+        assert_eq!(
+            imported_provider
+                .tx_ref()
+                .get::<tables::Bytecodes>(code_hash)
+                .unwrap()
+                .map(|code| code.0.original_bytes()),
+            Some(code)
+        );
+
+        // importing twice is rejected
+        let provider = imported_factory.provider_rw().unwrap();
+        assert!(matches!(
+            provider.import_state_snapshot(snapshot.as_slice()),
+            Err(StateSnapshotError::DatabaseNotEmpty)
+        ));
+    }
+
+    #[test]
+    fn corrupted_snapshot_is_rejected() {
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(db.as_ref(), MAINNET.clone());
+        let provider = factory.provider_rw().unwrap();
+        let state_root = insert_state(
+            &*provider,
+            &vec![(Address::with_last_byte(1), Account { nonce: 1, ..Default::default() }, vec![])],
+        );
+        insert_header(&*provider, 1, state_root);
+        provider.commit().unwrap();
+
+        let mut snapshot = Vec::new();
+        factory.provider().unwrap().export_state_snapshot(1, &mut snapshot).unwrap();
+        // flip a bit in the balance of the account record
+        let header_len = 8 + 4 + 8 + 32 + 32;
+        snapshot[header_len + 4 + 1 + 20 + 8] ^= 1;
+
+        let fresh = create_test_rw_db();
+        let factory = ProviderFactory::new(fresh.as_ref(), MAINNET.clone());
+        assert!(matches!(
+            factory.provider_rw().unwrap().import_state_snapshot(snapshot.as_slice()),
+            Err(StateSnapshotError::ChecksumMismatch(1))
+        ));
+    }
+}
@@ -29,6 +29,7 @@ use std::{
 use tracing::trace;
 
 pub use state::{
+    cached::{CachedStateProvider, StateCache},
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
@@ -0,0 +1,223 @@
+use crate::{
+    AccountReader, BlockHashReader, BundleStateWithReceipts, StateProvider, StateRootProvider,
+};
+use parking_lot::RwLock;
+use reth_interfaces::RethResult;
+use reth_primitives::{
+    trie::AccountProof, Account, Address, BlockNumHash, BlockNumber, Bytecode, StorageEntry,
+    StorageKey, StorageValue, B256,
+};
+use reth_trie::updates::TrieUpdates;
+use std::collections::HashMap;
+
+/// An in-memory cache of accounts, storage slots and bytecodes of the state at a single canonical
+/// block.
+///
+/// The cache is filled ahead of execution, e.g. by a warmup after a restart, and is only consulted
+/// for state at the block it was filled for, see [StateCache::is_valid_for]. Once the canonical
+/// chain advances, [StateCache::advance] drops the entries of all changed accounts, since the
+/// remaining entries are still valid for the new tip.
+#[derive(Debug, Default)]
+pub struct StateCache {
+    inner: RwLock<StateCacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct StateCacheInner {
+    /// The canonical block the cached state belongs to.
+    tip: Option<BlockNumHash>,
+    accounts: HashMap<Address, Option<Account>>,
+    storage: HashMap<Address, HashMap<StorageKey, Option<StorageValue>>>,
+    bytecodes: HashMap<B256, Option<Bytecode>>,
+}
+
+impl StateCache {
+    /// Returns true if the cache holds the state at the given block.
+    pub fn is_valid_for(&self, block: BlockNumHash) -> bool {
+        self.inner.read().tip == Some(block)
+    }
+
+    /// Sets the block the cached state belongs to, clearing the cache if it held the state of
+    /// another block.
+    pub fn set_tip(&self, block: BlockNumHash) {
+        let mut inner = self.inner.write();
+        if inner.tip != Some(block) {
+            *inner = StateCacheInner { tip: Some(block), ..Default::default() };
+        }
+    }
+
+    /// Moves the cache to the new canonical tip, dropping the entries of the accounts that were
+    /// changed by the blocks up to the tip.
+    pub fn advance<'a>(&self, tip: BlockNumHash, changed: impl IntoIterator<Item = &'a Address>) {
+        let mut inner = self.inner.write();
+        for address in changed {
+            inner.accounts.remove(address);
+            inner.storage.remove(address);
+        }
+        inner.tip = Some(tip);
+    }
+
+    /// Removes all entries and invalidates the cache.
+    pub fn clear(&self) {
+        *self.inner.write() = StateCacheInner::default();
+    }
+
+    /// Returns the number of cached accounts, storage slots and bytecodes.
+    pub fn len(&self) -> usize {
+        let inner = self.inner.read();
+        inner.accounts.len() +
+            inner.storage.values().map(HashMap::len).sum::<usize>() +
+            inner.bytecodes.len()
+    }
+
+    /// Returns true if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Caches the account.
+    pub fn insert_account(&self, address: Address, account: Option<Account>) {
+        self.inner.write().accounts.insert(address, account);
+    }
+
+    /// Caches the storage slot.
+    pub fn insert_storage(&self, address: Address, key: StorageKey, value: Option<StorageValue>) {
+        self.inner.write().storage.entry(address).or_default().insert(key, value);
+    }
+
+    /// Caches the bytecode.
+    pub fn insert_bytecode(&self, code_hash: B256, bytecode: Option<Bytecode>) {
+        self.inner.write().bytecodes.insert(code_hash, bytecode);
+    }
+
+    fn account(&self, address: &Address) -> Option<Option<Account>> {
+        self.inner.read().accounts.get(address).copied()
+    }
+
+    fn storage(&self, address: &Address, key: &StorageKey) -> Option<Option<StorageValue>> {
+        self.inner.read().storage.get(address).and_then(|storage| storage.get(key)).copied()
+    }
+
+    fn bytecode(&self, code_hash: &B256) -> Option<Option<Bytecode>> {
+        self.inner.read().bytecodes.get(code_hash).cloned()
+    }
+}
+
+/// A state provider that serves reads from a [StateCache] and falls back to the inner state
+/// provider.
+///
+/// The cache must hold the state the inner provider provides, see [StateCache::is_valid_for].
+#[derive(Debug)]
+pub struct CachedStateProvider<'a, SP: StateProvider> {
+    /// The inner state provider.
+    state_provider: SP,
+    /// The cache of the state of the inner provider.
+    cache: &'a StateCache,
+}
+
+impl<'a, SP: StateProvider> CachedStateProvider<'a, SP> {
+    /// Create new cached state provider
+    pub fn new(state_provider: SP, cache: &'a StateCache) -> Self {
+        Self { state_provider, cache }
+    }
+}
+
+/* Implement StateProvider traits */
+
+impl<'a, SP: StateProvider> BlockHashReader for CachedStateProvider<'a, SP> {
+    fn block_hash(&self, block_number: BlockNumber) -> RethResult<Option<B256>> {
+        self.state_provider.block_hash(block_number)
+    }
+
+    fn canonical_hashes_range(
+        &self,
+        start: BlockNumber,
+        end: BlockNumber,
+    ) -> RethResult<Vec<B256>> {
+        self.state_provider.canonical_hashes_range(start, end)
+    }
+}
+
+impl<'a, SP: StateProvider> AccountReader for CachedStateProvider<'a, SP> {
+    fn basic_account(&self, address: Address) -> RethResult<Option<Account>> {
+        match self.cache.account(&address) {
+            Some(account) => Ok(account),
+            None => self.state_provider.basic_account(address),
+        }
+    }
+}
+
+impl<'a, SP: StateProvider> StateRootProvider for CachedStateProvider<'a, SP> {
+    fn state_root(&self, post_state: &BundleStateWithReceipts) -> RethResult<B256> {
+        self.state_provider.state_root(post_state)
+    }
+
+    fn state_root_with_updates(
+        &self,
+        post_state: &BundleStateWithReceipts,
+    ) -> RethResult<(B256, TrieUpdates)> {
+        self.state_provider.state_root_with_updates(post_state)
+    }
+}
+
+impl<'a, SP: StateProvider> StateProvider for CachedStateProvider<'a, SP> {
+    fn storage(
+        &self,
+        account: Address,
+        storage_key: StorageKey,
+    ) -> RethResult<Option<StorageValue>> {
+        match self.cache.storage(&account, &storage_key) {
+            Some(value) => Ok(value),
+            None => self.state_provider.storage(account, storage_key),
+        }
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> RethResult<Option<Bytecode>> {
+        match self.cache.bytecode(&code_hash) {
+            Some(bytecode) => Ok(bytecode),
+            None => self.state_provider.bytecode_by_hash(code_hash),
+        }
+    }
+
+    fn proof(&self, address: Address, keys: &[B256]) -> RethResult<AccountProof> {
+        self.state_provider.proof(address, keys)
+    }
+
+    fn storage_entries(&self, account: Address) -> RethResult<Vec<StorageEntry>> {
+        self.state_provider.storage_entries(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::NoopProvider;
+    use reth_primitives::U256;
+
+    #[test]
+    fn serves_cached_entries_until_changed() {
+        let cache = StateCache::default();
+        let (address, other) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let account = Account { nonce: 1, ..Default::default() };
+        let key = B256::with_last_byte(1);
+        cache.set_tip(BlockNumHash::new(1, B256::with_last_byte(1)));
+        cache.insert_account(address, Some(account));
+        cache.insert_storage(address, key, Some(U256::from(7)));
+        cache.insert_account(other, Some(account));
+
+        let provider = CachedStateProvider::new(NoopProvider::default(), &cache);
+        assert_eq!(provider.basic_account(address).unwrap(), Some(account));
+        assert_eq!(provider.storage(address, key).unwrap(), Some(U256::from(7)));
+        assert_eq!(provider.storage(address, B256::ZERO).unwrap(), None);
+
+        let tip = BlockNumHash::new(2, B256::with_last_byte(2));
+        cache.advance(tip, [&address]);
+        assert!(cache.is_valid_for(tip));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(provider.basic_account(address).unwrap(), None);
+        assert_eq!(provider.basic_account(other).unwrap(), Some(account));
+
+        cache.set_tip(BlockNumHash::new(2, B256::with_last_byte(3)));
+        assert!(cache.is_empty());
+    }
+}
@@ -12,8 +12,11 @@ use reth_db::{
 };
 use reth_interfaces::RethResult;
 use reth_primitives::{
-    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageKey, StorageValue, B256,
+    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageEntry, StorageKey,
+    StorageValue, B256, U256,
 };
+use reth_trie::updates::TrieUpdates;
+use std::collections::BTreeSet;
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -201,6 +204,13 @@ impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
     fn state_root(&self, _post_state: &BundleStateWithReceipts) -> RethResult<B256> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
     }
+
+    fn state_root_with_updates(
+        &self,
+        _post_state: &BundleStateWithReceipts,
+    ) -> RethResult<(B256, TrieUpdates)> {
+        Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
+    }
 }
 
 impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
@@ -243,6 +253,34 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
     fn proof(&self, _address: Address, _keys: &[B256]) -> RethResult<AccountProof> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
     }
+
+    /// Get all non-zero storage slots of the account at the block.
+    ///
+    /// These are among the slots that are set in the latest state or have a history, i.e. were
+    /// changed by any block, which includes the slots that were cleared after the block.
+    fn storage_entries(&self, account: Address) -> RethResult<Vec<StorageEntry>> {
+        let mut keys = BTreeSet::new();
+        let mut plain_cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        for entry in plain_cursor.walk_dup(Some(account), None)? {
+            keys.insert(entry?.1.key);
+        }
+        let mut history_cursor = self.tx.cursor_read::<tables::StorageHistory>()?;
+        for entry in history_cursor.walk(Some(StorageShardedKey::new(account, B256::ZERO, 0)))? {
+            let (key, _) = entry?;
+            if key.address != account {
+                break
+            }
+            keys.insert(key.sharded_key.key);
+        }
+
+        let mut entries = Vec::new();
+        for key in keys {
+            if let Some(value) = self.storage(account, key)?.filter(|value| *value != U256::ZERO) {
+                entries.push(StorageEntry { key, value });
+            }
+        }
+        Ok(entries)
+    }
 }
 
 /// State provider for a given block number.
@@ -549,6 +587,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn history_provider_get_storage_entries() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        let cleared = B256::with_last_byte(1);
+        let created = B256::with_last_byte(2);
+        let unchanged = B256::with_last_byte(3);
+        for (key, block_number) in [(cleared, 5), (created, 8), (unchanged, 2)] {
+            tx.put::<tables::StorageHistory>(
+                StorageShardedKey {
+                    address: ADDRESS,
+                    sharded_key: ShardedKey { key, highest_block_number: u64::MAX },
+                },
+                BlockNumberList::new([block_number]).unwrap(),
+            )
+            .unwrap();
+        }
+        tx.put::<tables::StorageHistory>(
+            StorageShardedKey {
+                address: HIGHER_ADDRESS,
+                sharded_key: ShardedKey { key: cleared, highest_block_number: u64::MAX },
+            },
+            BlockNumberList::new([5]).unwrap(),
+        )
+        .unwrap();
+
+        // the slot that is cleared at block 5 is not in the plain state anymore
+        tx.put::<tables::StorageChangeSet>(
+            (5, ADDRESS).into(),
+            StorageEntry { key: cleared, value: U256::from(7) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (8, ADDRESS).into(),
+            StorageEntry { key: created, value: U256::ZERO },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (2, ADDRESS).into(),
+            StorageEntry { key: unchanged, value: U256::ZERO },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (5, HIGHER_ADDRESS).into(),
+            StorageEntry { key: cleared, value: U256::from(1) },
+        )
+        .unwrap();
+        tx.put::<tables::PlainStorageState>(
+            ADDRESS,
+            StorageEntry { key: created, value: U256::from(42) },
+        )
+        .unwrap();
+        tx.put::<tables::PlainStorageState>(
+            ADDRESS,
+            StorageEntry { key: unchanged, value: U256::from(3) },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 4).storage_entries(ADDRESS),
+            Ok(vec![
+                StorageEntry { key: cleared, value: U256::from(7) },
+                StorageEntry { key: unchanged, value: U256::from(3) },
+            ])
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 6).storage_entries(ADDRESS),
+            Ok(vec![StorageEntry { key: unchanged, value: U256::from(3) }])
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 10).storage_entries(ADDRESS),
+            Ok(vec![
+                StorageEntry { key: created, value: U256::from(42) },
+                StorageEntry { key: unchanged, value: U256::from(3) },
+            ])
+        );
+    }
+
     #[test]
     fn history_provider_unavailable() {
         let db = create_test_rw_db();
@@ -9,9 +9,10 @@ use reth_db::{
 };
 use reth_interfaces::{provider::ProviderError, RethError, RethResult};
 use reth_primitives::{
-    keccak256, trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageKey,
-    StorageValue, B256,
+    keccak256, trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageEntry,
+    StorageKey, StorageValue, B256,
 };
+use reth_trie::updates::TrieUpdates;
 
 /// State provider over latest state that takes tx reference.
 #[derive(Debug)]
@@ -62,6 +63,15 @@ impl<'b, TX: DbTx> StateRootProvider for LatestStateProviderRef<'b, TX> {
     fn state_root(&self, bundle_state: &BundleStateWithReceipts) -> RethResult<B256> {
         bundle_state.state_root_slow(self.db).map_err(|err| RethError::Database(err.into()))
     }
+
+    fn state_root_with_updates(
+        &self,
+        bundle_state: &BundleStateWithReceipts,
+    ) -> RethResult<(B256, TrieUpdates)> {
+        bundle_state
+            .state_root_slow_with_updates(self.db)
+            .map_err(|err| RethError::Database(err.into()))
+    }
 }
 
 impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
@@ -97,6 +107,14 @@ impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
 
         unimplemented!()
     }
+
+    fn storage_entries(&self, account: Address) -> RethResult<Vec<StorageEntry>> {
+        let mut cursor = self.db.cursor_dup_read::<tables::PlainStorageState>()?;
+        cursor
+            .walk_dup(Some(account), None)?
+            .map(|entry| entry.map(|(_, entry)| entry).map_err(Into::into))
+            .collect()
+    }
 }
 
 /// State provider for the latest state.
@@ -32,6 +32,7 @@ macro_rules! delegate_provider_impls {
             for $target =>
             StateRootProvider $(where [$($generics)*])? {
                 fn state_root(&self, state: &crate::BundleStateWithReceipts) -> reth_interfaces::RethResult<reth_primitives::B256>;
+                fn state_root_with_updates(&self, state: &crate::BundleStateWithReceipts) -> reth_interfaces::RethResult<(reth_primitives::B256, reth_trie::updates::TrieUpdates)>;
             }
             AccountReader $(where [$($generics)*])? {
                 fn basic_account(&self, address: reth_primitives::Address) -> reth_interfaces::RethResult<Option<reth_primitives::Account>>;
@@ -43,6 +44,7 @@ macro_rules! delegate_provider_impls {
             StateProvider $(where [$($generics)*])?{
                 fn storage(&self, account: reth_primitives::Address, storage_key: reth_primitives::StorageKey) -> reth_interfaces::RethResult<Option<reth_primitives::StorageValue>>;
                 fn proof(&self, address: reth_primitives::Address, keys: &[reth_primitives::B256]) -> reth_interfaces::RethResult<reth_primitives::trie::AccountProof>;
+                fn storage_entries(&self, account: reth_primitives::Address) -> reth_interfaces::RethResult<Vec<reth_primitives::StorageEntry>>;
                 fn bytecode_by_hash(&self, code_hash: reth_primitives::B256) -> reth_interfaces::RethResult<Option<reth_primitives::Bytecode>>;
             }
         );
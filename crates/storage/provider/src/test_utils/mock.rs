@@ -12,9 +12,10 @@ use reth_interfaces::{provider::ProviderError, RethResult};
 use reth_primitives::{
     keccak256, trie::AccountProof, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId,
     BlockNumber, BlockWithSenders, Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt,
-    SealedBlock, SealedHeader, StorageKey, StorageValue, TransactionMeta, TransactionSigned,
-    TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
+    SealedBlock, SealedHeader, StorageEntry, StorageKey, StorageValue, TransactionMeta,
+    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
 };
+use reth_trie::updates::TrieUpdates;
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
     collections::{BTreeMap, HashMap},
@@ -31,6 +32,8 @@ pub struct MockEthProvider {
     pub headers: Arc<Mutex<HashMap<B256, Header>>>,
     /// Local account store
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
+    /// Local receipt store, by block hash
+    pub receipts: Arc<Mutex<HashMap<B256, Vec<Receipt>>>>,
     /// Local chain spec
     pub chain_spec: Arc<ChainSpec>,
 }
@@ -41,6 +44,7 @@ impl Default for MockEthProvider {
             blocks: Default::default(),
             headers: Default::default(),
             accounts: Default::default(),
+            receipts: Default::default(),
             chain_spec: Arc::new(reth_primitives::ChainSpecBuilder::mainnet().build()),
         }
     }
@@ -110,6 +114,11 @@ impl MockEthProvider {
         }
     }
 
+    /// Add the receipts of a block to local receipt store
+    pub fn add_receipts(&self, block_hash: B256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(block_hash, receipts);
+    }
+
     /// Add account to local account store
     pub fn add_account(&self, address: Address, account: ExtendedAccount) {
         self.accounts.lock().insert(address, account);
@@ -331,8 +340,12 @@ impl ReceiptProvider for MockEthProvider {
         Ok(None)
     }
 
-    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> RethResult<Option<Vec<Receipt>>> {
-        Ok(None)
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> RethResult<Option<Vec<Receipt>>> {
+        let hash = match block {
+            BlockHashOrNumber::Hash(hash) => Some(hash),
+            BlockHashOrNumber::Number(number) => self.block_hash(number)?,
+        };
+        Ok(hash.and_then(|hash| self.receipts.lock().get(&hash).cloned()))
     }
 }
 
@@ -487,6 +500,13 @@ impl StateRootProvider for MockEthProvider {
     fn state_root(&self, _state: &BundleStateWithReceipts) -> RethResult<B256> {
         todo!()
     }
+
+    fn state_root_with_updates(
+        &self,
+        _state: &BundleStateWithReceipts,
+    ) -> RethResult<(B256, TrieUpdates)> {
+        Ok((B256::default(), TrieUpdates::default()))
+    }
 }
 
 impl StateProvider for MockEthProvider {
@@ -514,6 +534,21 @@ impl StateProvider for MockEthProvider {
     fn proof(&self, _address: Address, _keys: &[B256]) -> RethResult<AccountProof> {
         todo!()
     }
+
+    fn storage_entries(&self, account: Address) -> RethResult<Vec<StorageEntry>> {
+        let lock = self.accounts.lock();
+        Ok(lock
+            .get(&account)
+            .map(|account| {
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| **value != U256::ZERO)
+                    .map(|(key, value)| StorageEntry { key: *key, value: *value })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
 }
 
 impl EvmEnvProvider for MockEthProvider {
@@ -17,6 +17,7 @@ use reth_primitives::{
     SealedHeader, StorageKey, StorageValue, TransactionMeta, TransactionSigned,
     TransactionSignedNoHash, TxHash, TxNumber, B256, MAINNET, U256,
 };
+use reth_trie::updates::TrieUpdates;
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
     ops::{RangeBounds, RangeInclusive},
@@ -266,6 +267,13 @@ impl StateRootProvider for NoopProvider {
     fn state_root(&self, _state: &BundleStateWithReceipts) -> RethResult<B256> {
         todo!()
     }
+
+    fn state_root_with_updates(
+        &self,
+        _state: &BundleStateWithReceipts,
+    ) -> RethResult<(B256, TrieUpdates)> {
+        Ok((B256::default(), TrieUpdates::default()))
+    }
 }
 
 impl StateProvider for NoopProvider {
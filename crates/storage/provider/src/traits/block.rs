@@ -10,6 +10,7 @@ use reth_primitives::{
     ChainSpec, Header, PruneModes, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
     B256,
 };
+use reth_trie::updates::TrieUpdates;
 use std::ops::RangeInclusive;
 
 /// Enum to control transaction hash inclusion.
@@ -280,6 +281,8 @@ pub trait BlockWriter: Send + Sync {
     ///
     /// - `blocks`: Vector of `SealedBlockWithSenders` instances to append.
     /// - `state`: Post-state information to update after appending.
+    /// - `trie_updates`: Optional trie updates that were already computed for the state, which are
+    ///   written instead of recomputing the state root.
     /// - `prune_modes`: Optional pruning configuration.
     ///
     /// # Returns
@@ -289,6 +292,7 @@ pub trait BlockWriter: Send + Sync {
         &self,
         blocks: Vec<SealedBlockWithSenders>,
         state: BundleStateWithReceipts,
+        trie_updates: Option<TrieUpdates>,
         prune_modes: Option<&PruneModes>,
     ) -> RethResult<()>;
 }
@@ -2,8 +2,8 @@
 
 use crate::{bundle_state::BundleStateWithReceipts, StateProvider};
 use reth_interfaces::executor::BlockExecutionError;
-use reth_primitives::{Address, Block, BlockNumber, ChainSpec, PruneModes, U256};
-use std::time::Duration;
+use reth_primitives::{Address, Block, BlockHash, BlockNumber, ChainSpec, PruneModes, U256};
+use std::{collections::BTreeMap, time::Duration};
 use tracing::debug;
 
 /// Executor factory that would create the EVM with particular state provider.
@@ -16,6 +16,18 @@ pub trait ExecutorFactory: Send + Sync + 'static {
         _sp: SP,
     ) -> Box<dyn PrunableBlockExecutor + 'a>;
 
+    /// Executor with [`StateProvider`] and known hashes of the ancestors of the executed blocks.
+    ///
+    /// The hashes can include ancestors that are not canonical and only available in memory. By
+    /// default, the hashes are ignored and all hashes are looked up in the state provider.
+    fn with_state_and_block_hashes<'a, SP: StateProvider + 'a>(
+        &'a self,
+        sp: SP,
+        _block_hashes: BTreeMap<BlockNumber, BlockHash>,
+    ) -> Box<dyn PrunableBlockExecutor + 'a> {
+        self.with_state(sp)
+    }
+
     /// Return internal chainspec
     fn chain_spec(&self) -> &ChainSpec;
 }
@@ -4,8 +4,9 @@ use auto_impl::auto_impl;
 use reth_interfaces::{provider::ProviderError, RethResult};
 use reth_primitives::{
     trie::AccountProof, Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag,
-    Bytecode, StorageKey, StorageValue, B256, KECCAK_EMPTY, U256,
+    Bytecode, StorageEntry, StorageKey, StorageValue, B256, KECCAK_EMPTY, U256,
 };
+use reth_trie::updates::TrieUpdates;
 
 /// Type alias of boxed [StateProvider].
 pub type StateProviderBox<'a> = Box<dyn StateProvider + 'a>;
@@ -26,6 +27,14 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
     /// Get account and storage proofs.
     fn proof(&self, address: Address, keys: &[B256]) -> RethResult<AccountProof>;
 
+    /// Get all non-zero storage slots of the given account, in no particular order.
+    ///
+    /// Returns [ProviderError::StorageEntriesNotAvailable] if the provider can't enumerate the
+    /// storage of an account.
+    fn storage_entries(&self, _account: Address) -> RethResult<Vec<StorageEntry>> {
+        Err(ProviderError::StorageEntriesNotAvailable.into())
+    }
+
     /// Get account code by its address.
     ///
     /// Returns `None` if the account doesn't exist or account is not a contract
@@ -232,4 +241,11 @@ pub trait BundleStateDataProvider: Send + Sync {
 pub trait StateRootProvider: Send + Sync {
     /// Returns the state root of the BundleState on top of the current state.
     fn state_root(&self, post_state: &BundleStateWithReceipts) -> RethResult<B256>;
+
+    /// Returns the state root of the BundleState on top of the current state, and the trie
+    /// updates that lead to it.
+    fn state_root_with_updates(
+        &self,
+        post_state: &BundleStateWithReceipts,
+    ) -> RethResult<(B256, TrieUpdates)>;
 }
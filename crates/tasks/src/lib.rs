@@ -34,6 +34,7 @@ use tracing_futures::Instrument;
 
 pub mod metrics;
 pub mod shutdown;
+pub mod supervisor;
 
 /// A type that can spawn tasks.
 ///
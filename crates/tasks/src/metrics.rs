@@ -27,6 +27,17 @@ impl TaskExecutorMetrics {
     }
 }
 
+/// Metrics of a task spawned by the [TaskSupervisor](crate::supervisor::TaskSupervisor), labeled
+/// with the name of the task.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "executor.supervised")]
+pub(crate) struct SupervisedTaskMetrics {
+    /// Number of panics of the task
+    pub(crate) panics: Counter,
+    /// Number of restarts of the task
+    pub(crate) restarts: Counter,
+}
+
 /// Helper type for increasing counters even if a task fails.
 pub struct IncCounterOnDrop(Counter);
 
@@ -0,0 +1,276 @@
+//! Supervision of long-running tasks that are restarted when they panic.
+
+use crate::{metrics::SupervisedTaskMetrics, PanickedTaskError, TaskExecutor};
+use futures_util::{Future, FutureExt};
+use std::{
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+/// Determines what happens when a supervised task panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// The task is not restarted, the panic is only logged and counted.
+    Never,
+    /// The task is restarted after an exponentially increasing delay.
+    WithBackoff(Backoff),
+    /// The panic is reported to the [TaskManager](crate::TaskManager), which shuts down the node,
+    /// like for tasks spawned with [TaskExecutor::spawn_critical].
+    EscalateToShutdown,
+}
+
+/// The delays between the restarts of a task with [RestartPolicy::WithBackoff].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// The delay before the first restart, which doubles with every further restart.
+    pub initial: Duration,
+    /// The maximum delay between two restarts.
+    pub max: Duration,
+    /// The maximum number of restarts, after which the task is considered failed. Unlimited if
+    /// not set.
+    pub max_restarts: Option<usize>,
+}
+
+impl Backoff {
+    /// Returns the delay before the restart after the given number of previous restarts.
+    pub fn delay(&self, restarts: usize) -> Duration {
+        let factor = 1u32.checked_shl(restarts as u32).unwrap_or(u32::MAX);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self { initial: Duration::from_secs(1), max: Duration::from_secs(60), max_restarts: None }
+    }
+}
+
+/// The health of a supervised task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskHealth {
+    /// The task is running.
+    Running,
+    /// The task panicked and waits to be restarted.
+    Restarting,
+    /// The task completed.
+    Finished,
+    /// The task panicked and is not restarted.
+    Failed,
+}
+
+impl TaskHealth {
+    /// Returns true if the task is running or completed without a panic.
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, TaskHealth::Running | TaskHealth::Finished)
+    }
+}
+
+/// The status of a supervised task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskStatus {
+    /// The current health of the task.
+    pub health: TaskHealth,
+    /// The delays before every restart of the task, in order.
+    pub restart_delays: Vec<Duration>,
+}
+
+/// Spawns tasks that are supervised according to a [RestartPolicy].
+///
+/// A supervised task is created by a factory, which is called again for every restart. The factory
+/// is responsible for re-establishing the subscriptions of the task, e.g. by subscribing to a new
+/// stream of events, since the channels of the panicked task are dropped with it.
+///
+/// Like all tasks spawned by the [TaskExecutor], supervised tasks are terminated on shutdown.
+#[derive(Debug, Clone)]
+pub struct TaskSupervisor {
+    executor: TaskExecutor,
+    /// The status of all supervised tasks, by name.
+    tasks: Arc<Mutex<HashMap<&'static str, TaskStatus>>>,
+}
+
+impl TaskSupervisor {
+    /// Creates a new supervisor that spawns tasks with the given executor.
+    pub fn new(executor: TaskExecutor) -> Self {
+        Self { executor, tasks: Default::default() }
+    }
+
+    /// Returns the status of the supervised task with the given name.
+    pub fn status(&self, name: &str) -> Option<TaskStatus> {
+        self.tasks.lock().unwrap().get(name).cloned()
+    }
+
+    /// Spawns the future created by the factory as a supervised task with the given name.
+    ///
+    /// If the future panics, the panic is logged and counted, and the task is handled according
+    /// to the [RestartPolicy].
+    pub fn spawn_supervised<F, Fut>(
+        &self,
+        name: &'static str,
+        policy: RestartPolicy,
+        mut factory: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let panicked_tasks_tx = self.executor.panicked_tasks_tx.clone();
+        let metrics = SupervisedTaskMetrics::new_with_labels(&[("task", name)]);
+        let tasks = self.tasks.clone();
+        let set_health = move |health| {
+            tasks
+                .lock()
+                .unwrap()
+                .entry(name)
+                .or_insert_with(|| TaskStatus { health, restart_delays: Vec::new() })
+                .health = health;
+        };
+        let tasks = self.tasks.clone();
+        let record_restart = move |delay| {
+            if let Some(status) = tasks.lock().unwrap().get_mut(name) {
+                status.restart_delays.push(delay);
+            }
+        };
+
+        let task = async move {
+            let mut restarts = 0;
+            loop {
+                set_health(TaskHealth::Running);
+                // the factory is called within the task, so a panic while creating the future
+                // is handled like a panic of the future
+                let Err(error) = AssertUnwindSafe(async { factory().await }).catch_unwind().await
+                else {
+                    set_health(TaskHealth::Finished);
+                    return
+                };
+
+                let task_error = PanickedTaskError::new(name, error);
+                metrics.panics.increment(1);
+                error!(
+                    target: "tasks::supervisor",
+                    task = name,
+                    error = ?task_error.error,
+                    restarts,
+                    "Supervised task panicked"
+                );
+
+                let backoff = match policy {
+                    RestartPolicy::Never => {
+                        set_health(TaskHealth::Failed);
+                        return
+                    }
+                    RestartPolicy::EscalateToShutdown => {
+                        set_health(TaskHealth::Failed);
+                        let _ = panicked_tasks_tx.send(task_error);
+                        return
+                    }
+                    RestartPolicy::WithBackoff(backoff) => backoff,
+                };
+                if backoff.max_restarts.is_some_and(|max| restarts >= max) {
+                    error!(
+                        target: "tasks::supervisor",
+                        task = name,
+                        restarts,
+                        "Supervised task exceeded its maximum number of restarts"
+                    );
+                    set_health(TaskHealth::Failed);
+                    return
+                }
+
+                let delay = backoff.delay(restarts);
+                warn!(
+                    target: "tasks::supervisor",
+                    task = name,
+                    ?delay,
+                    "Restarting supervised task"
+                );
+                set_health(TaskHealth::Restarting);
+                record_restart(delay);
+                tokio::time::sleep(delay).await;
+
+                restarts += 1;
+                metrics.restarts.increment(1);
+            }
+        };
+
+        self.executor.spawn(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaskManager;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn restarts_with_backoff_until_healthy() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let manager = TaskManager::new(runtime.handle().clone());
+        let supervisor = TaskSupervisor::new(manager.executor());
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task_runs = runs.clone();
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(1),
+            max_restarts: None,
+        };
+        let task = supervisor.spawn_supervised(
+            "flaky task",
+            RestartPolicy::WithBackoff(backoff),
+            move || {
+                let run = task_runs.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if run < 2 {
+                        panic!("intentionally panic");
+                    }
+                }
+            },
+        );
+        runtime.block_on(task).unwrap();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        let status = supervisor.status("flaky task").unwrap();
+        assert_eq!(
+            status.restart_delays,
+            vec![Duration::from_millis(10), Duration::from_millis(20)]
+        );
+        assert!(status.restart_delays[0] < status.restart_delays[1]);
+        assert_eq!(status.health, TaskHealth::Finished);
+        assert!(status.health.is_healthy());
+    }
+
+    #[test]
+    fn escalates_to_shutdown() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let manager = TaskManager::new(runtime.handle().clone());
+        let supervisor = TaskSupervisor::new(manager.executor());
+
+        supervisor.spawn_supervised("engine", RestartPolicy::EscalateToShutdown, || async {
+            panic!("intentionally panic")
+        });
+
+        runtime.block_on(async move {
+            let err = manager.await;
+            assert_eq!(err.task_name, "engine");
+        });
+        assert_eq!(supervisor.status("engine").unwrap().health, TaskHealth::Failed);
+    }
+
+    #[test]
+    fn backoff_delay_is_capped() {
+        let backoff = Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(10),
+            max_restarts: None,
+        };
+        assert_eq!(backoff.delay(0), Duration::from_secs(1));
+        assert_eq!(backoff.delay(3), Duration::from_secs(8));
+        assert_eq!(backoff.delay(4), Duration::from_secs(10));
+        assert_eq!(backoff.delay(100), Duration::from_secs(10));
+    }
+}
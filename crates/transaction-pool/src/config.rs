@@ -1,4 +1,5 @@
 use reth_primitives::EIP4844_TX_TYPE_ID;
+use std::{path::PathBuf, time::Duration};
 
 /// Guarantees max transactions for one sender, compatible with geth/erigon
 pub const TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
@@ -30,6 +31,12 @@ pub struct PoolConfig {
     pub max_account_slots: usize,
     /// Price bump (in %) for the transaction pool underpriced check.
     pub price_bumps: PriceBumpConfig,
+    /// Limits for senders that are identified as spammers.
+    pub spam_guard: SpamGuardConfig,
+    /// Disk backed overflow tier for transactions evicted from the full queued sub-pool.
+    ///
+    /// Disabled if not set, evicted queued transactions are then discarded.
+    pub queued_overflow: Option<QueuedOverflowConfig>,
 }
 
 impl Default for PoolConfig {
@@ -40,6 +47,8 @@ impl Default for PoolConfig {
             queued_limit: Default::default(),
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bumps: Default::default(),
+            spam_guard: Default::default(),
+            queued_overflow: None,
         }
     }
 }
@@ -71,6 +80,30 @@ impl Default for SubPoolLimit {
     }
 }
 
+/// Default time a transaction is kept in the queued overflow tier.
+pub const QUEUED_OVERFLOW_TTL_DEFAULT: Duration = Duration::from_secs(3 * 60 * 60);
+
+/// Config of the disk backed overflow tier of the queued sub-pool.
+///
+/// Instead of discarding the worst transactions of the full queued sub-pool, they are written to
+/// disk and re-inserted once their nonce gap is closed.
+#[derive(Debug, Clone)]
+pub struct QueuedOverflowConfig {
+    /// Directory the evicted transactions are stored in.
+    pub dir: PathBuf,
+    /// Max number and combined size (in bytes) of the stored transactions.
+    pub limit: SubPoolLimit,
+    /// How long a transaction is stored before it is discarded.
+    pub ttl: Duration,
+}
+
+impl QueuedOverflowConfig {
+    /// Creates a config that stores transactions in the given directory with the default limits.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), limit: Default::default(), ttl: QUEUED_OVERFLOW_TTL_DEFAULT }
+    }
+}
+
 /// Price bump config (in %) for the transaction pool underpriced check.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct PriceBumpConfig {
@@ -99,3 +132,45 @@ impl Default for PriceBumpConfig {
         }
     }
 }
+
+/// Default length of the window over which the activity of a sender is scored.
+pub const SPAM_GUARD_WINDOW_DEFAULT: Duration = Duration::from_secs(10);
+
+/// Default spam score at which a sender is throttled.
+pub const SPAM_GUARD_THRESHOLD_DEFAULT: f64 = 100.0;
+
+/// Spam scoring config for the [PoolSpamGuard](crate::pool::PoolSpamGuard).
+///
+/// Every insertion, replacement and invalidation of a sender within the window adds its weight to
+/// the sender's score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpamGuardConfig {
+    /// Length of the sliding window over which the activity of a sender is scored.
+    pub window: Duration,
+    /// Score of an inserted transaction.
+    pub insertion_weight: f64,
+    /// Score of a transaction that replaced another transaction of the same sender.
+    pub replacement_weight: f64,
+    /// Score of a transaction that failed validation.
+    pub invalidation_weight: f64,
+    /// Score at which a sender is throttled.
+    pub threshold: f64,
+    /// Max number of transactions a throttled sender may have in the pool.
+    pub throttled_max_account_slots: usize,
+    /// Price bump (in %) a throttled sender needs to replace a transaction.
+    pub throttled_price_bump: u128,
+}
+
+impl Default for SpamGuardConfig {
+    fn default() -> Self {
+        Self {
+            window: SPAM_GUARD_WINDOW_DEFAULT,
+            insertion_weight: 1.0,
+            replacement_weight: 4.0,
+            invalidation_weight: 8.0,
+            threshold: SPAM_GUARD_THRESHOLD_DEFAULT,
+            throttled_max_account_slots: 4,
+            throttled_price_bump: 100,
+        }
+    }
+}
@@ -32,6 +32,10 @@ pub enum PoolError {
     /// Thrown when the number of unique transactions of a sender exceeded the slot capacity.
     #[error("{0:?} identified as spammer. Transaction {1:?} rejected.")]
     SpammerExceededCapacity(Address, TxHash),
+    /// Thrown when the sender is throttled by the spam guard and the transaction exceeds the
+    /// stricter limits for throttled senders.
+    #[error("{0:?} is rate limited. Transaction {1:?} rejected.")]
+    RateLimited(Address, TxHash),
     /// Thrown when a new transaction is added to the pool, but then immediately discarded to
     /// respect the size limits of the pool.
     #[error("[{0:?}] Transaction discarded outright due to pool size constraints.")]
@@ -58,6 +62,7 @@ impl PoolError {
             PoolError::ReplacementUnderpriced(hash) => hash,
             PoolError::FeeCapBelowMinimumProtocolFeeCap(hash, _) => hash,
             PoolError::SpammerExceededCapacity(_, hash) => hash,
+            PoolError::RateLimited(_, hash) => hash,
             PoolError::DiscardedOnInsert(hash) => hash,
             PoolError::InvalidTransaction(hash, _) => hash,
             PoolError::Other(hash, _) => hash,
@@ -102,6 +107,11 @@ impl PoolError {
                 // (pool lags behind) and old transaction still occupy a slot in the pool
                 false
             }
+            PoolError::RateLimited(_, _) => {
+                // the sender is throttled because of its recent activity, which is not a property
+                // of the transaction itself
+                false
+            }
             PoolError::DiscardedOnInsert(_) => {
                 // valid tx but dropped due to size constraints
                 false
@@ -155,7 +155,9 @@ use tracing::{instrument, trace};
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
     config::{
-        PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP,
+        PoolConfig, PriceBumpConfig, QueuedOverflowConfig, SpamGuardConfig, SubPoolLimit,
+        DEFAULT_PRICE_BUMP, QUEUED_OVERFLOW_TTL_DEFAULT, REPLACE_BLOB_PRICE_BUMP,
+        SPAM_GUARD_THRESHOLD_DEFAULT, SPAM_GUARD_WINDOW_DEFAULT,
         TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
         TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
@@ -257,6 +259,19 @@ where
         (hash, outcome)
     }
 
+    /// Validates and inserts the transactions of the queued overflow tier that became executable
+    /// after transactions of the given senders were inserted.
+    async fn rehydrate_overflow(&self, senders: impl IntoIterator<Item = Address>) {
+        for (origin, transaction) in self.pool.take_rehydratable(senders) {
+            let (hash, outcome) = self.validate(origin, transaction).await;
+            if let Some(Err(err)) =
+                self.pool.add_transactions(origin, std::iter::once(outcome)).pop()
+            {
+                trace!(target: "txpool", %err, ?hash, "failed to rehydrate queued transaction");
+            }
+        }
+    }
+
     /// Number of transactions in the entire pool
     pub fn len(&self) -> usize {
         self.pool.len()
@@ -327,8 +342,13 @@ where
         origin: TransactionOrigin,
         transaction: Self::Transaction,
     ) -> PoolResult<TransactionEvents> {
+        self.pool.check_spam_guard(origin, &transaction)?;
+        let sender = transaction.sender();
+        let _turn = self.pool.sender_turn(sender).await;
         let (_, tx) = self.validate(origin, transaction).await;
-        self.pool.add_transaction_and_subscribe(origin, tx)
+        let res = self.pool.add_transaction_and_subscribe(origin, tx);
+        self.rehydrate_overflow([sender]).await;
+        res
     }
 
     async fn add_transaction(
@@ -336,8 +356,15 @@ where
         origin: TransactionOrigin,
         transaction: Self::Transaction,
     ) -> PoolResult<TxHash> {
+        self.pool.check_spam_guard(origin, &transaction)?;
+        let sender = transaction.sender();
+        // validation can finish out of order, e.g. for blob transactions
+        let _turn = self.pool.sender_turn(sender).await;
         let (_, tx) = self.validate(origin, transaction).await;
-        self.pool.add_transactions(origin, std::iter::once(tx)).pop().expect("exists; qed")
+        let res =
+            self.pool.add_transactions(origin, std::iter::once(tx)).pop().expect("exists; qed");
+        self.rehydrate_overflow([sender]).await;
+        res
     }
 
     async fn add_transactions(
@@ -345,10 +372,29 @@ where
         origin: TransactionOrigin,
         transactions: Vec<Self::Transaction>,
     ) -> PoolResult<Vec<PoolResult<TxHash>>> {
+        // reject transactions of throttled senders before validating them
+        let mut rate_limited = Vec::new();
+        let transactions = transactions
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, tx)| match self.pool.check_spam_guard(origin, &tx) {
+                Ok(()) => Some(tx),
+                Err(err) => {
+                    rate_limited.push((idx, err));
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let senders = transactions.iter().map(|tx| tx.sender()).collect::<HashSet<_>>();
+
         let validated = self.validate_all(origin, transactions).await?;
 
-        let transactions =
+        let mut transactions =
             self.pool.add_transactions(origin, validated.into_iter().map(|(_, tx)| tx));
+        for (idx, err) in rate_limited {
+            transactions.insert(idx, Err(err));
+        }
+        self.rehydrate_overflow(senders).await;
         Ok(transactions)
     }
 
@@ -375,6 +421,13 @@ where
         self.pool.add_new_transaction_listener(kind)
     }
 
+    fn new_transactions_filtered(
+        &self,
+        filter: TransactionFilter,
+    ) -> FilteredTransactionStream<Self::Transaction> {
+        self.pool.add_filtered_transaction_listener(filter)
+    }
+
     fn pooled_transaction_hashes(&self) -> Vec<TxHash> {
         self.pool.pooled_transactions_hashes()
     }
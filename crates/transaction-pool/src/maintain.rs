@@ -12,7 +12,8 @@ use futures_util::{
 };
 use reth_interfaces::RethError;
 use reth_primitives::{
-    Address, BlockHash, BlockNumber, BlockNumberOrTag, FromRecoveredTransaction,
+    basefee::next_block_base_fee, Address, BlockHash, BlockNumber, BlockNumberOrTag,
+    FromRecoveredTransaction,
 };
 use reth_provider::{
     BlockReaderIdExt, BundleStateWithReceipts, CanonStateNotification, ChainSpecProvider,
@@ -91,9 +92,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
         let info = BlockInfo {
             last_seen_block_hash: latest.hash,
             last_seen_block_number: latest.number,
-            pending_basefee: latest
-                .next_block_base_fee(chain_spec.base_fee_params)
-                .unwrap_or_default(),
+            pending_basefee: next_block_base_fee(&latest, &chain_spec).unwrap_or_default(),
             pending_blob_fee: latest.next_block_blob_fee(),
         };
         pool.set_block_info(info);
@@ -239,7 +238,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
 
                 // fees for the next block: `new_tip+1`
                 let pending_block_base_fee =
-                    new_tip.next_block_base_fee(chain_spec.base_fee_params).unwrap_or_default();
+                    next_block_base_fee(new_tip, &chain_spec).unwrap_or_default();
                 let pending_block_blob_fee = new_tip.next_block_blob_fee();
 
                 // we know all changed account in the new chain
@@ -320,7 +319,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
 
                 // fees for the next block: `tip+1`
                 let pending_block_base_fee =
-                    tip.next_block_base_fee(chain_spec.base_fee_params).unwrap_or_default();
+                    next_block_base_fee(tip, &chain_spec).unwrap_or_default();
                 let pending_block_blob_fee = tip.next_block_blob_fee();
 
                 let first_block = blocks.first();
@@ -1,7 +1,7 @@
 //! Transaction pool metrics.
 
 use reth_metrics::{
-    metrics::{Counter, Gauge},
+    metrics::{Counter, Gauge, Histogram},
     Metrics,
 };
 
@@ -52,6 +52,26 @@ pub struct BlobStoreMetrics {
     pub(crate) blobstore_entries: Gauge,
 }
 
+/// Transaction pool KZG proof verification metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool")]
+pub struct KzgVerificationMetrics {
+    /// How long it took to verify the KZG proofs of a blob transaction
+    pub(crate) kzg_verification_duration: Histogram,
+    /// Number of blob transactions whose blobs were already verified
+    pub(crate) kzg_verification_cache_hits: Counter,
+}
+
+/// Transaction pool spam guard metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool")]
+pub struct SpamGuardMetrics {
+    /// Number of times a sender was throttled
+    pub(crate) throttled_senders: Counter,
+    /// Number of transactions of throttled senders that were rejected
+    pub(crate) rate_limited_transactions: Counter,
+}
+
 /// Transaction pool maintenance metrics
 #[derive(Metrics)]
 #[metrics(scope = "transaction_pool")]
@@ -7,8 +7,8 @@ use crate::{
     blobstore::BlobStoreError,
     error::PoolError,
     traits::{
-        BestTransactionsAttributes, GetPooledTransactionLimit, NewBlobSidecar,
-        TransactionListenerKind,
+        BestTransactionsAttributes, FilteredTransactionStream, GetPooledTransactionLimit,
+        NewBlobSidecar, TransactionFilter, TransactionListenerKind,
     },
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPooledTransaction,
@@ -107,6 +107,13 @@ impl TransactionPool for NoopTransactionPool {
         mpsc::channel(1).1
     }
 
+    fn new_transactions_filtered(
+        &self,
+        _filter: TransactionFilter,
+    ) -> FilteredTransactionStream<Self::Transaction> {
+        FilteredTransactionStream::new(mpsc::channel(1).1, Default::default())
+    }
+
     fn pooled_transaction_hashes(&self) -> Vec<TxHash> {
         vec![]
     }
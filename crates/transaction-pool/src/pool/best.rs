@@ -1,8 +1,8 @@
 use crate::{
-    identifier::TransactionId, pool::pending::PendingTransaction, PoolTransaction,
-    TransactionOrdering, ValidPoolTransaction,
+    identifier::{SenderId, TransactionId},
+    pool::pending::PendingTransaction,
+    PoolTransaction, TransactionOrdering, ValidPoolTransaction,
 };
-use reth_primitives::B256 as TxHash;
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
     sync::Arc,
@@ -13,15 +13,18 @@ use tracing::debug;
 /// An iterator that returns transactions that can be executed on the current state (*best*
 /// transactions).
 ///
-/// This is a wrapper around [`BestTransactions`] that also enforces a specific basefee.
+/// This is a wrapper around [`BestTransactions`] that also enforces a specific basefee and
+/// optionally a blob fee.
 ///
-/// This iterator guarantees that all transaction it returns satisfy the base fee.
-pub(crate) struct BestTransactionsWithBasefee<T: TransactionOrdering> {
+/// This iterator guarantees that all transaction it returns satisfy both the base fee and the blob
+/// fee. Transactions that don't are skipped together with all their descendants.
+pub(crate) struct BestTransactionsWithFees<T: TransactionOrdering> {
     pub(crate) best: BestTransactions<T>,
     pub(crate) base_fee: u64,
+    pub(crate) blob_fee: Option<u64>,
 }
 
-impl<T: TransactionOrdering> crate::traits::BestTransactions for BestTransactionsWithBasefee<T> {
+impl<T: TransactionOrdering> crate::traits::BestTransactions for BestTransactionsWithFees<T> {
     fn mark_invalid(&mut self, tx: &Self::Item) {
         BestTransactions::mark_invalid(&mut self.best, tx)
     }
@@ -39,15 +42,20 @@ impl<T: TransactionOrdering> crate::traits::BestTransactions for BestTransaction
     }
 }
 
-impl<T: TransactionOrdering> Iterator for BestTransactionsWithBasefee<T> {
+impl<T: TransactionOrdering> Iterator for BestTransactionsWithFees<T> {
     type Item = Arc<ValidPoolTransaction<T::Transaction>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // find the next transaction that satisfies the base fee
+        // find the next transaction that satisfies the base fee and the blob fee
         loop {
             let best = self.best.next()?;
-            if best.transaction.max_fee_per_gas() < self.base_fee as u128 {
-                // tx violates base fee, mark it as invalid and continue
+            let violates_blob_fee = self.blob_fee.map_or(false, |blob_fee| {
+                best.transaction
+                    .max_fee_per_blob_gas()
+                    .map_or(false, |max_fee_per_blob_gas| max_fee_per_blob_gas < blob_fee as u128)
+            });
+            if best.transaction.max_fee_per_gas() < self.base_fee as u128 || violates_blob_fee {
+                // tx violates the fees, mark it as invalid and continue
                 crate::traits::BestTransactions::mark_invalid(self, &best);
             } else {
                 return Some(best)
@@ -72,8 +80,12 @@ pub(crate) struct BestTransactions<T: TransactionOrdering> {
     /// Once an `independent` transaction with the nonce `N` is returned, it unlocks `N+1`, which
     /// then can be moved from the `all` set to the `independent` set.
     pub(crate) independent: BTreeSet<PendingTransaction<T>>,
-    /// There might be the case where a yielded transactions is invalid, this will track it.
-    pub(crate) invalid: HashSet<TxHash>,
+    /// There might be the case where a yielded transactions is invalid, this will track the
+    /// senders of those.
+    ///
+    /// Since the transactions of a sender are yielded in nonce order, all descendants of an
+    /// invalid transaction are skipped, including those that were already unlocked.
+    pub(crate) invalid: HashSet<SenderId>,
     /// Used to receive any new pending transactions that have been added to the pool after this
     /// iterator was snapshotted
     ///
@@ -87,7 +99,7 @@ pub(crate) struct BestTransactions<T: TransactionOrdering> {
 impl<T: TransactionOrdering> BestTransactions<T> {
     /// Mark the transaction and it's descendants as invalid.
     pub(crate) fn mark_invalid(&mut self, tx: &Arc<ValidPoolTransaction<T::Transaction>>) {
-        self.invalid.insert(*tx.hash());
+        self.invalid.insert(tx.sender_id());
     }
 
     /// Returns the ancestor the given transaction, the transaction with `nonce - 1`.
@@ -162,14 +174,14 @@ impl<T: TransactionOrdering> Iterator for BestTransactions<T> {
             self.add_new_transactions();
             // Remove the next independent tx with the highest priority
             let best = self.independent.pop_last()?;
-            let hash = best.transaction.hash();
 
-            // skip transactions that were marked as invalid
-            if self.invalid.contains(hash) {
+            // skip transactions of senders with an invalid transaction, these are descendants of
+            // the invalid transaction
+            if self.invalid.contains(&best.transaction.sender_id()) {
                 debug!(
                     target: "txpool",
                     "[{:?}] skipping invalid transaction",
-                    hash
+                    best.transaction.hash()
                 );
                 continue
             }
@@ -247,4 +259,43 @@ mod tests {
         // iterator is empty
         assert!(best.next().is_none());
     }
+
+    #[test]
+    fn test_best_iter_invalid_skips_unlocked_descendants() {
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let mut f = MockTransactionFactory::default();
+
+        let tx = MockTransaction::eip1559();
+        for nonce in 0..3 {
+            let tx = tx.clone().rng_hash().with_nonce(nonce);
+            pool.add_transaction(Arc::new(f.validated(tx)), 0);
+        }
+        let other = MockTransaction::eip1559();
+        pool.add_transaction(Arc::new(f.validated(other.clone())), 0);
+
+        let mut best = pool.best();
+        let mut yielded = Vec::new();
+        while let Some(tx) = best.next() {
+            // the first tx of the sender already unlocked its descendant when it was yielded
+            if tx.sender() != other.get_sender() {
+                best.mark_invalid(&tx);
+            }
+            yielded.push(*tx.hash());
+        }
+        assert_eq!(yielded.len(), 2);
+        assert!(yielded.contains(&other.get_hash()));
+    }
+
+    #[test]
+    fn test_best_with_fees_skips_descendants() {
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let mut f = MockTransactionFactory::default();
+
+        let tx = MockTransaction::eip1559().with_max_fee(10);
+        pool.add_transaction(Arc::new(f.validated(tx.clone())), 0);
+        pool.add_transaction(Arc::new(f.validated(tx.next().with_max_fee(100))), 0);
+
+        assert_eq!(pool.best_with_fees(10, None).count(), 2);
+        assert_eq!(pool.best_with_fees(11, None).count(), 0);
+    }
 }
@@ -70,12 +70,14 @@ use crate::{
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
         listener::PoolEventBroadcast,
+        overflow::QueuedOverflow,
         state::SubPool,
-        txpool::{SenderInfo, TxPool},
+        txpool::{DiscardedTransactions, SenderInfo, TxPool},
     },
     traits::{
-        AllPoolTransactions, BestTransactionsAttributes, BlockInfo, NewTransactionEvent, PoolSize,
-        PoolTransaction, PropagatedTransactions, TransactionOrigin,
+        AllPoolTransactions, BestTransactionsAttributes, BlockInfo, FilteredTransactionStream,
+        NewTransactionEvent, PoolSize, PoolTransaction, PropagatedTransactions, TransactionFilter,
+        TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
     CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
@@ -89,7 +91,10 @@ use reth_primitives::{
 use std::{
     collections::{HashMap, HashSet},
     fmt,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     time::Instant,
 };
 use tokio::sync::mpsc;
@@ -107,12 +112,17 @@ use crate::{
 };
 use alloy_rlp::Encodable;
 pub use listener::{AllTransactionsEvents, TransactionEvents};
+use sequencer::{SenderSequencer, SenderTurn};
+pub use spam::{PoolSpamGuard, SpamEvent};
 
 mod best;
 mod blob;
+mod overflow;
 mod parked;
 pub(crate) mod pending;
+mod sequencer;
 pub(crate) mod size;
+mod spam;
 pub(crate) mod state;
 pub mod txpool;
 mod update;
@@ -142,10 +152,18 @@ where
     pending_transaction_listener: Mutex<Vec<PendingTransactionHashListener>>,
     /// Listeners for new transactions added to the pool.
     transaction_listener: Mutex<Vec<TransactionListener<T::Transaction>>>,
+    /// Listeners for new transactions added to the pool that match a filter.
+    filtered_transaction_listener: Mutex<Vec<FilteredTransactionListener<T::Transaction>>>,
     /// Listener for new blob transaction sidecars added to the pool.
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Tracks the activity of senders to throttle spammers.
+    spam_guard: Mutex<PoolSpamGuard>,
+    /// Keeps concurrent submissions of the same sender in order.
+    sender_sequencer: SenderSequencer,
+    /// Disk backed storage for transactions evicted from the full queued sub-pool, if enabled.
+    queued_overflow: Option<Mutex<QueuedOverflow>>,
 }
 
 // === impl PoolInner ===
@@ -158,17 +176,26 @@ where
 {
     /// Create a new transaction pool instance.
     pub(crate) fn new(validator: V, ordering: T, blob_store: S, config: PoolConfig) -> Self {
+        let queued_overflow = config.queued_overflow.clone().and_then(|overflow| {
+            QueuedOverflow::open(overflow)
+                .map_err(|err| warn!(target: "txpool", %err, "failed to open queued overflow tier"))
+                .ok()
+        });
         Self {
             identifiers: Default::default(),
             validator,
             event_listener: Default::default(),
             pool: RwLock::new(TxPool::new(ordering, config.clone())),
+            spam_guard: Mutex::new(PoolSpamGuard::new(config.spam_guard.clone())),
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
+            filtered_transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
             config,
             blob_store,
             blob_store_metrics: Default::default(),
+            sender_sequencer: Default::default(),
+            queued_overflow: queued_overflow.map(Mutex::new),
         }
     }
 
@@ -246,6 +273,20 @@ where
         self.transaction_listener.lock().push(listener);
         rx
     }
+
+    /// Adds a new transaction listener to the pool that gets notified about every new transaction
+    /// that matches the filter.
+    pub fn add_filtered_transaction_listener(
+        &self,
+        filter: TransactionFilter,
+    ) -> FilteredTransactionStream<T::Transaction> {
+        let (sender, rx) = mpsc::channel(NEW_TX_LISTENER_BUFFER_SIZE);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let listener = FilteredTransactionListener { sender, filter, dropped: dropped.clone() };
+        self.filtered_transaction_listener.lock().push(listener);
+        FilteredTransactionStream::new(rx, dropped)
+    }
+
     /// Adds a new blob sidecar listener to the pool that gets notified about every new
     /// eip4844 transaction's blob sidecar.
     pub fn add_blob_sidecar_listener(&self) -> mpsc::Receiver<NewBlobSidecar> {
@@ -340,6 +381,12 @@ where
         let CanonicalStateUpdate { new_tip, changed_accounts, mined_transactions, .. } = update;
         self.validator.on_new_head_block(new_tip);
 
+        if let Some(overflow) = &self.queued_overflow {
+            overflow
+                .lock()
+                .on_nonce_changes(changed_accounts.iter().map(|acc| (acc.address, acc.nonce)));
+        }
+
         let changed_senders = self.changed_senders(changed_accounts.into_iter());
 
         // update the pool
@@ -373,6 +420,54 @@ where
         self.delete_discarded_blobs(discarded.iter());
     }
 
+    /// Waits until all earlier submissions of the sender have been validated and inserted.
+    ///
+    /// The returned turn must be held until the transaction was inserted.
+    pub(crate) async fn sender_turn(&self, sender: Address) -> SenderTurn<'_> {
+        self.sender_sequencer.turn(sender).await
+    }
+
+    /// Checks the transaction against the stricter limits for senders that are throttled by the
+    /// [PoolSpamGuard].
+    ///
+    /// This is cheap and intended to be called before the transaction is validated. Local
+    /// transactions are exempt.
+    pub(crate) fn check_spam_guard(
+        &self,
+        origin: TransactionOrigin,
+        transaction: &T::Transaction,
+    ) -> PoolResult<()> {
+        if origin.is_local() {
+            return Ok(())
+        }
+
+        let sender = transaction.sender();
+        let mut spam_guard = self.spam_guard.lock();
+        if !spam_guard.is_throttled(&sender, Instant::now()) {
+            return Ok(())
+        }
+
+        let sender_txs = match self.identifiers.read().sender_id(&sender) {
+            Some(sender_id) => self.pool.read().get_transactions_by_sender(sender_id),
+            None => Vec::new(),
+        };
+        let config = spam_guard.config();
+        let exceeded = match sender_txs.iter().find(|tx| tx.nonce() == transaction.nonce()) {
+            Some(existing) => spam::is_underpriced_replacement(
+                &existing.transaction,
+                transaction,
+                config.throttled_price_bump,
+            ),
+            None => sender_txs.len() >= config.throttled_max_account_slots,
+        };
+
+        if exceeded {
+            spam_guard.on_rate_limited();
+            return Err(PoolError::RateLimited(sender, *transaction.hash()))
+        }
+        Ok(())
+    }
+
     /// Add a single validated transaction into the pool.
     ///
     /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
@@ -389,7 +484,8 @@ where
                 transaction,
                 propagate,
             } => {
-                let sender_id = self.get_sender_id(transaction.sender());
+                let sender = transaction.sender();
+                let sender_id = self.get_sender_id(sender);
                 let transaction_id = TransactionId::new(sender_id, transaction.nonce());
                 let _encoded_length = transaction.encoded_length();
 
@@ -416,6 +512,15 @@ where
                 let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
                 let hash = *added.hash();
 
+                if !origin.is_local() {
+                    let mut spam_guard = self.spam_guard.lock();
+                    let now = Instant::now();
+                    spam_guard.record(sender, SpamEvent::Insertion, now);
+                    if added.replaced().is_some() {
+                        spam_guard.record(sender, SpamEvent::Replacement, now);
+                    }
+                }
+
                 // transaction was successfully inserted into the pool
                 if let Some(sidecar) = maybe_sidecar {
                     // notify blob sidecar listeners
@@ -447,6 +552,13 @@ where
                 Ok(hash)
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
+                if !origin.is_local() {
+                    self.spam_guard.lock().record(
+                        tx.sender(),
+                        SpamEvent::Invalidation,
+                        Instant::now(),
+                    );
+                }
                 let mut listener = self.event_listener.write();
                 listener.discarded(tx.hash());
                 Err(PoolError::InvalidTransaction(*tx.hash(), err))
@@ -524,6 +636,10 @@ where
 
     /// Notify all listeners about a newly inserted pending transaction.
     fn on_new_transaction(&self, event: NewTransactionEvent<T::Transaction>) {
+        self.filtered_transaction_listener
+            .lock()
+            .retain_mut(|listener| listener.send_all(std::iter::once(&event)));
+
         let mut transaction_listeners = self.transaction_listener.lock();
         transaction_listeners.retain_mut(|listener| {
             if listener.kind.is_propagate_only() && !event.transaction.propagate {
@@ -573,7 +689,15 @@ where
             let mut transaction_full_listeners = self.transaction_listener.lock();
             transaction_full_listeners.retain_mut(|listener| {
                 listener.send_all(outcome.full_pending_transactions(listener.kind))
-            })
+            });
+
+            let mut filtered_listeners = self.filtered_transaction_listener.lock();
+            if !filtered_listeners.is_empty() {
+                let events = outcome
+                    .full_pending_transactions(TransactionListenerKind::PropagateOnly)
+                    .collect::<Vec<_>>();
+                filtered_listeners.retain_mut(|listener| listener.send_all(&events));
+            }
         }
 
         let OnNewCanonicalStateOutcome { mined, promoted, discarded, block_hash } = outcome;
@@ -728,8 +852,57 @@ where
     }
 
     /// Enforces the size limits of pool and returns the discarded transactions if violated.
+    ///
+    /// Transactions evicted from the queued sub-pool are moved to the overflow tier if it is
+    /// enabled and are not considered discarded.
     pub(crate) fn discard_worst(&self) -> HashSet<TxHash> {
-        self.pool.write().discard_worst().into_iter().map(|tx| *tx.hash()).collect()
+        let DiscardedTransactions { removed, queued } = self.pool.write().discard_worst();
+        let mut discarded = removed.into_iter().map(|tx| *tx.hash()).collect::<HashSet<_>>();
+        match &self.queued_overflow {
+            Some(overflow) => {
+                let mut overflow = overflow.lock();
+                discarded
+                    .extend(queued.iter().filter(|tx| !overflow.spill(tx)).map(|tx| *tx.hash()));
+            }
+            None => discarded.extend(queued.iter().map(|tx| *tx.hash())),
+        }
+        discarded
+    }
+
+    /// Takes the transactions of the overflow tier that can be executed after the transactions of
+    /// their sender in the pool, so they can be validated and inserted again.
+    ///
+    /// This includes the transactions of the given senders whose predecessor is pending or parked
+    /// for its base fee and the transactions of senders whose on chain nonce reached them.
+    pub(crate) fn take_rehydratable(
+        &self,
+        senders: impl IntoIterator<Item = Address>,
+    ) -> Vec<(TransactionOrigin, T::Transaction)> {
+        let Some(overflow) = &self.queued_overflow else { return Vec::new() };
+        let mut overflow = overflow.lock();
+        let mut next_nonces = overflow.take_ready();
+
+        {
+            let identifiers = self.identifiers.read();
+            let pool = self.pool.read();
+            for sender in senders {
+                let Some(sender_id) = identifiers.sender_id(&sender) else { continue };
+                let gap_closed = overflow.nonces(&sender).into_iter().find(|nonce| {
+                    let Some(prev) = nonce.checked_sub(1) else { return false };
+                    let prev = TransactionId::new(sender_id, prev);
+                    pool.subpool_contains(SubPool::Pending, &prev) ||
+                        pool.subpool_contains(SubPool::BaseFee, &prev)
+                });
+                if let Some(nonce) = gap_closed {
+                    next_nonces.entry(sender).or_insert(nonce);
+                }
+            }
+        }
+
+        next_nonces
+            .into_iter()
+            .flat_map(|(sender, nonce)| overflow.take_consecutive(sender, nonce))
+            .collect()
     }
 
     /// Inserts a blob transaction into the blob store
@@ -862,6 +1035,40 @@ impl<T: PoolTransaction> TransactionListener<T> {
     }
 }
 
+/// An active listener for new transactions that match a filter.
+///
+/// Only transactions that are allowed to be propagated are sent.
+#[derive(Debug)]
+struct FilteredTransactionListener<T: PoolTransaction> {
+    sender: mpsc::Sender<NewTransactionEvent<T>>,
+    filter: TransactionFilter,
+    /// The number of matching transactions that didn't fit into the channel.
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T: PoolTransaction> FilteredTransactionListener<T> {
+    /// Attempts to send all matching events to the listener, a matching event is only cloned if
+    /// it's sent.
+    ///
+    /// Returns false if the channel is closed (receiver dropped)
+    fn send_all<'a>(&self, events: impl IntoIterator<Item = &'a NewTransactionEvent<T>>) -> bool {
+        for event in events {
+            if !event.transaction.propagate || !self.filter.matches(&event.transaction.transaction)
+            {
+                continue
+            }
+            match self.sender.try_send(event.clone()) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => return false,
+            }
+        }
+        !self.sender.is_closed()
+    }
+}
+
 /// An active listener for new blobs
 #[derive(Debug)]
 struct BlobTransactionSidecarListener {
@@ -0,0 +1,310 @@
+//! Disk backed overflow tier of the queued sub-pool.
+
+use crate::{
+    config::QueuedOverflowConfig, PoolTransaction, TransactionOrigin, ValidPoolTransaction,
+};
+use reth_primitives::{
+    Address, Bytes, FromRecoveredTransaction, IntoRecoveredTransaction, TransactionSigned,
+    TransactionSignedEcRecovered, TxHash,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::PathBuf,
+    time::Instant,
+};
+use tracing::{debug, trace};
+
+/// Metadata of a transaction that was spilled to disk.
+#[derive(Debug, Clone)]
+struct SpilledTransaction {
+    sender: Address,
+    nonce: u64,
+    max_fee_per_gas: u128,
+    /// Size of the file in bytes.
+    size: usize,
+    origin: TransactionOrigin,
+    spilled_at: Instant,
+}
+
+/// Stores the transactions that were evicted from the full queued sub-pool on disk, so they can be
+/// re-inserted once they are executable.
+///
+/// Every transaction is stored in its own file `<dir>/<tx hash>` that contains the signer followed
+/// by the enveloped transaction. Only the metadata needed to decide when a transaction becomes
+/// relevant again is kept in memory. If the tier exceeds its limits, expired transactions are
+/// dropped first and then the transactions with the lowest fee cap.
+#[derive(Debug)]
+pub(crate) struct QueuedOverflow {
+    config: QueuedOverflowConfig,
+    /// All spilled transactions by hash.
+    by_hash: HashMap<TxHash, SpilledTransaction>,
+    /// Hashes of the spilled transactions by sender and nonce.
+    by_sender: HashMap<Address, BTreeMap<u64, TxHash>>,
+    /// Senders whose on chain nonce reached their lowest spilled transaction.
+    ready: HashMap<Address, u64>,
+    /// Combined size of all files in bytes.
+    size: usize,
+}
+
+// === impl QueuedOverflow ===
+
+impl QueuedOverflow {
+    /// Opens the overflow tier in the configured directory.
+    ///
+    /// The metadata of spilled transactions is not persisted, so transactions left over from a
+    /// previous run are removed.
+    pub(crate) fn open(config: QueuedOverflowConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        for entry in fs::read_dir(&config.dir)? {
+            let path = entry?.path();
+            let is_spilled_tx = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.parse::<TxHash>().is_ok());
+            if is_spilled_tx && path.is_file() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(Self {
+            config,
+            by_hash: Default::default(),
+            by_sender: Default::default(),
+            ready: Default::default(),
+            size: 0,
+        })
+    }
+
+    /// Number of spilled transactions.
+    pub(crate) fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    /// Returns true if the transaction is stored in the overflow tier.
+    pub(crate) fn contains(&self, hash: &TxHash) -> bool {
+        self.by_hash.contains_key(hash)
+    }
+
+    /// Returns the nonces of the spilled transactions of the sender in ascending order.
+    pub(crate) fn nonces(&self, sender: &Address) -> Vec<u64> {
+        self.by_sender.get(sender).map(|txs| txs.keys().copied().collect()).unwrap_or_default()
+    }
+
+    /// Returns the senders whose on chain nonce reached one of their spilled transactions and the
+    /// nonce of that transaction.
+    pub(crate) fn take_ready(&mut self) -> HashMap<Address, u64> {
+        std::mem::take(&mut self.ready)
+    }
+
+    fn path(&self, hash: &TxHash) -> PathBuf {
+        self.config.dir.join(format!("{hash:x}"))
+    }
+
+    /// Writes the transaction to disk.
+    ///
+    /// Returns false if the transaction can't be stored, e.g. because it is a blob transaction or
+    /// because it was immediately evicted again to keep the tier within its limits.
+    pub(crate) fn spill<T: PoolTransaction>(&mut self, tx: &ValidPoolTransaction<T>) -> bool {
+        if tx.is_eip4844() {
+            return false
+        }
+        let hash = *tx.hash();
+        let recovered = tx.transaction.to_recovered_transaction();
+        let mut data = recovered.signer().to_vec();
+        data.extend_from_slice(&recovered.into_signed().envelope_encoded());
+
+        if let Err(err) = fs::write(self.path(&hash), &data) {
+            debug!(target: "txpool", %err, ?hash, "failed to spill queued transaction");
+            return false
+        }
+        trace!(target: "txpool", ?hash, "spilled queued transaction");
+
+        let spilled = SpilledTransaction {
+            sender: tx.sender(),
+            nonce: tx.nonce(),
+            max_fee_per_gas: tx.max_fee_per_gas(),
+            size: data.len(),
+            origin: tx.origin,
+            spilled_at: Instant::now(),
+        };
+        self.remove(&hash);
+        self.size += spilled.size;
+        self.by_sender.entry(spilled.sender).or_default().insert(spilled.nonce, hash);
+        self.by_hash.insert(hash, spilled);
+
+        self.enforce_limits(Instant::now());
+        self.contains(&hash)
+    }
+
+    /// Removes expired transactions and then the transactions with the lowest fee cap until the
+    /// tier is within its limits.
+    pub(crate) fn enforce_limits(&mut self, now: Instant) {
+        let ttl = self.config.ttl;
+        let expired = self
+            .by_hash
+            .iter()
+            .filter(|(_, tx)| now.saturating_duration_since(tx.spilled_at) > ttl)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in expired {
+            self.remove(&hash);
+        }
+
+        while self.config.limit.is_exceeded(self.len(), self.size) {
+            let worst = self
+                .by_hash
+                .iter()
+                .min_by_key(|(_, tx)| (tx.max_fee_per_gas, std::cmp::Reverse(tx.nonce)))
+                .map(|(hash, _)| *hash);
+            let Some(worst) = worst else { break };
+            self.remove(&worst);
+        }
+    }
+
+    /// Updates the tier with the new on chain nonces of the senders.
+    ///
+    /// Transactions with a nonce below the on chain nonce are removed. If the lowest remaining
+    /// transaction has exactly the on chain nonce, the sender is marked as ready.
+    pub(crate) fn on_nonce_changes(&mut self, accounts: impl IntoIterator<Item = (Address, u64)>) {
+        for (sender, state_nonce) in accounts {
+            let stale = match self.by_sender.get(&sender) {
+                Some(txs) => txs.range(..state_nonce).map(|(_, hash)| *hash).collect::<Vec<_>>(),
+                None => continue,
+            };
+            for hash in stale {
+                self.remove(&hash);
+            }
+            if self.nonces(&sender).first() == Some(&state_nonce) {
+                self.ready.insert(sender, state_nonce);
+            }
+        }
+        self.enforce_limits(Instant::now());
+    }
+
+    /// Removes the spilled transactions of the sender with consecutive nonces, starting at the
+    /// given nonce, and reads them from disk.
+    pub(crate) fn take_consecutive<T: PoolTransaction>(
+        &mut self,
+        sender: Address,
+        mut nonce: u64,
+    ) -> Vec<(TransactionOrigin, T)> {
+        let mut transactions = Vec::new();
+        while let Some(hash) = self.by_sender.get(&sender).and_then(|txs| txs.get(&nonce)).copied()
+        {
+            let read = read_transaction(self.path(&hash), hash);
+            let Some(spilled) = self.remove(&hash) else { break };
+            match read {
+                Ok(tx) => {
+                    transactions.push((
+                        spilled.origin,
+                        <T as FromRecoveredTransaction>::from_recovered_transaction(tx),
+                    ));
+                }
+                Err(err) => {
+                    debug!(target: "txpool", %err, ?hash, "failed to read spilled transaction");
+                    break
+                }
+            }
+            nonce += 1;
+        }
+        transactions
+    }
+
+    /// Removes the transaction from the tier and deletes its file.
+    fn remove(&mut self, hash: &TxHash) -> Option<SpilledTransaction> {
+        let spilled = self.by_hash.remove(hash)?;
+        self.size -= spilled.size;
+        if let Some(txs) = self.by_sender.get_mut(&spilled.sender) {
+            txs.remove(&spilled.nonce);
+            if txs.is_empty() {
+                self.by_sender.remove(&spilled.sender);
+                self.ready.remove(&spilled.sender);
+            }
+        }
+        let _ = fs::remove_file(self.path(hash));
+        Some(spilled)
+    }
+}
+
+/// Reads a spilled transaction from the file.
+///
+/// The hash is taken from the file name, because the transaction was identified by it while it was
+/// in the pool.
+fn read_transaction(path: PathBuf, hash: TxHash) -> io::Result<TransactionSignedEcRecovered> {
+    let data = fs::read(path)?;
+    if data.len() < Address::len_bytes() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated spilled transaction"))
+    }
+    let (signer, tx) = data.split_at(Address::len_bytes());
+    let mut tx = TransactionSigned::decode_enveloped(Bytes::copy_from_slice(tx))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    tx.hash = hash;
+    Ok(TransactionSignedEcRecovered::from_signed_transaction(tx, Address::from_slice(signer)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::SubPoolLimit,
+        test_utils::{MockTransaction, MockTransactionFactory},
+    };
+    use std::time::Duration;
+
+    fn overflow(dir: &tempfile::TempDir, max_txs: usize, ttl: Duration) -> QueuedOverflow {
+        QueuedOverflow::open(QueuedOverflowConfig {
+            dir: dir.path().to_path_buf(),
+            limit: SubPoolLimit { max_txs, max_size: usize::MAX },
+            ttl,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn spill_roundtrip_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut f = MockTransactionFactory::default();
+        let mut overflow = overflow(&dir, 2, Duration::from_secs(60));
+
+        let tx = MockTransaction::eip1559().with_nonce(3).inc_price_by(10);
+        let next = f.validated(tx.next());
+        let tx = f.validated(tx);
+        let cheap = f.validated(MockTransaction::eip1559().with_nonce(5));
+        assert!(overflow.spill(&tx));
+        assert!(overflow.spill(&next));
+
+        // the cheapest transaction is dropped
+        assert!(!overflow.spill(&cheap));
+        assert_eq!(overflow.len(), 2);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        let taken = overflow.take_consecutive::<MockTransaction>(tx.sender(), 3);
+        assert_eq!(taken.len(), 2);
+        assert_eq!(taken[0].1.get_hash(), *tx.hash());
+        assert_eq!(taken[0].1.get_nonce(), 3);
+        assert_eq!(taken[1].1.get_hash(), *next.hash());
+        assert_eq!(overflow.len(), 0);
+        assert_eq!(overflow.size, 0);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn expired_and_stale_transactions_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut f = MockTransactionFactory::default();
+        let mut overflow = overflow(&dir, 10, Duration::ZERO);
+
+        let tx = f.validated(MockTransaction::eip1559().with_nonce(1));
+        overflow.spill(&tx);
+        overflow.enforce_limits(Instant::now() + Duration::from_secs(1));
+        assert_eq!(overflow.len(), 0);
+
+        let mut overflow = self::overflow(&dir, 10, Duration::from_secs(60));
+        let tx = MockTransaction::eip1559().with_nonce(1);
+        overflow.spill(&f.validated(tx.clone()));
+        overflow.spill(&f.validated(tx.next()));
+        overflow.on_nonce_changes([(tx.get_sender(), 2)]);
+        assert_eq!(overflow.nonces(&tx.get_sender()), vec![2]);
+        assert_eq!(overflow.take_ready(), HashMap::from([(tx.get_sender(), 2)]));
+    }
+}
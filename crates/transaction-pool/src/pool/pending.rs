@@ -4,7 +4,7 @@ use crate::{
     Priority, TransactionOrdering, ValidPoolTransaction,
 };
 
-use crate::pool::best::BestTransactionsWithBasefee;
+use crate::pool::best::BestTransactionsWithFees;
 use std::{
     cmp::Ordering,
     collections::{BTreeMap, BTreeSet},
@@ -106,12 +106,32 @@ impl<T: TransactionOrdering> PendingPool<T> {
         }
     }
 
-    /// Same as `best` but only returns transactions that satisfy the given basefee.
-    pub(crate) fn best_with_basefee(&self, base_fee: u64) -> BestTransactionsWithBasefee<T> {
-        BestTransactionsWithBasefee { best: self.best(), base_fee }
+    /// Same as `best` but the priority of all transactions is computed at the given base fee.
+    fn best_at_base_fee(&self, base_fee: u64) -> BestTransactions<T> {
+        let mut best = self.best();
+        for tx in best.all.values_mut() {
+            tx.priority = self.ordering.priority(&tx.transaction.transaction, base_fee);
+        }
+        best.independent = self
+            .independent_transactions
+            .iter()
+            .filter_map(|tx| best.all.get(tx.transaction.id()).cloned())
+            .collect();
+        best
+    }
+
+    /// Same as `best` but only returns transactions that satisfy the given basefee and blob fee.
+    ///
+    /// The transactions are ordered by their priority at the given base fee.
+    pub(crate) fn best_with_fees(
+        &self,
+        base_fee: u64,
+        blob_fee: Option<u64>,
+    ) -> BestTransactionsWithFees<T> {
+        BestTransactionsWithFees { best: self.best_at_base_fee(base_fee), base_fee, blob_fee }
     }
 
-    /// Same as `best` but also includes the given unlocked transactions.
+    /// Same as `best_with_fees` but also includes the given unlocked transactions.
     ///
     /// This mimics the [Self::add_transaction] method, but does not insert the transactions into
     /// pool but only into the returned iterator.
@@ -125,8 +145,9 @@ impl<T: TransactionOrdering> PendingPool<T> {
         &self,
         unlocked: Vec<Arc<ValidPoolTransaction<T::Transaction>>>,
         base_fee: u64,
-    ) -> BestTransactions<T> {
-        let mut best = self.best();
+        blob_fee: Option<u64>,
+    ) -> BestTransactionsWithFees<T> {
+        let mut best = self.best_at_base_fee(base_fee);
         let mut submission_id = self.submission_id;
         for tx in unlocked {
             submission_id += 1;
@@ -140,7 +161,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
             best.all.insert(tx_id, transaction);
         }
 
-        best
+        BestTransactionsWithFees { best, base_fee, blob_fee }
     }
 
     /// Returns an iterator over all transactions in the pool
@@ -0,0 +1,70 @@
+//! Ordering of concurrent submissions of the same sender.
+
+use parking_lot::Mutex;
+use reth_primitives::Address;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Ensures that concurrently submitted transactions of the same sender are validated and inserted
+/// in the order they were submitted.
+///
+/// Validation can finish out of order, e.g. while the KZG proofs of a blob transaction are
+/// verified the next nonce of the sender could already be inserted, which would be rejected as a
+/// nonce gap. Submissions of different senders are not affected.
+#[derive(Debug, Default)]
+pub(crate) struct SenderSequencer {
+    /// The lock of every sender that has a submission in progress.
+    senders: Mutex<HashMap<Address, Arc<AsyncMutex<()>>>>,
+}
+
+// === impl SenderSequencer ===
+
+impl SenderSequencer {
+    /// Waits until all earlier submissions of the sender are done.
+    ///
+    /// Waiting submissions proceed in the order they called this function.
+    pub(crate) async fn turn(&self, sender: Address) -> SenderTurn<'_> {
+        let lock = self.senders.lock().entry(sender).or_default().clone();
+        let guard = lock.lock_owned().await;
+        SenderTurn { sequencer: self, sender, _guard: guard }
+    }
+}
+
+/// The turn of a submission, the next submission of the sender proceeds once this is dropped.
+#[derive(Debug)]
+pub(crate) struct SenderTurn<'a> {
+    sequencer: &'a SenderSequencer,
+    sender: Address,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for SenderTurn<'_> {
+    fn drop(&mut self) {
+        let mut senders = self.sequencer.senders.lock();
+        // only the map and this turn hold the lock if no other submission is waiting
+        if senders.get(&self.sender).map_or(false, |lock| Arc::strong_count(lock) == 2) {
+            senders.remove(&self.sender);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn turns_in_submission_order() {
+        let sequencer = SenderSequencer::default();
+        let sender = Address::random();
+
+        let first = sequencer.turn(sender).await;
+        let mut second = Box::pin(sequencer.turn(sender));
+        let other = sequencer.turn(Address::random()).await;
+        assert!(futures_util::poll!(second.as_mut()).is_pending());
+
+        drop(first);
+        drop(other);
+        drop(second.await);
+        assert!(sequencer.senders.lock().is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+//! Per sender spam scoring.
+
+use crate::{config::SpamGuardConfig, metrics::SpamGuardMetrics, PoolTransaction};
+use reth_primitives::Address;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+use tracing::debug;
+
+/// The maximum number of tracked senders before senders without recent activity are dropped.
+const MAX_TRACKED_SENDERS: usize = 10_000;
+
+/// An activity of a sender that contributes to its spam score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpamEvent {
+    /// A transaction of the sender was inserted into the pool.
+    Insertion,
+    /// A transaction of the sender replaced another transaction of the sender.
+    Replacement,
+    /// A transaction of the sender failed validation.
+    Invalidation,
+}
+
+/// The recent activity of a single sender.
+#[derive(Debug, Default)]
+struct SenderActivity {
+    /// Events within the sliding window, oldest first.
+    events: VecDeque<(Instant, SpamEvent)>,
+    /// Whether the sender is currently throttled.
+    throttled: bool,
+}
+
+/// Tracks the insertion rate, replacement churn and invalidation rate of every sender over a
+/// sliding window, and throttles senders whose spam score crosses the configured threshold.
+///
+/// Throttled senders are subject to the stricter limits of the [SpamGuardConfig]. Since only
+/// events within the window are scored, a sender recovers once its burst of activity has passed.
+///
+/// The guard itself does not exempt any sender, local transactions are expected to not be
+/// recorded in the first place.
+#[derive(Debug)]
+pub struct PoolSpamGuard {
+    config: SpamGuardConfig,
+    senders: HashMap<Address, SenderActivity>,
+    metrics: SpamGuardMetrics,
+}
+
+// === impl PoolSpamGuard ===
+
+impl PoolSpamGuard {
+    /// Creates a new guard with the given config.
+    pub fn new(config: SpamGuardConfig) -> Self {
+        Self { config, senders: Default::default(), metrics: Default::default() }
+    }
+
+    /// Returns the config of the guard.
+    pub fn config(&self) -> &SpamGuardConfig {
+        &self.config
+    }
+
+    /// Records an event for the sender and updates its throttled status.
+    pub fn record(&mut self, sender: Address, event: SpamEvent, now: Instant) {
+        let activity = self.senders.entry(sender).or_default();
+        activity.events.push_back((now, event));
+        Self::prune(&self.config, activity, now);
+
+        let score = Self::score_of(&self.config, activity);
+        if !activity.throttled && score >= self.config.threshold {
+            activity.throttled = true;
+            self.metrics.throttled_senders.increment(1);
+            debug!(target: "txpool", %sender, score, "Throttling sender identified as spammer");
+        }
+
+        if self.senders.len() > MAX_TRACKED_SENDERS {
+            let config = &self.config;
+            self.senders.retain(|_, activity| {
+                Self::prune(config, activity, now);
+                !activity.events.is_empty()
+            });
+        }
+    }
+
+    /// Returns the current spam score of the sender.
+    pub fn score(&mut self, sender: &Address, now: Instant) -> f64 {
+        match self.senders.get_mut(sender) {
+            Some(activity) => {
+                Self::prune(&self.config, activity, now);
+                Self::score_of(&self.config, activity)
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Returns `true` if the sender is currently subject to the stricter limits.
+    ///
+    /// A throttled sender is released once its score decayed below the threshold.
+    pub fn is_throttled(&mut self, sender: &Address, now: Instant) -> bool {
+        let Some(activity) = self.senders.get_mut(sender) else { return false };
+        Self::prune(&self.config, activity, now);
+        if activity.throttled && Self::score_of(&self.config, activity) < self.config.threshold {
+            activity.throttled = false;
+        }
+        activity.throttled
+    }
+
+    /// Records that a transaction of a throttled sender was rejected.
+    pub(crate) fn on_rate_limited(&self) {
+        self.metrics.rate_limited_transactions.increment(1);
+    }
+
+    /// Removes all events that are outside of the window.
+    fn prune(config: &SpamGuardConfig, activity: &mut SenderActivity, now: Instant) {
+        while let Some((at, _)) = activity.events.front() {
+            if now.saturating_duration_since(*at) < config.window {
+                break
+            }
+            activity.events.pop_front();
+        }
+    }
+
+    fn score_of(config: &SpamGuardConfig, activity: &SenderActivity) -> f64 {
+        activity
+            .events
+            .iter()
+            .map(|(_, event)| match event {
+                SpamEvent::Insertion => config.insertion_weight,
+                SpamEvent::Replacement => config.replacement_weight,
+                SpamEvent::Invalidation => config.invalidation_weight,
+            })
+            .sum()
+    }
+}
+
+impl Default for PoolSpamGuard {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+/// Returns `true` if the replacement does not bump the fees of the existing transaction by at least
+/// `price_bump` percent.
+pub(crate) fn is_underpriced_replacement<T: PoolTransaction>(
+    existing: &T,
+    replacement: &T,
+    price_bump: u128,
+) -> bool {
+    let bumped = |fee: u128| fee.saturating_mul(100 + price_bump) / 100;
+
+    if replacement.max_fee_per_gas() < bumped(existing.max_fee_per_gas()) {
+        return true
+    }
+
+    match (existing.max_priority_fee_per_gas(), replacement.max_priority_fee_per_gas()) {
+        (Some(existing), Some(replacement)) => replacement < bumped(existing),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn throttles_and_recovers() {
+        let config = SpamGuardConfig::default();
+        let window = config.window;
+        let mut guard = PoolSpamGuard::new(config);
+        let spammer = Address::random();
+        let other = Address::random();
+        let start = Instant::now();
+
+        let mut now = start;
+        for _ in 0..50 {
+            guard.record(spammer, SpamEvent::Insertion, now);
+            guard.record(spammer, SpamEvent::Replacement, now);
+            now += Duration::from_millis(20);
+        }
+        guard.record(other, SpamEvent::Insertion, now);
+
+        assert!(guard.is_throttled(&spammer, now));
+        assert!(!guard.is_throttled(&other, now));
+
+        // the burst decays once it falls out of the window
+        let later = now + window;
+        assert_eq!(guard.score(&spammer, later), 0.0);
+        assert!(!guard.is_throttled(&spammer, later));
+    }
+}
@@ -225,55 +225,38 @@ impl<T: TransactionOrdering> TxPool<T> {
         basefee: u64,
     ) -> Box<dyn crate::traits::BestTransactions<Item = Arc<ValidPoolTransaction<T::Transaction>>>>
     {
-        match basefee.cmp(&self.all_transactions.pending_basefee) {
-            Ordering::Equal => {
-                // fee unchanged, nothing to shift
-                Box::new(self.best_transactions())
-            }
-            Ordering::Greater => {
-                // base fee increased, we only need to enforces this on the pending pool
-                Box::new(self.pending_pool.best_with_basefee(basefee))
-            }
-            Ordering::Less => {
-                // base fee decreased, we need to move transactions from the basefee pool to the
-                // pending pool
-                let unlocked = self.basefee_pool.satisfy_base_fee_transactions(basefee);
-                Box::new(
-                    self.pending_pool
-                        .best_with_unlocked(unlocked, self.all_transactions.pending_basefee),
-                )
-            }
-        }
+        self.best_transactions_with_attributes(BestTransactionsAttributes::base_fee(basefee))
     }
 
     /// Returns an iterator that yields transactions that are ready to be included in the block with
     /// the given base fee and optional blob fee.
+    ///
+    /// The transactions are ordered by their priority at the given base fee, and transactions that
+    /// can't pay the fees of that block are skipped together with their descendants.
+    ///
+    /// Note: blob transactions that are parked in the blob sub-pool are not considered, even if
+    /// they satisfy the given blob fee.
     pub(crate) fn best_transactions_with_attributes(
         &self,
         best_transactions_attributes: BestTransactionsAttributes,
     ) -> Box<dyn crate::traits::BestTransactions<Item = Arc<ValidPoolTransaction<T::Transaction>>>>
     {
-        match best_transactions_attributes.basefee.cmp(&self.all_transactions.pending_basefee) {
-            Ordering::Equal => {
+        let BestTransactionsAttributes { basefee, blob_fee } = best_transactions_attributes;
+        match basefee.cmp(&self.all_transactions.pending_basefee) {
+            Ordering::Equal if blob_fee.is_none() => {
                 // fee unchanged, nothing to shift
                 Box::new(self.best_transactions())
             }
-            Ordering::Greater => {
-                // base fee increased, we only need to enforce this on the pending pool
-                Box::new(self.pending_pool.best_with_basefee(best_transactions_attributes.basefee))
+            Ordering::Equal | Ordering::Greater => {
+                // base fee increased or blob fee enforced, we only need to enforce this on the
+                // pending pool
+                Box::new(self.pending_pool.best_with_fees(basefee, blob_fee))
             }
             Ordering::Less => {
                 // base fee decreased, we need to move transactions from the basefee pool to the
-                // pending pool and satisfy blob fee transactions as well
-                let unlocked_with_blob =
-                    self.blob_transactions.satisfy_attributes(best_transactions_attributes);
-
-                Box::new(
-                    self.pending_pool.best_with_unlocked(
-                        unlocked_with_blob,
-                        self.all_transactions.pending_basefee,
-                    ),
-                )
+                // pending pool
+                let unlocked = self.basefee_pool.satisfy_base_fee_transactions(basefee);
+                Box::new(self.pending_pool.best_with_unlocked(unlocked, basefee, blob_fee))
             }
         }
     }
@@ -296,7 +279,6 @@ impl<T: TransactionOrdering> TxPool<T> {
     }
 
     /// Returns `true` if the transaction with the given id is already included in the given subpool
-    #[cfg(test)]
     pub(crate) fn subpool_contains(&self, subpool: SubPool, id: &TransactionId) -> bool {
         match subpool {
             SubPool::Queued => self.queued_pool.contains(id),
@@ -681,8 +663,9 @@ impl<T: TransactionOrdering> TxPool<T> {
     ///
     /// If the current size exceeds the given bounds, the worst transactions are evicted from the
     /// pool and returned.
-    pub(crate) fn discard_worst(&mut self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+    pub(crate) fn discard_worst(&mut self) -> DiscardedTransactions<T::Transaction> {
         let mut removed = Vec::new();
+        let mut queued = Vec::new();
 
         // Helper macro that discards the worst transactions for the pools
         macro_rules! discard_worst {
@@ -701,7 +684,7 @@ impl<T: TransactionOrdering> TxPool<T> {
                             $this.all_transactions.remove_transaction(&id);
 
                             // record the removed transaction
-                            removed.push(tx);
+                            $removed.push(tx);
 
                             // this might have introduced a nonce gap, so we also discard any descendants
                             $this.remove_descendants(&id, &mut $removed);
@@ -715,12 +698,12 @@ impl<T: TransactionOrdering> TxPool<T> {
         discard_worst!(
             self, removed, [
                 pending_limit  => pending_pool,
-                basefee_limit  => basefee_pool,
-                queued_limit  => queued_pool
+                basefee_limit  => basefee_pool
             ]
         );
+        discard_worst!(self, queued, [queued_limit => queued_pool]);
 
-        removed
+        DiscardedTransactions { removed, queued }
     }
 
     /// Number of transactions in the entire pool
@@ -1648,6 +1631,15 @@ impl<T: PoolTransaction> PoolInternalTransaction<T> {
     }
 }
 
+/// Transactions that were evicted because a sub-pool exceeded its limits.
+#[derive(Debug)]
+pub(crate) struct DiscardedTransactions<T: PoolTransaction> {
+    /// Transactions evicted from the pending and basefee sub-pools and their descendants.
+    pub(crate) removed: Vec<Arc<ValidPoolTransaction<T>>>,
+    /// Transactions evicted from the queued sub-pool and their descendants.
+    pub(crate) queued: Vec<Arc<ValidPoolTransaction<T>>>,
+}
+
 /// Tracks the result after updating the pool
 #[derive(Debug)]
 pub(crate) struct UpdateOutcome<T: PoolTransaction> {
@@ -2163,4 +2155,85 @@ mod tests {
         assert_eq!(outcome.discarded.len(), 1);
         assert_eq!(pool.pending_pool.len(), 1);
     }
+
+    #[test]
+    fn best_transactions_with_attributes_skips_unminable() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let basefee = 100;
+        pool.update_basefee(basefee as u64);
+
+        // minable at the current base fee, but not at the base fee of the next block
+        let borderline = MockTransaction::eip1559().with_max_fee(basefee + 5);
+        let descendant = borderline.next().with_max_fee(basefee + 100);
+        let other = MockTransaction::eip1559().with_max_fee(basefee + 100);
+        for tx in [borderline.clone(), descendant.clone(), other.clone()] {
+            pool.add_transaction(f.validated(tx), U256::from(1_000), 0).unwrap();
+        }
+        assert_eq!(pool.pending_pool.len(), 3);
+
+        let best = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::base_fee(basefee as u64))
+            .collect::<Vec<_>>();
+        assert_eq!(best.len(), 3);
+
+        let best = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::base_fee(
+                basefee as u64 + 10,
+            ))
+            .map(|tx| *tx.hash())
+            .collect::<Vec<_>>();
+        assert_eq!(best, vec![other.get_hash()]);
+    }
+
+    #[test]
+    fn best_transactions_with_attributes_orders_by_next_base_fee() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let basefee = 100;
+        pool.update_basefee(basefee as u64);
+
+        // the effective tip of `capped` drops below the tip of `uncapped` at a higher base fee
+        let capped = MockTransaction::eip1559().with_max_fee(basefee + 100).with_priority_fee(50);
+        let uncapped =
+            MockTransaction::eip1559().with_max_fee(basefee + 1_000).with_priority_fee(45);
+        for tx in [capped.clone(), uncapped.clone()] {
+            pool.add_transaction(f.validated(tx), U256::from(1_000), 0).unwrap();
+        }
+
+        let best = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::base_fee(basefee as u64))
+            .map(|tx| *tx.hash())
+            .collect::<Vec<_>>();
+        assert_eq!(best, vec![capped.get_hash(), uncapped.get_hash()]);
+
+        let best = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::base_fee(
+                basefee as u64 + 60,
+            ))
+            .map(|tx| *tx.hash())
+            .collect::<Vec<_>>();
+        assert_eq!(best, vec![uncapped.get_hash(), capped.get_hash()]);
+    }
+
+    #[test]
+    fn best_transactions_with_attributes_skips_blob_fee() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let basefee = pool.all_transactions.pending_basefee;
+        let blob_fee = pool.all_transactions.pending_blob_fee;
+
+        let blob = MockTransaction::eip4844().with_blob_fee(blob_fee + 1);
+        let descendant = blob.next().with_blob_fee(blob_fee + 100);
+        for tx in [blob, descendant] {
+            pool.add_transaction(f.validated(tx), U256::from(1_000), 0).unwrap();
+        }
+        assert_eq!(pool.pending_pool.len(), 2);
+
+        let attributes = BestTransactionsAttributes::new(basefee, Some(blob_fee as u64 + 1));
+        assert_eq!(pool.best_transactions_with_attributes(attributes).count(), 2);
+
+        let attributes = BestTransactionsAttributes::new(basefee, Some(blob_fee as u64 + 2));
+        assert_eq!(pool.best_transactions_with_attributes(attributes).count(), 0);
+    }
 }
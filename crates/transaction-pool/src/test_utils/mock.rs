@@ -466,6 +466,10 @@ impl PoolTransaction for MockTransaction {
         }
     }
 
+    fn value(&self) -> U256 {
+        self.get_value()
+    }
+
     fn gas_limit(&self) -> u64 {
         self.get_gas_limit()
     }
@@ -8,15 +8,18 @@ use futures_util::{ready, Stream};
 use reth_primitives::{
     AccessList, Address, BlobTransactionSidecar, BlobTransactionValidationError,
     FromRecoveredPooledTransaction, FromRecoveredTransaction, IntoRecoveredTransaction, PeerId,
-    PooledTransactionsElement, PooledTransactionsElementEcRecovered, SealedBlock, Transaction,
-    TransactionKind, TransactionSignedEcRecovered, TxEip4844, TxHash, B256, EIP1559_TX_TYPE_ID,
-    EIP4844_TX_TYPE_ID, U256,
+    PooledTransactionsElement, PooledTransactionsElementEcRecovered, SealedBlock, Selector,
+    Transaction, TransactionKind, TransactionSignedEcRecovered, TxEip4844, TxHash, B256,
+    EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256,
 };
 use std::{
     collections::{HashMap, HashSet},
     fmt,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
 };
 use tokio::sync::mpsc::Receiver;
@@ -140,6 +143,17 @@ pub trait TransactionPool: Send + Sync + Clone {
         kind: TransactionListenerKind,
     ) -> Receiver<NewTransactionEvent<Self::Transaction>>;
 
+    /// Returns a new stream that yields the new valid transactions added to the pool that match
+    /// the given [TransactionFilter].
+    ///
+    /// Transactions are matched before they're handed to the listener, and matching transactions
+    /// that don't fit into the buffer of the listener are dropped. If transactions were dropped the
+    /// stream yields [FilteredTransactionEvent::Lagged] and ends.
+    fn new_transactions_filtered(
+        &self,
+        filter: TransactionFilter,
+    ) -> FilteredTransactionStream<Self::Transaction>;
+
     /// Returns a new Stream that yields new transactions added to the pending sub-pool.
     ///
     /// This is a convenience wrapper around [Self::new_transactions_listener] that filters for
@@ -685,6 +699,9 @@ pub trait PoolTransaction:
     /// max_blob_fee_per_gas * blob_gas_used`.
     fn cost(&self) -> U256;
 
+    /// Returns the value transferred by this transaction.
+    fn value(&self) -> U256;
+
     /// Amount of gas that should be used in executing this transaction. This is paid up-front.
     fn gas_limit(&self) -> u64;
 
@@ -894,6 +911,10 @@ impl PoolTransaction for EthPooledTransaction {
         self.cost
     }
 
+    fn value(&self) -> U256 {
+        self.transaction.value().into()
+    }
+
     /// Amount of gas that should be used in executing this transaction. This is paid up-front.
     fn gas_limit(&self) -> u64 {
         self.transaction.gas_limit()
@@ -1094,6 +1115,101 @@ impl GetPooledTransactionLimit {
     }
 }
 
+/// Selects the new transactions yielded by [TransactionPool::new_transactions_filtered].
+///
+/// A transaction matches if it satisfies all criteria, an empty criterion matches every
+/// transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    /// Only transactions of these senders.
+    pub senders: HashSet<Address>,
+    /// Only transactions that call one of these recipients.
+    pub recipients: HashSet<Address>,
+    /// Only transactions whose input starts with one of these function selectors.
+    pub selectors: HashSet<Selector>,
+    /// Only transactions that transfer at least this value.
+    pub min_value: Option<U256>,
+    /// Only transactions of these types.
+    pub tx_types: HashSet<u8>,
+}
+
+// === impl TransactionFilter ===
+
+impl TransactionFilter {
+    /// Returns true if the transaction matches the filter.
+    pub fn matches<T: PoolTransaction>(&self, tx: &T) -> bool {
+        if !self.senders.is_empty() && !self.senders.contains(&tx.sender()) {
+            return false
+        }
+        if !self.recipients.is_empty() && !tx.to().map_or(false, |to| self.recipients.contains(&to))
+        {
+            return false
+        }
+        if !self.selectors.is_empty() &&
+            !tx.input().get(..4).map_or(false, |selector| {
+                self.selectors.contains(&Selector::from_slice(selector))
+            })
+        {
+            return false
+        }
+        if self.min_value.map_or(false, |min_value| tx.value() < min_value) {
+            return false
+        }
+        self.tx_types.is_empty() || self.tx_types.contains(&tx.tx_type())
+    }
+}
+
+/// An item of a [FilteredTransactionStream].
+#[derive(Debug)]
+pub enum FilteredTransactionEvent<T: PoolTransaction> {
+    /// A new transaction that matches the filter.
+    Transaction(NewTransactionEvent<T>),
+    /// Matching transactions were dropped because the listener didn't keep up, this is the last
+    /// item of the stream.
+    Lagged {
+        /// The number of dropped transactions.
+        dropped: u64,
+    },
+}
+
+/// A Stream that yields the new transactions that match a [TransactionFilter].
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct FilteredTransactionStream<Tx: PoolTransaction> {
+    st: Receiver<NewTransactionEvent<Tx>>,
+    /// The number of matching transactions the pool failed to send.
+    dropped: Arc<AtomicU64>,
+    /// Whether the stream ended after lagging.
+    lagged: bool,
+}
+
+// === impl FilteredTransactionStream ===
+
+impl<Tx: PoolTransaction> FilteredTransactionStream<Tx> {
+    /// Create a new stream that yields the transactions of the receiver until transactions were
+    /// dropped, as counted by `dropped`.
+    pub fn new(st: Receiver<NewTransactionEvent<Tx>>, dropped: Arc<AtomicU64>) -> Self {
+        Self { st, dropped, lagged: false }
+    }
+}
+
+impl<Tx: PoolTransaction> Stream for FilteredTransactionStream<Tx> {
+    type Item = FilteredTransactionEvent<Tx>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.lagged {
+            return Poll::Ready(None)
+        }
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            self.lagged = true;
+            self.st.close();
+            return Poll::Ready(Some(FilteredTransactionEvent::Lagged { dropped }))
+        }
+        self.st.poll_recv(cx).map(|event| event.map(FilteredTransactionEvent::Transaction))
+    }
+}
+
 /// A Stream that yields full transactions the subpool
 #[must_use = "streams do nothing unless polled"]
 #[derive(Debug)]
@@ -4,7 +4,10 @@ use crate::{
     blobstore::BlobStore,
     error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
     traits::TransactionOrigin,
-    validate::{ValidTransaction, ValidationTask, MAX_INIT_CODE_SIZE, TX_MAX_SIZE},
+    validate::{
+        kzg::KzgVerifier, TransactionValidatorError, ValidTransaction, ValidationTask,
+        MAX_INIT_CODE_SIZE, TX_MAX_SIZE,
+    },
     EthBlobTransactionSidecar, EthPoolTransaction, PoolTransaction, TransactionValidationOutcome,
     TransactionValidationTaskExecutor, TransactionValidator,
 };
@@ -14,17 +17,18 @@ use reth_primitives::{
         ETHEREUM_BLOCK_GAS_LIMIT,
     },
     kzg::KzgSettings,
-    revm::compat::calculate_intrinsic_gas_after_merge,
-    ChainSpec, InvalidTransactionError, SealedBlock, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
-    EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
+    revm::compat::intrinsic_gas,
+    BlobTransactionSidecar, ChainSpec, InvalidTransactionError, SealedBlock, TxEip4844,
+    EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
 };
 use reth_provider::{AccountReader, StateProviderFactory};
 use reth_tasks::TaskSpawner;
+use revm::primitives::SpecId;
 use std::{
     marker::PhantomData,
     sync::{atomic::AtomicBool, Arc},
 };
-use tokio::sync::Mutex;
+use tokio::sync::{oneshot, Mutex};
 
 /// Validator for Ethereum transactions.
 #[derive(Debug, Clone)]
@@ -60,6 +64,43 @@ where
     ) -> Vec<TransactionValidationOutcome<Tx>> {
         transactions.into_iter().map(|(origin, tx)| self.validate_one(origin, tx)).collect()
     }
+
+    /// Validates a single transaction like [Self::validate_one], but verifies the KZG proofs of
+    /// blob transactions on a blocking task, so that other validations are not stalled.
+    pub async fn validate_one_offloaded(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Tx,
+    ) -> TransactionValidationOutcome<Tx> {
+        let (transaction, maybe_blob_sidecar) =
+            match self.inner.validate_stateless(origin, transaction) {
+                Ok(validated) => validated,
+                Err(outcome) => return outcome,
+            };
+
+        let Some(sidecar) = maybe_blob_sidecar else {
+            return self.inner.validate_against_state(origin, transaction, None)
+        };
+
+        let (tx, rx) = oneshot::channel();
+        let verifier = Arc::clone(&self.inner.kzg_verifier);
+        let eip4844 = transaction.as_eip4844().cloned();
+        self.inner.blocking_tasks.spawn_blocking(Box::pin(async move {
+            let res = verify_blob_sidecar(&verifier, eip4844.as_ref(), &sidecar);
+            let _ = tx.send((sidecar, res));
+        }));
+
+        match rx.await {
+            Ok((sidecar, Ok(()))) => {
+                self.inner.validate_against_state(origin, transaction, Some(sidecar))
+            }
+            Ok((_, Err(err))) => TransactionValidationOutcome::Invalid(transaction, err),
+            Err(_) => TransactionValidationOutcome::Error(
+                *transaction.hash(),
+                Box::new(TransactionValidatorError::ValidationServiceUnreachable),
+            ),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -75,14 +116,18 @@ where
         origin: TransactionOrigin,
         transaction: Self::Transaction,
     ) -> TransactionValidationOutcome<Self::Transaction> {
-        self.validate_one(origin, transaction)
+        self.validate_one_offloaded(origin, transaction).await
     }
 
     async fn validate_transactions(
         &self,
         transactions: Vec<(TransactionOrigin, Self::Transaction)>,
     ) -> Vec<TransactionValidationOutcome<Self::Transaction>> {
-        self.validate_all(transactions)
+        let mut outcomes = Vec::with_capacity(transactions.len());
+        for (origin, transaction) in transactions {
+            outcomes.push(self.validate_one_offloaded(origin, transaction).await);
+        }
+        outcomes
     }
 
     fn on_new_head_block(&self, new_tip_block: &SealedBlock) {
@@ -113,8 +158,10 @@ pub(crate) struct EthTransactionValidatorInner<Client, T> {
     minimum_priority_fee: Option<u128>,
     /// Toggle to determine if a local transaction should be propagated
     propagate_local_transactions: bool,
-    /// Stores the setup and parameters needed for validating KZG proofs.
-    kzg_settings: Arc<KzgSettings>,
+    /// Verifies the KZG proofs of blob transactions.
+    kzg_verifier: Arc<KzgVerifier>,
+    /// Spawns the blocking KZG proof verification tasks.
+    blocking_tasks: Box<dyn TaskSpawner>,
     /// Marker for the transaction type
     _marker: PhantomData<T>,
 }
@@ -137,8 +184,34 @@ where
     fn validate_one(
         &self,
         origin: TransactionOrigin,
-        mut transaction: Tx,
+        transaction: Tx,
     ) -> TransactionValidationOutcome<Tx> {
+        let (transaction, maybe_blob_sidecar) = match self.validate_stateless(origin, transaction) {
+            Ok(validated) => validated,
+            Err(outcome) => return outcome,
+        };
+
+        if let Some(sidecar) = &maybe_blob_sidecar {
+            if let Err(err) =
+                verify_blob_sidecar(&self.kzg_verifier, transaction.as_eip4844(), sidecar)
+            {
+                return TransactionValidationOutcome::Invalid(transaction, err)
+            }
+        }
+
+        self.validate_against_state(origin, transaction, maybe_blob_sidecar)
+    }
+
+    /// Performs all checks that don't require the state or the KZG proofs of the blob sidecar.
+    ///
+    /// Returns the transaction and the extracted blob sidecar whose proofs still need to be
+    /// verified, or the outcome if the transaction is invalid.
+    #[allow(clippy::result_large_err)]
+    fn validate_stateless(
+        &self,
+        origin: TransactionOrigin,
+        mut transaction: Tx,
+    ) -> Result<(Tx, Option<BlobTransactionSidecar>), TransactionValidationOutcome<Tx>> {
         // Checks for tx_type
         match transaction.tx_type() {
             LEGACY_TX_TYPE_ID => {
@@ -147,70 +220,70 @@ where
             EIP2930_TX_TYPE_ID => {
                 // Accept only legacy transactions until EIP-2718/2930 activates
                 if !self.eip2718 {
-                    return TransactionValidationOutcome::Invalid(
+                    return Err(TransactionValidationOutcome::Invalid(
                         transaction,
                         InvalidTransactionError::Eip1559Disabled.into(),
-                    )
+                    ))
                 }
             }
             EIP1559_TX_TYPE_ID => {
                 // Reject dynamic fee transactions until EIP-1559 activates.
                 if !self.eip1559 {
-                    return TransactionValidationOutcome::Invalid(
+                    return Err(TransactionValidationOutcome::Invalid(
                         transaction,
                         InvalidTransactionError::Eip1559Disabled.into(),
-                    )
+                    ))
                 }
             }
             EIP4844_TX_TYPE_ID => {
                 // Reject blob transactions.
                 if !self.eip4844 {
-                    return TransactionValidationOutcome::Invalid(
+                    return Err(TransactionValidationOutcome::Invalid(
                         transaction,
                         InvalidTransactionError::Eip4844Disabled.into(),
-                    )
+                    ))
                 }
             }
 
             _ => {
-                return TransactionValidationOutcome::Invalid(
+                return Err(TransactionValidationOutcome::Invalid(
                     transaction,
                     InvalidTransactionError::TxTypeNotSupported.into(),
-                )
+                ))
             }
         };
 
         // Reject transactions over defined size to prevent DOS attacks
         if transaction.size() > TX_MAX_SIZE {
             let size = transaction.size();
-            return TransactionValidationOutcome::Invalid(
+            return Err(TransactionValidationOutcome::Invalid(
                 transaction,
                 InvalidPoolTransactionError::OversizedData(size, TX_MAX_SIZE),
-            )
+            ))
         }
 
         // Check whether the init code size has been exceeded.
         if self.fork_tracker.is_shanghai_activated() {
             if let Err(err) = ensure_max_init_code_size(&transaction, MAX_INIT_CODE_SIZE) {
-                return TransactionValidationOutcome::Invalid(transaction, err)
+                return Err(TransactionValidationOutcome::Invalid(transaction, err))
             }
         }
 
         // Checks for gas limit
         if transaction.gas_limit() > self.block_gas_limit {
             let gas_limit = transaction.gas_limit();
-            return TransactionValidationOutcome::Invalid(
+            return Err(TransactionValidationOutcome::Invalid(
                 transaction,
                 InvalidPoolTransactionError::ExceedsGasLimit(gas_limit, self.block_gas_limit),
-            )
+            ))
         }
 
         // Ensure max_priority_fee_per_gas (if EIP1559) is less than max_fee_per_gas if any.
         if transaction.max_priority_fee_per_gas() > Some(transaction.max_fee_per_gas()) {
-            return TransactionValidationOutcome::Invalid(
+            return Err(TransactionValidationOutcome::Invalid(
                 transaction,
                 InvalidTransactionError::TipAboveFeeCap.into(),
-            )
+            ))
         }
 
         // Drop non-local transactions with a fee lower than the configured fee for acceptance into
@@ -219,39 +292,46 @@ where
             transaction.is_eip1559() &&
             transaction.max_priority_fee_per_gas() < self.minimum_priority_fee
         {
-            return TransactionValidationOutcome::Invalid(
+            return Err(TransactionValidationOutcome::Invalid(
                 transaction,
                 InvalidPoolTransactionError::Underpriced,
-            )
+            ))
         }
 
         // Checks for chainid
         if let Some(chain_id) = transaction.chain_id() {
             if chain_id != self.chain_id() {
-                return TransactionValidationOutcome::Invalid(
+                return Err(TransactionValidationOutcome::Invalid(
                     transaction,
                     InvalidTransactionError::ChainIdMismatch.into(),
-                )
+                ))
             }
         }
 
         // intrinsic gas checks
         let access_list =
             transaction.access_list().map(|list| list.flattened()).unwrap_or_default();
-        let is_shanghai = self.fork_tracker.is_shanghai_activated();
-
-        if transaction.gas_limit() <
-            calculate_intrinsic_gas_after_merge(
-                transaction.input(),
-                transaction.kind(),
-                &access_list,
-                is_shanghai,
-            )
-        {
-            return TransactionValidationOutcome::Invalid(
+        let intrinsic_gas = match intrinsic_gas(
+            transaction.input(),
+            transaction.kind().is_create(),
+            &access_list,
+            self.fork_tracker.spec_id(),
+        ) {
+            Ok(gas) => gas,
+            Err(_) => {
+                let size = transaction.size();
+                return Err(TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::ExceedsMaxInitCodeSize(size, MAX_INIT_CODE_SIZE),
+                ))
+            }
+        };
+
+        if transaction.gas_limit() < intrinsic_gas {
+            return Err(TransactionValidationOutcome::Invalid(
                 transaction,
                 InvalidPoolTransactionError::IntrinsicGasTooLow,
-            )
+            ))
         }
 
         let mut maybe_blob_sidecar = None;
@@ -260,26 +340,26 @@ where
         if transaction.is_eip4844() {
             // Cancun fork is required for blob txs
             if !self.fork_tracker.is_cancun_activated() {
-                return TransactionValidationOutcome::Invalid(
+                return Err(TransactionValidationOutcome::Invalid(
                     transaction,
                     InvalidTransactionError::TxTypeNotSupported.into(),
-                )
+                ))
             }
 
             let blob_count = transaction.blob_count();
             if blob_count == 0 {
                 // no blobs
-                return TransactionValidationOutcome::Invalid(
+                return Err(TransactionValidationOutcome::Invalid(
                     transaction,
                     InvalidPoolTransactionError::Eip4844(
                         Eip4844PoolTransactionError::NoEip4844Blobs,
                     ),
-                )
+                ))
             }
 
             if blob_count > MAX_BLOBS_PER_BLOCK {
                 // too many blobs
-                return TransactionValidationOutcome::Invalid(
+                return Err(TransactionValidationOutcome::Invalid(
                     transaction,
                     InvalidPoolTransactionError::Eip4844(
                         Eip4844PoolTransactionError::TooManyEip4844Blobs {
@@ -287,54 +367,49 @@ where
                             permitted: MAX_BLOBS_PER_BLOCK,
                         },
                     ),
-                )
+                ))
             }
 
             // extract the blob from the transaction
             match transaction.take_blob() {
                 EthBlobTransactionSidecar::None => {
                     // this should not happen
-                    return TransactionValidationOutcome::Invalid(
+                    return Err(TransactionValidationOutcome::Invalid(
                         transaction,
                         InvalidTransactionError::TxTypeNotSupported.into(),
-                    )
+                    ))
                 }
                 EthBlobTransactionSidecar::Missing => {
                     if let Ok(Some(_)) = self.blob_store.get(*transaction.hash()) {
                         // validated transaction is already in the store
                     } else {
-                        return TransactionValidationOutcome::Invalid(
+                        return Err(TransactionValidationOutcome::Invalid(
                             transaction,
                             InvalidPoolTransactionError::Eip4844(
                                 Eip4844PoolTransactionError::MissingEip4844BlobSidecar,
                             ),
-                        )
+                        ))
                     }
                 }
                 EthBlobTransactionSidecar::Present(blob) => {
-                    if let Some(eip4844) = transaction.as_eip4844() {
-                        // validate the blob
-                        if let Err(err) = eip4844.validate_blob(&blob, &self.kzg_settings) {
-                            return TransactionValidationOutcome::Invalid(
-                                transaction,
-                                InvalidPoolTransactionError::Eip4844(
-                                    Eip4844PoolTransactionError::InvalidEip4844Blob(err),
-                                ),
-                            )
-                        }
-                        // store the extracted blob
-                        maybe_blob_sidecar = Some(blob);
-                    } else {
-                        // this should not happen
-                        return TransactionValidationOutcome::Invalid(
-                            transaction,
-                            InvalidTransactionError::TxTypeNotSupported.into(),
-                        )
-                    }
+                    // the proofs are verified separately
+                    maybe_blob_sidecar = Some(blob);
                 }
             }
         }
 
+        Ok((transaction, maybe_blob_sidecar))
+    }
+
+    /// Validates the transaction against the latest state.
+    ///
+    /// The KZG proofs of the blob sidecar must have been verified already.
+    fn validate_against_state(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Tx,
+        maybe_blob_sidecar: Option<BlobTransactionSidecar>,
+    ) -> TransactionValidationOutcome<Tx> {
         let account = match self
             .client
             .latest()
@@ -554,6 +629,8 @@ impl EthTransactionValidatorBuilder {
     /// The validator will spawn `additional_tasks` additional tasks for validation.
     ///
     /// By default this will spawn 1 additional task.
+    ///
+    /// The KZG proofs of blob transactions are verified on blocking tasks spawned by `tasks`.
     pub fn build_with_tasks<Client, Tx, T, S>(
         self,
         client: Client,
@@ -561,7 +638,7 @@ impl EthTransactionValidatorBuilder {
         blob_store: S,
     ) -> TransactionValidationTaskExecutor<EthTransactionValidator<Client, Tx>>
     where
-        T: TaskSpawner,
+        T: TaskSpawner + 'static,
         S: BlobStore,
     {
         let Self {
@@ -581,21 +658,6 @@ impl EthTransactionValidatorBuilder {
         let fork_tracker =
             ForkTracker { shanghai: AtomicBool::new(shanghai), cancun: AtomicBool::new(cancun) };
 
-        let inner = EthTransactionValidatorInner {
-            chain_spec,
-            client,
-            eip2718,
-            eip1559,
-            fork_tracker,
-            eip4844,
-            block_gas_limit,
-            minimum_priority_fee,
-            propagate_local_transactions,
-            blob_store: Box::new(blob_store),
-            kzg_settings,
-            _marker: Default::default(),
-        };
-
         let (tx, task) = ValidationTask::new();
 
         // Spawn validation tasks, they are blocking because they perform db lookups
@@ -613,6 +675,22 @@ impl EthTransactionValidatorBuilder {
             }),
         );
 
+        let inner = EthTransactionValidatorInner {
+            chain_spec,
+            client,
+            eip2718,
+            eip1559,
+            fork_tracker,
+            eip4844,
+            block_gas_limit,
+            minimum_priority_fee,
+            propagate_local_transactions,
+            blob_store: Box::new(blob_store),
+            kzg_verifier: Arc::new(KzgVerifier::new(kzg_settings)),
+            blocking_tasks: Box::new(tasks),
+            _marker: Default::default(),
+        };
+
         let to_validation_task = Arc::new(Mutex::new(tx));
 
         TransactionValidationTaskExecutor {
@@ -641,6 +719,32 @@ impl ForkTracker {
     pub(crate) fn is_cancun_activated(&self) -> bool {
         self.cancun.load(std::sync::atomic::Ordering::Relaxed)
     }
+
+    /// Returns the spec of the activated fork, the pool only accepts transactions after the Merge.
+    pub(crate) fn spec_id(&self) -> SpecId {
+        if self.is_cancun_activated() {
+            SpecId::CANCUN
+        } else if self.is_shanghai_activated() {
+            SpecId::SHANGHAI
+        } else {
+            SpecId::MERGE
+        }
+    }
+}
+
+/// Verifies the KZG proofs of the blob sidecar of the transaction.
+fn verify_blob_sidecar(
+    verifier: &KzgVerifier,
+    transaction: Option<&TxEip4844>,
+    sidecar: &BlobTransactionSidecar,
+) -> Result<(), InvalidPoolTransactionError> {
+    let Some(transaction) = transaction else {
+        // this should not happen
+        return Err(InvalidTransactionError::TxTypeNotSupported.into())
+    };
+    verifier.verify(transaction, sidecar).map_err(|err| {
+        InvalidPoolTransactionError::Eip4844(Eip4844PoolTransactionError::InvalidEip4844Blob(err))
+    })
 }
 
 /// Ensure that the code size is not greater than `max_init_code_size`.
@@ -0,0 +1,114 @@
+//! KZG proof verification of blob transaction sidecars.
+
+use crate::metrics::KzgVerificationMetrics;
+use parking_lot::Mutex;
+use reth_primitives::{
+    keccak256, kzg::KzgSettings, BlobTransactionSidecar, BlobTransactionValidationError, TxEip4844,
+    B256,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Instant,
+};
+
+/// The maximum number of verified blobs that are remembered.
+const MAX_VERIFIED_BLOBS: usize = 1_024;
+
+/// Verifies the KZG proofs of blob sidecars and remembers the blobs that were verified
+/// successfully.
+///
+/// A blob is identified by its versioned hash, so a blob transaction that is submitted again, e.g.
+/// by another peer, is not verified again if it comes with the same blobs and proofs.
+#[derive(Debug)]
+pub(crate) struct KzgVerifier {
+    /// Stores the setup and parameters needed for validating KZG proofs.
+    settings: Arc<KzgSettings>,
+    /// Recently verified blobs.
+    verified: Mutex<VerifiedBlobs>,
+    metrics: KzgVerificationMetrics,
+}
+
+// === impl KzgVerifier ===
+
+impl KzgVerifier {
+    /// Creates a new verifier with the given settings.
+    pub(crate) fn new(settings: Arc<KzgSettings>) -> Self {
+        Self { settings, verified: Default::default(), metrics: Default::default() }
+    }
+
+    /// Verifies the blobs of the sidecar against the versioned hashes of the transaction.
+    ///
+    /// All proofs that were not verified before are verified in a single batch.
+    pub(crate) fn verify(
+        &self,
+        transaction: &TxEip4844,
+        sidecar: &BlobTransactionSidecar,
+    ) -> Result<(), BlobTransactionValidationError> {
+        let digests = blob_digests(sidecar);
+        if transaction.blob_versioned_hashes.len() == digests.len() &&
+            self.verified.lock().contains_all(&transaction.blob_versioned_hashes, &digests)
+        {
+            self.metrics.kzg_verification_cache_hits.increment(1);
+            return Ok(())
+        }
+
+        let start = Instant::now();
+        let res = transaction.validate_blob(sidecar, &self.settings);
+        self.metrics.kzg_verification_duration.record(start.elapsed());
+        res?;
+
+        let mut verified = self.verified.lock();
+        for (versioned_hash, digest) in transaction.blob_versioned_hashes.iter().zip(digests) {
+            verified.insert(*versioned_hash, digest);
+        }
+        Ok(())
+    }
+}
+
+/// Returns a digest of every blob of the sidecar, including its commitment and proof.
+fn blob_digests(sidecar: &BlobTransactionSidecar) -> Vec<B256> {
+    if sidecar.blobs.len() != sidecar.commitments.len() ||
+        sidecar.blobs.len() != sidecar.proofs.len()
+    {
+        return Vec::new()
+    }
+    sidecar
+        .blobs
+        .iter()
+        .zip(sidecar.commitments.iter().zip(sidecar.proofs.iter()))
+        .map(|(blob, (commitment, proof))| {
+            keccak256([blob.as_slice(), commitment.as_slice(), proof.as_slice()].concat())
+        })
+        .collect()
+}
+
+/// A bounded set of verified blobs, the oldest blobs are evicted first.
+#[derive(Debug, Default)]
+struct VerifiedBlobs {
+    /// The digest of the verified blob by versioned hash.
+    digests: HashMap<B256, B256>,
+    /// Versioned hashes in insertion order.
+    order: VecDeque<B256>,
+}
+
+impl VerifiedBlobs {
+    /// Returns true if all blobs were verified with exactly the same data.
+    fn contains_all(&self, versioned_hashes: &[B256], digests: &[B256]) -> bool {
+        versioned_hashes
+            .iter()
+            .zip(digests)
+            .all(|(versioned_hash, digest)| self.digests.get(versioned_hash) == Some(digest))
+    }
+
+    fn insert(&mut self, versioned_hash: B256, digest: B256) {
+        if self.digests.insert(versioned_hash, digest).is_none() {
+            self.order.push_back(versioned_hash);
+        }
+        while self.order.len() > MAX_VERIFIED_BLOBS {
+            if let Some(evicted) = self.order.pop_front() {
+                self.digests.remove(&evicted);
+            }
+        }
+    }
+}
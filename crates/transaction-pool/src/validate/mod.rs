@@ -13,6 +13,7 @@ use std::{fmt, time::Instant};
 
 mod constants;
 mod eth;
+mod kzg;
 mod task;
 
 /// A `TransactionValidator` implementation that validates ethereum transaction.
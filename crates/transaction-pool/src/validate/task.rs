@@ -103,7 +103,7 @@ impl<Client, Tx> TransactionValidationTaskExecutor<EthTransactionValidator<Clien
         tasks: T,
     ) -> Self
     where
-        T: TaskSpawner,
+        T: TaskSpawner + 'static,
     {
         Self::eth_with_additional_tasks(client, chain_spec, blob_store, tasks, 0)
     }
@@ -125,7 +125,7 @@ impl<Client, Tx> TransactionValidationTaskExecutor<EthTransactionValidator<Clien
         num_additional_tasks: usize,
     ) -> Self
     where
-        T: TaskSpawner,
+        T: TaskSpawner + 'static,
     {
         EthTransactionValidatorBuilder::new(chain_spec)
             .with_additional_tasks(num_additional_tasks)
@@ -1,10 +1,22 @@
 //! Blob transaction tests
 
+use reth_primitives::{
+    constants::{eip4844::MAINNET_KZG_TRUSTED_SETUP, ETH_TO_WEI, GWEI_TO_WEI},
+    kzg::{Blob, KzgCommitment, KzgProof, BYTES_PER_BLOB},
+    kzg_to_versioned_hash, sign_message, Address, BlobTransaction, BlobTransactionSidecar,
+    BlobTransactionValidationError, ChainSpecBuilder, PooledTransactionsElement, Transaction,
+    TransactionKind, TransactionSigned, TxEip4844, B256, U256,
+};
+use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+use reth_tasks::TokioTaskExecutor;
 use reth_transaction_pool::{
-    error::PoolError,
+    blobstore::InMemoryBlobStore,
+    error::{Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolError},
     test_utils::{testing_pool, MockTransaction, MockTransactionFactory},
-    TransactionOrigin, TransactionPool,
+    EthPooledTransaction, EthTransactionPool, Pool, PoolTransaction, TransactionOrigin,
+    TransactionPool, TransactionValidationTaskExecutor,
 };
+use std::sync::Arc;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn blobs_exclusive() {
@@ -40,3 +52,108 @@ async fn blobs_exclusive() {
         _ => unreachable!(),
     }
 }
+
+/// Returns a pool with an [EthTransactionValidator](reth_transaction_pool::EthTransactionValidator)
+/// for a chain with Cancun activated.
+fn eth_pool(provider: MockEthProvider) -> EthTransactionPool<MockEthProvider, InMemoryBlobStore> {
+    let blob_store = InMemoryBlobStore::default();
+    let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+    let validator = TransactionValidationTaskExecutor::eth(
+        provider,
+        chain_spec,
+        blob_store.clone(),
+        TokioTaskExecutor::default(),
+    );
+    Pool::eth_pool(validator, blob_store, Default::default())
+}
+
+/// Returns a blob transaction signed with the secret that carries `blob_count` blobs with valid
+/// proofs.
+fn blob_transaction(secret: B256, nonce: u64, blob_count: usize) -> BlobTransaction {
+    let settings = MAINNET_KZG_TRUSTED_SETUP.clone();
+    let mut sidecar = BlobTransactionSidecar::default();
+    let mut blob_versioned_hashes = Vec::new();
+    for idx in 0..blob_count {
+        let mut data = [0u8; BYTES_PER_BLOB];
+        data[31] = nonce as u8;
+        data[63] = idx as u8;
+        let blob = Blob::from(data);
+        let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, &settings).unwrap();
+        let proof =
+            KzgProof::compute_blob_kzg_proof(&blob, &commitment.to_bytes(), &settings).unwrap();
+        sidecar.commitments.push(commitment.to_bytes());
+        sidecar.proofs.push(proof.to_bytes());
+        sidecar.blobs.push(blob);
+        blob_versioned_hashes.push(kzg_to_versioned_hash(commitment));
+    }
+
+    let transaction = Transaction::Eip4844(TxEip4844 {
+        chain_id: 1,
+        nonce,
+        gas_limit: 21_000,
+        max_fee_per_gas: 100 * GWEI_TO_WEI as u128,
+        max_priority_fee_per_gas: GWEI_TO_WEI as u128,
+        max_fee_per_blob_gas: GWEI_TO_WEI as u128,
+        to: TransactionKind::Call(Address::random()),
+        blob_versioned_hashes,
+        ..Default::default()
+    });
+    let signature = sign_message(secret, transaction.signature_hash()).unwrap();
+    let transaction = TransactionSigned::from_transaction_and_signature(transaction, signature);
+    BlobTransaction::try_from_signed(transaction, sidecar).unwrap()
+}
+
+fn pooled(transaction: BlobTransaction) -> EthPooledTransaction {
+    PooledTransactionsElement::BlobTransaction(transaction).try_into_ecrecovered().unwrap().into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn blob_transactions_keep_submission_order() {
+    let secret = B256::random();
+    // verifying the proofs of the first transaction takes much longer
+    let first = pooled(blob_transaction(secret, 0, 6));
+    let second = pooled(blob_transaction(secret, 1, 1));
+    let (first_hash, second_hash) = (*first.hash(), *second.hash());
+
+    let provider = MockEthProvider::default();
+    provider.add_account(first.sender(), ExtendedAccount::new(0, U256::from(ETH_TO_WEI)));
+    let pool = eth_pool(provider);
+
+    let (first, second) = futures_util::join!(
+        pool.add_external_transaction(first),
+        pool.add_external_transaction(second)
+    );
+    assert_eq!(first.unwrap(), first_hash);
+    assert_eq!(second.unwrap(), second_hash);
+
+    let mut best = pool.best_transactions();
+    assert_eq!(*best.next().unwrap().hash(), first_hash);
+    assert_eq!(*best.next().unwrap().hash(), second_hash);
+    assert!(best.next().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_invalid_blob_proofs() {
+    let mut transaction = blob_transaction(B256::random(), 0, 2);
+    transaction.sidecar.proofs.swap(0, 1);
+    let transaction = pooled(transaction);
+    let hash = *transaction.hash();
+
+    let provider = MockEthProvider::default();
+    provider.add_account(transaction.sender(), ExtendedAccount::new(0, U256::from(ETH_TO_WEI)));
+    let pool = eth_pool(provider);
+
+    let err = pool.add_external_transaction(transaction).await.unwrap_err();
+    assert!(matches!(
+        err,
+        PoolError::InvalidTransaction(
+            err_hash,
+            InvalidPoolTransactionError::Eip4844(
+                Eip4844PoolTransactionError::InvalidEip4844Blob(
+                    BlobTransactionValidationError::InvalidProof
+                )
+            )
+        ) if err_hash == hash
+    ));
+    assert!(pool.is_empty());
+}
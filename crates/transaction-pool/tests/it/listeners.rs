@@ -1,12 +1,15 @@
 use assert_matches::assert_matches;
+use reth_primitives::{Bytes, Selector};
 use reth_transaction_pool::{
     noop::MockTransactionValidator,
-    test_utils::{testing_pool, testing_pool_with_validator, MockTransactionFactory},
-    FullTransactionEvent, TransactionEvent, TransactionListenerKind, TransactionOrigin,
-    TransactionPool,
+    test_utils::{
+        testing_pool, testing_pool_with_validator, MockTransaction, MockTransactionFactory,
+    },
+    FilteredTransactionEvent, FullTransactionEvent, TransactionEvent, TransactionFilter,
+    TransactionListenerKind, TransactionOrigin, TransactionPool,
 };
-use std::{future::poll_fn, task::Poll};
-use tokio_stream::StreamExt;
+use std::{future::poll_fn, pin::Pin, task::Poll};
+use tokio_stream::{Stream, StreamExt};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn txpool_listener_by_hash() {
@@ -87,3 +90,32 @@ async fn txpool_listener_new_propagate_only() {
     })
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_listener_filtered_by_selector() {
+    let txpool = testing_pool();
+    let selector = Selector::from([0xa9, 0x05, 0x9c, 0xbb]);
+    let filter = TransactionFilter { selectors: [selector].into(), ..Default::default() };
+    let mut listener = txpool.new_transactions_filtered(filter);
+
+    let other =
+        MockTransaction::eip1559().with_input(Bytes::from_static(&[0x09, 0x5e, 0xa7, 0xb3]));
+    let short = MockTransaction::eip1559().with_input(Bytes::from_static(&[0xa9, 0x05]));
+    let matching =
+        MockTransaction::eip1559().with_input(Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb, 0x01]));
+    for tx in [other, short, matching.clone()] {
+        assert!(txpool.add_transaction(TransactionOrigin::External, tx).await.is_ok());
+    }
+
+    assert_matches!(
+        listener.next().await,
+        Some(FilteredTransactionEvent::Transaction(event))
+            if *event.transaction.hash() == matching.get_hash()
+    );
+    poll_fn(|cx| {
+        // no other transaction matches
+        assert!(Pin::new(&mut listener).poll_next(cx).is_pending());
+        Poll::Ready(())
+    })
+    .await;
+}
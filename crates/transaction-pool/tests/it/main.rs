@@ -5,6 +5,10 @@ mod blobs;
 #[cfg(feature = "test-utils")]
 mod listeners;
 #[cfg(feature = "test-utils")]
+mod overflow;
+#[cfg(feature = "test-utils")]
 mod pending;
+#[cfg(feature = "test-utils")]
+mod spam;
 
 fn main() {}
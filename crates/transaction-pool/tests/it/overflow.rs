@@ -0,0 +1,43 @@
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore,
+    noop::MockTransactionValidator,
+    test_utils::{MockOrdering, MockTransaction},
+    Pool, PoolConfig, QueuedOverflowConfig, SubPoolLimit, TransactionOrigin, TransactionPool,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_rehydrates_spilled_queued_transaction() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = PoolConfig {
+        queued_limit: SubPoolLimit { max_txs: 1, max_size: usize::MAX },
+        queued_overflow: Some(QueuedOverflowConfig::new(dir.path())),
+        ..Default::default()
+    };
+    let txpool = Pool::new(
+        MockTransactionValidator::default(),
+        MockOrdering::default(),
+        InMemoryBlobStore::default(),
+        config,
+    );
+
+    // two transactions with nonce gaps, the queued pool only has room for one
+    let first = MockTransaction::eip1559().with_nonce(2);
+    txpool.add_transaction(TransactionOrigin::External, first.clone()).await.unwrap();
+    let gapped = MockTransaction::eip1559().with_nonce(1);
+    txpool.add_transaction(TransactionOrigin::External, gapped.clone()).await.unwrap();
+
+    // the newer transaction was moved to disk and is not part of the pool
+    assert_eq!(txpool.pool_size().queued, 1);
+    assert!(txpool.contains(&first.get_hash()));
+    assert!(!txpool.contains(&gapped.get_hash()));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    assert_eq!(txpool.best_transactions().count(), 0);
+
+    // closing the nonce gap re-inserts the spilled transaction
+    txpool.add_transaction(TransactionOrigin::External, gapped.prev()).await.unwrap();
+    let pending = txpool.pending_transactions();
+    assert_eq!(pending.len(), 2);
+    assert!(pending.iter().any(|tx| *tx.hash() == gapped.get_hash()));
+    assert_eq!(txpool.best_transactions().count(), 2);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
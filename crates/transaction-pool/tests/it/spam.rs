@@ -0,0 +1,48 @@
+use assert_matches::assert_matches;
+use reth_transaction_pool::{
+    error::PoolError,
+    test_utils::{testing_pool, MockTransaction},
+    TransactionOrigin, TransactionPool,
+};
+
+/// Returns a replacement for the transaction with a 20% fee bump.
+fn bumped(tx: &MockTransaction) -> MockTransaction {
+    tx.inc_price_by(tx.get_gas_price() / 5 + 1).rng_hash()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_rate_limits_replacement_spam() {
+    let txpool = testing_pool();
+
+    let mut tx = MockTransaction::eip1559();
+    let spammer = tx.get_sender();
+    txpool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+
+    // replace the same nonce 50 times in a row
+    let mut rate_limited = 0;
+    for _ in 0..50 {
+        let replacement = bumped(&tx);
+        match txpool.add_transaction(TransactionOrigin::External, replacement.clone()).await {
+            Ok(_) => tx = replacement,
+            Err(err) => {
+                assert_matches!(err, PoolError::RateLimited(sender, _) if sender == spammer);
+                rate_limited += 1;
+            }
+        }
+    }
+    assert!(rate_limited > 0);
+
+    // subsequent replacements are rejected
+    assert_matches!(
+        txpool.add_transaction(TransactionOrigin::External, bumped(&tx)).await,
+        Err(PoolError::RateLimited(sender, _)) if sender == spammer
+    );
+
+    // a second sender is unaffected
+    let other = MockTransaction::eip1559();
+    txpool.add_transaction(TransactionOrigin::External, other.clone()).await.unwrap();
+    txpool.add_transaction(TransactionOrigin::External, bumped(&other)).await.unwrap();
+
+    // local transactions are exempt
+    txpool.add_transaction(TransactionOrigin::Local, bumped(&tx)).await.unwrap();
+}